@@ -0,0 +1,206 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! Benchmarks of the representative workloads of the wrapper.
+//!
+//! The frameworks and dynamics are drawn by the seeded generators of `crusti_arg::testing`, so that the runs are comparable.
+//! The dynamics loop is run against a mock solver giving the same answer at each step.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use crusti_arg::{
+    testing::{self, TestRng},
+    AAFramework, ArgumentSet, AspartixReader, AspartixWriter,
+};
+use iccma_dynamics::runner::{self, QueryType};
+use std::io::{BufReader, Read};
+
+const SEED: u64 = 42;
+
+const N_ARGS: [usize; 3] = [100, 1_000, 10_000];
+
+const N_STEPS: [usize; 3] = [10, 100, 1_000];
+
+/// The average number of attacks per argument of the generated frameworks.
+const ATTACKS_PER_ARG: f64 = 4.0;
+
+fn framework(n_args: usize) -> AAFramework<String> {
+    testing::arbitrary_framework(
+        &mut TestRng::new(SEED),
+        n_args,
+        ATTACKS_PER_ARG / n_args as f64,
+    )
+}
+
+fn aspartix_instance(framework: &AAFramework<String>) -> String {
+    let mut instance = Vec::new();
+    AspartixWriter::default()
+        .write(framework, &mut instance)
+        .unwrap();
+    String::from_utf8(instance).unwrap()
+}
+
+fn bench_framework_construction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("framework_construction");
+    for n_args in N_ARGS {
+        let af = framework(n_args);
+        let labels = af.argument_set().iter().map(|a| a.label().to_string());
+        let labels = labels.collect::<Vec<String>>();
+        let attacks = af
+            .iter_attacks_by_ids()
+            .map(|att| (att.attacker().id(), att.attacked().id()))
+            .collect::<Vec<(usize, usize)>>();
+        group.throughput(Throughput::Elements((n_args + attacks.len()) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n_args), &n_args, |b, _| {
+            b.iter(|| {
+                let mut af = AAFramework::new(ArgumentSet::new(labels.clone()));
+                for (from, to) in attacks.iter() {
+                    af.new_attack_by_ids(*from, *to).unwrap();
+                }
+                af
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_aspartix_parsing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("aspartix_parsing");
+    for n_args in N_ARGS {
+        let instance = aspartix_instance(&framework(n_args));
+        group.throughput(Throughput::Bytes(instance.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(n_args),
+            &instance,
+            |b, instance| {
+                b.iter(|| {
+                    AspartixReader::default()
+                        .read(&mut instance.as_bytes())
+                        .unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_grounded(c: &mut Criterion) {
+    let mut group = c.benchmark_group("grounded");
+    for n_args in N_ARGS {
+        let af = framework(n_args);
+        group.throughput(Throughput::Elements(n_args as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n_args), &af, |b, af| {
+            b.iter(|| af.least_fixpoint())
+        });
+    }
+    group.finish();
+}
+
+/// The output of a mock solver, giving a fixed answer a given number of times.
+///
+/// As a solver waiting for the next modification, each call to `read` returns bytes from a single answer.
+struct MockSolver<'a> {
+    answer: &'a [u8],
+    n_answers: usize,
+    offset: usize,
+}
+
+impl<'a> MockSolver<'a> {
+    fn new(answer: &'a str, n_answers: usize) -> Self {
+        MockSolver {
+            answer: answer.as_bytes(),
+            n_answers,
+            offset: 0,
+        }
+    }
+}
+
+impl Read for MockSolver<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.n_answers == 0 {
+            return Ok(0);
+        }
+        let n = buf.len().min(self.answer.len() - self.offset);
+        buf[..n].copy_from_slice(&self.answer[self.offset..self.offset + n]);
+        self.offset += n;
+        if self.offset == self.answer.len() {
+            self.offset = 0;
+            self.n_answers -= 1;
+        }
+        Ok(n)
+    }
+}
+
+/// The number of arguments of the framework the dynamics are drawn from.
+///
+/// The mock solver does not interpret the modifications, and the generator enumerates the missing attacks at each step, so a small framework is used.
+const DYNAMICS_N_ARGS: usize = 20;
+
+/// Runs the dynamics loop against a mock solver giving the same answer at each step.
+fn bench_dynamics_loop(c: &mut Criterion, name: &str, query: QueryType, answer: &str) {
+    let af = framework(DYNAMICS_N_ARGS);
+    let mut group = c.benchmark_group(name);
+    for n_steps in N_STEPS {
+        let modifications = testing::arbitrary_modifications(&mut TestRng::new(SEED), &af, n_steps)
+            .iter()
+            .map(|m| format!("{}\n", m))
+            .collect::<String>();
+        group.throughput(Throughput::Elements(n_steps as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n_steps), &n_steps, |b, _| {
+            b.iter(|| {
+                let mut child_stdout = BufReader::new(MockSolver::new(answer, n_steps + 1));
+                runner::execute_dynamics(
+                    &mut modifications.as_bytes(),
+                    query.answer_reading_function(),
+                    &Ok,
+                    &mut |_| Ok(()),
+                    &mut std::io::sink(),
+                    &mut child_stdout,
+                )
+                .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_dynamics(c: &mut Criterion) {
+    bench_dynamics_loop(
+        c,
+        "dynamics_acceptance",
+        QueryType::DC("a0".to_string()),
+        "YES\n",
+    );
+    let extension = format!(
+        "[{}]\n",
+        (0..1_000)
+            .map(|i| format!("a{}", i))
+            .collect::<Vec<String>>()
+            .join(", ")
+    );
+    bench_dynamics_loop(c, "dynamics_extension", QueryType::SE, &extension);
+}
+
+criterion_group!(
+    benches,
+    bench_framework_construction,
+    bench_aspartix_parsing,
+    bench_grounded,
+    bench_dynamics
+);
+criterion_main!(benches);
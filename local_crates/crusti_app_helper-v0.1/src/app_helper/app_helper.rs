@@ -15,6 +15,7 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::cli_manager::{cli_manager::CliManager, command::Command};
+use crate::ExitStatusError;
 use anyhow::Result;
 use log::{error, info};
 use std::{ffi::OsString, time::SystemTime};
@@ -30,9 +31,11 @@ use std::{ffi::OsString, time::SystemTime};
 ///
 /// Calling [`launch_app`] is probably the last thing you do in your app.
 /// It initializes the logger, reads the CLI arguments, and execute the right command.
-/// If an error is returned by a command, the error stack is displayed and a status of 1 is returned to the system.
+/// If an error is returned by a command, the error stack is displayed and a status of 1 is returned to the system,
+/// unless the error was given another status through an [`ExitStatusError`].
 ///
 /// [`Command`]: trait.Command.html
+/// [`ExitStatusError`]: struct.ExitStatusError.html
 /// [`launch_app`]: struct.AppHelper.html#method.launch_app
 pub struct AppHelper<'a> {
     cli_manager: CliManager<'a>,
@@ -97,7 +100,11 @@ impl<'a> AppHelper<'a> {
             e.chain()
                 .skip(1)
                 .for_each(|err| error!("caused by: {}", err));
-            std::process::exit(1);
+            let status = e
+                .downcast_ref::<ExitStatusError>()
+                .map(|s| s.status())
+                .unwrap_or(1);
+            std::process::exit(status);
         }
     }
 
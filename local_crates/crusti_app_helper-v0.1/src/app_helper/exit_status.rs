@@ -0,0 +1,178 @@
+// crusti_app_helper
+// Copyright (C) 2020  Univ. Artois & CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use std::fmt::Display;
+
+/// An error requiring the app to exit with a specific status code.
+///
+/// When a command returns an error built upon an `ExitStatusError`, the app exits with its status code instead of the default one (1).
+/// Errors are classified using [`ExitStatusError::classify`] or the [`WithExitStatus`] trait; the first classification of an error is kept.
+/// The classified error is kept as is, so that the typed errors of its chain can still be retrieved through [`ExitStatusError::error`].
+///
+/// [`ExitStatusError::classify`]: struct.ExitStatusError.html#method.classify
+/// [`ExitStatusError::error`]: struct.ExitStatusError.html#method.error
+/// [`WithExitStatus`]: trait.WithExitStatus.html
+#[derive(Debug)]
+pub struct ExitStatusError {
+    status: i32,
+    error: anyhow::Error,
+}
+
+impl ExitStatusError {
+    /// Builds a new error given the exit status and the error message.
+    ///
+    /// # Arguments
+    /// * `status` - the status code the app must exit with
+    /// * `message` - the error message
+    pub fn new<M: Display>(status: i32, message: M) -> Self {
+        ExitStatusError {
+            status,
+            error: anyhow::Error::msg(message.to_string()),
+        }
+    }
+
+    /// Sets the exit status of an error, unless it was already set.
+    ///
+    /// The error is kept as the content of the classified one, which displays as it and shares its sources.
+    ///
+    /// # Arguments
+    /// * `error` - the error
+    /// * `status` - the status code the app must exit with
+    pub fn classify(error: anyhow::Error, status: i32) -> anyhow::Error {
+        if error.downcast_ref::<ExitStatusError>().is_some() {
+            return error;
+        }
+        anyhow::Error::new(ExitStatusError { status, error })
+    }
+
+    /// Returns the status code the app must exit with.
+    pub fn status(&self) -> i32 {
+        self.status
+    }
+
+    /// Returns the classified error, which can be used to retrieve the typed errors of its chain with `downcast_ref`.
+    pub fn error(&self) -> &anyhow::Error {
+        &self.error
+    }
+
+    /// Returns the messages of the classified error, from the outermost to the innermost one.
+    pub fn causes(&self) -> Vec<String> {
+        self.error.chain().map(|e| e.to_string()).collect()
+    }
+}
+
+impl Display for ExitStatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl std::error::Error for ExitStatusError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+/// A trait used to set the exit status of the errors of results.
+pub trait WithExitStatus<T> {
+    /// Sets the exit status of the error, if any, unless it was already set.
+    ///
+    /// See [`ExitStatusError::classify`](struct.ExitStatusError.html#method.classify).
+    ///
+    /// # Arguments
+    /// * `status` - the status code the app must exit with
+    fn with_exit_status(self, status: i32) -> Result<T>;
+}
+
+impl<T> WithExitStatus<T> for Result<T> {
+    fn with_exit_status(self, status: i32) -> Result<T> {
+        self.map_err(|e| ExitStatusError::classify(e, status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{anyhow, Context};
+
+    #[test]
+    fn test_classify() {
+        let error = Err::<(), _>(anyhow!("root"))
+            .context("context")
+            .with_exit_status(10)
+            .unwrap_err();
+        let status_error = error.downcast_ref::<ExitStatusError>().unwrap();
+        assert_eq!(10, status_error.status());
+        assert_eq!(
+            vec!["context".to_string(), "root".to_string()],
+            status_error.causes()
+        );
+    }
+
+    #[test]
+    fn test_first_classification_is_kept() {
+        let error = Err::<(), _>(anyhow!("root"))
+            .with_exit_status(40)
+            .context("context")
+            .with_exit_status(10)
+            .unwrap_err();
+        assert_eq!(
+            40,
+            error.downcast_ref::<ExitStatusError>().unwrap().status()
+        );
+        assert_eq!("context", error.to_string());
+    }
+
+    #[test]
+    fn test_classified_error_is_kept() {
+        #[derive(Debug, PartialEq)]
+        struct TypedError;
+
+        impl Display for TypedError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "typed")
+            }
+        }
+
+        impl std::error::Error for TypedError {}
+
+        let error = Err::<(), _>(anyhow!("root"))
+            .context(TypedError)
+            .with_exit_status(10)
+            .context("context")
+            .unwrap_err();
+        let status_error = error.downcast_ref::<ExitStatusError>().unwrap();
+        assert_eq!(
+            Some(&TypedError),
+            status_error.error().downcast_ref::<TypedError>()
+        );
+        assert_eq!(
+            vec!["context", "typed", "root"],
+            error.chain().map(|e| e.to_string()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_new() {
+        let error = anyhow::Error::new(ExitStatusError::new(20, "crash"));
+        assert_eq!(
+            20,
+            error.downcast_ref::<ExitStatusError>().unwrap().status()
+        );
+        assert_eq!("crash", error.to_string());
+    }
+}
@@ -1,2 +1,3 @@
 pub(crate) mod app_helper;
+pub(crate) mod exit_status;
 pub(crate) mod license_command;
@@ -28,5 +28,6 @@ pub use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
 pub use log::{debug, error, info, trace, warn};
 
 pub use app_helper::app_helper::AppHelper;
+pub use app_helper::exit_status::{ExitStatusError, WithExitStatus};
 pub use app_helper::license_command::LicenseCommand;
 pub use cli_manager::command::Command;
@@ -20,8 +20,15 @@
 use crate::aa::arguments::Argument;
 use crate::aa::arguments::ArgumentSet;
 use crate::aa::arguments::LabelType;
+use crate::aa::io::dynamics::Modification;
+use crate::aa::semantics::Semantics;
+use crate::aa::solvers;
+use crate::aa::stats::DegreeStats;
 use anyhow::{anyhow, Context, Result};
-use std::fmt::Display;
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Display},
+};
 
 /// An Abstract Argumentation framework as defined in Dung semantics.
 pub struct AAFramework<T>
@@ -30,6 +37,41 @@ where
 {
     arguments: ArgumentSet<T>,
     attacks: Vec<(usize, usize)>,
+    attack_weights: HashMap<usize, f64>,
+    argument_weights: HashMap<usize, f64>,
+}
+
+/// The status of an argument in the labelling induced by a set of arguments.
+///
+/// The arguments of the set are labelled `IN`, the ones it attacks are labelled `OUT`, and the other ones are labelled `UNDEC`.
+/// See [`AAFramework::labelling`].
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::LabellingStatus;
+/// assert_eq!("UNDEC", LabellingStatus::Undec.to_string());
+/// ```
+///
+/// [`AAFramework::labelling`]: struct.AAFramework.html#method.labelling
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LabellingStatus {
+    /// The argument belongs to the set.
+    In,
+    /// The argument is attacked by the set.
+    Out,
+    /// The argument neither belongs to the set nor is attacked by it.
+    Undec,
+}
+
+impl Display for LabellingStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LabellingStatus::In => write!(f, "IN"),
+            LabellingStatus::Out => write!(f, "OUT"),
+            LabellingStatus::Undec => write!(f, "UNDEC"),
+        }
+    }
 }
 
 /// An attack, represented as a couple of two arguments.
@@ -37,7 +79,7 @@ where
 /// Attacks are built by [`AAFramework`] objects.
 ///
 /// [`AAFramework`]: struct.AAFramework.html
-pub struct Attack<'a, T>(&'a Argument<T>, &'a Argument<T>)
+pub struct Attack<'a, T>(&'a Argument<T>, &'a Argument<T>, Option<f64>)
 where
     T: LabelType;
 
@@ -72,6 +114,23 @@ where
     pub fn attacked(&self) -> &'a Argument<T> {
         self.1
     }
+
+    /// Returns the weight of the attack, if any.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let labels = vec!["a", "b"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[0], &labels[1]).unwrap();
+    /// framework.new_weighted_attack(&labels[1], &labels[0], 0.7).unwrap();
+    /// let weights = framework.iter_attacks().map(|att| att.weight()).collect::<Vec<_>>();
+    /// assert_eq!(vec![None, Some(0.7)], weights);
+    /// ```
+    pub fn weight(&self) -> Option<f64> {
+        self.2
+    }
 }
 
 impl<'a, T> Display for Attack<'a, T>
@@ -83,6 +142,80 @@ where
     }
 }
 
+/// Displays the framework on several lines: the list of its arguments, followed by one line per attack.
+///
+/// Weights are given between parentheses after the arguments and attacks they are attached to.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{ArgumentSet, AAFramework};
+/// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+/// framework.new_attack(&"a", &"b").unwrap();
+/// framework.new_weighted_attack(&"b", &"c", 0.5).unwrap();
+/// assert_eq!("arguments: a, b, c\na → b\nb → c (0.5)\n", framework.to_string());
+/// ```
+impl<T> Display for AAFramework<T>
+where
+    T: LabelType,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "arguments:")?;
+        for (i, arg) in self.arguments.iter().enumerate() {
+            write!(f, "{}{}", if i == 0 { " " } else { ", " }, arg)?;
+            if let Some(w) = self.argument_weight(arg) {
+                write!(f, " ({})", w)?;
+            }
+        }
+        writeln!(f)?;
+        for attack in self.iter_attacks() {
+            match attack.weight() {
+                Some(w) => writeln!(f, "{} ({})", attack, w)?,
+                None => writeln!(f, "{}", attack)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Gives a compact, single-line description of the framework, listing its arguments and attacks by their labels.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{ArgumentSet, AAFramework};
+/// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+/// framework.new_attack(&"a", &"b").unwrap();
+/// assert_eq!(
+///     r#"AAFramework { arguments: ["a", "b"], attacks: [("a", "b")] }"#,
+///     format!("{:?}", framework)
+/// );
+/// ```
+impl<T> Debug for AAFramework<T>
+where
+    T: LabelType,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AAFramework")
+            .field(
+                "arguments",
+                &self
+                    .arguments
+                    .iter()
+                    .map(|a| a.label())
+                    .collect::<Vec<&T>>(),
+            )
+            .field(
+                "attacks",
+                &self
+                    .iter_attacks()
+                    .map(|a| (a.attacker().label(), a.attacked().label()))
+                    .collect::<Vec<(&T, &T)>>(),
+            )
+            .finish()
+    }
+}
+
 pub(crate) fn format_attack<T>(attacker: &T, attacked: &T) -> String
 where
     T: Display,
@@ -115,6 +248,8 @@ where
         AAFramework {
             arguments,
             attacks: vec![],
+            attack_weights: HashMap::new(),
+            argument_weights: HashMap::new(),
         } // kcov-ignore
     }
 
@@ -154,6 +289,34 @@ where
         Ok(())
     }
 
+    /// Adds a new weighted attack given the labels of the source and destination arguments.
+    ///
+    /// This function behaves like [`new_attack`], but also sets the weight of the attack.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - the label of the source arguments (attacker)
+    /// * `to` - the label of the destination argument (attacked)
+    /// * `weight` - the weight of the attack
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let labels = vec!["a", "b", "c"];
+    /// let arguments = ArgumentSet::new(labels.clone());
+    /// let mut framework = AAFramework::new(arguments);
+    /// framework.new_weighted_attack(&labels[0], &labels[1], 0.7);
+    /// assert_eq!(Some(0.7), framework.iter_attacks().next().unwrap().weight());
+    /// ```
+    ///
+    /// [`new_attack`]: struct.AAFramework.html#method.new_attack
+    pub fn new_weighted_attack(&mut self, from: &T, to: &T, weight: f64) -> Result<()> {
+        self.new_attack(from, to)?;
+        self.attack_weights.insert(self.attacks.len() - 1, weight);
+        Ok(())
+    }
+
     /// Adds a new attack given the IDs of the source and destination arguments.
     ///
     /// If the provided arguments are undefined, an error is returned.
@@ -191,6 +354,212 @@ where
         Ok(())
     }
 
+    /// Removes an argument given its label, as well as the attacks it is involved in.
+    ///
+    /// The removed attacks are returned as modifications, in the order they were added to the framework; they may be ignored by callers which do not need them.
+    /// The ids of the arguments following the removed one are decreased by one.
+    ///
+    /// If the provided argument is undefined, an error is returned.
+    /// See [`remove_argument`] for a variant refusing to remove arguments involved in attacks.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - the label of the argument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// # use crusti_arg::dynamics::Modification;
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// framework.new_attack(&"c", &"a").unwrap();
+    /// framework.new_attack(&"b", &"c").unwrap();
+    /// assert_eq!(
+    ///     vec![Modification::RemoveAttack("a", "b"), Modification::RemoveAttack("c", "a")],
+    ///     framework.remove_argument_cascade(&"a").unwrap()
+    /// );
+    /// assert_eq!("2 argument(s), 1 attack(s)", framework.summary());
+    /// ```
+    ///
+    /// [`remove_argument`]: #method.remove_argument
+    pub fn remove_argument_cascade(&mut self, label: &T) -> Result<Vec<Modification<T>>> {
+        let id = self
+            .arguments
+            .get_argument_index(label)
+            .with_context(|| format!("cannot remove argument {:?}", label))?;
+        let removed = self
+            .iter_attacks_of(self.arguments.get_argument_by_id(id))
+            .map(|att| {
+                Modification::RemoveAttack(
+                    att.attacker().label().clone(),
+                    att.attacked().label().clone(),
+                )
+            })
+            .collect();
+        self.remove_argument_by_id(id);
+        Ok(removed)
+    }
+
+    /// Removes an argument given its label, requiring it to be involved in no attack.
+    ///
+    /// If the provided argument is undefined or if it attacks or is attacked by an argument, an error is returned and the framework is left unchanged.
+    /// The ids of the arguments following the removed one are decreased by one.
+    /// See [`remove_argument_cascade`] for a variant removing the attacks too.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - the label of the argument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// assert!(framework.remove_argument(&"a").is_err());
+    /// framework.remove_argument(&"c").unwrap();
+    /// assert_eq!("2 argument(s), 1 attack(s)", framework.summary());
+    /// ```
+    ///
+    /// [`remove_argument_cascade`]: #method.remove_argument_cascade
+    pub fn remove_argument(&mut self, label: &T) -> Result<()> {
+        let context = || format!("cannot remove argument {:?}", label);
+        let id = self
+            .arguments
+            .get_argument_index(label)
+            .with_context(context)?;
+        let n_attacks = self
+            .iter_attacks_of(self.arguments.get_argument_by_id(id))
+            .count();
+        if n_attacks > 0 {
+            return Err(anyhow!(
+                "the argument is involved in {} attack(s)",
+                n_attacks
+            ))
+            .with_context(context);
+        }
+        self.remove_argument_by_id(id);
+        Ok(())
+    }
+
+    fn remove_argument_by_id(&mut self, id: usize) {
+        let shift = |i: usize| if i > id { i - 1 } else { i };
+        self.arguments = ArgumentSet::new(
+            self.arguments
+                .iter()
+                .filter(|a| a.id() != id)
+                .map(|a| a.label().clone())
+                .collect(),
+        );
+        let mut attacks = Vec::with_capacity(self.attacks.len());
+        let mut attack_weights = HashMap::new();
+        for (i, (from, to)) in self.attacks.iter().enumerate() {
+            if *from == id || *to == id {
+                continue;
+            }
+            if let Some(w) = self.attack_weights.get(&i) {
+                attack_weights.insert(attacks.len(), *w);
+            }
+            attacks.push((shift(*from), shift(*to)));
+        }
+        self.attacks = attacks;
+        self.attack_weights = attack_weights;
+        self.argument_weights = self
+            .argument_weights
+            .drain()
+            .filter(|(i, _)| *i != id)
+            .map(|(i, w)| (shift(i), w))
+            .collect();
+    }
+
+    /// Sets the weight of an argument given its label.
+    ///
+    /// If the provided argument is undefined, an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - the label of the argument
+    /// * `weight` - the weight of the argument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let labels = vec!["a", "b"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.set_argument_weight(&labels[0], 0.5).unwrap();
+    /// let a = framework.argument_set().get_argument_by_id(0);
+    /// assert_eq!(Some(0.5), framework.argument_weight(a));
+    /// ```
+    pub fn set_argument_weight(&mut self, label: &T, weight: f64) -> Result<()> {
+        let id = self
+            .arguments
+            .get_argument_index(label)
+            .with_context(|| format!("cannot set the weight of argument {:?}", label))?;
+        self.argument_weights.insert(id, weight);
+        Ok(())
+    }
+
+    /// Returns the weight of an argument, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `argument` - the argument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let labels = vec!["a", "b"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.set_argument_weight(&labels[0], 0.5).unwrap();
+    /// let b = framework.argument_set().get_argument_by_id(1);
+    /// assert_eq!(None, framework.argument_weight(b));
+    /// ```
+    pub fn argument_weight(&self, argument: &Argument<T>) -> Option<f64> {
+        self.argument_weights.get(&argument.id()).copied()
+    }
+
+    /// Returns `true` if a weight is attached to an argument or an attack of this framework.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let labels = vec!["a", "b"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// assert!(!framework.is_weighted());
+    /// framework.new_weighted_attack(&labels[0], &labels[1], 0.7).unwrap();
+    /// assert!(framework.is_weighted());
+    /// ```
+    pub fn is_weighted(&self) -> bool {
+        !self.attack_weights.is_empty() || !self.argument_weights.is_empty()
+    }
+
+    /// Returns a one-line summary of the framework, giving its numbers of arguments and attacks.
+    ///
+    /// The full content of the framework is given by its `Display` implementation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// assert_eq!("2 argument(s), 1 attack(s)", framework.summary());
+    /// framework.new_weighted_attack(&"b", &"a", 0.5).unwrap();
+    /// assert_eq!("2 argument(s), 2 attack(s), weighted", framework.summary());
+    /// ```
+    pub fn summary(&self) -> String {
+        format!(
+            "{} argument(s), {} attack(s){}",
+            self.arguments.len(),
+            self.attacks.len(),
+            if self.is_weighted() { ", weighted" } else { "" }
+        )
+    }
+
     /// Returns the argument set of the framework.
     ///
     /// # Example
@@ -206,7 +575,7 @@ where
         &self.arguments
     }
 
-    /// Provides an iterator to the attacks.
+    /// Provides an iterator to the attacks, in the order they were added to the framework.
     ///
     /// # Example
     ///
@@ -220,12 +589,67 @@ where
     /// assert_eq!(1, framework.iter_attacks().count());
     /// ```
     pub fn iter_attacks<'a>(&'a self) -> Box<dyn Iterator<Item = Attack<'a, T>> + 'a> {
-        Box::new(self.attacks.iter().map(move |att| {
-            Attack(
-                self.arguments.get_argument_by_id(att.0),
-                self.arguments.get_argument_by_id(att.1),
-            )
-        }))
+        Box::new((0..self.attacks.len()).map(move |i| self.attack(i)))
+    }
+
+    /// Provides an iterator to the attacks, sorted by the id of their attacker and then by the id of the attacked argument.
+    ///
+    /// Contrary to [`iter_attacks`], the order does not depend on the order in which the attacks were added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+    /// framework.new_attack_by_ids(1, 0).unwrap();
+    /// framework.new_attack_by_ids(0, 2).unwrap();
+    /// framework.new_attack_by_ids(0, 1).unwrap();
+    /// let attacks = framework.iter_attacks_by_ids().map(|a| a.to_string()).collect::<Vec<_>>();
+    /// assert_eq!(vec!["a → b", "a → c", "b → a"], attacks);
+    /// ```
+    ///
+    /// [`iter_attacks`]: #method.iter_attacks
+    pub fn iter_attacks_by_ids<'a>(&'a self) -> Box<dyn Iterator<Item = Attack<'a, T>> + 'a> {
+        let mut indices = (0..self.attacks.len()).collect::<Vec<usize>>();
+        indices.sort_by_key(|i| self.attacks[*i]);
+        Box::new(indices.into_iter().map(move |i| self.attack(i)))
+    }
+
+    /// Provides an iterator to the attacks, sorted by the label of their attacker and then by the label of the attacked argument.
+    ///
+    /// Contrary to [`iter_attacks`] and [`iter_attacks_by_ids`], the order depends neither on the order of the attacks nor on the order of the arguments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["c", "b", "a"]));
+    /// framework.new_attack(&"c", &"a").unwrap();
+    /// framework.new_attack(&"a", &"c").unwrap();
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// let attacks = framework.iter_attacks_by_labels().map(|a| a.to_string()).collect::<Vec<_>>();
+    /// assert_eq!(vec!["a → b", "a → c", "c → a"], attacks);
+    /// ```
+    ///
+    /// [`iter_attacks`]: #method.iter_attacks
+    /// [`iter_attacks_by_ids`]: #method.iter_attacks_by_ids
+    pub fn iter_attacks_by_labels<'a>(&'a self) -> Box<dyn Iterator<Item = Attack<'a, T>> + 'a>
+    where
+        T: Ord,
+    {
+        let label = |id| self.arguments.get_argument_by_id(id).label();
+        let mut indices = (0..self.attacks.len()).collect::<Vec<usize>>();
+        indices.sort_by_key(|i| (label(self.attacks[*i].0), label(self.attacks[*i].1)));
+        Box::new(indices.into_iter().map(move |i| self.attack(i)))
+    }
+
+    fn attack(&self, index: usize) -> Attack<'_, T> {
+        let (from, to) = self.attacks[index];
+        Attack(
+            self.arguments.get_argument_by_id(from),
+            self.arguments.get_argument_by_id(to),
+            self.attack_weights.get(&index).copied(),
+        )
     }
 
     /// returns the number of attacks in this framework.
@@ -241,9 +665,562 @@ where
     /// framework.new_attack_by_ids(0, 1); // "a" attacks "b"
     /// assert_eq!(1, framework.n_attacks());
     /// ```
-    pub fn n_attacks<'a>(&'a self) -> usize {
+    pub fn n_attacks(&self) -> usize {
         self.attacks.len()
     }
+
+    /// Returns the number of attacks from an argument to another.
+    ///
+    /// Since attacks may be added several times, this number may be greater than one.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - the attacker
+    /// * `to` - the attacked argument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// let a = framework.argument_set().get_argument_by_id(0);
+    /// let b = framework.argument_set().get_argument_by_id(1);
+    /// assert_eq!(1, framework.attacks_between(a, b));
+    /// assert_eq!(0, framework.attacks_between(b, a));
+    /// ```
+    pub fn attacks_between(&self, from: &Argument<T>, to: &Argument<T>) -> usize {
+        let pair = (from.id(), to.id());
+        self.attacks.iter().filter(|att| **att == pair).count()
+    }
+
+    /// Provides an iterator to the attacks involving an argument, either as the attacker or as the attacked argument.
+    ///
+    /// The attacks are given in the order they were added to the framework, as in [`iter_attacks`].
+    /// A self-attack is given once.
+    ///
+    /// # Arguments
+    ///
+    /// * `argument` - the argument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// framework.new_attack(&"c", &"a").unwrap();
+    /// framework.new_attack(&"b", &"c").unwrap();
+    /// let a = framework.argument_set().get_argument_by_id(0);
+    /// let attacks = framework.iter_attacks_of(a).map(|a| a.to_string()).collect::<Vec<_>>();
+    /// assert_eq!(vec!["a → b", "c → a"], attacks);
+    /// ```
+    ///
+    /// [`iter_attacks`]: #method.iter_attacks
+    pub fn iter_attacks_of<'a>(
+        &'a self,
+        argument: &Argument<T>,
+    ) -> Box<dyn Iterator<Item = Attack<'a, T>> + 'a> {
+        let id = argument.id();
+        Box::new(
+            (0..self.attacks.len())
+                .filter(move |i| self.attacks[*i].0 == id || self.attacks[*i].1 == id)
+                .map(move |i| self.attack(i)),
+        )
+    }
+
+    /// Returns `true` iff an argument belongs to at least one extension under the given semantics (credulous acceptance, `DC` problems).
+    ///
+    /// The grounded semantics is handled for frameworks of any size.
+    /// The other semantics are handled by the [`NaiveSolver`], and are thus only available for frameworks of at most [`MAX_ARGUMENTS`] arguments; an error is returned for larger frameworks.
+    /// An error is also returned if the argument is undefined.
+    ///
+    /// # Arguments
+    ///
+    /// * `arg` - the label of the argument
+    /// * `semantics` - the semantics
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework, Semantics};
+    /// let labels = vec!["a", "b"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[0], &labels[1]).unwrap();
+    /// framework.new_attack(&labels[1], &labels[0]).unwrap();
+    /// assert!(framework.credulously_accepted(&labels[0], Semantics::Preferred).unwrap());
+    /// assert!(!framework.credulously_accepted(&labels[0], Semantics::Grounded).unwrap());
+    /// ```
+    ///
+    /// [`NaiveSolver`]: solvers/struct.NaiveSolver.html
+    /// [`MAX_ARGUMENTS`]: solvers/constant.MAX_ARGUMENTS.html
+    pub fn credulously_accepted(&self, arg: &T, semantics: Semantics) -> Result<bool> {
+        solvers::is_accepted(self, arg, semantics, false)
+    }
+
+    /// Returns `true` iff an argument belongs to all the extensions under the given semantics (skeptical acceptance, `DS` problems).
+    ///
+    /// The grounded semantics and the complete semantics are handled for frameworks of any size.
+    /// The other semantics are handled by the [`NaiveSolver`], and are thus only available for frameworks of at most [`MAX_ARGUMENTS`] arguments; an error is returned for larger frameworks.
+    /// An error is also returned if the argument is undefined.
+    ///
+    /// Note that an argument is skeptically accepted by a semantics admitting no extension (e.g. the stable semantics for some frameworks).
+    ///
+    /// # Arguments
+    ///
+    /// * `arg` - the label of the argument
+    /// * `semantics` - the semantics
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework, Semantics};
+    /// let labels = vec!["a", "b", "c"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[0], &labels[1]).unwrap();
+    /// framework.new_attack(&labels[1], &labels[2]).unwrap();
+    /// assert!(framework.skeptically_accepted(&labels[2], Semantics::Complete).unwrap());
+    /// assert!(!framework.skeptically_accepted(&labels[1], Semantics::Stable).unwrap());
+    /// ```
+    ///
+    /// [`NaiveSolver`]: solvers/struct.NaiveSolver.html
+    /// [`MAX_ARGUMENTS`]: solvers/constant.MAX_ARGUMENTS.html
+    pub fn skeptically_accepted(&self, arg: &T, semantics: Semantics) -> Result<bool> {
+        solvers::is_accepted(self, arg, semantics, true)
+    }
+
+    /// Applies the characteristic function of the framework to a set of arguments.
+    ///
+    /// The result is the set of the arguments defended by the provided set, i.e. the arguments of which each attacker is attacked by an argument of the set.
+    /// Sets of arguments are given by membership vectors, indexed by the identifiers of the arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `set` - the set of arguments
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the membership vector differs from the number of arguments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let labels = vec!["a", "b", "c"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[0], &labels[1]).unwrap();
+    /// framework.new_attack(&labels[1], &labels[2]).unwrap();
+    /// assert_eq!(vec![true, false, false], framework.defended_by(&[false, false, false]));
+    /// assert_eq!(vec![true, false, true], framework.defended_by(&[true, false, false]));
+    /// ```
+    pub fn defended_by(&self, set: &[bool]) -> Vec<bool> {
+        self.characteristic_function(&self.attackers_lists(), set)
+    }
+
+    /// Returns `true` iff a set of arguments is a fixpoint of the characteristic function, i.e. a set defending exactly its own arguments.
+    ///
+    /// The conflict-free fixpoints of the characteristic function are the complete extensions of the framework.
+    ///
+    /// # Arguments
+    ///
+    /// * `set` - the set of arguments, as a membership vector
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the membership vector differs from the number of arguments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let labels = vec!["a", "b", "c"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[0], &labels[1]).unwrap();
+    /// framework.new_attack(&labels[1], &labels[2]).unwrap();
+    /// assert!(framework.is_fixpoint(&[true, false, true]));
+    /// assert!(!framework.is_fixpoint(&[true, false, false]));
+    /// ```
+    pub fn is_fixpoint(&self, set: &[bool]) -> bool {
+        self.defended_by(set) == set
+    }
+
+    /// Iterates the characteristic function from a set of arguments until a fixpoint is reached.
+    ///
+    /// The provided set must be included in the set it defends, ensuring the iteration converges to the least fixpoint containing it; an error is returned otherwise.
+    /// Starting from an admissible set, the result is the least complete extension containing it.
+    ///
+    /// # Arguments
+    ///
+    /// * `set` - the initial set of arguments, as a membership vector
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the membership vector differs from the number of arguments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let labels = vec!["a", "b", "c"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[0], &labels[1]).unwrap();
+    /// framework.new_attack(&labels[1], &labels[0]).unwrap();
+    /// framework.new_attack(&labels[1], &labels[2]).unwrap();
+    /// assert_eq!(vec![true, false, true], framework.fixpoint_from(&[true, false, false]).unwrap());
+    /// assert!(framework.fixpoint_from(&[false, false, true]).is_err());
+    /// ```
+    pub fn fixpoint_from(&self, set: &[bool]) -> Result<Vec<bool>> {
+        let attackers = self.attackers_lists();
+        let mut current = set.to_vec();
+        loop {
+            let next = self.characteristic_function(&attackers, &current);
+            if next == current {
+                return Ok(current);
+            }
+            if current.iter().zip(next.iter()).any(|(c, n)| *c && !*n) {
+                return Err(anyhow!(
+                    "cannot iterate the characteristic function: the initial set does not defend all its arguments"
+                ));
+            }
+            current = next;
+        }
+    }
+
+    /// Computes the least fixpoint of the characteristic function, i.e. the grounded extension of the framework.
+    ///
+    /// Instead of iterating the characteristic function, the extension is computed in linear time in the size of the framework:
+    /// the unattacked arguments are accepted, the arguments they attack are rejected, and each argument whose attackers are all rejected is accepted in turn.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let labels = vec!["a", "b", "c", "d"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[0], &labels[1]).unwrap();
+    /// framework.new_attack(&labels[1], &labels[2]).unwrap();
+    /// framework.new_attack(&labels[3], &labels[3]).unwrap();
+    /// assert_eq!(vec![true, false, true, false], framework.least_fixpoint());
+    /// ```
+    pub fn least_fixpoint(&self) -> Vec<bool> {
+        let n_args = self.arguments.len();
+        let mut n_attackers = vec![0; n_args];
+        let mut attacked_starts = vec![0; n_args + 1];
+        for (attacker, attacked) in self.attacks.iter() {
+            n_attackers[*attacked] += 1;
+            attacked_starts[*attacker + 1] += 1;
+        }
+        for i in 0..n_args {
+            attacked_starts[i + 1] += attacked_starts[i];
+        }
+        let mut attacked_lists = vec![0; self.attacks.len()];
+        let mut next_slot = attacked_starts.clone();
+        for (attacker, attacked) in self.attacks.iter() {
+            attacked_lists[next_slot[*attacker]] = *attacked;
+            next_slot[*attacker] += 1;
+        }
+        let attacked_by =
+            |arg: usize| &attacked_lists[attacked_starts[arg]..attacked_starts[arg + 1]];
+        let mut accepted = vec![false; n_args];
+        let mut rejected = vec![false; n_args];
+        let mut to_accept = (0..n_args)
+            .filter(|i| n_attackers[*i] == 0)
+            .collect::<Vec<usize>>();
+        while let Some(arg) = to_accept.pop() {
+            accepted[arg] = true;
+            for attacked in attacked_by(arg) {
+                if rejected[*attacked] {
+                    continue;
+                }
+                rejected[*attacked] = true;
+                for defended in attacked_by(*attacked) {
+                    n_attackers[*defended] -= 1;
+                    if n_attackers[*defended] == 0 {
+                        to_accept.push(*defended);
+                    }
+                }
+            }
+        }
+        accepted
+    }
+
+    /// Returns the set of the arguments attacked by at least one argument of a set.
+    ///
+    /// Sets of arguments are given by membership vectors, indexed by the identifiers of the arguments.
+    /// The computation is linear in the number of attacks.
+    ///
+    /// # Arguments
+    ///
+    /// * `set` - the set of arguments
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the membership vector differs from the number of arguments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let labels = vec!["a", "b", "c"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[0], &labels[1]).unwrap();
+    /// framework.new_attack(&labels[1], &labels[2]).unwrap();
+    /// assert_eq!(vec![false, true, false], framework.attacked_by(&[true, false, false]));
+    /// ```
+    pub fn attacked_by(&self, set: &[bool]) -> Vec<bool> {
+        self.check_set_len(set);
+        let mut attacked = vec![false; set.len()];
+        for (attacker, attacked_arg) in self.attacks.iter() {
+            if set[*attacker] {
+                attacked[*attacked_arg] = true;
+            }
+        }
+        attacked
+    }
+
+    /// Returns the range of a set of arguments, i.e. the arguments of the set and the ones it attacks.
+    ///
+    /// # Arguments
+    ///
+    /// * `set` - the set of arguments, as a membership vector
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the membership vector differs from the number of arguments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let labels = vec!["a", "b", "c"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[0], &labels[1]).unwrap();
+    /// assert_eq!(vec![true, true, false], framework.range_of(&[true, false, false]));
+    /// ```
+    pub fn range_of(&self, set: &[bool]) -> Vec<bool> {
+        let mut range = self.attacked_by(set);
+        range.iter_mut().zip(set.iter()).for_each(|(r, s)| *r |= *s);
+        range
+    }
+
+    /// Returns `true` iff a set of arguments is conflict-free, i.e. no argument of the set attacks an argument of the set.
+    ///
+    /// # Arguments
+    ///
+    /// * `set` - the set of arguments, as a membership vector
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the membership vector differs from the number of arguments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let labels = vec!["a", "b", "c"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[0], &labels[1]).unwrap();
+    /// assert!(framework.conflict_free(&[true, false, true]));
+    /// assert!(!framework.conflict_free(&[true, true, false]));
+    /// ```
+    pub fn conflict_free(&self, set: &[bool]) -> bool {
+        self.check_set_len(set);
+        self.attacks
+            .iter()
+            .all(|(attacker, attacked)| !set[*attacker] || !set[*attacked])
+    }
+
+    /// Returns the labelling induced by a set of arguments, indexed by the identifiers of the arguments.
+    ///
+    /// An argument of the set is labelled `IN`, even if the set attacks it; an argument attacked by the set is labelled `OUT`, and the other ones `UNDEC`.
+    /// Applied to the grounded extension given by [`least_fixpoint`], this function returns the grounded labelling.
+    ///
+    /// # Arguments
+    ///
+    /// * `set` - the set of arguments, as a membership vector
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the membership vector differs from the number of arguments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework, LabellingStatus};
+    /// let labels = vec!["a", "b", "c"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[0], &labels[1]).unwrap();
+    /// framework.new_attack(&labels[2], &labels[2]).unwrap();
+    /// assert_eq!(
+    ///     vec![LabellingStatus::In, LabellingStatus::Out, LabellingStatus::Undec],
+    ///     framework.labelling(&framework.least_fixpoint())
+    /// );
+    /// ```
+    ///
+    /// [`least_fixpoint`]: struct.AAFramework.html#method.least_fixpoint
+    pub fn labelling(&self, set: &[bool]) -> Vec<LabellingStatus> {
+        self.attacked_by(set)
+            .iter()
+            .zip(set.iter())
+            .map(|(attacked, member)| match (member, attacked) {
+                (true, _) => LabellingStatus::In,
+                (false, true) => LabellingStatus::Out,
+                (false, false) => LabellingStatus::Undec,
+            })
+            .collect()
+    }
+
+    /// Computes the in-degrees and out-degrees of the arguments.
+    ///
+    /// See the [`stats`](stats/index.html) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// let stats = framework.degree_stats();
+    /// assert_eq!(1, stats.max_in_degree());
+    /// assert_eq!(0.5, stats.mean_degree());
+    /// ```
+    pub fn degree_stats(&self) -> DegreeStats {
+        DegreeStats::new(self.arguments.len(), &self.attacks)
+    }
+
+    /// Returns the highest number of attackers of an argument, or 0 if the framework has no argument.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// framework.new_attack(&"b", &"b").unwrap();
+    /// assert_eq!(2, framework.max_in_degree());
+    /// ```
+    pub fn max_in_degree(&self) -> usize {
+        self.degree_stats().max_in_degree()
+    }
+
+    /// Returns the density of the attack graph, i.e. its number of attacks divided by the number of possible attacks (including self-attacks).
+    ///
+    /// The density of a framework without arguments is 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// assert_eq!(0.25, framework.density());
+    /// ```
+    pub fn density(&self) -> f64 {
+        let n_args = self.arguments.len();
+        if n_args == 0 {
+            0.
+        } else {
+            self.attacks.len() as f64 / (n_args * n_args) as f64
+        }
+    }
+
+    /// Computes the strongly connected components of the attack graph.
+    ///
+    /// Components are given as lists of argument identifiers, in a topological order: the attackers of the arguments of a component belong to this component or to a previous one.
+    /// The computation is linear in the size of the framework.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let labels = vec!["a", "b", "c"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[1], &labels[2]).unwrap();
+    /// framework.new_attack(&labels[2], &labels[1]).unwrap();
+    /// framework.new_attack(&labels[2], &labels[0]).unwrap();
+    /// assert_eq!(vec![vec![1, 2], vec![0]], framework.strongly_connected_components());
+    /// ```
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let n_args = self.arguments.len();
+        let mut attacked_lists = vec![vec![]; n_args];
+        for (attacker, attacked) in self.attacks.iter() {
+            attacked_lists[*attacker].push(*attacked);
+        }
+        let mut indices: Vec<Option<usize>> = vec![None; n_args];
+        let mut low_links = vec![0; n_args];
+        let mut on_stack = vec![false; n_args];
+        let mut stack = vec![];
+        let mut components = vec![];
+        let mut next_index = 0;
+        for root in 0..n_args {
+            if indices[root].is_some() {
+                continue;
+            }
+            let mut call_stack = vec![(root, 0)];
+            while let Some((arg, next_child)) = call_stack.pop() {
+                if next_child == 0 {
+                    indices[arg] = Some(next_index);
+                    low_links[arg] = next_index;
+                    next_index += 1;
+                    stack.push(arg);
+                    on_stack[arg] = true;
+                }
+                if let Some(child) = attacked_lists[arg].get(next_child) {
+                    call_stack.push((arg, next_child + 1));
+                    match indices[*child] {
+                        None => call_stack.push((*child, 0)),
+                        Some(child_index) if on_stack[*child] => {
+                            low_links[arg] = usize::min(low_links[arg], child_index)
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+                if low_links[arg] == indices[arg].unwrap() {
+                    let mut component = vec![];
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == arg {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    components.push(component);
+                }
+                if let Some((parent, _)) = call_stack.last() {
+                    low_links[*parent] = usize::min(low_links[*parent], low_links[arg]);
+                }
+            }
+        }
+        components.reverse();
+        components
+    }
+
+    fn check_set_len(&self, set: &[bool]) {
+        assert_eq!(
+            self.arguments.len(),
+            set.len(),
+            "the membership vector does not match the number of arguments"
+        );
+    }
+
+    fn attackers_lists(&self) -> Vec<Vec<usize>> {
+        let mut attackers = vec![vec![]; self.arguments.len()];
+        for (attacker, attacked) in self.attacks.iter() {
+            attackers[*attacked].push(*attacker);
+        }
+        attackers
+    }
+
+    fn characteristic_function(&self, attackers: &[Vec<usize>], set: &[bool]) -> Vec<bool> {
+        let attacked_by_set = self.attacked_by(set);
+        attackers
+            .iter()
+            .map(|att| att.iter().all(|a| attacked_by_set[*a]))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -300,6 +1277,284 @@ mod tests {
         attacks.new_attack_by_ids(3, 0).unwrap_err();
     }
 
+    #[test]
+    fn test_attacks_between_and_of() {
+        let mut af = AAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+        af.new_attack(&"a", &"b").unwrap();
+        af.new_attack(&"a", &"b").unwrap();
+        af.new_weighted_attack(&"c", &"c", 0.5).unwrap();
+        let arg = |id| af.argument_set().get_argument_by_id(id);
+        assert_eq!(2, af.attacks_between(arg(0), arg(1)));
+        assert_eq!(0, af.attacks_between(arg(1), arg(0)));
+        assert_eq!(1, af.attacks_between(arg(2), arg(2)));
+        assert_eq!(2, af.iter_attacks_of(arg(1)).count());
+        let self_attacks = af.iter_attacks_of(arg(2)).collect::<Vec<_>>();
+        assert_eq!(1, self_attacks.len());
+        assert_eq!(Some(0.5), self_attacks[0].weight());
+    }
+
+    #[test]
+    fn test_remove_argument_cascade() {
+        let mut af = AAFramework::new(ArgumentSet::new(vec!["a", "b", "c", "d"]));
+        af.new_weighted_attack(&"b", &"b", 0.1).unwrap();
+        af.new_weighted_attack(&"a", &"c", 0.2).unwrap();
+        af.new_weighted_attack(&"d", &"c", 0.3).unwrap();
+        af.set_argument_weight(&"b", 1.).unwrap();
+        af.set_argument_weight(&"d", 2.).unwrap();
+        assert_eq!(
+            vec![Modification::RemoveAttack("b", "b")],
+            af.remove_argument_cascade(&"b").unwrap()
+        );
+        assert_eq!(vec![(0, 1), (2, 1)], af.attacks);
+        let attacks = af
+            .iter_attacks()
+            .map(|att| (att.to_string(), att.weight()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("a → c".to_string(), Some(0.2)),
+                ("d → c".to_string(), Some(0.3))
+            ],
+            attacks
+        );
+        let d = af.argument_set().get_argument_by_id(2);
+        assert_eq!(&"d", d.label());
+        assert_eq!(Some(2.), af.argument_weight(d));
+        assert_eq!(1, af.argument_weights.len());
+        assert!(af.remove_argument_cascade(&"b").is_err());
+    }
+
+    #[test]
+    fn test_remove_argument_strict() {
+        let mut af = AAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+        af.new_attack(&"c", &"c").unwrap();
+        assert!(af.remove_argument(&"c").is_err());
+        assert!(af.remove_argument(&"d").is_err());
+        assert_eq!(3, af.argument_set().len());
+        af.remove_argument(&"a").unwrap();
+        assert_eq!(vec![(1, 1)], af.attacks);
+        assert_eq!(&"b", af.argument_set().get_argument_by_id(0).label());
+    }
+
+    #[test]
+    fn test_display() {
+        let mut framework = AAFramework::new(ArgumentSet::new(vec![] as Vec<String>));
+        assert_eq!("arguments:\n", framework.to_string());
+        assert_eq!("0 argument(s), 0 attack(s)", framework.summary());
+        framework = AAFramework::new(ArgumentSet::new(vec!["a".to_string()]));
+        framework.set_argument_weight(&"a".to_string(), 2.).unwrap();
+        framework
+            .new_attack(&"a".to_string(), &"a".to_string())
+            .unwrap();
+        assert_eq!("arguments: a (2)\na → a\n", framework.to_string());
+        assert_eq!("1 argument(s), 1 attack(s), weighted", framework.summary());
+    }
+
+    #[test]
+    fn test_sorted_attacks_keep_weights() {
+        let arg_labels = vec!["b".to_string(), "a".to_string()];
+        let mut framework = AAFramework::new(ArgumentSet::new(arg_labels.clone()));
+        framework
+            .new_weighted_attack(&arg_labels[1], &arg_labels[0], 0.5)
+            .unwrap();
+        framework
+            .new_attack(&arg_labels[0], &arg_labels[1])
+            .unwrap();
+        let describe = |att: Attack<String>| (att.to_string(), att.weight());
+        assert_eq!(
+            vec![
+                ("b → a".to_string(), None),
+                ("a → b".to_string(), Some(0.5))
+            ],
+            framework
+                .iter_attacks_by_ids()
+                .map(describe)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                ("a → b".to_string(), Some(0.5)),
+                ("b → a".to_string(), None)
+            ],
+            framework
+                .iter_attacks_by_labels()
+                .map(describe)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_weights() {
+        let arg_labels = vec!["a".to_string(), "b".to_string()];
+        let mut framework = AAFramework::new(ArgumentSet::new(arg_labels.clone()));
+        framework
+            .new_attack(&arg_labels[0], &arg_labels[1])
+            .unwrap();
+        framework
+            .new_weighted_attack(&arg_labels[1], &arg_labels[0], 0.25)
+            .unwrap();
+        assert!(framework
+            .new_weighted_attack(&arg_labels[1], &"c".to_string(), 0.5)
+            .is_err());
+        assert_eq!(2, framework.n_attacks());
+        assert_eq!(
+            vec![None, Some(0.25)],
+            framework
+                .iter_attacks()
+                .map(|att| att.weight())
+                .collect::<Vec<Option<f64>>>()
+        );
+        framework.set_argument_weight(&arg_labels[1], 2.).unwrap();
+        assert!(framework.set_argument_weight(&"c".to_string(), 2.).is_err());
+        let args = framework.argument_set();
+        assert_eq!(None, framework.argument_weight(args.get_argument_by_id(0)));
+        assert_eq!(
+            Some(2.),
+            framework.argument_weight(args.get_argument_by_id(1))
+        );
+    }
+
+    #[test]
+    fn test_fixpoints() {
+        let arg_labels = vec!["a", "b", "c", "d"];
+        let mut framework = AAFramework::new(ArgumentSet::new(arg_labels.clone()));
+        framework.new_attack(&"a", &"b").unwrap();
+        framework.new_attack(&"b", &"a").unwrap();
+        framework.new_attack(&"b", &"c").unwrap();
+        framework.new_attack(&"c", &"d").unwrap();
+        assert_eq!(vec![false; 4], framework.least_fixpoint());
+        assert!(framework.is_fixpoint(&[false; 4]));
+        assert_eq!(
+            vec![false, true, false, true],
+            framework
+                .fixpoint_from(&[false, true, false, false])
+                .unwrap()
+        );
+        assert!(framework.is_fixpoint(&[false, true, false, true]));
+        assert!(framework.is_fixpoint(&[true, false, true, false]));
+        assert!(framework
+            .fixpoint_from(&[false, false, false, true])
+            .is_err());
+    }
+
+    #[test]
+    fn test_range_helpers() {
+        let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b", "c", "d"]));
+        framework.new_attack(&"a", &"b").unwrap();
+        framework.new_attack(&"b", &"c").unwrap();
+        framework.new_attack(&"d", &"d").unwrap();
+        assert_eq!(
+            vec![false, true, true, false],
+            framework.attacked_by(&[true, true, false, false])
+        );
+        assert_eq!(
+            vec![true, true, true, false],
+            framework.range_of(&[true, true, false, false])
+        );
+        assert_eq!(vec![false; 4], framework.range_of(&[false; 4]));
+        assert!(framework.conflict_free(&[true, false, true, false]));
+        assert!(!framework.conflict_free(&[false, true, true, false]));
+        assert!(!framework.conflict_free(&[false, false, false, true]));
+    }
+
+    #[test]
+    fn test_labelling() {
+        let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b", "c", "d"]));
+        framework.new_attack(&"a", &"b").unwrap();
+        framework.new_attack(&"b", &"c").unwrap();
+        framework.new_attack(&"d", &"d").unwrap();
+        assert_eq!(
+            vec![
+                LabellingStatus::In,
+                LabellingStatus::Out,
+                LabellingStatus::In,
+                LabellingStatus::Undec
+            ],
+            framework.labelling(&framework.least_fixpoint())
+        );
+        assert_eq!(
+            vec![LabellingStatus::Undec; 4],
+            framework.labelling(&[false; 4])
+        );
+    }
+
+    #[test]
+    fn test_least_fixpoint_matches_iteration() {
+        let mut framework = AAFramework::new(ArgumentSet::new((0..8).collect::<Vec<usize>>()));
+        for (from, to) in &[
+            (0, 1),
+            (1, 2),
+            (2, 3),
+            (3, 3),
+            (4, 5),
+            (5, 4),
+            (6, 7),
+            (6, 7),
+            (7, 0),
+        ] {
+            framework.new_attack_by_ids(*from, *to).unwrap();
+        }
+        let expected = framework.fixpoint_from(&[false; 8]).unwrap();
+        assert_eq!(
+            vec![true, false, true, false, false, false, true, false],
+            expected
+        );
+        assert_eq!(expected, framework.least_fixpoint());
+        assert!(framework.is_fixpoint(&framework.least_fixpoint()));
+    }
+
+    #[test]
+    fn test_least_fixpoint_million_attacks() {
+        let n_args = 250_000;
+        let mut framework = AAFramework::new(ArgumentSet::new((0..n_args).collect::<Vec<usize>>()));
+        for i in 0..n_args - 1 {
+            framework.new_attack_by_ids(i, i + 1).unwrap();
+        }
+        for i in 0..3 * n_args {
+            framework
+                .new_attack_by_ids(i % n_args, (i * 7 + 3) % n_args)
+                .unwrap();
+        }
+        let grounded = framework.least_fixpoint();
+        assert!(framework.conflict_free(&grounded));
+        assert!(framework.is_fixpoint(&grounded));
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let mut framework = AAFramework::new(ArgumentSet::new((0..7).collect::<Vec<usize>>()));
+        for (from, to) in &[
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (4, 3),
+            (5, 5),
+            (6, 0),
+        ] {
+            framework.new_attack_by_ids(*from, *to).unwrap();
+        }
+        let components = framework.strongly_connected_components();
+        assert_eq!(4, components.len());
+        let position = |arg: usize| components.iter().position(|c| c.contains(&arg)).unwrap();
+        assert_eq!(vec![0, 1, 2], components[position(0)]);
+        assert_eq!(vec![3, 4], components[position(3)]);
+        assert_eq!(vec![5], components[position(5)]);
+        assert!(position(6) < position(0));
+        assert!(position(0) < position(3));
+        assert!(AAFramework::new(ArgumentSet::<usize>::new(vec![]))
+            .strongly_connected_components()
+            .is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_defended_by_wrong_length() {
+        let framework = AAFramework::new(ArgumentSet::new(vec!["a"]));
+        framework.defended_by(&[false, false]);
+    }
+
     #[test]
     fn test_new_attack_by_ids_unknown_id_2() {
         let arg_labels = vec!["a".to_string(), "b".to_string(), "c".to_string()];
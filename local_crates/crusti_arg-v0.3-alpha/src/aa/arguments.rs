@@ -18,6 +18,7 @@
 //   *   CRIL - initial API and implementation
 
 use anyhow::{anyhow, Result};
+use std::borrow::Borrow;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fmt::Display;
@@ -165,6 +166,7 @@ where
     ///
     /// If no such label exists, an error is returned.
     ///
+    /// The label may be given by any borrowed form of the label type, e.g. a `&str` for `String` labels.
     /// See constructor methods for information about indexes.
     ///
     /// # Arguments
@@ -180,12 +182,18 @@ where
     /// assert_eq!(0, arguments.get_argument_index(&labels[0]).unwrap());
     /// assert_eq!(1, arguments.get_argument_index(&labels[1]).unwrap());
     /// assert_eq!(2, arguments.get_argument_index(&labels[2]).unwrap());
+    /// let owned = ArgumentSet::new(vec!["a".to_string()]);
+    /// assert_eq!(0, owned.get_argument_index("a").unwrap());
     /// ```
-    pub fn get_argument_index(&self, label: &T) -> Result<usize> {
+    pub fn get_argument_index<Q>(&self, label: &Q) -> Result<usize>
+    where
+        T: Borrow<Q>,
+        Q: Display + Eq + Hash + ?Sized,
+    {
         self.label_to_id
             .get(label)
             .ok_or_else(|| anyhow!("no such argument: {}", label))
-            .map(|i| *i)
+            .copied()
     }
 
     /// Returns the argument with the corresponding id.
@@ -210,7 +218,7 @@ where
         &self.arguments[id]
     }
 
-    /// Returns an iterator to the arguments.
+    /// Returns an iterator to the arguments, in the order of their ids.
     ///
     /// # Example
     ///
@@ -223,6 +231,172 @@ where
     pub fn iter(&self) -> std::slice::Iter<'_, Argument<T>> {
         self.arguments.iter()
     }
+
+    /// Returns an iterator to the arguments, sorted by their labels.
+    ///
+    /// Contrary to [`iter`], the order does not depend on the order in which the labels were given to the set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::ArgumentSet;
+    /// let arguments = ArgumentSet::new(vec!["c", "a", "b"]);
+    /// let labels = arguments.iter_sorted().map(|a| *a.label()).collect::<Vec<_>>();
+    /// assert_eq!(vec!["a", "b", "c"], labels);
+    /// ```
+    ///
+    /// [`iter`]: #method.iter
+    pub fn iter_sorted(&self) -> std::vec::IntoIter<&Argument<T>>
+    where
+        T: Ord,
+    {
+        let mut arguments = self.arguments.iter().collect::<Vec<&Argument<T>>>();
+        arguments.sort_by(|a, b| a.label.cmp(&b.label));
+        arguments.into_iter()
+    }
+
+    /// Returns `true` iff the set contains an argument with the provided label.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - the argument label
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::ArgumentSet;
+    /// let arguments = ArgumentSet::new(vec!["a", "b"]);
+    /// assert!(arguments.contains(&"a"));
+    /// assert!(!arguments.contains(&"c"));
+    /// ```
+    pub fn contains<Q>(&self, label: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.label_to_id.contains_key(label)
+    }
+
+    /// Returns `true` iff each argument of this set has a label belonging to the other set.
+    ///
+    /// The ids of the arguments are not considered.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - the other set
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::ArgumentSet;
+    /// let arguments = ArgumentSet::new(vec!["b", "a"]);
+    /// assert!(arguments.is_subset_of(&ArgumentSet::new(vec!["a", "b", "c"])));
+    /// assert!(!arguments.is_subset_of(&ArgumentSet::new(vec!["a"])));
+    /// ```
+    pub fn is_subset_of(&self, other: &ArgumentSet<T>) -> bool {
+        self.iter().all(|a| other.contains(a.label()))
+    }
+
+    /// Builds the set of the arguments belonging to this set or to the other one.
+    ///
+    /// The arguments of this set come first, followed by the ones of the other set which are not in this one, each in the order of their set.
+    /// Ids are assigned according to this order.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - the other set
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::ArgumentSet;
+    /// let union = ArgumentSet::new(vec!["a", "b"]).union(&ArgumentSet::new(vec!["c", "b"]));
+    /// assert_eq!(vec!["a", "b", "c"], union.iter().map(|a| *a.label()).collect::<Vec<_>>());
+    /// ```
+    pub fn union(&self, other: &ArgumentSet<T>) -> ArgumentSet<T> {
+        ArgumentSet::new(
+            self.iter()
+                .chain(other.iter().filter(|a| !self.contains(a.label())))
+                .map(|a| a.label().clone())
+                .collect(),
+        )
+    }
+
+    /// Builds the set of the arguments belonging to both this set and the other one, in the order of this set.
+    ///
+    /// Ids are assigned according to this order.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - the other set
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::ArgumentSet;
+    /// let intersection = ArgumentSet::new(vec!["a", "b", "c"]).intersection(&ArgumentSet::new(vec!["c", "a"]));
+    /// assert_eq!(vec!["a", "c"], intersection.iter().map(|a| *a.label()).collect::<Vec<_>>());
+    /// ```
+    pub fn intersection(&self, other: &ArgumentSet<T>) -> ArgumentSet<T> {
+        self.filtered(|a| other.contains(a.label()))
+    }
+
+    /// Builds the set of the arguments belonging to this set but not to the other one, in the order of this set.
+    ///
+    /// Ids are assigned according to this order.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - the other set
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::ArgumentSet;
+    /// let difference = ArgumentSet::new(vec!["a", "b", "c"]).difference(&ArgumentSet::new(vec!["b"]));
+    /// assert_eq!(vec!["a", "c"], difference.iter().map(|a| *a.label()).collect::<Vec<_>>());
+    /// ```
+    pub fn difference(&self, other: &ArgumentSet<T>) -> ArgumentSet<T> {
+        self.filtered(|a| !other.contains(a.label()))
+    }
+
+    /// Builds the set of the arguments belonging to exactly one of this set and the other one.
+    ///
+    /// The arguments of this set come first, followed by the ones of the other set, each in the order of their set.
+    /// Ids are assigned according to this order.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - the other set
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::ArgumentSet;
+    /// let difference = ArgumentSet::new(vec!["a", "b"]).symmetric_difference(&ArgumentSet::new(vec!["c", "b"]));
+    /// assert_eq!(vec!["a", "c"], difference.iter().map(|a| *a.label()).collect::<Vec<_>>());
+    /// ```
+    pub fn symmetric_difference(&self, other: &ArgumentSet<T>) -> ArgumentSet<T> {
+        ArgumentSet::new(
+            self.iter()
+                .filter(|a| !other.contains(a.label()))
+                .chain(other.iter().filter(|a| !self.contains(a.label())))
+                .map(|a| a.label().clone())
+                .collect(),
+        )
+    }
+
+    fn filtered<P>(&self, predicate: P) -> ArgumentSet<T>
+    where
+        P: Fn(&Argument<T>) -> bool,
+    {
+        ArgumentSet::new(
+            self.iter()
+                .filter(|a| predicate(a))
+                .map(|a| a.label().clone())
+                .collect(),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -250,6 +424,47 @@ mod tests {
         assert!(args.is_empty());
     }
 
+    fn labels(args: &ArgumentSet<&'static str>) -> Vec<&'static str> {
+        args.iter().map(|a| *a.label()).collect()
+    }
+
+    #[test]
+    fn test_set_algebra() {
+        let first = ArgumentSet::new(vec!["a", "b", "c"]);
+        let second = ArgumentSet::new(vec!["d", "c", "a"]);
+        assert_eq!(vec!["a", "b", "c", "d"], labels(&first.union(&second)));
+        assert_eq!(vec!["a", "c"], labels(&first.intersection(&second)));
+        assert_eq!(vec!["b"], labels(&first.difference(&second)));
+        assert_eq!(vec!["d"], labels(&second.difference(&first)));
+        assert_eq!(vec!["b", "d"], labels(&first.symmetric_difference(&second)));
+        let union = first.union(&second);
+        assert_eq!(3, union.get_argument_index(&"d").unwrap());
+        assert!(first.intersection(&second).is_subset_of(&first));
+        assert!(!first.is_subset_of(&second));
+        assert!(ArgumentSet::new(vec![]).is_subset_of(&first));
+    }
+
+    #[test]
+    fn test_iter_sorted() {
+        let args = ArgumentSet::new(vec!["b".to_string(), "c".to_string(), "a".to_string()]);
+        assert_eq!(
+            vec![(2, "a"), (0, "b"), (1, "c")],
+            args.iter_sorted()
+                .map(|a| (a.id(), a.label().as_str()))
+                .collect::<Vec<(usize, &str)>>()
+        );
+    }
+
+    #[test]
+    fn test_set_algebra_empty() {
+        let empty = ArgumentSet::new(vec![] as Vec<&str>);
+        let first = ArgumentSet::new(vec!["a"]);
+        assert_eq!(vec!["a"], labels(&empty.union(&first)));
+        assert!(empty.intersection(&first).is_empty());
+        assert_eq!(vec!["a"], labels(&first.difference(&empty)));
+        assert_eq!(vec!["a"], labels(&empty.symmetric_difference(&first)));
+    }
+
     #[test]
     fn test_into_iterator() {
         let arg_labels = vec!["a".to_string(), "b".to_string(), "c".to_string()];
@@ -0,0 +1,282 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::aa_framework::AAFramework;
+use crate::aa::arguments::{Argument, ArgumentSet, LabelType};
+use anyhow::{Context, Result};
+use std::fmt::Display;
+
+/// A Bipolar Argumentation framework, in which arguments may attack or support each other.
+///
+/// The attack relation is handled by an underlying [`AAFramework`], while supports are stored alongside it.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{ArgumentSet, BAFramework};
+/// let labels = vec!["a", "b", "c"];
+/// let mut framework = BAFramework::new(ArgumentSet::new(labels.clone()));
+/// framework.new_attack(&labels[0], &labels[1]).unwrap();
+/// framework.new_support(&labels[2], &labels[0]).unwrap();
+/// assert_eq!(1, framework.attack_framework().n_attacks());
+/// assert_eq!(1, framework.n_supports());
+/// ```
+///
+/// [`AAFramework`]: struct.AAFramework.html
+pub struct BAFramework<T>
+where
+    T: LabelType,
+{
+    framework: AAFramework<T>,
+    supports: Vec<(usize, usize)>,
+}
+
+/// A support, represented as a couple of two arguments.
+///
+/// Supports are built by [`BAFramework`] objects.
+///
+/// [`BAFramework`]: struct.BAFramework.html
+pub struct Support<'a, T>(&'a Argument<T>, &'a Argument<T>)
+where
+    T: LabelType;
+
+impl<'a, T> Support<'a, T>
+where
+    T: LabelType,
+{
+    /// Returns the supporting argument.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// # use crusti_arg::{Support, LabelType};
+    /// fn describe_support<T: LabelType>(support: &Support<T>) {
+    ///     println!("{} supports {}", support.supporter(), support.supported());
+    /// }
+    /// ```
+    pub fn supporter(&self) -> &'a Argument<T> {
+        self.0
+    }
+
+    /// Returns the supported argument.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// # use crusti_arg::{Support, LabelType};
+    /// fn describe_support<T: LabelType>(support: &Support<T>) {
+    ///     println!("{} supports {}", support.supporter(), support.supported());
+    /// }
+    /// ```
+    pub fn supported(&self) -> &'a Argument<T> {
+        self.1
+    }
+}
+
+impl<'a, T> Display for Support<'a, T>
+where
+    T: LabelType,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ⇒ {}", self.0, self.1)
+    }
+}
+
+impl<T> BAFramework<T>
+where
+    T: LabelType,
+{
+    /// Builds a bipolar framework with no attacks and no supports.
+    ///
+    /// # Arguments
+    ///
+    /// * `arguments` - the set of arguments
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, BAFramework};
+    /// let framework = BAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+    /// assert_eq!(3, framework.argument_set().len());
+    /// assert_eq!(0, framework.n_supports());
+    /// ```
+    pub fn new(arguments: ArgumentSet<T>) -> Self {
+        BAFramework {
+            framework: AAFramework::new(arguments),
+            supports: vec![],
+        } // kcov-ignore
+    }
+
+    /// Adds a new attack given the labels of the source and destination arguments.
+    ///
+    /// See [`AAFramework::new_attack`] for more information.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - the label of the source arguments (attacker)
+    /// * `to` - the label of the destination argument (attacked)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, BAFramework};
+    /// let labels = vec!["a", "b"];
+    /// let mut framework = BAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[0], &labels[1]).unwrap();
+    /// assert_eq!(1, framework.attack_framework().n_attacks());
+    /// ```
+    ///
+    /// [`AAFramework::new_attack`]: struct.AAFramework.html#method.new_attack
+    pub fn new_attack(&mut self, from: &T, to: &T) -> Result<()> {
+        self.framework.new_attack(from, to)
+    }
+
+    /// Adds a new support given the labels of the source and destination arguments.
+    ///
+    /// If the provided arguments are undefined, an error is returned.
+    /// Else, the support is added.
+    ///
+    /// If the support already exists, it is added another time (no checks are made for existence).
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - the label of the source arguments (supporter)
+    /// * `to` - the label of the destination argument (supported)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, BAFramework};
+    /// let labels = vec!["a", "b"];
+    /// let mut framework = BAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_support(&labels[0], &labels[1]).unwrap();
+    /// assert_eq!(1, framework.n_supports());
+    /// ```
+    pub fn new_support(&mut self, from: &T, to: &T) -> Result<()> {
+        let context = || format!("cannot add a support from {:?} to {:?}", from, to);
+        let arguments = self.framework.argument_set();
+        let from_id = arguments.get_argument_index(from).with_context(context)?;
+        let to_id = arguments.get_argument_index(to).with_context(context)?;
+        self.supports.push((from_id, to_id));
+        Ok(())
+    }
+
+    /// Returns the argument set of the framework.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, BAFramework};
+    /// let framework = BAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+    /// assert_eq!(3, framework.argument_set().len());
+    /// ```
+    pub fn argument_set(&self) -> &ArgumentSet<T> {
+        self.framework.argument_set()
+    }
+
+    /// Returns the framework made of the arguments and the attacks of this bipolar framework.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, BAFramework};
+    /// let labels = vec!["a", "b"];
+    /// let mut framework = BAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[0], &labels[1]).unwrap();
+    /// framework.new_support(&labels[1], &labels[0]).unwrap();
+    /// assert_eq!(1, framework.attack_framework().n_attacks());
+    /// ```
+    pub fn attack_framework(&self) -> &AAFramework<T> {
+        &self.framework
+    }
+
+    /// Provides an iterator to the supports.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, BAFramework};
+    /// let labels = vec!["a", "b"];
+    /// let mut framework = BAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_support(&labels[0], &labels[1]).unwrap();
+    /// let support = framework.iter_supports().next().unwrap();
+    /// assert_eq!(&"a", support.supporter().label());
+    /// ```
+    pub fn iter_supports<'a>(&'a self) -> Box<dyn Iterator<Item = Support<'a, T>> + 'a> {
+        let arguments = self.framework.argument_set();
+        Box::new(self.supports.iter().map(move |sup| {
+            Support(
+                arguments.get_argument_by_id(sup.0),
+                arguments.get_argument_by_id(sup.1),
+            )
+        }))
+    }
+
+    /// Returns the number of supports in this framework.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, BAFramework};
+    /// let labels = vec!["a", "b"];
+    /// let mut framework = BAFramework::new(ArgumentSet::new(labels.clone()));
+    /// assert_eq!(0, framework.n_supports());
+    /// framework.new_support(&labels[0], &labels[1]).unwrap();
+    /// assert_eq!(1, framework.n_supports());
+    /// ```
+    pub fn n_supports(&self) -> usize {
+        self.supports.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_support_ok() {
+        let arg_labels = vec!["a".to_string(), "b".to_string()];
+        let mut framework = BAFramework::new(ArgumentSet::new(arg_labels.clone()));
+        framework
+            .new_support(&arg_labels[0], &arg_labels[1])
+            .unwrap();
+        assert_eq!(vec![(0, 1)], framework.supports);
+        assert_eq!(0, framework.attack_framework().n_attacks());
+        assert_eq!(
+            vec!["a ⇒ b".to_string()],
+            framework
+                .iter_supports()
+                .map(|s| s.to_string())
+                .collect::<Vec<String>>()
+        );
+    }
+
+    #[test]
+    fn test_new_support_unknown_label() {
+        let arg_labels = vec!["a".to_string(), "b".to_string()];
+        let mut framework = BAFramework::new(ArgumentSet::new(arg_labels.clone()));
+        assert!(framework
+            .new_support(&arg_labels[0], &"c".to_string())
+            .is_err());
+        assert!(framework
+            .new_support(&"c".to_string(), &arg_labels[0])
+            .is_err());
+        assert_eq!(0, framework.n_supports());
+    }
+}
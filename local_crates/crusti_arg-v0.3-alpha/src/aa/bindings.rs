@@ -0,0 +1,298 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! Helpers shared by the Python and WebAssembly bindings, handling frameworks labeled by strings.
+
+use crate::{
+    dynamics::Modification, solvers, AAFramework, ArgumentSet, AspartixWriter, DotWriter, Semantics,
+};
+use anyhow::{anyhow, Result};
+
+/// Builds a framework from the labels of its arguments and its attacks.
+pub(crate) fn build(
+    arguments: Vec<String>,
+    attacks: &[(String, String)],
+) -> Result<AAFramework<String>> {
+    let mut framework = AAFramework::new(ArgumentSet::new(arguments));
+    for (from, to) in attacks.iter() {
+        framework.new_attack(from, to)?;
+    }
+    Ok(framework)
+}
+
+/// Returns the labels of the arguments, in the order of their ids.
+pub(crate) fn argument_labels(framework: &AAFramework<String>) -> Vec<String> {
+    framework
+        .argument_set()
+        .iter()
+        .map(|a| a.label().clone())
+        .collect()
+}
+
+/// Returns the attacks, as pairs of labels.
+pub(crate) fn attack_labels(framework: &AAFramework<String>) -> Vec<(String, String)> {
+    framework
+        .iter_attacks()
+        .map(|att| {
+            (
+                att.attacker().label().clone(),
+                att.attacked().label().clone(),
+            )
+        })
+        .collect()
+}
+
+/// Returns the labels of the arguments of a membership vector.
+pub(crate) fn members(framework: &AAFramework<String>, set: &[bool]) -> Vec<String> {
+    framework
+        .argument_set()
+        .iter()
+        .filter(|a| set[a.id()])
+        .map(|a| a.label().clone())
+        .collect()
+}
+
+/// Returns the membership vector of a set of arguments given by their labels.
+pub(crate) fn membership(framework: &AAFramework<String>, labels: &[String]) -> Result<Vec<bool>> {
+    let mut set = vec![false; framework.argument_set().len()];
+    for l in labels.iter() {
+        set[framework.argument_set().get_argument_index(l)?] = true;
+    }
+    Ok(set)
+}
+
+/// Builds the framework obtained by applying a modification.
+///
+/// Removing an argument also removes the attacks it is involved in; adding an existing argument or attack is an error.
+pub(crate) fn apply_modification(
+    framework: &AAFramework<String>,
+    modification: &Modification<String>,
+) -> Result<AAFramework<String>> {
+    let mut arguments = argument_labels(framework);
+    let mut attacks = attack_labels(framework);
+    let has_argument = |a: &String| framework.argument_set().contains(a);
+    match modification {
+        Modification::AddArgument(a) => {
+            if has_argument(a) {
+                return Err(anyhow!("argument {} already exists", a));
+            }
+            arguments.push(a.clone());
+        }
+        Modification::RemoveArgument(a) => {
+            if !has_argument(a) {
+                return Err(anyhow!("no such argument: {}", a));
+            }
+            arguments.retain(|l| l != a);
+            attacks.retain(|(from, to)| from != a && to != a);
+        }
+        Modification::AddAttack(a, b) => {
+            if attacks.iter().any(|(from, to)| from == a && to == b) {
+                return Err(anyhow!("attack {} → {} already exists", a, b));
+            }
+            attacks.push((a.clone(), b.clone()));
+        }
+        Modification::RemoveAttack(a, b) => {
+            let n_attacks = attacks.len();
+            attacks.retain(|(from, to)| from != a || to != b);
+            if attacks.len() == n_attacks {
+                return Err(anyhow!("no such attack: {} → {}", a, b));
+            }
+        }
+    }
+    build(arguments, &attacks)
+}
+
+/// Splits a modification into its sign (`+` or `-`), its kind (`arg` or `att`) and its labels.
+pub(crate) fn modification_parts(
+    modification: Modification<String>,
+) -> (&'static str, &'static str, Vec<String>) {
+    match modification {
+        Modification::AddArgument(a) => ("+", "arg", vec![a]),
+        Modification::RemoveArgument(a) => ("-", "arg", vec![a]),
+        Modification::AddAttack(a, b) => ("+", "att", vec![a, b]),
+        Modification::RemoveAttack(a, b) => ("-", "att", vec![a, b]),
+    }
+}
+
+/// Returns all the extensions of a semantics, computed by the native engines.
+pub(crate) fn extensions(
+    framework: &AAFramework<String>,
+    semantics: Semantics,
+) -> Result<Vec<Vec<String>>> {
+    Ok(solvers::default_solver(framework, semantics)?
+        .all_extensions(semantics)?
+        .iter()
+        .map(|e| members(framework, e))
+        .collect())
+}
+
+/// Returns an extension of a semantics computed by the native engines, if any.
+pub(crate) fn one_extension(
+    framework: &AAFramework<String>,
+    semantics: Semantics,
+) -> Result<Option<Vec<String>>> {
+    Ok(solvers::default_solver(framework, semantics)?
+        .one_extension(semantics)?
+        .map(|e| members(framework, &e)))
+}
+
+/// Returns `true` iff a set of arguments is admissible, i.e. conflict-free and defending all its members.
+pub(crate) fn is_admissible(framework: &AAFramework<String>, set: &[bool]) -> bool {
+    framework.conflict_free(set)
+        && framework
+            .defended_by(set)
+            .iter()
+            .zip(set.iter())
+            .all(|(defended, member)| *defended || !*member)
+}
+
+/// Returns `true` iff a set of arguments is an extension of a semantics.
+///
+/// Complete and grounded extensions are checked directly; the extensions of the other semantics are checked against the ones given by the native engines.
+pub(crate) fn is_extension(
+    framework: &AAFramework<String>,
+    set: &[bool],
+    semantics: Semantics,
+) -> Result<bool> {
+    match semantics {
+        Semantics::Complete => Ok(framework.conflict_free(set) && framework.is_fixpoint(set)),
+        Semantics::Grounded => Ok(framework.least_fixpoint() == set),
+        _ => Ok(solvers::default_solver(framework, semantics)?
+            .all_extensions(semantics)?
+            .iter()
+            .any(|e| e == set)),
+    }
+}
+
+/// Returns the labelling induced by a set of arguments, as pairs of a label and a status (`IN`, `OUT` or `UNDEC`).
+pub(crate) fn labelling(
+    framework: &AAFramework<String>,
+    labels: &[String],
+) -> Result<Vec<(String, String)>> {
+    let set = membership(framework, labels)?;
+    Ok(argument_labels(framework)
+        .into_iter()
+        .zip(framework.labelling(&set))
+        .map(|(label, status)| (label, status.to_string()))
+        .collect())
+}
+
+/// Writes a framework using the Aspartix format.
+pub(crate) fn to_aspartix(framework: &AAFramework<String>) -> Result<String> {
+    let mut out = Vec::new();
+    AspartixWriter::default().write(framework, &mut out)?;
+    Ok(String::from_utf8(out)?)
+}
+
+/// Writes a framework using the DOT format of Graphviz.
+pub(crate) fn to_dot(framework: &AAFramework<String>) -> Result<String> {
+    let mut out = Vec::new();
+    DotWriter::default().write(framework, &mut out)?;
+    Ok(String::from_utf8(out)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamics;
+
+    fn framework() -> AAFramework<String> {
+        build(
+            vec!["a".to_string(), "b".to_string()],
+            &[("a".to_string(), "b".to_string())],
+        )
+        .unwrap()
+    }
+
+    fn apply(framework: &AAFramework<String>, line: &str) -> Result<AAFramework<String>> {
+        apply_modification(framework, &dynamics::read_modification(line).unwrap())
+    }
+
+    #[test]
+    fn test_apply_modification() {
+        let af = apply(&framework(), "+arg(c).").unwrap();
+        let af = apply(&af, "+att(c,a).").unwrap();
+        let af = apply(&af, "-arg(b).").unwrap();
+        assert_eq!(vec!["a", "c"], argument_labels(&af));
+        assert_eq!(vec![("c".to_string(), "a".to_string())], attack_labels(&af));
+        let af = apply(&af, "-att(c,a).").unwrap();
+        assert_eq!(0, af.n_attacks());
+    }
+
+    #[test]
+    fn test_apply_modification_errors() {
+        let af = framework();
+        [
+            "+arg(a).",
+            "-arg(c).",
+            "+att(a,b).",
+            "-att(b,a).",
+            "+att(a,c).",
+        ]
+        .iter()
+        .for_each(|m| assert!(apply(&af, m).is_err(), "{}", m));
+    }
+
+    #[test]
+    fn test_membership() {
+        let af = framework();
+        let set = membership(&af, &["b".to_string()]).unwrap();
+        assert_eq!(vec![false, true], set);
+        assert_eq!(vec!["b"], members(&af, &set));
+        assert!(membership(&af, &["c".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_extensions() {
+        let af = apply(&framework(), "+att(b,a).").unwrap();
+        assert_eq!(
+            vec![vec!["a"], vec!["b"]],
+            extensions(&af, Semantics::Stable).unwrap()
+        );
+        assert_eq!(
+            Some(Vec::<String>::new()),
+            one_extension(&af, Semantics::Grounded).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_checkers() {
+        let af = build(
+            vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            &[
+                ("a".to_string(), "b".to_string()),
+                ("b".to_string(), "c".to_string()),
+            ],
+        )
+        .unwrap();
+        assert!(is_admissible(&af, &[true, false, false]));
+        assert!(!is_admissible(&af, &[false, false, true]));
+        assert!(!is_extension(&af, &[true, false, false], Semantics::Complete).unwrap());
+        for semantics in [
+            Semantics::Complete,
+            Semantics::Grounded,
+            Semantics::Preferred,
+        ]
+        .iter()
+        {
+            assert!(is_extension(&af, &[true, false, true], *semantics).unwrap());
+        }
+        assert!(!is_extension(&af, &[true, true, true], Semantics::Stable).unwrap());
+    }
+}
@@ -0,0 +1,610 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::aa_framework::AAFramework;
+use crate::aa::arguments::{Argument, ArgumentSet, LabelType};
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashSet;
+
+/// The part of a control framework an argument belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CafPart {
+    /// The argument is always present.
+    Fixed,
+    /// The presence of the argument is unknown.
+    Uncertain,
+    /// The argument is present if the agent decides to use it.
+    Control,
+}
+
+/// A Control Argumentation framework (CAF), made of a fixed, an uncertain and a control part.
+///
+/// Arguments are either fixed, uncertain or control arguments (see [`CafPart`]).
+/// Besides the (certain) attacks, the fixed and uncertain arguments may be involved in uncertain attacks, which may not exist,
+/// and in uncertain conflicts, which exist but whose direction is unknown.
+///
+/// A CAF is turned into a plain framework by [`project`], given the control arguments in use and a [`CafCompletion`] of its uncertain part.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{CafPart, CAFramework};
+/// let mut framework = CAFramework::new(vec!["a"], vec!["b"], vec!["c"]);
+/// framework.new_uncertain_attack(&"b", &"a").unwrap();
+/// framework.new_attack(&"c", &"b").unwrap();
+/// assert_eq!(CafPart::Uncertain, framework.part(&"b").unwrap());
+/// assert_eq!(1, framework.iter_uncertain_attacks().count());
+/// ```
+///
+/// [`CafPart`]: enum.CafPart.html
+/// [`CafCompletion`]: struct.CafCompletion.html
+/// [`project`]: struct.CAFramework.html#method.project
+pub struct CAFramework<T>
+where
+    T: LabelType,
+{
+    arguments: ArgumentSet<T>,
+    parts: Vec<CafPart>,
+    attacks: Vec<(usize, usize)>,
+    uncertain_attacks: Vec<(usize, usize)>,
+    uncertain_conflicts: Vec<(usize, usize)>,
+}
+
+/// A completion of the uncertain part of a [`CAFramework`].
+///
+/// A completion gives the uncertain arguments and the uncertain attacks which exist, and the direction(s) of the uncertain conflicts.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::CafCompletion;
+/// let completion = CafCompletion::new(vec!["b"], vec![("b", "a")], vec![]);
+/// assert_eq!(&["b"], completion.arguments());
+/// ```
+///
+/// [`CAFramework`]: struct.CAFramework.html
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CafCompletion<T>
+where
+    T: LabelType,
+{
+    arguments: Vec<T>,
+    attacks: Vec<(T, T)>,
+    conflict_directions: Vec<(T, T)>,
+}
+
+impl<T> CafCompletion<T>
+where
+    T: LabelType,
+{
+    /// Builds a completion.
+    ///
+    /// # Arguments
+    ///
+    /// * `arguments` - the labels of the uncertain arguments which exist
+    /// * `attacks` - the uncertain attacks which exist, as couples of labels
+    /// * `conflict_directions` - the directions of the uncertain conflicts, as couples of labels; a conflict given in both directions is a mutual attack
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::CafCompletion;
+    /// let completion = CafCompletion::new(vec![], vec![], vec![("a", "b")]);
+    /// assert_eq!(&[("a", "b")], completion.conflict_directions());
+    /// ```
+    pub fn new(arguments: Vec<T>, attacks: Vec<(T, T)>, conflict_directions: Vec<(T, T)>) -> Self {
+        CafCompletion {
+            arguments,
+            attacks,
+            conflict_directions,
+        }
+    }
+
+    /// Returns the labels of the uncertain arguments which exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::CafCompletion;
+    /// let completion = CafCompletion::new(vec!["b"], vec![], vec![]);
+    /// assert_eq!(&["b"], completion.arguments());
+    /// ```
+    pub fn arguments(&self) -> &[T] {
+        &self.arguments
+    }
+
+    /// Returns the uncertain attacks which exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::CafCompletion;
+    /// let completion = CafCompletion::new(vec![], vec![("b", "a")], vec![]);
+    /// assert_eq!(&[("b", "a")], completion.attacks());
+    /// ```
+    pub fn attacks(&self) -> &[(T, T)] {
+        &self.attacks
+    }
+
+    /// Returns the directions of the uncertain conflicts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::CafCompletion;
+    /// let completion = CafCompletion::new(vec![], vec![], vec![("a", "b"), ("b", "a")]);
+    /// assert_eq!(2, completion.conflict_directions().len());
+    /// ```
+    pub fn conflict_directions(&self) -> &[(T, T)] {
+        &self.conflict_directions
+    }
+}
+
+impl<T> CAFramework<T>
+where
+    T: LabelType,
+{
+    /// Builds a control framework with no attacks given the labels of its fixed, uncertain and control arguments.
+    ///
+    /// The ids of the arguments follow the order of the fixed, then the uncertain, then the control arguments.
+    /// The labels must be distinct.
+    ///
+    /// # Arguments
+    ///
+    /// * `fixed` - the labels of the fixed arguments
+    /// * `uncertain` - the labels of the uncertain arguments
+    /// * `control` - the labels of the control arguments
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::CAFramework;
+    /// let framework = CAFramework::new(vec!["a", "b"], vec!["c"], vec![]);
+    /// assert_eq!(3, framework.argument_set().len());
+    /// ```
+    pub fn new(fixed: Vec<T>, uncertain: Vec<T>, control: Vec<T>) -> Self {
+        let mut parts = vec![CafPart::Fixed; fixed.len()];
+        parts.append(&mut vec![CafPart::Uncertain; uncertain.len()]);
+        parts.append(&mut vec![CafPart::Control; control.len()]);
+        let labels = fixed.into_iter().chain(uncertain).chain(control).collect();
+        CAFramework {
+            arguments: ArgumentSet::new(labels),
+            parts,
+            attacks: vec![],
+            uncertain_attacks: vec![],
+            uncertain_conflicts: vec![],
+        } // kcov-ignore
+    }
+
+    /// Returns the argument set of the framework.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::CAFramework;
+    /// let framework = CAFramework::new(vec!["a"], vec!["b"], vec!["c"]);
+    /// assert_eq!(3, framework.argument_set().len());
+    /// ```
+    pub fn argument_set(&self) -> &ArgumentSet<T> {
+        &self.arguments
+    }
+
+    /// Returns the part an argument belongs to.
+    ///
+    /// If the argument is undefined, an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - the label of the argument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{CafPart, CAFramework};
+    /// let framework = CAFramework::new(vec!["a"], vec![], vec!["c"]);
+    /// assert_eq!(CafPart::Control, framework.part(&"c").unwrap());
+    /// assert!(framework.part(&"d").is_err());
+    /// ```
+    pub fn part(&self, label: &T) -> Result<CafPart> {
+        Ok(self.parts[self.arguments.get_argument_index(label)?])
+    }
+
+    /// Provides an iterator to the arguments of a part, in the order of their ids.
+    ///
+    /// # Arguments
+    ///
+    /// * `part` - the part
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{CafPart, CAFramework};
+    /// let framework = CAFramework::new(vec!["a", "b"], vec![], vec!["c"]);
+    /// assert_eq!(2, framework.iter_arguments_of(CafPart::Fixed).count());
+    /// ```
+    pub fn iter_arguments_of<'a>(
+        &'a self,
+        part: CafPart,
+    ) -> Box<dyn Iterator<Item = &'a Argument<T>> + 'a> {
+        Box::new(
+            self.arguments
+                .iter()
+                .filter(move |a| self.parts[a.id()] == part),
+        )
+    }
+
+    fn relation_ids(&self, from: &T, to: &T, uncertain: bool) -> Result<(usize, usize)> {
+        let from_id = self.arguments.get_argument_index(from)?;
+        let to_id = self.arguments.get_argument_index(to)?;
+        if uncertain
+            && (self.parts[from_id] == CafPart::Control || self.parts[to_id] == CafPart::Control)
+        {
+            return Err(anyhow!(
+                "control arguments cannot be involved in uncertain relations"
+            ));
+        }
+        Ok((from_id, to_id))
+    }
+
+    /// Adds a new (certain) attack given the labels of the source and destination arguments.
+    ///
+    /// If the provided arguments are undefined, an error is returned.
+    /// If the attack already exists, it is added another time (no checks are made for existence).
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - the label of the source argument (attacker)
+    /// * `to` - the label of the destination argument (attacked)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::CAFramework;
+    /// let mut framework = CAFramework::new(vec!["a"], vec![], vec!["c"]);
+    /// framework.new_attack(&"c", &"a").unwrap();
+    /// assert_eq!(1, framework.iter_attacks().count());
+    /// ```
+    pub fn new_attack(&mut self, from: &T, to: &T) -> Result<()> {
+        let ids = self
+            .relation_ids(from, to, false)
+            .with_context(|| format!("cannot add an attack from {:?} to {:?}", from, to))?;
+        self.attacks.push(ids);
+        Ok(())
+    }
+
+    /// Adds a new uncertain attack given the labels of the source and destination arguments.
+    ///
+    /// If the provided arguments are undefined, or if one of them is a control argument, an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - the label of the source argument (attacker)
+    /// * `to` - the label of the destination argument (attacked)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::CAFramework;
+    /// let mut framework = CAFramework::new(vec!["a"], vec!["b"], vec!["c"]);
+    /// framework.new_uncertain_attack(&"b", &"a").unwrap();
+    /// assert!(framework.new_uncertain_attack(&"c", &"a").is_err());
+    /// ```
+    pub fn new_uncertain_attack(&mut self, from: &T, to: &T) -> Result<()> {
+        let ids = self.relation_ids(from, to, true).with_context(|| {
+            format!("cannot add an uncertain attack from {:?} to {:?}", from, to)
+        })?;
+        self.uncertain_attacks.push(ids);
+        Ok(())
+    }
+
+    /// Adds a new uncertain conflict, i.e. an attack of unknown direction, between two arguments.
+    ///
+    /// If the provided arguments are undefined, or if one of them is a control argument, an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - the label of the first argument
+    /// * `b` - the label of the second argument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::CAFramework;
+    /// let mut framework = CAFramework::new(vec!["a", "b"], vec![], vec![]);
+    /// framework.new_uncertain_conflict(&"a", &"b").unwrap();
+    /// assert_eq!(1, framework.iter_uncertain_conflicts().count());
+    /// ```
+    pub fn new_uncertain_conflict(&mut self, a: &T, b: &T) -> Result<()> {
+        let ids = self.relation_ids(a, b, true).with_context(|| {
+            format!(
+                "cannot add an uncertain conflict between {:?} and {:?}",
+                a, b
+            )
+        })?;
+        self.uncertain_conflicts.push(ids);
+        Ok(())
+    }
+
+    fn iter_relation<'a>(
+        &'a self,
+        relation: &'a [(usize, usize)],
+    ) -> Box<dyn Iterator<Item = (&'a Argument<T>, &'a Argument<T>)> + 'a> {
+        Box::new(relation.iter().map(move |(a, b)| {
+            (
+                self.arguments.get_argument_by_id(*a),
+                self.arguments.get_argument_by_id(*b),
+            )
+        }))
+    }
+
+    /// Provides an iterator to the (certain) attacks, in the order they were added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::CAFramework;
+    /// let mut framework = CAFramework::new(vec!["a", "b"], vec![], vec![]);
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// let (from, to) = framework.iter_attacks().next().unwrap();
+    /// assert_eq!((&"a", &"b"), (from.label(), to.label()));
+    /// ```
+    pub fn iter_attacks<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = (&'a Argument<T>, &'a Argument<T>)> + 'a> {
+        self.iter_relation(&self.attacks)
+    }
+
+    /// Provides an iterator to the uncertain attacks, in the order they were added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::CAFramework;
+    /// let mut framework = CAFramework::new(vec!["a"], vec!["b"], vec![]);
+    /// framework.new_uncertain_attack(&"b", &"a").unwrap();
+    /// assert_eq!(1, framework.iter_uncertain_attacks().count());
+    /// ```
+    pub fn iter_uncertain_attacks<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = (&'a Argument<T>, &'a Argument<T>)> + 'a> {
+        self.iter_relation(&self.uncertain_attacks)
+    }
+
+    /// Provides an iterator to the uncertain conflicts, in the order they were added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::CAFramework;
+    /// let mut framework = CAFramework::new(vec!["a", "b"], vec![], vec![]);
+    /// framework.new_uncertain_conflict(&"a", &"b").unwrap();
+    /// assert_eq!(1, framework.iter_uncertain_conflicts().count());
+    /// ```
+    pub fn iter_uncertain_conflicts<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = (&'a Argument<T>, &'a Argument<T>)> + 'a> {
+        self.iter_relation(&self.uncertain_conflicts)
+    }
+
+    /// Projects this control framework into a plain framework, given the control arguments in use and a completion of the uncertain part.
+    ///
+    /// The resulting framework is made of the fixed arguments, the uncertain arguments of the completion and the given control arguments, in the order of their ids.
+    /// Its attacks are the certain attacks, the uncertain attacks of the completion and the directions of the uncertain conflicts given by the completion, restricted to these arguments.
+    ///
+    /// An error is returned if a control argument, an uncertain argument, an uncertain attack or a conflict direction given in the parameters does not belong to the corresponding part of the framework,
+    /// or if no direction is given for an uncertain conflict between two arguments of the resulting framework.
+    ///
+    /// # Arguments
+    ///
+    /// * `control` - the labels of the control arguments in use
+    /// * `completion` - the completion of the uncertain part
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{CafCompletion, CAFramework};
+    /// let mut framework = CAFramework::new(vec!["a"], vec!["b"], vec!["c"]);
+    /// framework.new_uncertain_attack(&"b", &"a").unwrap();
+    /// framework.new_attack(&"c", &"b").unwrap();
+    /// let completion = CafCompletion::new(vec!["b"], vec![("b", "a")], vec![]);
+    /// let af = framework.project(&["c"], &completion).unwrap();
+    /// assert_eq!(3, af.argument_set().len());
+    /// assert_eq!(2, af.n_attacks());
+    /// let af = framework.project(&[], &completion).unwrap();
+    /// assert_eq!(1, af.n_attacks());
+    /// ```
+    pub fn project(&self, control: &[T], completion: &CafCompletion<T>) -> Result<AAFramework<T>> {
+        self.try_project(control, completion)
+            .context("cannot project the control framework")
+    }
+
+    fn try_project(&self, control: &[T], completion: &CafCompletion<T>) -> Result<AAFramework<T>> {
+        let mut present = self
+            .parts
+            .iter()
+            .map(|p| *p == CafPart::Fixed)
+            .collect::<Vec<bool>>();
+        let mut set_present = |labels: &[T], part: CafPart| -> Result<()> {
+            for l in labels.iter() {
+                let id = self.arguments.get_argument_index(l)?;
+                if self.parts[id] != part {
+                    return Err(anyhow!("{:?} is not an argument of the {:?} part", l, part));
+                }
+                present[id] = true;
+            }
+            Ok(())
+        };
+        set_present(control, CafPart::Control)?;
+        set_present(completion.arguments(), CafPart::Uncertain)?;
+        let mut attacks = self.attacks.clone();
+        for (from, to) in completion.attacks().iter() {
+            let ids = (
+                self.arguments.get_argument_index(from)?,
+                self.arguments.get_argument_index(to)?,
+            );
+            if !self.uncertain_attacks.contains(&ids) {
+                return Err(anyhow!("({:?}, {:?}) is not an uncertain attack", from, to));
+            }
+            attacks.push(ids);
+        }
+        let mut directed = HashSet::new();
+        for (from, to) in completion.conflict_directions().iter() {
+            let ids = (
+                self.arguments.get_argument_index(from)?,
+                self.arguments.get_argument_index(to)?,
+            );
+            if !self.uncertain_conflicts.contains(&ids)
+                && !self.uncertain_conflicts.contains(&(ids.1, ids.0))
+            {
+                return Err(anyhow!(
+                    "there is no uncertain conflict between {:?} and {:?}",
+                    from,
+                    to
+                ));
+            }
+            directed.insert(ids);
+            attacks.push(ids);
+        }
+        for (a, b) in self.uncertain_conflicts.iter() {
+            if present[*a]
+                && present[*b]
+                && !directed.contains(&(*a, *b))
+                && !directed.contains(&(*b, *a))
+            {
+                return Err(anyhow!(
+                    "no direction is given for the uncertain conflict between {:?} and {:?}",
+                    self.arguments.get_argument_by_id(*a).label(),
+                    self.arguments.get_argument_by_id(*b).label()
+                ));
+            }
+        }
+        let labels = self
+            .arguments
+            .iter()
+            .filter(|a| present[a.id()])
+            .map(|a| a.label().clone())
+            .collect();
+        let mut framework = AAFramework::new(ArgumentSet::new(labels));
+        let mut added = HashSet::new();
+        for (from, to) in attacks.into_iter() {
+            if present[from] && present[to] && added.insert((from, to)) {
+                framework.new_attack(
+                    self.arguments.get_argument_by_id(from).label(),
+                    self.arguments.get_argument_by_id(to).label(),
+                )?;
+            }
+        }
+        Ok(framework)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn framework() -> CAFramework<&'static str> {
+        let mut framework = CAFramework::new(vec!["a", "b"], vec!["u"], vec!["c"]);
+        framework.new_attack(&"c", &"a").unwrap();
+        framework.new_uncertain_attack(&"u", &"a").unwrap();
+        framework.new_uncertain_conflict(&"a", &"b").unwrap();
+        framework.new_uncertain_conflict(&"b", &"u").unwrap();
+        framework
+    }
+
+    fn attacks(framework: &AAFramework<&'static str>) -> Vec<String> {
+        framework
+            .iter_attacks()
+            .map(|a| format!("{}{}", a.attacker(), a.attacked()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parts() {
+        let framework = framework();
+        assert_eq!(CafPart::Fixed, framework.part(&"b").unwrap());
+        assert_eq!(CafPart::Uncertain, framework.part(&"u").unwrap());
+        assert_eq!(
+            vec![&"c"],
+            framework
+                .iter_arguments_of(CafPart::Control)
+                .map(|a| a.label())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_uncertain_relations_with_control_arguments() {
+        let mut framework = framework();
+        assert!(framework.new_uncertain_attack(&"a", &"c").is_err());
+        assert!(framework.new_uncertain_conflict(&"c", &"u").is_err());
+        assert!(framework.new_uncertain_attack(&"a", &"d").is_err());
+        assert_eq!(1, framework.iter_uncertain_attacks().count());
+        assert_eq!(2, framework.iter_uncertain_conflicts().count());
+    }
+
+    #[test]
+    fn test_project_all() {
+        let completion = CafCompletion::new(
+            vec!["u"],
+            vec![("u", "a")],
+            vec![("b", "a"), ("b", "u"), ("u", "b")],
+        );
+        let af = framework().project(&["c"], &completion).unwrap();
+        assert_eq!(4, af.argument_set().len());
+        assert_eq!(vec!["ca", "ua", "ba", "bu", "ub"], attacks(&af));
+    }
+
+    #[test]
+    fn test_project_without_uncertain_argument() {
+        let completion = CafCompletion::new(vec![], vec![("u", "a")], vec![("a", "b")]);
+        let af = framework().project(&[], &completion).unwrap();
+        assert_eq!(2, af.argument_set().len());
+        assert_eq!(vec!["ab"], attacks(&af));
+    }
+
+    #[test]
+    fn test_project_errors() {
+        let framework = framework();
+        let direction = vec![("a", "b")];
+        for (control, completion) in [
+            (
+                vec!["a"],
+                CafCompletion::new(vec![], vec![], direction.clone()),
+            ),
+            (
+                vec![],
+                CafCompletion::new(vec!["c"], vec![], direction.clone()),
+            ),
+            (
+                vec![],
+                CafCompletion::new(vec![], vec![("a", "u")], direction.clone()),
+            ),
+            (vec![], CafCompletion::new(vec![], vec![], vec![("a", "u")])),
+            (vec![], CafCompletion::new(vec![], vec![], vec![])),
+            (
+                vec![],
+                CafCompletion::new(vec!["u"], vec![], direction.clone()),
+            ),
+        ]
+        .iter()
+        {
+            assert!(framework.project(control, completion).is_err());
+        }
+    }
+}
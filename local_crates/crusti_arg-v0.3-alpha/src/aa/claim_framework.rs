@@ -0,0 +1,370 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::aa_framework::AAFramework;
+use crate::aa::arguments::{ArgumentSet, LabelType};
+use crate::aa::semantics::Semantics;
+use crate::aa::solvers;
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashSet;
+
+/// A Claim-augmented Argumentation framework, in which each argument has a claim.
+///
+/// The attack relation is handled by an underlying [`AAFramework`], so that the extensions are computed by the existing engines;
+/// they are then projected to the claims of their arguments.
+/// The claims share the label type of the arguments, and an argument claims its own label until another claim is set.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{ArgumentSet, ClaimAAFramework, Semantics};
+/// let labels = vec!["a", "b", "c"];
+/// let mut framework = ClaimAAFramework::new(ArgumentSet::new(labels.clone()));
+/// framework.new_attack(&"a", &"b").unwrap();
+/// framework.new_attack(&"b", &"a").unwrap();
+/// framework.set_claim(&"a", "x").unwrap();
+/// framework.set_claim(&"b", "x").unwrap();
+/// assert!(framework.claim_skeptically_accepted(&"x", Semantics::Stable).unwrap());
+/// ```
+///
+/// [`AAFramework`]: struct.AAFramework.html
+pub struct ClaimAAFramework<T>
+where
+    T: LabelType,
+{
+    framework: AAFramework<T>,
+    claims: Vec<T>,
+}
+
+impl<T> ClaimAAFramework<T>
+where
+    T: LabelType,
+{
+    /// Builds a claim-augmented framework with no attacks, in which each argument claims its own label.
+    ///
+    /// # Arguments
+    ///
+    /// * `arguments` - the set of arguments
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, ClaimAAFramework};
+    /// let framework = ClaimAAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// assert_eq!(&"a", framework.claim_of(&"a").unwrap());
+    /// ```
+    pub fn new(arguments: ArgumentSet<T>) -> Self {
+        let claims = arguments.iter().map(|a| a.label().clone()).collect();
+        ClaimAAFramework {
+            framework: AAFramework::new(arguments),
+            claims,
+        } // kcov-ignore
+    }
+
+    /// Adds a new attack given the labels of the source and destination arguments.
+    ///
+    /// See [`AAFramework::new_attack`] for more information.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - the label of the source arguments (attacker)
+    /// * `to` - the label of the destination argument (attacked)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, ClaimAAFramework};
+    /// let mut framework = ClaimAAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// assert_eq!(1, framework.attack_framework().n_attacks());
+    /// ```
+    ///
+    /// [`AAFramework::new_attack`]: struct.AAFramework.html#method.new_attack
+    pub fn new_attack(&mut self, from: &T, to: &T) -> Result<()> {
+        self.framework.new_attack(from, to)
+    }
+
+    /// Sets the claim of an argument.
+    ///
+    /// If the argument is undefined, an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `argument` - the label of the argument
+    /// * `claim` - its claim
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, ClaimAAFramework};
+    /// let mut framework = ClaimAAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.set_claim(&"a", "b").unwrap();
+    /// assert_eq!(&"b", framework.claim_of(&"a").unwrap());
+    /// assert!(framework.set_claim(&"c", "b").is_err());
+    /// ```
+    pub fn set_claim(&mut self, argument: &T, claim: T) -> Result<()> {
+        let id = self
+            .framework
+            .argument_set()
+            .get_argument_index(argument)
+            .with_context(|| format!("cannot set the claim of {:?}", argument))?;
+        self.claims[id] = claim;
+        Ok(())
+    }
+
+    /// Returns the claim of an argument.
+    ///
+    /// If the argument is undefined, an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `argument` - the label of the argument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, ClaimAAFramework};
+    /// let framework = ClaimAAFramework::new(ArgumentSet::new(vec!["a"]));
+    /// assert_eq!(&"a", framework.claim_of(&"a").unwrap());
+    /// assert!(framework.claim_of(&"b").is_err());
+    /// ```
+    pub fn claim_of(&self, argument: &T) -> Result<&T> {
+        let id = self.framework.argument_set().get_argument_index(argument)?;
+        Ok(&self.claims[id])
+    }
+
+    /// Returns the argument set of the framework.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, ClaimAAFramework};
+    /// let framework = ClaimAAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// assert_eq!(2, framework.argument_set().len());
+    /// ```
+    pub fn argument_set(&self) -> &ArgumentSet<T> {
+        self.framework.argument_set()
+    }
+
+    /// Returns the framework made of the arguments and the attacks of this claim-augmented framework.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, ClaimAAFramework, Semantics};
+    /// let framework = ClaimAAFramework::new(ArgumentSet::new(vec!["a"]));
+    /// assert!(framework.attack_framework().credulously_accepted(&"a", Semantics::Stable).unwrap());
+    /// ```
+    pub fn attack_framework(&self) -> &AAFramework<T> {
+        &self.framework
+    }
+
+    /// Provides an iterator to the distinct claims, in the order of the first argument claiming them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, ClaimAAFramework};
+    /// let mut framework = ClaimAAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+    /// framework.set_claim(&"a", "c").unwrap();
+    /// assert_eq!(vec![&"c", &"b"], framework.iter_claims().collect::<Vec<_>>());
+    /// ```
+    pub fn iter_claims(&self) -> impl Iterator<Item = &T> {
+        let mut seen = HashSet::new();
+        self.claims.iter().filter(move |c| seen.insert(*c))
+    }
+
+    /// Projects an extension, given by its membership vector, to the set of the claims of its arguments.
+    ///
+    /// The claims are given in the order of [`iter_claims`], so that equal claim sets are given by equal vectors.
+    ///
+    /// # Arguments
+    ///
+    /// * `extension` - the membership vector of the extension, indexed by the argument ids
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, ClaimAAFramework};
+    /// let mut framework = ClaimAAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+    /// framework.set_claim(&"c", "a").unwrap();
+    /// assert_eq!(vec!["a"], framework.project_extension(&[false, false, true]));
+    /// ```
+    ///
+    /// [`iter_claims`]: struct.ClaimAAFramework.html#method.iter_claims
+    pub fn project_extension(&self, extension: &[bool]) -> Vec<T> {
+        let in_extension = self
+            .claims
+            .iter()
+            .zip(extension.iter())
+            .filter(|(_, b)| **b)
+            .map(|(c, _)| c)
+            .collect::<HashSet<&T>>();
+        self.iter_claims()
+            .filter(|c| in_extension.contains(c))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the claim-level extensions under a semantics, i.e. the distinct projections of the extensions to their claims.
+    ///
+    /// The extensions are computed by the [`solvers::default_solver`], and are thus subject to its limitations.
+    ///
+    /// # Arguments
+    ///
+    /// * `semantics` - the semantics
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, ClaimAAFramework, Semantics};
+    /// let mut framework = ClaimAAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// framework.new_attack(&"b", &"a").unwrap();
+    /// framework.set_claim(&"b", "a").unwrap();
+    /// assert_eq!(vec![vec!["a"]], framework.claim_extensions(Semantics::Preferred).unwrap());
+    /// ```
+    ///
+    /// [`solvers::default_solver`]: solvers/fn.default_solver.html
+    pub fn claim_extensions(&self, semantics: Semantics) -> Result<Vec<Vec<T>>> {
+        let extensions = solvers::default_solver(&self.framework, semantics)
+            .and_then(|s| s.all_extensions(semantics))
+            .with_context(|| format!("cannot compute the {} claim extensions", semantics))?;
+        let mut claim_extensions: Vec<Vec<T>> = Vec::with_capacity(extensions.len());
+        for extension in extensions.iter() {
+            let projection = self.project_extension(extension);
+            if !claim_extensions.contains(&projection) {
+                claim_extensions.push(projection);
+            }
+        }
+        Ok(claim_extensions)
+    }
+
+    /// Returns `true` iff a claim belongs to at least one claim-level extension under the given semantics.
+    ///
+    /// An error is returned if no argument has this claim, or if the extensions cannot be computed (see [`claim_extensions`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `claim` - the claim
+    /// * `semantics` - the semantics
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, ClaimAAFramework, Semantics};
+    /// let mut framework = ClaimAAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// framework.new_attack(&"b", &"a").unwrap();
+    /// assert!(framework.claim_credulously_accepted(&"b", Semantics::Stable).unwrap());
+    /// ```
+    ///
+    /// [`claim_extensions`]: struct.ClaimAAFramework.html#method.claim_extensions
+    pub fn claim_credulously_accepted(&self, claim: &T, semantics: Semantics) -> Result<bool> {
+        Ok(self
+            .claim_extensions_for(claim, semantics)?
+            .iter()
+            .any(|e| e.contains(claim)))
+    }
+
+    /// Returns `true` iff a claim belongs to all the claim-level extensions under the given semantics.
+    ///
+    /// An error is returned if no argument has this claim, or if the extensions cannot be computed (see [`claim_extensions`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `claim` - the claim
+    /// * `semantics` - the semantics
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, ClaimAAFramework, Semantics};
+    /// let mut framework = ClaimAAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// framework.new_attack(&"b", &"a").unwrap();
+    /// assert!(!framework.claim_skeptically_accepted(&"b", Semantics::Stable).unwrap());
+    /// ```
+    ///
+    /// [`claim_extensions`]: struct.ClaimAAFramework.html#method.claim_extensions
+    pub fn claim_skeptically_accepted(&self, claim: &T, semantics: Semantics) -> Result<bool> {
+        Ok(self
+            .claim_extensions_for(claim, semantics)?
+            .iter()
+            .all(|e| e.contains(claim)))
+    }
+
+    fn claim_extensions_for(&self, claim: &T, semantics: Semantics) -> Result<Vec<Vec<T>>> {
+        if !self.claims.contains(claim) {
+            return Err(anyhow!("no argument has the claim {:?}", claim));
+        }
+        self.claim_extensions(semantics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn framework() -> ClaimAAFramework<&'static str> {
+        let mut framework = ClaimAAFramework::new(ArgumentSet::new(vec!["a", "b", "c", "d"]));
+        framework.new_attack(&"a", &"b").unwrap();
+        framework.new_attack(&"b", &"a").unwrap();
+        framework.new_attack(&"c", &"d").unwrap();
+        framework.set_claim(&"a", "x").unwrap();
+        framework.set_claim(&"b", "y").unwrap();
+        framework.set_claim(&"c", "y").unwrap();
+        framework.set_claim(&"d", "x").unwrap();
+        framework
+    }
+
+    #[test]
+    fn test_claim_extensions() {
+        let framework = framework();
+        assert_eq!(
+            vec![&"x", &"y"],
+            framework.iter_claims().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![vec!["x", "y"], vec!["y"]],
+            framework.claim_extensions(Semantics::Stable).unwrap()
+        );
+        assert_eq!(
+            vec![vec!["y"]],
+            framework.claim_extensions(Semantics::Grounded).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_claim_acceptance() {
+        let framework = framework();
+        assert!(framework
+            .claim_credulously_accepted(&"x", Semantics::Stable)
+            .unwrap());
+        assert!(!framework
+            .claim_skeptically_accepted(&"x", Semantics::Stable)
+            .unwrap());
+        assert!(framework
+            .claim_skeptically_accepted(&"y", Semantics::Preferred)
+            .unwrap());
+        assert!(framework
+            .claim_credulously_accepted(&"a", Semantics::Stable)
+            .is_err());
+    }
+}
@@ -0,0 +1,793 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! A module computing modifications of frameworks which enforce the acceptance of a set of arguments.
+//!
+//! The enforcement problem consists in finding a set of attack additions and removals after which the target arguments are (credulously or skeptically) accepted.
+//! The problem is encoded into a CNF formula by an [`EnforcementEncoding`], in which a variable is attached to each possible attack between the arguments of the framework.
+//! An [`Enforcer`] solves the formula with a [`MaxSatSolver`], minimizing the number of changes or their weighted cost, and reports whether the modifications it found are optimal; the [`enforce`] function minimizes the number of changes.
+//!
+//! The credulous acceptance is handled under the complete, preferred and stable semantics, and both acceptances are handled under the grounded semantics; the skeptical acceptance under the complete semantics is the one under the grounded semantics.
+//!
+//! [`EnforcementEncoding`]: struct.EnforcementEncoding.html
+//! [`Enforcer`]: struct.Enforcer.html
+//! [`MaxSatSolver`]: ../maxsat/trait.MaxSatSolver.html
+//! [`enforce`]: fn.enforce.html
+
+use crate::aa::aa_framework::AAFramework;
+use crate::aa::arguments::LabelType;
+use crate::aa::io::dynamics::Modification;
+use crate::aa::maxsat::{LinearSearchSolver, MaxSatSolver, MaxSatStatus};
+use crate::aa::sat::Cnf;
+use crate::aa::semantics::Semantics;
+use anyhow::{anyhow, Context, Result};
+use std::{fmt::Display, str::FromStr, time::Instant};
+
+/// The kind of acceptance to enforce.
+///
+/// Acceptances can be parsed from and displayed as their acronyms used in the ICCMA problem names (`DC` and `DS`).
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::enforcement::Acceptance;
+/// assert_eq!(Acceptance::Skeptical, "DS".parse::<Acceptance>().unwrap());
+/// assert_eq!("DC", Acceptance::Credulous.to_string());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Acceptance {
+    /// The credulous acceptance (`DC`).
+    Credulous,
+    /// The skeptical acceptance (`DS`).
+    Skeptical,
+}
+
+impl FromStr for Acceptance {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "DC" => Ok(Acceptance::Credulous),
+            "DS" => Ok(Acceptance::Skeptical),
+            _ => Err(anyhow!(r#"unknown acceptance "{}""#, s)),
+        }
+    }
+}
+
+impl Display for Acceptance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Acceptance::Credulous => write!(f, "DC"),
+            Acceptance::Skeptical => write!(f, "DS"),
+        }
+    }
+}
+
+/// The encoding of an enforcement problem into a CNF formula.
+///
+/// The formula has a variable for each couple of arguments, set to `true` iff the first argument attacks the second one after the modifications.
+/// Its models are the frameworks over the same arguments in which the targets are accepted.
+/// The changes of the framework are given by the [`change_literals`], one per couple of arguments, which are `true` iff the attack is added or removed.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{enforcement::{Acceptance, EnforcementEncoding}, sat::CdclSolver, AAFramework, ArgumentSet, Semantics};
+/// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+/// framework.new_attack(&"a", &"b").unwrap();
+/// let encoding = EnforcementEncoding::new(&framework, Semantics::Grounded, Acceptance::Credulous, &["b"]).unwrap();
+/// let model = CdclSolver::new(encoding.cnf()).solve().unwrap();
+/// assert!(!encoding.modifications(&model).is_empty());
+/// ```
+///
+/// [`change_literals`]: #method.change_literals
+pub struct EnforcementEncoding<'a, T>
+where
+    T: LabelType,
+{
+    framework: &'a AAFramework<T>,
+    cnf: Cnf,
+    attack_vars: Vec<i32>,
+    change_literals: Vec<i32>,
+}
+
+impl<'a, T> EnforcementEncoding<'a, T>
+where
+    T: LabelType,
+{
+    /// Encodes an enforcement problem.
+    ///
+    /// If a target argument is undefined, or if the acceptance is not handled under the semantics, an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `framework` - the framework to modify
+    /// * `semantics` - the semantics
+    /// * `acceptance` - the acceptance to enforce
+    /// * `targets` - the labels of the arguments to accept
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{enforcement::{Acceptance, EnforcementEncoding}, AAFramework, ArgumentSet, Semantics};
+    /// let framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// assert!(EnforcementEncoding::new(&framework, Semantics::Stable, Acceptance::Credulous, &["a"]).is_ok());
+    /// assert!(EnforcementEncoding::new(&framework, Semantics::Stable, Acceptance::Skeptical, &["a"]).is_err());
+    /// assert!(EnforcementEncoding::new(&framework, Semantics::Stable, Acceptance::Credulous, &["c"]).is_err());
+    /// ```
+    pub fn new(
+        framework: &'a AAFramework<T>,
+        semantics: Semantics,
+        acceptance: Acceptance,
+        targets: &[T],
+    ) -> Result<Self> {
+        let context = || format!("cannot encode the {}-{} enforcement", acceptance, semantics);
+        let targets = targets
+            .iter()
+            .map(|t| framework.argument_set().get_argument_index(t))
+            .collect::<Result<Vec<usize>>>()
+            .with_context(context)?;
+        let n = framework.argument_set().len();
+        let mut cnf = Cnf::default();
+        let attack_vars = (0..n * n).map(|_| cnf.new_var()).collect::<Vec<i32>>();
+        let mut initial = vec![false; n * n];
+        for att in framework.iter_attacks() {
+            initial[att.attacker().id() * n + att.attacked().id()] = true;
+        }
+        let change_literals = attack_vars
+            .iter()
+            .zip(initial.iter())
+            .map(|(v, i)| if *i { -v } else { *v })
+            .collect();
+        let mut encoding = EnforcementEncoding {
+            framework,
+            cnf,
+            attack_vars,
+            change_literals,
+        };
+        match (semantics, acceptance) {
+            (Semantics::Grounded, _) | (Semantics::Complete, Acceptance::Skeptical) => {
+                encoding.encode_grounded(&targets)
+            }
+            (Semantics::Complete, Acceptance::Credulous)
+            | (Semantics::Preferred, Acceptance::Credulous) => {
+                encoding.encode_extension(&targets, false)
+            }
+            (Semantics::Stable, Acceptance::Credulous) => encoding.encode_extension(&targets, true),
+            _ => {
+                return Err(anyhow!("this enforcement problem is not handled"))
+                    .with_context(context)
+            }
+        }
+        Ok(encoding)
+    }
+
+    fn attack(&self, from: usize, to: usize) -> i32 {
+        self.attack_vars[from * self.framework.argument_set().len() + to]
+    }
+
+    fn encode_attacked_by(&mut self, members: &[i32]) -> Vec<i32> {
+        let n = members.len();
+        let attacked = (0..n).map(|_| self.cnf.new_var()).collect::<Vec<i32>>();
+        for (b, attacked_b) in attacked.iter().enumerate() {
+            let mut witnesses = vec![-attacked_b];
+            for (c, member_c) in members.iter().enumerate() {
+                let attack = self.attack(c, b);
+                let witness = self.cnf.new_var();
+                self.cnf.add_clause(&[-witness, *member_c]);
+                self.cnf.add_clause(&[-witness, attack]);
+                witnesses.push(witness);
+            }
+            self.cnf.add_clause(&witnesses);
+        }
+        attacked
+    }
+
+    fn encode_extension(&mut self, targets: &[usize], stable: bool) {
+        let n = self.framework.argument_set().len();
+        let members = (0..n).map(|_| self.cnf.new_var()).collect::<Vec<i32>>();
+        targets
+            .iter()
+            .for_each(|t| self.cnf.add_clause(&[members[*t]]));
+        let attacked = self.encode_attacked_by(&members);
+        for a in 0..n {
+            for b in 0..n {
+                let attack = self.attack(b, a);
+                self.cnf.add_clause(&[-members[a], -members[b], -attack]);
+                if !stable {
+                    self.cnf.add_clause(&[-members[a], -attack, attacked[b]]);
+                }
+            }
+            if stable {
+                self.cnf.add_clause(&[members[a], attacked[a]]);
+            }
+        }
+    }
+
+    fn encode_grounded(&mut self, targets: &[usize]) {
+        let n = self.framework.argument_set().len();
+        let mut members = vec![];
+        for _ in 0..n {
+            let next = (0..n).map(|_| self.cnf.new_var()).collect::<Vec<i32>>();
+            let attacked = if members.is_empty() {
+                None
+            } else {
+                Some(self.encode_attacked_by(&members))
+            };
+            for (a, next_a) in next.iter().enumerate() {
+                for b in 0..n {
+                    let mut clause = vec![-next_a, -self.attack(b, a)];
+                    if let Some(att) = &attacked {
+                        clause.push(att[b]);
+                    }
+                    self.cnf.add_clause(&clause);
+                }
+            }
+            members = next;
+        }
+        targets
+            .iter()
+            .for_each(|t| self.cnf.add_clause(&[members[*t]]));
+    }
+
+    /// Returns the formula encoding the problem.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{enforcement::{Acceptance, EnforcementEncoding}, AAFramework, ArgumentSet, Semantics};
+    /// let framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// let encoding = EnforcementEncoding::new(&framework, Semantics::Complete, Acceptance::Credulous, &["a"]).unwrap();
+    /// assert!(encoding.cnf().n_vars() >= 4);
+    /// ```
+    pub fn cnf(&self) -> &Cnf {
+        &self.cnf
+    }
+
+    /// Returns the literals which are `true` iff an attack is added or removed, one per couple of arguments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{enforcement::{Acceptance, EnforcementEncoding}, AAFramework, ArgumentSet, Semantics};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// let encoding = EnforcementEncoding::new(&framework, Semantics::Complete, Acceptance::Credulous, &["a"]).unwrap();
+    /// assert_eq!(&[1, -2, 3, 4], encoding.change_literals());
+    /// ```
+    pub fn change_literals(&self) -> &[i32] {
+        &self.change_literals
+    }
+
+    /// Adds a constraint allowing at most `k` changes of the framework.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - the maximal number of added or removed attacks
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{enforcement::{Acceptance, EnforcementEncoding}, sat::CdclSolver, AAFramework, ArgumentSet, Semantics};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// let mut encoding = EnforcementEncoding::new(&framework, Semantics::Grounded, Acceptance::Credulous, &["b"]).unwrap();
+    /// encoding.add_max_changes(0);
+    /// assert!(CdclSolver::new(encoding.cnf()).solve().is_none());
+    /// ```
+    pub fn add_max_changes(&mut self, k: usize) {
+        let literals = self.change_literals.clone();
+        self.cnf.add_at_most(&literals, k);
+    }
+
+    /// Returns the soft literals of the MaxSAT problem minimizing the cost of the changes.
+    ///
+    /// There is a soft literal per couple of arguments, which is `true` iff the attack is unchanged, weighted by the cost of its addition or removal.
+    ///
+    /// # Arguments
+    ///
+    /// * `addition_cost` - the cost of an attack addition
+    /// * `removal_cost` - the cost of an attack removal
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{enforcement::{Acceptance, EnforcementEncoding}, AAFramework, ArgumentSet, Semantics};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// let encoding = EnforcementEncoding::new(&framework, Semantics::Complete, Acceptance::Credulous, &["a"]).unwrap();
+    /// assert_eq!(vec![(-1, 1), (2, 3), (-3, 1), (-4, 1)], encoding.soft_literals(1, 3));
+    /// ```
+    pub fn soft_literals(&self, addition_cost: u64, removal_cost: u64) -> Vec<(i32, u64)> {
+        self.change_literals
+            .iter()
+            .map(|l| {
+                let cost = if *l < 0 { removal_cost } else { addition_cost };
+                (-l, cost)
+            })
+            .collect()
+    }
+
+    /// Translates a model of the formula into the modifications of the framework it implies.
+    ///
+    /// Attack removals come first, followed by the additions; each kind of modification is sorted by the identifiers of the attackers, then by the ones of the attacked arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - the model, giving the value of variable `v` at index `v-1`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{dynamics::Modification, enforcement::{Acceptance, EnforcementEncoding}, sat::CdclSolver, AAFramework, ArgumentSet, Semantics};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// let mut encoding = EnforcementEncoding::new(&framework, Semantics::Grounded, Acceptance::Credulous, &["b"]).unwrap();
+    /// encoding.add_max_changes(1);
+    /// let model = CdclSolver::new(encoding.cnf()).solve().unwrap();
+    /// assert_eq!(vec![Modification::RemoveAttack("a", "b")], encoding.modifications(&model));
+    /// ```
+    pub fn modifications(&self, model: &[bool]) -> Vec<Modification<T>> {
+        let n = self.framework.argument_set().len();
+        let label = |id: usize| {
+            self.framework
+                .argument_set()
+                .get_argument_by_id(id)
+                .label()
+                .clone()
+        };
+        let changes = (0..n * n)
+            .filter(|i| {
+                let l = self.change_literals[*i];
+                model[l.unsigned_abs() as usize - 1] == (l > 0)
+            })
+            .collect::<Vec<usize>>();
+        let removals = changes
+            .iter()
+            .filter(|i| self.change_literals[**i] < 0)
+            .map(|i| Modification::RemoveAttack(label(i / n), label(i % n)));
+        let additions = changes
+            .iter()
+            .filter(|i| self.change_literals[**i] > 0)
+            .map(|i| Modification::AddAttack(label(i / n), label(i % n)));
+        removals.chain(additions).collect()
+    }
+}
+
+/// The result of an enforcement: the status of the search, and the best modifications found with their cost, if any.
+///
+/// The modifications are minimal when the status is [`Optimal`].
+///
+/// [`Optimal`]: ../maxsat/enum.MaxSatStatus.html#variant.Optimal
+#[derive(Clone, Debug, PartialEq)]
+pub struct Enforcement<T>
+where
+    T: LabelType,
+{
+    status: MaxSatStatus,
+    solution: Option<(Vec<Modification<T>>, u64)>,
+}
+
+impl<T> Enforcement<T>
+where
+    T: LabelType,
+{
+    /// Returns the status of the search.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{enforcement::{Acceptance, Enforcer}, maxsat::MaxSatStatus, AAFramework, ArgumentSet, Semantics};
+    /// let framework = AAFramework::new(ArgumentSet::new(vec!["a"]));
+    /// let enforcement = Enforcer::default().enforce(&framework, Semantics::Stable, Acceptance::Credulous, &["a"]).unwrap();
+    /// assert_eq!(MaxSatStatus::Optimal, enforcement.status());
+    /// ```
+    pub fn status(&self) -> MaxSatStatus {
+        self.status
+    }
+
+    /// Returns the best modifications found, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{enforcement::{Acceptance, Enforcer}, AAFramework, ArgumentSet, Semantics};
+    /// let framework = AAFramework::new(ArgumentSet::new(vec!["a"]));
+    /// let enforcement = Enforcer::default().enforce(&framework, Semantics::Stable, Acceptance::Credulous, &["a"]).unwrap();
+    /// assert_eq!(Some(&[][..]), enforcement.modifications());
+    /// ```
+    pub fn modifications(&self) -> Option<&[Modification<T>]> {
+        self.solution.as_ref().map(|(m, _)| m.as_slice())
+    }
+
+    /// Returns the cost of the best modifications found, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{enforcement::{Acceptance, Enforcer}, AAFramework, ArgumentSet, Semantics};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a"]));
+    /// framework.new_attack(&"a", &"a").unwrap();
+    /// let mut enforcer = Enforcer::default();
+    /// enforcer.set_removal_cost(5);
+    /// let enforcement = enforcer.enforce(&framework, Semantics::Grounded, Acceptance::Credulous, &["a"]).unwrap();
+    /// assert_eq!(Some(5), enforcement.cost());
+    /// ```
+    pub fn cost(&self) -> Option<u64> {
+        self.solution.as_ref().map(|(_, c)| *c)
+    }
+}
+
+/// An object computing the attack additions and removals of minimal cost after which the target arguments are accepted.
+///
+/// The problem is encoded by an [`EnforcementEncoding`] and solved by a [`MaxSatSolver`], the [`LinearSearchSolver`] by default.
+/// Each addition and each removal costs `1` by default, so that the number of changes is minimized.
+/// A maximal number of changes and a deadline may be set; when the deadline is reached, the best modifications found so far are returned, without any guarantee of optimality.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{dynamics::Modification, enforcement::{Acceptance, Enforcer}, maxsat::MaxSatStatus, AAFramework, ArgumentSet, Semantics};
+/// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+/// framework.new_attack(&"a", &"b").unwrap();
+/// let mut enforcer = Enforcer::default();
+/// enforcer.set_removal_cost(3);
+/// let enforcement = enforcer.enforce(&framework, Semantics::Stable, Acceptance::Credulous, &["b"]).unwrap();
+/// assert_eq!(MaxSatStatus::Optimal, enforcement.status());
+/// assert_eq!(Some(&[Modification::AddAttack("b", "a")][..]), enforcement.modifications());
+/// ```
+///
+/// [`EnforcementEncoding`]: struct.EnforcementEncoding.html
+/// [`LinearSearchSolver`]: ../maxsat/struct.LinearSearchSolver.html
+/// [`MaxSatSolver`]: ../maxsat/trait.MaxSatSolver.html
+pub struct Enforcer {
+    solver: Box<dyn MaxSatSolver>,
+    addition_cost: u64,
+    removal_cost: u64,
+    max_changes: Option<usize>,
+    deadline: Option<Instant>,
+}
+
+impl Default for Enforcer {
+    fn default() -> Self {
+        Enforcer {
+            solver: Box::new(LinearSearchSolver),
+            addition_cost: 1,
+            removal_cost: 1,
+            max_changes: None,
+            deadline: None,
+        }
+    }
+}
+
+impl Enforcer {
+    /// Sets the MaxSAT solver used to find the modifications.
+    ///
+    /// # Arguments
+    ///
+    /// * `solver` - the solver
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{enforcement::Enforcer, maxsat::LinearSearchSolver};
+    /// let mut enforcer = Enforcer::default();
+    /// enforcer.set_solver(Box::new(LinearSearchSolver::default()));
+    /// ```
+    pub fn set_solver(&mut self, solver: Box<dyn MaxSatSolver>) {
+        self.solver = solver;
+    }
+
+    /// Sets the cost of an attack addition (`1` by default).
+    ///
+    /// # Arguments
+    ///
+    /// * `cost` - the cost
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::enforcement::Enforcer;
+    /// let mut enforcer = Enforcer::default();
+    /// enforcer.set_addition_cost(2);
+    /// ```
+    pub fn set_addition_cost(&mut self, cost: u64) {
+        self.addition_cost = cost;
+    }
+
+    /// Sets the cost of an attack removal (`1` by default).
+    ///
+    /// # Arguments
+    ///
+    /// * `cost` - the cost
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::enforcement::Enforcer;
+    /// let mut enforcer = Enforcer::default();
+    /// enforcer.set_removal_cost(2);
+    /// ```
+    pub fn set_removal_cost(&mut self, cost: u64) {
+        self.removal_cost = cost;
+    }
+
+    /// Sets the maximal number of changes, regardless of their costs.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_changes` - the maximal number of added or removed attacks
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{enforcement::{Acceptance, Enforcer}, maxsat::MaxSatStatus, AAFramework, ArgumentSet, Semantics};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a"]));
+    /// framework.new_attack(&"a", &"a").unwrap();
+    /// let mut enforcer = Enforcer::default();
+    /// enforcer.set_max_changes(0);
+    /// let enforcement = enforcer.enforce(&framework, Semantics::Grounded, Acceptance::Credulous, &["a"]).unwrap();
+    /// assert_eq!(MaxSatStatus::Unsatisfiable, enforcement.status());
+    /// ```
+    pub fn set_max_changes(&mut self, max_changes: usize) {
+        self.max_changes = Some(max_changes);
+    }
+
+    /// Sets the instant at which the search is stopped.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - the deadline
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::enforcement::Enforcer;
+    /// # use std::time::{Duration, Instant};
+    /// let mut enforcer = Enforcer::default();
+    /// enforcer.set_deadline(Instant::now() + Duration::from_secs(10));
+    /// ```
+    pub fn set_deadline(&mut self, deadline: Instant) {
+        self.deadline = Some(deadline);
+    }
+
+    /// Computes the modifications of minimal cost after which the target arguments are accepted.
+    ///
+    /// If a target argument is undefined, or if the acceptance is not handled under the semantics, an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `framework` - the framework to modify
+    /// * `semantics` - the semantics
+    /// * `acceptance` - the acceptance to enforce
+    /// * `targets` - the labels of the arguments to accept
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{enforcement::{Acceptance, Enforcer}, AAFramework, ArgumentSet, Semantics};
+    /// let framework = AAFramework::new(ArgumentSet::new(vec!["a"]));
+    /// assert!(Enforcer::default().enforce(&framework, Semantics::Stable, Acceptance::Credulous, &["b"]).is_err());
+    /// ```
+    pub fn enforce<T>(
+        &mut self,
+        framework: &AAFramework<T>,
+        semantics: Semantics,
+        acceptance: Acceptance,
+        targets: &[T],
+    ) -> Result<Enforcement<T>>
+    where
+        T: LabelType,
+    {
+        let mut encoding = EnforcementEncoding::new(framework, semantics, acceptance, targets)?;
+        if let Some(k) = self.max_changes {
+            encoding.add_max_changes(k);
+        }
+        let soft = encoding.soft_literals(self.addition_cost, self.removal_cost);
+        let outcome = self.solver.solve(encoding.cnf(), &soft, self.deadline);
+        Ok(Enforcement {
+            status: outcome.status(),
+            solution: outcome
+                .model()
+                .map(|m| (encoding.modifications(m), outcome.cost().unwrap())),
+        })
+    }
+}
+
+/// Computes a minimal set of attack additions and removals after which the target arguments are accepted.
+///
+/// If no solution of at most `max_changes` changes exists, `None` is returned.
+/// If a target argument is undefined, or if the acceptance is not handled under the semantics, an error is returned.
+/// See [`Enforcer`] for weighted changes and time limits.
+///
+/// # Arguments
+///
+/// * `framework` - the framework to modify
+/// * `semantics` - the semantics
+/// * `acceptance` - the acceptance to enforce
+/// * `targets` - the labels of the arguments to accept
+/// * `max_changes` - the maximal number of changes, if any
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{enforcement::{self, Acceptance}, AAFramework, ArgumentSet, Semantics};
+/// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+/// framework.new_attack(&"a", &"b").unwrap();
+/// framework.new_attack(&"b", &"c").unwrap();
+/// // removing a → b or adding b → a
+/// let modifications = enforcement::enforce(&framework, Semantics::Stable, Acceptance::Credulous, &["b"], None).unwrap();
+/// assert_eq!(1, modifications.unwrap().len());
+/// ```
+///
+/// [`Enforcer`]: struct.Enforcer.html
+pub fn enforce<T>(
+    framework: &AAFramework<T>,
+    semantics: Semantics,
+    acceptance: Acceptance,
+    targets: &[T],
+    max_changes: Option<usize>,
+) -> Result<Option<Vec<Modification<T>>>>
+where
+    T: LabelType,
+{
+    let mut enforcer = Enforcer::default();
+    if let Some(k) = max_changes {
+        enforcer.set_max_changes(k);
+    }
+    let enforcement = enforcer.enforce(framework, semantics, acceptance, targets)?;
+    Ok(enforcement.modifications().map(|m| m.to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aa::arguments::ArgumentSet;
+
+    fn apply(
+        framework: &AAFramework<&'static str>,
+        modifications: &[Modification<&'static str>],
+    ) -> AAFramework<&'static str> {
+        let labels = framework
+            .argument_set()
+            .iter()
+            .map(|a| *a.label())
+            .collect();
+        let mut result = AAFramework::new(ArgumentSet::new(labels));
+        for att in framework.iter_attacks() {
+            let (from, to) = (att.attacker().label(), att.attacked().label());
+            if !modifications.contains(&Modification::RemoveAttack(*from, *to)) {
+                result.new_attack(from, to).unwrap();
+            }
+        }
+        for m in modifications {
+            if let Modification::AddAttack(from, to) = m {
+                result.new_attack(from, to).unwrap();
+            }
+        }
+        result
+    }
+
+    fn odd_cycle() -> AAFramework<&'static str> {
+        let mut af = AAFramework::new(ArgumentSet::new(vec!["a", "b", "c", "d"]));
+        af.new_attack(&"a", &"b").unwrap();
+        af.new_attack(&"b", &"c").unwrap();
+        af.new_attack(&"c", &"a").unwrap();
+        af.new_attack(&"d", &"a").unwrap();
+        af
+    }
+
+    fn check(semantics: Semantics, acceptance: Acceptance, targets: &[&'static str], n: usize) {
+        let af = odd_cycle();
+        let modifications = enforce(&af, semantics, acceptance, targets, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(n, modifications.len(), "{:?}", modifications);
+        let modified = apply(&af, &modifications);
+        for t in targets {
+            let accepted = match acceptance {
+                Acceptance::Credulous => modified.credulously_accepted(t, semantics),
+                Acceptance::Skeptical => modified.skeptically_accepted(t, semantics),
+            };
+            assert!(accepted.unwrap(), "{} in {:?}", t, modifications);
+        }
+    }
+
+    #[test]
+    fn test_enforce() {
+        check(Semantics::Grounded, Acceptance::Credulous, &["a"], 2);
+        check(Semantics::Complete, Acceptance::Skeptical, &["d", "b"], 0);
+        check(Semantics::Complete, Acceptance::Skeptical, &["d", "c"], 1);
+        check(Semantics::Complete, Acceptance::Credulous, &["a"], 2);
+        check(Semantics::Preferred, Acceptance::Credulous, &["d"], 0);
+        check(Semantics::Stable, Acceptance::Credulous, &["d", "c"], 1);
+        check(Semantics::Stable, Acceptance::Credulous, &["a", "b"], 2);
+    }
+
+    #[test]
+    fn test_enforce_max_changes() {
+        let af = odd_cycle();
+        assert_eq!(
+            None,
+            enforce(
+                &af,
+                Semantics::Grounded,
+                Acceptance::Credulous,
+                &["a"],
+                Some(1)
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_enforcer_costs() {
+        let af = odd_cycle();
+        let mut enforcer = Enforcer::default();
+        enforcer.set_removal_cost(3);
+        let enforcement = enforcer
+            .enforce(&af, Semantics::Grounded, Acceptance::Credulous, &["a"])
+            .unwrap();
+        assert_eq!(MaxSatStatus::Optimal, enforcement.status());
+        assert_eq!(Some(4), enforcement.cost());
+        let modified = apply(&af, enforcement.modifications().unwrap());
+        assert!(modified
+            .credulously_accepted(&"a", Semantics::Grounded)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_enforcer_large_costs() {
+        let af = odd_cycle();
+        let mut enforcer = Enforcer::default();
+        enforcer.set_addition_cost(1_000_000);
+        enforcer.set_removal_cost(3_000_000);
+        let enforcement = enforcer
+            .enforce(&af, Semantics::Grounded, Acceptance::Credulous, &["a"])
+            .unwrap();
+        assert_eq!(MaxSatStatus::Optimal, enforcement.status());
+        assert_eq!(Some(4_000_000), enforcement.cost());
+    }
+
+    #[test]
+    fn test_enforcer_deadline() {
+        let af = odd_cycle();
+        let mut enforcer = Enforcer::default();
+        enforcer.set_deadline(Instant::now());
+        let enforcement = enforcer
+            .enforce(&af, Semantics::Grounded, Acceptance::Credulous, &["a"])
+            .unwrap();
+        assert_eq!(MaxSatStatus::Unknown, enforcement.status());
+        assert_eq!(None, enforcement.modifications());
+    }
+
+    #[test]
+    fn test_unhandled_problem() {
+        let af = odd_cycle();
+        assert!(enforce(&af, Semantics::Stage, Acceptance::Credulous, &["a"], None).is_err());
+        assert!(enforce(
+            &af,
+            Semantics::Preferred,
+            Acceptance::Skeptical,
+            &["a"],
+            None
+        )
+        .is_err());
+    }
+}
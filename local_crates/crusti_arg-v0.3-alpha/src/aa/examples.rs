@@ -0,0 +1,240 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! A library of the classic small frameworks of the literature.
+//!
+//! Each [`Template`] builds a framework illustrating a well-known behavior of the semantics, such as the reinstatement of arguments, the floating acceptance, or the difference between even and odd cycles.
+//! These frameworks are handy for unit tests and teaching demos.
+//!
+//! ```
+//! # use crusti_arg::{examples::Template, Semantics};
+//! let framework = Template::FloatingAcceptance.framework();
+//! let d = "d".to_string();
+//! assert!(framework.skeptically_accepted(&d, Semantics::Preferred).unwrap());
+//! assert!(!framework.skeptically_accepted(&d, Semantics::Grounded).unwrap());
+//! ```
+//!
+//! [`Template`]: enum.Template.html
+
+use crate::{AAFramework, ArgumentSet};
+use anyhow::{anyhow, Result};
+use std::{fmt::Display, str::FromStr};
+
+/// The classic frameworks of the library.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::examples::Template;
+/// assert_eq!(Template::ThreeCycle, "3-cycle".parse::<Template>().unwrap());
+/// assert_eq!("nixon-diamond", Template::NixonDiamond.to_string());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Template {
+    /// Dung's reinstatement example: `a` attacks `b`, which attacks `c`; `c` is reinstated by `a`, and the grounded extension is `{a, c}`.
+    Reinstatement,
+    /// Two arguments attacking each other: the grounded extension is empty, and each argument is the only member of a stable extension.
+    MutualAttack,
+    /// An odd cycle of three arguments: the grounded, complete and preferred extensions are empty, and there is no stable extension.
+    ThreeCycle,
+    /// A self-attacking argument `a` attacking `b`: `a` belongs to no admissible set, so `b` is never defended.
+    SelfAttack,
+    /// The floating acceptance example: `a` and `b` attack each other and both attack `c`, which attacks `d`;
+    /// `d` is skeptically accepted under the preferred semantics, though it is not in the grounded extension.
+    FloatingAcceptance,
+    /// The Nixon diamond: Nixon is a pacifist since he is a quaker, and is not since he is a republican;
+    /// the arguments `pacifist` and `not_pacifist` attack each other.
+    NixonDiamond,
+}
+
+impl FromStr for Template {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "reinstatement" => Ok(Template::Reinstatement),
+            "mutual-attack" => Ok(Template::MutualAttack),
+            "3-cycle" => Ok(Template::ThreeCycle),
+            "self-attack" => Ok(Template::SelfAttack),
+            "floating-acceptance" => Ok(Template::FloatingAcceptance),
+            "nixon-diamond" => Ok(Template::NixonDiamond),
+            _ => Err(anyhow!(r#"unknown template "{}""#, s)),
+        }
+    }
+}
+
+impl Display for Template {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Template::Reinstatement => "reinstatement",
+            Template::MutualAttack => "mutual-attack",
+            Template::ThreeCycle => "3-cycle",
+            Template::SelfAttack => "self-attack",
+            Template::FloatingAcceptance => "floating-acceptance",
+            Template::NixonDiamond => "nixon-diamond",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Template {
+    /// The names of the templates, as accepted by their `FromStr` implementation.
+    pub const NAMES: [&'static str; 6] = [
+        "reinstatement",
+        "mutual-attack",
+        "3-cycle",
+        "self-attack",
+        "floating-acceptance",
+        "nixon-diamond",
+    ];
+
+    /// All the templates, in the order of their names.
+    pub const ALL: [Template; 6] = [
+        Template::Reinstatement,
+        Template::MutualAttack,
+        Template::ThreeCycle,
+        Template::SelfAttack,
+        Template::FloatingAcceptance,
+        Template::NixonDiamond,
+    ];
+
+    /// Builds the framework of this template.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::examples::Template;
+    /// let framework = Template::ThreeCycle.framework();
+    /// assert_eq!(3, framework.argument_set().len());
+    /// assert_eq!(3, framework.n_attacks());
+    /// ```
+    pub fn framework(&self) -> AAFramework<String> {
+        let (labels, attacks): (&[&str], &[(&str, &str)]) = match self {
+            Template::Reinstatement => (&["a", "b", "c"], &[("a", "b"), ("b", "c")]),
+            Template::MutualAttack => (&["a", "b"], &[("a", "b"), ("b", "a")]),
+            Template::ThreeCycle => (&["a", "b", "c"], &[("a", "b"), ("b", "c"), ("c", "a")]),
+            Template::SelfAttack => (&["a", "b"], &[("a", "a"), ("a", "b")]),
+            Template::FloatingAcceptance => (
+                &["a", "b", "c", "d"],
+                &[("a", "b"), ("b", "a"), ("a", "c"), ("b", "c"), ("c", "d")],
+            ),
+            Template::NixonDiamond => (
+                &["pacifist", "not_pacifist"],
+                &[("pacifist", "not_pacifist"), ("not_pacifist", "pacifist")],
+            ),
+        };
+        let mut framework = AAFramework::new(ArgumentSet::new(
+            labels.iter().map(|l| l.to_string()).collect(),
+        ));
+        for (from, to) in attacks.iter() {
+            framework
+                .new_attack(&from.to_string(), &to.to_string())
+                .unwrap();
+        }
+        framework
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{solvers, Semantics};
+
+    fn extensions(template: Template, semantics: Semantics) -> Vec<Vec<String>> {
+        let framework = template.framework();
+        let mut extensions = solvers::default_solver(&framework, semantics)
+            .unwrap()
+            .all_extensions(semantics)
+            .unwrap()
+            .iter()
+            .map(|e| {
+                framework
+                    .argument_set()
+                    .iter()
+                    .filter(|a| e[a.id()])
+                    .map(|a| a.label().clone())
+                    .collect::<Vec<String>>()
+            })
+            .collect::<Vec<Vec<String>>>();
+        extensions.sort();
+        extensions
+    }
+
+    #[test]
+    fn test_names() {
+        for (name, template) in Template::NAMES.iter().zip(Template::ALL.iter()) {
+            assert_eq!(*template, name.parse::<Template>().unwrap());
+            assert_eq!(*name, template.to_string());
+        }
+        assert!("4-cycle".parse::<Template>().is_err());
+    }
+
+    #[test]
+    fn test_reinstatement() {
+        assert_eq!(
+            vec![vec!["a", "c"]],
+            extensions(Template::Reinstatement, Semantics::Grounded)
+        );
+    }
+
+    #[test]
+    fn test_mutual_attack() {
+        assert_eq!(
+            vec![Vec::<String>::new()],
+            extensions(Template::MutualAttack, Semantics::Grounded)
+        );
+        assert_eq!(
+            vec![vec!["a"], vec!["b"]],
+            extensions(Template::MutualAttack, Semantics::Stable)
+        );
+        assert_eq!(
+            extensions(Template::MutualAttack, Semantics::Stable).len(),
+            extensions(Template::NixonDiamond, Semantics::Stable).len()
+        );
+    }
+
+    #[test]
+    fn test_three_cycle() {
+        assert!(extensions(Template::ThreeCycle, Semantics::Stable).is_empty());
+        assert_eq!(
+            vec![Vec::<String>::new()],
+            extensions(Template::ThreeCycle, Semantics::Preferred)
+        );
+    }
+
+    #[test]
+    fn test_self_attack() {
+        assert_eq!(
+            vec![Vec::<String>::new()],
+            extensions(Template::SelfAttack, Semantics::Preferred)
+        );
+    }
+
+    #[test]
+    fn test_floating_acceptance() {
+        assert_eq!(
+            vec![vec!["a", "d"], vec!["b", "d"]],
+            extensions(Template::FloatingAcceptance, Semantics::Preferred)
+        );
+        assert_eq!(
+            vec![Vec::<String>::new()],
+            extensions(Template::FloatingAcceptance, Semantics::Grounded)
+        );
+    }
+}
@@ -0,0 +1,515 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::aa_framework::AAFramework;
+use crate::aa::arguments::{Argument, ArgumentSet, LabelType};
+use crate::aa::enforcement::Acceptance;
+use crate::aa::semantics::Semantics;
+use anyhow::{Context, Result};
+
+/// An Incomplete Argumentation framework (IAF), in which some arguments and attacks are uncertain.
+///
+/// The completions of an IAF are the frameworks made of its certain arguments and attacks, together with some of its uncertain arguments and attacks.
+/// An argument is possibly (resp. necessarily) accepted if it is accepted in at least one (resp. each) completion.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{enforcement::Acceptance, IncompleteAAFramework, Semantics};
+/// let mut framework = IncompleteAAFramework::new(vec!["a"], vec!["b"]);
+/// framework.new_attack(&"b", &"a").unwrap();
+/// assert_eq!(2, framework.iter_completions().count());
+/// assert!(framework.possibly_accepted(&"a", Semantics::Grounded, Acceptance::Credulous).unwrap());
+/// assert!(!framework.necessarily_accepted(&"a", Semantics::Grounded, Acceptance::Credulous).unwrap());
+/// ```
+pub struct IncompleteAAFramework<T>
+where
+    T: LabelType,
+{
+    arguments: ArgumentSet<T>,
+    uncertain_arguments: Vec<bool>,
+    attacks: Vec<(usize, usize)>,
+    uncertain_attacks: Vec<(usize, usize)>,
+}
+
+impl<T> IncompleteAAFramework<T>
+where
+    T: LabelType,
+{
+    /// Builds an incomplete framework with no attacks given the labels of its certain and uncertain arguments.
+    ///
+    /// The ids of the arguments follow the order of the certain, then the uncertain arguments.
+    /// The labels must be distinct.
+    ///
+    /// # Arguments
+    ///
+    /// * `certain` - the labels of the certain arguments
+    /// * `uncertain` - the labels of the uncertain arguments
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::IncompleteAAFramework;
+    /// let framework = IncompleteAAFramework::new(vec!["a", "b"], vec!["c"]);
+    /// assert_eq!(3, framework.argument_set().len());
+    /// ```
+    pub fn new(certain: Vec<T>, uncertain: Vec<T>) -> Self {
+        let mut uncertain_arguments = vec![false; certain.len()];
+        uncertain_arguments.append(&mut vec![true; uncertain.len()]);
+        IncompleteAAFramework {
+            arguments: ArgumentSet::new(certain.into_iter().chain(uncertain).collect()),
+            uncertain_arguments,
+            attacks: vec![],
+            uncertain_attacks: vec![],
+        } // kcov-ignore
+    }
+
+    /// Returns the argument set of the framework, made of its certain and uncertain arguments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::IncompleteAAFramework;
+    /// let framework = IncompleteAAFramework::new(vec!["a"], vec!["b"]);
+    /// assert_eq!(2, framework.argument_set().len());
+    /// ```
+    pub fn argument_set(&self) -> &ArgumentSet<T> {
+        &self.arguments
+    }
+
+    /// Returns `true` iff an argument is uncertain.
+    ///
+    /// If the argument is undefined, an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - the label of the argument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::IncompleteAAFramework;
+    /// let framework = IncompleteAAFramework::new(vec!["a"], vec!["b"]);
+    /// assert!(!framework.is_uncertain(&"a").unwrap());
+    /// assert!(framework.is_uncertain(&"b").unwrap());
+    /// ```
+    pub fn is_uncertain(&self, label: &T) -> Result<bool> {
+        Ok(self.uncertain_arguments[self.arguments.get_argument_index(label)?])
+    }
+
+    fn attack_ids(&self, from: &T, to: &T) -> Result<(usize, usize)> {
+        Ok((
+            self.arguments.get_argument_index(from)?,
+            self.arguments.get_argument_index(to)?,
+        ))
+    }
+
+    /// Adds a new (certain) attack given the labels of the source and destination arguments.
+    ///
+    /// If the provided arguments are undefined, an error is returned.
+    /// An attack involving an uncertain argument only exists in the completions containing this argument.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - the label of the source argument (attacker)
+    /// * `to` - the label of the destination argument (attacked)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::IncompleteAAFramework;
+    /// let mut framework = IncompleteAAFramework::new(vec!["a", "b"], vec![]);
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// assert_eq!(1, framework.iter_attacks().count());
+    /// ```
+    pub fn new_attack(&mut self, from: &T, to: &T) -> Result<()> {
+        let ids = self
+            .attack_ids(from, to)
+            .with_context(|| format!("cannot add an attack from {:?} to {:?}", from, to))?;
+        self.attacks.push(ids);
+        Ok(())
+    }
+
+    /// Adds a new uncertain attack given the labels of the source and destination arguments.
+    ///
+    /// If the provided arguments are undefined, an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - the label of the source argument (attacker)
+    /// * `to` - the label of the destination argument (attacked)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::IncompleteAAFramework;
+    /// let mut framework = IncompleteAAFramework::new(vec!["a", "b"], vec![]);
+    /// framework.new_uncertain_attack(&"a", &"b").unwrap();
+    /// assert_eq!(1, framework.iter_uncertain_attacks().count());
+    /// ```
+    pub fn new_uncertain_attack(&mut self, from: &T, to: &T) -> Result<()> {
+        let ids = self.attack_ids(from, to).with_context(|| {
+            format!("cannot add an uncertain attack from {:?} to {:?}", from, to)
+        })?;
+        self.uncertain_attacks.push(ids);
+        Ok(())
+    }
+
+    fn iter_relation<'a>(
+        &'a self,
+        relation: &'a [(usize, usize)],
+    ) -> Box<dyn Iterator<Item = (&'a Argument<T>, &'a Argument<T>)> + 'a> {
+        Box::new(relation.iter().map(move |(a, b)| {
+            (
+                self.arguments.get_argument_by_id(*a),
+                self.arguments.get_argument_by_id(*b),
+            )
+        }))
+    }
+
+    /// Provides an iterator to the (certain) attacks, in the order they were added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::IncompleteAAFramework;
+    /// let mut framework = IncompleteAAFramework::new(vec!["a", "b"], vec![]);
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// let (from, to) = framework.iter_attacks().next().unwrap();
+    /// assert_eq!((&"a", &"b"), (from.label(), to.label()));
+    /// ```
+    pub fn iter_attacks<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = (&'a Argument<T>, &'a Argument<T>)> + 'a> {
+        self.iter_relation(&self.attacks)
+    }
+
+    /// Provides an iterator to the uncertain attacks, in the order they were added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::IncompleteAAFramework;
+    /// let mut framework = IncompleteAAFramework::new(vec!["a", "b"], vec![]);
+    /// framework.new_uncertain_attack(&"a", &"b").unwrap();
+    /// assert_eq!(1, framework.iter_uncertain_attacks().count());
+    /// ```
+    pub fn iter_uncertain_attacks<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = (&'a Argument<T>, &'a Argument<T>)> + 'a> {
+        self.iter_relation(&self.uncertain_attacks)
+    }
+
+    /// Provides an iterator to the completions of the framework.
+    ///
+    /// The first completion is made of the certain arguments and attacks only.
+    /// The uncertain attacks involving an absent argument are not considered, so that each completion is given once.
+    /// The number of completions is exponential in the number of uncertain arguments and attacks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::IncompleteAAFramework;
+    /// let mut framework = IncompleteAAFramework::new(vec!["a"], vec!["b"]);
+    /// framework.new_uncertain_attack(&"a", &"b").unwrap();
+    /// // {a}, {a, b} and {a, b} with a → b
+    /// assert_eq!(3, framework.iter_completions().count());
+    /// ```
+    pub fn iter_completions(&self) -> Completions<'_, T> {
+        Completions {
+            framework: self,
+            choices: Some(vec![
+                false;
+                self.uncertain_arguments.len()
+                    + self.uncertain_attacks.len()
+            ]),
+        }
+    }
+
+    /// Returns `true` iff an argument is accepted in at least one completion of the framework.
+    ///
+    /// The acceptance in each completion is decided by the native engines of the [`AAFramework`].
+    /// If the argument is undefined, an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - the label of the argument
+    /// * `semantics` - the semantics
+    /// * `acceptance` - the acceptance in the completions
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{enforcement::Acceptance, IncompleteAAFramework, Semantics};
+    /// let mut framework = IncompleteAAFramework::new(vec!["a", "b"], vec![]);
+    /// framework.new_uncertain_attack(&"b", &"a").unwrap();
+    /// assert!(framework.possibly_accepted(&"a", Semantics::Stable, Acceptance::Skeptical).unwrap());
+    /// ```
+    ///
+    /// [`AAFramework`]: struct.AAFramework.html
+    pub fn possibly_accepted(
+        &self,
+        label: &T,
+        semantics: Semantics,
+        acceptance: Acceptance,
+    ) -> Result<bool> {
+        let context = || {
+            format!(
+                "while checking the possible {}-{} acceptance of {:?}",
+                acceptance, semantics, label
+            )
+        };
+        self.acceptance_in_completions(label, semantics, acceptance)
+            .with_context(context)?
+            .try_fold(false, |acc, r| r.map(|b| acc || b))
+            .with_context(context)
+    }
+
+    /// Returns `true` iff an argument is accepted in each completion of the framework.
+    ///
+    /// The acceptance in each completion is decided by the native engines of the [`AAFramework`].
+    /// Uncertain arguments are not necessarily accepted, since they are absent from some completions.
+    /// If the argument is undefined, an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - the label of the argument
+    /// * `semantics` - the semantics
+    /// * `acceptance` - the acceptance in the completions
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{enforcement::Acceptance, IncompleteAAFramework, Semantics};
+    /// let mut framework = IncompleteAAFramework::new(vec!["a", "b"], vec![]);
+    /// framework.new_uncertain_attack(&"b", &"a").unwrap();
+    /// assert!(framework.necessarily_accepted(&"b", Semantics::Grounded, Acceptance::Skeptical).unwrap());
+    /// assert!(!framework.necessarily_accepted(&"a", Semantics::Grounded, Acceptance::Skeptical).unwrap());
+    /// ```
+    ///
+    /// [`AAFramework`]: struct.AAFramework.html
+    pub fn necessarily_accepted(
+        &self,
+        label: &T,
+        semantics: Semantics,
+        acceptance: Acceptance,
+    ) -> Result<bool> {
+        let context = || {
+            format!(
+                "while checking the necessary {}-{} acceptance of {:?}",
+                acceptance, semantics, label
+            )
+        };
+        self.acceptance_in_completions(label, semantics, acceptance)
+            .with_context(context)?
+            .try_fold(true, |acc, r| r.map(|b| acc && b))
+            .with_context(context)
+    }
+
+    fn acceptance_in_completions<'a>(
+        &'a self,
+        label: &'a T,
+        semantics: Semantics,
+        acceptance: Acceptance,
+    ) -> Result<impl Iterator<Item = Result<bool>> + 'a> {
+        self.arguments.get_argument_index(label)?;
+        Ok(self.iter_completions().map(move |completion| {
+            if !completion.argument_set().contains(label) {
+                return Ok(false);
+            }
+            match acceptance {
+                Acceptance::Credulous => completion.credulously_accepted(label, semantics),
+                Acceptance::Skeptical => completion.skeptically_accepted(label, semantics),
+            }
+        }))
+    }
+}
+
+/// An iterator to the completions of an [`IncompleteAAFramework`].
+///
+/// This iterator is built by [`IncompleteAAFramework::iter_completions`].
+///
+/// [`IncompleteAAFramework`]: struct.IncompleteAAFramework.html
+/// [`IncompleteAAFramework::iter_completions`]: struct.IncompleteAAFramework.html#method.iter_completions
+pub struct Completions<'a, T>
+where
+    T: LabelType,
+{
+    framework: &'a IncompleteAAFramework<T>,
+    choices: Option<Vec<bool>>,
+}
+
+impl<'a, T> Completions<'a, T>
+where
+    T: LabelType,
+{
+    fn present_arguments(&self, choices: &[bool]) -> Vec<bool> {
+        self.framework
+            .uncertain_arguments
+            .iter()
+            .zip(choices.iter())
+            .map(|(uncertain, chosen)| !uncertain || *chosen)
+            .collect()
+    }
+
+    fn completion(&self, choices: &[bool]) -> Option<AAFramework<T>> {
+        let n_arguments = self.framework.uncertain_arguments.len();
+        let present = self.present_arguments(choices);
+        let chosen_attacks = self
+            .framework
+            .uncertain_attacks
+            .iter()
+            .zip(choices[n_arguments..].iter())
+            .filter(|(_, chosen)| **chosen)
+            .map(|(attack, _)| *attack)
+            .collect::<Vec<(usize, usize)>>();
+        if chosen_attacks
+            .iter()
+            .any(|(a, b)| !present[*a] || !present[*b])
+        {
+            return None;
+        }
+        let arguments = &self.framework.arguments;
+        let labels = arguments
+            .iter()
+            .filter(|a| present[a.id()])
+            .map(|a| a.label().clone())
+            .collect();
+        let mut completion = AAFramework::new(ArgumentSet::new(labels));
+        for (from, to) in self.framework.attacks.iter().chain(chosen_attacks.iter()) {
+            if present[*from] && present[*to] {
+                completion
+                    .new_attack(
+                        arguments.get_argument_by_id(*from).label(),
+                        arguments.get_argument_by_id(*to).label(),
+                    )
+                    .unwrap();
+            }
+        }
+        Some(completion)
+    }
+}
+
+impl<'a, T> Iterator for Completions<'a, T>
+where
+    T: LabelType,
+{
+    type Item = AAFramework<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let uncertain_arguments = &self.framework.uncertain_arguments;
+        loop {
+            let choices = self.choices.take()?;
+            let completion = self.completion(&choices);
+            let mut next_choices = choices;
+            let mut i = 0;
+            while i < next_choices.len() {
+                // the choices related to certain arguments are left unset
+                if i < uncertain_arguments.len() && !uncertain_arguments[i] {
+                    i += 1;
+                    continue;
+                }
+                next_choices[i] = !next_choices[i];
+                if next_choices[i] {
+                    self.choices = Some(next_choices);
+                    break;
+                }
+                i += 1;
+            }
+            if completion.is_some() {
+                return completion;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn framework() -> IncompleteAAFramework<&'static str> {
+        let mut framework = IncompleteAAFramework::new(vec!["a", "b"], vec!["c"]);
+        framework.new_attack(&"c", &"a").unwrap();
+        framework.new_uncertain_attack(&"b", &"a").unwrap();
+        framework.new_uncertain_attack(&"b", &"c").unwrap();
+        framework
+    }
+
+    #[test]
+    fn test_iter_completions() {
+        let completions = framework()
+            .iter_completions()
+            .map(|c| format!("{:?}", c))
+            .collect::<Vec<String>>();
+        assert_eq!(6, completions.len());
+        let mut unique = completions.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(6, unique.len());
+        assert_eq!(
+            2,
+            framework()
+                .iter_completions()
+                .filter(|c| c.argument_set().len() == 2)
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_iter_completions_certain() {
+        let mut framework = IncompleteAAFramework::new(vec!["a", "b"], vec![]);
+        framework.new_attack(&"a", &"b").unwrap();
+        let completions = framework.iter_completions().collect::<Vec<_>>();
+        assert_eq!(1, completions.len());
+        assert_eq!(1, completions[0].n_attacks());
+    }
+
+    #[test]
+    fn test_possible_and_necessary_acceptance() {
+        let framework = framework();
+        for acceptance in [Acceptance::Credulous, Acceptance::Skeptical].iter() {
+            assert!(framework
+                .possibly_accepted(&"a", Semantics::Grounded, *acceptance)
+                .unwrap());
+            assert!(!framework
+                .necessarily_accepted(&"a", Semantics::Grounded, *acceptance)
+                .unwrap());
+            assert!(framework
+                .necessarily_accepted(&"b", Semantics::Grounded, *acceptance)
+                .unwrap());
+            assert!(framework
+                .possibly_accepted(&"c", Semantics::Grounded, *acceptance)
+                .unwrap());
+            assert!(!framework
+                .necessarily_accepted(&"c", Semantics::Grounded, *acceptance)
+                .unwrap());
+        }
+    }
+
+    #[test]
+    fn test_acceptance_unknown_argument() {
+        let framework = framework();
+        assert!(framework
+            .possibly_accepted(&"d", Semantics::Grounded, Acceptance::Credulous)
+            .is_err());
+        assert!(framework
+            .necessarily_accepted(&"d", Semantics::Grounded, Acceptance::Credulous)
+            .is_err());
+    }
+}
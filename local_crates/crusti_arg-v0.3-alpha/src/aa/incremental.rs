@@ -0,0 +1,441 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! A module solving the successive frameworks of a dynamics with a single incremental SAT solver.
+//!
+//! An [`IncrementalSolver`] encodes the arguments and the attacks of the frameworks it is given the first time they appear, and keeps its [`CdclSolver`] (and the clauses it learnt) from a framework to the next one.
+//! Each argument and each attack has an activation literal, assumed to be `true` while it belongs to the current framework and `false` otherwise, so that removing an argument or an attack never requires to encode the framework again.
+//! The clauses depending on the whole set of attackers of an argument are guarded by a selector literal, which is replaced by a new one when a new attacker appears.
+//!
+//! The complete and stable semantics are handled.
+//!
+//! [`CdclSolver`]: ../sat/struct.CdclSolver.html
+//! [`IncrementalSolver`]: struct.IncrementalSolver.html
+
+use crate::aa::aa_framework::AAFramework;
+use crate::aa::arguments::LabelType;
+use crate::aa::sat::{CdclSolver, Cnf};
+use crate::aa::semantics::Semantics;
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+/// The literals attached to an argument.
+#[derive(Default)]
+struct ArgumentLiterals {
+    /// `true` iff the argument belongs to the current framework
+    active: i32,
+    /// `true` iff the argument belongs to the extension
+    inside: i32,
+    /// `true` iff the argument is attacked by the extension
+    outside: i32,
+    /// the literals true iff an active attack comes from an argument of the extension, one per attacker
+    attacked_by_inside: Vec<i32>,
+    /// the literals true iff an active attack comes from an argument not attacked by the extension, one per attacker
+    attacked_by_not_outside: Vec<i32>,
+    /// the literal guarding the clauses depending on the set of attackers
+    selector: i32,
+}
+
+/// A solver answering queries on the successive frameworks of a dynamics, reusing the same SAT solver.
+///
+/// The current framework is set by [`set_framework`]; the arguments and the attacks are identified by their labels, so that the frameworks may be built independently.
+/// Extensions are given by membership vectors, indexed by the identifiers of the arguments of the current framework.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{incremental::IncrementalSolver, AAFramework, ArgumentSet, Semantics};
+/// let mut solver = IncrementalSolver::new(Semantics::Stable).unwrap();
+/// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+/// solver.set_framework(&framework);
+/// assert!(solver.is_skeptically_accepted(&"b").unwrap());
+/// framework.new_attack(&"a", &"b").unwrap();
+/// solver.set_framework(&framework);
+/// assert!(!solver.is_credulously_accepted(&"b").unwrap());
+/// ```
+///
+/// [`set_framework`]: #method.set_framework
+pub struct IncrementalSolver<T>
+where
+    T: LabelType,
+{
+    semantics: Semantics,
+    solver: CdclSolver,
+    argument_indices: HashMap<T, usize>,
+    arguments: Vec<ArgumentLiterals>,
+    attack_indices: HashMap<(T, T), usize>,
+    attacks: Vec<i32>,
+    current_arguments: Vec<usize>,
+    assumptions: Vec<i32>,
+}
+
+impl<T> IncrementalSolver<T>
+where
+    T: LabelType,
+{
+    /// Builds a solver for a semantics, with an empty current framework.
+    ///
+    /// An error is returned if the semantics is not handled.
+    ///
+    /// # Arguments
+    ///
+    /// * `semantics` - the semantics
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{incremental::IncrementalSolver, Semantics};
+    /// assert!(IncrementalSolver::<String>::new(Semantics::Complete).is_ok());
+    /// assert!(IncrementalSolver::<String>::new(Semantics::Preferred).is_err());
+    /// ```
+    pub fn new(semantics: Semantics) -> Result<Self> {
+        match semantics {
+            Semantics::Complete | Semantics::Stable => Ok(IncrementalSolver {
+                semantics,
+                solver: CdclSolver::new(&Cnf::default()),
+                argument_indices: HashMap::new(),
+                arguments: vec![],
+                attack_indices: HashMap::new(),
+                attacks: vec![],
+                current_arguments: vec![],
+                assumptions: vec![],
+            }),
+            _ => Err(anyhow!(
+                "the {} semantics is not handled by the incremental solver",
+                semantics
+            )),
+        }
+    }
+
+    /// Sets the framework the next queries are made on.
+    ///
+    /// The arguments and the attacks which were never encoded are added to the formula, and the activation literals of the current ones are assumed to be `true` (the other ones `false`).
+    ///
+    /// # Arguments
+    ///
+    /// * `framework` - the framework
+    pub fn set_framework(&mut self, framework: &AAFramework<T>) {
+        self.current_arguments = framework
+            .argument_set()
+            .iter()
+            .map(|a| match self.argument_indices.get(a.label()) {
+                Some(i) => *i,
+                None => {
+                    let literals = self.encode_argument();
+                    self.arguments.push(literals);
+                    self.argument_indices
+                        .insert(a.label().clone(), self.arguments.len() - 1);
+                    self.arguments.len() - 1
+                }
+            })
+            .collect();
+        let mut active_attacks = vec![false; self.attacks.len()];
+        for attack in framework.iter_attacks() {
+            let (from, to) = (attack.attacker().id(), attack.attacked().id());
+            let key = (
+                attack.attacker().label().clone(),
+                attack.attacked().label().clone(),
+            );
+            let index = match self.attack_indices.get(&key) {
+                Some(i) => *i,
+                None => {
+                    let literal = self
+                        .encode_attack(self.current_arguments[from], self.current_arguments[to]);
+                    self.attacks.push(literal);
+                    active_attacks.push(false);
+                    self.attack_indices.insert(key, self.attacks.len() - 1);
+                    self.attacks.len() - 1
+                }
+            };
+            active_attacks[index] = true;
+        }
+        let mut active_arguments = vec![false; self.arguments.len()];
+        self.current_arguments
+            .iter()
+            .for_each(|i| active_arguments[*i] = true);
+        let signed = |literal: i32, active: bool| if active { literal } else { -literal };
+        self.assumptions = self
+            .arguments
+            .iter()
+            .zip(active_arguments)
+            .flat_map(|(a, active)| [signed(a.active, active), a.selector])
+            .chain(
+                self.attacks
+                    .iter()
+                    .zip(active_attacks)
+                    .map(|(l, active)| signed(*l, active)),
+            )
+            .collect();
+    }
+
+    fn encode_argument(&mut self) -> ArgumentLiterals {
+        let active = self.solver.new_var();
+        let inside = self.solver.new_var();
+        let outside = self.solver.new_var();
+        self.solver.add_clause(&[-inside, active]);
+        self.solver.add_clause(&[-outside, active]);
+        self.solver.add_clause(&[-inside, -outside]);
+        if self.semantics == Semantics::Stable {
+            self.solver.add_clause(&[-active, inside, outside]);
+        }
+        let mut literals = ArgumentLiterals {
+            active,
+            inside,
+            outside,
+            attacked_by_inside: vec![],
+            attacked_by_not_outside: vec![],
+            selector: 0,
+        };
+        self.refresh_selector(&mut literals);
+        literals
+    }
+
+    fn encode_attack(&mut self, from: usize, to: usize) -> i32 {
+        let attack = self.solver.new_var();
+        let (from_inside, from_outside) =
+            (self.arguments[from].inside, self.arguments[from].outside);
+        let mut to_literals = std::mem::take(&mut self.arguments[to]);
+        // the attack comes from an argument of the extension
+        let by_inside = self.solver.new_var();
+        self.solver.add_clause(&[-by_inside, attack]);
+        self.solver.add_clause(&[-by_inside, from_inside]);
+        self.solver.add_clause(&[-attack, -from_inside, by_inside]);
+        self.solver.add_clause(&[-by_inside, to_literals.outside]);
+        to_literals.attacked_by_inside.push(by_inside);
+        if self.semantics == Semantics::Complete {
+            // the arguments of the extension are defended against the attack
+            self.solver
+                .add_clause(&[-to_literals.inside, -attack, from_outside]);
+            let by_not_outside = self.solver.new_var();
+            self.solver.add_clause(&[-by_not_outside, attack]);
+            self.solver.add_clause(&[-by_not_outside, -from_outside]);
+            self.solver
+                .add_clause(&[-attack, from_outside, by_not_outside]);
+            to_literals.attacked_by_not_outside.push(by_not_outside);
+        }
+        self.refresh_selector(&mut to_literals);
+        self.arguments[to] = to_literals;
+        attack
+    }
+
+    /// Replaces the clauses depending on the set of attackers of an argument by new ones, guarded by a new selector.
+    fn refresh_selector(&mut self, literals: &mut ArgumentLiterals) {
+        if literals.selector != 0 {
+            self.solver.add_clause(&[-literals.selector]);
+        }
+        let selector = self.solver.new_var();
+        // an argument attacked by the extension is attacked by one of its arguments
+        let mut clause = vec![-selector, -literals.outside];
+        clause.extend_from_slice(&literals.attacked_by_inside);
+        self.solver.add_clause(&clause);
+        if self.semantics == Semantics::Complete {
+            // an argument defended by the extension belongs to it
+            let mut clause = vec![-selector, -literals.active, literals.inside];
+            clause.extend_from_slice(&literals.attacked_by_not_outside);
+            self.solver.add_clause(&clause);
+        }
+        literals.selector = selector;
+    }
+
+    fn to_extension(&self, model: &[bool]) -> Vec<bool> {
+        self.current_arguments
+            .iter()
+            .map(|i| model[self.arguments[*i].inside as usize - 1])
+            .collect()
+    }
+
+    fn inside_literal(&self, arg: &T) -> Result<i32> {
+        self.argument_indices
+            .get(arg)
+            .filter(|i| self.current_arguments.contains(i))
+            .map(|i| self.arguments[*i].inside)
+            .ok_or_else(|| anyhow!(r#"no such argument: "{}""#, arg))
+    }
+
+    /// Computes an extension of the current framework, if there is one.
+    pub fn one_extension(&mut self) -> Option<Vec<bool>> {
+        self.solver
+            .solve_with_assumptions(&self.assumptions)
+            .map(|m| self.to_extension(&m))
+    }
+
+    /// Computes all the extensions of the current framework.
+    ///
+    /// The extensions already found are blocked by clauses guarded by a literal dedicated to this enumeration, which is falsified at its end.
+    pub fn all_extensions(&mut self) -> Vec<Vec<bool>> {
+        let enumeration = self.solver.new_var();
+        let mut assumptions = self.assumptions.clone();
+        assumptions.push(enumeration);
+        let mut extensions = vec![];
+        while let Some(model) = self.solver.solve_with_assumptions(&assumptions) {
+            let mut blocking = vec![-enumeration];
+            blocking.extend(self.current_arguments.iter().map(|i| {
+                let inside = self.arguments[*i].inside;
+                if model[inside as usize - 1] {
+                    -inside
+                } else {
+                    inside
+                }
+            }));
+            self.solver.add_clause(&blocking);
+            extensions.push(self.to_extension(&model));
+        }
+        self.solver.add_clause(&[-enumeration]);
+        extensions
+    }
+
+    /// Returns `true` iff an argument of the current framework belongs to at least one extension.
+    ///
+    /// An error is returned if the argument is not in the current framework.
+    ///
+    /// # Arguments
+    ///
+    /// * `arg` - the label of the argument
+    pub fn is_credulously_accepted(&mut self, arg: &T) -> Result<bool> {
+        let inside = self.inside_literal(arg)?;
+        let mut assumptions = self.assumptions.clone();
+        assumptions.push(inside);
+        Ok(self.solver.solve_with_assumptions(&assumptions).is_some())
+    }
+
+    /// Returns `true` iff an argument of the current framework belongs to all the extensions.
+    ///
+    /// An error is returned if the argument is not in the current framework.
+    ///
+    /// # Arguments
+    ///
+    /// * `arg` - the label of the argument
+    pub fn is_skeptically_accepted(&mut self, arg: &T) -> Result<bool> {
+        let inside = self.inside_literal(arg)?;
+        let mut assumptions = self.assumptions.clone();
+        assumptions.push(-inside);
+        Ok(self.solver.solve_with_assumptions(&assumptions).is_none())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aa::arguments::ArgumentSet;
+    use crate::aa::solvers::{NaiveSolver, SemanticsSolver};
+
+    fn sorted_extensions(
+        framework: &AAFramework<String>,
+        extensions: &[Vec<bool>],
+    ) -> Vec<Vec<String>> {
+        let mut labels = extensions
+            .iter()
+            .map(|e| {
+                let mut labels = framework
+                    .argument_set()
+                    .iter()
+                    .filter(|a| e[a.id()])
+                    .map(|a| a.label().clone())
+                    .collect::<Vec<String>>();
+                labels.sort();
+                labels
+            })
+            .collect::<Vec<Vec<String>>>();
+        labels.sort();
+        labels
+    }
+
+    fn check_against_naive(
+        solver: &mut IncrementalSolver<String>,
+        framework: &AAFramework<String>,
+        semantics: Semantics,
+    ) {
+        solver.set_framework(framework);
+        let naive = NaiveSolver::new(framework).unwrap();
+        assert_eq!(
+            sorted_extensions(framework, &naive.all_extensions(semantics).unwrap()),
+            sorted_extensions(framework, &solver.all_extensions()),
+            "{:?}",
+            framework
+                .iter_attacks()
+                .map(|a| (a.attacker().label(), a.attacked().label()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            naive.one_extension(semantics).unwrap().is_some(),
+            solver.one_extension().is_some()
+        );
+        for a in framework.argument_set().iter() {
+            assert_eq!(
+                naive.is_credulously_accepted(a.label(), semantics).unwrap(),
+                solver.is_credulously_accepted(a.label()).unwrap()
+            );
+            assert_eq!(
+                naive.is_skeptically_accepted(a.label(), semantics).unwrap(),
+                solver.is_skeptically_accepted(a.label()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_dynamics_against_naive_solver() {
+        let labels = (0..6).map(|i| format!("a{}", i)).collect::<Vec<String>>();
+        let mut seed = 7_u64;
+        let mut next = move |n: u64| {
+            seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+            (seed >> 33) % n
+        };
+        for semantics in [Semantics::Complete, Semantics::Stable] {
+            let mut solver = IncrementalSolver::new(semantics).unwrap();
+            let mut attacks = vec![];
+            let mut n_arguments = 3;
+            for _ in 0..30 {
+                match next(4) {
+                    0 if n_arguments < labels.len() => n_arguments += 1,
+                    1 if n_arguments > 1 => {
+                        n_arguments -= 1;
+                        attacks.retain(|(i, j)| *i < n_arguments && *j < n_arguments);
+                    }
+                    2 if !attacks.is_empty() => {
+                        attacks.remove(next(attacks.len() as u64) as usize);
+                    }
+                    _ => {
+                        let attack = (
+                            next(n_arguments as u64) as usize,
+                            next(n_arguments as u64) as usize,
+                        );
+                        if !attacks.contains(&attack) {
+                            attacks.push(attack);
+                        }
+                    }
+                }
+                let mut framework =
+                    AAFramework::new(ArgumentSet::new(labels[..n_arguments].to_vec()));
+                attacks
+                    .iter()
+                    .for_each(|(i, j)| framework.new_attack(&labels[*i], &labels[*j]).unwrap());
+                check_against_naive(&mut solver, &framework, semantics);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unknown_argument() {
+        let mut solver = IncrementalSolver::new(Semantics::Complete).unwrap();
+        solver.set_framework(&AAFramework::new(ArgumentSet::new(vec!["a", "b"])));
+        solver.set_framework(&AAFramework::new(ArgumentSet::new(vec!["a"])));
+        assert!(solver.is_credulously_accepted(&"a").unwrap());
+        assert!(solver.is_credulously_accepted(&"b").is_err());
+    }
+}
@@ -0,0 +1,189 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::{ArgumentSet, LabelType};
+use std::{collections::HashSet, sync::Arc};
+
+/// The type of the labels shared through a [`LabelInterner`].
+///
+/// Cloning an interned label only increments a reference counter, so that each label is stored once
+/// even if it is referenced by a large number of attacks and extensions.
+///
+/// [`LabelInterner`]: struct.LabelInterner.html
+pub type InternedLabel = Arc<str>;
+
+/// A pool of labels, giving the same [`InternedLabel`] for equal labels.
+///
+/// Interning is useful to handle large sets of extensions, in which each label would otherwise be allocated once per extension it belongs to.
+/// Interned labels may be looked up in argument sets labelled by `String` since both types borrow as `str`.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::LabelInterner;
+/// # use std::sync::Arc;
+/// let mut interner = LabelInterner::default();
+/// let a = interner.intern("a");
+/// let b = interner.intern("a");
+/// assert!(Arc::ptr_eq(&a, &b));
+/// assert_eq!(1, interner.len());
+/// ```
+///
+/// [`InternedLabel`]: type.InternedLabel.html
+#[derive(Debug, Default)]
+pub struct LabelInterner {
+    labels: HashSet<InternedLabel>,
+}
+
+impl LabelInterner {
+    /// Builds an interner containing the labels of an argument set, e.g. the arguments of a framework.
+    ///
+    /// # Arguments
+    /// * `arguments` - the argument set
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, LabelInterner};
+    /// let interner = LabelInterner::with_labels_of(&ArgumentSet::new(vec!["a", "b"]));
+    /// assert_eq!(2, interner.len());
+    /// ```
+    pub fn with_labels_of<T>(arguments: &ArgumentSet<T>) -> Self
+    where
+        T: LabelType,
+    {
+        let mut interner = LabelInterner {
+            labels: HashSet::with_capacity(arguments.len()),
+        };
+        arguments.iter().for_each(|a| {
+            interner.intern(&a.label().to_string());
+        });
+        interner
+    }
+
+    /// Returns the interned label equal to the provided one, adding it to the pool if needed.
+    ///
+    /// # Arguments
+    /// * `label` - the label
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::LabelInterner;
+    /// let mut interner = LabelInterner::default();
+    /// assert_eq!("a", &*interner.intern("a"));
+    /// ```
+    pub fn intern(&mut self, label: &str) -> InternedLabel {
+        if let Some(l) = self.labels.get(label) {
+            return Arc::clone(l);
+        }
+        let l: InternedLabel = Arc::from(label);
+        self.labels.insert(Arc::clone(&l));
+        l
+    }
+
+    /// Builds an argument set whose labels are the interned versions of the labels of another set.
+    ///
+    /// The ids of the arguments are preserved.
+    ///
+    /// # Arguments
+    /// * `arguments` - the argument set
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, LabelInterner};
+    /// let mut interner = LabelInterner::default();
+    /// let interned = interner.intern_set(&ArgumentSet::new(vec!["a", "b"]));
+    /// assert_eq!(1, interned.get_argument_index("b").unwrap());
+    /// ```
+    pub fn intern_set<T>(&mut self, arguments: &ArgumentSet<T>) -> ArgumentSet<InternedLabel>
+    where
+        T: LabelType,
+    {
+        ArgumentSet::new(
+            arguments
+                .iter()
+                .map(|a| self.intern(&a.label().to_string()))
+                .collect(),
+        )
+    }
+
+    /// Returns the number of distinct labels in the pool.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::LabelInterner;
+    /// let mut interner = LabelInterner::default();
+    /// interner.intern("a");
+    /// interner.intern("a");
+    /// assert_eq!(1, interner.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Returns `true` iff the pool contains no label.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::LabelInterner;
+    /// assert!(LabelInterner::default().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_shares_labels() {
+        let mut interner = LabelInterner::with_labels_of(&ArgumentSet::new(vec![
+            "a".to_string(),
+            "b".to_string(),
+        ]));
+        let first = interner.intern_set(&ArgumentSet::new(vec!["b", "a"]));
+        let second = interner.intern_set(&ArgumentSet::new(vec!["a"]));
+        assert_eq!(2, interner.len());
+        assert!(Arc::ptr_eq(
+            first.get_argument_by_id(1).label(),
+            second.get_argument_by_id(0).label()
+        ));
+        assert_eq!(5, Arc::strong_count(second.get_argument_by_id(0).label()));
+    }
+
+    #[test]
+    fn test_intern_set_keeps_ids() {
+        let mut interner = LabelInterner::default();
+        let interned = interner.intern_set(&ArgumentSet::new(vec!["c", "a", "b"]));
+        assert_eq!(
+            vec!["c", "a", "b"],
+            interned
+                .iter()
+                .map(|a| a.label().to_string())
+                .collect::<Vec<String>>()
+        );
+        assert_eq!(0, interned.get_argument_index("c").unwrap());
+    }
+}
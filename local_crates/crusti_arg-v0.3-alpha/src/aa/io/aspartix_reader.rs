@@ -17,23 +17,39 @@
 // Contributors:
 //   *   CRIL - initial API and implementation
 
-use crate::{utils::warning_result::WarningResult, AAFramework, ArgumentSet};
-use anyhow::{anyhow, Context, Result};
+use crate::{
+    aa::io::{
+        labels::{self, LABEL_AND_SPACE_PATTERN, QUOTED_LABEL_PATTERN},
+        parse_error::{self, first_token_offset, ParseError},
+    },
+    utils::warning_result::WarningResult,
+    AAFramework, ArgumentSet,
+};
+use anyhow::{Context, Result};
 use lazy_static::lazy_static;
 use regex::{Captures, Regex};
 use std::{
     cell::RefCell,
+    collections::HashSet,
     io::{BufRead, BufReader, Read},
     rc::Rc,
 };
 
-const ARG_AND_SPACE_PATTERN: &str = r"\s*[_[:alpha:]][_[:alpha:]\d]*\s*";
+pub(crate) const ARG_AND_SPACE_PATTERN: &str = LABEL_AND_SPACE_PATTERN;
 
 lazy_static! { // kcov-ignore
-    static ref ARG_LINE_PATTERN: Regex = Regex::new(r"^\s*arg\([^)]+\).\s*$").unwrap();
+    static ref ARG_LINE_PATTERN: Regex = Regex::new(&format!(
+        r#"^\s*arg\((?:[^)"]|{})+\).\s*$"#,
+        QUOTED_LABEL_PATTERN
+    ))
+    .unwrap();
     static ref ARG_LINE_ARG_NAME_PATTERN: Regex =
         Regex::new(&format!(r"^\s*arg\(({})\).\s*$", ARG_AND_SPACE_PATTERN)).unwrap();
-    static ref ATT_LINE_PATTERN: Regex = Regex::new(r"^\s*att\([^,]+,[^)]+\).\s*$").unwrap();
+    static ref ATT_LINE_PATTERN: Regex = Regex::new(&format!(
+        r#"^\s*att\((?:[^,"]|{})+,(?:[^)"]|{})+\).\s*$"#,
+        QUOTED_LABEL_PATTERN, QUOTED_LABEL_PATTERN
+    ))
+    .unwrap();
     static ref ATT_LINE_ARG_NAMES_PATTERN: Regex = Regex::new(&format!(
         r"^\s*att\(({}),({})\).\s*$",
         ARG_AND_SPACE_PATTERN, ARG_AND_SPACE_PATTERN,
@@ -41,22 +57,26 @@ lazy_static! { // kcov-ignore
     .unwrap();
 }
 
+/// The type of the callback functions called when warnings are raised while parsing an AF.
+pub(crate) type WarningHandler<'a> = Rc<RefCell<&'a mut dyn FnMut(usize, String)>>;
+
 const DEFAULT_ARG_LABELS_CAP: usize = 1 << 10;
 
-fn captured_arg(c: &Captures, i: usize) -> WarningResult<String, String> {
+pub(crate) fn captured_arg(c: &Captures, i: usize) -> WarningResult<String, String> {
     let str_arg = c.get(i).unwrap().as_str();
-    let trimmed_str_arg = str_arg.trim().to_string();
+    let trimmed_str_arg = str_arg.trim();
+    let label = labels::parse_label(trimmed_str_arg).unwrap();
     if trimmed_str_arg.len() == str_arg.len() {
-        WarningResult::Ok(trimmed_str_arg)
+        WarningResult::Ok(label)
     } else {
         WarningResult::Warned(
-            trimmed_str_arg,
+            label,
             vec!["argument names beginning or ending by spaces may be ambiguous".to_string()],
         ) // kcov-ignore
     }
 }
 
-fn try_read_arg_line<T>(l: T) -> Result<Option<WarningResult<String, String>>>
+pub(crate) fn try_read_arg_line<T>(l: T) -> Result<Option<WarningResult<String, String>>>
 where
     T: AsRef<str>,
 {
@@ -64,14 +84,14 @@ where
         let captures = ARG_LINE_ARG_NAME_PATTERN.captures(l.as_ref());
         match captures {
             Some(c) => Ok(Some(captured_arg(&c, 1))),
-            None => Err(anyhow!("invalid argument name in {}", l.as_ref().trim())),
+            None => Err(label_error(l.as_ref(), 1)),
         }
     } else {
         Ok(None)
     }
 }
 
-fn try_read_att_line<T>(l: T) -> Result<Option<WarningResult<(String, String), String>>>
+pub(crate) fn try_read_att_line<T>(l: T) -> Result<Option<WarningResult<(String, String), String>>>
 where
     T: AsRef<str>,
 {
@@ -79,13 +99,40 @@ where
         let captures = ATT_LINE_ARG_NAMES_PATTERN.captures(l.as_ref());
         match captures {
             Some(c) => Ok(Some(captured_arg(&c, 1).zip(captured_arg(&c, 2)))),
-            None => Err(anyhow!("invalid argument names in {}", l.as_ref().trim())),
+            None => Err(label_error(l.as_ref(), 2)),
         }
     } else {
         Ok(None)
     }
 }
 
+/// Returns the byte offsets of the labels of a declaration line expected to contain `n_labels` labels, or the error locating its first malformed part.
+fn locate_labels(l: &str, n_labels: usize) -> std::result::Result<Vec<usize>, ParseError> {
+    let mut offset = l.find('(').map(|i| i + 1).unwrap_or(0);
+    let mut offsets = Vec::with_capacity(n_labels);
+    for i in 0..n_labels {
+        offsets.push(offset + first_token_offset(&l[offset..]));
+        labels::parse_label_at(l, &mut offset)?;
+        let separator = if i + 1 < n_labels { ',' } else { ')' };
+        if !l[offset..].starts_with(separator) {
+            return Err(ParseError::new(
+                format!(r#"expected "{}""#, separator),
+                l,
+                offset,
+            ));
+        }
+        offset += 1;
+    }
+    Ok(offsets)
+}
+
+fn label_error(l: &str, n_labels: usize) -> anyhow::Error {
+    match locate_labels(l, n_labels) {
+        Err(e) => e.into(),
+        Ok(offsets) => ParseError::new("expected an argument label", l, offsets[0]).into(),
+    }
+}
+
 /// A reader for the Aspartix format.
 ///
 /// This object is used to read an [`AAFramework`] encoded using the Aspartix input format, as defined on [the Aspartix website](https://www.dbai.tuwien.ac.at/research/argumentation/aspartix/dung.html).
@@ -106,7 +153,7 @@ where
 /// [`LabelType`]: trait.LabelType.html
 #[derive(Default)]
 pub struct AspartixReader<'a> {
-    warning_handlers: Vec<Rc<RefCell<&'a mut dyn FnMut(usize, String)>>>,
+    warning_handlers: Vec<WarningHandler<'a>>,
 }
 
 impl<'a> AspartixReader<'a> {
@@ -133,10 +180,17 @@ impl<'a> AspartixReader<'a> {
         let mut arg_labels = Some(Vec::with_capacity(DEFAULT_ARG_LABELS_CAP));
         let mut af = None;
         let br = BufReader::new(reader);
-        let mut line_index_plus_one = 0;
-        for line in br.lines() {
-            line_index_plus_one += 1;
-            let context = || format!("while reading line {}", line_index_plus_one - 1);
+        let mut declared_labels = HashSet::new();
+        let mut declared_attacks = HashSet::new();
+        for (line_index, line) in br.lines().enumerate() {
+            let line_index_plus_one = line_index + 1;
+            let context = || format!("while reading line {}", line_index_plus_one);
+            let line_error = |message: &str, l: &str, offset: usize| -> anyhow::Error {
+                ParseError::new(message, l, offset)
+                    .with_line(line_index_plus_one)
+                    .into()
+            };
+            let locate = |e| parse_error::locate(e, line_index_plus_one);
             let warning_consumer = |warnings: Vec<String>| {
                 for w in warnings.iter() {
                     self.warning_handlers
@@ -148,39 +202,91 @@ impl<'a> AspartixReader<'a> {
             if l.trim().is_empty() {
                 continue;
             }
-            if let Some(a) = try_read_arg_line(l).with_context(context)? {
+            if let Some(a) = try_read_arg_line(l).map_err(locate)? {
                 if af.is_some() {
-                    return Err(anyhow!("found an argument declaration after an attack"))
-                        .with_context(context);
+                    return Err(line_error(
+                        "expected an attack, as arguments must be declared before the attacks",
+                        l,
+                        first_token_offset(l),
+                    ));
+                }
+                let a = a.consume_warnings(warning_consumer);
+                if declared_labels.insert(a.clone()) {
+                    arg_labels.as_mut().unwrap().push(a);
+                } else {
+                    warning_consumer(vec![format!(
+                        "argument {} is declared more than once; ignoring this declaration",
+                        labels::format_label(&a)
+                    )]);
                 }
-                arg_labels
-                    .as_mut()
-                    .unwrap()
-                    .push(a.consume_warnings(warning_consumer));
                 continue;
             }
-            if let Some(result) = try_read_att_line(l).with_context(context)? {
+            if let Some(result) = try_read_att_line(l).map_err(locate)? {
                 let (a, b) = result.consume_warnings(warning_consumer);
                 if af.is_none() {
                     af = Some(AAFramework::new(ArgumentSet::new(
                         arg_labels.take().unwrap(),
                     )));
                 }
-                af.as_mut()
-                    .unwrap()
-                    .new_attack(&a, &b)
-                    .with_context(context)?;
+                let framework = af.as_mut().unwrap();
+                let (from, to) = match (
+                    framework.argument_set().get_argument_index(&a),
+                    framework.argument_set().get_argument_index(&b),
+                ) {
+                    (Ok(from), Ok(to)) => (from, to),
+                    (from, _) => {
+                        let offsets = locate_labels(l, 2).unwrap_or_default();
+                        let i = if from.is_err() { 0 } else { 1 };
+                        return Err(line_error(
+                            "expected a declared argument",
+                            l,
+                            offsets.get(i).copied().unwrap_or(0),
+                        ));
+                    }
+                };
+                if !declared_attacks.insert((from, to)) {
+                    warning_consumer(vec![format!(
+                        "attack from {} to {} is declared more than once; ignoring this declaration",
+                        labels::format_label(&a),
+                        labels::format_label(&b)
+                    )]);
+                    continue;
+                }
+                framework.new_attack_by_ids(from, to)?;
                 continue;
             }
-            return Err(anyhow!("syntax error in line \"{}\"", l)).with_context(context);
+            return Err(line_error(
+                "expected an argument or an attack declaration",
+                l,
+                first_token_offset(l),
+            ));
         }
         match af {
             Some(a) => Ok(a),
-            None => Ok(AAFramework::new(ArgumentSet::new(vec![]))),
+            None => Ok(AAFramework::new(ArgumentSet::new(arg_labels.unwrap()))),
         }
     }
 
     /// Adds a callback function to call when warnings are raised while parsing an AF.
+    ///
+    /// Warnings are raised for recoverable issues: argument labels beginning or ending by spaces, and arguments or attacks declared more than once (in which case the duplicates are ignored).
+    /// The callback is given the index of the line (starting at 0) and the warning message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::AspartixReader;
+    /// let mut warnings = vec![];
+    /// let mut handler = |line, warning| warnings.push((line, warning));
+    /// let mut reader = AspartixReader::default();
+    /// reader.add_warning_handler(&mut handler);
+    /// let framework = reader.read(&mut "arg(a).\narg(a).\n".as_bytes()).unwrap();
+    /// assert_eq!(1, framework.argument_set().len());
+    /// assert_eq!(
+    ///     vec![(1, "argument a is declared more than once; ignoring this declaration".to_string())],
+    ///     warnings
+    /// );
+    /// ```
     pub fn add_warning_handler(&mut self, h: &'a mut dyn FnMut(usize, String)) {
         self.warning_handlers.push(Rc::new(RefCell::new(h)));
     }
@@ -345,6 +451,34 @@ mod tests {
         assert_eq!(vec!["(a,b)".to_string()], attacks);
     }
 
+    #[test]
+    fn test_read_quoted_labels() {
+        let instance = "arg(\"débat, tour 1\").\narg( \"a(\\\"b\\\")\" ).\narg(\"c\").\natt(\"débat, tour 1\",\"a(\\\"b\\\")\").\natt(c,\"c\").\n";
+        let af = AspartixReader::default()
+            .read(&mut instance.as_bytes())
+            .unwrap();
+        assert_eq!(vec!["débat, tour 1", "a(\"b\")", "c"], str_args(&af));
+        assert_eq!(vec!["(débat, tour 1,a(\"b\"))", "(c,c)"], str_attacks(&af));
+        let mut written = crate::utils::writable_string::WritableString::default();
+        crate::AspartixWriter::default()
+            .write(&af, &mut written)
+            .unwrap();
+        let reread = AspartixReader::default()
+            .read(&mut written.to_string().as_bytes())
+            .unwrap();
+        assert_eq!(str_args(&af), str_args(&reread));
+        assert_eq!(str_attacks(&af), str_attacks(&reread));
+    }
+
+    #[test]
+    fn test_read_wrong_quoted_labels() {
+        ["arg(\"a).", "arg(\"a\\b\").", "arg(\"a\"b)."]
+            .iter()
+            .for_each(|l| {
+                assert!(AspartixReader::default().read(&mut l.as_bytes()).is_err());
+            });
+    }
+
     #[test]
     fn test_read_empty() {
         let instance = "\n";
@@ -357,6 +491,18 @@ mod tests {
         assert_eq!(vec![] as Vec<String>, attacks);
     }
 
+    #[test]
+    fn test_read_no_attacks() {
+        let instance = "arg(a).\narg(b).\n";
+        let af = AspartixReader::default()
+            .read(&mut instance.as_bytes())
+            .unwrap();
+        let args = str_args(&af);
+        assert_eq!(vec!["a".to_string(), "b".to_string()], args);
+        let attacks = str_attacks(&af);
+        assert_eq!(vec![] as Vec<String>, attacks);
+    }
+
     #[test]
     fn test_read_arg_after_att() {
         let instance = "arg(a).\narg(b).\natt(a,b).\narg(c).\n";
@@ -397,4 +543,58 @@ mod tests {
             )]
         );
     }
+
+    #[test]
+    fn test_read_error_positions() {
+        let error = |instance: &str| {
+            let error = AspartixReader::default()
+                .read(&mut instance.as_bytes())
+                .unwrap_err();
+            let error = error.downcast_ref::<ParseError>().unwrap();
+            (error.line(), error.column(), error.token().to_string())
+        };
+        assert_eq!(
+            (Some(3), 8, "c".to_string()),
+            error("arg(a).\narg(b).\natt(a, c).\n")
+        );
+        assert_eq!(
+            (Some(2), 1, "argument".to_string()),
+            error("arg(a).\nargument(b).\n")
+        );
+        assert_eq!((Some(1), 7, "b".to_string()), error("arg(a b).\n"));
+        assert_eq!((Some(2), 7, "1".to_string()), error("arg(a).\natt(a,1).\n"));
+        assert_eq!(
+            (Some(3), 1, "arg".to_string()),
+            error("arg(a).\natt(a,a).\narg(b).\n")
+        );
+    }
+
+    #[test]
+    fn test_read_warn_duplicates() {
+        let instance = "arg(a).\narg(b).\narg(a).\natt(a,b).\natt(b,a).\natt(a,b).\n";
+        let mut warnings = vec![];
+        let mut closure = |i, w| warnings.push((i, w));
+        let mut reader = AspartixReader::default();
+        reader.add_warning_handler(&mut closure);
+        let af = reader.read(&mut instance.as_bytes()).unwrap();
+        assert_eq!(vec!["a".to_string(), "b".to_string()], str_args(&af));
+        assert_eq!(
+            vec!["(a,b)".to_string(), "(b,a)".to_string()],
+            str_attacks(&af)
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                (
+                    2,
+                    "argument a is declared more than once; ignoring this declaration".to_string()
+                ),
+                (
+                    5,
+                    "attack from a to b is declared more than once; ignoring this declaration"
+                        .to_string()
+                )
+            ]
+        );
+    }
 }
@@ -19,12 +19,17 @@
 
 use crate::aa::aa_framework::AAFramework;
 use crate::aa::arguments::LabelType;
+use crate::aa::io::labels;
 use anyhow::Result;
 use std::io::Write;
 
 /// A writer for the Aspartix format.
 ///
 /// This object is used to write an [`AAFramework`] using the Aspartix input format, as defined on [the Aspartix website](https://www.dbai.tuwien.ac.at/research/argumentation/aspartix/dung.html).
+/// Labels which are not made only of ASCII letters, digits and underscores are quoted (see the [`labels`] module).
+///
+/// The arguments are written in the order of their ids, and the attacks in the order they were added to the framework (see [`AAFramework::iter_attacks`]),
+/// so that writing a framework always gives the same output.
 ///
 /// # Example
 ///
@@ -44,6 +49,8 @@ use std::io::Write;
 /// ```
 ///
 /// [`AAFramework`]: struct.AAFramework.html
+/// [`labels`]: labels/index.html
+/// [`AAFramework::iter_attacks`]: struct.AAFramework.html#method.iter_attacks
 #[derive(Default)]
 pub struct AspartixWriter {}
 
@@ -80,14 +87,14 @@ impl AspartixWriter {
     ) -> Result<()> {
         let args = framework.argument_set();
         for arg in args.iter() {
-            writeln!(writer, "arg({}).", arg.to_string())?;
+            writeln!(writer, "arg({}).", labels::format_label(arg))?;
         }
         for attack in framework.iter_attacks() {
             writeln!(
                 writer,
                 "att({},{}).",
-                attack.attacker().to_string(),
-                attack.attacked().to_string(),
+                labels::format_label(attack.attacker()),
+                labels::format_label(attack.attacked()),
             )?;
         }
         writer.flush()?;
@@ -116,4 +123,19 @@ mod tests {
             result.to_string()
         )
     }
+
+    #[test]
+    fn test_write_quoted_labels() {
+        let arg_names = vec!["débat 1".to_string(), "a \"b\"".to_string()];
+        let args = ArgumentSet::new(arg_names.clone());
+        let mut framework = AAFramework::new(args);
+        framework.new_attack(&arg_names[0], &arg_names[1]).unwrap();
+        let mut result = WritableString::default();
+        let writer = AspartixWriter::default();
+        writer.write(&framework, &mut result).unwrap();
+        assert_eq!(
+            "arg(\"débat 1\").\narg(\"a \\\"b\\\"\").\natt(\"débat 1\",\"a \\\"b\\\"\").\n",
+            result.to_string()
+        )
+    }
 }
@@ -0,0 +1,186 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::io::aspartix_reader::{
+    captured_arg, try_read_arg_line, try_read_att_line, WarningHandler, ARG_AND_SPACE_PATTERN,
+};
+use crate::{ArgumentSet, BAFramework};
+use anyhow::{anyhow, Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::{
+    cell::RefCell,
+    io::{BufRead, BufReader, Read},
+    rc::Rc,
+};
+
+lazy_static! {
+    static ref SUP_LINE_PATTERN: Regex = Regex::new(r"^\s*sup\([^,]+,[^)]+\).\s*$").unwrap();
+    static ref SUP_LINE_ARG_NAMES_PATTERN: Regex = Regex::new(&format!(
+        r"^\s*sup\(({}),({})\).\s*$",
+        ARG_AND_SPACE_PATTERN, ARG_AND_SPACE_PATTERN,
+    ))
+    .unwrap();
+}
+
+/// A reader for the bipolar Aspartix dialect.
+///
+/// This dialect extends the Aspartix format by support declarations `sup(a,b).`, meaning `a` supports `b`.
+/// As for attacks, supports must be declared after the arguments; attacks and supports may be interleaved.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{BAFramework, BipolarAspartixReader};
+/// fn read_baf_from_str(s: &str) -> BAFramework<String> {
+///     let reader = BipolarAspartixReader::default();
+///     reader.read(&mut s.as_bytes()).expect("invalid bipolar Aspartix AF")
+/// }
+/// let baf = read_baf_from_str("arg(a).\narg(b).\natt(a,b).\nsup(b,a).");
+/// assert_eq!(1, baf.n_supports());
+/// ```
+#[derive(Default)]
+pub struct BipolarAspartixReader<'a> {
+    warning_handlers: Vec<WarningHandler<'a>>,
+}
+
+impl<'a> BipolarAspartixReader<'a> {
+    /// Reads a [`BAFramework`] encoded using the bipolar Aspartix dialect.
+    ///
+    /// In case warnings are raised, the callback functions registered by [`add_warning_handler`] are triggered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{BAFramework, BipolarAspartixReader};
+    /// fn read_baf_from_str(s: &str) -> BAFramework<String> {
+    ///     let reader = BipolarAspartixReader::default();
+    ///     reader.read(&mut s.as_bytes()).expect("invalid bipolar Aspartix AF")
+    /// }
+    /// # read_baf_from_str("arg(a).\nsup(a,a).");
+    /// ```
+    ///
+    /// [`BAFramework`]: struct.BAFramework.html
+    /// [`add_warning_handler`]: struct.BipolarAspartixReader.html#method.add_warning_handler
+    pub fn read(&self, reader: &mut dyn Read) -> Result<BAFramework<String>> {
+        let mut arg_labels = Some(vec![]);
+        let mut baf: Option<BAFramework<String>> = None;
+        for (line_index, line) in BufReader::new(reader).lines().enumerate() {
+            let context = || format!("while reading line {}", line_index);
+            let warning_consumer = |warnings: Vec<String>| {
+                for w in warnings.iter() {
+                    self.warning_handlers
+                        .iter()
+                        .for_each(|h| (*h.borrow_mut())(line_index, w.to_string()));
+                }
+            };
+            let l = &line.with_context(context)?;
+            if l.trim().is_empty() {
+                continue;
+            }
+            if let Some(a) = try_read_arg_line(l).with_context(context)? {
+                if baf.is_some() {
+                    return Err(anyhow!(
+                        "found an argument declaration after an attack or a support"
+                    ))
+                    .with_context(context);
+                }
+                arg_labels
+                    .as_mut()
+                    .unwrap()
+                    .push(a.consume_warnings(warning_consumer));
+                continue;
+            }
+            let framework = baf.get_or_insert_with(|| {
+                BAFramework::new(ArgumentSet::new(arg_labels.take().unwrap()))
+            });
+            if let Some(result) = try_read_att_line(l).with_context(context)? {
+                let (a, b) = result.consume_warnings(warning_consumer);
+                framework.new_attack(&a, &b).with_context(context)?;
+                continue;
+            }
+            if SUP_LINE_PATTERN.is_match(l) {
+                let (a, b) = match SUP_LINE_ARG_NAMES_PATTERN.captures(l) {
+                    Some(c) => captured_arg(&c, 1)
+                        .zip(captured_arg(&c, 2))
+                        .consume_warnings(warning_consumer),
+                    None => {
+                        return Err(anyhow!("invalid argument names in {}", l.trim()))
+                            .with_context(context)
+                    }
+                };
+                framework.new_support(&a, &b).with_context(context)?;
+                continue;
+            }
+            return Err(anyhow!("syntax error in line \"{}\"", l)).with_context(context);
+        }
+        match baf {
+            Some(b) => Ok(b),
+            None => Ok(BAFramework::new(ArgumentSet::new(
+                arg_labels.take().unwrap(),
+            ))),
+        }
+    }
+
+    /// Adds a callback function to call when warnings are raised while parsing a BAF.
+    pub fn add_warning_handler(&mut self, h: &'a mut dyn FnMut(usize, String)) {
+        self.warning_handlers.push(Rc::new(RefCell::new(h)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(instance: &str) -> Result<BAFramework<String>> {
+        BipolarAspartixReader::default().read(&mut instance.as_bytes())
+    }
+
+    #[test]
+    fn test_read_ok() {
+        let baf = read("arg(a).\narg(b).\nsup(a,b).\natt(b,a).\nsup( b,b).\n").unwrap();
+        assert_eq!(2, baf.argument_set().len());
+        assert_eq!(1, baf.attack_framework().n_attacks());
+        assert_eq!(
+            vec!["a ⇒ b".to_string(), "b ⇒ b".to_string()],
+            baf.iter_supports()
+                .map(|s| s.to_string())
+                .collect::<Vec<String>>()
+        );
+    }
+
+    #[test]
+    fn test_read_no_relations() {
+        let baf = read("arg(a).\n").unwrap();
+        assert_eq!(1, baf.argument_set().len());
+        assert_eq!(0, baf.n_supports());
+    }
+
+    #[test]
+    fn test_read_errors() {
+        [
+            "arg(a).\nsup(a,b).\n",
+            "arg(a).\nsup(a,1b).\n",
+            "arg(a).\nsup(a,a).\narg(b).\n",
+            "arg(a).\nsupport(a,a).\n",
+        ]
+        .iter()
+        .for_each(|i| assert!(read(i).is_err(), "{}", i));
+    }
+}
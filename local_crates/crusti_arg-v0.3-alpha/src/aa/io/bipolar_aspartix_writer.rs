@@ -0,0 +1,104 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::arguments::LabelType;
+use crate::aa::io::aspartix_writer::AspartixWriter;
+use crate::BAFramework;
+use anyhow::Result;
+use std::io::Write;
+
+/// A writer for the bipolar Aspartix dialect.
+///
+/// This object writes a [`BAFramework`] using the Aspartix format, followed by a `sup(a,b).` line for each support.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{ArgumentSet, BAFramework, BipolarAspartixWriter};
+/// let labels = vec!["a", "b"];
+/// let mut framework = BAFramework::new(ArgumentSet::new(labels.clone()));
+/// framework.new_support(&labels[0], &labels[1]).unwrap();
+/// let mut out = Vec::new();
+/// BipolarAspartixWriter::default().write(&framework, &mut out).unwrap();
+/// assert_eq!("arg(a).\narg(b).\nsup(a,b).\n", String::from_utf8(out).unwrap());
+/// ```
+///
+/// [`BAFramework`]: struct.BAFramework.html
+#[derive(Default)]
+pub struct BipolarAspartixWriter {}
+
+impl BipolarAspartixWriter {
+    /// Writes a bipolar framework using the bipolar Aspartix dialect to the provided writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `framework` - the framework
+    /// * `writer` - the writer
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::BAFramework;
+    /// # use crusti_arg::ArgumentSet;
+    /// # use crusti_arg::BipolarAspartixWriter;
+    /// # use crusti_arg::LabelType;
+    /// # use anyhow::Result;
+    /// fn write_baf_to_stdout<T: LabelType>(baf: &BAFramework<T>) -> Result<()> {
+    ///     let writer = BipolarAspartixWriter::default();
+    ///     writer.write(&baf, &mut std::io::stdout())
+    /// }
+    /// # write_baf_to_stdout(&BAFramework::new(ArgumentSet::new(vec![] as Vec<String>)));
+    /// ```
+    pub fn write<T: LabelType>(
+        &self,
+        framework: &BAFramework<T>,
+        writer: &mut dyn Write,
+    ) -> Result<()> {
+        AspartixWriter::default().write(framework.attack_framework(), writer)?;
+        for support in framework.iter_supports() {
+            writeln!(
+                writer,
+                "sup({},{}).",
+                support.supporter(),
+                support.supported()
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BipolarAspartixReader;
+
+    #[test]
+    fn test_write_read() {
+        let instance = "arg(a).\narg(b).\narg(c).\natt(a,b).\nsup(c,a).\nsup(b,c).\n";
+        let framework = BipolarAspartixReader::default()
+            .read(&mut instance.as_bytes())
+            .unwrap();
+        let mut out = Vec::new();
+        BipolarAspartixWriter::default()
+            .write(&framework, &mut out)
+            .unwrap();
+        assert_eq!(instance, String::from_utf8(out).unwrap());
+    }
+}
@@ -0,0 +1,244 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::io::aspartix_reader::{
+    captured_arg, try_read_arg_line, try_read_att_line, WarningHandler, ARG_AND_SPACE_PATTERN,
+};
+use crate::aa::io::labels::QUOTED_LABEL_PATTERN;
+use crate::CAFramework;
+use anyhow::{anyhow, Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    io::{BufRead, BufReader, Read},
+    rc::Rc,
+};
+
+lazy_static! {
+    static ref PART_ARG_LINE_PATTERN: Regex = Regex::new(&format!(
+        r#"^\s*[uc]arg\((?:[^)"]|{})+\).\s*$"#,
+        QUOTED_LABEL_PATTERN
+    ))
+    .unwrap();
+    static ref PART_ARG_LINE_ARG_NAME_PATTERN: Regex = Regex::new(&format!(
+        r"^\s*([uc])arg\(({})\).\s*$",
+        ARG_AND_SPACE_PATTERN
+    ))
+    .unwrap();
+    static ref UNCERTAIN_ATT_LINE_PATTERN: Regex = Regex::new(&format!(
+        r#"^\s*ud?att\((?:[^,"]|{})+,(?:[^)"]|{})+\).\s*$"#,
+        QUOTED_LABEL_PATTERN, QUOTED_LABEL_PATTERN
+    ))
+    .unwrap();
+    static ref UNCERTAIN_ATT_LINE_ARG_NAMES_PATTERN: Regex = Regex::new(&format!(
+        r"^\s*u(d?)att\(({}),({})\).\s*$",
+        ARG_AND_SPACE_PATTERN, ARG_AND_SPACE_PATTERN,
+    ))
+    .unwrap();
+}
+
+/// A reader for the control Aspartix dialect.
+///
+/// This dialect extends the Aspartix format to handle control frameworks:
+/// `arg(a).`, `uarg(a).` and `carg(a).` respectively declare fixed, uncertain and control arguments,
+/// while `att(a,b).`, `uatt(a,b).` and `udatt(a,b).` respectively declare attacks, uncertain attacks and uncertain conflicts (attacks of unknown direction).
+/// The arguments must be declared before the attacks, and an argument cannot be declared twice.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{CAFramework, CafAspartixReader};
+/// fn read_caf_from_str(s: &str) -> CAFramework<String> {
+///     let reader = CafAspartixReader::default();
+///     reader.read(&mut s.as_bytes()).expect("invalid control Aspartix AF")
+/// }
+/// let caf = read_caf_from_str("arg(a).\nuarg(b).\ncarg(c).\natt(c,b).\nuatt(b,a).");
+/// assert_eq!(1, caf.iter_uncertain_attacks().count());
+/// ```
+#[derive(Default)]
+pub struct CafAspartixReader<'a> {
+    warning_handlers: Vec<WarningHandler<'a>>,
+}
+
+impl<'a> CafAspartixReader<'a> {
+    /// Reads a [`CAFramework`] encoded using the control Aspartix dialect.
+    ///
+    /// In case warnings are raised, the callback functions registered by [`add_warning_handler`] are triggered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{CAFramework, CafAspartixReader};
+    /// fn read_caf_from_str(s: &str) -> CAFramework<String> {
+    ///     let reader = CafAspartixReader::default();
+    ///     reader.read(&mut s.as_bytes()).expect("invalid control Aspartix AF")
+    /// }
+    /// # read_caf_from_str("arg(a).\narg(b).\nudatt(a,b).");
+    /// ```
+    ///
+    /// [`CAFramework`]: struct.CAFramework.html
+    /// [`add_warning_handler`]: struct.CafAspartixReader.html#method.add_warning_handler
+    pub fn read(&self, reader: &mut dyn Read) -> Result<CAFramework<String>> {
+        let mut arg_labels = Some((vec![], vec![], vec![]));
+        let mut known_labels = HashSet::new();
+        let mut caf: Option<CAFramework<String>> = None;
+        for (line_index, line) in BufReader::new(reader).lines().enumerate() {
+            let context = || format!("while reading line {}", line_index);
+            let warning_consumer = |warnings: Vec<String>| {
+                for w in warnings.iter() {
+                    self.warning_handlers
+                        .iter()
+                        .for_each(|h| (*h.borrow_mut())(line_index, w.to_string()));
+                }
+            };
+            let l = &line.with_context(context)?;
+            if l.trim().is_empty() {
+                continue;
+            }
+            let declaration = if let Some(a) = try_read_arg_line(l).with_context(context)? {
+                Some((' ', a.consume_warnings(warning_consumer)))
+            } else if PART_ARG_LINE_PATTERN.is_match(l) {
+                match PART_ARG_LINE_ARG_NAME_PATTERN.captures(l) {
+                    Some(c) => Some((
+                        c.get(1).unwrap().as_str().chars().next().unwrap(),
+                        captured_arg(&c, 2).consume_warnings(warning_consumer),
+                    )),
+                    None => {
+                        return Err(anyhow!("invalid argument name in {}", l.trim()))
+                            .with_context(context)
+                    }
+                }
+            } else {
+                None
+            };
+            if let Some((part, label)) = declaration {
+                if caf.is_some() {
+                    return Err(anyhow!("found an argument declaration after an attack"))
+                        .with_context(context);
+                }
+                if !known_labels.insert(label.clone()) {
+                    return Err(anyhow!("argument {} is declared more than once", label))
+                        .with_context(context);
+                }
+                let (fixed, uncertain, control) = arg_labels.as_mut().unwrap();
+                match part {
+                    'u' => uncertain.push(label),
+                    'c' => control.push(label),
+                    _ => fixed.push(label),
+                }
+                continue;
+            }
+            let framework = caf.get_or_insert_with(|| {
+                let (fixed, uncertain, control) = arg_labels.take().unwrap();
+                CAFramework::new(fixed, uncertain, control)
+            });
+            if let Some(result) = try_read_att_line(l).with_context(context)? {
+                let (a, b) = result.consume_warnings(warning_consumer);
+                framework.new_attack(&a, &b).with_context(context)?;
+                continue;
+            }
+            if UNCERTAIN_ATT_LINE_PATTERN.is_match(l) {
+                let (undirected, a, b) = match UNCERTAIN_ATT_LINE_ARG_NAMES_PATTERN.captures(l) {
+                    Some(c) => {
+                        let (a, b) = captured_arg(&c, 2)
+                            .zip(captured_arg(&c, 3))
+                            .consume_warnings(warning_consumer);
+                        (!c.get(1).unwrap().as_str().is_empty(), a, b)
+                    }
+                    None => {
+                        return Err(anyhow!("invalid argument names in {}", l.trim()))
+                            .with_context(context)
+                    }
+                };
+                if undirected {
+                    framework.new_uncertain_conflict(&a, &b)
+                } else {
+                    framework.new_uncertain_attack(&a, &b)
+                }
+                .with_context(context)?;
+                continue;
+            }
+            return Err(anyhow!("syntax error in line \"{}\"", l)).with_context(context);
+        }
+        match caf {
+            Some(c) => Ok(c),
+            None => {
+                let (fixed, uncertain, control) = arg_labels.take().unwrap();
+                Ok(CAFramework::new(fixed, uncertain, control))
+            }
+        }
+    }
+
+    /// Adds a callback function to call when warnings are raised while parsing a CAF.
+    pub fn add_warning_handler(&mut self, h: &'a mut dyn FnMut(usize, String)) {
+        self.warning_handlers.push(Rc::new(RefCell::new(h)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CafPart;
+
+    fn read(instance: &str) -> Result<CAFramework<String>> {
+        CafAspartixReader::default().read(&mut instance.as_bytes())
+    }
+
+    #[test]
+    fn test_read_ok() {
+        let caf = read(
+            "carg(c).\narg(a).\nuarg(\"u 1\").\narg(b).\natt(c,a).\nuatt(\"u 1\",b).\nudatt( a,b).\n",
+        )
+        .unwrap();
+        assert_eq!(
+            vec!["a", "b", "u 1", "c"],
+            caf.argument_set()
+                .iter()
+                .map(|a| a.label().as_str())
+                .collect::<Vec<&str>>()
+        );
+        assert_eq!(CafPart::Uncertain, caf.part(&"u 1".to_string()).unwrap());
+        assert_eq!(CafPart::Control, caf.part(&"c".to_string()).unwrap());
+        assert_eq!(1, caf.iter_attacks().count());
+        assert_eq!(1, caf.iter_uncertain_attacks().count());
+        assert_eq!(1, caf.iter_uncertain_conflicts().count());
+    }
+
+    #[test]
+    fn test_read_no_relations() {
+        let caf = read("uarg(a).\n").unwrap();
+        assert_eq!(1, caf.argument_set().len());
+    }
+
+    #[test]
+    fn test_read_errors() {
+        [
+            "arg(a).\nuatt(a,b).\n",
+            "arg(a).\nuatt(a,1b).\n",
+            "arg(a).\natt(a,a).\ncarg(b).\n",
+            "arg(a).\nuarg(a).\n",
+            "carg(a).\nudatt(a,a).\n",
+            "arg(a).\nxatt(a,a).\n",
+        ]
+        .iter()
+        .for_each(|i| assert!(read(i).is_err(), "{}", i));
+    }
+}
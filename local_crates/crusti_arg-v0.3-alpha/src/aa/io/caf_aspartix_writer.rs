@@ -0,0 +1,123 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::arguments::{Argument, LabelType};
+use crate::aa::io::labels;
+use crate::{CAFramework, CafPart};
+use anyhow::Result;
+use std::io::Write;
+
+/// An iterator over the pairs of arguments of a relation, as given by a [`CAFramework`].
+type RelationIter<'a, T> = Box<dyn Iterator<Item = (&'a Argument<T>, &'a Argument<T>)> + 'a>;
+
+/// A writer for the control Aspartix dialect.
+///
+/// This object writes a [`CAFramework`] using the `arg`, `uarg` and `carg` declarations for its fixed, uncertain and control arguments,
+/// followed by the `att`, `uatt` and `udatt` declarations for its attacks, uncertain attacks and uncertain conflicts (see [`CafAspartixReader`]).
+/// The arguments are written in the order of their ids, and the relations in the order they were added.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{CAFramework, CafAspartixWriter};
+/// let mut framework = CAFramework::new(vec!["a"], vec!["b"], vec![]);
+/// framework.new_uncertain_attack(&"b", &"a").unwrap();
+/// let mut out = Vec::new();
+/// CafAspartixWriter::default().write(&framework, &mut out).unwrap();
+/// assert_eq!("arg(a).\nuarg(b).\nuatt(b,a).\n", String::from_utf8(out).unwrap());
+/// ```
+///
+/// [`CAFramework`]: struct.CAFramework.html
+/// [`CafAspartixReader`]: struct.CafAspartixReader.html
+#[derive(Default)]
+pub struct CafAspartixWriter {}
+
+impl CafAspartixWriter {
+    /// Writes a control framework using the control Aspartix dialect to the provided writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `framework` - the framework
+    /// * `writer` - the writer
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::CAFramework;
+    /// # use crusti_arg::CafAspartixWriter;
+    /// # use crusti_arg::LabelType;
+    /// # use anyhow::Result;
+    /// fn write_caf_to_stdout<T: LabelType>(caf: &CAFramework<T>) -> Result<()> {
+    ///     let writer = CafAspartixWriter::default();
+    ///     writer.write(&caf, &mut std::io::stdout())
+    /// }
+    /// # write_caf_to_stdout(&CAFramework::new(vec![] as Vec<String>, vec![], vec![]));
+    /// ```
+    pub fn write<T: LabelType>(
+        &self,
+        framework: &CAFramework<T>,
+        writer: &mut dyn Write,
+    ) -> Result<()> {
+        for arg in framework.argument_set().iter() {
+            let prefix = match framework.part(arg.label())? {
+                CafPart::Fixed => "",
+                CafPart::Uncertain => "u",
+                CafPart::Control => "c",
+            };
+            writeln!(writer, "{}arg({}).", prefix, labels::format_label(arg))?;
+        }
+        let relations: [(&str, RelationIter<T>); 3] = [
+            ("att", framework.iter_attacks()),
+            ("uatt", framework.iter_uncertain_attacks()),
+            ("udatt", framework.iter_uncertain_conflicts()),
+        ];
+        for (name, relation) in relations {
+            for (a, b) in relation {
+                writeln!(
+                    writer,
+                    "{}({},{}).",
+                    name,
+                    labels::format_label(a),
+                    labels::format_label(b)
+                )?;
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CafAspartixReader;
+
+    #[test]
+    fn test_write_read() {
+        let instance = "arg(a).\narg(b).\nuarg(\"u 1\").\ncarg(c).\natt(c,a).\nuatt(\"u 1\",b).\nudatt(a,b).\n";
+        let framework = CafAspartixReader::default()
+            .read(&mut instance.as_bytes())
+            .unwrap();
+        let mut out = Vec::new();
+        CafAspartixWriter::default()
+            .write(&framework, &mut out)
+            .unwrap();
+        assert_eq!(instance, String::from_utf8(out).unwrap());
+    }
+}
@@ -0,0 +1,213 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::io::aspartix_reader::{
+    captured_arg, try_read_arg_line, try_read_att_line, WarningHandler, ARG_AND_SPACE_PATTERN,
+};
+use crate::aa::io::labels::QUOTED_LABEL_PATTERN;
+use crate::{ArgumentSet, ClaimAAFramework};
+use anyhow::{anyhow, Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::{
+    cell::RefCell,
+    io::{BufRead, BufReader, Read},
+    rc::Rc,
+};
+
+lazy_static! {
+    static ref CLAIM_LINE_PATTERN: Regex = Regex::new(&format!(
+        r#"^\s*claim\((?:[^,"]|{})+,(?:[^)"]|{})+\).\s*$"#,
+        QUOTED_LABEL_PATTERN, QUOTED_LABEL_PATTERN
+    ))
+    .unwrap();
+    static ref CLAIM_LINE_ARG_NAMES_PATTERN: Regex = Regex::new(&format!(
+        r"^\s*claim\(({}),({})\).\s*$",
+        ARG_AND_SPACE_PATTERN, ARG_AND_SPACE_PATTERN,
+    ))
+    .unwrap();
+}
+
+/// A reader for the claim-augmented Aspartix dialect.
+///
+/// This dialect extends the Aspartix format by claim declarations `claim(a,c).`, meaning the claim of `a` is `c`.
+/// As for attacks, claims must be declared after the arguments; attacks and claims may be interleaved.
+/// The arguments with no claim declaration claim their own label.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{ClaimAAFramework, ClaimAspartixReader};
+/// fn read_claim_af_from_str(s: &str) -> ClaimAAFramework<String> {
+///     let reader = ClaimAspartixReader::default();
+///     reader.read(&mut s.as_bytes()).expect("invalid claim-augmented Aspartix AF")
+/// }
+/// let framework = read_claim_af_from_str("arg(a).\narg(b).\natt(a,b).\nclaim(b,c).");
+/// assert_eq!("c", framework.claim_of(&"b".to_string()).unwrap());
+/// ```
+#[derive(Default)]
+pub struct ClaimAspartixReader<'a> {
+    warning_handlers: Vec<WarningHandler<'a>>,
+}
+
+impl<'a> ClaimAspartixReader<'a> {
+    /// Reads a [`ClaimAAFramework`] encoded using the claim-augmented Aspartix dialect.
+    ///
+    /// In case warnings are raised, the callback functions registered by [`add_warning_handler`] are triggered.
+    /// A warning is also raised when the claim of an argument is declared more than once, the last declaration being kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ClaimAAFramework, ClaimAspartixReader};
+    /// fn read_claim_af_from_str(s: &str) -> ClaimAAFramework<String> {
+    ///     let reader = ClaimAspartixReader::default();
+    ///     reader.read(&mut s.as_bytes()).expect("invalid claim-augmented Aspartix AF")
+    /// }
+    /// # read_claim_af_from_str("arg(a).\nclaim(a,b).");
+    /// ```
+    ///
+    /// [`ClaimAAFramework`]: struct.ClaimAAFramework.html
+    /// [`add_warning_handler`]: struct.ClaimAspartixReader.html#method.add_warning_handler
+    pub fn read(&self, reader: &mut dyn Read) -> Result<ClaimAAFramework<String>> {
+        let mut arg_labels = Some(vec![]);
+        let mut claimed = vec![];
+        let mut caf: Option<ClaimAAFramework<String>> = None;
+        for (line_index, line) in BufReader::new(reader).lines().enumerate() {
+            let context = || format!("while reading line {}", line_index);
+            let warning_consumer = |warnings: Vec<String>| {
+                for w in warnings.iter() {
+                    self.warning_handlers
+                        .iter()
+                        .for_each(|h| (*h.borrow_mut())(line_index, w.to_string()));
+                }
+            };
+            let l = &line.with_context(context)?;
+            if l.trim().is_empty() {
+                continue;
+            }
+            if let Some(a) = try_read_arg_line(l).with_context(context)? {
+                if caf.is_some() {
+                    return Err(anyhow!(
+                        "found an argument declaration after an attack or a claim"
+                    ))
+                    .with_context(context);
+                }
+                arg_labels
+                    .as_mut()
+                    .unwrap()
+                    .push(a.consume_warnings(warning_consumer));
+                continue;
+            }
+            let framework = caf.get_or_insert_with(|| {
+                let labels = arg_labels.take().unwrap();
+                claimed = vec![false; labels.len()];
+                ClaimAAFramework::new(ArgumentSet::new(labels))
+            });
+            if let Some(result) = try_read_att_line(l).with_context(context)? {
+                let (a, b) = result.consume_warnings(warning_consumer);
+                framework.new_attack(&a, &b).with_context(context)?;
+                continue;
+            }
+            if CLAIM_LINE_PATTERN.is_match(l) {
+                let (a, c) = match CLAIM_LINE_ARG_NAMES_PATTERN.captures(l) {
+                    Some(c) => captured_arg(&c, 1)
+                        .zip(captured_arg(&c, 2))
+                        .consume_warnings(warning_consumer),
+                    None => {
+                        return Err(anyhow!("invalid argument or claim names in {}", l.trim()))
+                            .with_context(context)
+                    }
+                };
+                framework.set_claim(&a, c).with_context(context)?;
+                let id = framework.argument_set().get_argument_index(&a).unwrap();
+                if claimed[id] {
+                    warning_consumer(vec![format!(
+                        "the claim of argument {} is declared more than once",
+                        a
+                    )]);
+                }
+                claimed[id] = true;
+                continue;
+            }
+            return Err(anyhow!("syntax error in line \"{}\"", l)).with_context(context);
+        }
+        match caf {
+            Some(c) => Ok(c),
+            None => Ok(ClaimAAFramework::new(ArgumentSet::new(
+                arg_labels.take().unwrap(),
+            ))),
+        }
+    }
+
+    /// Adds a callback function to call when warnings are raised while parsing a claim-augmented AF.
+    pub fn add_warning_handler(&mut self, h: &'a mut dyn FnMut(usize, String)) {
+        self.warning_handlers.push(Rc::new(RefCell::new(h)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(instance: &str) -> Result<ClaimAAFramework<String>> {
+        ClaimAspartixReader::default().read(&mut instance.as_bytes())
+    }
+
+    #[test]
+    fn test_read_ok() {
+        let framework =
+            read("arg(a).\narg(b).\nclaim(a,\"c 1\").\natt(b,a).\nclaim( b,b).\n").unwrap();
+        assert_eq!(2, framework.argument_set().len());
+        assert_eq!(1, framework.attack_framework().n_attacks());
+        assert_eq!(
+            vec!["c 1", "b"],
+            framework
+                .iter_claims()
+                .map(|c| c.as_str())
+                .collect::<Vec<&str>>()
+        );
+    }
+
+    #[test]
+    fn test_read_claim_declared_twice() {
+        let mut warnings = vec![];
+        let mut handler = |line: usize, w: String| warnings.push((line, w));
+        let mut reader = ClaimAspartixReader::default();
+        reader.add_warning_handler(&mut handler);
+        let framework = reader
+            .read(&mut "arg(a).\nclaim(a,b).\nclaim(a,c).\n".as_bytes())
+            .unwrap();
+        assert_eq!("c", framework.claim_of(&"a".to_string()).unwrap());
+        assert_eq!(1, warnings.len());
+        assert_eq!(2, warnings[0].0);
+    }
+
+    #[test]
+    fn test_read_errors() {
+        [
+            "arg(a).\nclaim(b,a).\n",
+            "arg(a).\nclaim(a,1b).\n",
+            "arg(a).\nclaim(a,a).\narg(b).\n",
+            "arg(a).\nclaims(a,a).\n",
+        ]
+        .iter()
+        .for_each(|i| assert!(read(i).is_err(), "{}", i));
+    }
+}
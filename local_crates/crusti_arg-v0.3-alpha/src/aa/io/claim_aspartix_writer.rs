@@ -0,0 +1,104 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::arguments::LabelType;
+use crate::aa::io::aspartix_writer::AspartixWriter;
+use crate::aa::io::labels;
+use crate::ClaimAAFramework;
+use anyhow::Result;
+use std::io::Write;
+
+/// A writer for the claim-augmented Aspartix dialect.
+///
+/// This object writes a [`ClaimAAFramework`] using the Aspartix format, followed by a `claim(a,c).` line for each argument, in the order of their ids.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{ArgumentSet, ClaimAAFramework, ClaimAspartixWriter};
+/// let mut framework = ClaimAAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+/// framework.set_claim(&"b", "a").unwrap();
+/// let mut out = Vec::new();
+/// ClaimAspartixWriter::default().write(&framework, &mut out).unwrap();
+/// assert_eq!("arg(a).\narg(b).\nclaim(a,a).\nclaim(b,a).\n", String::from_utf8(out).unwrap());
+/// ```
+///
+/// [`ClaimAAFramework`]: struct.ClaimAAFramework.html
+#[derive(Default)]
+pub struct ClaimAspartixWriter {}
+
+impl ClaimAspartixWriter {
+    /// Writes a claim-augmented framework using the claim-augmented Aspartix dialect to the provided writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `framework` - the framework
+    /// * `writer` - the writer
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::ArgumentSet;
+    /// # use crusti_arg::ClaimAAFramework;
+    /// # use crusti_arg::ClaimAspartixWriter;
+    /// # use crusti_arg::LabelType;
+    /// # use anyhow::Result;
+    /// fn write_claim_af_to_stdout<T: LabelType>(framework: &ClaimAAFramework<T>) -> Result<()> {
+    ///     let writer = ClaimAspartixWriter::default();
+    ///     writer.write(&framework, &mut std::io::stdout())
+    /// }
+    /// # write_claim_af_to_stdout(&ClaimAAFramework::new(ArgumentSet::new(vec![] as Vec<String>)));
+    /// ```
+    pub fn write<T: LabelType>(
+        &self,
+        framework: &ClaimAAFramework<T>,
+        writer: &mut dyn Write,
+    ) -> Result<()> {
+        AspartixWriter::default().write(framework.attack_framework(), writer)?;
+        for arg in framework.argument_set().iter() {
+            writeln!(
+                writer,
+                "claim({},{}).",
+                labels::format_label(arg),
+                labels::format_label(framework.claim_of(arg.label())?)
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ClaimAspartixReader;
+
+    #[test]
+    fn test_write_read() {
+        let instance = "arg(a).\narg(b).\natt(a,b).\nclaim(a,\"c 1\").\nclaim(b,a).\n";
+        let framework = ClaimAspartixReader::default()
+            .read(&mut instance.as_bytes())
+            .unwrap();
+        let mut out = Vec::new();
+        ClaimAspartixWriter::default()
+            .write(&framework, &mut out)
+            .unwrap();
+        assert_eq!(instance, String::from_utf8(out).unwrap());
+    }
+}
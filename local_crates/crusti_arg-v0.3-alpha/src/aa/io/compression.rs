@@ -0,0 +1,384 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! A module used to read and write files compressed with gzip or xz.
+//!
+//! Compressed files are recognized by their first bytes when they are read, and by their extension (`.gz` or `.xz`) when they are written.
+//! The data are compressed and decompressed in the process, by the `flate2` (gzip) and `xz2` (xz) crates.
+//! The xz format requires the `xz` feature, enabled by default; without it, xz files are reported as unsupported.
+//!
+//! # Example
+//!
+//! ```
+//! # use crusti_arg::{compression, AspartixReader};
+//! # let path = std::env::temp_dir().join(format!("crusti_arg-doc-{}.apx", std::process::id()));
+//! # std::fs::write(&path, "arg(a).\n").unwrap();
+//! let mut reader = compression::open(&path).unwrap();
+//! let framework = AspartixReader::default().read(&mut reader).unwrap();
+//! assert_eq!(1, framework.argument_set().len());
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+use anyhow::{Context, Result};
+use flate2::{bufread::MultiGzDecoder, write::GzEncoder};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read, Write},
+    path::Path,
+};
+#[cfg(feature = "xz")]
+use xz2::{bufread::XzDecoder, write::XzEncoder};
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const XZ_MAGIC: &[u8] = &[0xfd, b'7', b'z', b'X', b'Z', 0x00];
+
+#[cfg(feature = "xz")]
+const XZ_LEVEL: u32 = 6;
+
+/// The compression formats handled by this module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    /// No compression.
+    None,
+    /// The gzip format (`.gz` files).
+    Gzip,
+    /// The xz format (`.xz` files).
+    Xz,
+}
+
+impl Compression {
+    /// Returns the compression format indicated by the extension of a path.
+    ///
+    /// # Arguments
+    /// * `path` - the path
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::compression::Compression;
+    /// assert_eq!(Compression::Gzip, Compression::from_extension("instance.apx.gz"));
+    /// assert_eq!(Compression::Xz, Compression::from_extension("instance.apxm.xz"));
+    /// assert_eq!(Compression::None, Compression::from_extension("instance.apx"));
+    /// ```
+    pub fn from_extension<P: AsRef<Path>>(path: P) -> Self {
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("xz") => Compression::Xz,
+            _ => Compression::None,
+        }
+    }
+
+    /// Returns the compression format indicated by the first bytes of some data.
+    ///
+    /// # Arguments
+    /// * `bytes` - the first bytes of the data
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::compression::Compression;
+    /// assert_eq!(Compression::Gzip, Compression::from_magic(&[0x1f, 0x8b, 0x08]));
+    /// assert_eq!(Compression::None, Compression::from_magic(b"arg(a)."));
+    /// ```
+    pub fn from_magic(bytes: &[u8]) -> Self {
+        if bytes.starts_with(GZIP_MAGIC) {
+            Compression::Gzip
+        } else if bytes.starts_with(XZ_MAGIC) {
+            Compression::Xz
+        } else {
+            Compression::None
+        }
+    }
+
+    /// Returns the compression format of a file, given by its first bytes.
+    ///
+    /// # Arguments
+    /// * `path` - the path of the file
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::compression::{self, Compression};
+    /// # let path = std::env::temp_dir().join(format!("crusti_arg-doc-detect-{}.apx", std::process::id()));
+    /// std::fs::write(&path, "arg(a).\n").unwrap();
+    /// assert_eq!(Compression::None, Compression::of_file(&path).unwrap());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn of_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file =
+            File::open(path).with_context(|| format!(r#"while opening "{}""#, path.display()))?;
+        BufReader::new(file)
+            .fill_buf()
+            .map(Compression::from_magic)
+            .with_context(|| format!(r#"while reading "{}""#, path.display()))
+    }
+}
+
+/// Opens a file for reading, decompressing it if it is compressed.
+///
+/// The compression format is detected from the first bytes of the file, whatever its extension.
+///
+/// # Arguments
+/// * `path` - the path of the file
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::compression;
+/// # use std::io::Read;
+/// # let path = std::env::temp_dir().join(format!("crusti_arg-doc-open-{}.apxm", std::process::id()));
+/// std::fs::write(&path, "+arg(b).\n").unwrap();
+/// let mut content = String::new();
+/// compression::open(&path).unwrap().read_to_string(&mut content).unwrap();
+/// assert_eq!("+arg(b).\n", content);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn open<P: AsRef<Path>>(path: P) -> Result<DecompressedReader> {
+    let path = path.as_ref();
+    let file =
+        File::open(path).with_context(|| format!(r#"while opening "{}""#, path.display()))?;
+    DecompressedReader::new(BufReader::new(file))
+        .with_context(|| format!(r#"while reading "{}""#, path.display()))
+}
+
+/// Creates a file for writing, compressing its content if its extension is `.gz` or `.xz`.
+///
+/// The compressed stream is completed when the writer is finished or dropped.
+///
+/// # Arguments
+/// * `path` - the path of the file
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::compression;
+/// # use std::io::Write;
+/// # let path = std::env::temp_dir().join(format!("crusti_arg-doc-create-{}.apx", std::process::id()));
+/// let mut writer = compression::create(&path).unwrap();
+/// writeln!(writer, "arg(a).").unwrap();
+/// writer.finish().unwrap();
+/// assert_eq!("arg(a).\n", std::fs::read_to_string(&path).unwrap());
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn create<P: AsRef<Path>>(path: P) -> Result<CompressedWriter> {
+    let path = path.as_ref();
+    let compression = Compression::from_extension(path);
+    check_supported(compression)?;
+    let file =
+        File::create(path).with_context(|| format!(r#"while creating "{}""#, path.display()))?;
+    Ok(CompressedWriter(match compression {
+        Compression::None => Encoder::File(file),
+        Compression::Gzip => Encoder::Gzip(GzEncoder::new(file, flate2::Compression::default())),
+        #[cfg(feature = "xz")]
+        Compression::Xz => Encoder::Xz(XzEncoder::new(file, XZ_LEVEL)),
+        #[cfg(not(feature = "xz"))]
+        Compression::Xz => unreachable!(),
+    }))
+}
+
+/// Returns an error if a compression format is not supported by the enabled features.
+fn check_supported(compression: Compression) -> Result<()> {
+    if cfg!(not(feature = "xz")) && compression == Compression::Xz {
+        return Err(anyhow::anyhow!(
+            "the xz format is not supported (the xz feature is disabled)"
+        ));
+    }
+    Ok(())
+}
+
+/// A reader giving the decompressed content of a buffered reader.
+///
+/// An error is returned while reading if the data are not valid for their compression format.
+pub struct DecompressedReader {
+    compression: Compression,
+    inner: Box<dyn Read + Send>,
+}
+
+impl DecompressedReader {
+    /// Builds a reader decompressing the data of a buffered reader.
+    ///
+    /// The compression format is detected from the bytes given by the first fill of the buffer, which are not consumed.
+    ///
+    /// # Arguments
+    /// * `reader` - the buffered reader
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::compression::{Compression, DecompressedReader};
+    /// # use std::io::Read;
+    /// let mut reader = DecompressedReader::new(&b"arg(a).\n"[..]).unwrap();
+    /// assert_eq!(Compression::None, reader.compression());
+    /// let mut content = String::new();
+    /// reader.read_to_string(&mut content).unwrap();
+    /// assert_eq!("arg(a).\n", content);
+    /// ```
+    pub fn new<R: BufRead + Send + 'static>(mut reader: R) -> Result<Self> {
+        let compression = Compression::from_magic(
+            reader
+                .fill_buf()
+                .context("while reading the first bytes of the data")?,
+        );
+        check_supported(compression)?;
+        let inner: Box<dyn Read + Send> = match compression {
+            Compression::None => Box::new(reader),
+            Compression::Gzip => Box::new(MultiGzDecoder::new(reader)),
+            #[cfg(feature = "xz")]
+            Compression::Xz => Box::new(XzDecoder::new_multi_decoder(reader)),
+            #[cfg(not(feature = "xz"))]
+            Compression::Xz => unreachable!(),
+        };
+        Ok(DecompressedReader { compression, inner })
+    }
+
+    /// Returns the compression format of the data.
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+}
+
+impl Read for DecompressedReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+/// A writer compressing its content into a file, if needed.
+pub struct CompressedWriter(Encoder);
+
+enum Encoder {
+    File(File),
+    Gzip(GzEncoder<File>),
+    #[cfg(feature = "xz")]
+    Xz(XzEncoder<File>),
+}
+
+impl CompressedWriter {
+    /// Flushes the content and completes the compressed stream, returning an error if the compression failed.
+    ///
+    /// Dropping the writer also completes the stream, but ignores its errors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::compression;
+    /// # use std::io::Write;
+    /// # let path = std::env::temp_dir().join(format!("crusti_arg-doc-finish-{}.apxm", std::process::id()));
+    /// let mut writer = compression::create(&path).unwrap();
+    /// writeln!(writer, "+arg(a).").unwrap();
+    /// writer.finish().unwrap();
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn finish(self) -> Result<()> {
+        let context = "while completing the compressed file";
+        let mut file = match self.0 {
+            Encoder::File(f) => f,
+            Encoder::Gzip(e) => e.finish().context(context)?,
+            #[cfg(feature = "xz")]
+            Encoder::Xz(e) => e.finish().context(context)?,
+        };
+        file.flush().context(context)
+    }
+}
+
+impl Write for CompressedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match &mut self.0 {
+            Encoder::File(f) => f.write(buf),
+            Encoder::Gzip(e) => e.write(buf),
+            #[cfg(feature = "xz")]
+            Encoder::Xz(e) => e.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.0 {
+            Encoder::File(f) => f.flush(),
+            Encoder::Gzip(e) => e.flush(),
+            #[cfg(feature = "xz")]
+            Encoder::Xz(e) => e.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "crusti_arg-compression-{}-{}",
+            std::process::id(),
+            name
+        ))
+    }
+
+    fn round_trip(name: &str, expected: Compression) {
+        let path = temp_path(name);
+        let mut writer = create(&path).unwrap();
+        writeln!(writer, "arg(a).").unwrap();
+        writer.finish().unwrap();
+        assert_eq!(expected, Compression::of_file(&path).unwrap());
+        let mut content = String::new();
+        open(&path).unwrap().read_to_string(&mut content).unwrap();
+        assert_eq!("arg(a).\n", content);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_round_trip_gzip() {
+        round_trip("instance.apx.gz", Compression::Gzip);
+    }
+
+    #[cfg(feature = "xz")]
+    #[test]
+    fn test_round_trip_xz() {
+        round_trip("instance.apx.xz", Compression::Xz);
+    }
+
+    #[test]
+    fn test_concatenated_gzip_members() {
+        let mut data = vec![];
+        for line in ["arg(a).\n", "arg(b).\n"] {
+            let mut encoder = GzEncoder::new(vec![], flate2::Compression::default());
+            encoder.write_all(line.as_bytes()).unwrap();
+            data.append(&mut encoder.finish().unwrap());
+        }
+        let mut reader = DecompressedReader::new(std::io::Cursor::new(data)).unwrap();
+        assert_eq!(Compression::Gzip, reader.compression());
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+        assert_eq!("arg(a).\narg(b).\n", content);
+    }
+
+    #[test]
+    fn test_corrupted_file() {
+        let path = temp_path("corrupted.apx.gz");
+        std::fs::write(&path, [0x1f, 0x8b, 0x00, 0x01]).unwrap();
+        let mut content = String::new();
+        assert!(open(&path).unwrap().read_to_string(&mut content).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_from_magic_xz() {
+        assert_eq!(Compression::Xz, Compression::from_magic(XZ_MAGIC));
+        assert_eq!(Compression::None, Compression::from_magic(&[0x1f]));
+    }
+}
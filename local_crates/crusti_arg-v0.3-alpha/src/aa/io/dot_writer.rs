@@ -0,0 +1,329 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::arguments::LabelType;
+use crate::{AAFramework, LabellingStatus};
+use anyhow::{anyhow, Result};
+use std::{collections::HashSet, io::Write};
+
+const ADDED_COLOR: &str = "blue";
+const REMOVED_COLOR: &str = "gray";
+
+/// A writer for the DOT format of Graphviz.
+///
+/// The arguments are written as nodes, in the order of their ids, followed by the attacks as edges.
+/// Nodes may be filled according to a labelling: `IN` arguments are green, `OUT` arguments are red and `UNDEC` arguments are yellow.
+///
+/// When writing a step of a dynamics with [`write_step`], the arguments and attacks added by the step are drawn in bold blue,
+/// while the removed ones are kept in the drawing as dashed gray elements.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{AAFramework, ArgumentSet, DotWriter};
+/// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+/// framework.new_attack(&"a", &"b").unwrap();
+/// let mut out = Vec::new();
+/// DotWriter::default().write(&framework, &mut out).unwrap();
+/// assert_eq!("digraph {\n    \"a\";\n    \"b\";\n    \"a\" -> \"b\";\n}\n", String::from_utf8(out).unwrap());
+/// ```
+///
+/// [`write_step`]: struct.DotWriter.html#method.write_step
+#[derive(Default)]
+pub struct DotWriter {}
+
+impl DotWriter {
+    /// Writes a framework using the DOT format to the provided writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `framework` - the framework
+    /// * `writer` - the writer
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::AAFramework;
+    /// # use crusti_arg::ArgumentSet;
+    /// # use crusti_arg::DotWriter;
+    /// # use crusti_arg::LabelType;
+    /// # use anyhow::Result;
+    /// fn write_af_to_stdout<T: LabelType>(framework: &AAFramework<T>) -> Result<()> {
+    ///     let writer = DotWriter::default();
+    ///     writer.write(&framework, &mut std::io::stdout())
+    /// }
+    /// # write_af_to_stdout(&AAFramework::new(ArgumentSet::new(vec![] as Vec<String>)));
+    /// ```
+    pub fn write<T: LabelType>(
+        &self,
+        framework: &AAFramework<T>,
+        writer: &mut dyn Write,
+    ) -> Result<()> {
+        self.write_graph(framework, None, None, writer)
+    }
+
+    /// Writes a framework using the DOT format to the provided writer, coloring its arguments according to a labelling.
+    ///
+    /// The labelling is indexed by the identifiers of the arguments, as the one returned by [`AAFramework::labelling`].
+    /// An error is returned if its length differs from the number of arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `framework` - the framework
+    /// * `labelling` - the labelling of the arguments
+    /// * `writer` - the writer
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{AAFramework, ArgumentSet, DotWriter};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// let labelling = framework.labelling(&framework.least_fixpoint());
+    /// let mut out = Vec::new();
+    /// DotWriter::default().write_labelled(&framework, &labelling, &mut out).unwrap();
+    /// assert!(String::from_utf8(out).unwrap().contains("\"b\" [style=\"filled\", fillcolor=lightcoral];"));
+    /// ```
+    ///
+    /// [`AAFramework::labelling`]: struct.AAFramework.html#method.labelling
+    pub fn write_labelled<T: LabelType>(
+        &self,
+        framework: &AAFramework<T>,
+        labelling: &[LabellingStatus],
+        writer: &mut dyn Write,
+    ) -> Result<()> {
+        self.write_graph(framework, Some(labelling), None, writer)
+    }
+
+    /// Writes a step of a dynamics using the DOT format to the provided writer, given the frameworks before and after the step.
+    ///
+    /// The arguments and attacks of the framework after the step are written, the ones which were not in the previous framework being highlighted.
+    /// The arguments and attacks of the previous framework which were removed by the step are written after them, as dashed gray elements.
+    /// Arguments are matched by their labels, and the optional labelling applies to the framework after the step.
+    ///
+    /// # Arguments
+    ///
+    /// * `previous` - the framework before the step
+    /// * `framework` - the framework after the step
+    /// * `labelling` - the optional labelling of the arguments of the framework after the step
+    /// * `writer` - the writer
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{AAFramework, ArgumentSet, DotWriter};
+    /// let mut previous = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// previous.new_attack(&"a", &"b").unwrap();
+    /// let framework = AAFramework::new(ArgumentSet::new(vec!["a", "c"]));
+    /// let mut out = Vec::new();
+    /// DotWriter::default().write_step(&previous, &framework, None, &mut out).unwrap();
+    /// let dot = String::from_utf8(out).unwrap();
+    /// assert!(dot.contains("\"c\" [style=\"bold\", color=blue];"));
+    /// assert!(dot.contains("\"a\" -> \"b\" [style=\"dashed\", color=gray];"));
+    /// ```
+    pub fn write_step<T: LabelType>(
+        &self,
+        previous: &AAFramework<T>,
+        framework: &AAFramework<T>,
+        labelling: Option<&[LabellingStatus]>,
+        writer: &mut dyn Write,
+    ) -> Result<()> {
+        self.write_graph(framework, labelling, Some(previous), writer)
+    }
+
+    fn write_graph<T: LabelType>(
+        &self,
+        framework: &AAFramework<T>,
+        labelling: Option<&[LabellingStatus]>,
+        previous: Option<&AAFramework<T>>,
+        writer: &mut dyn Write,
+    ) -> Result<()> {
+        let n_args = framework.argument_set().len();
+        if let Some(l) = labelling {
+            if l.len() != n_args {
+                return Err(anyhow!(
+                    "the labelling has {} statuses, but the framework has {} arguments",
+                    l.len(),
+                    n_args
+                ));
+            }
+        }
+        let attacks_of = |af: &AAFramework<T>| {
+            af.iter_attacks()
+                .map(|att| {
+                    (
+                        att.attacker().label().clone(),
+                        att.attacked().label().clone(),
+                    )
+                })
+                .collect::<HashSet<(T, T)>>()
+        };
+        let previous_attacks = previous.map(attacks_of);
+        writeln!(writer, "digraph {{")?;
+        for arg in framework.argument_set().iter() {
+            let mut node = Element::default();
+            if let Some(l) = labelling {
+                node.fill(l[arg.id()]);
+            }
+            if previous.is_some_and(|p| !p.argument_set().contains(arg.label())) {
+                node.highlight();
+            }
+            writeln!(writer, "    {}{};", quote(arg.label()), node)?;
+        }
+        if let Some(p) = previous {
+            for arg in p.argument_set().iter() {
+                if !framework.argument_set().contains(arg.label()) {
+                    let mut node = Element::default();
+                    node.fade();
+                    node.attributes.push(format!("fontcolor={}", REMOVED_COLOR));
+                    writeln!(writer, "    {}{};", quote(arg.label()), node)?;
+                }
+            }
+        }
+        for att in framework.iter_attacks() {
+            let (from, to) = (att.attacker().label(), att.attacked().label());
+            let mut edge = Element::default();
+            if let Some(attacks) = &previous_attacks {
+                if !attacks.contains(&(from.clone(), to.clone())) {
+                    edge.highlight();
+                }
+            }
+            writeln!(writer, "    {} -> {}{};", quote(from), quote(to), edge)?;
+        }
+        if let Some(p) = previous {
+            let attacks = attacks_of(framework);
+            for att in p.iter_attacks() {
+                let (from, to) = (att.attacker().label(), att.attacked().label());
+                if !attacks.contains(&(from.clone(), to.clone())) {
+                    let mut edge = Element::default();
+                    edge.fade();
+                    writeln!(writer, "    {} -> {}{};", quote(from), quote(to), edge)?;
+                }
+            }
+        }
+        writeln!(writer, "}}")?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// The style and the attributes of a node or an edge.
+#[derive(Default)]
+struct Element {
+    styles: Vec<&'static str>,
+    attributes: Vec<String>,
+}
+
+impl Element {
+    fn fill(&mut self, status: LabellingStatus) {
+        let color = match status {
+            LabellingStatus::In => "palegreen",
+            LabellingStatus::Out => "lightcoral",
+            LabellingStatus::Undec => "khaki",
+        };
+        self.styles.push("filled");
+        self.attributes.push(format!("fillcolor={}", color));
+    }
+
+    fn highlight(&mut self) {
+        self.styles.push("bold");
+        self.attributes.push(format!("color={}", ADDED_COLOR));
+    }
+
+    fn fade(&mut self) {
+        self.styles.push("dashed");
+        self.attributes.push(format!("color={}", REMOVED_COLOR));
+    }
+}
+
+impl std::fmt::Display for Element {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.styles.is_empty() && self.attributes.is_empty() {
+            return Ok(());
+        }
+        let mut attributes = vec![format!(r#"style="{}""#, self.styles.join(","))];
+        attributes.extend(self.attributes.iter().cloned());
+        write!(f, " [{}]", attributes.join(", "))
+    }
+}
+
+fn quote<T: LabelType>(label: &T) -> String {
+    format!(
+        "\"{}\"",
+        label.to_string().replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArgumentSet;
+
+    fn framework(
+        labels: Vec<&'static str>,
+        attacks: &[(&'static str, &'static str)],
+    ) -> AAFramework<&'static str> {
+        let mut framework = AAFramework::new(ArgumentSet::new(labels));
+        for (from, to) in attacks {
+            framework.new_attack(from, to).unwrap();
+        }
+        framework
+    }
+
+    #[test]
+    fn test_write_labelled() {
+        let af = framework(vec!["a", "b", "c"], &[("a", "b"), ("c", "c")]);
+        let mut out = Vec::new();
+        DotWriter::default()
+            .write_labelled(&af, &af.labelling(&af.least_fixpoint()), &mut out)
+            .unwrap();
+        assert_eq!(
+            "digraph {\n    \"a\" [style=\"filled\", fillcolor=palegreen];\n    \"b\" [style=\"filled\", fillcolor=lightcoral];\n    \"c\" [style=\"filled\", fillcolor=khaki];\n    \"a\" -> \"b\";\n    \"c\" -> \"c\";\n}\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_write_labelled_wrong_length() {
+        let af = framework(vec!["a", "b"], &[]);
+        assert!(DotWriter::default()
+            .write_labelled(&af, &[LabellingStatus::In], &mut Vec::new())
+            .is_err());
+    }
+
+    #[test]
+    fn test_write_step() {
+        let previous = framework(vec!["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        let af = framework(vec!["a", "c", "d"], &[("a", "c"), ("d", "a")]);
+        let labelling = af.labelling(&af.least_fixpoint());
+        let mut out = Vec::new();
+        DotWriter::default()
+            .write_step(&previous, &af, Some(&labelling), &mut out)
+            .unwrap();
+        assert_eq!(
+            "digraph {\n    \"a\" [style=\"filled\", fillcolor=lightcoral];\n    \"c\" [style=\"filled\", fillcolor=palegreen];\n    \"d\" [style=\"filled,bold\", fillcolor=palegreen, color=blue];\n    \"b\" [style=\"dashed\", color=gray, fontcolor=gray];\n    \"a\" -> \"c\" [style=\"bold\", color=blue];\n    \"d\" -> \"a\" [style=\"bold\", color=blue];\n    \"a\" -> \"b\" [style=\"dashed\", color=gray];\n    \"b\" -> \"c\" [style=\"dashed\", color=gray];\n}\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(r#""a \"b\" \\c""#, quote(&r#"a "b" \c"#));
+    }
+}
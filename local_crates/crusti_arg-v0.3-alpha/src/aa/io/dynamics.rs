@@ -0,0 +1,425 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! A module used to read and write the modifications of dynamic frameworks.
+//!
+//! Each line of a dynamics file gives a single modification, using an Aspartix-like syntax prefixed by `+` (addition) or `-` (removal):
+//! `+arg(a).`, `-arg(a).`, `+att(a,b).` and `-att(a,b).`.
+//! Labels may be quoted as in the instances (see the [`labels`] module): `+att("first claim","second claim").`.
+//! A dynamics ends at the end of the file or at its first empty line; each modification leads to a new step.
+//!
+//! In the batched dialect, a step may contain several modifications, terminated by an empty line or a `query.` line (see [`read_batches`]).
+//!
+//! [`labels`]: ../labels/index.html
+//! [`read_batches`]: fn.read_batches.html
+use anyhow::{Context, Result};
+use std::{fmt::Display, io::BufRead};
+
+use crate::{
+    aa::io::{
+        labels,
+        parse_error::{first_token_offset, ParseError},
+    },
+    LabelType,
+};
+
+/// A single modification of a dynamic framework.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Modification<T>
+where
+    T: LabelType,
+{
+    /// The addition of an argument.
+    AddArgument(T),
+    /// The removal of an argument.
+    RemoveArgument(T),
+    /// The addition of an attack, given by its attacker and the attacked argument.
+    AddAttack(T, T),
+    /// The removal of an attack, given by its attacker and the attacked argument.
+    RemoveAttack(T, T),
+}
+
+impl<T> Modification<T>
+where
+    T: LabelType,
+{
+    /// Builds a new modification by translating the labels involved in this one.
+    ///
+    /// If the translation function fails, its error is returned.
+    ///
+    /// # Arguments
+    /// * `f` - the label translation function
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::dynamics::Modification;
+    /// let m = Modification::AddAttack("a".to_string(), "b".to_string());
+    /// let translated = m.try_map(|l| Ok(l.len())).unwrap();
+    /// assert_eq!(Modification::AddAttack(1, 1), translated);
+    /// ```
+    pub fn try_map<U, F>(&self, mut f: F) -> Result<Modification<U>>
+    where
+        U: LabelType,
+        F: FnMut(&T) -> Result<U>,
+    {
+        Ok(match self {
+            Modification::AddArgument(a) => Modification::AddArgument(f(a)?),
+            Modification::RemoveArgument(a) => Modification::RemoveArgument(f(a)?),
+            Modification::AddAttack(a, b) => Modification::AddAttack(f(a)?, f(b)?),
+            Modification::RemoveAttack(a, b) => Modification::RemoveAttack(f(a)?, f(b)?),
+        })
+    }
+}
+
+impl<T> Display for Modification<T>
+where
+    T: LabelType,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Modification::AddArgument(a) => write!(f, "+arg({}).", labels::format_label(a)),
+            Modification::RemoveArgument(a) => write!(f, "-arg({}).", labels::format_label(a)),
+            Modification::AddAttack(a, b) => write!(
+                f,
+                "+att({},{}).",
+                labels::format_label(a),
+                labels::format_label(b)
+            ),
+            Modification::RemoveAttack(a, b) => write!(
+                f,
+                "-att({},{}).",
+                labels::format_label(a),
+                labels::format_label(b)
+            ),
+        }
+    }
+}
+
+/// The line ending a step in the batched dialect of the dynamics files.
+pub const QUERY_MARKER: &str = "query.";
+
+/// Reads the steps of a dynamics written in the batched dialect, in which a step may contain several modifications.
+///
+/// The modification lines of a step are terminated by an empty line or a [`QUERY_MARKER`] line; the last step may also be terminated by the end of the reader.
+/// Consecutive empty lines are considered as a single one, while a [`QUERY_MARKER`] line always ends a step, which may then contain no modification.
+/// The syntax of the modifications is not checked.
+///
+/// # Arguments
+/// * `reader` - the reader of the dynamics
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::dynamics;
+/// let batches = dynamics::read_batches(&mut "+arg(a).\n+att(a,b).\n\n\n-arg(b).\nquery.\nquery.\n+arg(c).\n".as_bytes()).unwrap();
+/// assert_eq!(
+///     vec![vec!["+arg(a).", "+att(a,b)."], vec!["-arg(b)."], vec![], vec!["+arg(c)."]],
+///     batches
+/// );
+/// ```
+///
+/// [`QUERY_MARKER`]: constant.QUERY_MARKER.html
+pub fn read_batches(reader: &mut dyn BufRead) -> Result<Vec<Vec<String>>> {
+    let mut batches = vec![];
+    let mut batch = vec![];
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.with_context(|| format!("while reading line {}", i + 1))?;
+        let line = line.trim_end_matches('\r');
+        if line.trim() == QUERY_MARKER {
+            batches.push(std::mem::take(&mut batch));
+        } else if line.trim().is_empty() {
+            if !batch.is_empty() {
+                batches.push(std::mem::take(&mut batch));
+            }
+        } else {
+            batch.push(line.to_string());
+        }
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    Ok(batches)
+}
+
+/// Reads a modification from a line of a dynamics file.
+///
+/// If the line does not match any modification, an error built upon a [`ParseError`] locating the offending token is returned.
+///
+/// # Arguments
+/// * `line` - the line
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::dynamics::{self, Modification};
+/// assert_eq!(
+///     Modification::RemoveAttack("a".to_string(), "b".to_string()),
+///     dynamics::read_modification("-att(a,b).").unwrap()
+/// );
+/// assert_eq!(
+///     r#"column 2: expected "arg(" or "att(", found "arc""#,
+///     dynamics::read_modification("-arc(a,b).").unwrap_err().to_string()
+/// );
+/// ```
+///
+/// [`ParseError`]: ../struct.ParseError.html
+pub fn read_modification(line: &str) -> Result<Modification<String>> {
+    Ok(parse_modification(line)?)
+}
+
+fn parse_modification(line: &str) -> std::result::Result<Modification<String>, ParseError> {
+    let error = |message: &str, offset: usize| ParseError::new(message, line, offset);
+    let mut offset = first_token_offset(line);
+    let addition = match line[offset..].chars().next() {
+        Some('+') => true,
+        Some('-') => false,
+        _ => {
+            return Err(error(
+                r#"expected a modification beginning by "+" or "-""#,
+                offset,
+            ))
+        }
+    };
+    offset += 1;
+    offset += first_token_offset(&line[offset..]);
+    let is_argument = match line.get(offset..offset + 4) {
+        Some("arg(") => true,
+        Some("att(") => false,
+        _ => return Err(error(r#"expected "arg(" or "att(""#, offset)),
+    };
+    offset += 4;
+    let first = labels::parse_label_at(line, &mut offset)?;
+    let second = if is_argument {
+        None
+    } else {
+        if !line[offset..].starts_with(',') {
+            return Err(error("expected a comma", offset));
+        }
+        offset += 1;
+        Some(labels::parse_label_at(line, &mut offset)?)
+    };
+    if !line[offset..].starts_with(").") {
+        return Err(error(r#"expected ").""#, offset));
+    }
+    offset += 2;
+    if !line[offset..].trim().is_empty() {
+        return Err(error(
+            "expected the end of the modification",
+            offset + first_token_offset(&line[offset..]),
+        ));
+    }
+    Ok(match (addition, second) {
+        (true, None) => Modification::AddArgument(first),
+        (false, None) => Modification::RemoveArgument(first),
+        (true, Some(s)) => Modification::AddAttack(first, s),
+        (false, Some(s)) => Modification::RemoveAttack(first, s),
+    })
+}
+
+/// Counts the steps of a dynamics, given by the number of its modification lines.
+///
+/// The lines are read until the end of the reader or the first empty line; their syntax is not checked (see [`validate`]).
+///
+/// # Arguments
+/// * `reader` - the reader of the dynamics
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::dynamics;
+/// assert_eq!(2, dynamics::count_steps(&mut "+arg(a).\r\n+att(a,a).\n\n+arg(b).\n".as_bytes()).unwrap());
+/// ```
+///
+/// [`validate`]: fn.validate.html
+pub fn count_steps(reader: &mut dyn BufRead) -> Result<usize> {
+    let mut n_steps = 0;
+    for line in reader.lines() {
+        let line = line.with_context(|| format!("while reading line {}", n_steps + 1))?;
+        if line.trim_end_matches('\r').is_empty() {
+            break;
+        }
+        n_steps += 1;
+    }
+    Ok(n_steps)
+}
+
+/// Checks the syntax of the modification lines of a dynamics, returning its number of steps.
+///
+/// The lines are read as by [`count_steps`]; the first line which is not a modification makes this function return an error built upon a [`ParseError`], which gives its line number (starting at 1).
+///
+/// # Arguments
+/// * `reader` - the reader of the dynamics
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::dynamics;
+/// assert_eq!(2, dynamics::validate(&mut "+arg(a).\n-att(a,b).\n".as_bytes()).unwrap());
+/// let error = dynamics::validate(&mut "+arg(a).\n+att(a).\n".as_bytes()).unwrap_err();
+/// assert_eq!(r#"line 2, column 7: expected a comma, found ")""#, error.to_string());
+/// ```
+///
+/// [`count_steps`]: fn.count_steps.html
+/// [`ParseError`]: ../struct.ParseError.html
+pub fn validate(reader: &mut dyn BufRead) -> Result<usize> {
+    let mut n_steps = 0;
+    for line in reader.lines() {
+        let context = || format!("while reading line {}", n_steps + 1);
+        let line = line.with_context(context)?;
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() {
+            break;
+        }
+        parse_modification(line).map_err(|e| e.with_line(n_steps + 1))?;
+        n_steps += 1;
+    }
+    Ok(n_steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_modification_ok() {
+        assert_eq!(
+            Modification::AddArgument("a".to_string()),
+            read_modification("+arg(a).").unwrap()
+        );
+        assert_eq!(
+            Modification::RemoveArgument("a".to_string()),
+            read_modification(" -arg( a ). ").unwrap()
+        );
+        assert_eq!(
+            Modification::AddAttack("a".to_string(), "b".to_string()),
+            read_modification("+att(a, b).").unwrap()
+        );
+        assert_eq!(
+            Modification::RemoveAttack("a".to_string(), "b".to_string()),
+            read_modification("-att(a,b).\r\n").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_read_modification_quoted() {
+        assert_eq!(
+            Modification::AddAttack("débat, 1".to_string(), "b".to_string()),
+            read_modification(r#"+att("débat, 1", "b")."#).unwrap()
+        );
+        assert_eq!(
+            Modification::RemoveArgument("a \"b\")".to_string()),
+            read_modification(r#"-arg("a \"b\")")."#).unwrap()
+        );
+        assert!(read_modification(r#"+arg("a)."#).is_err());
+    }
+
+    #[test]
+    fn test_read_modification_wrong_arity() {
+        assert!(read_modification("+arg(a,b).").is_err());
+        assert!(read_modification("+att(a).").is_err());
+    }
+
+    #[test]
+    fn test_read_modification_syntax_error() {
+        [
+            "arg(a).",
+            "*arg(a).",
+            "+arg(a)",
+            "+arg(1a).",
+            "+foo(a).",
+            "",
+        ]
+        .iter()
+        .for_each(|l| assert!(read_modification(l).is_err()));
+    }
+
+    #[test]
+    fn test_read_modification_error_position() {
+        let position = |l| {
+            let error = read_modification(l).unwrap_err();
+            let error = error.downcast_ref::<ParseError>().unwrap();
+            (error.column(), error.token().to_string())
+        };
+        assert_eq!((1, "*arg".to_string()), position("*arg(a)."));
+        assert_eq!((7, ",".to_string()), position("+arg(a,b)."));
+        assert_eq!((9, "1".to_string()), position("+att(a, 1)."));
+        assert_eq!((10, "x".to_string()), position("-arg(a). x"));
+        assert_eq!((7, "".to_string()), position("-arg(a"));
+    }
+
+    #[test]
+    fn test_count_steps() {
+        assert_eq!(0, count_steps(&mut "".as_bytes()).unwrap());
+        assert_eq!(0, count_steps(&mut "\n+arg(a).\n".as_bytes()).unwrap());
+        assert_eq!(2, count_steps(&mut "+arg(a).\nfoo\n".as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(0, validate(&mut "".as_bytes()).unwrap());
+        assert_eq!(
+            1,
+            validate(&mut "+arg(a).\r\n\r\nfoo\n".as_bytes()).unwrap()
+        );
+        let error = validate(&mut "+arg(a).\n-arg(b).\n*arg(c).\n".as_bytes()).unwrap_err();
+        let error = error.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(Some(3), error.line());
+        assert_eq!("*arg", error.token());
+    }
+
+    #[test]
+    fn test_read_batches() {
+        assert!(read_batches(&mut "".as_bytes()).unwrap().is_empty());
+        assert!(read_batches(&mut "\n\r\n".as_bytes()).unwrap().is_empty());
+        assert_eq!(
+            vec![vec!["+arg(a)."], vec!["+arg(b).", "+arg(c)."]],
+            read_batches(&mut "+arg(a).\r\n query. \r\n+arg(b).\n+arg(c).\n\n".as_bytes()).unwrap()
+        );
+        assert_eq!(
+            vec![Vec::<String>::new(), vec![]],
+            read_batches(&mut "query.\n\nquery.\n".as_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        [
+            Modification::AddArgument("a".to_string()),
+            Modification::RemoveArgument("a".to_string()),
+            Modification::AddAttack("a".to_string(), "b".to_string()),
+            Modification::RemoveAttack("a".to_string(), "b".to_string()),
+            Modification::AddAttack("a b".to_string(), "c\\d".to_string()),
+        ]
+        .iter()
+        .for_each(|m| assert_eq!(*m, read_modification(&m.to_string()).unwrap()));
+    }
+
+    #[test]
+    fn test_try_map_err() {
+        let m = Modification::AddAttack("a".to_string(), "b".to_string());
+        assert!(m
+            .try_map(|l| if l == "a" {
+                Ok(0)
+            } else {
+                Err(anyhow::anyhow!("no id"))
+            })
+            .is_err());
+    }
+}
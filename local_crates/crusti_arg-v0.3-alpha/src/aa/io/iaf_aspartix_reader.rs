@@ -0,0 +1,224 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::io::aspartix_reader::{
+    captured_arg, try_read_arg_line, try_read_att_line, WarningHandler, ARG_AND_SPACE_PATTERN,
+};
+use crate::aa::io::labels::QUOTED_LABEL_PATTERN;
+use crate::IncompleteAAFramework;
+use anyhow::{anyhow, Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    io::{BufRead, BufReader, Read},
+    rc::Rc,
+};
+
+lazy_static! {
+    static ref UNCERTAIN_ARG_LINE_PATTERN: Regex = Regex::new(&format!(
+        r#"^\s*\?arg\((?:[^)"]|{})+\).\s*$"#,
+        QUOTED_LABEL_PATTERN
+    ))
+    .unwrap();
+    static ref UNCERTAIN_ARG_LINE_ARG_NAME_PATTERN: Regex =
+        Regex::new(&format!(r"^\s*\?arg\(({})\).\s*$", ARG_AND_SPACE_PATTERN)).unwrap();
+    static ref UNCERTAIN_ATT_LINE_PATTERN: Regex = Regex::new(&format!(
+        r#"^\s*\?att\((?:[^,"]|{})+,(?:[^)"]|{})+\).\s*$"#,
+        QUOTED_LABEL_PATTERN, QUOTED_LABEL_PATTERN
+    ))
+    .unwrap();
+    static ref UNCERTAIN_ATT_LINE_ARG_NAMES_PATTERN: Regex = Regex::new(&format!(
+        r"^\s*\?att\(({}),({})\).\s*$",
+        ARG_AND_SPACE_PATTERN, ARG_AND_SPACE_PATTERN,
+    ))
+    .unwrap();
+}
+
+/// A reader for the incomplete Aspartix dialect.
+///
+/// This dialect extends the Aspartix format by uncertain arguments `?arg(a).` and uncertain attacks `?att(a,b).`.
+/// The arguments must be declared before the attacks, and an argument cannot be declared twice.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{IafAspartixReader, IncompleteAAFramework};
+/// fn read_iaf_from_str(s: &str) -> IncompleteAAFramework<String> {
+///     let reader = IafAspartixReader::default();
+///     reader.read(&mut s.as_bytes()).expect("invalid incomplete Aspartix AF")
+/// }
+/// let iaf = read_iaf_from_str("arg(a).\n?arg(b).\n?att(b,a).");
+/// assert_eq!(3, iaf.iter_completions().count());
+/// ```
+#[derive(Default)]
+pub struct IafAspartixReader<'a> {
+    warning_handlers: Vec<WarningHandler<'a>>,
+}
+
+impl<'a> IafAspartixReader<'a> {
+    /// Reads an [`IncompleteAAFramework`] encoded using the incomplete Aspartix dialect.
+    ///
+    /// In case warnings are raised, the callback functions registered by [`add_warning_handler`] are triggered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{IafAspartixReader, IncompleteAAFramework};
+    /// fn read_iaf_from_str(s: &str) -> IncompleteAAFramework<String> {
+    ///     let reader = IafAspartixReader::default();
+    ///     reader.read(&mut s.as_bytes()).expect("invalid incomplete Aspartix AF")
+    /// }
+    /// # read_iaf_from_str("arg(a).\n?att(a,a).");
+    /// ```
+    ///
+    /// [`IncompleteAAFramework`]: struct.IncompleteAAFramework.html
+    /// [`add_warning_handler`]: struct.IafAspartixReader.html#method.add_warning_handler
+    pub fn read(&self, reader: &mut dyn Read) -> Result<IncompleteAAFramework<String>> {
+        let mut arg_labels = Some((vec![], vec![]));
+        let mut known_labels = HashSet::new();
+        let mut iaf: Option<IncompleteAAFramework<String>> = None;
+        for (line_index, line) in BufReader::new(reader).lines().enumerate() {
+            let context = || format!("while reading line {}", line_index);
+            let warning_consumer = |warnings: Vec<String>| {
+                for w in warnings.iter() {
+                    self.warning_handlers
+                        .iter()
+                        .for_each(|h| (*h.borrow_mut())(line_index, w.to_string()));
+                }
+            };
+            let l = &line.with_context(context)?;
+            if l.trim().is_empty() {
+                continue;
+            }
+            let declaration = if let Some(a) = try_read_arg_line(l).with_context(context)? {
+                Some((false, a.consume_warnings(warning_consumer)))
+            } else if UNCERTAIN_ARG_LINE_PATTERN.is_match(l) {
+                match UNCERTAIN_ARG_LINE_ARG_NAME_PATTERN.captures(l) {
+                    Some(c) => Some((true, captured_arg(&c, 1).consume_warnings(warning_consumer))),
+                    None => {
+                        return Err(anyhow!("invalid argument name in {}", l.trim()))
+                            .with_context(context)
+                    }
+                }
+            } else {
+                None
+            };
+            if let Some((uncertain, label)) = declaration {
+                if iaf.is_some() {
+                    return Err(anyhow!("found an argument declaration after an attack"))
+                        .with_context(context);
+                }
+                if !known_labels.insert(label.clone()) {
+                    return Err(anyhow!("argument {} is declared more than once", label))
+                        .with_context(context);
+                }
+                let (certain_labels, uncertain_labels) = arg_labels.as_mut().unwrap();
+                if uncertain {
+                    uncertain_labels.push(label);
+                } else {
+                    certain_labels.push(label);
+                }
+                continue;
+            }
+            let framework = iaf.get_or_insert_with(|| {
+                let (certain, uncertain) = arg_labels.take().unwrap();
+                IncompleteAAFramework::new(certain, uncertain)
+            });
+            if let Some(result) = try_read_att_line(l).with_context(context)? {
+                let (a, b) = result.consume_warnings(warning_consumer);
+                framework.new_attack(&a, &b).with_context(context)?;
+                continue;
+            }
+            if UNCERTAIN_ATT_LINE_PATTERN.is_match(l) {
+                let (a, b) = match UNCERTAIN_ATT_LINE_ARG_NAMES_PATTERN.captures(l) {
+                    Some(c) => captured_arg(&c, 1)
+                        .zip(captured_arg(&c, 2))
+                        .consume_warnings(warning_consumer),
+                    None => {
+                        return Err(anyhow!("invalid argument names in {}", l.trim()))
+                            .with_context(context)
+                    }
+                };
+                framework
+                    .new_uncertain_attack(&a, &b)
+                    .with_context(context)?;
+                continue;
+            }
+            return Err(anyhow!("syntax error in line \"{}\"", l)).with_context(context);
+        }
+        match iaf {
+            Some(i) => Ok(i),
+            None => {
+                let (certain, uncertain) = arg_labels.take().unwrap();
+                Ok(IncompleteAAFramework::new(certain, uncertain))
+            }
+        }
+    }
+
+    /// Adds a callback function to call when warnings are raised while parsing an IAF.
+    pub fn add_warning_handler(&mut self, h: &'a mut dyn FnMut(usize, String)) {
+        self.warning_handlers.push(Rc::new(RefCell::new(h)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(instance: &str) -> Result<IncompleteAAFramework<String>> {
+        IafAspartixReader::default().read(&mut instance.as_bytes())
+    }
+
+    #[test]
+    fn test_read_ok() {
+        let iaf = read("?arg(\"c 1\").\narg(a).\narg(b).\natt(\"c 1\",a).\n?att( b,a).\n").unwrap();
+        assert_eq!(
+            vec!["a", "b", "c 1"],
+            iaf.argument_set()
+                .iter()
+                .map(|a| a.label().as_str())
+                .collect::<Vec<&str>>()
+        );
+        assert!(iaf.is_uncertain(&"c 1".to_string()).unwrap());
+        assert!(!iaf.is_uncertain(&"a".to_string()).unwrap());
+        assert_eq!(1, iaf.iter_attacks().count());
+        assert_eq!(1, iaf.iter_uncertain_attacks().count());
+    }
+
+    #[test]
+    fn test_read_no_relations() {
+        let iaf = read("?arg(a).\n").unwrap();
+        assert_eq!(2, iaf.iter_completions().count());
+    }
+
+    #[test]
+    fn test_read_errors() {
+        [
+            "arg(a).\n?att(a,b).\n",
+            "arg(a).\n?att(a,1b).\n",
+            "arg(a).\n?att(a,a).\n?arg(b).\n",
+            "arg(a).\n?arg(a).\n",
+            "arg(a).\n!att(a,a).\n",
+        ]
+        .iter()
+        .for_each(|i| assert!(read(i).is_err(), "{}", i));
+    }
+}
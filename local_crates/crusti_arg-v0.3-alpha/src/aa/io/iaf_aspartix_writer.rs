@@ -0,0 +1,118 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::arguments::LabelType;
+use crate::aa::io::labels;
+use crate::IncompleteAAFramework;
+use anyhow::Result;
+use std::io::Write;
+
+/// A writer for the incomplete Aspartix dialect.
+///
+/// This object writes an [`IncompleteAAFramework`] using `arg(a).` and `?arg(a).` lines for its certain and uncertain arguments,
+/// followed by `att(a,b).` and `?att(a,b).` lines for its certain and uncertain attacks.
+/// The arguments are written in the order of their ids, and the attacks in the order they were added.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{IafAspartixWriter, IncompleteAAFramework};
+/// let mut framework = IncompleteAAFramework::new(vec!["a"], vec!["b"]);
+/// framework.new_uncertain_attack(&"b", &"a").unwrap();
+/// let mut out = Vec::new();
+/// IafAspartixWriter::default().write(&framework, &mut out).unwrap();
+/// assert_eq!("arg(a).\n?arg(b).\n?att(b,a).\n", String::from_utf8(out).unwrap());
+/// ```
+///
+/// [`IncompleteAAFramework`]: struct.IncompleteAAFramework.html
+#[derive(Default)]
+pub struct IafAspartixWriter {}
+
+impl IafAspartixWriter {
+    /// Writes an incomplete framework using the incomplete Aspartix dialect to the provided writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `framework` - the framework
+    /// * `writer` - the writer
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::IafAspartixWriter;
+    /// # use crusti_arg::IncompleteAAFramework;
+    /// # use crusti_arg::LabelType;
+    /// # use anyhow::Result;
+    /// fn write_iaf_to_stdout<T: LabelType>(iaf: &IncompleteAAFramework<T>) -> Result<()> {
+    ///     let writer = IafAspartixWriter::default();
+    ///     writer.write(&iaf, &mut std::io::stdout())
+    /// }
+    /// # write_iaf_to_stdout(&IncompleteAAFramework::new(vec![] as Vec<String>, vec![]));
+    /// ```
+    pub fn write<T: LabelType>(
+        &self,
+        framework: &IncompleteAAFramework<T>,
+        writer: &mut dyn Write,
+    ) -> Result<()> {
+        for arg in framework.argument_set().iter() {
+            let prefix = if framework.is_uncertain(arg.label())? {
+                "?"
+            } else {
+                ""
+            };
+            writeln!(writer, "{}arg({}).", prefix, labels::format_label(arg))?;
+        }
+        for (prefix, attacks) in [
+            ("", framework.iter_attacks()),
+            ("?", framework.iter_uncertain_attacks()),
+        ] {
+            for (a, b) in attacks {
+                writeln!(
+                    writer,
+                    "{}att({},{}).",
+                    prefix,
+                    labels::format_label(a),
+                    labels::format_label(b)
+                )?;
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IafAspartixReader;
+
+    #[test]
+    fn test_write_read() {
+        let instance =
+            "arg(a).\narg(b).\n?arg(\"c 1\").\natt(\"c 1\",a).\n?att(b,a).\n?att(b,\"c 1\").\n";
+        let framework = IafAspartixReader::default()
+            .read(&mut instance.as_bytes())
+            .unwrap();
+        let mut out = Vec::new();
+        IafAspartixWriter::default()
+            .write(&framework, &mut out)
+            .unwrap();
+        assert_eq!(instance, String::from_utf8(out).unwrap());
+    }
+}
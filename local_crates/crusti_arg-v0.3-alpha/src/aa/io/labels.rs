@@ -0,0 +1,218 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! A module used to read and write the labels of the arguments.
+//!
+//! In the Aspartix-like formats (instances, dynamics and solver answers), a label is either an identifier
+//! (letters, digits and underscores, not beginning with a digit) or any UTF-8 string surrounded by double quotes.
+//! Inside quotes, double quotes and backslashes are escaped by a backslash: `"the \"best\" option, by far"`.
+//! The quotes and escape characters are not part of the label: `arg("a").` and `arg(a).` declare the same argument.
+use anyhow::{anyhow, Result};
+use std::{borrow::Cow, fmt::Display};
+
+use crate::aa::io::parse_error::{first_token_offset, ParseError};
+
+macro_rules! label_pattern {
+    () => {
+        r#"(?:[_[:alpha:]][_[:alpha:]\d]*|"(?:[^"\\]|\\["\\])*")"#
+    };
+}
+
+/// The regular expression matching a label surrounded by optional whitespace, without capturing groups.
+pub(crate) const LABEL_AND_SPACE_PATTERN: &str = concat!(r"\s*", label_pattern!(), r"\s*");
+
+/// The regular expression matching a quoted label, without capturing groups.
+pub(crate) const QUOTED_LABEL_PATTERN: &str = r#""(?:[^"\\]|\\.)*""#;
+
+/// Formats a label so that it can be written in an Aspartix-like format.
+///
+/// Labels made only of ASCII letters, digits and underscores are written as they are, so that numeric labels are left untouched.
+/// Other labels are quoted, escaping their double quotes and backslashes.
+///
+/// # Arguments
+/// * `label` - the label
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::labels;
+/// assert_eq!("a_1", labels::format_label(&"a_1"));
+/// assert_eq!("17", labels::format_label(&17));
+/// assert_eq!(r#""débat, \"tour\" 1""#, labels::format_label(&r#"débat, "tour" 1"#));
+/// ```
+pub fn format_label<T>(label: &T) -> String
+where
+    T: Display + ?Sized,
+{
+    let label = label.to_string();
+    if !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return label;
+    }
+    let mut quoted = String::with_capacity(label.len() + 2);
+    quoted.push('"');
+    for c in label.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Reads a label written in an Aspartix-like format, removing its quotes and escape characters if it is quoted.
+///
+/// An error is returned if the string is neither an identifier nor a well-formed quoted label.
+///
+/// # Arguments
+/// * `s` - the written label, without surrounding whitespace
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::labels;
+/// assert_eq!("a_1", labels::parse_label("a_1").unwrap());
+/// assert_eq!(r#"débat, "tour" 1"#, labels::parse_label(r#""débat, \"tour\" 1""#).unwrap());
+/// assert!(labels::parse_label("a b").is_err());
+/// ```
+pub fn parse_label(s: &str) -> Result<String> {
+    match scan_label(s) {
+        Some((l, len)) if len == s.len() => Ok(l.into_owned()),
+        _ => Err(anyhow!(r#"invalid label "{}""#, s)),
+    }
+}
+
+/// Reads a label at the beginning of a string, either an identifier or a quoted string.
+///
+/// Returns the label and the number of bytes it spans, or `None` if the string does not begin by a label.
+/// The label is borrowed from the string unless it contains escape characters.
+pub(crate) fn scan_label(s: &str) -> Option<(Cow<'_, str>, usize)> {
+    match s.chars().next()? {
+        '"' => scan_quoted_label(s),
+        c if c.is_alphabetic() || c == '_' => {
+            let len = s
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .unwrap_or(s.len());
+            Some((Cow::Borrowed(&s[..len]), len))
+        }
+        _ => None,
+    }
+}
+
+/// Reads a quoted label at the beginning of a string, as [`scan_label`] does.
+///
+/// The string is scanned by bytes, as the quotes and backslashes cannot be part of multibyte characters.
+///
+/// [`scan_label`]: fn.scan_label.html
+fn scan_quoted_label(s: &str) -> Option<(Cow<'_, str>, usize)> {
+    let bytes = s.as_bytes();
+    let mut unescaped: Option<String> = None;
+    let mut chunk_start = 1;
+    let mut i = 1;
+    loop {
+        match bytes.get(i)? {
+            b'"' => break,
+            b'\\' => match bytes.get(i + 1)? {
+                b'"' | b'\\' => {
+                    let label = unescaped.get_or_insert_with(String::new);
+                    label.push_str(&s[chunk_start..i]);
+                    chunk_start = i + 1;
+                    i += 2;
+                }
+                _ => return None,
+            },
+            _ => i += 1,
+        }
+    }
+    let label = match unescaped {
+        None => Cow::Borrowed(&s[1..i]),
+        Some(mut l) => {
+            l.push_str(&s[chunk_start..i]);
+            Cow::Owned(l)
+        }
+    };
+    Some((label, i + 1))
+}
+
+/// Reads a label surrounded by optional whitespace, starting at a byte offset which is moved after it.
+pub(crate) fn parse_label_at(
+    line: &str,
+    offset: &mut usize,
+) -> std::result::Result<String, ParseError> {
+    *offset += first_token_offset(&line[*offset..]);
+    match scan_label(&line[*offset..]) {
+        Some((l, len)) => {
+            *offset += len;
+            *offset += first_token_offset(&line[*offset..]);
+            Ok(l.into_owned())
+        }
+        None => Err(ParseError::new("expected an argument label", line, *offset)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_label() {
+        assert_eq!("a", format_label("a"));
+        assert_eq!(r#""""#, format_label(""));
+        assert_eq!(r#""a b""#, format_label("a b"));
+        assert_eq!(r#""a\\b""#, format_label(r#"a\b"#));
+        assert_eq!(r#""été""#, format_label("été"));
+    }
+
+    #[test]
+    fn test_parse_label() {
+        assert_eq!("a", parse_label("a").unwrap());
+        assert_eq!("", parse_label(r#""""#).unwrap());
+        assert_eq!("a, b)", parse_label(r#""a, b)""#).unwrap());
+        assert_eq!(r#"a\b"#, parse_label(r#""a\\b""#).unwrap());
+        assert_eq!("été", parse_label(r#""été""#).unwrap());
+        assert_eq!("été", parse_label("été").unwrap());
+        ["1a", "", r#""a"#, r#""a\b""#, r#""a"b"#, "a b"]
+            .iter()
+            .for_each(|s| assert!(parse_label(s).is_err()));
+    }
+
+    #[test]
+    fn test_format_parse_roundtrip() {
+        ["a", "a b", r#""quoted" \ label"#, "débat, tour 1", "_"]
+            .iter()
+            .for_each(|l| assert_eq!(*l, parse_label(&format_label(*l)).unwrap()));
+    }
+
+    #[test]
+    fn test_scan_label_borrows() {
+        assert!(matches!(
+            scan_label("a_1,b"),
+            Some((Cow::Borrowed("a_1"), 3))
+        ));
+        assert!(matches!(
+            scan_label(r#""été" x"#),
+            Some((Cow::Borrowed("été"), 7))
+        ));
+        assert!(matches!(
+            scan_label(r#""a\"b\\"]"#),
+            Some((Cow::Owned(l), 8)) if l == r#"a"b\"#
+        ));
+        assert!(scan_label("1a").is_none());
+    }
+}
@@ -19,4 +19,23 @@
 
 pub(crate) mod aspartix_reader;
 pub(crate) mod aspartix_writer;
+pub(crate) mod bipolar_aspartix_reader;
+pub(crate) mod bipolar_aspartix_writer;
+pub(crate) mod caf_aspartix_reader;
+pub(crate) mod caf_aspartix_writer;
+pub(crate) mod claim_aspartix_reader;
+pub(crate) mod claim_aspartix_writer;
+pub mod compression;
+pub(crate) mod dot_writer;
+pub mod dynamics;
+pub(crate) mod iaf_aspartix_reader;
+pub(crate) mod iaf_aspartix_writer;
+pub mod labels;
+pub(crate) mod numeric_writer;
+pub(crate) mod parse_error;
+pub(crate) mod setaf_aspartix_reader;
+pub(crate) mod setaf_aspartix_writer;
 pub mod solutions;
+pub mod warmstart;
+pub(crate) mod weighted_aspartix_reader;
+pub(crate) mod weighted_aspartix_writer;
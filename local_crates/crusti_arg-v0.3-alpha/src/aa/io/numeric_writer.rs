@@ -0,0 +1,111 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::aa_framework::AAFramework;
+use crate::aa::arguments::LabelType;
+use anyhow::Result;
+use std::io::Write;
+
+/// A writer for the numeric format.
+///
+/// In this format, arguments are only known by their identifiers, which are the integers from 1 to `n` (`n` being the number of arguments).
+/// The first line is a header `p af n` giving the number of arguments, and each following line gives an attack by the identifiers of the attacker and the attacked argument, separated by a space.
+///
+/// The identifier of an argument in the numeric format is its id in the [`ArgumentSet`] plus one.
+/// The attacks are written in the order they were added to the framework, so that writing a framework always gives the same output.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::AAFramework;
+/// # use crusti_arg::ArgumentSet;
+/// # use crusti_arg::NumericWriter;
+/// # use crusti_arg::LabelType;
+/// # use anyhow::Result;
+/// fn write_af_to_stdout<T: LabelType>(af: &AAFramework<T>) -> Result<()> {
+///     let writer = NumericWriter::default();
+///     writer.write(&af, &mut std::io::stdout())
+/// }
+/// # write_af_to_stdout(&AAFramework::new(ArgumentSet::new(vec![] as Vec<String>)));
+/// ```
+///
+/// [`ArgumentSet`]: struct.ArgumentSet.html
+#[derive(Default)]
+pub struct NumericWriter {}
+
+impl NumericWriter {
+    /// Writes a framework using the numeric format to the provided writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `framework` - the framework
+    /// * `writer` - the writer
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::AAFramework;
+    /// # use crusti_arg::ArgumentSet;
+    /// # use crusti_arg::NumericWriter;
+    /// # use crusti_arg::LabelType;
+    /// # use anyhow::Result;
+    /// fn write_af_to_stdout<T: LabelType>(af: &AAFramework<T>) -> Result<()> {
+    ///     let writer = NumericWriter::default();
+    ///     writer.write(&af, &mut std::io::stdout())
+    /// }
+    /// # write_af_to_stdout(&AAFramework::new(ArgumentSet::new(vec![] as Vec<String>)));
+    /// ```
+    pub fn write<T: LabelType>(
+        &self,
+        framework: &AAFramework<T>,
+        writer: &mut dyn Write,
+    ) -> Result<()> {
+        writeln!(writer, "p af {}", framework.argument_set().len())?;
+        for attack in framework.iter_attacks() {
+            writeln!(
+                writer,
+                "{} {}",
+                attack.attacker().id() + 1,
+                attack.attacked().id() + 1,
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aa::arguments::ArgumentSet;
+    use crate::utils::writable_string::WritableString;
+
+    #[test]
+    fn test_write() {
+        let arg_names = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let args = ArgumentSet::new(arg_names.clone());
+        let mut framework = AAFramework::new(args);
+        framework.new_attack(&arg_names[0], &arg_names[0]).unwrap();
+        framework.new_attack(&arg_names[1], &arg_names[2]).unwrap();
+        let mut result = WritableString::default();
+        let writer = NumericWriter::default();
+        writer.write(&framework, &mut result).unwrap();
+        assert_eq!("p af 3\n1 1\n2 3\n", result.to_string())
+    }
+}
@@ -0,0 +1,204 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::fmt::Display;
+
+/// The maximal number of characters of the offending tokens reported by the errors.
+const MAX_TOKEN_LEN: usize = 32;
+
+/// An error raised by a parser, locating the offending token in its input.
+///
+/// The column is the position of the token (starting at 1, in characters) in its line.
+/// The line number (starting at 1) is only known when the parser reads a whole input; functions parsing a single line leave it to their callers, which may set it with [`with_line`].
+///
+/// The parsers of the crate return their syntax errors as `anyhow` errors built upon a `ParseError`, which can be retrieved by downcasting them.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{solutions, ParseError};
+/// let error = solutions::read_extension(&mut "[a, b; c]\n".as_bytes()).err().unwrap();
+/// let parse_error = error.downcast_ref::<ParseError>().unwrap();
+/// assert_eq!(Some(1), parse_error.line());
+/// assert_eq!(6, parse_error.column());
+/// assert_eq!(";", parse_error.token());
+/// assert_eq!(r#"line 1, column 6: expected an extension line, found ";""#, error.to_string());
+/// ```
+///
+/// [`with_line`]: #method.with_line
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    line: Option<usize>,
+    column: usize,
+    token: String,
+    message: String,
+}
+
+impl ParseError {
+    /// Builds a new error for the token beginning at a byte offset of a line.
+    ///
+    /// The token is made of the characters following the offset up to the next whitespace or delimiter (one of `,()[]`), or of the delimiter itself.
+    /// An offset equal to the length of the line denotes its end.
+    ///
+    /// # Arguments
+    /// * `message` - the error message, telling what was expected
+    /// * `line` - the content of the line
+    /// * `offset` - the byte offset of the offending token in the line
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::ParseError;
+    /// let error = ParseError::new("expected a modification", "+arc(a,b).", 1);
+    /// assert_eq!(None, error.line());
+    /// assert_eq!(2, error.column());
+    /// assert_eq!("arc", error.token());
+    /// ```
+    pub fn new<S>(message: S, line: &str, offset: usize) -> Self
+    where
+        S: Into<String>,
+    {
+        let rest = line[offset..].trim_end_matches(['\r', '\n']);
+        let is_delimiter = |c: char| ",()[]".contains(c);
+        let token_len = match rest.chars().next() {
+            Some(c) if is_delimiter(c) || c.is_whitespace() => c.len_utf8(),
+            Some(_) => rest
+                .find(|c: char| c.is_whitespace() || is_delimiter(c))
+                .unwrap_or(rest.len()),
+            None => 0,
+        };
+        ParseError {
+            line: None,
+            column: line[..offset].chars().count() + 1,
+            token: rest[..token_len].chars().take(MAX_TOKEN_LEN).collect(),
+            message: message.into(),
+        }
+    }
+
+    /// Sets the number of the line (starting at 1) the error occurred at.
+    ///
+    /// # Arguments
+    /// * `line` - the line number
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::ParseError;
+    /// let error = ParseError::new("expected a modification", "+arc(a,b).", 1).with_line(3);
+    /// assert_eq!(Some(3), error.line());
+    /// ```
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    /// Returns the number of the line (starting at 1) the error occurred at, if it is known.
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Returns the column (starting at 1, in characters) of the offending token.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the offending token, which is empty if the error occurred at the end of the line.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Returns the error message, telling what was expected.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(l) = self.line {
+            write!(f, "line {}, ", l)?;
+        }
+        write!(f, "column {}: {}, ", self.column, self.message)?;
+        if self.token.is_empty() {
+            write!(f, "found the end of the line")
+        } else {
+            write!(f, r#"found "{}""#, self.token)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Sets the line number of an error if it is built upon a [`ParseError`] which does not have one yet.
+pub(crate) fn locate(error: anyhow::Error, line: usize) -> anyhow::Error {
+    match error.downcast_ref::<ParseError>() {
+        Some(e) if e.line.is_none() => e.clone().with_line(line).into(),
+        _ => error,
+    }
+}
+
+/// Returns the byte offset of the first non-whitespace character of a line, or its length if there is none.
+pub(crate) fn first_token_offset(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token() {
+        let token = |line, offset| ParseError::new("", line, offset).token().to_string();
+        assert_eq!("arg", token("  arg(a).", 2));
+        assert_eq!("(", token("arg(a).", 3));
+        assert_eq!("a.", token("arg(a.\r\n", 4));
+        assert_eq!("", token("arg(a).\n", 7));
+        assert_eq!(MAX_TOKEN_LEN, token(&"a".repeat(100), 0).len());
+    }
+
+    #[test]
+    fn test_column_counts_characters() {
+        assert_eq!(4, ParseError::new("", "éé x", 5).column());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            "column 8: expected a dot, found the end of the line",
+            ParseError::new("expected a dot", "arg(a))", 7).to_string()
+        );
+        assert_eq!(
+            r#"line 2, column 1: expected an argument, found "x""#,
+            ParseError::new("expected an argument", "x", 0)
+                .with_line(2)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_locate() {
+        let error = locate(ParseError::new("", "x", 0).into(), 3);
+        assert_eq!(Some(3), error.downcast_ref::<ParseError>().unwrap().line());
+        let error = locate(ParseError::new("", "x", 0).with_line(1).into(), 3);
+        assert_eq!(Some(1), error.downcast_ref::<ParseError>().unwrap().line());
+        assert!(locate(anyhow::anyhow!("other"), 3)
+            .downcast_ref::<ParseError>()
+            .is_none());
+    }
+}
@@ -0,0 +1,193 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::io::aspartix_reader::{
+    captured_arg, try_read_arg_line, try_read_att_line, WarningHandler, ARG_AND_SPACE_PATTERN,
+};
+use crate::{ArgumentSet, SetAFramework};
+use anyhow::{anyhow, Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io::{BufRead, BufReader, Read},
+    rc::Rc,
+};
+
+lazy_static! {
+    static ref MEM_LINE_PATTERN: Regex = Regex::new(r"^\s*mem\([^,]+,[^)]+\).\s*$").unwrap();
+    static ref MEM_LINE_ARG_NAMES_PATTERN: Regex = Regex::new(&format!(
+        r"^\s*mem\(({}),({})\).\s*$",
+        ARG_AND_SPACE_PATTERN, ARG_AND_SPACE_PATTERN,
+    ))
+    .unwrap();
+}
+
+/// A reader for the SETAF Aspartix dialect.
+///
+/// In this dialect, each collective attack is given a name.
+/// The line `att(r,b).` declares an attack named `r` targeting the argument `b`, while the lines `mem(r,a).` add the argument `a` to the attackers of `r`.
+/// Attack and member declarations must follow the argument declarations, and may be given in any order.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{SetAFramework, SetafAspartixReader};
+/// fn read_setaf_from_str(s: &str) -> SetAFramework<String> {
+///     let reader = SetafAspartixReader::default();
+///     reader.read(&mut s.as_bytes()).expect("invalid SETAF Aspartix AF")
+/// }
+/// let setaf = read_setaf_from_str("arg(a).\narg(b).\narg(c).\natt(r1,c).\nmem(r1,a).\nmem(r1,b).");
+/// assert_eq!(2, setaf.iter_attacks().next().unwrap().attackers().len());
+/// ```
+#[derive(Default)]
+pub struct SetafAspartixReader<'a> {
+    warning_handlers: Vec<WarningHandler<'a>>,
+}
+
+impl<'a> SetafAspartixReader<'a> {
+    /// Reads a [`SetAFramework`] encoded using the SETAF Aspartix dialect.
+    ///
+    /// Attacks are added to the framework in the order of their `att` declarations.
+    /// An error is returned if an attack has no member, or if a member is given for an undeclared attack.
+    ///
+    /// In case warnings are raised, the callback functions registered by [`add_warning_handler`] are triggered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{SetAFramework, SetafAspartixReader};
+    /// fn read_setaf_from_str(s: &str) -> SetAFramework<String> {
+    ///     let reader = SetafAspartixReader::default();
+    ///     reader.read(&mut s.as_bytes()).expect("invalid SETAF Aspartix AF")
+    /// }
+    /// # read_setaf_from_str("arg(a).\natt(r,a).\nmem(r,a).");
+    /// ```
+    ///
+    /// [`SetAFramework`]: struct.SetAFramework.html
+    /// [`add_warning_handler`]: struct.SetafAspartixReader.html#method.add_warning_handler
+    pub fn read(&self, reader: &mut dyn Read) -> Result<SetAFramework<String>> {
+        let mut arg_labels = vec![];
+        let mut attacks: Vec<(String, String)> = vec![];
+        let mut members: HashMap<String, Vec<String>> = HashMap::new();
+        for (line_index, line) in BufReader::new(reader).lines().enumerate() {
+            let context = || format!("while reading line {}", line_index);
+            let warning_consumer = |warnings: Vec<String>| {
+                for w in warnings.iter() {
+                    self.warning_handlers
+                        .iter()
+                        .for_each(|h| (*h.borrow_mut())(line_index, w.to_string()));
+                }
+            };
+            let l = &line.with_context(context)?;
+            if l.trim().is_empty() {
+                continue;
+            }
+            if let Some(a) = try_read_arg_line(l).with_context(context)? {
+                if !attacks.is_empty() || !members.is_empty() {
+                    return Err(anyhow!("found an argument declaration after an attack"))
+                        .with_context(context);
+                }
+                arg_labels.push(a.consume_warnings(warning_consumer));
+                continue;
+            }
+            if let Some(result) = try_read_att_line(l).with_context(context)? {
+                let (name, attacked) = result.consume_warnings(warning_consumer);
+                if attacks.iter().any(|(n, _)| *n == name) {
+                    return Err(anyhow!(r#"attack "{}" is declared twice"#, name))
+                        .with_context(context);
+                }
+                attacks.push((name, attacked));
+                continue;
+            }
+            if MEM_LINE_PATTERN.is_match(l) {
+                let (name, member) = match MEM_LINE_ARG_NAMES_PATTERN.captures(l) {
+                    Some(c) => captured_arg(&c, 1)
+                        .zip(captured_arg(&c, 2))
+                        .consume_warnings(warning_consumer),
+                    None => {
+                        return Err(anyhow!("invalid argument names in {}", l.trim()))
+                            .with_context(context)
+                    }
+                };
+                members.entry(name).or_default().push(member);
+                continue;
+            }
+            return Err(anyhow!("syntax error in line \"{}\"", l)).with_context(context);
+        }
+        let mut framework = SetAFramework::new(ArgumentSet::new(arg_labels));
+        for (name, attacked) in attacks.iter() {
+            let attackers = members
+                .remove(name)
+                .ok_or_else(|| anyhow!(r#"attack "{}" has no member"#, name))?;
+            framework
+                .new_attack(&attackers, attacked)
+                .with_context(|| format!(r#"while building attack "{}""#, name))?;
+        }
+        if let Some(name) = members.keys().next() {
+            return Err(anyhow!(r#"members given for undeclared attack "{}""#, name));
+        }
+        Ok(framework)
+    }
+
+    /// Adds a callback function to call when warnings are raised while parsing a SETAF.
+    pub fn add_warning_handler(&mut self, h: &'a mut dyn FnMut(usize, String)) {
+        self.warning_handlers.push(Rc::new(RefCell::new(h)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(instance: &str) -> Result<SetAFramework<String>> {
+        SetafAspartixReader::default().read(&mut instance.as_bytes())
+    }
+
+    #[test]
+    fn test_read_ok() {
+        let setaf = read(
+            "arg(a).\narg(b).\narg(c).\nmem(r2,c).\natt(r1,c).\nmem(r1,a).\natt(r2,a).\nmem(r1, b).\n",
+        )
+        .unwrap();
+        assert_eq!(3, setaf.argument_set().len());
+        assert_eq!(
+            vec!["{a, b} → c".to_string(), "{c} → a".to_string()],
+            setaf
+                .iter_attacks()
+                .map(|att| att.to_string())
+                .collect::<Vec<String>>()
+        );
+    }
+
+    #[test]
+    fn test_read_errors() {
+        [
+            "arg(a).\natt(r,a).\n",
+            "arg(a).\nmem(r,a).\n",
+            "arg(a).\natt(r,a).\nmem(r,b).\n",
+            "arg(a).\natt(r,a).\natt(r,a).\nmem(r,a).\n",
+            "arg(a).\natt(r,a).\nmem(r,a).\narg(b).\n",
+            "arg(a).\natt(r,a).\nmember(r,a).\n",
+        ]
+        .iter()
+        .for_each(|i| assert!(read(i).is_err(), "{}", i));
+    }
+}
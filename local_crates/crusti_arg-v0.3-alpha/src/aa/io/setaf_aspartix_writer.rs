@@ -0,0 +1,107 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::arguments::LabelType;
+use crate::SetAFramework;
+use anyhow::Result;
+use std::io::Write;
+
+/// A writer for the SETAF Aspartix dialect.
+///
+/// Attacks are named `r1`, `r2`, and so on, following their order in the framework.
+/// See [`SetafAspartixReader`] for a description of the dialect.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{ArgumentSet, SetAFramework, SetafAspartixWriter};
+/// let labels = vec!["a", "b"];
+/// let mut framework = SetAFramework::new(ArgumentSet::new(labels.clone()));
+/// framework.new_attack(&[labels[0], labels[1]], &labels[1]).unwrap();
+/// let mut out = Vec::new();
+/// SetafAspartixWriter::default().write(&framework, &mut out).unwrap();
+/// assert_eq!(
+///     "arg(a).\narg(b).\natt(r1,b).\nmem(r1,a).\nmem(r1,b).\n",
+///     String::from_utf8(out).unwrap()
+/// );
+/// ```
+///
+/// [`SetafAspartixReader`]: struct.SetafAspartixReader.html
+#[derive(Default)]
+pub struct SetafAspartixWriter {}
+
+impl SetafAspartixWriter {
+    /// Writes a SETAF using the SETAF Aspartix dialect to the provided writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `framework` - the framework
+    /// * `writer` - the writer
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::SetAFramework;
+    /// # use crusti_arg::ArgumentSet;
+    /// # use crusti_arg::SetafAspartixWriter;
+    /// # use crusti_arg::LabelType;
+    /// # use anyhow::Result;
+    /// fn write_setaf_to_stdout<T: LabelType>(setaf: &SetAFramework<T>) -> Result<()> {
+    ///     let writer = SetafAspartixWriter::default();
+    ///     writer.write(&setaf, &mut std::io::stdout())
+    /// }
+    /// # write_setaf_to_stdout(&SetAFramework::new(ArgumentSet::new(vec![] as Vec<String>)));
+    /// ```
+    pub fn write<T: LabelType>(
+        &self,
+        framework: &SetAFramework<T>,
+        writer: &mut dyn Write,
+    ) -> Result<()> {
+        for arg in framework.argument_set().iter() {
+            writeln!(writer, "arg({}).", arg)?;
+        }
+        for (i, attack) in framework.iter_attacks().enumerate() {
+            writeln!(writer, "att(r{},{}).", i + 1, attack.attacked())?;
+            for attacker in attack.attackers() {
+                writeln!(writer, "mem(r{},{}).", i + 1, attacker)?;
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SetafAspartixReader;
+
+    #[test]
+    fn test_write_read() {
+        let instance = "arg(a).\narg(b).\narg(c).\natt(r1,c).\nmem(r1,a).\nmem(r1,b).\natt(r2,a).\nmem(r2,c).\n";
+        let framework = SetafAspartixReader::default()
+            .read(&mut instance.as_bytes())
+            .unwrap();
+        let mut out = Vec::new();
+        SetafAspartixWriter::default()
+            .write(&framework, &mut out)
+            .unwrap();
+        assert_eq!(instance, String::from_utf8(out).unwrap());
+    }
+}
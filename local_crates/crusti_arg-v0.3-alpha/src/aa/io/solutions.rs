@@ -21,23 +21,595 @@
 use anyhow::{anyhow, Context, Result};
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::io::{BufRead, Write};
+use std::{
+    borrow::Cow,
+    io::{BufRead, Write},
+};
 
-use crate::{ArgumentSet, LabelType};
+use crate::{
+    aa::io::{
+        labels,
+        parse_error::{self, first_token_offset, ParseError},
+    },
+    ArgumentSet, InternedLabel, LabelInterner, LabelType,
+};
 
-const ARG_AND_SPACE_PATTERN: &str = r"\s*[_[:alpha:]][_[:alpha:]\d]*\s*";
+const UNKNOWN: &str = "UNKNOWN";
+const TIMEOUT: &str = "TIMEOUT";
 
 lazy_static! {
-    static ref ACCEPTANCE_STATUS_LINE_PATTERN: Regex = Regex::new(r"^\s*([^\s]+)\s*$").unwrap();
     static ref EXTENSION_COUNT_LINE_PATTERN: Regex = Regex::new(r"^\s*(\d+)\s*$").unwrap();
-    static ref EXTENSION_LINE_PATTERN: Regex = Regex::new(&format!(
-        r"^\s*\[\s*({}(,\s*{})*)?\]\s*$",
-        ARG_AND_SPACE_PATTERN, ARG_AND_SPACE_PATTERN
-    ))
-    .unwrap();
     static ref EMPTY_EXTENSION_SET_LINE_PATTERN: Regex = Regex::new(r"^\s*\[\s*\]\s*$").unwrap();
     static ref EXTENSION_SET_BEGIN_LINE_PATTERN: Regex = Regex::new(r"^\s*\[\s*$").unwrap();
     static ref EXTENSION_SET_END_LINE_PATTERN: Regex = Regex::new(r"^\s*\]\s*$").unwrap();
+    static ref STRICT_EXTENSION_COUNT_LINE_PATTERN: Regex = Regex::new(r"^(0|[1-9]\d*)$").unwrap();
+}
+
+/// The level of conformance to the output grammar of the ICCMA competitions required when reading solutions.
+///
+/// In both levels, lines may end with LF or CRLF.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Conformance {
+    /// Any deviation from the grammar is an error.
+    ///
+    /// No whitespace is allowed, except a single space after the commas of the extensions, and each line must be terminated.
+    /// Counts must be written without leading zeros.
+    Strict,
+    /// Extra whitespace is tolerated around the answers and their elements, and empty lines and comment lines (beginning with `#`) are ignored.
+    #[default]
+    Lenient,
+}
+
+/// The grammar of the argument labels in the extensions output by solvers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LabelGrammar {
+    /// The labels of the Aspartix format: identifiers made of letters, digits and underscores, not beginning with a digit, or quoted labels (see the [`labels`] module).
+    ///
+    /// [`labels`]: ../labels/index.html
+    #[default]
+    Identifier,
+    /// Sequences of digits, as output by the solvers using the numeric format (e.g. `[1, 17, 42]`).
+    Numeric,
+    /// Strings surrounded by double quotes, in which double quotes and backslashes are escaped by a backslash (e.g. `["a \"b\", c"]`).
+    ///
+    /// The labels are read without their quotes and escape characters.
+    Quoted,
+}
+
+/// A result of a `DC` or `DS` query given by an approximate solver.
+///
+/// Approximate solvers may give a confidence in their answer, between 0 and 1, or no answer at all.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ApproximateStatus {
+    /// The argument is believed to be accepted, with an optional confidence.
+    Yes(Option<f64>),
+    /// The argument is believed not to be accepted, with an optional confidence.
+    No(Option<f64>),
+    /// The solver gives no answer.
+    Unknown,
+}
+
+impl ApproximateStatus {
+    /// Returns the acceptance status given by this answer, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::solutions::ApproximateStatus;
+    /// assert_eq!(Some(true), ApproximateStatus::Yes(Some(0.9)).status());
+    /// assert_eq!(None, ApproximateStatus::Unknown.status());
+    /// ```
+    pub fn status(&self) -> Option<bool> {
+        match self {
+            ApproximateStatus::Yes(_) => Some(true),
+            ApproximateStatus::No(_) => Some(false),
+            ApproximateStatus::Unknown => None,
+        }
+    }
+}
+
+/// A reader for the solutions output by argumentation solvers, given the level of conformance they must satisfy.
+///
+/// The free functions of this module read the solutions using a lenient reader.
+/// The labels of the extensions follow the identifier grammar, unless another [`LabelGrammar`] is set.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::solutions::{Conformance, SolutionReader};
+/// let lenient = SolutionReader::new(Conformance::Lenient);
+/// assert!(lenient.read_acceptance_status(&mut "# a comment\n YES \n".as_bytes()).unwrap());
+/// let strict = SolutionReader::new(Conformance::Strict);
+/// assert!(strict.read_acceptance_status(&mut "YES\n".as_bytes()).unwrap());
+/// assert!(strict.read_acceptance_status(&mut " YES\n".as_bytes()).is_err());
+/// ```
+///
+/// [`LabelGrammar`]: enum.LabelGrammar.html
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SolutionReader {
+    conformance: Conformance,
+    label_grammar: LabelGrammar,
+    approximate: bool,
+    unknown_allowed: bool,
+}
+
+impl SolutionReader {
+    /// Builds a reader requiring the given level of conformance.
+    ///
+    /// # Arguments
+    /// * `conformance` - the level of conformance
+    pub fn new(conformance: Conformance) -> Self {
+        SolutionReader {
+            conformance,
+            label_grammar: LabelGrammar::default(),
+            approximate: false,
+            unknown_allowed: false,
+        }
+    }
+
+    /// Sets the grammar of the argument labels in the extensions read by this reader.
+    ///
+    /// # Arguments
+    /// * `label_grammar` - the label grammar
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::solutions::{Conformance, LabelGrammar, SolutionReader};
+    /// let mut reader = SolutionReader::new(Conformance::Lenient);
+    /// assert!(reader.read_extension(&mut "[1, 17, 42]\n".as_bytes()).is_err());
+    /// reader.set_label_grammar(LabelGrammar::Numeric);
+    /// let extension = reader.read_extension(&mut "[1, 17, 42]\n".as_bytes()).unwrap();
+    /// assert_eq!("17", extension.get_argument_by_id(1).label());
+    /// ```
+    pub fn set_label_grammar(&mut self, label_grammar: LabelGrammar) {
+        self.label_grammar = label_grammar;
+    }
+
+    /// Returns the grammar of the argument labels read by this reader.
+    pub fn label_grammar(&self) -> LabelGrammar {
+        self.label_grammar
+    }
+
+    /// Returns the level of conformance required by this reader.
+    pub fn conformance(&self) -> Conformance {
+        self.conformance
+    }
+
+    /// Sets whether the acceptance statuses are expected from approximate solvers.
+    ///
+    /// This setting is a hint for the users of the reader, choosing between [`read_acceptance_status`] and [`read_approximate_status`].
+    ///
+    /// # Arguments
+    /// * `approximate` - `true` if the acceptance statuses are given by approximate solvers
+    ///
+    /// [`read_acceptance_status`]: #method.read_acceptance_status
+    /// [`read_approximate_status`]: #method.read_approximate_status
+    pub fn set_approximate(&mut self, approximate: bool) {
+        self.approximate = approximate;
+    }
+
+    /// Returns `true` if the acceptance statuses are expected from approximate solvers.
+    pub fn is_approximate(&self) -> bool {
+        self.approximate
+    }
+
+    /// Sets whether the solvers may give up on a `DC` or `DS` query, answering `UNKNOWN` or `TIMEOUT`.
+    ///
+    /// Like [`set_approximate`], this setting is a hint for the users of the reader, choosing between [`read_acceptance_status`] and [`read_acceptance_status_or_unknown`].
+    ///
+    /// # Arguments
+    /// * `unknown_allowed` - `true` if the solvers may give up
+    ///
+    /// [`set_approximate`]: #method.set_approximate
+    /// [`read_acceptance_status`]: #method.read_acceptance_status
+    /// [`read_acceptance_status_or_unknown`]: #method.read_acceptance_status_or_unknown
+    pub fn set_unknown_allowed(&mut self, unknown_allowed: bool) {
+        self.unknown_allowed = unknown_allowed;
+    }
+
+    /// Returns `true` if the solvers may answer `UNKNOWN` or `TIMEOUT` to `DC` and `DS` queries.
+    pub fn is_unknown_allowed(&self) -> bool {
+        self.unknown_allowed
+    }
+
+    /// Reads a result of a `DC` or `DS` query.
+    ///
+    /// See [`read_acceptance_status`](fn.read_acceptance_status.html).
+    ///
+    /// # Arguments
+    /// * `reader` - the reader in which the result must be read
+    pub fn read_acceptance_status(&self, reader: &mut dyn BufRead) -> Result<bool> {
+        self.read_status_word(reader, "expected an acceptance status", |w| match w {
+            "YES" => Some(true),
+            "NO" => Some(false),
+            _ => None,
+        })
+    }
+
+    /// Reads a result of a `DC` or `DS` query, which may be `UNKNOWN` or `TIMEOUT` if the solver gave up.
+    ///
+    /// See [`read_acceptance_status_or_unknown`](fn.read_acceptance_status_or_unknown.html).
+    ///
+    /// # Arguments
+    /// * `reader` - the reader in which the result must be read
+    pub fn read_acceptance_status_or_unknown(
+        &self,
+        reader: &mut dyn BufRead,
+    ) -> Result<Option<bool>> {
+        self.read_status_word(
+            reader,
+            &format!(r#"expected an acceptance status or "{}""#, UNKNOWN),
+            |w| match w {
+                "YES" => Some(Some(true)),
+                "NO" => Some(Some(false)),
+                UNKNOWN | TIMEOUT => Some(None),
+                _ => None,
+            },
+        )
+    }
+
+    /// Reads a line made of a single word, which is given to a parsing function returning `None` if the word is unexpected.
+    fn read_status_word<T, F>(
+        &self,
+        reader: &mut dyn BufRead,
+        expected: &str,
+        parse: F,
+    ) -> Result<T>
+    where
+        F: Fn(&str) -> Option<T>,
+    {
+        let (line, line_number) = self.read_single_line(reader, "an acceptance status")?;
+        let words = self.words(&line);
+        let offset = match words.as_slice() {
+            [(o, w)] => match parse(w) {
+                Some(t) => return Ok(t),
+                None => *o,
+            },
+            [] => line.trim_end().len(),
+            [(o, ""), ..] | [_, (o, _), ..] => *o,
+        };
+        Err(ParseError::new(expected, &line, offset)
+            .with_line(line_number)
+            .into())
+    }
+
+    /// Reads a result of a `DC` or `DS` query given by an approximate solver.
+    ///
+    /// See [`read_approximate_status`](fn.read_approximate_status.html).
+    /// In strict mode, the confidence must follow the status after a single space.
+    ///
+    /// # Arguments
+    /// * `reader` - the reader in which the result must be read
+    pub fn read_approximate_status(&self, reader: &mut dyn BufRead) -> Result<ApproximateStatus> {
+        let (line, line_number) =
+            self.read_single_line(reader, "an approximate acceptance status")?;
+        let words = self.words(&line);
+        let error = |message: &str, offset: usize| -> anyhow::Error {
+            ParseError::new(message, &line, offset)
+                .with_line(line_number)
+                .into()
+        };
+        let status_error = |offset| error("expected an approximate acceptance status", offset);
+        let confidence = |word: Option<&(usize, &str)>| match word {
+            Some((o, w)) => match w.parse::<f64>() {
+                Ok(c) if (0.0..=1.0).contains(&c) => Ok(Some(c)),
+                _ => Err(error("expected a confidence between 0 and 1", *o)),
+            },
+            None => Ok(None),
+        };
+        let (status, max_words) = match words.first() {
+            Some((_, "YES")) => (Some(true), 2),
+            Some((_, "NO")) => (Some(false), 2),
+            Some((_, UNKNOWN)) | Some((_, TIMEOUT)) => (None, 1),
+            Some((o, _)) => return Err(status_error(*o)),
+            None => return Err(status_error(line.trim_end().len())),
+        };
+        if let Some((o, _)) = words.get(max_words) {
+            return Err(status_error(*o));
+        }
+        Ok(match status {
+            Some(true) => ApproximateStatus::Yes(confidence(words.get(1))?),
+            Some(false) => ApproximateStatus::No(confidence(words.get(1))?),
+            None => ApproximateStatus::Unknown,
+        })
+    }
+
+    /// Reads an extension count (`CE`) query.
+    ///
+    /// See [`read_extension_count`](fn.read_extension_count.html).
+    ///
+    /// # Arguments
+    /// * `reader` - the reader in which the result must be read
+    pub fn read_extension_count(&self, reader: &mut dyn BufRead) -> Result<usize> {
+        self.read_count_digits(reader)?
+            .parse::<usize>()
+            .context("while parsing an extension count")
+    }
+
+    /// Reads an extension count (`CE`) query without any limit on its value.
+    ///
+    /// See [`read_big_extension_count`](fn.read_big_extension_count.html).
+    ///
+    /// # Arguments
+    /// * `reader` - the reader in which the result must be read
+    pub fn read_big_extension_count(&self, reader: &mut dyn BufRead) -> Result<String> {
+        let count = self.read_count_digits(reader)?;
+        let digits = count.trim_start_matches('0');
+        Ok(if digits.is_empty() { "0" } else { digits }.to_string())
+    }
+
+    fn read_count_digits(&self, reader: &mut dyn BufRead) -> Result<String> {
+        let (line, line_number) = self.read_single_line(reader, "an extension count")?;
+        let pattern: &Regex = match self.conformance {
+            Conformance::Strict => &STRICT_EXTENSION_COUNT_LINE_PATTERN,
+            Conformance::Lenient => &EXTENSION_COUNT_LINE_PATTERN,
+        };
+        match pattern.captures(&line) {
+            Some(c) => Ok(c.get(1).unwrap().as_str().to_string()),
+            None => {
+                let lenient = self.conformance == Conformance::Lenient;
+                let start = if lenient {
+                    first_token_offset(&line)
+                } else {
+                    0
+                };
+                let offset = match line[start..].find(|c: char| !c.is_ascii_digit()) {
+                    Some(p) if p > 0 && lenient => {
+                        start + p + first_token_offset(&line[start + p..])
+                    }
+                    Some(p) if p > 0 => start + p,
+                    _ => start,
+                };
+                Err(
+                    ParseError::new("expected an extension count", &line, offset)
+                        .with_line(line_number)
+                        .into(),
+                )
+            }
+        }
+    }
+
+    /// Reads an extension.
+    ///
+    /// See [`read_extension`](fn.read_extension.html).
+    ///
+    /// # Arguments
+    /// * `reader` - the reader in which the content must be read
+    pub fn read_extension(&self, reader: &mut dyn BufRead) -> Result<ArgumentSet<String>> {
+        let (line, line_number) = self.read_single_line(reader, "an extension line")?;
+        self.extension_from_line(&line)
+            .map_err(|e| parse_error::locate(e, line_number))
+    }
+
+    /// Reads an extension given by the numeric identifiers of its arguments.
+    ///
+    /// See [`read_numeric_extension`](fn.read_numeric_extension.html).
+    ///
+    /// # Arguments
+    /// * `reader` - the reader in which the content must be read
+    pub fn read_numeric_extension(&self, reader: &mut dyn BufRead) -> Result<ArgumentSet<usize>> {
+        let (line, line_number) = self.read_single_line(reader, "an extension line")?;
+        self.numeric_extension_from_line(&line)
+            .map_err(|e| parse_error::locate(e, line_number))
+    }
+
+    /// Reads a set of extensions.
+    ///
+    /// See [`read_extension_set`](fn.read_extension_set.html).
+    ///
+    /// # Arguments
+    /// * `reader` - the reader in which the content must be read
+    pub fn read_extension_set(&self, reader: &mut dyn BufRead) -> Result<Vec<ArgumentSet<String>>> {
+        self.iter_extension_set(reader).collect()
+    }
+
+    /// Iterates over a set of extensions, reading them lazily.
+    ///
+    /// See [`iter_extension_set`](fn.iter_extension_set.html).
+    ///
+    /// # Arguments
+    /// * `reader` - the reader in which the content must be read
+    pub fn iter_extension_set<'a>(
+        &self,
+        reader: &'a mut dyn BufRead,
+    ) -> ExtensionSetIter<'a, String> {
+        self.iter_extension_set_with(reader, |r, l| r.extension_from_line(l))
+    }
+
+    /// Reads an extension, sharing its labels through an interner.
+    ///
+    /// See [`read_interned_extension_set`](#method.read_interned_extension_set).
+    ///
+    /// # Arguments
+    /// * `reader` - the reader in which the content must be read
+    /// * `interner` - the interner giving the labels
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{LabelInterner, solutions::SolutionReader};
+    /// let mut interner = LabelInterner::default();
+    /// let reader = SolutionReader::default();
+    /// let extension = reader
+    ///     .read_interned_extension(&mut "[a, b]\n".as_bytes(), &mut interner)
+    ///     .unwrap();
+    /// assert_eq!(2, extension.len());
+    /// assert_eq!(2, interner.len());
+    /// ```
+    pub fn read_interned_extension(
+        &self,
+        reader: &mut dyn BufRead,
+        interner: &mut LabelInterner,
+    ) -> Result<ArgumentSet<InternedLabel>> {
+        let (line, line_number) = self.read_single_line(reader, "an extension line")?;
+        self.interned_extension_from_line(&line, interner)
+            .map_err(|e| parse_error::locate(e, line_number))
+    }
+
+    /// Reads a set of extensions, sharing their labels through an interner.
+    ///
+    /// Each label is allocated once by the interner, whatever the number of extensions it belongs to,
+    /// which reduces the memory needed to handle large sets of extensions.
+    /// Initializing the interner with the labels of the framework (see [`LabelInterner::with_labels_of`]) avoids allocating any label while reading the set.
+    ///
+    /// See [`read_extension_set`](fn.read_extension_set.html).
+    ///
+    /// # Arguments
+    /// * `reader` - the reader in which the content must be read
+    /// * `interner` - the interner giving the labels
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{LabelInterner, solutions::SolutionReader};
+    /// let mut interner = LabelInterner::default();
+    /// let reader = SolutionReader::default();
+    /// let extensions = reader
+    ///     .read_interned_extension_set(&mut "[\n[a, b]\n[a, c]\n]\n".as_bytes(), &mut interner)
+    ///     .unwrap();
+    /// assert_eq!(2, extensions.len());
+    /// assert_eq!(3, interner.len());
+    /// ```
+    ///
+    /// [`LabelInterner::with_labels_of`]: ../struct.LabelInterner.html#method.with_labels_of
+    pub fn read_interned_extension_set(
+        &self,
+        reader: &mut dyn BufRead,
+        interner: &mut LabelInterner,
+    ) -> Result<Vec<ArgumentSet<InternedLabel>>> {
+        self.iter_extension_set_with(reader, |r, l| r.interned_extension_from_line(l, interner))
+            .collect()
+    }
+
+    /// Reads a set of extensions given by the numeric identifiers of their arguments.
+    ///
+    /// See [`read_numeric_extension_set`](fn.read_numeric_extension_set.html).
+    ///
+    /// # Arguments
+    /// * `reader` - the reader in which the content must be read
+    pub fn read_numeric_extension_set(
+        &self,
+        reader: &mut dyn BufRead,
+    ) -> Result<Vec<ArgumentSet<usize>>> {
+        self.iter_extension_set_with(reader, |r, l| r.numeric_extension_from_line(l))
+            .collect()
+    }
+
+    /// Reads the next line of an answer into a buffer, returning `false` at the end of the input.
+    ///
+    /// The buffer is cleared before reading, so that a single buffer can be reused for all the lines of an answer.
+    /// The line number is incremented for each line read, including the skipped ones, so that it gives the number of the returned line.
+    /// In strict mode, the line is returned without its line ending, which is mandatory.
+    /// In lenient mode, empty lines and comment lines are skipped, and the line is returned as is.
+    fn read_answer_line(
+        &self,
+        reader: &mut dyn BufRead,
+        line: &mut String,
+        line_number: &mut usize,
+    ) -> Result<bool> {
+        loop {
+            line.clear();
+            if reader.read_line(line)? == 0 {
+                return Ok(false);
+            }
+            *line_number += 1;
+            match self.conformance {
+                Conformance::Strict => {
+                    if !line.ends_with('\n') {
+                        return Err(ParseError::new("expected a line ending", line, line.len())
+                            .with_line(*line_number)
+                            .into());
+                    }
+                    line.pop();
+                    if line.ends_with('\r') {
+                        line.pop();
+                    }
+                    return Ok(true);
+                }
+                Conformance::Lenient => {
+                    let trimmed = line.trim();
+                    if !trimmed.is_empty() && !trimmed.starts_with('#') {
+                        return Ok(true);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reads the only line of a single-line answer, returning it with its line number.
+    fn read_single_line(&self, reader: &mut dyn BufRead, what: &str) -> Result<(String, usize)> {
+        let mut line = String::new();
+        let mut line_number = 0;
+        if !self
+            .read_answer_line(reader, &mut line, &mut line_number)
+            .with_context(|| format!("while parsing {}", what))?
+        {
+            return Err(anyhow!("read EOF while parsing {}", what));
+        }
+        Ok((line, line_number))
+    }
+
+    /// Splits an answer line into its words, given with their byte offsets.
+    ///
+    /// In strict mode, the words are separated by single spaces, so that empty words denote extra spaces.
+    /// In lenient mode, they are separated by any whitespace.
+    fn words<'l>(&self, line: &'l str) -> Vec<(usize, &'l str)> {
+        let offset = |w: &str| w.as_ptr() as usize - line.as_ptr() as usize;
+        match self.conformance {
+            Conformance::Strict => line.split(' ').map(|w| (offset(w), w)).collect(),
+            Conformance::Lenient => line.split_whitespace().map(|w| (offset(w), w)).collect(),
+        }
+    }
+
+    fn extension_from_line(&self, line: &str) -> Result<ArgumentSet<String>> {
+        extension_labels(line, self.label_grammar, self.conformance)
+            .map(|labels| ArgumentSet::new(labels.into_iter().map(Cow::into_owned).collect()))
+            .map_err(|o| ParseError::new("expected an extension line", line, o).into())
+    }
+
+    fn interned_extension_from_line(
+        &self,
+        line: &str,
+        interner: &mut LabelInterner,
+    ) -> Result<ArgumentSet<InternedLabel>> {
+        extension_labels(line, self.label_grammar, self.conformance)
+            .map(|labels| ArgumentSet::new(labels.iter().map(|l| interner.intern(l)).collect()))
+            .map_err(|o| ParseError::new("expected an extension line", line, o).into())
+    }
+
+    fn numeric_extension_from_line(&self, line: &str) -> Result<ArgumentSet<usize>> {
+        let labels = extension_labels(line, LabelGrammar::Numeric, self.conformance)
+            .map_err(|o| ParseError::new("expected a numeric extension line", line, o))?;
+        Ok(ArgumentSet::new(
+            labels
+                .iter()
+                .map(|a| {
+                    a.parse::<usize>()
+                        .context("while parsing an argument identifier")
+                })
+                .collect::<Result<Vec<usize>>>()?,
+        ))
+    }
+
+    fn iter_extension_set_with<'a, T, F>(
+        &self,
+        reader: &'a mut dyn BufRead,
+        read_extension_line: F,
+    ) -> ExtensionSetIter<'a, T>
+    where
+        T: LabelType,
+        F: FnMut(&SolutionReader, &str) -> Result<ArgumentSet<T>> + 'a,
+    {
+        ExtensionSetIter {
+            solution_reader: *self,
+            reader,
+            read_extension_line: Box::new(read_extension_line),
+            line: String::new(),
+            line_number: 0,
+            state: ExtensionSetIterState::NotBegun,
+        }
+    }
 }
 
 /// Reads a result of a `DC` (credulous acceptance) or `DS` (skeptical acceptance) query.
@@ -45,27 +617,60 @@ lazy_static! {
 /// Such result must be a single line containing the string "YES" or "NO", depending on the acceptance status.
 ///
 /// If the result does not match these words, an error is returned.
+/// The result is read by a lenient [`SolutionReader`](struct.SolutionReader.html).
 ///
 /// # Arguments
 /// * `reader` - the reader in which the result must be read
 pub fn read_acceptance_status(reader: &mut dyn BufRead) -> Result<bool> {
-    let mut line = String::new();
-    let wrong_acceptance_status =
-        |s| Err(anyhow!(r#"expected an acceptance status, found "{}""#, s));
-    match reader
-        .read_line(&mut line)
-        .context("while parsing an acceptance status")?
-    {
-        0 => Err(anyhow!("read EOF while parsing an acceptance status")),
-        _ => match ACCEPTANCE_STATUS_LINE_PATTERN.captures(line.as_str()) {
-            Some(c) => match c.get(1).unwrap().as_str() {
-                "YES" => Ok(true),
-                "NO" => Ok(false),
-                _ => wrong_acceptance_status(c.get(1).unwrap().as_str()),
-            },
-            None => wrong_acceptance_status(line.as_str()),
-        }, // kcov-ignore
-    }
+    SolutionReader::default().read_acceptance_status(reader)
+}
+
+/// Reads a result of a `DC` or `DS` query, which may be `UNKNOWN` if the solver gave up.
+///
+/// Such result must be a single line containing the string "YES" or "NO", depending on the acceptance status, or the string "UNKNOWN".
+/// The string "TIMEOUT", given by solvers running out of time, is read as "UNKNOWN".
+/// The unknown status is returned as `None`.
+///
+/// If the result does not match these words, an error is returned.
+/// The result is read by a lenient [`SolutionReader`](struct.SolutionReader.html).
+///
+/// # Arguments
+/// * `reader` - the reader in which the result must be read
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::solutions;
+/// assert_eq!(Some(true), solutions::read_acceptance_status_or_unknown(&mut "YES\n".as_bytes()).unwrap());
+/// assert_eq!(None, solutions::read_acceptance_status_or_unknown(&mut "TIMEOUT\n".as_bytes()).unwrap());
+/// assert!(solutions::read_acceptance_status(&mut "UNKNOWN\n".as_bytes()).is_err());
+/// ```
+pub fn read_acceptance_status_or_unknown(reader: &mut dyn BufRead) -> Result<Option<bool>> {
+    SolutionReader::default().read_acceptance_status_or_unknown(reader)
+}
+
+/// Reads a result of a `DC` or `DS` query given by an approximate solver.
+///
+/// Such result must be a single line containing the string "YES" or "NO", optionally followed by a confidence between 0 and 1, or the string "UNKNOWN".
+/// The string "TIMEOUT", given by solvers running out of time, is read as "UNKNOWN".
+///
+/// If the result does not match this grammar, an error is returned.
+/// The result is read by a lenient [`SolutionReader`](struct.SolutionReader.html).
+///
+/// # Arguments
+/// * `reader` - the reader in which the result must be read
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::solutions::{self, ApproximateStatus};
+/// assert_eq!(ApproximateStatus::Yes(Some(0.75)), solutions::read_approximate_status(&mut "YES 0.75\n".as_bytes()).unwrap());
+/// assert_eq!(ApproximateStatus::No(None), solutions::read_approximate_status(&mut "NO\n".as_bytes()).unwrap());
+/// assert_eq!(ApproximateStatus::Unknown, solutions::read_approximate_status(&mut "UNKNOWN\n".as_bytes()).unwrap());
+/// assert!(solutions::read_approximate_status(&mut "YES 1.5\n".as_bytes()).is_err());
+/// ```
+pub fn read_approximate_status(reader: &mut dyn BufRead) -> Result<ApproximateStatus> {
+    SolutionReader::default().read_approximate_status(reader)
 }
 
 /// Reads an extension count (`CE`) query.
@@ -73,29 +678,36 @@ pub fn read_acceptance_status(reader: &mut dyn BufRead) -> Result<bool> {
 /// Such result must be a single line containing a positive number.
 ///
 /// If the result does not match a positive number, an error is returned.
+/// The result is read by a lenient [`SolutionReader`](struct.SolutionReader.html).
 ///
 /// # Arguments
 /// * `reader` - the reader in which the result must be read
 pub fn read_extension_count(reader: &mut dyn BufRead) -> Result<usize> {
-    let mut line = String::new();
-    match reader
-        .read_line(&mut line)
-        .context("while parsing an extension count")?
-    {
-        0 => Err(anyhow!("read EOF while parsing an acceptance status")),
-        _ => match EXTENSION_COUNT_LINE_PATTERN.captures(line.as_str()) {
-            Some(c) => c
-                .get(1)
-                .unwrap()
-                .as_str()
-                .parse::<usize>()
-                .context("while parsing an extension count"),
-            None => Err(anyhow!(
-                r#"expected an extension count, found "{}""#,
-                line.as_str()
-            )),
-        },
-    }
+    SolutionReader::default().read_extension_count(reader)
+}
+
+/// Reads an extension count (`CE`) query without any limit on its value.
+///
+/// This function behaves like [`read_extension_count`], but the count is returned as its decimal representation (without leading zeros) instead of a machine integer.
+/// It must be preferred when the count may exceed `usize::MAX`, e.g. for preferred or stable extension counts on large instances.
+///
+/// If the result does not match a positive number, an error is returned.
+/// The result is read by a lenient [`SolutionReader`](struct.SolutionReader.html).
+///
+/// # Arguments
+/// * `reader` - the reader in which the result must be read
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::solutions;
+/// let count = solutions::read_big_extension_count(&mut "0123456789012345678901234567890\n".as_bytes()).unwrap();
+/// assert_eq!("123456789012345678901234567890", count);
+/// ```
+///
+/// [`read_extension_count`]: fn.read_extension_count.html
+pub fn read_big_extension_count(reader: &mut dyn BufRead) -> Result<String> {
+    SolutionReader::default().read_big_extension_count(reader)
 }
 
 /// Reads an extension.
@@ -104,29 +716,136 @@ pub fn read_extension_count(reader: &mut dyn BufRead) -> Result<usize> {
 /// The arguments composing the extension must be split be commas.
 ///
 /// If the content does not match these requirements, an error is returned.
+/// The extension is read by a lenient [`SolutionReader`](struct.SolutionReader.html).
 ///
 /// # Arguments
 /// * `reader` - the reader in which the content must be read
 pub fn read_extension(reader: &mut dyn BufRead) -> Result<ArgumentSet<String>> {
-    let mut line = String::new();
-    match reader
-        .read_line(&mut line)
-        .context("while parsing an extension line")?
-    {
-        0 => Err(anyhow!("read EOF while parsing an extension line")),
-        _ => read_extension_line_from_str(line.as_str()),
+    SolutionReader::default().read_extension(reader)
+}
+
+/// Reads an extension given by the numeric identifiers of its arguments, as output by solvers using the numeric format.
+///
+/// The extension must be given on a single line, surrounded between square brackets.
+/// The identifiers composing the extension must be split be commas.
+///
+/// If the content does not match these requirements, an error is returned.
+/// The extension is read by a lenient [`SolutionReader`](struct.SolutionReader.html).
+///
+/// # Arguments
+/// * `reader` - the reader in which the content must be read
+pub fn read_numeric_extension(reader: &mut dyn BufRead) -> Result<ArgumentSet<usize>> {
+    SolutionReader::default().read_numeric_extension(reader)
+}
+
+pub(crate) fn read_extension_line_from_str(line: &str) -> Result<ArgumentSet<String>> {
+    SolutionReader::default().extension_from_line(line)
+}
+
+/// Reads the labels of an extension line following a label grammar.
+///
+/// The labels are borrowed from the line, except the quoted ones containing escape characters.
+/// If the line does not follow the grammar, the byte offset of the offending token is returned.
+/// In strict mode, no whitespace is allowed except a single space after the commas.
+fn extension_labels(
+    line: &str,
+    grammar: LabelGrammar,
+    conformance: Conformance,
+) -> std::result::Result<Vec<Cow<'_, str>>, usize> {
+    let lenient = conformance == Conformance::Lenient;
+    let end = if lenient {
+        line.trim_end().len()
+    } else {
+        line.len()
+    };
+    let mut scanner = ExtensionScanner {
+        line: &line[..end],
+        offset: 0,
+        lenient,
+    };
+    scanner.skip_spaces();
+    scanner.expect('[')?;
+    let mut labels = vec![];
+    scanner.skip_spaces();
+    if !scanner.eat(']') {
+        loop {
+            labels.push(scanner.label(grammar)?);
+            scanner.skip_spaces();
+            if scanner.eat(']') {
+                break;
+            }
+            scanner.expect(',')?;
+            if lenient {
+                scanner.skip_spaces();
+            } else {
+                scanner.eat(' ');
+            }
+        }
+    }
+    if scanner.offset == end {
+        Ok(labels)
+    } else {
+        Err(scanner.offset)
     }
 }
 
-fn read_extension_line_from_str(line: &str) -> Result<ArgumentSet<String>> {
-    match EXTENSION_LINE_PATTERN.captures(line) {
-        Some(c) if c.get(1).is_none() => Ok(ArgumentSet::new(vec![])),
-        Some(c) => Ok(ArgumentSet::new(
-            c[1].split(',')
-                .map(|a| a.trim().to_string())
-                .collect::<Vec<String>>(),
-        )),
-        None => Err(anyhow!(r#"expected an extension line, found "{}""#, line)),
+/// A scanner of an extension line, reading its tokens from a byte offset without copying them.
+struct ExtensionScanner<'l> {
+    line: &'l str,
+    offset: usize,
+    lenient: bool,
+}
+
+impl<'l> ExtensionScanner<'l> {
+    fn rest(&self) -> &'l str {
+        &self.line[self.offset..]
+    }
+
+    /// Skips the whitespace at the current offset, in lenient mode only.
+    fn skip_spaces(&mut self) {
+        if self.lenient {
+            self.offset += first_token_offset(self.rest());
+        }
+    }
+
+    /// Consumes an ASCII character if it is the next one, returning whether it was.
+    fn eat(&mut self, c: char) -> bool {
+        if self.rest().starts_with(c) {
+            self.offset += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes an ASCII character, or returns the current offset if it is not the next one.
+    fn expect(&mut self, c: char) -> std::result::Result<(), usize> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.offset)
+        }
+    }
+
+    /// Reads a label following a grammar, or returns the current offset if there is none.
+    fn label(&mut self, grammar: LabelGrammar) -> std::result::Result<Cow<'l, str>, usize> {
+        let rest = self.rest();
+        let scanned = match grammar {
+            LabelGrammar::Identifier => labels::scan_label(rest),
+            LabelGrammar::Numeric => {
+                let len = rest.bytes().take_while(u8::is_ascii_digit).count();
+                Some((Cow::Borrowed(&rest[..len]), len)).filter(|_| len > 0)
+            }
+            LabelGrammar::Quoted if rest.starts_with('"') => labels::scan_label(rest),
+            LabelGrammar::Quoted => None,
+        };
+        match scanned {
+            Some((label, len)) => {
+                self.offset += len;
+                Ok(label)
+            }
+            None => Err(self.offset),
+        }
     }
 }
 
@@ -141,113 +860,567 @@ fn read_extension_line_from_str(line: &str) -> Result<ArgumentSet<String>> {
 /// or by a single containg containing the two brackets.
 ///
 /// If the content does not match these requirements, an error is returned.
+/// The set is read by a lenient [`SolutionReader`](struct.SolutionReader.html).
 ///
 /// # Arguments
 /// * `reader` - the reader in which the content must be read
 pub fn read_extension_set(reader: &mut dyn BufRead) -> Result<Vec<ArgumentSet<String>>> {
-    let mut extensions = None;
-    let mut line_count = 0;
-    for line in reader.lines() {
-        line_count += 1;
-        let l =
-            line.with_context(|| format!("while reading an extension set (line {})", line_count))?;
-        if EMPTY_EXTENSION_SET_LINE_PATTERN.is_match(&l) && extensions.is_none() {
-            return Ok(vec![]);
-        } else if EXTENSION_SET_BEGIN_LINE_PATTERN.is_match(&l) {
-            if extensions.is_some() {
-                return Err(anyhow!(
-                    "unexpected second extension beginning pattern (line {})",
-                    line_count
-                ));
+    SolutionReader::default().read_extension_set(reader)
+}
+
+/// Iterates over a set of extensions, reading them lazily.
+///
+/// The set must be formatted as described in [`read_extension_set`](fn.read_extension_set.html).
+/// Each extension is read when the iterator is advanced, so that large sets can be processed without collecting them first.
+///
+/// The closing bracket is checked when the iterator is exhausted: if the input ends before it, the last item is an error.
+/// After an error has been yielded, the iteration stops.
+/// Dropping the iterator before its end leaves the remaining lines unread; use [`finish`](struct.ExtensionSetIter.html#method.finish) to check them.
+/// The set is read by a lenient [`SolutionReader`](struct.SolutionReader.html).
+///
+/// # Arguments
+/// * `reader` - the reader in which the content must be read
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::solutions;
+/// let mut input = "[\n[a, b]\n[c]\n]\n".as_bytes();
+/// let sizes = solutions::iter_extension_set(&mut input)
+///     .map(|e| e.map(|e| e.len()))
+///     .collect::<anyhow::Result<Vec<usize>>>()
+///     .unwrap();
+/// assert_eq!(vec![2, 1], sizes);
+///
+/// let mut unterminated = "[\n[a, b]\n".as_bytes();
+/// let mut iter = solutions::iter_extension_set(&mut unterminated);
+/// assert!(iter.next().unwrap().is_ok());
+/// assert!(iter.next().unwrap().is_err());
+/// assert!(iter.next().is_none());
+/// ```
+pub fn iter_extension_set(reader: &mut dyn BufRead) -> ExtensionSetIter<'_, String> {
+    SolutionReader::default().iter_extension_set(reader)
+}
+
+/// The function turning a line of an extension set into an extension.
+type ExtensionLineReader<'a, T> =
+    Box<dyn FnMut(&SolutionReader, &str) -> Result<ArgumentSet<T>> + 'a>;
+
+/// An iterator over the extensions of a set, reading them lazily.
+///
+/// See [`iter_extension_set`](fn.iter_extension_set.html).
+pub struct ExtensionSetIter<'a, T>
+where
+    T: LabelType,
+{
+    solution_reader: SolutionReader,
+    reader: &'a mut dyn BufRead,
+    read_extension_line: ExtensionLineReader<'a, T>,
+    line: String,
+    line_number: usize,
+    state: ExtensionSetIterState,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ExtensionSetIterState {
+    NotBegun,
+    Begun,
+    Ended,
+}
+
+impl<T> ExtensionSetIter<'_, T>
+where
+    T: LabelType,
+{
+    /// Reads the remaining extensions of the set, checking that it is correctly terminated.
+    ///
+    /// The extensions are discarded; the first error met is returned.
+    pub fn finish(mut self) -> Result<()> {
+        for extension in &mut self {
+            extension?;
+        }
+        Ok(())
+    }
+
+    fn read_next(&mut self) -> Result<Option<ArgumentSet<T>>> {
+        let conformance = self.solution_reader.conformance;
+        let is_line = |l: &str, pattern: &Regex, exact: &str| match conformance {
+            Conformance::Strict => l == exact,
+            Conformance::Lenient => pattern.is_match(l),
+        };
+        loop {
+            if !self
+                .solution_reader
+                .read_answer_line(self.reader, &mut self.line, &mut self.line_number)
+                .with_context(|| {
+                    format!(
+                        "while reading an extension set (line {})",
+                        self.line_number + 1
+                    )
+                })?
+            {
+                return Err(anyhow!("unterminated extension set"));
             }
-            extensions = Some(vec![]);
-        } else {
-            if extensions.is_none() {
-                return Err(anyhow!(
-                    "expected an extension beginning pattern (line {})",
-                    line_count
-                ));
+            let l = &self.line;
+            let line_number = self.line_number;
+            let error = |message: &str| -> anyhow::Error {
+                ParseError::new(message, l, first_token_offset(l))
+                    .with_line(line_number)
+                    .into()
+            };
+            let begun = self.state == ExtensionSetIterState::Begun;
+            if is_line(l, &EMPTY_EXTENSION_SET_LINE_PATTERN, "[]") && !begun {
+                return Ok(None);
+            } else if is_line(l, &EXTENSION_SET_BEGIN_LINE_PATTERN, "[") {
+                if begun {
+                    return Err(error("unexpected second beginning of the extension set"));
+                }
+                self.state = ExtensionSetIterState::Begun;
+            } else {
+                if !begun {
+                    return Err(error("expected the beginning of an extension set"));
+                }
+                if is_line(l, &EXTENSION_SET_END_LINE_PATTERN, "]") {
+                    return Ok(None);
+                }
+                return (self.read_extension_line)(&self.solution_reader, l)
+                    .map(Some)
+                    .map_err(|e| parse_error::locate(e, line_number));
             }
-            if EXTENSION_SET_END_LINE_PATTERN.is_match(&l) {
-                return Ok(extensions.unwrap());
+        }
+    }
+}
+
+impl<T> Iterator for ExtensionSetIter<'_, T>
+where
+    T: LabelType,
+{
+    type Item = Result<ArgumentSet<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state == ExtensionSetIterState::Ended {
+            return None;
+        }
+        let next = self.read_next();
+        if !matches!(next, Ok(Some(_))) {
+            self.state = ExtensionSetIterState::Ended;
+        }
+        next.transpose()
+    }
+}
+
+/// Reads a set of extensions given by the numeric identifiers of their arguments, as output by solvers using the numeric format.
+///
+/// The set of extensions must follow the same rules as the ones given for [`read_extension_set`](crate::solutions::read_extension_set),
+/// except that each extension is read as described in [`read_numeric_extension`](crate::solutions::read_numeric_extension).
+///
+/// If the content does not match these requirements, an error is returned.
+/// The set is read by a lenient [`SolutionReader`](struct.SolutionReader.html).
+///
+/// # Arguments
+/// * `reader` - the reader in which the content must be read
+pub fn read_numeric_extension_set(reader: &mut dyn BufRead) -> Result<Vec<ArgumentSet<usize>>> {
+    SolutionReader::default().read_numeric_extension_set(reader)
+}
+
+/// Writes an acceptance status into the provided writer.
+///
+/// # Arguments
+/// * `writer` - the writer in which the status must be written
+/// * `status` - the acceptance status
+pub fn write_acceptance_status(writer: &mut dyn Write, status: bool) -> Result<()> {
+    writeln!(writer, "{}", if status { "YES" } else { "NO" })
+        .context("while writing an acceptance status")
+}
+
+/// Writes an acceptance status into the provided writer, or "UNKNOWN" if the status is unknown.
+///
+/// # Arguments
+/// * `writer` - the writer in which the status must be written
+/// * `status` - the acceptance status, if known
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::solutions;
+/// let mut out = Vec::new();
+/// solutions::write_acceptance_status_or_unknown(&mut out, Some(false)).unwrap();
+/// solutions::write_acceptance_status_or_unknown(&mut out, None).unwrap();
+/// assert_eq!("NO\nUNKNOWN\n", String::from_utf8(out).unwrap());
+/// ```
+pub fn write_acceptance_status_or_unknown(
+    writer: &mut dyn Write,
+    status: Option<bool>,
+) -> Result<()> {
+    match status {
+        Some(s) => write_acceptance_status(writer, s),
+        None => writeln!(writer, "{}", UNKNOWN).context("while writing an acceptance status"),
+    }
+}
+
+/// Writes an acceptance status given by an approximate solver into the provided writer.
+///
+/// # Arguments
+/// * `writer` - the writer in which the status must be written
+/// * `status` - the approximate acceptance status
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::solutions::{self, ApproximateStatus};
+/// let mut out = Vec::new();
+/// solutions::write_approximate_status(&mut out, ApproximateStatus::Yes(Some(0.75))).unwrap();
+/// solutions::write_approximate_status(&mut out, ApproximateStatus::Unknown).unwrap();
+/// assert_eq!("YES 0.75\nUNKNOWN\n", String::from_utf8(out).unwrap());
+/// ```
+pub fn write_approximate_status(writer: &mut dyn Write, status: ApproximateStatus) -> Result<()> {
+    let (word, confidence) = match status {
+        ApproximateStatus::Yes(c) => ("YES", c),
+        ApproximateStatus::No(c) => ("NO", c),
+        ApproximateStatus::Unknown => (UNKNOWN, None),
+    };
+    match confidence {
+        Some(c) => writeln!(writer, "{} {}", word, c),
+        None => writeln!(writer, "{}", word),
+    }
+    .context("while writing an approximate acceptance status")
+}
+
+/// Writes an extension count into the provided writer.
+///
+/// # Arguments
+/// * `writer` - the writer in which the status must be written
+/// * `count` - the extension count
+pub fn write_extension_count(writer: &mut dyn Write, count: usize) -> Result<()> {
+    writeln!(writer, "{}", count).context("while writing an extension count")
+}
+
+/// Writes an extension count given by its decimal representation into the provided writer.
+///
+/// This function is the counterpart of [`read_big_extension_count`].
+/// If the count is not a decimal number, an error is returned.
+///
+/// # Arguments
+/// * `writer` - the writer in which the status must be written
+/// * `count` - the decimal representation of the extension count
+///
+/// [`read_big_extension_count`]: fn.read_big_extension_count.html
+pub fn write_big_extension_count(writer: &mut dyn Write, count: &str) -> Result<()> {
+    if count.is_empty() || !count.chars().all(|c| c.is_ascii_digit()) {
+        return Err(anyhow!(r#"expected an extension count, found "{}""#, count));
+    }
+    writeln!(writer, "{}", count).context("while writing an extension count")
+}
+
+/// Writes an extension into the provided writer.
+///
+/// The arguments are written in the order of their ids.
+/// Use [`ArgumentSet::iter_sorted`] to build an extension whose output does not depend on the order of its arguments.
+///
+/// # Arguments
+/// * `writer` - the writer in which the status must be written
+/// * `extension` - the extension
+///
+/// [`ArgumentSet::iter_sorted`]: ../struct.ArgumentSet.html#method.iter_sorted
+pub fn write_extension<T>(writer: &mut dyn Write, extension: &ArgumentSet<T>) -> Result<()>
+where
+    T: LabelType,
+{
+    writeln!(
+        writer,
+        "[{}]",
+        extension.iter().map(labels::format_label).fold(
+            String::new(),
+            |acc, s| if acc.is_empty() {
+                s
+            } else {
+                format!("{}, {}", acc, s)
             }
-            extensions
-                .as_mut()
-                .unwrap()
-                .push(read_extension_line_from_str(&l)?);
+        )
+    )
+    .context("while writing an extension")
+}
+
+/// Writes an extension set into the provided writer.
+///
+/// The extensions are written in the order of the slice, each one as described in [`write_extension`](fn.write_extension.html).
+///
+/// # Arguments
+/// * `writer` - the writer in which the status must be written
+/// * `extension_set` - the extension set
+pub fn write_extension_set<T>(
+    writer: &mut dyn Write,
+    extension_set: &[&ArgumentSet<T>],
+) -> Result<()>
+where
+    T: LabelType,
+{
+    let mut set_writer = ExtensionSetWriter::new(writer);
+    set_writer.begin()?;
+    for ext in extension_set {
+        set_writer.write_extension(ext)?;
+    }
+    set_writer.end()
+}
+
+/// A writer of extension sets, emitting their extensions one at a time.
+///
+/// This writer allows the producers of extension sets to write them as the extensions are computed, without collecting them first.
+/// The set must be opened by [`begin`] and closed by [`end`], the extensions being written between these calls as described in [`write_extension`].
+/// The output is the same as [`write_extension_set`]'s.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{solutions::ExtensionSetWriter, ArgumentSet};
+/// let mut out = Vec::new();
+/// let mut writer = ExtensionSetWriter::new(&mut out);
+/// writer.begin().unwrap();
+/// for labels in [vec!["a"], vec!["b", "c"]] {
+///     writer.write_extension(&ArgumentSet::new(labels)).unwrap();
+/// }
+/// writer.end().unwrap();
+/// assert_eq!(2, writer.n_extensions());
+/// assert_eq!("[\n[a]\n[b, c]\n]\n", String::from_utf8(out).unwrap());
+/// ```
+///
+/// [`begin`]: #method.begin
+/// [`end`]: #method.end
+/// [`write_extension`]: fn.write_extension.html
+/// [`write_extension_set`]: fn.write_extension_set.html
+pub struct ExtensionSetWriter<'a> {
+    writer: &'a mut dyn Write,
+    state: ExtensionSetWriterState,
+    n_extensions: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ExtensionSetWriterState {
+    NotBegun,
+    Begun,
+    Ended,
+}
+
+impl<'a> ExtensionSetWriter<'a> {
+    const CONTEXT: &'static str = "while writing an extension set";
+
+    /// Builds a new writer of an extension set.
+    ///
+    /// Nothing is written until [`begin`](#method.begin) is called.
+    ///
+    /// # Arguments
+    /// * `writer` - the writer in which the extension set must be written
+    pub fn new(writer: &'a mut dyn Write) -> Self {
+        ExtensionSetWriter {
+            writer,
+            state: ExtensionSetWriterState::NotBegun,
+            n_extensions: 0,
         }
     }
-    Err(anyhow!("unterminated extension set"))
+
+    /// Writes the beginning of the extension set.
+    ///
+    /// An error is returned if the set was already begun.
+    pub fn begin(&mut self) -> Result<()> {
+        if self.state != ExtensionSetWriterState::NotBegun {
+            return Err(anyhow!("the extension set was already begun"));
+        }
+        writeln!(self.writer, "[").context(Self::CONTEXT)?;
+        self.state = ExtensionSetWriterState::Begun;
+        Ok(())
+    }
+
+    /// Writes an extension of the set.
+    ///
+    /// An error is returned if the set is not begun or if it is ended.
+    ///
+    /// # Arguments
+    /// * `extension` - the extension
+    pub fn write_extension<T>(&mut self, extension: &ArgumentSet<T>) -> Result<()>
+    where
+        T: LabelType,
+    {
+        if self.state != ExtensionSetWriterState::Begun {
+            return Err(anyhow!(
+                "extensions must be written between the beginning and the end of the set"
+            ));
+        }
+        write_extension(self.writer, extension).context(Self::CONTEXT)?;
+        self.n_extensions += 1;
+        Ok(())
+    }
+
+    /// Writes the end of the extension set.
+    ///
+    /// An error is returned if the set is not begun or if it is already ended.
+    pub fn end(&mut self) -> Result<()> {
+        if self.state != ExtensionSetWriterState::Begun {
+            return Err(anyhow!("the extension set is not begun or already ended"));
+        }
+        writeln!(self.writer, "]").context(Self::CONTEXT)?;
+        self.state = ExtensionSetWriterState::Ended;
+        Ok(())
+    }
+
+    /// Returns the number of extensions written so far.
+    pub fn n_extensions(&self) -> usize {
+        self.n_extensions
+    }
+}
+
+/// Checks whether two extensions contain the same arguments, regardless of their order.
+fn same_extension<T>(first: &ArgumentSet<T>, second: &ArgumentSet<T>) -> bool
+where
+    T: LabelType,
+{
+    first.len() == second.len() && first.is_subset_of(second)
+}
+
+fn contains_extension<T>(extension_set: &[&ArgumentSet<T>], extension: &ArgumentSet<T>) -> bool
+where
+    T: LabelType,
+{
+    extension_set.iter().any(|e| same_extension(e, extension))
+}
+
+/// Checks whether two extension sets contain the same extensions, regardless of the order of the extensions and of their arguments.
+///
+/// Extensions given more than once are considered once.
+///
+/// # Arguments
+/// * `first` - the first extension set
+/// * `second` - the second extension set
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{solutions, ArgumentSet};
+/// let first = vec![ArgumentSet::new(vec!["a", "b"]), ArgumentSet::new(vec![])];
+/// let second = vec![ArgumentSet::new(vec![]), ArgumentSet::new(vec!["b", "a"])];
+/// assert!(solutions::extension_sets_equal(&first, &second));
+/// assert!(!solutions::extension_sets_equal(&first, &second[..1]));
+/// ```
+pub fn extension_sets_equal<T>(first: &[ArgumentSet<T>], second: &[ArgumentSet<T>]) -> bool
+where
+    T: LabelType,
+{
+    extension_set_difference(first, second).is_empty()
+        && extension_set_difference(second, first).is_empty()
 }
 
-/// Writes an acceptance status into the provided writer.
+/// Returns the extensions of the first set which are not in the second one, in the order of the first set.
 ///
-/// # Arguments
-/// * `writer` - the writer in which the status must be written
-/// * `status` - the acceptance status
-pub fn write_acceptance_status(writer: &mut dyn Write, status: bool) -> Result<()> {
-    writeln!(writer, "{}", if status { "YES" } else { "NO" })
-        .context("while writing an acceptance status")
-}
-
-/// Writes an extension count into the provided writer.
+/// Extensions are compared regardless of the order of their arguments, and extensions given more than once are returned once.
 ///
 /// # Arguments
-/// * `writer` - the writer in which the status must be written
-/// * `count` - the extension count
-pub fn write_extension_count(writer: &mut dyn Write, count: usize) -> Result<()> {
-    writeln!(writer, "{}", count).context("while writing an extension count")
+/// * `first` - the extension set whose extensions are returned
+/// * `second` - the extension set whose extensions are removed
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{solutions, ArgumentSet};
+/// let first = vec![ArgumentSet::new(vec!["a", "b"]), ArgumentSet::new(vec!["c"])];
+/// let second = vec![ArgumentSet::new(vec!["b", "a"])];
+/// let difference = solutions::extension_set_difference(&first, &second);
+/// assert_eq!(1, difference.len());
+/// assert_eq!(&"c", difference[0].get_argument_by_id(0).label());
+/// ```
+pub fn extension_set_difference<'a, T>(
+    first: &'a [ArgumentSet<T>],
+    second: &[ArgumentSet<T>],
+) -> Vec<&'a ArgumentSet<T>>
+where
+    T: LabelType,
+{
+    let second = second.iter().collect::<Vec<&ArgumentSet<T>>>();
+    first.iter().fold(vec![], |mut acc, e| {
+        if !contains_extension(&second, e) && !contains_extension(&acc, e) {
+            acc.push(e);
+        }
+        acc
+    })
 }
 
-/// Writes an extension into the provided writer.
+/// Returns the extensions belonging to both extension sets, in the order of the first set.
+///
+/// Extensions are compared regardless of the order of their arguments, and extensions given more than once are returned once.
 ///
 /// # Arguments
-/// * `writer` - the writer in which the status must be written
-/// * `extension` - the extension
-pub fn write_extension<T>(writer: &mut dyn Write, extension: &ArgumentSet<T>) -> Result<()>
+/// * `first` - the first extension set
+/// * `second` - the second extension set
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{solutions, ArgumentSet};
+/// let first = vec![ArgumentSet::new(vec!["a", "b"]), ArgumentSet::new(vec!["c"])];
+/// let second = vec![ArgumentSet::new(vec!["d"]), ArgumentSet::new(vec!["b", "a"])];
+/// let intersection = solutions::extension_set_intersection(&first, &second);
+/// assert_eq!(1, intersection.len());
+/// assert_eq!(2, intersection[0].len());
+/// ```
+pub fn extension_set_intersection<'a, T>(
+    first: &'a [ArgumentSet<T>],
+    second: &[ArgumentSet<T>],
+) -> Vec<&'a ArgumentSet<T>>
 where
     T: LabelType,
 {
-    writeln!(
-        writer,
-        "[{}]",
-        extension.iter().map(|a| format!("{}", a)).fold(
-            String::new(),
-            |acc, s| if acc.is_empty() {
-                s
-            } else {
-                format!("{}, {}", acc, s)
-            }
-        )
-    )
-    .context("while writing an extension")
+    let second = second.iter().collect::<Vec<&ArgumentSet<T>>>();
+    first.iter().fold(vec![], |mut acc, e| {
+        if contains_extension(&second, e) && !contains_extension(&acc, e) {
+            acc.push(e);
+        }
+        acc
+    })
 }
 
-/// Writes an extension set into the provided writer.
+/// Returns the extensions belonging to any of the extension sets: the ones of the first set, then the other ones of the second set.
+///
+/// Extensions are compared regardless of the order of their arguments, and extensions given more than once are returned once.
+/// The result can be written by [`write_extension_set`].
 ///
 /// # Arguments
-/// * `writer` - the writer in which the status must be written
-/// * `extension_set` - the extension set
-pub fn write_extension_set<T>(
-    writer: &mut dyn Write,
-    extension_set: &[&ArgumentSet<T>],
-) -> Result<()>
+/// * `first` - the first extension set
+/// * `second` - the second extension set
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{solutions, ArgumentSet};
+/// let first = vec![ArgumentSet::new(vec!["a", "b"])];
+/// let second = vec![ArgumentSet::new(vec!["c"]), ArgumentSet::new(vec!["b", "a"])];
+/// let union = solutions::extension_set_union(&first, &second);
+/// let mut output = Vec::new();
+/// solutions::write_extension_set(&mut output, &union).unwrap();
+/// assert_eq!("[\n[a, b]\n[c]\n]\n", String::from_utf8(output).unwrap());
+/// ```
+///
+/// [`write_extension_set`]: fn.write_extension_set.html
+pub fn extension_set_union<'a, T>(
+    first: &'a [ArgumentSet<T>],
+    second: &'a [ArgumentSet<T>],
+) -> Vec<&'a ArgumentSet<T>>
 where
     T: LabelType,
 {
-    const CONTEXT: &str = "while writing an extension set";
-    writeln!(writer, "[").context(CONTEXT)?;
-    for ext in extension_set {
-        write_extension(writer, ext).context(CONTEXT)?;
-    }
-    writeln!(writer, "]").context(CONTEXT)
+    first
+        .iter()
+        .chain(second.iter())
+        .fold(vec![], |mut acc, e| {
+            if !contains_extension(&acc, e) {
+                acc.push(e);
+            }
+            acc
+        })
 }
 
 // kcov-ignore-start
 
 #[cfg(test)]
 mod tests {
-    use std::io::{Cursor, Read, Seek, SeekFrom};
+    use crate::utils::{read_log::ReadLog, writable_string::WritableString};
 
     use super::*;
 
@@ -278,17 +1451,213 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_strict_acceptance_status() {
+        let reader = SolutionReader::new(Conformance::Strict);
+        assert!(reader
+            .read_acceptance_status(&mut "YES\n".as_bytes())
+            .unwrap());
+        assert!(!reader
+            .read_acceptance_status(&mut "NO\r\n".as_bytes())
+            .unwrap());
+        assert!(reader
+            .read_acceptance_status(&mut "YES \n".as_bytes())
+            .is_err());
+        assert!(reader
+            .read_acceptance_status(&mut "\nYES\n".as_bytes())
+            .is_err());
+        assert!(reader
+            .read_acceptance_status(&mut "YES".as_bytes())
+            .is_err());
+    }
+
+    #[test]
+    fn test_lenient_skips_comments() {
+        let reader = SolutionReader::new(Conformance::Lenient);
+        assert!(reader
+            .read_acceptance_status(&mut "# c\n\n YES\n".as_bytes())
+            .unwrap());
+        assert_eq!(
+            2,
+            reader
+                .read_extension_set(&mut "[\n# c\n[a]\n\n[b]\n]\n".as_bytes())
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[test]
+    fn test_approximate_status() {
+        let read = |s: &str| read_approximate_status(&mut s.as_bytes());
+        assert_eq!(ApproximateStatus::Yes(None), read("YES\n").unwrap());
+        assert_eq!(
+            ApproximateStatus::No(Some(0.)),
+            read(" NO  0 \r\n").unwrap()
+        );
+        assert_eq!(ApproximateStatus::Yes(Some(1.)), read("YES 1.0\n").unwrap());
+        assert_eq!(ApproximateStatus::Unknown, read("UNKNOWN\n").unwrap());
+        for wrong in &[
+            "UNKNOWN 0.5\n",
+            "YES -0.1\n",
+            "NO x\n",
+            "YES 0.5 0.5\n",
+            "\n",
+        ] {
+            assert!(read(wrong).is_err());
+        }
+        let strict = SolutionReader::new(Conformance::Strict);
+        assert_eq!(
+            ApproximateStatus::No(Some(0.25)),
+            strict
+                .read_approximate_status(&mut "NO 0.25\n".as_bytes())
+                .unwrap()
+        );
+        assert!(strict
+            .read_approximate_status(&mut "NO  0.25\n".as_bytes())
+            .is_err());
+    }
+
+    #[test]
+    fn test_write_approximate_status() {
+        for status in &[
+            ApproximateStatus::Yes(None),
+            ApproximateStatus::No(Some(0.5)),
+            ApproximateStatus::Unknown,
+        ] {
+            let mut out = Vec::new();
+            write_approximate_status(&mut out, *status).unwrap();
+            assert_eq!(
+                *status,
+                read_approximate_status(&mut out.as_slice()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_strict_extensions() {
+        let reader = SolutionReader::new(Conformance::Strict);
+        assert_eq!(
+            2,
+            reader
+                .read_extension(&mut "[a,b]\n".as_bytes())
+                .unwrap()
+                .len()
+        );
+        assert_eq!(
+            2,
+            reader
+                .read_extension(&mut "[a, b]\n".as_bytes())
+                .unwrap()
+                .len()
+        );
+        assert_eq!(
+            0,
+            reader.read_extension(&mut "[]\n".as_bytes()).unwrap().len()
+        );
+        assert!(reader.read_extension(&mut "[ a,b]\n".as_bytes()).is_err());
+        assert!(reader.read_extension(&mut "[a,  b]\n".as_bytes()).is_err());
+        assert!(reader
+            .read_numeric_extension(&mut "[1, 2]\n".as_bytes())
+            .is_ok());
+        assert!(reader
+            .read_numeric_extension(&mut "[1 ,2]\n".as_bytes())
+            .is_err());
+        assert_eq!(
+            2,
+            reader
+                .read_extension_set(&mut "[\n[a]\n[b]\n]\n".as_bytes())
+                .unwrap()
+                .len()
+        );
+        assert!(reader
+            .read_extension_set(&mut "[\n[a]\n ]\n".as_bytes())
+            .is_err());
+        assert!(reader
+            .read_extension_set(&mut "[\n# c\n]\n".as_bytes())
+            .is_err());
+    }
+
+    #[test]
+    fn test_strict_extension_count() {
+        let reader = SolutionReader::new(Conformance::Strict);
+        assert_eq!(
+            12,
+            reader.read_extension_count(&mut "12\n".as_bytes()).unwrap()
+        );
+        assert_eq!(
+            "0",
+            reader
+                .read_big_extension_count(&mut "0\n".as_bytes())
+                .unwrap()
+        );
+        assert!(reader
+            .read_extension_count(&mut "012\n".as_bytes())
+            .is_err());
+        assert!(reader
+            .read_extension_count(&mut " 12\n".as_bytes())
+            .is_err());
+    }
+
     #[test]
     fn test_wrong_acceptance_status() {
         let answer = "MAYBE\n";
         assert_eq!(
-            "expected an acceptance status, found \"MAYBE\"",
+            "line 1, column 1: expected an acceptance status, found \"MAYBE\"",
             read_acceptance_status(&mut answer.as_bytes())
                 .unwrap_err()
                 .to_string()
         );
     }
 
+    #[test]
+    fn test_error_positions() {
+        let position = |error: anyhow::Error| {
+            let error = error.downcast_ref::<ParseError>().unwrap().clone();
+            (error.line(), error.column(), error.token().to_string())
+        };
+        assert_eq!(
+            (Some(4), 7, "d".to_string()),
+            position(
+                read_extension_set(&mut "[\n[a]\n# comment\n[b, c d]\n]\n".as_bytes())
+                    .err()
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            (Some(2), 1, "[".to_string()),
+            position(read_extension_set(&mut "[\n[\n".as_bytes()).err().unwrap())
+        );
+        assert_eq!(
+            (Some(1), 3, "x".to_string()),
+            position(read_extension_count(&mut " 1x\n".as_bytes()).err().unwrap())
+        );
+        assert_eq!(
+            (Some(1), 5, "2".to_string()),
+            position(
+                read_approximate_status(&mut "YES 2\n".as_bytes())
+                    .err()
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            (Some(1), 4, "".to_string()),
+            position(
+                SolutionReader::new(Conformance::Strict)
+                    .read_acceptance_status(&mut "YES".as_bytes())
+                    .err()
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            (Some(1), 5, "x".to_string()),
+            position(
+                read_numeric_extension(&mut "[1, x]\n".as_bytes())
+                    .err()
+                    .unwrap()
+            )
+        );
+    }
+
     #[test]
     fn test_empty_acceptance_status() {
         let answer = "";
@@ -304,7 +1673,7 @@ mod tests {
     fn test_acceptance_status_no_newline() {
         let answer = "YES or NO";
         assert_eq!(
-            "expected an acceptance status, found \"YES or NO\"",
+            "line 1, column 5: expected an acceptance status, found \"or\"",
             read_acceptance_status(&mut answer.as_bytes())
                 .unwrap_err()
                 .to_string()
@@ -394,6 +1763,150 @@ mod tests {
         assert!(read_extension(&mut answer.as_bytes()).is_err());
     }
 
+    #[test]
+    fn test_numeric_extension_line() {
+        let answer = " [ 1, 17,42 ] ";
+        let extension = read_numeric_extension(&mut answer.as_bytes()).unwrap();
+        assert_eq!(
+            vec![1, 17, 42],
+            extension.iter().map(|a| *a.label()).collect::<Vec<usize>>()
+        );
+    }
+
+    #[test]
+    fn test_numeric_extension_line_empty() {
+        let answer = "[]";
+        let extension = read_numeric_extension(&mut answer.as_bytes()).unwrap();
+        assert_eq!(0, extension.len());
+    }
+
+    #[test]
+    fn test_numeric_extension_line_with_label() {
+        let answer = "[1, a]";
+        assert!(read_numeric_extension(&mut answer.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_numeric_label_grammar() {
+        let mut reader = SolutionReader::default();
+        reader.set_label_grammar(LabelGrammar::Numeric);
+        let extension = reader
+            .read_extension(&mut " [ 1, 17,42 ] \n".as_bytes())
+            .unwrap();
+        assert_eq!(
+            vec!["1", "17", "42"],
+            extension
+                .iter()
+                .map(|a| a.label().as_str())
+                .collect::<Vec<&str>>()
+        );
+        assert!(reader.read_extension(&mut "[1, a]\n".as_bytes()).is_err());
+        assert_eq!(
+            2,
+            reader
+                .read_extension_set(&mut "[\n[1]\n[2,3]\n]\n".as_bytes())
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[test]
+    fn test_quoted_label_grammar() {
+        let mut reader = SolutionReader::default();
+        reader.set_label_grammar(LabelGrammar::Quoted);
+        let extension = reader
+            .read_extension(&mut r#"["a, b", "c \"d\"", "e\\f", ""]"#.as_bytes())
+            .unwrap();
+        assert_eq!(
+            vec!["a, b", "c \"d\"", "e\\f", ""],
+            extension
+                .iter()
+                .map(|a| a.label().as_str())
+                .collect::<Vec<&str>>()
+        );
+        assert!(reader.read_extension(&mut "[a]\n".as_bytes()).is_err());
+        assert!(reader.read_extension(&mut r#"["a]"#.as_bytes()).is_err());
+        assert!(reader.read_extension(&mut r#"["a\b"]"#.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_identifier_label_grammar_quoted() {
+        let extension = read_extension(&mut r#"[a, "débat, tour 1", été]"#.as_bytes()).unwrap();
+        assert_eq!(
+            vec!["a", "débat, tour 1", "été"],
+            extension
+                .iter()
+                .map(|a| a.label().as_str())
+                .collect::<Vec<&str>>()
+        );
+        let mut output = WritableString::default();
+        write_extension(&mut output, &extension).unwrap();
+        assert_eq!("[a, \"débat, tour 1\", \"été\"]\n", output.as_str());
+        assert!(read_extension(&mut "[a b]".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_extension_labels_borrowed() {
+        let labels = extension_labels(
+            r#"[a, "b c", "d\"e", 1]"#,
+            LabelGrammar::Identifier,
+            Conformance::Lenient,
+        );
+        assert_eq!(Err(19), labels);
+        let labels = extension_labels(
+            r#"[a, "b c", "d\"e"]"#,
+            LabelGrammar::Identifier,
+            Conformance::Lenient,
+        )
+        .unwrap();
+        assert!(matches!(labels[0], Cow::Borrowed("a")));
+        assert!(matches!(labels[1], Cow::Borrowed("b c")));
+        assert!(matches!(&labels[2], Cow::Owned(l) if l == r#"d"e"#));
+    }
+
+    #[test]
+    fn test_interned_extension_set() {
+        let mut interner = LabelInterner::with_labels_of(&ArgumentSet::new(vec!["a", "b"]));
+        let extensions = SolutionReader::new(Conformance::Strict)
+            .read_interned_extension_set(&mut "[\n[a, b]\n[b]\n[]\n]\n".as_bytes(), &mut interner)
+            .unwrap();
+        assert_eq!(3, extensions.len());
+        assert_eq!(2, interner.len());
+        assert!(std::sync::Arc::ptr_eq(
+            extensions[0].get_argument_by_id(1).label(),
+            extensions[1].get_argument_by_id(0).label()
+        ));
+        assert!(SolutionReader::default()
+            .read_interned_extension_set(&mut "[\n[a b]\n]\n".as_bytes(), &mut interner)
+            .is_err());
+    }
+
+    #[test]
+    fn test_strict_label_grammar() {
+        let mut reader = SolutionReader::new(Conformance::Strict);
+        reader.set_label_grammar(LabelGrammar::Quoted);
+        assert_eq!(
+            2,
+            reader
+                .read_extension(&mut "[\"a\", \"b\"]\n".as_bytes())
+                .unwrap()
+                .len()
+        );
+        assert!(reader
+            .read_extension(&mut "[\"a\" ,\"b\"]\n".as_bytes())
+            .is_err());
+        assert!(reader.read_extension(&mut " [\"a\"]\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_numeric_extension_set() {
+        let answer = "[\n[1,2]\n[]\n]\n";
+        let extensions = read_numeric_extension_set(&mut answer.as_bytes()).unwrap();
+        assert_eq!(2, extensions.len());
+        assert_eq!(2, extensions[0].len());
+        assert_eq!(0, extensions[1].len());
+    }
+
     #[test]
     fn test_extension_set_empty_single_line() {
         let answer = "[]";
@@ -450,6 +1963,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_extension_set_crlf() {
+        let answer = "[\r\n[a0, a1]\r\n[]\r\n]\r\n";
+        let ext_set = read_extension_set(&mut answer.as_bytes()).unwrap();
+        assert_eq!(2, ext_set.len());
+        assert_eq!(2, ext_set[0].len());
+        assert_eq!(0, ext_set[1].len());
+    }
+
     #[test]
     fn test_extension_set_containing_empty_extension() {
         let answer = "[\n[]\n]";
@@ -520,66 +2042,87 @@ mod tests {
     }
 
     #[test]
-    fn test_write_acceptance_status_yes() {
-        let mut cursor = Cursor::new(vec![]);
-        write_acceptance_status(&mut cursor, true).unwrap();
-        cursor.seek(SeekFrom::Start(0)).unwrap();
+    fn test_big_extension_count() {
+        let answer = "123456789012345678901234567890\n";
+        assert_eq!(
+            "123456789012345678901234567890",
+            read_big_extension_count(&mut answer.as_bytes()).unwrap()
+        );
+        assert_eq!(
+            "0",
+            read_big_extension_count(&mut "000".as_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_big_extension_count_crlf() {
+        assert_eq!(
+            "12",
+            read_big_extension_count(&mut "12\r\n".as_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_big_extension_count_errors() {
+        assert!(read_big_extension_count(&mut "-1".as_bytes()).is_err());
+        assert!(read_big_extension_count(&mut "a".as_bytes()).is_err());
+        assert!(read_big_extension_count(&mut "".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_write_big_extension_count() {
         let mut out = Vec::new();
-        cursor.read_to_end(&mut out).unwrap();
-        assert_eq!("YES\n", String::from_utf8(out).unwrap());
+        write_big_extension_count(&mut out, "123456789012345678901234567890").unwrap();
+        assert_eq!(
+            "123456789012345678901234567890\n",
+            String::from_utf8(out).unwrap()
+        );
+        assert!(write_big_extension_count(&mut Vec::new(), "1e3").is_err());
+    }
+
+    #[test]
+    fn test_write_acceptance_status_yes() {
+        let mut output = WritableString::default();
+        write_acceptance_status(&mut output, true).unwrap();
+        assert_eq!("YES\n", output.as_str());
     }
 
     #[test]
     fn test_write_acceptance_status_no() {
-        let mut cursor = Cursor::new(vec![]);
-        write_acceptance_status(&mut cursor, false).unwrap();
-        cursor.seek(SeekFrom::Start(0)).unwrap();
-        let mut out = Vec::new();
-        cursor.read_to_end(&mut out).unwrap();
-        assert_eq!("NO\n", String::from_utf8(out).unwrap());
+        let mut output = WritableString::default();
+        write_acceptance_status(&mut output, false).unwrap();
+        assert_eq!("NO\n", output.as_str());
     }
 
     #[test]
     fn test_write_extension_count() {
-        let mut cursor = Cursor::new(vec![]);
-        write_extension_count(&mut cursor, 1).unwrap();
-        cursor.seek(SeekFrom::Start(0)).unwrap();
-        let mut out = Vec::new();
-        cursor.read_to_end(&mut out).unwrap();
-        assert_eq!("1\n", String::from_utf8(out).unwrap());
+        let mut output = WritableString::default();
+        write_extension_count(&mut output, 1).unwrap();
+        assert_eq!("1\n", output.as_str());
     }
 
     #[test]
     fn test_write_extension_no_args() {
         let extension = ArgumentSet::new(vec![] as Vec<String>);
-        let mut cursor = Cursor::new(vec![]);
-        write_extension(&mut cursor, &extension).unwrap();
-        cursor.seek(SeekFrom::Start(0)).unwrap();
-        let mut out = Vec::new();
-        cursor.read_to_end(&mut out).unwrap();
-        assert_eq!("[]\n", String::from_utf8(out).unwrap());
+        let mut output = WritableString::default();
+        write_extension(&mut output, &extension).unwrap();
+        assert_eq!("[]\n", output.as_str());
     }
 
     #[test]
     fn test_write_extension_one_arg() {
         let extension = ArgumentSet::new(vec!["a"]);
-        let mut cursor = Cursor::new(vec![]);
-        write_extension(&mut cursor, &extension).unwrap();
-        cursor.seek(SeekFrom::Start(0)).unwrap();
-        let mut out = Vec::new();
-        cursor.read_to_end(&mut out).unwrap();
-        assert_eq!("[a]\n", String::from_utf8(out).unwrap());
+        let mut output = WritableString::default();
+        write_extension(&mut output, &extension).unwrap();
+        assert_eq!("[a]\n", output.as_str());
     }
 
     #[test]
     fn test_write_extension_two_args() {
         let extension = ArgumentSet::new(vec!["a", "b"]);
-        let mut cursor = Cursor::new(vec![]);
-        write_extension(&mut cursor, &extension).unwrap();
-        cursor.seek(SeekFrom::Start(0)).unwrap();
-        let mut out = Vec::new();
-        cursor.read_to_end(&mut out).unwrap();
-        assert_eq!("[a, b]\n", String::from_utf8(out).unwrap());
+        let mut output = WritableString::default();
+        write_extension(&mut output, &extension).unwrap();
+        assert_eq!("[a, b]\n", output.as_str());
     }
 
     #[test]
@@ -589,16 +2132,191 @@ mod tests {
             ArgumentSet::new(vec!["a"]),
             ArgumentSet::new(vec!["a", "b"]),
         ];
-        let mut cursor = Cursor::new(vec![]);
+        let mut output = WritableString::default();
         write_extension_set(
-            &mut cursor,
+            &mut output,
             &extension_set.iter().collect::<Vec<&ArgumentSet<&str>>>(),
         )
         .unwrap();
-        cursor.seek(SeekFrom::Start(0)).unwrap();
-        let mut out = Vec::new();
-        cursor.read_to_end(&mut out).unwrap();
-        assert_eq!("[\n[]\n[a]\n[a, b]\n]\n", String::from_utf8(out).unwrap());
+        assert_eq!("[\n[]\n[a]\n[a, b]\n]\n", output.as_str());
+    }
+
+    #[test]
+    fn test_extension_set_writer_empty() {
+        let mut output = WritableString::default();
+        let mut writer = ExtensionSetWriter::new(&mut output);
+        writer.begin().unwrap();
+        writer.end().unwrap();
+        assert_eq!(0, writer.n_extensions());
+        assert_eq!("[\n]\n", output.as_str());
+    }
+
+    #[test]
+    fn test_extension_set_writer_misuse() {
+        let mut output = WritableString::default();
+        let mut writer = ExtensionSetWriter::new(&mut output);
+        assert!(writer
+            .write_extension(&ArgumentSet::new(vec!["a"]))
+            .is_err());
+        assert!(writer.end().is_err());
+        writer.begin().unwrap();
+        assert!(writer.begin().is_err());
+        writer.end().unwrap();
+        assert!(writer
+            .write_extension(&ArgumentSet::new(vec!["a"]))
+            .is_err());
+        assert!(writer.end().is_err());
+        assert_eq!("[\n]\n", output.as_str());
+    }
+
+    #[test]
+    fn test_iter_extension_set_lazy() {
+        let mut log = ReadLog::new("[\n[a, b]\n[c]\n]\n".as_bytes());
+        let mut iter = iter_extension_set(&mut log);
+        assert_eq!(2, iter.next().unwrap().unwrap().len());
+        drop(iter);
+        assert_eq!("[\n[a, b]\n", log.to_string());
+    }
+
+    #[test]
+    fn test_iter_extension_set_empty() {
+        assert!(iter_extension_set(&mut "[]\n".as_bytes()).next().is_none());
+        assert!(iter_extension_set(&mut "[\n]\n".as_bytes())
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn test_iter_extension_set_unterminated() {
+        let mut input = "[\n[a]\n".as_bytes();
+        let mut iter = iter_extension_set(&mut input);
+        assert!(iter.next().unwrap().is_ok());
+        assert_eq!(
+            "unterminated extension set",
+            iter.next().unwrap().err().unwrap().to_string()
+        );
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_iter_extension_set_stops_after_error() {
+        let mut input = "[\n[a\n[b]\n]\n".as_bytes();
+        let mut iter = iter_extension_set(&mut input);
+        let error = iter.next().unwrap().err().unwrap();
+        assert_eq!(Some(2), error.downcast_ref::<ParseError>().unwrap().line());
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_iter_extension_set_finish() {
+        let mut input = "[\n[a]\n[b]\n]\n".as_bytes();
+        let mut iter = iter_extension_set(&mut input);
+        iter.next().unwrap().unwrap();
+        iter.finish().unwrap();
+        assert!(input.is_empty());
+        let mut input = "[\n[a]\n[b]\n".as_bytes();
+        let mut iter = iter_extension_set(&mut input);
+        iter.next().unwrap().unwrap();
+        assert!(iter.finish().is_err());
+    }
+
+    fn extension_set(extensions: &[&[&'static str]]) -> Vec<ArgumentSet<&'static str>> {
+        extensions
+            .iter()
+            .map(|e| ArgumentSet::new(e.to_vec()))
+            .collect()
+    }
+
+    fn labels(extensions: &[&ArgumentSet<&'static str>]) -> Vec<Vec<&'static str>> {
+        extensions
+            .iter()
+            .map(|e| e.iter().map(|a| *a.label()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_extension_sets_equal() {
+        let first = extension_set(&[&["a", "b"], &[], &["c"]]);
+        assert!(extension_sets_equal(
+            &first,
+            &extension_set(&[&["c"], &["b", "a"], &[]])
+        ));
+        assert!(extension_sets_equal(
+            &first,
+            &extension_set(&[&["c"], &["b", "a"], &[], &["c"]])
+        ));
+        assert!(!extension_sets_equal(
+            &first,
+            &extension_set(&[&["c"], &["b", "a"]])
+        ));
+        assert!(!extension_sets_equal(
+            &first,
+            &extension_set(&[&["c"], &["b"], &[]])
+        ));
+        assert!(extension_sets_equal(
+            &extension_set(&[]),
+            &extension_set(&[])
+        ));
+    }
+
+    #[test]
+    fn test_extension_set_difference() {
+        let first = extension_set(&[&["a", "b"], &["c"], &["d"], &["c"]]);
+        let second = extension_set(&[&["b", "a"], &["e"]]);
+        assert_eq!(
+            vec![vec!["c"], vec!["d"]],
+            labels(&extension_set_difference(&first, &second))
+        );
+        assert_eq!(
+            vec![vec!["e"]],
+            labels(&extension_set_difference(&second, &first))
+        );
+    }
+
+    #[test]
+    fn test_extension_set_intersection() {
+        let first = extension_set(&[&["a", "b"], &["c"], &["a", "b"]]);
+        let second = extension_set(&[&["c"], &["b", "a"], &["e"]]);
+        assert_eq!(
+            vec![vec!["a", "b"], vec!["c"]],
+            labels(&extension_set_intersection(&first, &second))
+        );
+        assert!(extension_set_intersection(&first, &extension_set(&[])).is_empty());
+    }
+
+    #[test]
+    fn test_extension_set_union() {
+        let first = extension_set(&[&["a", "b"], &["a", "b"]]);
+        let second = extension_set(&[&["c"], &["b", "a"], &[]]);
+        assert_eq!(
+            vec![vec!["a", "b"], vec!["c"], vec![]],
+            labels(&extension_set_union(&first, &second))
+        );
+    }
+
+    #[test]
+    fn test_acceptance_status_or_unknown() {
+        let read = |s: &str| read_acceptance_status_or_unknown(&mut s.as_bytes());
+        assert_eq!(Some(true), read("YES\n").unwrap());
+        assert_eq!(Some(false), read(" NO \r\n").unwrap());
+        assert_eq!(None, read("UNKNOWN\n").unwrap());
+        assert_eq!(None, read("TIMEOUT\n").unwrap());
+        assert!(read("MAYBE\n").is_err());
+        assert!(read("\n").is_err());
+        let strict = SolutionReader::new(Conformance::Strict);
+        assert_eq!(
+            None,
+            strict
+                .read_acceptance_status_or_unknown(&mut "UNKNOWN\n".as_bytes())
+                .unwrap()
+        );
+        assert!(strict
+            .read_acceptance_status_or_unknown(&mut " UNKNOWN\n".as_bytes())
+            .is_err());
+        assert_eq!(
+            ApproximateStatus::Unknown,
+            read_approximate_status(&mut "TIMEOUT\n".as_bytes()).unwrap()
+        );
     }
 }
 
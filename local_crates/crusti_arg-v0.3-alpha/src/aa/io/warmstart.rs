@@ -0,0 +1,129 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! A module used to read and write warm-start blocks, giving solvers some extensions known in advance.
+//!
+//! A warm-start block lists extensions, one per line, using the format of the solutions (e.g. `[a, b]`).
+//! The block ends with an empty line.
+//! By convention, solvers expecting such a block are given the [`WARMSTART_FLAG`] on their command line, and read the block on their standard input before their first answer.
+//!
+//! [`WARMSTART_FLAG`]: constant.WARMSTART_FLAG.html
+use anyhow::{Context, Result};
+use std::io::{BufRead, Write};
+
+use crate::aa::io::solutions;
+use crate::{ArgumentSet, LabelType};
+
+/// The flag given to the solvers expecting a warm-start block.
+pub const WARMSTART_FLAG: &str = "-ws";
+
+/// Writes a warm-start block made of the provided extensions.
+///
+/// # Arguments
+/// * `writer` - the writer in which the block must be written
+/// * `extensions` - the extensions
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{warmstart, ArgumentSet};
+/// let extension = ArgumentSet::new(vec!["a".to_string(), "b".to_string()]);
+/// let mut out = Vec::new();
+/// warmstart::write_warmstart(&mut out, &[&extension]).unwrap();
+/// assert_eq!("[a, b]\n\n", String::from_utf8(out).unwrap());
+/// ```
+pub fn write_warmstart<T>(writer: &mut dyn Write, extensions: &[&ArgumentSet<T>]) -> Result<()>
+where
+    T: LabelType,
+{
+    const CONTEXT: &str = "while writing a warm-start block";
+    for ext in extensions {
+        solutions::write_extension(writer, ext).context(CONTEXT)?;
+    }
+    writeln!(writer).context(CONTEXT)?;
+    writer.flush().context(CONTEXT)
+}
+
+/// Reads a warm-start block, until an empty line or the end of the reader.
+///
+/// If a line of the block is not an extension, an error is returned.
+///
+/// # Arguments
+/// * `reader` - the reader in which the block must be read
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::warmstart;
+/// let extensions = warmstart::read_warmstart(&mut "[a]\n[]\n\n+arg(b).\n".as_bytes()).unwrap();
+/// assert_eq!(2, extensions.len());
+/// ```
+pub fn read_warmstart(reader: &mut dyn BufRead) -> Result<Vec<ArgumentSet<String>>> {
+    let mut extensions = vec![];
+    loop {
+        let mut line = String::new();
+        if reader
+            .read_line(&mut line)
+            .context("while reading a warm-start block")?
+            == 0
+            || line.trim().is_empty()
+        {
+            return Ok(extensions);
+        }
+        extensions.push(
+            solutions::read_extension_line_from_str(&line).with_context(|| {
+                format!(
+                    "while reading warm-start extension {}",
+                    extensions.len() + 1
+                )
+            })?,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_read() {
+        let e1 = ArgumentSet::new(vec!["a".to_string()]);
+        let e2 = ArgumentSet::new(vec![] as Vec<String>);
+        let mut out = Vec::new();
+        write_warmstart(&mut out, &[&e1, &e2]).unwrap();
+        assert_eq!("[a]\n[]\n\n", String::from_utf8(out.clone()).unwrap());
+        let mut reader = out.as_slice();
+        let extensions = read_warmstart(&mut reader).unwrap();
+        assert_eq!(2, extensions.len());
+        assert_eq!(1, extensions[0].len());
+        assert!(extensions[1].is_empty());
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_read_empty_block() {
+        assert!(read_warmstart(&mut "\n".as_bytes()).unwrap().is_empty());
+        assert!(read_warmstart(&mut "".as_bytes()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_wrong_line() {
+        assert!(read_warmstart(&mut "[a]\nfoo\n\n".as_bytes()).is_err());
+    }
+}
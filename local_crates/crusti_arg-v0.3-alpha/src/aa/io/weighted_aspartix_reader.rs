@@ -0,0 +1,238 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::io::aspartix_reader::{captured_arg, WarningHandler, ARG_AND_SPACE_PATTERN};
+use crate::{AAFramework, ArgumentSet};
+use anyhow::{anyhow, Context, Result};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::{
+    cell::RefCell,
+    io::{BufRead, BufReader, Read},
+    rc::Rc,
+};
+
+const WEIGHT_PATTERN: &str = r"(,\s*([^,()\s]+)\s*)?";
+
+lazy_static! {
+    static ref WEIGHTED_ARG_LINE_PATTERN: Regex = Regex::new(&format!(
+        r"^\s*arg\(({}){}\)\.\s*$",
+        ARG_AND_SPACE_PATTERN, WEIGHT_PATTERN
+    ))
+    .unwrap();
+    static ref WEIGHTED_ATT_LINE_PATTERN: Regex = Regex::new(&format!(
+        r"^\s*att\(({}),({}){}\)\.\s*$",
+        ARG_AND_SPACE_PATTERN, ARG_AND_SPACE_PATTERN, WEIGHT_PATTERN
+    ))
+    .unwrap();
+}
+
+fn captured_weight(c: &Captures, i: usize) -> Result<Option<f64>> {
+    match c.get(i) {
+        Some(w) => w
+            .as_str()
+            .parse::<f64>()
+            .map(Some)
+            .with_context(|| format!(r#"invalid weight "{}""#, w.as_str())),
+        None => Ok(None),
+    }
+}
+
+/// A reader for the weighted Aspartix dialect.
+///
+/// This dialect extends the Aspartix format by an optional weight given as the last parameter of the argument and attack declarations, e.g. `arg(a,0.5).` and `att(a,b,0.7).`.
+/// Declarations without weights are allowed, so any Aspartix framework is also a weighted Aspartix framework.
+/// The [`LabelType`] of the returned argument frameworks is `String`.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{AAFramework, WeightedAspartixReader};
+/// fn read_af_from_str(s: &str) -> AAFramework<String> {
+///     let reader = WeightedAspartixReader::default();
+///     reader.read(&mut s.as_bytes()).expect("invalid weighted Aspartix AF")
+/// }
+/// let af = read_af_from_str("arg(a).\narg(b).\natt(a,b,0.7).");
+/// assert_eq!(Some(0.7), af.iter_attacks().next().unwrap().weight());
+/// ```
+///
+/// [`LabelType`]: trait.LabelType.html
+#[derive(Default)]
+pub struct WeightedAspartixReader<'a> {
+    warning_handlers: Vec<WarningHandler<'a>>,
+}
+
+impl<'a> WeightedAspartixReader<'a> {
+    /// Reads an [`AAFramework`] encoded using the weighted Aspartix dialect.
+    /// The [`LabelType`] of the returned AFs is `String`.
+    ///
+    /// In case warnings are raised, the callback functions registered by [`add_warning_handler`] are triggered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{AAFramework, WeightedAspartixReader};
+    /// fn read_af_from_str(s: &str) -> AAFramework<String> {
+    ///     let reader = WeightedAspartixReader::default();
+    ///     reader.read(&mut s.as_bytes()).expect("invalid weighted Aspartix AF")
+    /// }
+    /// # read_af_from_str("arg(a,0.5).");
+    /// ```
+    ///
+    /// [`AAFramework`]: struct.AAFramework.html
+    /// [`LabelType`]: trait.LabelType.html
+    /// [`add_warning_handler`]: struct.WeightedAspartixReader.html#method.add_warning_handler
+    pub fn read(&self, reader: &mut dyn Read) -> Result<AAFramework<String>> {
+        let mut args = Some(vec![]);
+        let mut af: Option<AAFramework<String>> = None;
+        for (line_index, line) in BufReader::new(reader).lines().enumerate() {
+            let context = || format!("while reading line {}", line_index);
+            let warning_consumer = |warnings: Vec<String>| {
+                for w in warnings.iter() {
+                    self.warning_handlers
+                        .iter()
+                        .for_each(|h| (*h.borrow_mut())(line_index, w.to_string()));
+                }
+            };
+            let l = &line.with_context(context)?;
+            if l.trim().is_empty() {
+                continue;
+            }
+            if let Some(c) = WEIGHTED_ARG_LINE_PATTERN.captures(l) {
+                if af.is_some() {
+                    return Err(anyhow!("found an argument declaration after an attack"))
+                        .with_context(context);
+                }
+                let label = captured_arg(&c, 1).consume_warnings(warning_consumer);
+                let weight = captured_weight(&c, 3).with_context(context)?;
+                args.as_mut().unwrap().push((label, weight));
+                continue;
+            }
+            if let Some(c) = WEIGHTED_ATT_LINE_PATTERN.captures(l) {
+                let (a, b) = captured_arg(&c, 1)
+                    .zip(captured_arg(&c, 2))
+                    .consume_warnings(warning_consumer);
+                let weight = captured_weight(&c, 4).with_context(context)?;
+                if af.is_none() {
+                    af = Some(new_framework(args.take().unwrap())?);
+                }
+                let framework = af.as_mut().unwrap();
+                match weight {
+                    Some(w) => framework.new_weighted_attack(&a, &b, w),
+                    None => framework.new_attack(&a, &b),
+                }
+                .with_context(context)?;
+                continue;
+            }
+            return Err(anyhow!("syntax error in line \"{}\"", l)).with_context(context);
+        }
+        match af {
+            Some(a) => Ok(a),
+            None => new_framework(args.take().unwrap()),
+        }
+    }
+
+    /// Adds a callback function to call when warnings are raised while parsing an AF.
+    pub fn add_warning_handler(&mut self, h: &'a mut dyn FnMut(usize, String)) {
+        self.warning_handlers.push(Rc::new(RefCell::new(h)));
+    }
+}
+
+fn new_framework(args: Vec<(String, Option<f64>)>) -> Result<AAFramework<String>> {
+    let mut framework = AAFramework::new(ArgumentSet::new(
+        args.iter().map(|(l, _)| l.clone()).collect(),
+    ));
+    for (label, weight) in args.iter() {
+        if let Some(w) = weight {
+            framework.set_argument_weight(label, *w)?;
+        }
+    }
+    Ok(framework)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(instance: &str) -> Result<AAFramework<String>> {
+        WeightedAspartixReader::default().read(&mut instance.as_bytes())
+    }
+
+    #[test]
+    fn test_read_ok() {
+        let af = read("arg(a, 0.5).\narg(b).\natt(a,b).\natt(b,a, 0.7 ).\n").unwrap();
+        let args = af.argument_set();
+        assert_eq!(2, args.len());
+        assert_eq!(Some(0.5), af.argument_weight(args.get_argument_by_id(0)));
+        assert_eq!(None, af.argument_weight(args.get_argument_by_id(1)));
+        assert_eq!(
+            vec![
+                ("a".to_string(), "b".to_string(), None),
+                ("b".to_string(), "a".to_string(), Some(0.7))
+            ],
+            af.iter_attacks()
+                .map(|att| (
+                    att.attacker().to_string(),
+                    att.attacked().to_string(),
+                    att.weight()
+                ))
+                .collect::<Vec<(String, String, Option<f64>)>>()
+        );
+    }
+
+    #[test]
+    fn test_read_no_attacks() {
+        let af = read("arg(a,1).\n").unwrap();
+        assert_eq!(
+            Some(1.),
+            af.argument_weight(af.argument_set().get_argument_by_id(0))
+        );
+    }
+
+    #[test]
+    fn test_read_errors() {
+        [
+            "arg(a,x).\n",
+            "arg(a).\natt(a,a,0.1,0.2).\n",
+            "arg(a).\natt(a,b,0.1).\n",
+            "arg(a).\natt(a,a).\narg(b).\n",
+            "argument(a).\n",
+        ]
+        .iter()
+        .for_each(|i| assert!(read(i).is_err(), "{}", i));
+    }
+
+    #[test]
+    fn test_read_warn_arg_left_space() {
+        let mut warnings = vec![];
+        let mut closure = |i, w| warnings.push((i, w));
+        let mut reader = WeightedAspartixReader::default();
+        reader.add_warning_handler(&mut closure);
+        reader
+            .read(&mut "arg(a).\narg( b,0.1).\n".as_bytes())
+            .unwrap();
+        assert_eq!(
+            warnings,
+            vec![(
+                1,
+                "argument names beginning or ending by spaces may be ambiguous".to_string()
+            )]
+        );
+    }
+}
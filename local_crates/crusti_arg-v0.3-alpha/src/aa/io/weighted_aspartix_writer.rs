@@ -0,0 +1,129 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::aa_framework::AAFramework;
+use crate::aa::arguments::LabelType;
+use anyhow::Result;
+use std::io::Write;
+
+/// A writer for the weighted Aspartix dialect.
+///
+/// This object writes an [`AAFramework`] using the Aspartix format, appending the weights of the arguments and the attacks as their last parameter, e.g. `arg(a,0.5).` and `att(a,b,0.7).`.
+/// Arguments and attacks without weights are written as in the Aspartix format.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{AAFramework, ArgumentSet, WeightedAspartixWriter};
+/// let labels = vec!["a", "b"];
+/// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+/// framework.new_weighted_attack(&labels[0], &labels[1], 0.7).unwrap();
+/// let mut out = Vec::new();
+/// WeightedAspartixWriter::default().write(&framework, &mut out).unwrap();
+/// assert_eq!("arg(a).\narg(b).\natt(a,b,0.7).\n", String::from_utf8(out).unwrap());
+/// ```
+///
+/// [`AAFramework`]: struct.AAFramework.html
+#[derive(Default)]
+pub struct WeightedAspartixWriter {}
+
+impl WeightedAspartixWriter {
+    /// Writes a framework using the weighted Aspartix dialect to the provided writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `framework` - the framework
+    /// * `writer` - the writer
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::AAFramework;
+    /// # use crusti_arg::ArgumentSet;
+    /// # use crusti_arg::WeightedAspartixWriter;
+    /// # use crusti_arg::LabelType;
+    /// # use anyhow::Result;
+    /// fn write_af_to_stdout<T: LabelType>(af: &AAFramework<T>) -> Result<()> {
+    ///     let writer = WeightedAspartixWriter::default();
+    ///     writer.write(&af, &mut std::io::stdout())
+    /// }
+    /// # write_af_to_stdout(&AAFramework::new(ArgumentSet::new(vec![] as Vec<String>)));
+    /// ```
+    pub fn write<T: LabelType>(
+        &self,
+        framework: &AAFramework<T>,
+        writer: &mut dyn Write,
+    ) -> Result<()> {
+        for arg in framework.argument_set().iter() {
+            match framework.argument_weight(arg) {
+                Some(w) => writeln!(writer, "arg({},{}).", arg, w)?,
+                None => writeln!(writer, "arg({}).", arg)?,
+            }
+        }
+        for attack in framework.iter_attacks() {
+            match attack.weight() {
+                Some(w) => writeln!(
+                    writer,
+                    "att({},{},{}).",
+                    attack.attacker(),
+                    attack.attacked(),
+                    w
+                )?,
+                None => writeln!(writer, "att({},{}).", attack.attacker(), attack.attacked())?,
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aa::arguments::ArgumentSet;
+    use crate::WeightedAspartixReader;
+
+    #[test]
+    fn test_write_read() {
+        let instance = "arg(a,0.5).\narg(b).\natt(a,b).\natt(b,a,0.7).\n";
+        let framework = WeightedAspartixReader::default()
+            .read(&mut instance.as_bytes())
+            .unwrap();
+        let mut out = Vec::new();
+        WeightedAspartixWriter::default()
+            .write(&framework, &mut out)
+            .unwrap();
+        assert_eq!(instance, String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn test_write_unweighted() {
+        let arg_names = vec!["a".to_string(), "b".to_string()];
+        let mut framework = AAFramework::new(ArgumentSet::new(arg_names.clone()));
+        framework.new_attack(&arg_names[0], &arg_names[1]).unwrap();
+        let mut out = Vec::new();
+        WeightedAspartixWriter::default()
+            .write(&framework, &mut out)
+            .unwrap();
+        assert_eq!(
+            "arg(a).\narg(b).\natt(a,b).\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+}
@@ -0,0 +1,324 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! A module containing the MaxSAT solvers used to find optimal models of CNF formulas.
+//!
+//! A MaxSAT problem is made of a hard formula, which must be satisfied, and of weighted soft literals; the cost of a model is the sum of the weights of the soft literals it falsifies.
+//! The solvers implement the [`MaxSatSolver`] trait, and give a [`MaxSatOutcome`] telling whether the returned model is optimal.
+//! The [`LinearSearchSolver`] improves a model until no better one exists, and can be stopped at a deadline to get the best model found so far.
+//!
+//! [`LinearSearchSolver`]: struct.LinearSearchSolver.html
+//! [`MaxSatOutcome`]: struct.MaxSatOutcome.html
+//! [`MaxSatSolver`]: trait.MaxSatSolver.html
+
+use crate::aa::sat::{CdclSolver, Cnf, SatOutcome};
+use std::{fmt::Display, time::Instant};
+
+/// The status of the outcome of a MaxSAT solver.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MaxSatStatus {
+    /// A model is given, and no model has a lower cost.
+    Optimal,
+    /// A model is given, but the solver stopped before proving its optimality.
+    Feasible,
+    /// The hard formula is unsatisfiable.
+    Unsatisfiable,
+    /// The solver stopped before finding a model.
+    Unknown,
+}
+
+impl Display for MaxSatStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            MaxSatStatus::Optimal => "optimal",
+            MaxSatStatus::Feasible => "feasible",
+            MaxSatStatus::Unsatisfiable => "unsatisfiable",
+            MaxSatStatus::Unknown => "unknown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The outcome of a MaxSAT solver: a status, and the best model found with its cost, if any.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::maxsat::{MaxSatOutcome, MaxSatStatus};
+/// let outcome = MaxSatOutcome::new(MaxSatStatus::Optimal, Some((vec![true], 0)));
+/// assert_eq!(MaxSatStatus::Optimal, outcome.status());
+/// assert_eq!(Some(&[true][..]), outcome.model());
+/// assert_eq!(Some(0), outcome.cost());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct MaxSatOutcome {
+    status: MaxSatStatus,
+    solution: Option<(Vec<bool>, u64)>,
+}
+
+impl MaxSatOutcome {
+    /// Builds an outcome.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - the status of the outcome
+    /// * `solution` - the best model and its cost, if any
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::maxsat::{MaxSatOutcome, MaxSatStatus};
+    /// let outcome = MaxSatOutcome::new(MaxSatStatus::Unknown, None);
+    /// assert_eq!(None, outcome.model());
+    /// ```
+    pub fn new(status: MaxSatStatus, solution: Option<(Vec<bool>, u64)>) -> Self {
+        MaxSatOutcome { status, solution }
+    }
+
+    /// Returns the status of the outcome.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::maxsat::{MaxSatOutcome, MaxSatStatus};
+    /// let outcome = MaxSatOutcome::new(MaxSatStatus::Unsatisfiable, None);
+    /// assert_eq!(MaxSatStatus::Unsatisfiable, outcome.status());
+    /// ```
+    pub fn status(&self) -> MaxSatStatus {
+        self.status
+    }
+
+    /// Returns the best model found, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::maxsat::{MaxSatOutcome, MaxSatStatus};
+    /// let outcome = MaxSatOutcome::new(MaxSatStatus::Feasible, Some((vec![false], 3)));
+    /// assert_eq!(Some(&[false][..]), outcome.model());
+    /// ```
+    pub fn model(&self) -> Option<&[bool]> {
+        self.solution.as_ref().map(|(m, _)| m.as_slice())
+    }
+
+    /// Returns the cost of the best model found, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::maxsat::{MaxSatOutcome, MaxSatStatus};
+    /// let outcome = MaxSatOutcome::new(MaxSatStatus::Feasible, Some((vec![false], 3)));
+    /// assert_eq!(Some(3), outcome.cost());
+    /// ```
+    pub fn cost(&self) -> Option<u64> {
+        self.solution.as_ref().map(|(_, c)| *c)
+    }
+}
+
+/// A solver computing the models of minimal cost of a MaxSAT problem.
+///
+/// Soft literals are given with their weights, and the cost of a model is the sum of the weights of the soft literals it falsifies.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{maxsat::{MaxSatOutcome, MaxSatSolver, MaxSatStatus}, sat::Cnf};
+/// # use std::time::Instant;
+/// /// A solver giving up immediately.
+/// struct LazySolver;
+///
+/// impl MaxSatSolver for LazySolver {
+///     fn solve(&mut self, _hard: &Cnf, _soft: &[(i32, u64)], _deadline: Option<Instant>) -> MaxSatOutcome {
+///         MaxSatOutcome::new(MaxSatStatus::Unknown, None)
+///     }
+/// }
+///
+/// let outcome = LazySolver.solve(&Cnf::default(), &[], None);
+/// assert_eq!(MaxSatStatus::Unknown, outcome.status());
+/// ```
+pub trait MaxSatSolver {
+    /// Solves a MaxSAT problem.
+    ///
+    /// When the deadline is reached, the solver returns the best model found so far with the [`Feasible`] status, or the [`Unknown`] status if no model was found.
+    ///
+    /// # Arguments
+    ///
+    /// * `hard` - the formula which must be satisfied
+    /// * `soft` - the soft literals and their weights
+    /// * `deadline` - the instant at which the search is stopped, if any
+    ///
+    /// [`Feasible`]: enum.MaxSatStatus.html#variant.Feasible
+    /// [`Unknown`]: enum.MaxSatStatus.html#variant.Unknown
+    fn solve(
+        &mut self,
+        hard: &Cnf,
+        soft: &[(i32, u64)],
+        deadline: Option<Instant>,
+    ) -> MaxSatOutcome;
+}
+
+/// A MaxSAT solver improving a model until no better one exists, using the [`CdclSolver`].
+///
+/// Each time a model is found, a constraint requiring a lower cost is added and the formula is solved again; the last model is optimal when the formula becomes unsatisfiable.
+/// The weights are divided by their greatest common divisor, and the constraint is encoded by a generalized totalizer (see [`add_weighted_at_most`]), whose size depends on the number of distinct costs rather than on the weights themselves.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{maxsat::{LinearSearchSolver, MaxSatSolver, MaxSatStatus}, sat::Cnf};
+/// let mut cnf = Cnf::default();
+/// let a = cnf.new_var();
+/// let b = cnf.new_var();
+/// cnf.add_clause(&[a, b]);
+/// let outcome = LinearSearchSolver.solve(&cnf, &[(-a, 2), (-b, 1)], None);
+/// assert_eq!(MaxSatStatus::Optimal, outcome.status());
+/// assert_eq!(Some(&[false, true][..]), outcome.model());
+/// assert_eq!(Some(1), outcome.cost());
+/// ```
+///
+/// [`CdclSolver`]: ../sat/struct.CdclSolver.html
+/// [`add_weighted_at_most`]: ../sat/struct.Cnf.html#method.add_weighted_at_most
+#[derive(Default)]
+pub struct LinearSearchSolver;
+
+fn cost(model: &[bool], soft: &[(i32, u64)]) -> u64 {
+    soft.iter()
+        .filter(|(l, _)| model[l.unsigned_abs() as usize - 1] != (*l > 0))
+        .map(|(_, w)| *w)
+        .sum()
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl MaxSatSolver for LinearSearchSolver {
+    fn solve(
+        &mut self,
+        hard: &Cnf,
+        soft: &[(i32, u64)],
+        deadline: Option<Instant>,
+    ) -> MaxSatOutcome {
+        let divisor = soft.iter().fold(0, |d, (_, w)| gcd(d, *w)).max(1);
+        let violations = soft
+            .iter()
+            .map(|(l, w)| (-l, w / divisor))
+            .collect::<Vec<(i32, u64)>>();
+        let mut best: Option<(Vec<bool>, u64)> = None;
+        loop {
+            let mut cnf = hard.clone();
+            if let Some((_, c)) = &best {
+                if *c == 0 {
+                    return MaxSatOutcome::new(MaxSatStatus::Optimal, best);
+                }
+                cnf.add_weighted_at_most(&violations, c / divisor - 1);
+            }
+            match CdclSolver::new(&cnf).solve_until(deadline) {
+                SatOutcome::Satisfiable(mut model) => {
+                    model.truncate(hard.n_vars());
+                    let c = cost(&model, soft);
+                    best = Some((model, c));
+                }
+                SatOutcome::Unsatisfiable => {
+                    let status = if best.is_some() {
+                        MaxSatStatus::Optimal
+                    } else {
+                        MaxSatStatus::Unsatisfiable
+                    };
+                    return MaxSatOutcome::new(status, best);
+                }
+                SatOutcome::Unknown => {
+                    let status = if best.is_some() {
+                        MaxSatStatus::Feasible
+                    } else {
+                        MaxSatStatus::Unknown
+                    };
+                    return MaxSatOutcome::new(status, best);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weighted_optimum() {
+        let mut cnf = Cnf::default();
+        let vars = (0..5).map(|_| cnf.new_var()).collect::<Vec<i32>>();
+        for i in 0..4 {
+            cnf.add_clause(&[vars[i], vars[i + 1]]);
+        }
+        let soft = vars
+            .iter()
+            .map(|v| (-v, 1 + (*v as u64 % 2)))
+            .collect::<Vec<_>>();
+        let outcome = LinearSearchSolver.solve(&cnf, &soft, None);
+        assert_eq!(MaxSatStatus::Optimal, outcome.status());
+        assert_eq!(
+            Some(&[false, true, false, true, false][..]),
+            outcome.model()
+        );
+        assert_eq!(Some(2), outcome.cost());
+    }
+
+    #[test]
+    fn test_large_weights() {
+        let mut cnf = Cnf::default();
+        let vars = (0..40).map(|_| cnf.new_var()).collect::<Vec<i32>>();
+        for i in 0..39 {
+            cnf.add_clause(&[vars[i], vars[i + 1]]);
+        }
+        let soft = vars
+            .iter()
+            .map(|v| (-v, 1_000_000 + 999_999 * (*v as u64 % 2)))
+            .collect::<Vec<_>>();
+        let outcome = LinearSearchSolver.solve(&cnf, &soft, None);
+        assert_eq!(MaxSatStatus::Optimal, outcome.status());
+        assert_eq!(Some(20_000_000), outcome.cost());
+    }
+
+    #[test]
+    fn test_unsatisfiable() {
+        let mut cnf = Cnf::default();
+        let a = cnf.new_var();
+        cnf.add_clause(&[a]);
+        cnf.add_clause(&[-a]);
+        let outcome = LinearSearchSolver.solve(&cnf, &[(a, 1)], None);
+        assert_eq!(
+            MaxSatOutcome::new(MaxSatStatus::Unsatisfiable, None),
+            outcome
+        );
+    }
+
+    #[test]
+    fn test_deadline() {
+        let mut cnf = Cnf::default();
+        let a = cnf.new_var();
+        cnf.add_clause(&[a, -a]);
+        let outcome = LinearSearchSolver.solve(&cnf, &[(a, 1)], Some(Instant::now()));
+        assert_eq!(MaxSatOutcome::new(MaxSatStatus::Unknown, None), outcome);
+    }
+}
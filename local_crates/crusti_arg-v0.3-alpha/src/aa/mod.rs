@@ -19,4 +19,28 @@
 
 pub(crate) mod aa_framework;
 pub(crate) mod arguments;
+pub(crate) mod ba_framework;
+#[cfg(any(feature = "python", feature = "wasm"))]
+pub(crate) mod bindings;
+pub(crate) mod caf_framework;
+pub(crate) mod claim_framework;
+pub mod enforcement;
+pub mod examples;
+pub(crate) mod iaf_framework;
+pub mod incremental;
+pub(crate) mod interner;
 pub(crate) mod io;
+pub mod maxsat;
+pub(crate) mod preference_framework;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod revision;
+pub mod sat;
+pub(crate) mod semantics;
+pub(crate) mod setaf_framework;
+pub mod solvers;
+pub mod stats;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,346 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::aa_framework::AAFramework;
+use crate::aa::arguments::{Argument, ArgumentSet, LabelType};
+use crate::aa::io::dynamics::Modification;
+use anyhow::{anyhow, Context, Result};
+use std::collections::BTreeSet;
+
+/// A preference-based Argumentation framework, i.e. a framework coupled with a strict preference relation over its arguments.
+///
+/// The preference relation is given by the couples `(a, b)` such that `a` is strictly preferred to `b`; no closure is computed.
+/// An attack from `a` to `b` succeeds (it is a defeat) unless `b` is strictly preferred to `a`.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{AAFramework, ArgumentSet, PreferenceAAFramework};
+/// let labels = vec!["a", "b"];
+/// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+/// framework.new_attack(&labels[0], &labels[1]).unwrap();
+/// framework.new_attack(&labels[1], &labels[0]).unwrap();
+/// let mut pref_framework = PreferenceAAFramework::new(framework);
+/// pref_framework.add_preference(&labels[0], &labels[1]).unwrap();
+/// assert_eq!(1, pref_framework.defeat_framework().n_attacks());
+/// ```
+pub struct PreferenceAAFramework<T>
+where
+    T: LabelType,
+{
+    framework: AAFramework<T>,
+    preferences: BTreeSet<(usize, usize)>,
+}
+
+impl<T> PreferenceAAFramework<T>
+where
+    T: LabelType,
+{
+    /// Builds a preference-based framework with an empty preference relation.
+    ///
+    /// # Arguments
+    ///
+    /// * `framework` - the framework giving the arguments and the attacks
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{AAFramework, ArgumentSet, PreferenceAAFramework};
+    /// let framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// let pref_framework = PreferenceAAFramework::new(framework);
+    /// assert_eq!(0, pref_framework.iter_preferences().count());
+    /// ```
+    pub fn new(framework: AAFramework<T>) -> Self {
+        PreferenceAAFramework {
+            framework,
+            preferences: BTreeSet::new(),
+        } // kcov-ignore
+    }
+
+    /// Returns the argument set of the framework.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{AAFramework, ArgumentSet, PreferenceAAFramework};
+    /// let framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// let pref_framework = PreferenceAAFramework::new(framework);
+    /// assert_eq!(2, pref_framework.argument_set().len());
+    /// ```
+    pub fn argument_set(&self) -> &ArgumentSet<T> {
+        self.framework.argument_set()
+    }
+
+    /// Returns the framework made of the arguments and the attacks, regardless of the preferences.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{AAFramework, ArgumentSet, PreferenceAAFramework};
+    /// let framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// let pref_framework = PreferenceAAFramework::new(framework);
+    /// assert_eq!(0, pref_framework.attack_framework().n_attacks());
+    /// ```
+    pub fn attack_framework(&self) -> &AAFramework<T> {
+        &self.framework
+    }
+
+    /// Returns `true` iff the first argument is strictly preferred to the second one.
+    ///
+    /// If the provided arguments are undefined, an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `preferred` - the label of the argument which may be preferred
+    /// * `other` - the label of the other argument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{AAFramework, ArgumentSet, PreferenceAAFramework};
+    /// let labels = vec!["a", "b"];
+    /// let framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// let mut pref_framework = PreferenceAAFramework::new(framework);
+    /// pref_framework.add_preference(&labels[0], &labels[1]).unwrap();
+    /// assert!(pref_framework.is_preferred(&labels[0], &labels[1]).unwrap());
+    /// assert!(!pref_framework.is_preferred(&labels[1], &labels[0]).unwrap());
+    /// ```
+    pub fn is_preferred(&self, preferred: &T, other: &T) -> Result<bool> {
+        let (p, o) = self.ids_of(preferred, other)?;
+        Ok(self.preferences.contains(&(p, o)))
+    }
+
+    /// Sets an argument as strictly preferred to another one.
+    ///
+    /// The modifications of the defeat relation implied by this new preference are returned, allowing dynamics over preferences to be expressed as attack modifications.
+    ///
+    /// If the provided arguments are undefined, if they are equal, or if the second one is already preferred to the first one, an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `preferred` - the label of the preferred argument
+    /// * `other` - the label of the other argument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{AAFramework, ArgumentSet, PreferenceAAFramework};
+    /// # use crusti_arg::dynamics::Modification;
+    /// let labels = vec!["a", "b"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[1], &labels[0]).unwrap();
+    /// let mut pref_framework = PreferenceAAFramework::new(framework);
+    /// assert_eq!(
+    ///     vec![Modification::RemoveAttack("b", "a")],
+    ///     pref_framework.add_preference(&labels[0], &labels[1]).unwrap()
+    /// );
+    /// ```
+    pub fn add_preference(&mut self, preferred: &T, other: &T) -> Result<Vec<Modification<T>>> {
+        let context = || format!("cannot prefer {:?} to {:?}", preferred, other);
+        let (p, o) = self.ids_of(preferred, other).with_context(context)?;
+        if p == o {
+            return Err(anyhow!("an argument cannot be preferred to itself")).with_context(context);
+        }
+        if self.preferences.contains(&(o, p)) {
+            return Err(anyhow!("the reverse preference is set")).with_context(context);
+        }
+        if !self.preferences.insert((p, o)) || !self.has_attack(o, p) {
+            return Ok(vec![]);
+        }
+        Ok(vec![Modification::RemoveAttack(
+            other.clone(),
+            preferred.clone(),
+        )])
+    }
+
+    /// Removes a preference between two arguments.
+    ///
+    /// The modifications of the defeat relation implied by this removal are returned.
+    ///
+    /// If the provided arguments are undefined, an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `preferred` - the label of the preferred argument
+    /// * `other` - the label of the other argument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{AAFramework, ArgumentSet, PreferenceAAFramework};
+    /// # use crusti_arg::dynamics::Modification;
+    /// let labels = vec!["a", "b"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[1], &labels[0]).unwrap();
+    /// let mut pref_framework = PreferenceAAFramework::new(framework);
+    /// pref_framework.add_preference(&labels[0], &labels[1]).unwrap();
+    /// assert_eq!(
+    ///     vec![Modification::AddAttack("b", "a")],
+    ///     pref_framework.remove_preference(&labels[0], &labels[1]).unwrap()
+    /// );
+    /// ```
+    pub fn remove_preference(&mut self, preferred: &T, other: &T) -> Result<Vec<Modification<T>>> {
+        let (p, o) = self.ids_of(preferred, other).with_context(|| {
+            format!(
+                "cannot remove the preference of {:?} over {:?}",
+                preferred, other
+            )
+        })?;
+        if !self.preferences.remove(&(p, o)) || !self.has_attack(o, p) {
+            return Ok(vec![]);
+        }
+        Ok(vec![Modification::AddAttack(
+            other.clone(),
+            preferred.clone(),
+        )])
+    }
+
+    /// Provides an iterator to the preferences, as couples of a preferred argument and the argument it is preferred to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{AAFramework, ArgumentSet, PreferenceAAFramework};
+    /// let labels = vec!["a", "b"];
+    /// let framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// let mut pref_framework = PreferenceAAFramework::new(framework);
+    /// pref_framework.add_preference(&labels[0], &labels[1]).unwrap();
+    /// let (p, o) = pref_framework.iter_preferences().next().unwrap();
+    /// assert_eq!((&"a", &"b"), (p.label(), o.label()));
+    /// ```
+    pub fn iter_preferences<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = (&'a Argument<T>, &'a Argument<T>)> + 'a> {
+        let arguments = self.framework.argument_set();
+        Box::new(self.preferences.iter().map(move |(p, o)| {
+            (
+                arguments.get_argument_by_id(*p),
+                arguments.get_argument_by_id(*o),
+            )
+        }))
+    }
+
+    /// Computes the framework given by the defeat relation induced by the attacks and the preferences.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{AAFramework, ArgumentSet, PreferenceAAFramework};
+    /// let labels = vec!["a", "b"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[1], &labels[0]).unwrap();
+    /// let mut pref_framework = PreferenceAAFramework::new(framework);
+    /// assert_eq!(1, pref_framework.defeat_framework().n_attacks());
+    /// pref_framework.add_preference(&labels[0], &labels[1]).unwrap();
+    /// assert_eq!(0, pref_framework.defeat_framework().n_attacks());
+    /// ```
+    pub fn defeat_framework(&self) -> AAFramework<T> {
+        let mut defeats = AAFramework::new(ArgumentSet::new(
+            self.argument_set()
+                .iter()
+                .map(|a| a.label().clone())
+                .collect(),
+        ));
+        self.framework
+            .iter_attacks()
+            .map(|att| (att.attacker().id(), att.attacked().id()))
+            .filter(|(a, b)| !self.preferences.contains(&(*b, *a)))
+            .for_each(|(a, b)| defeats.new_attack_by_ids(a, b).unwrap());
+        defeats
+    }
+
+    fn ids_of(&self, a: &T, b: &T) -> Result<(usize, usize)> {
+        let arguments = self.framework.argument_set();
+        Ok((
+            arguments.get_argument_index(a)?,
+            arguments.get_argument_index(b)?,
+        ))
+    }
+
+    fn has_attack(&self, from: usize, to: usize) -> bool {
+        self.framework
+            .iter_attacks()
+            .any(|att| att.attacker().id() == from && att.attacked().id() == to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pref_framework() -> (Vec<String>, PreferenceAAFramework<String>) {
+        let labels = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+        framework.new_attack(&labels[0], &labels[1]).unwrap();
+        framework.new_attack(&labels[1], &labels[0]).unwrap();
+        framework.new_attack(&labels[1], &labels[2]).unwrap();
+        (labels, PreferenceAAFramework::new(framework))
+    }
+
+    fn str_attacks(af: &AAFramework<String>) -> Vec<String> {
+        af.iter_attacks().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_defeats() {
+        let (labels, mut pf) = pref_framework();
+        assert_eq!(
+            vec!["a → b", "b → a", "b → c"],
+            str_attacks(&pf.defeat_framework())
+        );
+        assert_eq!(
+            vec![Modification::RemoveAttack(
+                labels[1].clone(),
+                labels[0].clone()
+            )],
+            pf.add_preference(&labels[0], &labels[1]).unwrap()
+        );
+        assert!(pf
+            .add_preference(&labels[0], &labels[1])
+            .unwrap()
+            .is_empty());
+        assert!(pf
+            .add_preference(&labels[1], &labels[2])
+            .unwrap()
+            .is_empty());
+        assert_eq!(vec!["a → b", "b → c"], str_attacks(&pf.defeat_framework()));
+        assert_eq!(
+            vec![Modification::AddAttack(
+                labels[1].clone(),
+                labels[0].clone()
+            )],
+            pf.remove_preference(&labels[0], &labels[1]).unwrap()
+        );
+        assert!(pf
+            .remove_preference(&labels[0], &labels[1])
+            .unwrap()
+            .is_empty());
+        assert_eq!(1, pf.iter_preferences().count());
+    }
+
+    #[test]
+    fn test_add_preference_errors() {
+        let (labels, mut pf) = pref_framework();
+        assert!(pf.add_preference(&labels[0], &labels[0]).is_err());
+        assert!(pf.add_preference(&labels[0], &"d".to_string()).is_err());
+        pf.add_preference(&labels[0], &labels[1]).unwrap();
+        assert!(pf.add_preference(&labels[1], &labels[0]).is_err());
+        assert!(!pf.is_preferred(&labels[1], &labels[0]).unwrap());
+    }
+}
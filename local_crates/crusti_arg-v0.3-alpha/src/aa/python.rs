@@ -0,0 +1,192 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! Python bindings of the library, enabled by the `python` feature.
+//!
+//! The [`register`] function adds the classes and functions of the bindings to a Python module;
+//! the `crusti_arg_py` crate uses it to build the `crusti_arg_py` extension module.
+//! Arguments are labeled by Python strings, semantics are given by their acronyms (e.g. `"PR"`), and the errors of the library are raised as `ValueError`.
+//!
+//! ```python
+//! import crusti_arg_py
+//! af = crusti_arg_py.read_aspartix("arg(a).\narg(b).\natt(a,b).\n")
+//! af.apply("+att(b,a).")
+//! assert af.extensions("ST") == [["a"], ["b"]]
+//! ```
+//!
+//! [`register`]: fn.register.html
+
+// the code generated by the pyo3 macros converts the error of each returned `PyResult` into a `PyErr`, which it already is
+#![allow(clippy::useless_conversion)]
+
+use super::bindings;
+use crate::{dynamics, solutions, AAFramework, AspartixReader, Semantics};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+fn to_py_err(e: anyhow::Error) -> PyErr {
+    PyValueError::new_err(format!("{:#}", e))
+}
+
+fn parse_semantics(semantics: &str) -> PyResult<Semantics> {
+    semantics.parse::<Semantics>().map_err(to_py_err)
+}
+
+/// An Abstract Argumentation framework labeled by strings, exposed to Python as `AAFramework`.
+#[pyclass(name = "AAFramework", module = "crusti_arg_py")]
+pub struct PyAAFramework {
+    framework: AAFramework<String>,
+}
+
+#[pymethods]
+impl PyAAFramework {
+    #[new]
+    #[pyo3(signature = (arguments, attacks = vec![]))]
+    fn py_new(arguments: Vec<String>, attacks: Vec<(String, String)>) -> PyResult<Self> {
+        let framework = bindings::build(arguments, &attacks).map_err(to_py_err)?;
+        Ok(PyAAFramework { framework })
+    }
+
+    /// Returns the labels of the arguments, in the order of their ids.
+    fn arguments(&self) -> Vec<String> {
+        bindings::argument_labels(&self.framework)
+    }
+
+    /// Returns the attacks, as pairs of labels.
+    fn attacks(&self) -> Vec<(String, String)> {
+        bindings::attack_labels(&self.framework)
+    }
+
+    /// Adds an attack between two existing arguments.
+    fn new_attack(&mut self, attacker: String, attacked: String) -> PyResult<()> {
+        self.framework
+            .new_attack(&attacker, &attacked)
+            .map_err(to_py_err)
+    }
+
+    /// Applies a modification given by a line of a dynamics file (e.g. `"+att(a,b)."`).
+    fn apply(&mut self, modification: &str) -> PyResult<()> {
+        let m = dynamics::read_modification(modification).map_err(to_py_err)?;
+        self.framework = bindings::apply_modification(&self.framework, &m).map_err(to_py_err)?;
+        Ok(())
+    }
+
+    /// Returns all the extensions of a semantics, computed by the native engines.
+    fn extensions(&self, semantics: &str) -> PyResult<Vec<Vec<String>>> {
+        bindings::extensions(&self.framework, parse_semantics(semantics)?).map_err(to_py_err)
+    }
+
+    /// Returns an extension of a semantics computed by the native engines, or `None` if there is no extension.
+    fn one_extension(&self, semantics: &str) -> PyResult<Option<Vec<String>>> {
+        bindings::one_extension(&self.framework, parse_semantics(semantics)?).map_err(to_py_err)
+    }
+
+    /// Returns `true` iff a set of arguments is an extension of a semantics.
+    fn is_extension(&self, extension: Vec<String>, semantics: &str) -> PyResult<bool> {
+        let semantics = parse_semantics(semantics)?;
+        let set = bindings::membership(&self.framework, &extension).map_err(to_py_err)?;
+        bindings::is_extension(&self.framework, &set, semantics).map_err(to_py_err)
+    }
+
+    /// Returns `true` iff a set of arguments is admissible.
+    fn is_admissible(&self, extension: Vec<String>) -> PyResult<bool> {
+        let set = bindings::membership(&self.framework, &extension).map_err(to_py_err)?;
+        Ok(bindings::is_admissible(&self.framework, &set))
+    }
+
+    /// Returns `true` iff an argument belongs to an extension of a semantics.
+    fn credulously_accepted(&self, argument: String, semantics: &str) -> PyResult<bool> {
+        self.framework
+            .credulously_accepted(&argument, parse_semantics(semantics)?)
+            .map_err(to_py_err)
+    }
+
+    /// Returns `true` iff an argument belongs to all the extensions of a semantics.
+    fn skeptically_accepted(&self, argument: String, semantics: &str) -> PyResult<bool> {
+        self.framework
+            .skeptically_accepted(&argument, parse_semantics(semantics)?)
+            .map_err(to_py_err)
+    }
+
+    /// Returns the labelling induced by a set of arguments, as pairs of a label and a status (`IN`, `OUT` or `UNDEC`).
+    fn labelling(&self, extension: Vec<String>) -> PyResult<Vec<(String, String)>> {
+        bindings::labelling(&self.framework, &extension).map_err(to_py_err)
+    }
+
+    /// Writes the framework using the Aspartix format.
+    fn to_aspartix(&self) -> PyResult<String> {
+        bindings::to_aspartix(&self.framework).map_err(to_py_err)
+    }
+
+    /// Writes the framework using the DOT format of Graphviz.
+    fn to_dot(&self) -> PyResult<String> {
+        bindings::to_dot(&self.framework).map_err(to_py_err)
+    }
+
+    fn __len__(&self) -> usize {
+        self.framework.argument_set().len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("AAFramework({})", self.framework.summary())
+    }
+}
+
+/// Reads a framework given in the Aspartix format.
+#[pyfunction]
+fn read_aspartix(content: &str) -> PyResult<PyAAFramework> {
+    let framework = AspartixReader::default()
+        .read(&mut content.as_bytes())
+        .map_err(to_py_err)?;
+    Ok(PyAAFramework { framework })
+}
+
+/// Parses a line of a dynamics file, returning its sign (`+` or `-`), its kind (`arg` or `att`) and its labels.
+#[pyfunction]
+fn parse_modification(line: &str) -> PyResult<(String, String, Vec<String>)> {
+    let m = dynamics::read_modification(line).map_err(to_py_err)?;
+    let (sign, kind, labels) = bindings::modification_parts(m);
+    Ok((sign.to_string(), kind.to_string(), labels))
+}
+
+/// Reads an extension given as a solver answer (e.g. `"[a,b]"`).
+#[pyfunction]
+fn read_extension(answer: &str) -> PyResult<Vec<String>> {
+    let extension = solutions::read_extension(&mut answer.as_bytes()).map_err(to_py_err)?;
+    Ok(extension.iter().map(|a| a.label().clone()).collect())
+}
+
+/// Reads an acceptance status given as a solver answer (`YES` or `NO`).
+#[pyfunction]
+fn read_acceptance_status(answer: &str) -> PyResult<bool> {
+    solutions::read_acceptance_status(&mut answer.as_bytes()).map_err(to_py_err)
+}
+
+/// Adds the classes and the functions of the bindings to a Python module.
+///
+/// # Arguments
+///
+/// * `module` - the Python module
+pub fn register(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyAAFramework>()?;
+    module.add_function(wrap_pyfunction!(read_aspartix, module)?)?;
+    module.add_function(wrap_pyfunction!(parse_modification, module)?)?;
+    module.add_function(wrap_pyfunction!(read_extension, module)?)?;
+    module.add_function(wrap_pyfunction!(read_acceptance_status, module)?)?;
+    Ok(())
+}
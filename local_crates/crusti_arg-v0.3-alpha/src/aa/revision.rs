@@ -0,0 +1,355 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! A module providing the revision operators of frameworks studied in the literature on dynamics.
+//!
+//! A normal expansion adds new arguments and attacks, each new attack involving at least one new argument; see [`normal_expansion`] and [`is_normal_expansion`].
+//! Contractions remove arguments together with the attacks they are involved in.
+//! A weak contraction removes arguments attacking no remaining argument, and a strong contraction removes arguments attacked by no remaining argument; they are the inverse operations of the weak and strong expansions.
+//!
+//! The operators build new frameworks, keeping the weights of the remaining arguments and attacks.
+//!
+//! [`normal_expansion`]: fn.normal_expansion.html
+//! [`is_normal_expansion`]: fn.is_normal_expansion.html
+
+use crate::aa::aa_framework::{AAFramework, Attack};
+use crate::aa::arguments::{ArgumentSet, LabelType};
+use crate::aa::io::dynamics::Modification;
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashSet;
+
+/// Builds the normal expansion of a framework by new arguments and attacks.
+///
+/// Each attack must involve at least one of the new arguments.
+/// If a new argument already belongs to the framework or is given twice, if an attack involves an undefined argument or only arguments of the framework, an error is returned.
+///
+/// # Arguments
+///
+/// * `framework` - the framework to expand
+/// * `arguments` - the labels of the new arguments
+/// * `attacks` - the new attacks, given by the labels of their attacker and of the attacked argument
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{revision, ArgumentSet, AAFramework};
+/// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+/// framework.new_attack(&"a", &"b").unwrap();
+/// let expanded = revision::normal_expansion(&framework, &["c"], &[("c", "a")]).unwrap();
+/// assert_eq!("3 argument(s), 2 attack(s)", expanded.summary());
+/// assert!(revision::normal_expansion(&framework, &["c"], &[("b", "a")]).is_err());
+/// ```
+pub fn normal_expansion<T>(
+    framework: &AAFramework<T>,
+    arguments: &[T],
+    attacks: &[(T, T)],
+) -> Result<AAFramework<T>>
+where
+    T: LabelType,
+{
+    let context = "cannot build the normal expansion";
+    let mut new_arguments = HashSet::with_capacity(arguments.len());
+    for a in arguments {
+        if framework.argument_set().contains(a) || !new_arguments.insert(a) {
+            return Err(anyhow!("argument {} already exists", a)).context(context);
+        }
+    }
+    if let Some((a, b)) = attacks
+        .iter()
+        .find(|(a, b)| !new_arguments.contains(a) && !new_arguments.contains(b))
+    {
+        return Err(anyhow!("the attack {} → {} involves no new argument", a, b)).context(context);
+    }
+    let mut expanded = copy_framework(framework, arguments)?;
+    for (a, b) in attacks {
+        expanded.new_attack(a, b).context(context)?;
+    }
+    Ok(expanded)
+}
+
+/// Checks whether a sequence of modifications is a normal expansion of a framework.
+///
+/// Such a sequence only adds arguments which are not in the framework and attacks involving at least one of the arguments added by the sequence.
+/// Removals, additions of existing arguments and attacks involving undefined arguments make the check fail.
+///
+/// # Arguments
+///
+/// * `framework` - the framework the modifications apply to
+/// * `modifications` - the sequence of modifications
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{revision, ArgumentSet, AAFramework};
+/// # use crusti_arg::dynamics::Modification;
+/// let framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+/// assert!(revision::is_normal_expansion(
+///     &framework,
+///     &[Modification::AddArgument("c"), Modification::AddAttack("a", "c")]
+/// ));
+/// assert!(!revision::is_normal_expansion(
+///     &framework,
+///     &[Modification::AddArgument("c"), Modification::AddAttack("a", "b")]
+/// ));
+/// ```
+pub fn is_normal_expansion<T>(framework: &AAFramework<T>, modifications: &[Modification<T>]) -> bool
+where
+    T: LabelType,
+{
+    let arguments = framework.argument_set();
+    let mut new_arguments = HashSet::new();
+    modifications.iter().all(|m| match m {
+        Modification::AddArgument(a) => !arguments.contains(a) && new_arguments.insert(a),
+        Modification::AddAttack(a, b) => {
+            let is_new = |l| new_arguments.contains(l);
+            let exists = |l| arguments.contains(l) || is_new(l);
+            exists(a) && exists(b) && (is_new(a) || is_new(b))
+        }
+        Modification::RemoveArgument(_) | Modification::RemoveAttack(_, _) => false,
+    })
+}
+
+/// Builds the normal contraction of a framework, removing arguments and the attacks they are involved in.
+///
+/// If an argument is undefined or given twice, an error is returned.
+///
+/// # Arguments
+///
+/// * `framework` - the framework to contract
+/// * `arguments` - the labels of the arguments to remove
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{revision, ArgumentSet, AAFramework};
+/// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+/// framework.new_attack(&"a", &"b").unwrap();
+/// framework.new_attack(&"b", &"c").unwrap();
+/// let contracted = revision::normal_contraction(&framework, &["b"]).unwrap();
+/// assert_eq!("2 argument(s), 0 attack(s)", contracted.summary());
+/// ```
+pub fn normal_contraction<T>(framework: &AAFramework<T>, arguments: &[T]) -> Result<AAFramework<T>>
+where
+    T: LabelType,
+{
+    contraction(framework, arguments, |_, _| Ok(())).context("cannot build the normal contraction")
+}
+
+/// Builds the weak contraction of a framework, removing arguments which attack none of the remaining arguments.
+///
+/// The attacks the removed arguments are involved in are removed too.
+/// If an argument is undefined or given twice, or if a removed argument attacks a remaining one, an error is returned.
+///
+/// # Arguments
+///
+/// * `framework` - the framework to contract
+/// * `arguments` - the labels of the arguments to remove
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{revision, ArgumentSet, AAFramework};
+/// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+/// framework.new_attack(&"a", &"b").unwrap();
+/// assert!(revision::weak_contraction(&framework, &["b"]).is_ok());
+/// assert!(revision::weak_contraction(&framework, &["a"]).is_err());
+/// ```
+pub fn weak_contraction<T>(framework: &AAFramework<T>, arguments: &[T]) -> Result<AAFramework<T>>
+where
+    T: LabelType,
+{
+    contraction(framework, arguments, |attack, removed| {
+        if removed[attack.attacker().id()] && !removed[attack.attacked().id()] {
+            return Err(anyhow!(
+                "removed argument {} attacks remaining argument {}",
+                attack.attacker().label(),
+                attack.attacked().label()
+            ));
+        }
+        Ok(())
+    })
+    .context("cannot build the weak contraction")
+}
+
+/// Builds the strong contraction of a framework, removing arguments which are attacked by none of the remaining arguments.
+///
+/// The attacks the removed arguments are involved in are removed too.
+/// If an argument is undefined or given twice, or if a removed argument is attacked by a remaining one, an error is returned.
+///
+/// # Arguments
+///
+/// * `framework` - the framework to contract
+/// * `arguments` - the labels of the arguments to remove
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{revision, ArgumentSet, AAFramework};
+/// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+/// framework.new_attack(&"a", &"b").unwrap();
+/// assert!(revision::strong_contraction(&framework, &["a"]).is_ok());
+/// assert!(revision::strong_contraction(&framework, &["b"]).is_err());
+/// ```
+pub fn strong_contraction<T>(framework: &AAFramework<T>, arguments: &[T]) -> Result<AAFramework<T>>
+where
+    T: LabelType,
+{
+    contraction(framework, arguments, |attack, removed| {
+        if !removed[attack.attacker().id()] && removed[attack.attacked().id()] {
+            return Err(anyhow!(
+                "removed argument {} is attacked by remaining argument {}",
+                attack.attacked().label(),
+                attack.attacker().label()
+            ));
+        }
+        Ok(())
+    })
+    .context("cannot build the strong contraction")
+}
+
+fn contraction<T, F>(
+    framework: &AAFramework<T>,
+    arguments: &[T],
+    check: F,
+) -> Result<AAFramework<T>>
+where
+    T: LabelType,
+    F: Fn(&Attack<T>, &[bool]) -> Result<()>,
+{
+    let mut removed = vec![false; framework.argument_set().len()];
+    for a in arguments {
+        let id = framework.argument_set().get_argument_index(a)?;
+        if removed[id] {
+            return Err(anyhow!("argument {} is given twice", a));
+        }
+        removed[id] = true;
+    }
+    for attack in framework.iter_attacks() {
+        check(&attack, &removed)?;
+    }
+    let mut contracted = copy_framework(framework, &[])?;
+    for a in arguments {
+        contracted.remove_argument_cascade(a)?;
+    }
+    Ok(contracted)
+}
+
+fn copy_framework<T>(framework: &AAFramework<T>, new_arguments: &[T]) -> Result<AAFramework<T>>
+where
+    T: LabelType,
+{
+    let labels = framework
+        .argument_set()
+        .iter()
+        .map(|a| a.label().clone())
+        .chain(new_arguments.iter().cloned())
+        .collect();
+    let mut copy = AAFramework::new(ArgumentSet::new(labels));
+    for a in framework.argument_set().iter() {
+        if let Some(w) = framework.argument_weight(a) {
+            copy.set_argument_weight(a.label(), w)?;
+        }
+    }
+    for attack in framework.iter_attacks() {
+        let (from, to) = (attack.attacker().label(), attack.attacked().label());
+        match attack.weight() {
+            Some(w) => copy.new_weighted_attack(from, to, w)?,
+            None => copy.new_attack(from, to)?,
+        }
+    }
+    Ok(copy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn framework() -> AAFramework<&'static str> {
+        let mut af = AAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+        af.new_weighted_attack(&"a", &"b", 0.5).unwrap();
+        af.new_attack(&"b", &"c").unwrap();
+        af.set_argument_weight(&"c", 2.).unwrap();
+        af
+    }
+
+    #[test]
+    fn test_normal_expansion_keeps_weights() {
+        let af = normal_expansion(&framework(), &["d"], &[("d", "d"), ("c", "d")]).unwrap();
+        assert_eq!("4 argument(s), 4 attack(s), weighted", af.summary());
+        assert_eq!(Some(0.5), af.iter_attacks().next().unwrap().weight());
+        let c = af.argument_set().get_argument_by_id(2);
+        assert_eq!(Some(2.), af.argument_weight(c));
+    }
+
+    #[test]
+    fn test_normal_expansion_errors() {
+        let af = framework();
+        assert!(normal_expansion(&af, &["a"], &[]).is_err());
+        assert!(normal_expansion(&af, &["d", "d"], &[]).is_err());
+        assert!(normal_expansion(&af, &["d"], &[("d", "e")]).is_err());
+    }
+
+    #[test]
+    fn test_is_normal_expansion() {
+        let af = framework();
+        assert!(is_normal_expansion(&af, &[]));
+        assert!(!is_normal_expansion(
+            &af,
+            &[Modification::AddAttack("a", "c")]
+        ));
+        assert!(!is_normal_expansion(
+            &af,
+            &[
+                Modification::AddAttack("d", "a"),
+                Modification::AddArgument("d")
+            ]
+        ));
+        assert!(!is_normal_expansion(
+            &af,
+            &[
+                Modification::AddArgument("d"),
+                Modification::AddAttack("d", "e")
+            ]
+        ));
+        assert!(!is_normal_expansion(
+            &af,
+            &[
+                Modification::AddArgument("d"),
+                Modification::AddArgument("d")
+            ]
+        ));
+        assert!(!is_normal_expansion(
+            &af,
+            &[Modification::RemoveArgument("a")]
+        ));
+    }
+
+    #[test]
+    fn test_contractions() {
+        let af = framework();
+        let contracted = normal_contraction(&af, &["a"]).unwrap();
+        assert_eq!("2 argument(s), 1 attack(s), weighted", contracted.summary());
+        assert!(normal_contraction(&af, &["d"]).is_err());
+        assert!(normal_contraction(&af, &["a", "a"]).is_err());
+        assert!(weak_contraction(&af, &["b", "c"]).is_ok());
+        assert!(weak_contraction(&af, &["b"]).is_err());
+        assert!(strong_contraction(&af, &["a", "b"]).is_ok());
+        assert!(strong_contraction(&af, &["b"]).is_err());
+    }
+}
@@ -0,0 +1,928 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! A module containing a small SAT solver and the CNF formulas it handles.
+//!
+//! Formulas are built by a [`Cnf`] object, which gives the variables and stores the clauses.
+//! Literals follow the DIMACS conventions: variables are numbered from `1`, and negative integers are negative literals.
+//! Formulas can be written in the DIMACS format, and solved by the [`CdclSolver`], a conflict-driven clause learning solver dedicated to the small formulas built by this library.
+//! The solver is incremental: variables and clauses can be added between two searches, and each search can be made under assumptions, keeping the clauses learnt by the previous ones.
+//!
+//! [`CdclSolver`]: struct.CdclSolver.html
+//! [`Cnf`]: struct.Cnf.html
+
+use anyhow::{Context, Result};
+use std::{collections::BTreeMap, io::Write, time::Instant};
+
+/// A formula in conjunctive normal form.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::sat::Cnf;
+/// let mut cnf = Cnf::default();
+/// let a = cnf.new_var();
+/// let b = cnf.new_var();
+/// cnf.add_clause(&[a, b]);
+/// cnf.add_clause(&[-a]);
+/// let mut dimacs = Vec::new();
+/// cnf.write_dimacs(&mut dimacs).unwrap();
+/// assert_eq!("p cnf 2 2\n1 2 0\n-1 0\n", String::from_utf8(dimacs).unwrap());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Cnf {
+    n_vars: usize,
+    clauses: Vec<Vec<i32>>,
+}
+
+impl Cnf {
+    /// Creates a new variable, and returns its positive literal.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::sat::Cnf;
+    /// let mut cnf = Cnf::default();
+    /// assert_eq!(1, cnf.new_var());
+    /// assert_eq!(2, cnf.new_var());
+    /// assert_eq!(2, cnf.n_vars());
+    /// ```
+    pub fn new_var(&mut self) -> i32 {
+        self.n_vars += 1;
+        self.n_vars as i32
+    }
+
+    /// Returns the number of variables of the formula.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::sat::Cnf;
+    /// assert_eq!(0, Cnf::default().n_vars());
+    /// ```
+    pub fn n_vars(&self) -> usize {
+        self.n_vars
+    }
+
+    /// Adds a clause to the formula.
+    ///
+    /// # Arguments
+    ///
+    /// * `clause` - the literals of the clause
+    ///
+    /// # Panics
+    ///
+    /// Panics if a literal is `0` or refers to an undefined variable.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::sat::Cnf;
+    /// let mut cnf = Cnf::default();
+    /// let a = cnf.new_var();
+    /// cnf.add_clause(&[a]);
+    /// assert_eq!(1, cnf.clauses().len());
+    /// ```
+    pub fn add_clause(&mut self, clause: &[i32]) {
+        assert!(
+            clause
+                .iter()
+                .all(|l| *l != 0 && l.unsigned_abs() as usize <= self.n_vars),
+            "undefined variable in clause {:?}",
+            clause
+        );
+        self.clauses.push(clause.to_vec());
+    }
+
+    /// Returns the clauses of the formula, in the order they were added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::sat::Cnf;
+    /// let mut cnf = Cnf::default();
+    /// let a = cnf.new_var();
+    /// cnf.add_clause(&[-a]);
+    /// assert_eq!(&[vec![-1]], cnf.clauses());
+    /// ```
+    pub fn clauses(&self) -> &[Vec<i32>] {
+        &self.clauses
+    }
+
+    /// Adds clauses ensuring that at most `k` of the given literals are true.
+    ///
+    /// The constraint is encoded by a sequential counter, introducing `(n-1)·k` new variables for `n` literals.
+    ///
+    /// # Arguments
+    ///
+    /// * `literals` - the literals
+    /// * `k` - the maximal number of true literals
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::sat::{CdclSolver, Cnf};
+    /// let mut cnf = Cnf::default();
+    /// let vars = (0..4).map(|_| cnf.new_var()).collect::<Vec<i32>>();
+    /// cnf.add_at_most(&vars, 1);
+    /// cnf.add_clause(&[vars[0], vars[1]]);
+    /// cnf.add_clause(&[vars[1], vars[2]]);
+    /// let model = CdclSolver::new(&cnf).solve().unwrap();
+    /// assert_eq!(&[false, true, false, false], &model[..4]);
+    /// ```
+    pub fn add_at_most(&mut self, literals: &[i32], k: usize) {
+        let n = literals.len();
+        if k >= n {
+            return;
+        }
+        if k == 0 {
+            literals.iter().for_each(|l| self.add_clause(&[-l]));
+            return;
+        }
+        let mut previous: Vec<i32> = vec![];
+        for (i, l) in literals.iter().enumerate().take(n - 1) {
+            let counter = (0..k).map(|_| self.new_var()).collect::<Vec<i32>>();
+            self.add_clause(&[-l, counter[0]]);
+            if i == 0 {
+                counter[1..].iter().for_each(|s| self.add_clause(&[-s]));
+            } else {
+                for j in 0..k {
+                    self.add_clause(&[-previous[j], counter[j]]);
+                    if j > 0 {
+                        self.add_clause(&[-l, -previous[j - 1], counter[j]]);
+                    }
+                }
+                self.add_clause(&[-l, -previous[k - 1]]);
+            }
+            previous = counter;
+        }
+        self.add_clause(&[-literals[n - 1], -previous[k - 1]]);
+    }
+
+    /// Adds clauses ensuring that the sum of the weights of the true literals is at most `k`.
+    ///
+    /// The constraint is encoded by a generalized totalizer: a binary tree whose nodes get a new variable per sum of weights reachable by their literals, up to `k`.
+    /// The size of the encoding depends on the number of distinct sums, not on the weights themselves; literals of weight `0` are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `literals` - the literals and their weights
+    /// * `k` - the maximal sum of the weights of the true literals
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::sat::{CdclSolver, Cnf};
+    /// let mut cnf = Cnf::default();
+    /// let vars = (0..3).map(|_| cnf.new_var()).collect::<Vec<i32>>();
+    /// cnf.add_weighted_at_most(&[(vars[0], 1_000_000), (vars[1], 2_000_000), (vars[2], 2_000_000)], 3_000_000);
+    /// cnf.add_clause(&[vars[1], vars[2]]);
+    /// cnf.add_clause(&[vars[0]]);
+    /// let model = CdclSolver::new(&cnf).solve().unwrap();
+    /// assert_eq!(1, model[1..3].iter().filter(|b| **b).count());
+    /// ```
+    pub fn add_weighted_at_most(&mut self, literals: &[(i32, u64)], k: u64) {
+        let literals = literals
+            .iter()
+            .filter(|(_, w)| *w > 0)
+            .copied()
+            .collect::<Vec<(i32, u64)>>();
+        if literals
+            .iter()
+            .fold(0_u64, |s, (_, w)| s.saturating_add(*w))
+            <= k
+        {
+            return;
+        }
+        self.add_weighted_sums(&literals, k);
+    }
+
+    /// Encodes the sums of the weights of the given literals up to `k`, forbidding the greater ones.
+    ///
+    /// Returns the sums in increasing order, each one with a literal which is `true` if the sum of the weights of the true literals is at least this sum.
+    fn add_weighted_sums(&mut self, literals: &[(i32, u64)], k: u64) -> Vec<(u64, i32)> {
+        match literals {
+            [] => return vec![],
+            [(l, w)] if *w > k => {
+                self.add_clause(&[-l]);
+                return vec![];
+            }
+            [(l, w)] => return vec![(*w, *l)],
+            _ => {}
+        }
+        let (left, right) = literals.split_at(literals.len() / 2);
+        let left = self.add_weighted_sums(left, k);
+        let right = self.add_weighted_sums(right, k);
+        let with_empty_sum = |sums: Vec<(u64, i32)>| {
+            std::iter::once((0, None))
+                .chain(sums.into_iter().map(|(s, l)| (s, Some(l))))
+                .collect::<Vec<(u64, Option<i32>)>>()
+        };
+        let (left, right) = (with_empty_sum(left), with_empty_sum(right));
+        let mut sums = BTreeMap::new();
+        for (a, left_literal) in left.iter() {
+            for (b, right_literal) in right.iter() {
+                let mut clause = left_literal
+                    .iter()
+                    .chain(right_literal.iter())
+                    .map(|l| -l)
+                    .collect::<Vec<i32>>();
+                if clause.is_empty() {
+                    continue;
+                }
+                match a.checked_add(*b) {
+                    Some(sum) if sum <= k => {
+                        let output = *sums.entry(sum).or_insert_with(|| self.new_var());
+                        clause.push(output);
+                    }
+                    _ => {}
+                }
+                self.add_clause(&clause);
+            }
+        }
+        sums.into_iter().collect()
+    }
+
+    /// Writes the formula in the DIMACS format.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - the writer
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::sat::Cnf;
+    /// let mut dimacs = Vec::new();
+    /// Cnf::default().write_dimacs(&mut dimacs).unwrap();
+    /// assert_eq!("p cnf 0 0\n", String::from_utf8(dimacs).unwrap());
+    /// ```
+    pub fn write_dimacs(&self, writer: &mut dyn Write) -> Result<()> {
+        let context = "while writing a DIMACS formula";
+        writeln!(writer, "p cnf {} {}", self.n_vars, self.clauses.len()).context(context)?;
+        for c in self.clauses.iter() {
+            for l in c.iter() {
+                write!(writer, "{} ", l).context(context)?;
+            }
+            writeln!(writer, "0").context(context)?;
+        }
+        writer.flush().context(context)
+    }
+}
+
+/// The outcome of a SAT solver.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SatOutcome {
+    /// The formula is satisfiable, and a model is given.
+    Satisfiable(Vec<bool>),
+    /// The formula is unsatisfiable.
+    Unsatisfiable,
+    /// The solver gave up before deciding the formula.
+    Unknown,
+}
+
+const DEADLINE_CHECK_PERIOD: usize = 256;
+
+const UNDEF: i8 = 0;
+const TRUE: i8 = 1;
+const FALSE: i8 = -1;
+
+/// A conflict-driven clause learning SAT solver.
+///
+/// The solver learns a clause at each conflict (first unique implication point), chooses the variables involved in the recent conflicts first, keeps the last polarity of the variables and restarts at geometrically increasing intervals.
+/// It is intended to the formulas built by this library on small frameworks, and does not compete with the state-of-the-art solvers.
+///
+/// Variables and clauses can be added to the solver after it was built, and the formula can be solved under assumptions (see [`solve_with_assumptions`]).
+/// The clauses learnt during a search are kept for the next ones, since they are implied by the clauses of the formula whatever the assumptions.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::sat::{CdclSolver, Cnf};
+/// let mut cnf = Cnf::default();
+/// let a = cnf.new_var();
+/// let b = cnf.new_var();
+/// cnf.add_clause(&[a, b]);
+/// cnf.add_clause(&[-a, b]);
+/// assert!(CdclSolver::new(&cnf).solve().unwrap()[1]);
+/// cnf.add_clause(&[-b]);
+/// assert_eq!(None, CdclSolver::new(&cnf).solve());
+/// ```
+///
+/// [`solve_with_assumptions`]: #method.solve_with_assumptions
+pub struct CdclSolver {
+    n_vars: usize,
+    clauses: Vec<Vec<usize>>,
+    watches: Vec<Vec<usize>>,
+    values: Vec<i8>,
+    levels: Vec<usize>,
+    reasons: Vec<Option<usize>>,
+    trail: Vec<usize>,
+    trail_limits: Vec<usize>,
+    propagated: usize,
+    activities: Vec<f64>,
+    activity_increment: f64,
+    phases: Vec<bool>,
+    unsatisfiable: bool,
+}
+
+fn to_code(literal: i32) -> usize {
+    let var = literal.unsigned_abs() as usize - 1;
+    2 * var + (literal < 0) as usize
+}
+
+fn code_value(values: &[i8], code: usize) -> i8 {
+    let value = values[code >> 1];
+    if code & 1 == 1 {
+        -value
+    } else {
+        value
+    }
+}
+
+impl CdclSolver {
+    /// Builds a solver for a formula.
+    ///
+    /// # Arguments
+    ///
+    /// * `cnf` - the formula
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::sat::{CdclSolver, Cnf};
+    /// assert_eq!(Some(vec![]), CdclSolver::new(&Cnf::default()).solve());
+    /// ```
+    pub fn new(cnf: &Cnf) -> Self {
+        let n_vars = cnf.n_vars();
+        let mut solver = CdclSolver {
+            n_vars,
+            clauses: Vec::with_capacity(cnf.clauses().len()),
+            watches: vec![vec![]; 2 * n_vars],
+            values: vec![UNDEF; n_vars],
+            levels: vec![0; n_vars],
+            reasons: vec![None; n_vars],
+            trail: Vec::with_capacity(n_vars),
+            trail_limits: vec![],
+            propagated: 0,
+            activities: vec![0.; n_vars],
+            activity_increment: 1.,
+            phases: vec![false; n_vars],
+            unsatisfiable: false,
+        };
+        cnf.clauses().iter().for_each(|c| solver.add_clause(c));
+        solver
+    }
+
+    /// Creates a new variable, and returns its positive literal.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::sat::{CdclSolver, Cnf};
+    /// let mut cnf = Cnf::default();
+    /// cnf.new_var();
+    /// let mut solver = CdclSolver::new(&cnf);
+    /// assert_eq!(2, solver.new_var());
+    /// ```
+    pub fn new_var(&mut self) -> i32 {
+        self.n_vars += 1;
+        self.watches.push(vec![]);
+        self.watches.push(vec![]);
+        self.values.push(UNDEF);
+        self.levels.push(0);
+        self.reasons.push(None);
+        self.activities.push(0.);
+        self.phases.push(false);
+        self.n_vars as i32
+    }
+
+    /// Adds a clause to the formula of the solver.
+    ///
+    /// The assignment left by the previous search, if any, is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `clause` - the literals of the clause
+    ///
+    /// # Panics
+    ///
+    /// Panics if a literal is `0` or refers to an undefined variable.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::sat::{CdclSolver, Cnf};
+    /// let mut solver = CdclSolver::new(&Cnf::default());
+    /// let a = solver.new_var();
+    /// solver.add_clause(&[-a]);
+    /// assert_eq!(Some(vec![false]), solver.solve());
+    /// solver.add_clause(&[a]);
+    /// assert_eq!(None, solver.solve());
+    /// ```
+    pub fn add_clause(&mut self, clause: &[i32]) {
+        assert!(
+            clause
+                .iter()
+                .all(|l| *l != 0 && l.unsigned_abs() as usize <= self.n_vars),
+            "undefined variable in clause {:?}",
+            clause
+        );
+        self.backtrack(0);
+        let mut codes = clause.iter().map(|l| to_code(*l)).collect::<Vec<usize>>();
+        codes.sort_unstable();
+        codes.dedup();
+        if codes.windows(2).any(|w| w[0] ^ 1 == w[1])
+            || codes.iter().any(|c| code_value(&self.values, *c) == TRUE)
+        {
+            return;
+        }
+        // the literals falsified at level 0 never satisfy the clause
+        codes.retain(|c| code_value(&self.values, *c) == UNDEF);
+        self.add_codes(codes);
+    }
+
+    fn add_codes(&mut self, codes: Vec<usize>) {
+        match codes.len() {
+            0 => self.unsatisfiable = true,
+            1 => self.assign(codes[0], None),
+            _ => {
+                self.watches[codes[0] ^ 1].push(self.clauses.len());
+                self.watches[codes[1] ^ 1].push(self.clauses.len());
+                self.clauses.push(codes);
+            }
+        }
+    }
+
+    fn assign(&mut self, code: usize, reason: Option<usize>) {
+        let var = code >> 1;
+        self.values[var] = if code & 1 == 1 { FALSE } else { TRUE };
+        self.levels[var] = self.trail_limits.len();
+        self.reasons[var] = reason;
+        self.trail.push(code);
+    }
+
+    fn propagate(&mut self) -> Option<usize> {
+        while self.propagated < self.trail.len() {
+            let code = self.trail[self.propagated];
+            self.propagated += 1;
+            let false_code = code ^ 1;
+            let mut watching = std::mem::take(&mut self.watches[code]);
+            let mut i = 0;
+            let mut conflict = None;
+            while i < watching.len() {
+                let ci = watching[i];
+                let clause = &mut self.clauses[ci];
+                if clause[0] == false_code {
+                    clause.swap(0, 1);
+                }
+                if code_value(&self.values, clause[0]) == TRUE {
+                    i += 1;
+                    continue;
+                }
+                let values = &self.values;
+                match (2..clause.len()).find(|k| code_value(values, clause[*k]) != FALSE) {
+                    Some(k) => {
+                        clause.swap(1, k);
+                        self.watches[clause[1] ^ 1].push(ci);
+                        watching.swap_remove(i);
+                    }
+                    None => {
+                        let first = clause[0];
+                        if code_value(&self.values, first) == FALSE {
+                            conflict = Some(ci);
+                            break;
+                        }
+                        self.assign(first, Some(ci));
+                        i += 1;
+                    }
+                }
+            }
+            self.watches[code] = watching;
+            if conflict.is_some() {
+                self.propagated = self.trail.len();
+                return conflict;
+            }
+        }
+        None
+    }
+
+    fn bump(&mut self, var: usize) {
+        self.activities[var] += self.activity_increment;
+        if self.activities[var] > 1e100 {
+            self.activities.iter_mut().for_each(|a| *a *= 1e-100);
+            self.activity_increment *= 1e-100;
+        }
+    }
+
+    fn analyze(&mut self, conflict: usize) -> (Vec<usize>, usize) {
+        let level = self.trail_limits.len();
+        let mut seen = vec![false; self.n_vars];
+        let mut learnt = vec![0];
+        let mut n_pending = 0;
+        let mut index = self.trail.len();
+        let mut reason = Some(conflict);
+        let mut implied: Option<usize> = None;
+        loop {
+            let clause = self.clauses[reason.unwrap()].clone();
+            for code in clause.into_iter().filter(|c| Some(*c) != implied) {
+                let var = code >> 1;
+                if seen[var] || self.levels[var] == 0 {
+                    continue;
+                }
+                seen[var] = true;
+                self.bump(var);
+                if self.levels[var] == level {
+                    n_pending += 1;
+                } else {
+                    learnt.push(code);
+                }
+            }
+            loop {
+                index -= 1;
+                if seen[self.trail[index] >> 1] {
+                    break;
+                }
+            }
+            let code = self.trail[index];
+            seen[code >> 1] = false;
+            n_pending -= 1;
+            if n_pending == 0 {
+                learnt[0] = code ^ 1;
+                break;
+            }
+            implied = Some(code);
+            reason = self.reasons[code >> 1];
+        }
+        self.activity_increment /= 0.95;
+        let mut backtrack_level = 0;
+        if learnt.len() > 1 {
+            let max = (1..learnt.len())
+                .max_by_key(|i| self.levels[learnt[*i] >> 1])
+                .unwrap();
+            learnt.swap(1, max);
+            backtrack_level = self.levels[learnt[1] >> 1];
+        }
+        (learnt, backtrack_level)
+    }
+
+    fn backtrack(&mut self, level: usize) {
+        if self.trail_limits.len() <= level {
+            return;
+        }
+        let limit = self.trail_limits[level];
+        for code in self.trail.drain(limit..) {
+            let var = code >> 1;
+            self.phases[var] = code & 1 == 0;
+            self.values[var] = UNDEF;
+            self.reasons[var] = None;
+        }
+        self.trail_limits.truncate(level);
+        self.propagated = limit;
+    }
+
+    fn decide(&mut self) -> Option<usize> {
+        let var = (0..self.n_vars)
+            .filter(|v| self.values[*v] == UNDEF)
+            .max_by(|v, w| {
+                self.activities[*v]
+                    .partial_cmp(&self.activities[*w])
+                    .unwrap()
+            })?;
+        Some(2 * var + (!self.phases[var]) as usize)
+    }
+
+    /// Solves the formula, returning a model if it is satisfiable.
+    ///
+    /// The model gives the value of each variable, the value of variable `v` being at index `v-1`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::sat::{CdclSolver, Cnf};
+    /// let mut cnf = Cnf::default();
+    /// let a = cnf.new_var();
+    /// cnf.add_clause(&[a]);
+    /// assert_eq!(Some(vec![true]), CdclSolver::new(&cnf).solve());
+    /// ```
+    pub fn solve(&mut self) -> Option<Vec<bool>> {
+        match self.solve_until(None) {
+            SatOutcome::Satisfiable(model) => Some(model),
+            _ => None,
+        }
+    }
+
+    /// Solves the formula, giving up when the deadline is reached.
+    ///
+    /// The deadline is checked every few conflicts and decisions, so that the search may slightly exceed it.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - the instant at which the search is stopped, if any
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::sat::{CdclSolver, Cnf, SatOutcome};
+    /// # use std::time::Instant;
+    /// let mut cnf = Cnf::default();
+    /// let a = cnf.new_var();
+    /// cnf.add_clause(&[a, -a]);
+    /// assert_eq!(SatOutcome::Unknown, CdclSolver::new(&cnf).solve_until(Some(Instant::now())));
+    /// assert_eq!(SatOutcome::Satisfiable(vec![false]), CdclSolver::new(&cnf).solve_until(None));
+    /// ```
+    pub fn solve_until(&mut self, deadline: Option<Instant>) -> SatOutcome {
+        self.search(&[], deadline)
+    }
+
+    /// Solves the formula under assumptions, returning a model if it is satisfiable when the assumed literals are true.
+    ///
+    /// The assumptions only hold for this search; the formula of the solver is left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `assumptions` - the literals assumed to be true
+    ///
+    /// # Panics
+    ///
+    /// Panics if a literal is `0` or refers to an undefined variable.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::sat::{CdclSolver, Cnf};
+    /// let mut cnf = Cnf::default();
+    /// let a = cnf.new_var();
+    /// let b = cnf.new_var();
+    /// cnf.add_clause(&[-a, -b]);
+    /// let mut solver = CdclSolver::new(&cnf);
+    /// assert_eq!(None, solver.solve_with_assumptions(&[a, b]));
+    /// assert_eq!(Some(vec![true, false]), solver.solve_with_assumptions(&[a]));
+    /// assert!(solver.solve().is_some());
+    /// ```
+    pub fn solve_with_assumptions(&mut self, assumptions: &[i32]) -> Option<Vec<bool>> {
+        assert!(
+            assumptions
+                .iter()
+                .all(|l| *l != 0 && l.unsigned_abs() as usize <= self.n_vars),
+            "undefined variable in assumptions {:?}",
+            assumptions
+        );
+        match self.search(assumptions, None) {
+            SatOutcome::Satisfiable(model) => Some(model),
+            _ => None,
+        }
+    }
+
+    /// Runs the search, the assumptions being the first decisions (one per decision level).
+    ///
+    /// An assumption falsified by the previous ones and the formula ends the search, without making the formula itself unsatisfiable.
+    fn search(&mut self, assumptions: &[i32], deadline: Option<Instant>) -> SatOutcome {
+        if self.unsatisfiable {
+            return SatOutcome::Unsatisfiable;
+        }
+        self.backtrack(0);
+        let assumptions = assumptions
+            .iter()
+            .map(|l| to_code(*l))
+            .collect::<Vec<usize>>();
+        let mut restart_limit = 100.;
+        let mut n_conflicts = 0;
+        let mut n_steps: usize = 0;
+        loop {
+            if let Some(d) = deadline {
+                if n_steps.is_multiple_of(DEADLINE_CHECK_PERIOD) && Instant::now() >= d {
+                    return SatOutcome::Unknown;
+                }
+            }
+            n_steps += 1;
+            if let Some(conflict) = self.propagate() {
+                if self.trail_limits.is_empty() {
+                    self.unsatisfiable = true;
+                    return SatOutcome::Unsatisfiable;
+                }
+                n_conflicts += 1;
+                let (learnt, backtrack_level) = self.analyze(conflict);
+                self.backtrack(backtrack_level);
+                let asserting = learnt[0];
+                if learnt.len() == 1 {
+                    self.assign(asserting, None);
+                } else {
+                    self.watches[learnt[0] ^ 1].push(self.clauses.len());
+                    self.watches[learnt[1] ^ 1].push(self.clauses.len());
+                    self.clauses.push(learnt);
+                    self.assign(asserting, Some(self.clauses.len() - 1));
+                }
+                continue;
+            }
+            if n_conflicts as f64 >= restart_limit {
+                n_conflicts = 0;
+                restart_limit *= 1.5;
+                self.backtrack(0);
+                continue;
+            }
+            if let Some(code) = assumptions.get(self.trail_limits.len()) {
+                let value = code_value(&self.values, *code);
+                if value == FALSE {
+                    return SatOutcome::Unsatisfiable;
+                }
+                // an assumption already satisfied opens an empty decision level
+                self.trail_limits.push(self.trail.len());
+                if value == UNDEF {
+                    self.assign(*code, None);
+                }
+                continue;
+            }
+            match self.decide() {
+                Some(code) => {
+                    self.trail_limits.push(self.trail.len());
+                    self.assign(code, None);
+                }
+                None => {
+                    return SatOutcome::Satisfiable(
+                        self.values.iter().map(|v| *v == TRUE).collect(),
+                    )
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pigeon_hole(n_holes: usize) -> Cnf {
+        let mut cnf = Cnf::default();
+        let vars = (0..=n_holes)
+            .map(|_| (0..n_holes).map(|_| cnf.new_var()).collect::<Vec<i32>>())
+            .collect::<Vec<Vec<i32>>>();
+        vars.iter().for_each(|p| cnf.add_clause(p));
+        for h in 0..n_holes {
+            let column = vars.iter().map(|p| p[h]).collect::<Vec<i32>>();
+            cnf.add_at_most(&column, 1);
+        }
+        cnf
+    }
+
+    #[test]
+    fn test_pigeon_hole_unsat() {
+        for n in 1..6 {
+            assert_eq!(None, CdclSolver::new(&pigeon_hole(n)).solve());
+        }
+    }
+
+    #[test]
+    fn test_model_satisfies_clauses() {
+        let mut cnf = Cnf::default();
+        let vars = (0..12).map(|_| cnf.new_var()).collect::<Vec<i32>>();
+        for i in 0..12 {
+            cnf.add_clause(&[vars[i], -vars[(i + 1) % 12], vars[(i + 5) % 12]]);
+            cnf.add_clause(&[-vars[i], -vars[(i + 3) % 12]]);
+        }
+        cnf.add_at_most(&vars, 4);
+        cnf.add_clause(&[vars[0], vars[0]]);
+        cnf.add_clause(&[vars[2], -vars[2]]);
+        let model = CdclSolver::new(&cnf).solve().unwrap();
+        assert!(cnf.clauses().iter().all(|c| c
+            .iter()
+            .any(|l| model[l.unsigned_abs() as usize - 1] == (*l > 0))));
+    }
+
+    #[test]
+    fn test_at_most() {
+        for n in 1..5 {
+            for k in 0..=n {
+                let mut cnf = Cnf::default();
+                let vars = (0..n).map(|_| cnf.new_var()).collect::<Vec<i32>>();
+                cnf.add_at_most(&vars, k);
+                for (i, v) in vars.iter().enumerate() {
+                    if i <= k {
+                        cnf.add_clause(&[*v]);
+                    }
+                }
+                assert_eq!(k == n, CdclSolver::new(&cnf).solve().is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_at_most() {
+        let weights = [3, 5, 5, 7];
+        for k in 0..=21 {
+            for forced in 0..16_usize {
+                let mut cnf = Cnf::default();
+                let vars = (0..4).map(|_| cnf.new_var()).collect::<Vec<i32>>();
+                let literals = vars
+                    .iter()
+                    .zip(weights.iter())
+                    .map(|(v, w)| (*v, *w * 1_000))
+                    .collect::<Vec<(i32, u64)>>();
+                cnf.add_weighted_at_most(&literals, k * 1_000);
+                let mut sum = 0;
+                for (i, v) in vars.iter().enumerate() {
+                    if forced & (1 << i) != 0 {
+                        cnf.add_clause(&[*v]);
+                        sum += weights[i];
+                    }
+                }
+                let model = CdclSolver::new(&cnf).solve();
+                assert_eq!(sum <= k, model.is_some());
+                if let Some(m) = model {
+                    let true_sum = (0..4).filter(|i| m[*i]).map(|i| weights[i]).sum::<u64>();
+                    assert!(true_sum <= k);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_at_most_large_weights() {
+        let mut cnf = Cnf::default();
+        let vars = (0..100).map(|_| cnf.new_var()).collect::<Vec<i32>>();
+        let literals = vars
+            .iter()
+            .map(|v| (*v, u64::MAX / 2))
+            .collect::<Vec<(i32, u64)>>();
+        cnf.add_weighted_at_most(&literals, u64::MAX - 1);
+        assert!(cnf.n_vars() < 1_000);
+        cnf.add_clause(&[vars[0]]);
+        cnf.add_clause(&[vars[99]]);
+        let model = CdclSolver::new(&cnf).solve().unwrap();
+        assert_eq!(2, model[..100].iter().filter(|b| **b).count());
+    }
+
+    #[test]
+    fn test_incremental_solving_with_assumptions() {
+        let mut seed = 42_u64;
+        let mut next = move |n: u64| {
+            seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+            (seed >> 33) % n
+        };
+        let mut cnf = Cnf::default();
+        let mut solver = CdclSolver::new(&cnf);
+        for _ in 0..40 {
+            if cnf.n_vars() < 10 {
+                assert_eq!(cnf.new_var(), solver.new_var());
+            }
+            let n_vars = cnf.n_vars() as u64;
+            let random_literal = |next: &mut dyn FnMut(u64) -> u64| {
+                let var = next(n_vars) as i32 + 1;
+                if next(2) == 0 {
+                    var
+                } else {
+                    -var
+                }
+            };
+            let clause = (0..3)
+                .map(|_| random_literal(&mut next))
+                .collect::<Vec<i32>>();
+            cnf.add_clause(&clause);
+            solver.add_clause(&clause);
+            let assumptions = (0..next(4))
+                .map(|_| random_literal(&mut next))
+                .collect::<Vec<i32>>();
+            let mut assumed = cnf.clone();
+            assumptions.iter().for_each(|l| assumed.add_clause(&[*l]));
+            let expected = CdclSolver::new(&assumed).solve().is_some();
+            match solver.solve_with_assumptions(&assumptions) {
+                Some(model) => {
+                    assert!(expected);
+                    assert!(assumed.clauses().iter().all(|c| c
+                        .iter()
+                        .any(|l| model[l.unsigned_abs() as usize - 1] == (*l > 0))));
+                }
+                None => assert!(!expected),
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_clause() {
+        let mut cnf = Cnf::default();
+        cnf.add_clause(&[]);
+        assert_eq!(None, CdclSolver::new(&cnf).solve());
+    }
+
+    #[test]
+    #[should_panic(expected = "undefined variable")]
+    fn test_undefined_variable() {
+        Cnf::default().add_clause(&[1]);
+    }
+}
@@ -0,0 +1,104 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use anyhow::{anyhow, Result};
+use std::{fmt::Display, str::FromStr};
+
+/// The semantics of abstract argumentation frameworks.
+///
+/// Semantics can be parsed from and displayed as their acronyms used in the ICCMA problem names (e.g. `PR` for the preferred semantics).
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::Semantics;
+/// let semantics = "PR".parse::<Semantics>().unwrap();
+/// assert_eq!(Semantics::Preferred, semantics);
+/// assert_eq!("PR", semantics.to_string());
+/// assert!("XX".parse::<Semantics>().is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Semantics {
+    /// The grounded semantics (`GR`).
+    Grounded,
+    /// The complete semantics (`CO`).
+    Complete,
+    /// The stable semantics (`ST`).
+    Stable,
+    /// The preferred semantics (`PR`).
+    Preferred,
+    /// The semi-stable semantics (`SST`).
+    SemiStable,
+    /// The stage semantics (`STG`).
+    Stage,
+    /// The ideal semantics (`ID`).
+    Ideal,
+}
+
+impl FromStr for Semantics {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "GR" => Ok(Semantics::Grounded),
+            "CO" => Ok(Semantics::Complete),
+            "ST" => Ok(Semantics::Stable),
+            "PR" => Ok(Semantics::Preferred),
+            "SST" => Ok(Semantics::SemiStable),
+            "STG" => Ok(Semantics::Stage),
+            "ID" => Ok(Semantics::Ideal),
+            _ => Err(anyhow!(r#"unknown semantics "{}""#, s)),
+        }
+    }
+}
+
+impl Display for Semantics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let acronym = match self {
+            Semantics::Grounded => "GR",
+            Semantics::Complete => "CO",
+            Semantics::Stable => "ST",
+            Semantics::Preferred => "PR",
+            Semantics::SemiStable => "SST",
+            Semantics::Stage => "STG",
+            Semantics::Ideal => "ID",
+        };
+        write!(f, "{}", acronym)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str_display() {
+        for s in &["GR", "CO", "ST", "PR", "SST", "STG", "ID"] {
+            assert_eq!(*s, s.parse::<Semantics>().unwrap().to_string());
+        }
+    }
+
+    #[test]
+    fn test_from_str_unknown() {
+        assert_eq!(
+            r#"unknown semantics "co""#,
+            "co".parse::<Semantics>().unwrap_err().to_string()
+        );
+    }
+}
@@ -0,0 +1,245 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crate::aa::arguments::{Argument, ArgumentSet, LabelType};
+use anyhow::{anyhow, Context, Result};
+use std::fmt::Display;
+
+/// An Argumentation framework with collective attacks (SETAF), in which an attack is made by a set of arguments.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{ArgumentSet, SetAFramework};
+/// let labels = vec!["a", "b", "c"];
+/// let mut framework = SetAFramework::new(ArgumentSet::new(labels.clone()));
+/// framework.new_attack(&[labels[0], labels[1]], &labels[2]).unwrap();
+/// assert_eq!(1, framework.n_attacks());
+/// ```
+pub struct SetAFramework<T>
+where
+    T: LabelType,
+{
+    arguments: ArgumentSet<T>,
+    attacks: Vec<(Vec<usize>, usize)>,
+}
+
+/// A collective attack, represented by the set of attackers and the attacked argument.
+///
+/// Attacks are built by [`SetAFramework`] objects.
+///
+/// [`SetAFramework`]: struct.SetAFramework.html
+pub struct SetAttack<'a, T>(Vec<&'a Argument<T>>, &'a Argument<T>)
+where
+    T: LabelType;
+
+impl<'a, T> SetAttack<'a, T>
+where
+    T: LabelType,
+{
+    /// Returns the attackers.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// # use crusti_arg::{SetAttack, LabelType};
+    /// fn describe_attack<T: LabelType>(attack: &SetAttack<T>) {
+    ///     println!("{} arguments attack {}", attack.attackers().len(), attack.attacked());
+    /// }
+    /// ```
+    pub fn attackers(&self) -> &[&'a Argument<T>] {
+        &self.0
+    }
+
+    /// Returns the attacked argument.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// # use crusti_arg::{SetAttack, LabelType};
+    /// fn describe_attack<T: LabelType>(attack: &SetAttack<T>) {
+    ///     println!("{} arguments attack {}", attack.attackers().len(), attack.attacked());
+    /// }
+    /// ```
+    pub fn attacked(&self) -> &'a Argument<T> {
+        self.1
+    }
+}
+
+impl<'a, T> Display for SetAttack<'a, T>
+where
+    T: LabelType,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let attackers = self
+            .0
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+        write!(f, "{{{}}} → {}", attackers, self.1)
+    }
+}
+
+impl<T> SetAFramework<T>
+where
+    T: LabelType,
+{
+    /// Builds a SETAF with no attacks.
+    ///
+    /// # Arguments
+    ///
+    /// * `arguments` - the set of arguments
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, SetAFramework};
+    /// let framework = SetAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+    /// assert_eq!(3, framework.argument_set().len());
+    /// assert_eq!(0, framework.n_attacks());
+    /// ```
+    pub fn new(arguments: ArgumentSet<T>) -> Self {
+        SetAFramework {
+            arguments,
+            attacks: vec![],
+        } // kcov-ignore
+    }
+
+    /// Adds a new collective attack given the labels of the attackers and of the attacked argument.
+    ///
+    /// If the set of attackers is empty or if some provided arguments are undefined, an error is returned.
+    /// Else, the attack is added.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - the labels of the attackers
+    /// * `to` - the label of the attacked argument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, SetAFramework};
+    /// let labels = vec!["a", "b", "c"];
+    /// let mut framework = SetAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&[labels[0], labels[1]], &labels[2]).unwrap();
+    /// assert!(framework.new_attack(&[], &labels[2]).is_err());
+    /// ```
+    pub fn new_attack(&mut self, from: &[T], to: &T) -> Result<()> {
+        let context = || format!("cannot add an attack from {:?} to {:?}", from, to);
+        if from.is_empty() {
+            return Err(anyhow!("empty set of attackers")).with_context(context);
+        }
+        let attackers = from
+            .iter()
+            .map(|a| self.arguments.get_argument_index(a))
+            .collect::<Result<Vec<usize>>>()
+            .with_context(context)?;
+        let attacked = self
+            .arguments
+            .get_argument_index(to)
+            .with_context(context)?;
+        self.attacks.push((attackers, attacked));
+        Ok(())
+    }
+
+    /// Returns the argument set of the framework.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, SetAFramework};
+    /// let framework = SetAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+    /// assert_eq!(3, framework.argument_set().len());
+    /// ```
+    pub fn argument_set(&self) -> &ArgumentSet<T> {
+        &self.arguments
+    }
+
+    /// Provides an iterator to the attacks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, SetAFramework};
+    /// let labels = vec!["a", "b", "c"];
+    /// let mut framework = SetAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&[labels[0], labels[1]], &labels[2]).unwrap();
+    /// let attack = framework.iter_attacks().next().unwrap();
+    /// assert_eq!(2, attack.attackers().len());
+    /// ```
+    pub fn iter_attacks<'a>(&'a self) -> Box<dyn Iterator<Item = SetAttack<'a, T>> + 'a> {
+        Box::new(self.attacks.iter().map(move |att| {
+            SetAttack(
+                att.0
+                    .iter()
+                    .map(|a| self.arguments.get_argument_by_id(*a))
+                    .collect(),
+                self.arguments.get_argument_by_id(att.1),
+            )
+        }))
+    }
+
+    /// Returns the number of attacks in this framework.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, SetAFramework};
+    /// let labels = vec!["a", "b"];
+    /// let mut framework = SetAFramework::new(ArgumentSet::new(labels.clone()));
+    /// assert_eq!(0, framework.n_attacks());
+    /// framework.new_attack(&[labels[0]], &labels[1]).unwrap();
+    /// assert_eq!(1, framework.n_attacks());
+    /// ```
+    pub fn n_attacks(&self) -> usize {
+        self.attacks.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_attack_ok() {
+        let arg_labels = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let mut framework = SetAFramework::new(ArgumentSet::new(arg_labels.clone()));
+        framework
+            .new_attack(&arg_labels[0..2], &arg_labels[2])
+            .unwrap();
+        assert_eq!(vec![(vec![0, 1], 2)], framework.attacks);
+        assert_eq!(
+            "{a, b} → c",
+            framework.iter_attacks().next().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_new_attack_errors() {
+        let arg_labels = vec!["a".to_string(), "b".to_string()];
+        let mut framework = SetAFramework::new(ArgumentSet::new(arg_labels.clone()));
+        assert!(framework.new_attack(&[], &arg_labels[0]).is_err());
+        assert!(framework
+            .new_attack(&["c".to_string()], &arg_labels[0])
+            .is_err());
+        assert!(framework.new_attack(&arg_labels, &"c".to_string()).is_err());
+        assert_eq!(0, framework.n_attacks());
+    }
+}
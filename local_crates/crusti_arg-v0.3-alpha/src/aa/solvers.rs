@@ -0,0 +1,677 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! A module containing the native engines used to compute the extensions of frameworks.
+//!
+//! The engines implement the [`SemanticsSolver`] trait, which gives the extensions and decides the acceptance of arguments.
+//! The [`NaiveSolver`] enumerates the conflict-free sets of small frameworks, and handles all the [`Semantics`].
+//! The [`GroundedSolver`] computes the grounded extension of frameworks of any size, in linear time.
+//! The acceptance methods of [`AAFramework`] dispatch the queries to the engines, using dedicated algorithms when the semantics allows it.
+//!
+//! [`AAFramework`]: ../struct.AAFramework.html
+//! [`GroundedSolver`]: struct.GroundedSolver.html
+//! [`NaiveSolver`]: struct.NaiveSolver.html
+//! [`Semantics`]: ../enum.Semantics.html
+//! [`SemanticsSolver`]: trait.SemanticsSolver.html
+use anyhow::{anyhow, Result};
+
+use crate::{AAFramework, LabelType, Semantics};
+
+/// A solver computing the extensions of a framework under some semantics.
+///
+/// Extensions are given by membership vectors, indexed by the identifiers of the arguments.
+/// An error is returned by the methods of a solver when it does not handle the requested semantics.
+///
+/// Only [`framework`] and [`all_extensions`] must be implemented; the other methods are computed from the set of extensions by default, and may be overridden by solvers able to answer them more efficiently.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{solvers::SemanticsSolver, AAFramework, ArgumentSet, Semantics};
+/// # use anyhow::Result;
+/// /// A solver considering the empty set as the only extension.
+/// struct EmptySolver<'a>(&'a AAFramework<&'static str>);
+///
+/// impl SemanticsSolver<&'static str> for EmptySolver<'_> {
+///     fn framework(&self) -> &AAFramework<&'static str> {
+///         self.0
+///     }
+///
+///     fn all_extensions(&self, _semantics: Semantics) -> Result<Vec<Vec<bool>>> {
+///         Ok(vec![vec![false; self.0.argument_set().len()]])
+///     }
+/// }
+///
+/// let framework = AAFramework::new(ArgumentSet::new(vec!["a"]));
+/// let solver = EmptySolver(&framework);
+/// assert_eq!(1, solver.count_extensions(Semantics::Complete).unwrap());
+/// assert!(!solver.is_credulously_accepted(&"a", Semantics::Complete).unwrap());
+/// ```
+///
+/// [`all_extensions`]: #tymethod.all_extensions
+/// [`framework`]: #tymethod.framework
+pub trait SemanticsSolver<T>
+where
+    T: LabelType,
+{
+    /// Returns the framework this solver works on.
+    fn framework(&self) -> &AAFramework<T>;
+
+    /// Computes all the extensions of the framework under the given semantics.
+    ///
+    /// # Arguments
+    ///
+    /// * `semantics` - the semantics
+    fn all_extensions(&self, semantics: Semantics) -> Result<Vec<Vec<bool>>>;
+
+    /// Computes an extension of the framework under the given semantics, if there is one.
+    ///
+    /// # Arguments
+    ///
+    /// * `semantics` - the semantics
+    fn one_extension(&self, semantics: Semantics) -> Result<Option<Vec<bool>>> {
+        Ok(self.all_extensions(semantics)?.into_iter().next())
+    }
+
+    /// Counts the extensions of the framework under the given semantics.
+    ///
+    /// # Arguments
+    ///
+    /// * `semantics` - the semantics
+    fn count_extensions(&self, semantics: Semantics) -> Result<usize> {
+        Ok(self.all_extensions(semantics)?.len())
+    }
+
+    /// Returns `true` iff an argument belongs to at least one extension under the given semantics.
+    ///
+    /// An error is returned if the argument is undefined.
+    ///
+    /// # Arguments
+    ///
+    /// * `arg` - the label of the argument
+    /// * `semantics` - the semantics
+    fn is_credulously_accepted(&self, arg: &T, semantics: Semantics) -> Result<bool> {
+        let id = self.framework().argument_set().get_argument_index(arg)?;
+        Ok(self.all_extensions(semantics)?.iter().any(|e| e[id]))
+    }
+
+    /// Returns `true` iff an argument belongs to all the extensions under the given semantics.
+    ///
+    /// An error is returned if the argument is undefined.
+    ///
+    /// # Arguments
+    ///
+    /// * `arg` - the label of the argument
+    /// * `semantics` - the semantics
+    fn is_skeptically_accepted(&self, arg: &T, semantics: Semantics) -> Result<bool> {
+        let id = self.framework().argument_set().get_argument_index(arg)?;
+        Ok(self.all_extensions(semantics)?.iter().all(|e| e[id]))
+    }
+}
+
+/// Returns the default solver for a framework and a semantics.
+///
+/// The [`GroundedSolver`] is returned for the grounded semantics, and the [`NaiveSolver`] for the other ones.
+/// An error is returned if the framework is too large for the selected solver.
+///
+/// # Arguments
+///
+/// * `framework` - the framework
+/// * `semantics` - the semantics
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{solvers, AAFramework, ArgumentSet, Semantics};
+/// let framework = AAFramework::new(ArgumentSet::new((0..100).collect::<Vec<usize>>()));
+/// let solver = solvers::default_solver(&framework, Semantics::Grounded).unwrap();
+/// assert_eq!(1, solver.count_extensions(Semantics::Grounded).unwrap());
+/// assert!(solvers::default_solver(&framework, Semantics::Preferred).is_err());
+/// ```
+///
+/// [`GroundedSolver`]: struct.GroundedSolver.html
+/// [`NaiveSolver`]: struct.NaiveSolver.html
+pub fn default_solver<'a, T>(
+    framework: &'a AAFramework<T>,
+    semantics: Semantics,
+) -> Result<Box<dyn SemanticsSolver<T> + 'a>>
+where
+    T: LabelType,
+{
+    if semantics == Semantics::Grounded {
+        Ok(Box::new(GroundedSolver::new(framework)))
+    } else {
+        Ok(Box::new(NaiveSolver::new(framework)?))
+    }
+}
+
+/// A solver computing the grounded extension of frameworks of any size.
+///
+/// The extension is computed in linear time by [`AAFramework::least_fixpoint`].
+///
+/// Since the grounded extension is the least complete extension, this solver also gives a complete extension and decides the skeptical acceptance under the complete semantics.
+/// The other queries are not handled.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{solvers::{GroundedSolver, SemanticsSolver}, AAFramework, ArgumentSet, Semantics};
+/// let labels = vec!["a", "b", "c"];
+/// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+/// framework.new_attack(&labels[0], &labels[1]).unwrap();
+/// framework.new_attack(&labels[1], &labels[2]).unwrap();
+/// let solver = GroundedSolver::new(&framework);
+/// assert_eq!(vec![vec![true, false, true]], solver.all_extensions(Semantics::Grounded).unwrap());
+/// assert!(solver.is_skeptically_accepted(&"c", Semantics::Complete).unwrap());
+/// assert!(solver.all_extensions(Semantics::Complete).is_err());
+/// ```
+///
+/// [`AAFramework::least_fixpoint`]: ../struct.AAFramework.html#method.least_fixpoint
+pub struct GroundedSolver<'a, T>
+where
+    T: LabelType,
+{
+    framework: &'a AAFramework<T>,
+}
+
+impl<'a, T> GroundedSolver<'a, T>
+where
+    T: LabelType,
+{
+    /// Builds a grounded solver for a framework.
+    ///
+    /// # Arguments
+    ///
+    /// * `framework` - the framework
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{solvers::{GroundedSolver, SemanticsSolver}, AAFramework, ArgumentSet, Semantics};
+    /// let framework = AAFramework::new(ArgumentSet::new(vec!["a"]));
+    /// let solver = GroundedSolver::new(&framework);
+    /// assert!(solver.is_credulously_accepted(&"a", Semantics::Grounded).unwrap());
+    /// ```
+    pub fn new(framework: &'a AAFramework<T>) -> Self {
+        GroundedSolver { framework }
+    }
+
+    fn unsupported(semantics: Semantics, query: &str) -> anyhow::Error {
+        anyhow!(
+            "the grounded solver cannot compute {} under the {} semantics",
+            query,
+            semantics
+        )
+    }
+}
+
+impl<T> SemanticsSolver<T> for GroundedSolver<'_, T>
+where
+    T: LabelType,
+{
+    fn framework(&self) -> &AAFramework<T> {
+        self.framework
+    }
+
+    fn all_extensions(&self, semantics: Semantics) -> Result<Vec<Vec<bool>>> {
+        match semantics {
+            Semantics::Grounded => Ok(vec![self.framework.least_fixpoint()]),
+            _ => Err(Self::unsupported(semantics, "all the extensions")),
+        }
+    }
+
+    fn one_extension(&self, semantics: Semantics) -> Result<Option<Vec<bool>>> {
+        match semantics {
+            Semantics::Grounded | Semantics::Complete => Ok(Some(self.framework.least_fixpoint())),
+            _ => Err(Self::unsupported(semantics, "an extension")),
+        }
+    }
+
+    fn is_credulously_accepted(&self, arg: &T, semantics: Semantics) -> Result<bool> {
+        match semantics {
+            Semantics::Grounded => {
+                let id = self.framework.argument_set().get_argument_index(arg)?;
+                Ok(self.framework.least_fixpoint()[id])
+            }
+            _ => Err(Self::unsupported(semantics, "credulous acceptance")),
+        }
+    }
+
+    fn is_skeptically_accepted(&self, arg: &T, semantics: Semantics) -> Result<bool> {
+        match semantics {
+            Semantics::Grounded | Semantics::Complete => {
+                let id = self.framework.argument_set().get_argument_index(arg)?;
+                Ok(self.framework.least_fixpoint()[id])
+            }
+            _ => Err(Self::unsupported(semantics, "skeptical acceptance")),
+        }
+    }
+}
+
+/// The maximal number of arguments of the frameworks handled by the [`NaiveSolver`].
+///
+/// [`NaiveSolver`]: struct.NaiveSolver.html
+pub const MAX_ARGUMENTS: usize = 64;
+
+/// The number of arguments above which the [`NaiveSolver`] is expected to be slow.
+///
+/// [`NaiveSolver`]: struct.NaiveSolver.html
+pub const SMALL_FRAMEWORK_SIZE: usize = 25;
+
+/// A brute-force solver enumerating the conflict-free sets of small frameworks.
+///
+/// The enumeration skips the sets that are not conflict-free, and fixes the arguments of the grounded extension and the ones it attacks for the semantics based on completeness.
+/// Extensions are given by membership vectors, indexed by the identifiers of the arguments.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{solvers::NaiveSolver, AAFramework, ArgumentSet, Semantics};
+/// let labels = vec!["a", "b", "c"];
+/// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+/// framework.new_attack(&labels[0], &labels[1]).unwrap();
+/// framework.new_attack(&labels[1], &labels[0]).unwrap();
+/// framework.new_attack(&labels[1], &labels[2]).unwrap();
+/// let solver = NaiveSolver::new(&framework).unwrap();
+/// assert_eq!(
+///     vec![vec![true, false, true], vec![false, true, false]],
+///     solver.extensions(Semantics::Preferred)
+/// );
+/// ```
+pub struct NaiveSolver<'a, T>
+where
+    T: LabelType,
+{
+    framework: &'a AAFramework<T>,
+    attackers: Vec<u64>,
+    attacked: Vec<u64>,
+}
+
+impl<'a, T> NaiveSolver<'a, T>
+where
+    T: LabelType,
+{
+    /// Builds a naive solver for a framework.
+    ///
+    /// If the framework has more than [`MAX_ARGUMENTS`] arguments, an error is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `framework` - the framework
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{solvers::{self, NaiveSolver}, AAFramework, ArgumentSet};
+    /// let labels = (0..=solvers::MAX_ARGUMENTS).collect::<Vec<usize>>();
+    /// let framework = AAFramework::new(ArgumentSet::new(labels));
+    /// assert!(NaiveSolver::new(&framework).is_err());
+    /// ```
+    ///
+    /// [`MAX_ARGUMENTS`]: constant.MAX_ARGUMENTS.html
+    pub fn new(framework: &'a AAFramework<T>) -> Result<Self> {
+        let n_args = framework.argument_set().len();
+        if n_args > MAX_ARGUMENTS {
+            return Err(anyhow!(
+                "the naive solver handles at most {} arguments, but the framework has {}",
+                MAX_ARGUMENTS,
+                n_args
+            ));
+        }
+        let mut attackers = vec![0; n_args];
+        let mut attacked = vec![0; n_args];
+        for att in framework.iter_attacks() {
+            attackers[att.attacked().id()] |= 1 << att.attacker().id();
+            attacked[att.attacker().id()] |= 1 << att.attacked().id();
+        }
+        Ok(NaiveSolver {
+            framework,
+            attackers,
+            attacked,
+        })
+    }
+
+    fn attacked_by(&self, set: u64) -> u64 {
+        members(set).fold(0, |acc, i| acc | self.attacked[i])
+    }
+
+    fn attackers_of(&self, set: u64) -> u64 {
+        members(set).fold(0, |acc, i| acc | self.attackers[i])
+    }
+
+    fn range(&self, set: u64) -> u64 {
+        set | self.attacked_by(set)
+    }
+
+    fn defended(&self, set: u64) -> u64 {
+        let set_attacks = self.attacked_by(set);
+        (0..self.attackers.len())
+            .filter(|i| self.attackers[*i] & !set_attacks == 0)
+            .fold(0, |acc, i| acc | 1 << i)
+    }
+
+    fn is_complete(&self, set: u64) -> bool {
+        self.defended(set) == set
+    }
+
+    fn grounded(&self) -> u64 {
+        let mut set = 0;
+        loop {
+            let next = self.defended(set);
+            if next == set {
+                return set;
+            }
+            set = next;
+        }
+    }
+
+    /// Computes the largest admissible subset of a conflict-free set, by removing the arguments it does not defend until a fixpoint is reached.
+    fn largest_admissible_subset(&self, set: u64) -> u64 {
+        let mut subset = set;
+        loop {
+            let next = subset & self.defended(subset);
+            if next == subset {
+                return subset;
+            }
+            subset = next;
+        }
+    }
+
+    /// Enumerates the conflict-free sets including `included` and disjoint from `excluded` that satisfy the filter.
+    fn conflict_free_sets(
+        &self,
+        included: u64,
+        excluded: u64,
+        filter: &dyn Fn(u64) -> bool,
+    ) -> Vec<u64> {
+        let mut sets = vec![];
+        let forbidden = excluded | self.attacked_by(included) | self.attackers_of(included);
+        self.extend_conflict_free_set(0, included, forbidden, filter, &mut sets);
+        sets
+    }
+
+    fn extend_conflict_free_set(
+        &self,
+        next_arg: usize,
+        set: u64,
+        forbidden: u64,
+        filter: &dyn Fn(u64) -> bool,
+        sets: &mut Vec<u64>,
+    ) {
+        if next_arg == self.attackers.len() {
+            if filter(set) {
+                sets.push(set);
+            }
+            return;
+        }
+        let arg_mask = 1 << next_arg;
+        if set & arg_mask == 0
+            && forbidden & arg_mask == 0
+            && self.attackers[next_arg] & arg_mask == 0
+        {
+            self.extend_conflict_free_set(
+                next_arg + 1,
+                set | arg_mask,
+                forbidden | self.attackers[next_arg] | self.attacked[next_arg],
+                filter,
+                sets,
+            );
+        }
+        self.extend_conflict_free_set(next_arg + 1, set, forbidden, filter, sets);
+    }
+
+    fn extension_masks(&self, semantics: Semantics) -> Vec<u64> {
+        let grounded = self.grounded();
+        let grounded_attacks = self.attacked_by(grounded);
+        let complete_sets =
+            || self.conflict_free_sets(grounded, grounded_attacks, &|s| self.is_complete(s));
+        let all_args = (0..self.attackers.len()).fold(0, |acc, i| acc | 1 << i);
+        match semantics {
+            Semantics::Grounded => vec![grounded],
+            Semantics::Complete => complete_sets(),
+            Semantics::Preferred => maximal_sets(complete_sets(), |s| s),
+            Semantics::Stable => {
+                self.conflict_free_sets(grounded, grounded_attacks, &|s| self.range(s) == all_args)
+            }
+            Semantics::SemiStable => maximal_sets(complete_sets(), |s| self.range(s)),
+            Semantics::Stage => {
+                maximal_sets(self.conflict_free_sets(0, 0, &|_| true), |s| self.range(s))
+            }
+            Semantics::Ideal => {
+                let preferred = maximal_sets(complete_sets(), |s| s);
+                let skeptical = preferred.iter().fold(all_args, |acc, s| acc & s);
+                vec![self.largest_admissible_subset(skeptical)]
+            }
+        }
+    }
+
+    /// Computes the extensions of the framework under the given semantics.
+    ///
+    /// Each extension is given by a membership vector, indexed by the identifiers of the arguments.
+    /// This method never fails, unlike [`all_extensions`] which must be able to report errors for other solvers.
+    ///
+    /// # Arguments
+    ///
+    /// * `semantics` - the semantics
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{solvers::NaiveSolver, AAFramework, ArgumentSet, Semantics};
+    /// let labels = vec!["a", "b"];
+    /// let mut framework = AAFramework::new(ArgumentSet::new(labels.clone()));
+    /// framework.new_attack(&labels[0], &labels[1]).unwrap();
+    /// framework.new_attack(&labels[1], &labels[0]).unwrap();
+    /// let solver = NaiveSolver::new(&framework).unwrap();
+    /// assert_eq!(vec![vec![false, false]], solver.extensions(Semantics::Grounded));
+    /// assert_eq!(3, solver.extensions(Semantics::Complete).len());
+    /// ```
+    ///
+    /// [`all_extensions`]: trait.SemanticsSolver.html#tymethod.all_extensions
+    pub fn extensions(&self, semantics: Semantics) -> Vec<Vec<bool>> {
+        self.extension_masks(semantics)
+            .iter()
+            .map(|e| {
+                (0..self.framework.argument_set().len())
+                    .map(|i| e & (1 << i) != 0)
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl<T> SemanticsSolver<T> for NaiveSolver<'_, T>
+where
+    T: LabelType,
+{
+    fn framework(&self) -> &AAFramework<T> {
+        self.framework
+    }
+
+    fn all_extensions(&self, semantics: Semantics) -> Result<Vec<Vec<bool>>> {
+        Ok(self.extensions(semantics))
+    }
+}
+
+fn members(set: u64) -> impl Iterator<Item = usize> {
+    (0..MAX_ARGUMENTS).filter(move |i| set & (1 << i) != 0)
+}
+
+/// Keeps the sets whose key is maximal w.r.t. set inclusion.
+fn maximal_sets<F>(sets: Vec<u64>, key: F) -> Vec<u64>
+where
+    F: Fn(u64) -> u64,
+{
+    let keys = sets.iter().map(|s| key(*s)).collect::<Vec<u64>>();
+    sets.iter()
+        .enumerate()
+        .filter(|(i, _)| {
+            !keys
+                .iter()
+                .any(|k| *k != keys[*i] && k & keys[*i] == keys[*i])
+        })
+        .map(|(_, s)| *s)
+        .collect()
+}
+
+/// Decides the acceptance of an argument, dispatching the query to the appropriate engine.
+///
+/// The grounded semantics, and the skeptical acceptance under the complete semantics, are decided by the grounded solver for frameworks of any size.
+/// The other queries are decided by the naive solver.
+pub(crate) fn is_accepted<T>(
+    framework: &AAFramework<T>,
+    arg: &T,
+    semantics: Semantics,
+    skeptical: bool,
+) -> Result<bool>
+where
+    T: LabelType,
+{
+    if skeptical && semantics == Semantics::Complete {
+        return GroundedSolver::new(framework).is_skeptically_accepted(arg, semantics);
+    }
+    let solver = default_solver(framework, semantics)?;
+    if skeptical {
+        solver.is_skeptically_accepted(arg, semantics)
+    } else {
+        solver.is_credulously_accepted(arg, semantics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AspartixReader;
+
+    fn read_af(s: &str) -> AAFramework<String> {
+        AspartixReader::default().read(&mut s.as_bytes()).unwrap()
+    }
+
+    fn labelled_extensions(af: &AAFramework<String>, semantics: Semantics) -> Vec<String> {
+        let solver = NaiveSolver::new(af).unwrap();
+        let mut extensions = solver
+            .extensions(semantics)
+            .iter()
+            .map(|e| {
+                af.argument_set()
+                    .iter()
+                    .filter(|a| e[a.id()])
+                    .map(|a| a.label().to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            })
+            .collect::<Vec<String>>();
+        extensions.sort();
+        extensions
+    }
+
+    /// a and b attack each other, b attacks c, c attacks d, and e attacks itself and d.
+    const INSTANCE: &str = "arg(a).\narg(b).\narg(c).\narg(d).\narg(e).\natt(a,b).\natt(b,a).\natt(b,c).\natt(c,d).\natt(e,e).\natt(e,d).\n";
+
+    /// a and b attack each other and both attack c, which attacks and is attacked by d.
+    const IDEAL_INSTANCE: &str = "arg(a).\narg(b).\narg(c).\narg(d).\natt(a,b).\natt(b,a).\natt(a,c).\natt(b,c).\natt(c,d).\natt(d,c).\n";
+
+    #[test]
+    fn test_extensions() {
+        let af = read_af(INSTANCE);
+        assert_eq!(vec![""], labelled_extensions(&af, Semantics::Grounded));
+        assert_eq!(
+            vec!["", "a,c", "b"],
+            labelled_extensions(&af, Semantics::Complete)
+        );
+        assert_eq!(
+            vec!["a,c", "b"],
+            labelled_extensions(&af, Semantics::Preferred)
+        );
+        assert!(labelled_extensions(&af, Semantics::Stable).is_empty());
+        assert_eq!(vec!["a,c"], labelled_extensions(&af, Semantics::SemiStable));
+        assert_eq!(
+            vec!["a,c", "b,d"],
+            labelled_extensions(&af, Semantics::Stage)
+        );
+        assert_eq!(vec![""], labelled_extensions(&af, Semantics::Ideal));
+    }
+
+    #[test]
+    fn test_extensions_ideal_not_grounded() {
+        let af = read_af(IDEAL_INSTANCE);
+        assert_eq!(vec![""], labelled_extensions(&af, Semantics::Grounded));
+        assert_eq!(vec!["d"], labelled_extensions(&af, Semantics::Ideal));
+        assert_eq!(
+            vec!["a,d", "b,d"],
+            labelled_extensions(&af, Semantics::Stable)
+        );
+    }
+
+    #[test]
+    fn test_grounded_extension() {
+        let af = read_af("arg(a).\narg(b).\narg(c).\narg(d).\natt(a,b).\natt(b,c).\natt(d,d).\n");
+        assert_eq!(vec![true, false, true, false], af.least_fixpoint());
+    }
+
+    #[test]
+    fn test_grounded_solver_unsupported() {
+        let af = read_af(INSTANCE);
+        let solver = GroundedSolver::new(&af);
+        assert_eq!(
+            "the grounded solver cannot compute all the extensions under the PR semantics",
+            solver
+                .all_extensions(Semantics::Preferred)
+                .unwrap_err()
+                .to_string()
+        );
+        assert!(solver.one_extension(Semantics::Stable).is_err());
+        assert!(solver
+            .is_credulously_accepted(&"a".to_string(), Semantics::Complete)
+            .is_err());
+        assert_eq!(
+            Some(vec![false; 5]),
+            solver.one_extension(Semantics::Complete).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_naive_solver_trait_methods() {
+        let af = read_af(IDEAL_INSTANCE);
+        let solver = NaiveSolver::new(&af).unwrap();
+        assert_eq!(2, solver.count_extensions(Semantics::Preferred).unwrap());
+        assert_eq!(
+            Some(vec![true, false, false, true]),
+            solver.one_extension(Semantics::Stable).unwrap()
+        );
+        assert!(solver
+            .is_skeptically_accepted(&"d".to_string(), Semantics::Stable)
+            .unwrap());
+        assert!(solver
+            .is_credulously_accepted(&"e".to_string(), Semantics::Stable)
+            .is_err());
+    }
+
+    #[test]
+    fn test_is_accepted() {
+        let af = read_af(IDEAL_INSTANCE);
+        let d = "d".to_string();
+        assert!(!is_accepted(&af, &d, Semantics::Grounded, false).unwrap());
+        assert!(!is_accepted(&af, &d, Semantics::Complete, true).unwrap());
+        assert!(is_accepted(&af, &d, Semantics::Preferred, true).unwrap());
+        assert!(is_accepted(&af, &"a".to_string(), Semantics::Stable, false).unwrap());
+        assert!(!is_accepted(&af, &"a".to_string(), Semantics::Stable, true).unwrap());
+        assert!(is_accepted(&af, &"e".to_string(), Semantics::Stable, true).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! A module computing structural statistics of frameworks.
+//!
+//! The degrees of the arguments are given by [`AAFramework::degree_stats`], which returns a [`DegreeStats`] object.
+//! The in-degree of an argument is its number of attackers, and its out-degree is the number of arguments it attacks.
+//!
+//! [`AAFramework::degree_stats`]: ../struct.AAFramework.html#method.degree_stats
+//! [`DegreeStats`]: struct.DegreeStats.html
+
+/// The degrees of the arguments of a framework.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{ArgumentSet, AAFramework};
+/// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+/// framework.new_attack(&"a", &"b").unwrap();
+/// framework.new_attack(&"a", &"c").unwrap();
+/// let stats = framework.degree_stats();
+/// assert_eq!(&[0, 1, 1], stats.in_degrees());
+/// assert_eq!(&[2, 0, 0], stats.out_degrees());
+/// assert_eq!(vec![1, 2], stats.in_degree_histogram());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct DegreeStats {
+    in_degrees: Vec<usize>,
+    out_degrees: Vec<usize>,
+}
+
+impl DegreeStats {
+    pub(crate) fn new(n_arguments: usize, attacks: &[(usize, usize)]) -> Self {
+        let mut in_degrees = vec![0; n_arguments];
+        let mut out_degrees = vec![0; n_arguments];
+        for (attacker, attacked) in attacks {
+            out_degrees[*attacker] += 1;
+            in_degrees[*attacked] += 1;
+        }
+        DegreeStats {
+            in_degrees,
+            out_degrees,
+        }
+    }
+
+    /// Returns the in-degrees of the arguments, indexed by their ids.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// assert_eq!(&[0, 1], framework.degree_stats().in_degrees());
+    /// ```
+    pub fn in_degrees(&self) -> &[usize] {
+        &self.in_degrees
+    }
+
+    /// Returns the out-degrees of the arguments, indexed by their ids.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// assert_eq!(&[1, 0], framework.degree_stats().out_degrees());
+    /// ```
+    pub fn out_degrees(&self) -> &[usize] {
+        &self.out_degrees
+    }
+
+    /// Returns the highest in-degree, or 0 if the framework has no argument.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// framework.new_attack(&"b", &"b").unwrap();
+    /// assert_eq!(2, framework.degree_stats().max_in_degree());
+    /// ```
+    pub fn max_in_degree(&self) -> usize {
+        self.in_degrees.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Returns the highest out-degree, or 0 if the framework has no argument.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// framework.new_attack(&"a", &"a").unwrap();
+    /// assert_eq!(2, framework.degree_stats().max_out_degree());
+    /// ```
+    pub fn max_out_degree(&self) -> usize {
+        self.out_degrees.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Returns the mean degree of the arguments, which is the same for in-degrees and out-degrees, or 0 if the framework has no argument.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// assert_eq!(0.5, framework.degree_stats().mean_degree());
+    /// ```
+    pub fn mean_degree(&self) -> f64 {
+        if self.in_degrees.is_empty() {
+            0.
+        } else {
+            self.in_degrees.iter().sum::<usize>() as f64 / self.in_degrees.len() as f64
+        }
+    }
+
+    /// Returns the number of arguments of each in-degree, indexed by the in-degree.
+    ///
+    /// See [`histogram`](fn.histogram.html).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// assert_eq!(vec![1, 1], framework.degree_stats().in_degree_histogram());
+    /// ```
+    pub fn in_degree_histogram(&self) -> Vec<usize> {
+        histogram(&self.in_degrees)
+    }
+
+    /// Returns the number of arguments of each out-degree, indexed by the out-degree.
+    ///
+    /// See [`histogram`](fn.histogram.html).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::{ArgumentSet, AAFramework};
+    /// let mut framework = AAFramework::new(ArgumentSet::new(vec!["a", "b", "c"]));
+    /// framework.new_attack(&"a", &"b").unwrap();
+    /// framework.new_attack(&"a", &"c").unwrap();
+    /// assert_eq!(vec![2, 0, 1], framework.degree_stats().out_degree_histogram());
+    /// ```
+    pub fn out_degree_histogram(&self) -> Vec<usize> {
+        histogram(&self.out_degrees)
+    }
+}
+
+/// Counts the occurrences of each value, returning a vector whose item at index `i` is the number of occurrences of `i`.
+///
+/// The vector ends with the highest value, and is empty if there is no value.
+///
+/// # Arguments
+/// * `values` - the values
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::stats;
+/// assert_eq!(vec![1, 0, 2], stats::histogram(&[2, 0, 2]));
+/// assert!(stats::histogram(&[]).is_empty());
+/// ```
+pub fn histogram(values: &[usize]) -> Vec<usize> {
+    let mut counts = vec![0; values.iter().max().map_or(0, |m| m + 1)];
+    values.iter().for_each(|v| counts[*v] += 1);
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_degree_stats() {
+        let stats = DegreeStats::new(4, &[(0, 1), (0, 2), (1, 2), (2, 2)]);
+        assert_eq!(&[0, 1, 3, 0], stats.in_degrees());
+        assert_eq!(&[2, 1, 1, 0], stats.out_degrees());
+        assert_eq!(3, stats.max_in_degree());
+        assert_eq!(2, stats.max_out_degree());
+        assert_eq!(1., stats.mean_degree());
+        assert_eq!(vec![2, 1, 0, 1], stats.in_degree_histogram());
+        assert_eq!(vec![1, 2, 1], stats.out_degree_histogram());
+    }
+
+    #[test]
+    fn test_degree_stats_empty() {
+        let stats = DegreeStats::new(0, &[]);
+        assert_eq!(0, stats.max_in_degree());
+        assert_eq!(0, stats.max_out_degree());
+        assert_eq!(0., stats.mean_degree());
+        assert!(stats.in_degree_histogram().is_empty());
+    }
+}
@@ -0,0 +1,776 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+//! Generators of random frameworks and dynamics, for property-based tests.
+//!
+//! This module is available with the `testing` feature.
+//! The generators draw their values from a [`TestRng`], a small seeded generator, so that the failing tests can be replayed from their seed.
+//! They are the ones used by the `fuzz-solver` subcommand of the dynamics wrapper, so that solver authors can test their own code against the same distributions.
+//!
+//! ```
+//! # use crusti_arg::testing::{self, TestRng};
+//! let mut rng = TestRng::new(42);
+//! let framework = testing::arbitrary_framework(&mut rng, 5, 0.3);
+//! let modifications = testing::arbitrary_modifications(&mut rng, &framework, 10);
+//! assert_eq!(5, framework.argument_set().len());
+//! assert_eq!(10, modifications.len());
+//! ```
+//!
+//! Besides uniformly random attacks, the frameworks may follow a [`Structure`] (attack chains, even or odd cycles, layered graphs) known to make hard instances for the incremental reasoning.
+//!
+//! [`Structure`]: enum.Structure.html
+//! [`TestRng`]: struct.TestRng.html
+use std::{collections::HashSet, fmt::Display, str::FromStr};
+
+use anyhow::{anyhow, Result};
+
+use crate::{dynamics::Modification, AAFramework, ArgumentSet};
+
+/// A pseudo-random number generator (xorshift64*), seeded so that the generated values can be replayed.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::testing::TestRng;
+/// let mut rng = TestRng::new(7);
+/// let values = (0..3).map(|_| rng.below(10)).collect::<Vec<usize>>();
+/// let mut replay = TestRng::new(7);
+/// assert_eq!(values, (0..3).map(|_| replay.below(10)).collect::<Vec<usize>>());
+/// ```
+#[derive(Clone, Debug)]
+pub struct TestRng(u64);
+
+impl TestRng {
+    /// Builds a new generator from a seed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::testing::TestRng;
+    /// let mut rng = TestRng::new(0);
+    /// assert_ne!(0, rng.next_u64());
+    /// ```
+    pub fn new(seed: u64) -> Self {
+        TestRng((seed ^ 0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    /// Returns the next 64-bit value of the generator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::testing::TestRng;
+    /// let mut rng = TestRng::new(0);
+    /// assert_ne!(rng.next_u64(), rng.next_u64());
+    /// ```
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns an integer in `0..n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::testing::TestRng;
+    /// let mut rng = TestRng::new(0);
+    /// assert!((0..100).all(|_| rng.below(3) < 3));
+    /// ```
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "cannot draw a value below 0");
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Returns a float in `[0, 1)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::testing::TestRng;
+    /// let mut rng = TestRng::new(0);
+    /// assert!((0..100).map(|_| rng.next_f64()).all(|f| (0.0..1.0).contains(&f)));
+    /// ```
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Generates a framework of `n_args` arguments, labelled `a0`, `a1`, and so on, in which each attack (including self-attacks) is present with probability `density`.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::testing::{self, TestRng};
+/// let mut rng = TestRng::new(0);
+/// assert_eq!(16, testing::arbitrary_framework(&mut rng, 4, 1.0).iter_attacks().count());
+/// assert_eq!(0, testing::arbitrary_framework(&mut rng, 4, 0.0).iter_attacks().count());
+/// ```
+pub fn arbitrary_framework(rng: &mut TestRng, n_args: usize, density: f64) -> AAFramework<String> {
+    FrameworkGenerator::default().framework(rng, n_args, density)
+}
+
+/// Generates a sequence of `len` modifications which can be applied in order to the framework.
+///
+/// Each modification is drawn among the kinds of modifications applicable to the current state of the framework (the argument additions being always applicable), then among the modifications of this kind.
+/// New arguments are labelled like the ones of [`arbitrary_framework`], using the first free labels.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::testing::{self, TestRng};
+/// # use crusti_arg::dynamics::Modification;
+/// let mut rng = TestRng::new(0);
+/// let framework = testing::arbitrary_framework(&mut rng, 0, 0.5);
+/// let modifications = testing::arbitrary_modifications(&mut rng, &framework, 3);
+/// assert_eq!(Modification::AddArgument("a0".to_string()), modifications[0]);
+/// ```
+///
+/// [`arbitrary_framework`]: fn.arbitrary_framework.html
+pub fn arbitrary_modifications(
+    rng: &mut TestRng,
+    framework: &AAFramework<String>,
+    len: usize,
+) -> Vec<Modification<String>> {
+    FrameworkGenerator::default().modifications(rng, framework, len)
+}
+
+/// The shape of the attack graphs produced by a [`FrameworkGenerator`].
+///
+/// Apart from [`Structure::Random`], the attacks of the structure are always present, and the other attacks are noise drawn with the density given to the generator.
+/// The structures involve long propagations of the acceptance statuses, making hard instances for the incremental reasoning (including the grounded one).
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::testing::Structure;
+/// assert_eq!(Structure::Chain, "chain".parse::<Structure>().unwrap());
+/// assert_eq!("odd-cycle", Structure::OddCycle.to_string());
+/// ```
+///
+/// [`FrameworkGenerator`]: struct.FrameworkGenerator.html
+/// [`Structure::Random`]: enum.Structure.html#variant.Random
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Structure {
+    /// Each attack is drawn independently.
+    #[default]
+    Random,
+    /// The arguments form a chain in which each argument attacks the next one.
+    Chain,
+    /// The arguments form a cycle of even length; an argument left out of the cycle is attacked by its first argument.
+    EvenCycle,
+    /// The arguments form a cycle of odd length; an argument left out of the cycle is attacked by its first argument.
+    OddCycle,
+    /// The arguments are split into layers of about the square root of their number, each argument attacking all the ones of the next layer.
+    Layered,
+}
+
+impl FromStr for Structure {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "random" => Ok(Structure::Random),
+            "chain" => Ok(Structure::Chain),
+            "even-cycle" => Ok(Structure::EvenCycle),
+            "odd-cycle" => Ok(Structure::OddCycle),
+            "layered" => Ok(Structure::Layered),
+            _ => Err(anyhow!(r#"unknown structure "{}""#, s)),
+        }
+    }
+}
+
+impl Display for Structure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Structure::Random => "random",
+            Structure::Chain => "chain",
+            Structure::EvenCycle => "even-cycle",
+            Structure::OddCycle => "odd-cycle",
+            Structure::Layered => "layered",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Structure {
+    /// The names of the structures, as accepted by their `FromStr` implementation.
+    pub const NAMES: [&'static str; 5] = ["random", "chain", "even-cycle", "odd-cycle", "layered"];
+
+    /// Returns the attacks of the structure among `n_args` arguments, as pairs of argument indices.
+    fn attacks(&self, n_args: usize) -> Vec<(usize, usize)> {
+        let cycle = |len: usize| {
+            let mut attacks = (0..len).map(|i| (i, (i + 1) % len)).collect::<Vec<_>>();
+            if len > 0 && len < n_args {
+                attacks.push((0, len));
+            }
+            attacks
+        };
+        match self {
+            Structure::Random => vec![],
+            Structure::Chain => (1..n_args).map(|i| (i - 1, i)).collect(),
+            Structure::EvenCycle => cycle(n_args - n_args % 2),
+            Structure::OddCycle if n_args == 0 => vec![],
+            Structure::OddCycle => cycle(n_args - 1 + n_args % 2),
+            Structure::Layered => {
+                let width = (1..).find(|w| w * w >= n_args).unwrap().max(1);
+                (0..n_args)
+                    .flat_map(|i| {
+                        let next_layer = (i / width + 1) * width;
+                        (next_layer..n_args.min(next_layer + width)).map(move |j| (i, j))
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// A generator of frameworks and modifications with custom labels and constraints.
+///
+/// By default, the arguments are labelled `a0`, `a1`, and so on, the attacks are random, the number of arguments is not bounded, and any argument may be removed.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::testing::{FrameworkGenerator, TestRng};
+/// # use crusti_arg::dynamics::Modification;
+/// let mut generator = FrameworkGenerator::default();
+/// generator.set_labels(|i| format!("arg_{}", i));
+/// generator.set_max_arguments(3);
+/// generator.keep_argument("arg_0");
+/// let mut rng = TestRng::new(0);
+/// let framework = generator.framework(&mut rng, 3, 0.5);
+/// let modifications = generator.modifications(&mut rng, &framework, 10);
+/// assert_eq!(10, modifications.len());
+/// assert!(!modifications.contains(&Modification::RemoveArgument("arg_0".to_string())));
+/// ```
+#[derive(Clone, Debug)]
+pub struct FrameworkGenerator {
+    labels: fn(usize) -> String,
+    structure: Structure,
+    max_arguments: Option<usize>,
+    kept_arguments: HashSet<String>,
+    focus: Option<(String, f64)>,
+}
+
+impl Default for FrameworkGenerator {
+    fn default() -> Self {
+        FrameworkGenerator {
+            labels: |i| format!("a{}", i),
+            structure: Structure::default(),
+            max_arguments: None,
+            kept_arguments: HashSet::new(),
+            focus: None,
+        }
+    }
+}
+
+impl FrameworkGenerator {
+    /// Sets the function giving the label of the argument of index `i`.
+    ///
+    /// The function must give distinct labels for distinct indices.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::testing::{FrameworkGenerator, TestRng};
+    /// let mut generator = FrameworkGenerator::default();
+    /// generator.set_labels(|i| format!("x{}", i));
+    /// let framework = generator.framework(&mut TestRng::new(0), 1, 0.0);
+    /// assert_eq!("x0", framework.argument_set().get_argument_by_id(0).label());
+    /// ```
+    pub fn set_labels(&mut self, labels: fn(usize) -> String) {
+        self.labels = labels;
+    }
+
+    /// Sets the structure of the generated frameworks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::testing::{FrameworkGenerator, Structure, TestRng};
+    /// let mut generator = FrameworkGenerator::default();
+    /// generator.set_structure(Structure::Chain);
+    /// let framework = generator.framework(&mut TestRng::new(0), 4, 0.0);
+    /// assert_eq!(3, framework.iter_attacks().count());
+    /// ```
+    pub fn set_structure(&mut self, structure: Structure) {
+        self.structure = structure;
+    }
+
+    /// Sets the maximal number of arguments of the frameworks along the generated modifications.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::testing::{FrameworkGenerator, TestRng};
+    /// # use crusti_arg::dynamics::Modification;
+    /// let mut generator = FrameworkGenerator::default();
+    /// generator.set_max_arguments(0);
+    /// let framework = generator.framework(&mut TestRng::new(0), 0, 0.0);
+    /// assert!(generator.modifications(&mut TestRng::new(0), &framework, 5).is_empty());
+    /// ```
+    pub fn set_max_arguments(&mut self, max_arguments: usize) {
+        self.max_arguments = Some(max_arguments);
+    }
+
+    /// Prevents the generated modifications from removing an argument (e.g. the one queried by a problem).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::testing::{FrameworkGenerator, TestRng};
+    /// # use crusti_arg::dynamics::Modification;
+    /// let mut generator = FrameworkGenerator::default();
+    /// generator.keep_argument("a0");
+    /// let framework = generator.framework(&mut TestRng::new(0), 1, 0.0);
+    /// let modifications = generator.modifications(&mut TestRng::new(0), &framework, 20);
+    /// assert!(!modifications.contains(&Modification::RemoveArgument("a0".to_string())));
+    /// ```
+    pub fn keep_argument(&mut self, label: &str) {
+        self.kept_arguments.insert(label.to_string());
+    }
+
+    /// Biases the generated modifications toward the neighborhood of an argument (e.g. the one queried by a problem), which is also kept.
+    ///
+    /// With probability `bias`, a modification is drawn among the ones removing an argument of the neighborhood or changing the attackers of one of its arguments, if any.
+    /// The neighborhood of an argument is made of itself and of the arguments reaching it through at most two attacks, whose changes are the most likely to change its acceptance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::testing::{FrameworkGenerator, TestRng};
+    /// # use crusti_arg::dynamics::Modification;
+    /// let mut generator = FrameworkGenerator::default();
+    /// generator.set_focus("a0", 1.0);
+    /// let mut rng = TestRng::new(0);
+    /// let framework = generator.framework(&mut rng, 10, 0.0);
+    /// // without attacks, the neighborhood of a0 is a0 itself, which can only get new attackers
+    /// let modifications = generator.modifications(&mut rng, &framework, 1);
+    /// assert!(matches!(&modifications[0], Modification::AddAttack(_, b) if b == "a0"));
+    /// ```
+    pub fn set_focus(&mut self, label: &str, bias: f64) {
+        self.keep_argument(label);
+        self.focus = Some((label.to_string(), bias));
+    }
+
+    /// Generates a framework of `n_args` arguments in which each attack (including self-attacks) is present with probability `density`.
+    ///
+    /// The attacks of the [`Structure`] of the generator are added regardless of the density.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::testing::{FrameworkGenerator, TestRng};
+    /// let framework = FrameworkGenerator::default().framework(&mut TestRng::new(0), 3, 1.0);
+    /// assert_eq!(3, framework.argument_set().len());
+    /// assert_eq!(9, framework.iter_attacks().count());
+    /// ```
+    ///
+    /// [`Structure`]: enum.Structure.html
+    pub fn framework(&self, rng: &mut TestRng, n_args: usize, density: f64) -> AAFramework<String> {
+        let mut framework =
+            AAFramework::new(ArgumentSet::new((0..n_args).map(self.labels).collect()));
+        let structure_attacks = self
+            .structure
+            .attacks(n_args)
+            .into_iter()
+            .collect::<HashSet<(usize, usize)>>();
+        for i in 0..n_args {
+            for j in 0..n_args {
+                if structure_attacks.contains(&(i, j)) || rng.next_f64() < density {
+                    framework.new_attack_by_ids(i, j).unwrap();
+                }
+            }
+        }
+        framework
+    }
+
+    /// Generates a sequence of at most `len` modifications which can be applied in order to the framework.
+    ///
+    /// Each modification is drawn among the kinds of modifications applicable to the current state of the framework, then among the modifications of this kind.
+    /// Less than `len` modifications are returned only if no modification is applicable at some point.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use crusti_arg::testing::{FrameworkGenerator, TestRng};
+    /// let generator = FrameworkGenerator::default();
+    /// let mut rng = TestRng::new(0);
+    /// let framework = generator.framework(&mut rng, 4, 0.2);
+    /// assert_eq!(8, generator.modifications(&mut rng, &framework, 8).len());
+    /// ```
+    pub fn modifications(
+        &self,
+        rng: &mut TestRng,
+        framework: &AAFramework<String>,
+        len: usize,
+    ) -> Vec<Modification<String>> {
+        let mut state = GeneratorState::new(framework);
+        let mut next_label = framework.argument_set().len();
+        let mut modifications = Vec::with_capacity(len);
+        for _ in 0..len {
+            let focused = match &self.focus {
+                Some((label, bias)) if rng.next_f64() < *bias => {
+                    self.focused_modification(rng, &state, label)
+                }
+                _ => None,
+            };
+            let modification = match focused {
+                Some(m) => m,
+                None => match self.random_modification(rng, &state, &mut next_label) {
+                    Some(m) => m,
+                    None => break,
+                },
+            };
+            state.apply(&modification);
+            modifications.push(modification);
+        }
+        modifications
+    }
+
+    fn random_modification(
+        &self,
+        rng: &mut TestRng,
+        state: &GeneratorState,
+        next_label: &mut usize,
+    ) -> Option<Modification<String>> {
+        let mut kinds = vec![];
+        if !matches!(self.max_arguments, Some(m) if state.arguments.len() >= m) {
+            kinds.push(ModificationKind::AddArgument);
+        }
+        let removable = state
+            .arguments
+            .iter()
+            .filter(|a| !self.kept_arguments.contains(*a))
+            .cloned()
+            .collect::<Vec<String>>();
+        if !removable.is_empty() {
+            kinds.push(ModificationKind::RemoveArgument);
+        }
+        if state.attacks.len() < state.arguments.len() * state.arguments.len() {
+            kinds.push(ModificationKind::AddAttack);
+        }
+        if !state.attacks.is_empty() {
+            kinds.push(ModificationKind::RemoveAttack);
+        }
+        if kinds.is_empty() {
+            return None;
+        }
+        let modification = match kinds[rng.below(kinds.len())] {
+            ModificationKind::AddArgument => {
+                let label = loop {
+                    let label = (self.labels)(*next_label);
+                    *next_label += 1;
+                    if !state.arguments.contains(&label) {
+                        break label;
+                    }
+                };
+                Modification::AddArgument(label)
+            }
+            ModificationKind::RemoveArgument => {
+                Modification::RemoveArgument(removable[rng.below(removable.len())].clone())
+            }
+            ModificationKind::AddAttack => {
+                let missing = state.missing_attacks(|_| true);
+                let (a, b) = missing[rng.below(missing.len())];
+                Modification::AddAttack(a.clone(), b.clone())
+            }
+            ModificationKind::RemoveAttack => {
+                let (a, b) = state.attacks[rng.below(state.attacks.len())].clone();
+                Modification::RemoveAttack(a, b)
+            }
+        };
+        Some(modification)
+    }
+
+    /// Draws a modification changing the attackers of an argument of the neighborhood of the focus, or removing such an argument.
+    ///
+    /// Returns `None` if no such modification is applicable.
+    fn focused_modification(
+        &self,
+        rng: &mut TestRng,
+        state: &GeneratorState,
+        focus: &str,
+    ) -> Option<Modification<String>> {
+        let neighborhood = state.neighborhood(focus);
+        let removable = state
+            .arguments
+            .iter()
+            .filter(|a| neighborhood.contains(a.as_str()) && !self.kept_arguments.contains(*a))
+            .map(|a| Modification::RemoveArgument(a.clone()))
+            .collect::<Vec<Modification<String>>>();
+        let addable = state
+            .missing_attacks(|b| neighborhood.contains(b))
+            .into_iter()
+            .map(|(a, b)| Modification::AddAttack(a.clone(), b.clone()))
+            .collect::<Vec<Modification<String>>>();
+        let deletable = state
+            .attacks
+            .iter()
+            .filter(|(_, b)| neighborhood.contains(b.as_str()))
+            .map(|(a, b)| Modification::RemoveAttack(a.clone(), b.clone()))
+            .collect::<Vec<Modification<String>>>();
+        let mut candidates = vec![removable, addable, deletable];
+        candidates.retain(|kind| !kind.is_empty());
+        if candidates.is_empty() {
+            return None;
+        }
+        let kind = &candidates[rng.below(candidates.len())];
+        Some(kind[rng.below(kind.len())].clone())
+    }
+}
+
+/// The maximal number of attacks between an argument of the neighborhood of the focus and the focus itself.
+const FOCUS_DISTANCE: usize = 2;
+
+#[derive(Clone, Copy)]
+enum ModificationKind {
+    AddArgument,
+    RemoveArgument,
+    AddAttack,
+    RemoveAttack,
+}
+
+/// The state of a framework along the generated modifications.
+struct GeneratorState {
+    arguments: Vec<String>,
+    attacks: Vec<(String, String)>,
+}
+
+impl GeneratorState {
+    fn new(framework: &AAFramework<String>) -> Self {
+        GeneratorState {
+            arguments: framework
+                .argument_set()
+                .iter()
+                .map(|a| a.label().clone())
+                .collect(),
+            attacks: framework
+                .iter_attacks()
+                .map(|att| {
+                    (
+                        att.attacker().label().clone(),
+                        att.attacked().label().clone(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the missing attacks (including self-attacks) whose attacked argument satisfies a predicate.
+    fn missing_attacks<F: Fn(&str) -> bool>(&self, attacked: F) -> Vec<(&String, &String)> {
+        self.arguments
+            .iter()
+            .flat_map(|a| self.arguments.iter().map(move |b| (a, b)))
+            .filter(|(a, b)| {
+                attacked(b.as_str()) && !self.attacks.contains(&((*a).clone(), (*b).clone()))
+            })
+            .collect()
+    }
+
+    /// Returns the argument and the ones reaching it through at most `FOCUS_DISTANCE` attacks.
+    fn neighborhood(&self, label: &str) -> HashSet<&str> {
+        let mut neighborhood = HashSet::new();
+        let mut frontier = match self.arguments.iter().find(|a| *a == label) {
+            Some(a) => vec![a.as_str()],
+            None => return neighborhood,
+        };
+        neighborhood.insert(frontier[0]);
+        for _ in 0..FOCUS_DISTANCE {
+            frontier = self
+                .attacks
+                .iter()
+                .filter(|(_, to)| frontier.contains(&to.as_str()))
+                .map(|(from, _)| from.as_str())
+                .filter(|from| neighborhood.insert(from))
+                .collect();
+        }
+        neighborhood
+    }
+
+    fn apply(&mut self, modification: &Modification<String>) {
+        match modification {
+            Modification::AddArgument(a) => self.arguments.push(a.clone()),
+            Modification::RemoveArgument(a) => {
+                self.arguments.retain(|l| l != a);
+                self.attacks.retain(|(from, to)| from != a && to != a);
+            }
+            Modification::AddAttack(a, b) => self.attacks.push((a.clone(), b.clone())),
+            Modification::RemoveAttack(a, b) => {
+                self.attacks.retain(|(from, to)| from != a || to != b)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AspartixReader;
+
+    fn check_applicable(framework: &AAFramework<String>, modifications: &[Modification<String>]) {
+        let mut state = GeneratorState::new(framework);
+        for m in modifications {
+            match m {
+                Modification::AddArgument(a) => assert!(!state.arguments.contains(a)),
+                Modification::RemoveArgument(a) => assert!(state.arguments.contains(a)),
+                Modification::AddAttack(a, b) => {
+                    assert!(state.arguments.contains(a) && state.arguments.contains(b));
+                    assert!(!state.attacks.contains(&(a.clone(), b.clone())));
+                }
+                Modification::RemoveAttack(a, b) => {
+                    assert!(state.attacks.contains(&(a.clone(), b.clone())))
+                }
+            }
+            state.apply(m);
+        }
+    }
+
+    #[test]
+    fn test_modifications_applicable() {
+        let mut rng = TestRng::new(1);
+        for _ in 0..200 {
+            let n_args = rng.below(6);
+            let density = rng.next_f64();
+            let framework = arbitrary_framework(&mut rng, n_args, density);
+            let modifications = arbitrary_modifications(&mut rng, &framework, 20);
+            assert_eq!(20, modifications.len());
+            check_applicable(&framework, &modifications);
+        }
+    }
+
+    #[test]
+    fn test_modifications_constraints() {
+        let mut generator = FrameworkGenerator::default();
+        generator.set_max_arguments(4);
+        generator.keep_argument("a1");
+        let mut rng = TestRng::new(2);
+        for _ in 0..100 {
+            let framework = generator.framework(&mut rng, 2, 0.5);
+            let modifications = generator.modifications(&mut rng, &framework, 20);
+            check_applicable(&framework, &modifications);
+            let mut state = GeneratorState::new(&framework);
+            for m in modifications.iter() {
+                state.apply(m);
+                assert!(state.arguments.len() <= 4);
+                assert!(state.arguments.contains(&"a1".to_string()));
+            }
+        }
+    }
+
+    fn structure_attacks(structure: Structure, n_args: usize) -> Vec<(usize, usize)> {
+        let mut generator = FrameworkGenerator::default();
+        generator.set_structure(structure);
+        let framework = generator.framework(&mut TestRng::new(0), n_args, 0.0);
+        let mut attacks = framework
+            .iter_attacks()
+            .map(|att| (att.attacker().id(), att.attacked().id()))
+            .collect::<Vec<(usize, usize)>>();
+        attacks.sort_unstable();
+        attacks
+    }
+
+    #[test]
+    fn test_structures() {
+        assert_eq!(vec![(0, 1), (1, 2)], structure_attacks(Structure::Chain, 3));
+        assert_eq!(
+            vec![(0, 1), (0, 4), (1, 2), (2, 3), (3, 0)],
+            structure_attacks(Structure::EvenCycle, 5)
+        );
+        assert_eq!(
+            vec![(0, 1), (0, 3), (1, 2), (2, 0)],
+            structure_attacks(Structure::OddCycle, 4)
+        );
+        assert_eq!(vec![(0, 0)], structure_attacks(Structure::OddCycle, 1));
+        assert_eq!(
+            vec![(0, 3), (0, 4), (1, 3), (1, 4), (2, 3), (2, 4)],
+            structure_attacks(Structure::Layered, 5)
+        );
+        for structure in Structure::NAMES.iter() {
+            let structure = structure.parse::<Structure>().unwrap();
+            assert_eq!(structure, structure.to_string().parse().unwrap());
+            assert!(structure_attacks(structure, 0).is_empty());
+        }
+        assert!("cycle".parse::<Structure>().is_err());
+    }
+
+    #[test]
+    fn test_structure_with_noise() {
+        let mut generator = FrameworkGenerator::default();
+        generator.set_structure(Structure::Chain);
+        let framework = generator.framework(&mut TestRng::new(4), 10, 0.2);
+        let attacks = framework
+            .iter_attacks()
+            .map(|att| (att.attacker().id(), att.attacked().id()))
+            .collect::<HashSet<(usize, usize)>>();
+        assert!(attacks.len() > 9);
+        assert!((1..10).all(|i| attacks.contains(&(i - 1, i))));
+    }
+
+    #[test]
+    fn test_focused_modifications() {
+        let framework = AspartixReader::default()
+            .read(&mut "arg(a0).\narg(a1).\narg(a2).\narg(a3).\narg(a4).\natt(a1,a0).\natt(a2,a1).\natt(a3,a2).\n".as_bytes())
+            .unwrap();
+        let state = GeneratorState::new(&framework);
+        let mut neighborhood = state.neighborhood("a0").into_iter().collect::<Vec<&str>>();
+        neighborhood.sort_unstable();
+        assert_eq!(vec!["a0", "a1", "a2"], neighborhood);
+        let mut generator = FrameworkGenerator::default();
+        generator.set_focus("a0", 1.0);
+        let mut rng = TestRng::new(6);
+        for _ in 0..50 {
+            let modification = generator.modifications(&mut rng, &framework, 1).remove(0);
+            match &modification {
+                Modification::RemoveArgument(a) => assert!(["a1", "a2"].contains(&a.as_str())),
+                Modification::AddAttack(_, b) | Modification::RemoveAttack(_, b) => {
+                    assert!(["a0", "a1", "a2"].contains(&b.as_str()))
+                }
+                Modification::AddArgument(_) => panic!("unexpected {}", modification),
+            }
+        }
+        for _ in 0..20 {
+            let modifications = generator.modifications(&mut rng, &framework, 20);
+            check_applicable(&framework, &modifications);
+            assert!(!modifications.contains(&Modification::RemoveArgument("a0".to_string())));
+        }
+    }
+
+    #[test]
+    fn test_new_labels_are_free() {
+        let framework = arbitrary_framework(&mut TestRng::new(0), 2, 0.0);
+        let mut rng = TestRng::new(3);
+        let modifications = (0..50)
+            .map(|_| arbitrary_modifications(&mut rng, &framework, 10))
+            .collect::<Vec<Vec<Modification<String>>>>();
+        modifications
+            .iter()
+            .for_each(|m| check_applicable(&framework, m));
+        assert!(modifications
+            .iter()
+            .flatten()
+            .any(|m| *m == Modification::AddArgument("a2".to_string())));
+    }
+}
@@ -0,0 +1,221 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! WebAssembly bindings of the library, enabled by the `wasm` feature.
+//!
+//! These bindings give browser-based tools the parsers, the native engines and the checkers of the library through `wasm-bindgen`, without any file access or process spawning.
+//! The `crusti_arg_wasm` crate builds them as a WebAssembly module for the `wasm32-unknown-unknown` target.
+//! Arguments are labeled by JavaScript strings, semantics are given by their acronyms (e.g. `"CO"`), and the errors of the library are thrown as JavaScript `Error`s.
+//!
+//! ```bash
+//! cd local_crates/crusti_arg_wasm-v0.1
+//! wasm-pack build --target web
+//! ```
+//!
+//! ```js
+//! import init, { readAspartix } from "./pkg/crusti_arg_wasm.js";
+//! await init();
+//! const af = readAspartix("arg(a).\narg(b).\natt(a,b).\n");
+//! af.apply("+att(b,a).");
+//! console.log(af.extensions("CO")); // [[], ["a"], ["b"]]
+//! console.log(af.isAdmissible(["a"])); // true
+//! ```
+
+use super::bindings;
+use crate::{dynamics, solutions, AAFramework, AspartixReader, Semantics};
+use js_sys::Array;
+use wasm_bindgen::prelude::*;
+
+fn to_js_err(e: anyhow::Error) -> JsError {
+    JsError::new(&format!("{:#}", e))
+}
+
+fn parse_semantics(semantics: &str) -> Result<Semantics, JsError> {
+    semantics.parse::<Semantics>().map_err(to_js_err)
+}
+
+fn string_array(labels: Vec<String>) -> Array {
+    labels.into_iter().map(JsValue::from).collect()
+}
+
+fn membership(framework: &AAFramework<String>, labels: &[String]) -> Result<Vec<bool>, JsError> {
+    bindings::membership(framework, labels).map_err(to_js_err)
+}
+
+/// An Abstract Argumentation framework labeled by strings, exposed to JavaScript as `AAFramework`.
+#[wasm_bindgen(js_name = AAFramework)]
+pub struct WasmAAFramework {
+    framework: AAFramework<String>,
+}
+
+#[wasm_bindgen(js_class = AAFramework)]
+impl WasmAAFramework {
+    /// Builds a framework without attacks from the labels of its arguments.
+    #[wasm_bindgen(constructor)]
+    pub fn new(arguments: Vec<String>) -> Result<WasmAAFramework, JsError> {
+        let framework = bindings::build(arguments, &[]).map_err(to_js_err)?;
+        Ok(WasmAAFramework { framework })
+    }
+
+    /// Returns the labels of the arguments, in the order of their ids.
+    pub fn arguments(&self) -> Vec<String> {
+        bindings::argument_labels(&self.framework)
+    }
+
+    /// Returns the attacks, as pairs of labels.
+    pub fn attacks(&self) -> Array {
+        bindings::attack_labels(&self.framework)
+            .into_iter()
+            .map(|(from, to)| Array::of2(&from.into(), &to.into()))
+            .collect()
+    }
+
+    /// Adds an attack between two existing arguments.
+    #[wasm_bindgen(js_name = newAttack)]
+    pub fn new_attack(&mut self, attacker: String, attacked: String) -> Result<(), JsError> {
+        self.framework
+            .new_attack(&attacker, &attacked)
+            .map_err(to_js_err)
+    }
+
+    /// Applies a modification given by a line of a dynamics file (e.g. `"+att(a,b)."`).
+    pub fn apply(&mut self, modification: &str) -> Result<(), JsError> {
+        let m = dynamics::read_modification(modification).map_err(to_js_err)?;
+        self.framework = bindings::apply_modification(&self.framework, &m).map_err(to_js_err)?;
+        Ok(())
+    }
+
+    /// Returns all the extensions of a semantics, computed by the native engines.
+    pub fn extensions(&self, semantics: &str) -> Result<Array, JsError> {
+        let extensions = bindings::extensions(&self.framework, parse_semantics(semantics)?)
+            .map_err(to_js_err)?;
+        Ok(extensions.into_iter().map(string_array).collect())
+    }
+
+    /// Returns an extension of a semantics computed by the native engines, or `undefined` if there is no extension.
+    #[wasm_bindgen(js_name = oneExtension)]
+    pub fn one_extension(&self, semantics: &str) -> Result<Option<Vec<String>>, JsError> {
+        bindings::one_extension(&self.framework, parse_semantics(semantics)?).map_err(to_js_err)
+    }
+
+    /// Returns the grounded extension.
+    #[wasm_bindgen(js_name = groundedExtension)]
+    pub fn grounded_extension(&self) -> Vec<String> {
+        bindings::members(&self.framework, &self.framework.least_fixpoint())
+    }
+
+    /// Returns `true` iff a set of arguments is conflict-free.
+    #[wasm_bindgen(js_name = isConflictFree)]
+    pub fn is_conflict_free(&self, extension: Vec<String>) -> Result<bool, JsError> {
+        Ok(self
+            .framework
+            .conflict_free(&membership(&self.framework, &extension)?))
+    }
+
+    /// Returns `true` iff a set of arguments is admissible.
+    #[wasm_bindgen(js_name = isAdmissible)]
+    pub fn is_admissible(&self, extension: Vec<String>) -> Result<bool, JsError> {
+        let set = membership(&self.framework, &extension)?;
+        Ok(bindings::is_admissible(&self.framework, &set))
+    }
+
+    /// Returns `true` iff a set of arguments is an extension of a semantics.
+    #[wasm_bindgen(js_name = isExtension)]
+    pub fn is_extension(&self, extension: Vec<String>, semantics: &str) -> Result<bool, JsError> {
+        let set = membership(&self.framework, &extension)?;
+        bindings::is_extension(&self.framework, &set, parse_semantics(semantics)?)
+            .map_err(to_js_err)
+    }
+
+    /// Returns `true` iff an argument belongs to an extension of a semantics.
+    #[wasm_bindgen(js_name = credulouslyAccepted)]
+    pub fn credulously_accepted(&self, argument: String, semantics: &str) -> Result<bool, JsError> {
+        self.framework
+            .credulously_accepted(&argument, parse_semantics(semantics)?)
+            .map_err(to_js_err)
+    }
+
+    /// Returns `true` iff an argument belongs to all the extensions of a semantics.
+    #[wasm_bindgen(js_name = skepticallyAccepted)]
+    pub fn skeptically_accepted(&self, argument: String, semantics: &str) -> Result<bool, JsError> {
+        self.framework
+            .skeptically_accepted(&argument, parse_semantics(semantics)?)
+            .map_err(to_js_err)
+    }
+
+    /// Returns the labelling induced by a set of arguments, as pairs of a label and a status (`IN`, `OUT` or `UNDEC`).
+    pub fn labelling(&self, extension: Vec<String>) -> Result<Array, JsError> {
+        let labelling = bindings::labelling(&self.framework, &extension).map_err(to_js_err)?;
+        Ok(labelling
+            .into_iter()
+            .map(|(label, status)| Array::of2(&label.into(), &status.into()))
+            .collect())
+    }
+
+    /// Writes the framework using the Aspartix format.
+    #[wasm_bindgen(js_name = toAspartix)]
+    pub fn to_aspartix(&self) -> Result<String, JsError> {
+        bindings::to_aspartix(&self.framework).map_err(to_js_err)
+    }
+
+    /// Writes the framework using the DOT format of Graphviz.
+    #[wasm_bindgen(js_name = toDot)]
+    pub fn to_dot(&self) -> Result<String, JsError> {
+        bindings::to_dot(&self.framework).map_err(to_js_err)
+    }
+
+    /// Returns a summary of the framework, giving its numbers of arguments and attacks.
+    pub fn summary(&self) -> String {
+        self.framework.summary()
+    }
+}
+
+/// Reads a framework given in the Aspartix format.
+#[wasm_bindgen(js_name = readAspartix)]
+pub fn read_aspartix(content: &str) -> Result<WasmAAFramework, JsError> {
+    let framework = AspartixReader::default()
+        .read(&mut content.as_bytes())
+        .map_err(to_js_err)?;
+    Ok(WasmAAFramework { framework })
+}
+
+/// Parses a line of a dynamics file, returning its sign (`+` or `-`), its kind (`arg` or `att`) and its labels.
+#[wasm_bindgen(js_name = parseModification)]
+pub fn parse_modification(line: &str) -> Result<Array, JsError> {
+    let m = dynamics::read_modification(line).map_err(to_js_err)?;
+    let (sign, kind, labels) = bindings::modification_parts(m);
+    Ok(Array::of3(
+        &sign.into(),
+        &kind.into(),
+        &string_array(labels).into(),
+    ))
+}
+
+/// Reads an extension given as a solver answer (e.g. `"[a,b]"`).
+#[wasm_bindgen(js_name = readExtension)]
+pub fn read_extension(answer: &str) -> Result<Vec<String>, JsError> {
+    let extension = solutions::read_extension(&mut answer.as_bytes()).map_err(to_js_err)?;
+    Ok(extension.iter().map(|a| a.label().clone()).collect())
+}
+
+/// Reads an acceptance status given as a solver answer (`YES` or `NO`).
+#[wasm_bindgen(js_name = readAcceptanceStatus)]
+pub fn read_acceptance_status(answer: &str) -> Result<bool, JsError> {
+    solutions::read_acceptance_status(&mut answer.as_bytes()).map_err(to_js_err)
+}
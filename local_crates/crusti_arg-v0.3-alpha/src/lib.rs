@@ -38,6 +38,14 @@
 //! assert_eq!(1, framework.iter_attacks().count());
 //! ```
 //!
+//! Frameworks can be printed for humans: `Display` gives the list of the arguments followed by one `a → b` line per attack, `Debug` gives the same content on a single line, and `summary` only gives the numbers of arguments and attacks.
+//!
+//! Argument sets, which also represent extensions, can be combined by their labels with `union`, `intersection`, `difference` and `symmetric_difference`, and compared with `is_subset_of`.
+//! Arguments are iterated in the order of their ids and attacks in the order they were added, which is also the order followed by the writers; `iter_sorted`, `iter_attacks_by_ids` and `iter_attacks_by_labels` give orders which do not depend on how the framework was built.
+//!
+//! Frameworks and extension sets labelled by `String` store each label once per argument set.
+//! When handling large sets of extensions, the [`LabelInterner`] gives shared [`InternedLabel`] values (`Arc<str>`) instead, so that each label is allocated once.
+//!
 //! # Reading and writing AAF
 //!
 //! The library provides structures to read and write AAFs, taking advantage of [the Aspartix format](https://www.dbai.tuwien.ac.at/research/argumentation/aspartix/dung.html).
@@ -68,15 +76,154 @@
 //! # write_af_to_stdout(&AAFramework::new(ArgumentSet::new(vec![] as Vec<String>)));
 //! ```
 //!
+//! Labels which are not identifiers can be quoted, as in `arg("débat, tour 1").`; the [`labels`] module reads and writes such labels, and is used by the readers and writers of instances, dynamics and solver answers.
+//!
+//! Files compressed with gzip or xz can be read and written through the [`compression`] module, which relies on the `gzip` and `xz` commands.
+//!
+//! # Semantics and acceptance
+//!
+//! The credulous and skeptical acceptance of arguments under a [`Semantics`] can be decided by the framework itself, using the native engines of the [`solvers`] module.
+//! The grounded semantics (and the skeptical acceptance under the complete one) is handled for frameworks of any size, while the other semantics are handled by a naive solver dedicated to small frameworks.
+//!
+//! ```
+//! # use crusti_arg::{AAFramework, AspartixReader, Semantics};
+//! let framework = AspartixReader::default().read(&mut "arg(a).\narg(b).\natt(a,b).\natt(b,a).\n".as_bytes()).unwrap();
+//! let a = "a".to_string();
+//! assert!(framework.credulously_accepted(&a, Semantics::Stable).unwrap());
+//! assert!(!framework.skeptically_accepted(&a, Semantics::Stable).unwrap());
+//! ```
+//!
+//! Custom semantics may be built upon the characteristic function of the framework, given by [`AAFramework::defended_by`], and the related fixpoint utilities ([`AAFramework::is_fixpoint`], [`AAFramework::fixpoint_from`] and [`AAFramework::least_fixpoint`]), and the set operations computing the arguments attacked by a set ([`AAFramework::attacked_by`]), its range ([`AAFramework::range_of`]) and its conflict-freeness ([`AAFramework::conflict_free`]).
+//!
+//! The [`examples`] module builds the classic small frameworks of the literature (reinstatement, odd cycles, floating acceptance, Nixon diamond), which are handy for tests and teaching demos.
+//!
+//! The [`incremental`] module answers queries on the successive frameworks of a dynamics with the small SAT solver of the [`sat`] module, activating and deactivating arguments and attacks through assumptions instead of encoding each framework from scratch.
+//!
+//! Structural statistics of a framework, such as its degree sequences ([`AAFramework::degree_stats`]) and the density of its attack graph ([`AAFramework::density`]), are given by the [`stats`] module.
+//!
+//! # Weighted frameworks
+//!
+//! Weights may be attached to the arguments and the attacks of an [`AAFramework`].
+//! The [`WeightedAspartixReader`] and [`WeightedAspartixWriter`] handle a dialect of the Aspartix format in which weights are given as the last parameter of declarations, e.g. `att(a,b,0.7).`.
+//!
+//! # Bipolar frameworks
+//!
+//! The [`BAFramework`] struct adds a support relation to the attacks of a framework.
+//! The [`BipolarAspartixReader`] and [`BipolarAspartixWriter`] handle a dialect of the Aspartix format in which supports are declared by `sup(a,b).` lines.
+//!
+//! # Preference-based frameworks
+//!
+//! The [`PreferenceAAFramework`] struct couples a framework with a strict preference relation over its arguments, and computes the induced defeat relation.
+//! Changes of the preferences are translated into modifications of the defeat relation, so that dynamics over preferences can be expressed by dynamics files.
+//!
+//! # Frameworks with collective attacks
+//!
+//! The [`SetAFramework`] struct handles frameworks in which attacks are made by sets of arguments (SETAF).
+//! The [`SetafAspartixReader`] and [`SetafAspartixWriter`] handle the SETAF dialect of the Aspartix format, in which attacks are named by `att(r,b).` lines and their members given by `mem(r,a).` lines.
+//!
+//! # Control frameworks
+//!
+//! The [`CAFramework`] struct handles Control Argumentation frameworks, whose arguments are split into a fixed, an uncertain and a control part, and which may contain uncertain attacks and conflicts of unknown direction.
+//! Such a framework is projected into an [`AAFramework`] given the control arguments in use and a [`CafCompletion`] of its uncertain part.
+//! The [`CafAspartixReader`] and [`CafAspartixWriter`] handle a dialect of the Aspartix format with `uarg`, `carg`, `uatt` and `udatt` declarations.
+//!
+//! # Claim-augmented frameworks
+//!
+//! The [`ClaimAAFramework`] struct attaches a claim to each argument of a framework.
+//! Its extensions are computed by the native engines and projected to the claims of their arguments, giving the claim-level extensions and the acceptance of claims.
+//! The [`ClaimAspartixReader`] and [`ClaimAspartixWriter`] handle a dialect of the Aspartix format in which claims are declared by `claim(a,c).` lines.
+//!
+//! # Incomplete frameworks
+//!
+//! The [`IncompleteAAFramework`] struct handles Incomplete Argumentation frameworks, in which some arguments and attacks are uncertain.
+//! Its completions can be enumerated, and the possible and necessary acceptance of arguments are decided by running the native engines on each completion.
+//! The [`IafAspartixReader`] and [`IafAspartixWriter`] handle a dialect of the Aspartix format in which uncertain arguments and attacks are declared by `?arg(a).` and `?att(a,b).` lines.
+//!
+//! # Visualization
+//!
+//! The [`DotWriter`] writes frameworks in the DOT format of Graphviz.
+//! Arguments may be colored according to the IN/OUT/UNDEC labelling induced by an extension, as given by [`AAFramework::labelling`], and the arguments and attacks added or removed by a step of a dynamics may be highlighted.
+//!
+//! # Revision of frameworks
+//!
+//! Besides the raw additions and removals of arguments and attacks, the [`revision`] module provides the revision operators studied in the literature on dynamics: normal expansions, and normal, weak and strong contractions.
+//! It also checks whether a sequence of modifications is a normal expansion of a framework.
+//!
+//! The [`enforcement`] module computes the attack additions and removals after which a set of arguments is accepted, by encoding the problem into a CNF formula solved by the small SAT solver of the [`sat`] module.
+//! The number of changes or their weighted cost is minimized by the solvers of the [`maxsat`] module, which can be stopped at a deadline.
+//!
+//! # Property-based testing
+//!
+//! With the `testing` feature, the [`testing`] module exposes seeded generators of random frameworks and sequences of modifications, which can be used to property-test the code handling frameworks and their dynamics.
+//! With the `test-utils` feature, the [`test_utils`] module exposes a writer collecting its content into a string and a reader recording what was read through it.
+//!
+//! # Python bindings
+//!
+//! With the `python` feature, the [`python`] module exposes frameworks, their dynamics, the native engines and the parsers of solver answers to Python through PyO3.
+//! The `crusti_arg_py` crate builds them as an extension module, e.g. with `maturin develop`.
+//!
+//! # WebAssembly
+//!
+//! The library does not spawn processes, and compiles to the `wasm32-unknown-unknown` target; the functions reading and writing files (e.g. those of the [`compression`] module) fail there, but all the readers and writers work on in-memory buffers.
+//! With the `wasm` feature, the [`wasm`] module exposes the parsers, the native engines and the checkers to JavaScript through `wasm-bindgen`, so that browser-based tools use the same implementations of the semantics.
+//! The `crusti_arg_wasm` crate builds them as a WebAssembly module, e.g. with `wasm-pack build --target web`.
+//!
 //! # License
 //!
 //! Crusti_binnet is developed at CRIL (Centre de Recherche en Informatique de Lens).
 //! It is made available under the terms of the GNU GPLv3 license.
 //!
 //! [`AAFramework`]: struct.AAFramework.html
+//! [`AAFramework::attacked_by`]: struct.AAFramework.html#method.attacked_by
+//! [`AAFramework::conflict_free`]: struct.AAFramework.html#method.conflict_free
+//! [`AAFramework::degree_stats`]: struct.AAFramework.html#method.degree_stats
+//! [`AAFramework::defended_by`]: struct.AAFramework.html#method.defended_by
+//! [`AAFramework::density`]: struct.AAFramework.html#method.density
+//! [`AAFramework::fixpoint_from`]: struct.AAFramework.html#method.fixpoint_from
+//! [`AAFramework::labelling`]: struct.AAFramework.html#method.labelling
+//! [`AAFramework::is_fixpoint`]: struct.AAFramework.html#method.is_fixpoint
+//! [`AAFramework::least_fixpoint`]: struct.AAFramework.html#method.least_fixpoint
+//! [`AAFramework::range_of`]: struct.AAFramework.html#method.range_of
 //! [`AspartixReader`]: struct.AspartixReader.html
+//! [`BAFramework`]: struct.BAFramework.html
+//! [`BipolarAspartixReader`]: struct.BipolarAspartixReader.html
+//! [`BipolarAspartixWriter`]: struct.BipolarAspartixWriter.html
+//! [`CAFramework`]: struct.CAFramework.html
+//! [`CafAspartixReader`]: struct.CafAspartixReader.html
+//! [`CafAspartixWriter`]: struct.CafAspartixWriter.html
+//! [`CafCompletion`]: struct.CafCompletion.html
 //! [`AspartixWriter`]: struct.AspartixWriter.html
+//! [`ClaimAAFramework`]: struct.ClaimAAFramework.html
+//! [`ClaimAspartixReader`]: struct.ClaimAspartixReader.html
+//! [`ClaimAspartixWriter`]: struct.ClaimAspartixWriter.html
+//! [`DotWriter`]: struct.DotWriter.html
+//! [`IafAspartixReader`]: struct.IafAspartixReader.html
+//! [`IafAspartixWriter`]: struct.IafAspartixWriter.html
+//! [`IncompleteAAFramework`]: struct.IncompleteAAFramework.html
+//! [`InternedLabel`]: type.InternedLabel.html
+//! [`LabelInterner`]: struct.LabelInterner.html
 //! [`LabelType`]: trait.LabelType.html
+//! [`PreferenceAAFramework`]: struct.PreferenceAAFramework.html
+//! [`Semantics`]: enum.Semantics.html
+//! [`SetAFramework`]: struct.SetAFramework.html
+//! [`SetafAspartixReader`]: struct.SetafAspartixReader.html
+//! [`SetafAspartixWriter`]: struct.SetafAspartixWriter.html
+//! [`WeightedAspartixReader`]: struct.WeightedAspartixReader.html
+//! [`WeightedAspartixWriter`]: struct.WeightedAspartixWriter.html
+//! [`compression`]: compression/index.html
+//! [`enforcement`]: enforcement/index.html
+//! [`examples`]: examples/index.html
+//! [`incremental`]: incremental/index.html
+//! [`labels`]: labels/index.html
+//! [`maxsat`]: maxsat/index.html
+//! [`python`]: python/index.html
+//! [`revision`]: revision/index.html
+//! [`sat`]: sat/index.html
+//! [`solvers`]: solvers/index.html
+//! [`stats`]: stats/index.html
+//! [`test_utils`]: test_utils/index.html
+//! [`testing`]: testing/index.html
+//! [`wasm`]: wasm/index.html
 
 #![warn(missing_docs)]
 #![warn(missing_doc_code_examples)]
@@ -84,8 +231,51 @@
 mod aa;
 mod utils;
 
-pub use crate::aa::aa_framework::{AAFramework, Attack};
+pub use crate::aa::aa_framework::{AAFramework, Attack, LabellingStatus};
 pub use crate::aa::arguments::{Argument, ArgumentSet, LabelType};
+pub use crate::aa::ba_framework::{BAFramework, Support};
+pub use crate::aa::caf_framework::{CAFramework, CafCompletion, CafPart};
+pub use crate::aa::claim_framework::ClaimAAFramework;
+pub use crate::aa::enforcement;
+pub use crate::aa::examples;
+pub use crate::aa::iaf_framework::{Completions, IncompleteAAFramework};
+pub use crate::aa::incremental;
+pub use crate::aa::interner::{InternedLabel, LabelInterner};
 pub use crate::aa::io::aspartix_reader::AspartixReader;
 pub use crate::aa::io::aspartix_writer::AspartixWriter;
+pub use crate::aa::io::bipolar_aspartix_reader::BipolarAspartixReader;
+pub use crate::aa::io::bipolar_aspartix_writer::BipolarAspartixWriter;
+pub use crate::aa::io::caf_aspartix_reader::CafAspartixReader;
+pub use crate::aa::io::caf_aspartix_writer::CafAspartixWriter;
+pub use crate::aa::io::claim_aspartix_reader::ClaimAspartixReader;
+pub use crate::aa::io::claim_aspartix_writer::ClaimAspartixWriter;
+pub use crate::aa::io::compression;
+pub use crate::aa::io::dot_writer::DotWriter;
+pub use crate::aa::io::dynamics;
+pub use crate::aa::io::iaf_aspartix_reader::IafAspartixReader;
+pub use crate::aa::io::iaf_aspartix_writer::IafAspartixWriter;
+pub use crate::aa::io::labels;
+pub use crate::aa::io::numeric_writer::NumericWriter;
+pub use crate::aa::io::parse_error::ParseError;
+pub use crate::aa::io::setaf_aspartix_reader::SetafAspartixReader;
+pub use crate::aa::io::setaf_aspartix_writer::SetafAspartixWriter;
 pub use crate::aa::io::solutions;
+pub use crate::aa::io::warmstart;
+pub use crate::aa::io::weighted_aspartix_reader::WeightedAspartixReader;
+pub use crate::aa::io::weighted_aspartix_writer::WeightedAspartixWriter;
+pub use crate::aa::maxsat;
+pub use crate::aa::preference_framework::PreferenceAAFramework;
+#[cfg(feature = "python")]
+pub use crate::aa::python;
+pub use crate::aa::revision;
+pub use crate::aa::sat;
+pub use crate::aa::semantics::Semantics;
+pub use crate::aa::setaf_framework::{SetAFramework, SetAttack};
+pub use crate::aa::solvers;
+pub use crate::aa::stats;
+#[cfg(feature = "testing")]
+pub use crate::aa::testing;
+#[cfg(feature = "wasm")]
+pub use crate::aa::wasm;
+#[cfg(feature = "test-utils")]
+pub use crate::utils::test_utils;
@@ -17,5 +17,10 @@
 // Contributors:
 //   *   CRIL - initial API and implementation
 
+#[cfg(any(test, feature = "test-utils"))]
+pub(crate) mod read_log;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
 pub(crate) mod warning_result;
+#[cfg(any(test, feature = "test-utils"))]
 pub(crate) mod writable_string;
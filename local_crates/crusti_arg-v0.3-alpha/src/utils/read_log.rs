@@ -0,0 +1,128 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::io::{BufRead, Read};
+
+/// A reader recording everything read through it.
+///
+/// This wrapper lets a test check which part of an input was consumed by a function, e.g. that a reader stops at the end of an answer.
+/// The bytes are recorded when they are returned by [`read`] or marked as consumed by [`consume`]; the ones only looked at by [`fill_buf`] are not.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::{solutions, test_utils::ReadLog};
+/// let mut reader = ReadLog::new("YES\nNO\n".as_bytes());
+/// assert!(solutions::read_acceptance_status(&mut reader).unwrap());
+/// assert_eq!("YES\n", reader.to_string());
+/// ```
+///
+/// [`read`]: #method.read
+/// [`consume`]: #method.consume
+/// [`fill_buf`]: #method.fill_buf
+pub struct ReadLog<R> {
+    inner: R,
+    log: Vec<u8>,
+}
+
+impl<R> ReadLog<R> {
+    /// Wraps a reader, with an empty record.
+    ///
+    /// # Arguments
+    /// * `inner` - the reader
+    pub fn new(inner: R) -> Self {
+        ReadLog { inner, log: vec![] }
+    }
+
+    /// Returns the bytes read so far.
+    pub fn log(&self) -> &[u8] {
+        &self.log
+    }
+
+    /// Consumes the wrapper, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> Read for ReadLog<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.log.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<R> BufRead for ReadLog<R>
+where
+    R: BufRead,
+{
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let Ok(buf) = self.inner.fill_buf() {
+            self.log.extend_from_slice(&buf[..amt.min(buf.len())]);
+        }
+        self.inner.consume(amt)
+    }
+}
+
+impl<R> std::fmt::Display for ReadLog<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", String::from_utf8_lossy(&self.log))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufReader;
+
+    #[test]
+    fn test_read() {
+        let mut reader = ReadLog::new("abcdef".as_bytes());
+        let mut buf = [0; 4];
+        assert_eq!(4, reader.read(&mut buf).unwrap());
+        assert_eq!(b"abcd", reader.log());
+    }
+
+    #[test]
+    fn test_read_line() {
+        let mut reader = ReadLog::new(BufReader::new("abc\ndef\n".as_bytes()));
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!("abc\n", reader.to_string());
+        assert_eq!("abc\n", line);
+    }
+
+    #[test]
+    fn test_fill_buf_not_recorded() {
+        let mut reader = ReadLog::new("abc".as_bytes());
+        assert_eq!(b"abc", reader.fill_buf().unwrap());
+        assert!(reader.log().is_empty());
+        reader.consume(2);
+        assert_eq!("ab", reader.to_string());
+        assert_eq!(b"c", reader.into_inner());
+    }
+}
@@ -0,0 +1,35 @@
+// crusti_arg
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+//! I/O utilities for the tests of the code reading and writing frameworks and answers.
+//!
+//! This module is available with the `test-utils` feature.
+//! A [`WritableString`] collects what is written by a function, while a [`ReadLog`] records what a function read from its input.
+//!
+//! ```
+//! # use crusti_arg::{solutions, test_utils::WritableString};
+//! let mut output = WritableString::default();
+//! solutions::write_acceptance_status(&mut output, true).unwrap();
+//! assert_eq!("YES\n", output.as_str());
+//! ```
+//!
+//! [`ReadLog`]: struct.ReadLog.html
+//! [`WritableString`]: struct.WritableString.html
+
+pub use crate::utils::read_log::ReadLog;
+pub use crate::utils::writable_string::WritableString;
@@ -19,10 +19,34 @@
 
 use std::io::Write;
 
+/// A writer collecting its content into a string.
+///
+/// This writer replaces the usual `Cursor<Vec<u8>>` when the content written by a function must be checked by a test.
+/// The written bytes must be valid UTF-8.
+///
+/// # Example
+///
+/// ```
+/// # use crusti_arg::test_utils::WritableString;
+/// # use std::io::Write;
+/// let mut s = WritableString::default();
+/// write!(s, "[a, b]").unwrap();
+/// assert_eq!("[a, b]", s.as_str());
+/// ```
 #[derive(Default)]
 pub struct WritableString(String);
 
 impl WritableString {
+    /// Returns the content written so far.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes the writer, returning its content.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
     fn format(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
     }
@@ -71,4 +95,12 @@ mod tests {
         write!(s, "def").unwrap();
         assert_eq!("abcdef", format!("{:?}", s))
     }
+
+    #[test]
+    fn test_into_string() {
+        let mut s = WritableString::default();
+        write!(s, "é").unwrap();
+        assert_eq!("é", s.as_str());
+        assert_eq!("é", s.into_string());
+    }
 }
@@ -0,0 +1,27 @@
+// crusti_arg_py
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! The `crusti_arg_py` Python extension module, built from the Python bindings of crusti_arg.
+
+use pyo3::prelude::*;
+
+#[pymodule]
+fn crusti_arg_py(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    crusti_arg::python::register(module)
+}
@@ -0,0 +1,104 @@
+#ifndef ICCMA_DYNAMICS_H
+#define ICCMA_DYNAMICS_H
+
+/* Generated by cbindgen from src/lib.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// A solver run on a dynamics, handled by C programs through a pointer.
+typedef struct IdwRunner IdwRunner;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Spawns a solver on a dynamic problem and reads its answer for the initial framework.
+//
+// The solver is given the ICCMA'21 command line arguments of the problem (e.g. `DC-CO-D`), the instance file (Aspartix format) and the queried argument.
+// Its standard error is copied to the one of the calling program, and it is killed if it does not give an answer within the time limit.
+// Returns `NULL` on failure.
+//
+// # Safety
+//
+// `solver`, `problem` and `instance` must be valid C strings; `argument` must be a valid C string for DC and DS problems, and may be `NULL` otherwise.
+//
+// # Arguments
+//
+// * `solver` - the solver command
+// * `problem` - the dynamic problem
+// * `instance` - the path of the instance file
+// * `argument` - the queried argument, if any
+// * `timeout_ms` - the time given to the solver for each answer, in milliseconds (`0` for no limit)
+struct IdwRunner *idw_runner_new(const char *solver,
+                                 const char *problem,
+                                 const char *instance,
+                                 const char *argument,
+                                 uint64_t timeout_ms);
+
+// Sends a modification (e.g. `+att(a,b).`) to the solver and reads its answer for the modified framework.
+//
+// Returns `0` on success and `-1` on failure.
+// A malformed modification is not sent to the solver, and the runner can go on; after any other failure (e.g. a solver reaching the time limit), the runner should be destroyed.
+//
+// # Safety
+//
+// `runner` must be a pointer returned by [`idw_runner_new`] and not destroyed yet, and `modification` must be a valid C string.
+//
+// # Arguments
+//
+// * `runner` - the runner
+// * `modification` - the modification
+//
+// [`idw_runner_new`]: fn.idw_runner_new.html
+int idw_runner_push_modification(struct IdwRunner *runner,
+                                 const char *modification);
+
+// Returns the answer of the solver for the current step, formatted as the answers written by the wrapper (e.g. `YES\n`).
+//
+// The string belongs to the runner, and is valid until the next call to [`idw_runner_push_modification`] or [`idw_runner_destroy`].
+// Returns `NULL` if `runner` is `NULL`.
+//
+// # Safety
+//
+// `runner` must be `NULL` or a pointer returned by [`idw_runner_new`] and not destroyed yet.
+//
+// [`idw_runner_new`]: fn.idw_runner_new.html
+// [`idw_runner_push_modification`]: fn.idw_runner_push_modification.html
+// [`idw_runner_destroy`]: fn.idw_runner_destroy.html
+const char *idw_runner_answer(const struct IdwRunner *runner);
+
+// Returns the current step of a runner, i.e. the number of modifications sent to the solver.
+//
+// # Safety
+//
+// `runner` must be a pointer returned by [`idw_runner_new`] and not destroyed yet.
+//
+// [`idw_runner_new`]: fn.idw_runner_new.html
+size_t idw_runner_step(const struct IdwRunner *runner);
+
+// Ends the dynamics, waits for the end of the solver and frees the runner.
+//
+// Returns `0` if the solver ended properly and `-1` otherwise (e.g. if it gave more answers than expected or failed); the runner is freed in both cases.
+// Destroying a `NULL` runner does nothing.
+//
+// # Safety
+//
+// `runner` must be `NULL` or a pointer returned by [`idw_runner_new`] and not destroyed yet.
+//
+// [`idw_runner_new`]: fn.idw_runner_new.html
+int idw_runner_destroy(struct IdwRunner *runner);
+
+// Returns the message of the last error which occurred in the current thread, or `NULL` if no error occurred.
+//
+// The string is valid until the next failing call in the same thread.
+const char *idw_last_error(void);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* ICCMA_DYNAMICS_H */
@@ -0,0 +1,354 @@
+// iccma_dynamics_ffi
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! A C interface to the runner of the `iccma_dynamics` library, so that programs written in C or C++ can run solvers on dynamics.
+//!
+//! A runner is created by [`idw_runner_new`], which spawns the solver and reads its answer for the initial framework.
+//! Modifications are then sent one at a time by [`idw_runner_push_modification`], the answer of the current step being given by [`idw_runner_answer`].
+//! [`idw_runner_destroy`] ends the dynamics, waits for the end of the solver and frees the runner.
+//!
+//! Strings are NUL-terminated and UTF-8 encoded.
+//! The functions failing return `NULL` or `-1`, and the message of their error is given by [`idw_last_error`].
+//! The crate is built as a shared and a static library; the C declarations of these functions are in `include/iccma_dynamics.h`, generated by `cbindgen`.
+//!
+//! [`idw_runner_new`]: fn.idw_runner_new.html
+//! [`idw_runner_push_modification`]: fn.idw_runner_push_modification.html
+//! [`idw_runner_answer`]: fn.idw_runner_answer.html
+//! [`idw_runner_destroy`]: fn.idw_runner_destroy.html
+//! [`idw_last_error`]: fn.idw_last_error.html
+
+#![warn(missing_docs)]
+
+use std::{
+    cell::RefCell,
+    convert::TryFrom,
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_int},
+    ptr,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context, Result};
+
+use iccma_dynamics::runner::{QueryType, RunLimits, SolverSession};
+
+/// The format of the instances given to the solvers.
+const FILE_FORMAT: &str = "apx";
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// A solver run on a dynamics, handled by C programs through a pointer.
+pub struct IdwRunner {
+    session: SolverSession,
+    answer: CString,
+}
+
+impl IdwRunner {
+    fn new(session: SolverSession) -> Result<Self> {
+        let answer = c_string(session.answer())?;
+        Ok(IdwRunner { session, answer })
+    }
+
+    fn push_modification(&mut self, modification: &str) -> Result<()> {
+        let answer = c_string(self.session.push_modification(modification)?)?;
+        self.answer = answer;
+        Ok(())
+    }
+}
+
+fn c_string(s: &str) -> Result<CString> {
+    CString::new(s).context("while converting an answer to a C string")
+}
+
+fn set_last_error(e: anyhow::Error) {
+    let message = format!("{:#}", e).replace('\0', " ");
+    LAST_ERROR.with(|last| *last.borrow_mut() = CString::new(message).ok());
+}
+
+/// Reads a C string, which must not be `NULL`.
+unsafe fn read_str<'a>(s: *const c_char, name: &str) -> Result<&'a str> {
+    if s.is_null() {
+        return Err(anyhow!("{} is NULL", name));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .with_context(|| format!("{} is not valid UTF-8", name))
+}
+
+/// Spawns a solver on a dynamic problem and reads its answer for the initial framework.
+///
+/// The solver is given the ICCMA'21 command line arguments of the problem (e.g. `DC-CO-D`), the instance file (Aspartix format) and the queried argument.
+/// Its standard error is copied to the one of the calling program, and it is killed if it does not give an answer within the time limit.
+/// Returns `NULL` on failure.
+///
+/// # Safety
+///
+/// `solver`, `problem` and `instance` must be valid C strings; `argument` must be a valid C string for DC and DS problems, and may be `NULL` otherwise.
+///
+/// # Arguments
+///
+/// * `solver` - the solver command
+/// * `problem` - the dynamic problem
+/// * `instance` - the path of the instance file
+/// * `argument` - the queried argument, if any
+/// * `timeout_ms` - the time given to the solver for each answer, in milliseconds (`0` for no limit)
+#[no_mangle]
+pub unsafe extern "C" fn idw_runner_new(
+    solver: *const c_char,
+    problem: *const c_char,
+    instance: *const c_char,
+    argument: *const c_char,
+    timeout_ms: u64,
+) -> *mut IdwRunner {
+    let runner = (|| -> Result<IdwRunner> {
+        let solver = read_str(solver, "solver")?;
+        let problem = read_str(problem, "problem")?;
+        let instance = read_str(instance, "instance")?;
+        let argument = if argument.is_null() {
+            None
+        } else {
+            Some(read_str(argument, "argument")?)
+        };
+        let query = QueryType::try_from((problem, argument))?;
+        let arguments = query.command_arguments(problem, instance, FILE_FORMAT);
+        let mut limits = RunLimits::default();
+        if timeout_ms > 0 {
+            limits.set_step_timeout(Duration::from_millis(timeout_ms));
+        }
+        IdwRunner::new(SolverSession::start(solver, &arguments, &query, &limits)?)
+    })();
+    match runner {
+        Ok(r) => Box::into_raw(Box::new(r)),
+        Err(e) => {
+            set_last_error(e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Sends a modification (e.g. `+att(a,b).`) to the solver and reads its answer for the modified framework.
+///
+/// Returns `0` on success and `-1` on failure.
+/// A malformed modification is not sent to the solver, and the runner can go on; after any other failure (e.g. a solver reaching the time limit), the runner should be destroyed.
+///
+/// # Safety
+///
+/// `runner` must be a pointer returned by [`idw_runner_new`] and not destroyed yet, and `modification` must be a valid C string.
+///
+/// # Arguments
+///
+/// * `runner` - the runner
+/// * `modification` - the modification
+///
+/// [`idw_runner_new`]: fn.idw_runner_new.html
+#[no_mangle]
+pub unsafe extern "C" fn idw_runner_push_modification(
+    runner: *mut IdwRunner,
+    modification: *const c_char,
+) -> c_int {
+    let result = match runner.as_mut() {
+        Some(r) => read_str(modification, "modification").and_then(|m| r.push_modification(m)),
+        None => Err(anyhow!("runner is NULL")),
+    };
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            set_last_error(e);
+            -1
+        }
+    }
+}
+
+/// Returns the answer of the solver for the current step, formatted as the answers written by the wrapper (e.g. `YES\n`).
+///
+/// The string belongs to the runner, and is valid until the next call to [`idw_runner_push_modification`] or [`idw_runner_destroy`].
+/// Returns `NULL` if `runner` is `NULL`.
+///
+/// # Safety
+///
+/// `runner` must be `NULL` or a pointer returned by [`idw_runner_new`] and not destroyed yet.
+///
+/// [`idw_runner_new`]: fn.idw_runner_new.html
+/// [`idw_runner_push_modification`]: fn.idw_runner_push_modification.html
+/// [`idw_runner_destroy`]: fn.idw_runner_destroy.html
+#[no_mangle]
+pub unsafe extern "C" fn idw_runner_answer(runner: *const IdwRunner) -> *const c_char {
+    match runner.as_ref() {
+        Some(r) => r.answer.as_ptr(),
+        None => ptr::null(),
+    }
+}
+
+/// Returns the current step of a runner, i.e. the number of modifications sent to the solver.
+///
+/// # Safety
+///
+/// `runner` must be a pointer returned by [`idw_runner_new`] and not destroyed yet.
+///
+/// [`idw_runner_new`]: fn.idw_runner_new.html
+#[no_mangle]
+pub unsafe extern "C" fn idw_runner_step(runner: *const IdwRunner) -> usize {
+    runner.as_ref().map(|r| r.session.step()).unwrap_or(0)
+}
+
+/// Ends the dynamics, waits for the end of the solver and frees the runner.
+///
+/// Returns `0` if the solver ended properly and `-1` otherwise (e.g. if it gave more answers than expected or failed); the runner is freed in both cases.
+/// Destroying a `NULL` runner does nothing.
+///
+/// # Safety
+///
+/// `runner` must be `NULL` or a pointer returned by [`idw_runner_new`] and not destroyed yet.
+///
+/// [`idw_runner_new`]: fn.idw_runner_new.html
+#[no_mangle]
+pub unsafe extern "C" fn idw_runner_destroy(runner: *mut IdwRunner) -> c_int {
+    if runner.is_null() {
+        return 0;
+    }
+    match Box::from_raw(runner).session.finish() {
+        Ok(_) => 0,
+        Err(e) => {
+            set_last_error(e);
+            -1
+        }
+    }
+}
+
+/// Returns the message of the last error which occurred in the current thread, or `NULL` if no error occurred.
+///
+/// The string is valid until the next failing call in the same thread.
+#[no_mangle]
+pub extern "C" fn idw_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| match last.borrow().as_ref() {
+        Some(message) => message.as_ptr(),
+        None => ptr::null(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(idw_last_error()) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_runner() {
+        use std::os::unix::fs::PermissionsExt;
+        let solver = std::env::temp_dir().join(format!("idw-test-ffi-{}.sh", std::process::id()));
+        // a fake solver answering YES for the initial framework, and NO after each modification
+        std::fs::write(
+            &solver,
+            "#!/bin/sh\necho YES\nwhile read l; do [ -z \"$l\" ] && exit 0; echo NO; done\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&solver, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let solver_path = c(solver.to_str().unwrap());
+        unsafe {
+            let runner = idw_runner_new(
+                solver_path.as_ptr(),
+                c("DC-CO-D").as_ptr(),
+                c("af.apx").as_ptr(),
+                c("a").as_ptr(),
+                1000,
+            );
+            assert!(!runner.is_null());
+            assert_eq!(
+                "YES\n",
+                CStr::from_ptr(idw_runner_answer(runner)).to_str().unwrap()
+            );
+            assert_eq!(
+                0,
+                idw_runner_push_modification(runner, c("+arg(b).").as_ptr())
+            );
+            assert_eq!(
+                "NO\n",
+                CStr::from_ptr(idw_runner_answer(runner)).to_str().unwrap()
+            );
+            assert_eq!(
+                -1,
+                idw_runner_push_modification(runner, c("+arg(b)").as_ptr())
+            );
+            assert!(last_error().contains("modification at line 2"));
+            assert_eq!(1, idw_runner_step(runner));
+            assert_eq!(0, idw_runner_destroy(runner));
+        }
+        std::fs::remove_file(&solver).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_runner_timeout() {
+        use std::os::unix::fs::PermissionsExt;
+        let solver =
+            std::env::temp_dir().join(format!("idw-test-ffi-timeout-{}.sh", std::process::id()));
+        std::fs::write(&solver, "#!/bin/sh\nexec sleep 60\n").unwrap();
+        std::fs::set_permissions(&solver, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let solver_path = c(solver.to_str().unwrap());
+        unsafe {
+            assert!(idw_runner_new(
+                solver_path.as_ptr(),
+                c("SE-CO-D").as_ptr(),
+                c("af.apx").as_ptr(),
+                ptr::null(),
+                100,
+            )
+            .is_null());
+        }
+        assert!(last_error().contains("time limit"));
+        std::fs::remove_file(&solver).unwrap();
+    }
+
+    #[test]
+    fn test_runner_new_errors() {
+        unsafe {
+            assert!(idw_runner_new(
+                c("/nonexistent/solver").as_ptr(),
+                c("SE-CO-D").as_ptr(),
+                c("af.apx").as_ptr(),
+                ptr::null(),
+                0,
+            )
+            .is_null());
+            assert!(last_error().contains("while spawning solver"));
+            assert!(idw_runner_new(
+                c("/nonexistent/solver").as_ptr(),
+                c("DC-CO-D").as_ptr(),
+                ptr::null(),
+                c("a").as_ptr(),
+                0,
+            )
+            .is_null());
+            assert_eq!("instance is NULL", last_error());
+            assert_eq!(0, idw_runner_destroy(ptr::null_mut()));
+        }
+    }
+}
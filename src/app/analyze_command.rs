@@ -0,0 +1,318 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    collections::BTreeSet,
+    convert::TryFrom,
+    io::{BufWriter, Write},
+};
+
+use anyhow::{Context, Result};
+use crusti_app_helper::{info, AppSettings, Arg, Command, SubCommand, WithExitStatus};
+use crusti_arg::{compression, solutions, AAFramework, AspartixReader, Semantics};
+use iccma_dynamics::runner::QueryType;
+
+use super::exit_status::MALFORMED_INPUT;
+use super::framework_state::FrameworkState;
+use super::solve_command;
+use super::wrap_command::read_modifications;
+
+pub(crate) struct AnalyzeDynamicsCommand;
+
+const CMD_NAME: &str = "analyze-dynamics";
+
+const ARG_PROBLEM: &str = "PROBLEM";
+const ARG_INPUT_FILE: &str = "INPUT_FILE";
+const ARG_MODIFICATION_FILE: &str = "MODIFICATION_FILE";
+const ARG_ARGUMENT: &str = "ARGUMENT";
+const ARG_OUTPUT_FILE: &str = "OUTPUT_FILE";
+
+impl AnalyzeDynamicsCommand {
+    pub fn new() -> Self {
+        AnalyzeDynamicsCommand
+    }
+}
+
+impl<'a> Command<'a> for AnalyzeDynamicsCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about("reports the steps of a dynamics at which the answer to a problem changes")
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_PROBLEM)
+                    .long("problem")
+                    .short("p")
+                    .takes_value(true)
+                    .help("sets the problem to analyze (e.g. DC-CO or DC-CO-D)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_INPUT_FILE)
+                    .long("input-file")
+                    .short("f")
+                    .takes_value(true)
+                    .help("sets the input file containing the framework (Aspartix format)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_MODIFICATION_FILE)
+                    .long("modification-file")
+                    .short("m")
+                    .takes_value(true)
+                    .help("sets the modification file containing the dynamics of the framework")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_ARGUMENT)
+                    .long("argument")
+                    .short("a")
+                    .takes_value(true)
+                    .help("sets the argument (for DC and DS problems)"),
+            )
+            .arg(
+                Arg::with_name(ARG_OUTPUT_FILE)
+                    .long("output-file")
+                    .short("o")
+                    .takes_value(true)
+                    .help(
+                        "sets the file in which the profile is written (default: standard output)",
+                    ),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let problem = arg_matches.value_of(ARG_PROBLEM).unwrap();
+        let dynamic_problem = if problem.split('-').count() == 2 {
+            format!("{}-D", problem)
+        } else {
+            problem.to_string()
+        };
+        let query =
+            QueryType::try_from((dynamic_problem.as_str(), arg_matches.value_of(ARG_ARGUMENT)))?;
+        let semantics = dynamic_problem
+            .split('-')
+            .nth(1)
+            .unwrap()
+            .parse::<Semantics>()?;
+        let framework = AspartixReader::default()
+            .read(
+                &mut compression::open(arg_matches.value_of(ARG_INPUT_FILE).unwrap())
+                    .context("while opening input file")
+                    .with_exit_status(MALFORMED_INPUT)?,
+            )
+            .context("while reading input file")
+            .with_exit_status(MALFORMED_INPUT)?;
+        let modifications =
+            read_modifications(arg_matches.value_of(ARG_MODIFICATION_FILE).unwrap())
+                .with_exit_status(MALFORMED_INPUT)?;
+        let mut writer: Box<dyn Write> = match arg_matches.value_of(ARG_OUTPUT_FILE) {
+            Some(path) => Box::new(BufWriter::new(
+                compression::create(path)
+                    .with_context(|| format!(r#"while creating file "{}""#, path))?,
+            )),
+            None => Box::new(std::io::stdout()),
+        };
+        let profile = analyze_dynamics(&framework, &modifications, semantics, &query)?;
+        write_profile(&profile, &mut writer)?;
+        let n_changes = profile.iter().filter(|s| s.changed).count();
+        info!(
+            "the answer changes at {} of the {} modification step(s) (volatility {:.3})",
+            n_changes,
+            modifications.len(),
+            volatility(n_changes, modifications.len())
+        );
+        Ok(())
+    }
+}
+
+/// An answer of the reference engine, in a form allowing comparisons between steps.
+#[derive(Clone, Debug, PartialEq)]
+enum Answer {
+    Status(bool),
+    Count(String),
+    Extensions(BTreeSet<BTreeSet<String>>),
+}
+
+impl Answer {
+    fn describe(&self) -> String {
+        match self {
+            Answer::Status(true) => "YES".to_string(),
+            Answer::Status(false) => "NO".to_string(),
+            Answer::Count(c) => c.clone(),
+            Answer::Extensions(extensions) => format!("{} extension(s)", extensions.len()),
+        }
+    }
+}
+
+/// The analysis of a step of a dynamics.
+#[derive(Clone, Debug, PartialEq)]
+struct StepProfile {
+    answer: Answer,
+    changed: bool,
+    added_extensions: Option<usize>,
+    removed_extensions: Option<usize>,
+}
+
+/// Computes the answer at each step of a dynamics with the native engines, and compares it to the one of the previous step.
+///
+/// The extensions of SE problems are all computed, since a change of the single extension given by a solver is not meaningful.
+fn analyze_dynamics(
+    framework: &AAFramework<String>,
+    modifications: &[String],
+    semantics: Semantics,
+    query: &QueryType,
+) -> Result<Vec<StepProfile>> {
+    let query = match query {
+        QueryType::SE => &QueryType::EE,
+        q => q,
+    };
+    let mut state = FrameworkState::new(framework);
+    let mut profile: Vec<StepProfile> = Vec::with_capacity(modifications.len() + 1);
+    for step in 0..=modifications.len() {
+        let answer = step_answer(&state.to_framework()?, semantics, query)
+            .with_context(|| format!("while solving step {}", step))?;
+        let step_profile = match (profile.last().map(|p| &p.answer), &answer) {
+            (Some(Answer::Extensions(previous)), Answer::Extensions(current)) => StepProfile {
+                changed: previous != current,
+                added_extensions: Some(current.difference(previous).count()),
+                removed_extensions: Some(previous.difference(current).count()),
+                answer,
+            },
+            (previous, _) => StepProfile {
+                changed: previous.map(|p| *p != answer).unwrap_or(false),
+                added_extensions: None,
+                removed_extensions: None,
+                answer,
+            },
+        };
+        profile.push(step_profile);
+        if let Some(m) = modifications.get(step) {
+            state
+                .apply(m)
+                .with_context(|| format!(r#"while applying modification "{}""#, m))
+                .with_exit_status(MALFORMED_INPUT)?;
+        }
+    }
+    Ok(profile)
+}
+
+fn step_answer(
+    framework: &AAFramework<String>,
+    semantics: Semantics,
+    query: &QueryType,
+) -> Result<Answer> {
+    let mut output = Vec::new();
+    solve_command::solve(framework, semantics, query, &mut output)?;
+    let mut reader = output.as_slice();
+    Ok(match query {
+        QueryType::SE | QueryType::EE => Answer::Extensions(
+            solutions::read_extension_set(&mut reader)?
+                .iter()
+                .map(|e| e.iter().map(|a| a.label().clone()).collect())
+                .collect(),
+        ),
+        QueryType::CE => Answer::Count(solutions::read_big_extension_count(&mut reader)?),
+        QueryType::DC(_) | QueryType::DS(_) => {
+            Answer::Status(solutions::read_acceptance_status(&mut reader)?)
+        }
+    })
+}
+
+/// Returns the rate of the modifications changing the answer.
+fn volatility(n_changes: usize, n_modifications: usize) -> f64 {
+    if n_modifications == 0 {
+        0.
+    } else {
+        n_changes as f64 / n_modifications as f64
+    }
+}
+
+fn write_profile(profile: &[StepProfile], writer: &mut dyn Write) -> Result<()> {
+    let context = "while writing the profile";
+    writeln!(
+        writer,
+        "step,answer,changed,added_extensions,removed_extensions"
+    )
+    .context(context)?;
+    let optional = |n: Option<usize>| n.map(|n| n.to_string()).unwrap_or_default();
+    for (step, s) in profile.iter().enumerate() {
+        writeln!(
+            writer,
+            "{},{},{},{},{}",
+            step,
+            s.answer.describe(),
+            s.changed,
+            optional(s.added_extensions),
+            optional(s.removed_extensions)
+        )
+        .context(context)?;
+    }
+    writer.flush().context(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INSTANCE: &str = "arg(a).\narg(b).\narg(c).\natt(a,b).\natt(b,c).\n";
+
+    fn profile(query: QueryType, modifications: &[&str]) -> String {
+        let framework = AspartixReader::default()
+            .read(&mut INSTANCE.as_bytes())
+            .unwrap();
+        let modifications = modifications
+            .iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<String>>();
+        let profile =
+            analyze_dynamics(&framework, &modifications, Semantics::Complete, &query).unwrap();
+        let mut output = Vec::new();
+        write_profile(&profile, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_acceptance_changes() {
+        assert_eq!(
+            "step,answer,changed,added_extensions,removed_extensions\n0,YES,false,,\n1,NO,true,,\n2,NO,false,,\n3,YES,true,,\n",
+            profile(
+                QueryType::DC("c".to_string()),
+                &["-att(a,b).", "+arg(d).", "+att(a,b)."]
+            )
+        );
+    }
+
+    #[test]
+    fn test_extension_changes() {
+        assert_eq!(
+            "step,answer,changed,added_extensions,removed_extensions\n0,1 extension(s),false,,\n1,3 extension(s),true,2,0\n2,3 extension(s),true,3,3\n",
+            profile(QueryType::SE, &["+att(b,a).", "+arg(d)."])
+        );
+    }
+
+    #[test]
+    fn test_volatility() {
+        assert_eq!(0., volatility(0, 0));
+        assert_eq!(0.5, volatility(2, 4));
+    }
+}
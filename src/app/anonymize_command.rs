@@ -0,0 +1,228 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+use anyhow::{Context, Result};
+use crusti_app_helper::{info, AppSettings, Arg, Command, SubCommand};
+use crusti_arg::{
+    compression::{self, CompressedWriter},
+    AAFramework, ArgumentSet, AspartixReader, AspartixWriter,
+};
+
+use super::translation::LabelMap;
+
+pub(crate) struct AnonymizeCommand;
+
+const CMD_NAME: &str = "anonymize";
+
+const ARG_INPUT_FILE: &str = "INPUT_FILE";
+const ARG_MODIFICATION_FILE: &str = "MODIFICATION_FILE";
+const ARG_OUTPUT_FILE: &str = "OUTPUT_FILE";
+const ARG_OUTPUT_MODIFICATION_FILE: &str = "OUTPUT_MODIFICATION_FILE";
+const ARG_MAPPING_FILE: &str = "MAPPING_FILE";
+
+impl AnonymizeCommand {
+    pub fn new() -> Self {
+        AnonymizeCommand
+    }
+}
+
+fn anonymous_label(id: usize) -> String {
+    format!("a{}", id)
+}
+
+impl<'a> Command<'a> for AnonymizeCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about("replaces the argument labels of a framework and its dynamics by anonymous ones")
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_INPUT_FILE)
+                    .long("input-file")
+                    .short("f")
+                    .takes_value(true)
+                    .help("sets the input file containing the framework (Aspartix format)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_MODIFICATION_FILE)
+                    .long("modification")
+                    .short("m")
+                    .takes_value(true)
+                    .requires(ARG_OUTPUT_MODIFICATION_FILE)
+                    .help("sets the modification file containing the dynamics of the framework"),
+            )
+            .arg(
+                Arg::with_name(ARG_OUTPUT_FILE)
+                    .long("output-file")
+                    .short("o")
+                    .takes_value(true)
+                    .help("sets the file in which the anonymized framework is written")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_OUTPUT_MODIFICATION_FILE)
+                    .long("output-modification")
+                    .short("n")
+                    .takes_value(true)
+                    .requires(ARG_MODIFICATION_FILE)
+                    .help("sets the file in which the anonymized dynamics is written"),
+            )
+            .arg(
+                Arg::with_name(ARG_MAPPING_FILE)
+                    .long("mapping")
+                    .short("k")
+                    .takes_value(true)
+                    .help("sets the file in which the mapping between anonymous and original labels is written")
+                    .required(true),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let framework = AspartixReader::default()
+            .read(
+                &mut compression::open(arg_matches.value_of(ARG_INPUT_FILE).unwrap())
+                    .context("while opening input file")?,
+            )
+            .context("while reading input file")?;
+        let mut label_map = LabelMap::new(&framework);
+        let mut af_writer = create_file(arg_matches.value_of(ARG_OUTPUT_FILE).unwrap())?;
+        AspartixWriter::default()
+            .write(&anonymize_framework(&framework), &mut af_writer)
+            .context("while writing the anonymized framework")?;
+        if let Some(m) = arg_matches.value_of(ARG_MODIFICATION_FILE) {
+            let mut mod_br =
+                BufReader::new(compression::open(m).context("while opening modification file")?);
+            let mut mod_writer =
+                create_file(arg_matches.value_of(ARG_OUTPUT_MODIFICATION_FILE).unwrap())?;
+            anonymize_modifications(&mut label_map, &mut mod_br, &mut mod_writer)?;
+        }
+        let mut mapping_writer = create_file(arg_matches.value_of(ARG_MAPPING_FILE).unwrap())?;
+        write_mapping(&label_map, &mut mapping_writer)?;
+        info!("anonymized {} argument(s)", label_map.labels().len());
+        Ok(())
+    }
+}
+
+fn create_file(path: &str) -> Result<BufWriter<CompressedWriter>> {
+    Ok(BufWriter::new(compression::create(path).with_context(
+        || format!(r#"while creating file "{}""#, path),
+    )?))
+}
+
+fn anonymize_framework(framework: &AAFramework<String>) -> AAFramework<String> {
+    let mut anonymized = AAFramework::new(ArgumentSet::new(
+        (1..=framework.argument_set().len())
+            .map(anonymous_label)
+            .collect(),
+    ));
+    framework.iter_attacks().for_each(|att| {
+        anonymized
+            .new_attack_by_ids(att.attacker().id(), att.attacked().id())
+            .unwrap()
+    });
+    anonymized
+}
+
+fn anonymize_modifications(
+    label_map: &mut LabelMap,
+    modifications: &mut dyn BufRead,
+    writer: &mut dyn Write,
+) -> Result<()> {
+    for (i, l) in modifications.lines().enumerate() {
+        let context = || format!("while anonymizing line {} of the modification file", i + 1);
+        let mod_line = l.with_context(context)?;
+        let mod_line = mod_line.trim_end_matches('\r');
+        if mod_line.is_empty() {
+            break;
+        }
+        let anonymized = label_map
+            .map_modification(mod_line, anonymous_label)
+            .with_context(context)?;
+        writeln!(writer, "{}", anonymized).with_context(context)?;
+    }
+    writer
+        .flush()
+        .context("while writing the anonymized modifications")
+}
+
+fn write_mapping(label_map: &LabelMap, writer: &mut dyn Write) -> Result<()> {
+    for (i, l) in label_map.labels().iter().enumerate() {
+        writeln!(writer, "{} {}", anonymous_label(i + 1), l)
+            .context("while writing the label mapping")?;
+    }
+    writer.flush().context("while writing the label mapping")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_af(s: &str) -> AAFramework<String> {
+        AspartixReader::default().read(&mut s.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_anonymize() {
+        let framework = read_af("arg(x).\narg(y).\natt(x,y).\natt(y,y).\n");
+        let mut label_map = LabelMap::new(&framework);
+        let mut af_out = Vec::new();
+        AspartixWriter::default()
+            .write(&anonymize_framework(&framework), &mut af_out)
+            .unwrap();
+        assert_eq!(
+            "arg(a1).\narg(a2).\natt(a1,a2).\natt(a2,a2).\n",
+            String::from_utf8(af_out).unwrap()
+        );
+        let mut mod_out = Vec::new();
+        anonymize_modifications(
+            &mut label_map,
+            &mut "+arg(z).\n+att(z,x).\n-att(x,y).\n".as_bytes(),
+            &mut mod_out,
+        )
+        .unwrap();
+        assert_eq!(
+            "+arg(a3).\n+att(a3,a1).\n-att(a1,a2).\n",
+            String::from_utf8(mod_out).unwrap()
+        );
+        let mut mapping_out = Vec::new();
+        write_mapping(&label_map, &mut mapping_out).unwrap();
+        assert_eq!(
+            "a1 x\na2 y\na3 z\n",
+            String::from_utf8(mapping_out).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_anonymize_modifications_unknown_argument() {
+        let framework = read_af("arg(x).\n");
+        let mut label_map = LabelMap::new(&framework);
+        assert!(anonymize_modifications(
+            &mut label_map,
+            &mut "+att(x,y).\n".as_bytes(),
+            &mut Vec::new(),
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,145 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::{info, AppSettings, Arg, Command, SubCommand, WithExitStatus};
+
+use super::exit_status::MALFORMED_INPUT;
+
+pub(crate) struct BatchCommand;
+
+const CMD_NAME: &str = "batch";
+
+const ARG_RECORD_FILES: &str = "RECORD_FILES";
+const ARG_OUTPUT_FILE: &str = "OUTPUT_FILE";
+
+impl BatchCommand {
+    pub fn new() -> Self {
+        BatchCommand
+    }
+}
+
+impl<'a> Command<'a> for BatchCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about(
+                "aggregates the run records written by the wrap command into a single results file",
+            )
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_RECORD_FILES)
+                    .index(1)
+                    .multiple(true)
+                    .help("the run records to aggregate (written by wrap --manifest)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_OUTPUT_FILE)
+                    .long("output-file")
+                    .short("o")
+                    .takes_value(true)
+                    .help("sets the results file (default: standard output)"),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let records = arg_matches
+            .values_of(ARG_RECORD_FILES)
+            .unwrap()
+            .map(|path| {
+                read_record(path)
+                    .with_context(|| format!(r#"while reading run record "{}""#, path))
+                    .with_exit_status(MALFORMED_INPUT)
+            })
+            .collect::<Result<Vec<String>>>()?;
+        let mut writer: Box<dyn Write> = match arg_matches.value_of(ARG_OUTPUT_FILE) {
+            Some(path) => Box::new(BufWriter::new(
+                File::create(path).with_context(|| format!(r#"while creating file "{}""#, path))?,
+            )),
+            None => Box::new(std::io::stdout()),
+        };
+        write_results(&mut writer, &records).context("while writing the results file")?;
+        info!("aggregated {} run record(s)", records.len());
+        Ok(())
+    }
+}
+
+/// Reads a run record, returning its JSON object.
+fn read_record(path: &str) -> Result<String> {
+    let content = std::fs::read_to_string(path)?;
+    let record = content.trim();
+    if !record.starts_with('{') || !record.ends_with('}') {
+        return Err(anyhow!("the file does not contain a JSON object"));
+    }
+    Ok(record.to_string())
+}
+
+/// Writes the results file, that is the array of the run records.
+fn write_results(writer: &mut dyn Write, records: &[String]) -> Result<()> {
+    writeln!(writer, "[")?;
+    for (i, record) in records.iter().enumerate() {
+        for (j, line) in record.lines().enumerate() {
+            let is_last_line = j == record.lines().count() - 1;
+            let separator = if is_last_line && i + 1 < records.len() {
+                ","
+            } else {
+                ""
+            };
+            writeln!(writer, "  {}{}", line, separator)?;
+        }
+    }
+    writeln!(writer, "]")?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_results() {
+        let records = vec![
+            "{\n  \"status\": 0\n}".to_string(),
+            "{\n  \"status\": 10\n}".to_string(),
+        ];
+        let mut out = Vec::new();
+        write_results(&mut out, &records).unwrap();
+        assert_eq!(
+            "[\n  {\n    \"status\": 0\n  },\n  {\n    \"status\": 10\n  }\n]\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_write_no_results() {
+        let mut out = Vec::new();
+        write_results(&mut out, &[]).unwrap();
+        assert_eq!("[\n]\n", String::from_utf8(out).unwrap());
+    }
+}
@@ -0,0 +1,614 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::{info, warn, AppSettings, Arg, Command, SubCommand, WithExitStatus};
+use crusti_arg::{
+    compression,
+    sat::{CdclSolver, Cnf},
+    solutions::SolutionReader,
+    solvers::{NaiveSolver, MAX_ARGUMENTS},
+    AAFramework, ArgumentSet, AspartixReader, InternedLabel, LabelInterner, LabelType, Semantics,
+};
+use rayon::prelude::*;
+
+use super::exit_status::{MALFORMED_INPUT, WRONG_ANSWER};
+
+pub(crate) struct CheckCommand;
+
+const CMD_NAME: &str = "check";
+
+const ARG_PROBLEM: &str = "PROBLEM";
+const ARG_INPUT_FILE: &str = "INPUT_FILE";
+const ARG_ANSWER_FILE: &str = "ANSWER_FILE";
+const ARG_JOBS: &str = "JOBS";
+
+impl CheckCommand {
+    pub fn new() -> Self {
+        CheckCommand
+    }
+}
+
+/// The answer kinds the checker is able to verify.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CheckedQuery {
+    SE(Semantics),
+    EE(Semantics),
+}
+
+impl CheckedQuery {
+    fn try_from_str(problem: &str) -> Result<Self> {
+        let err = || anyhow!(r#""{}" is not a problem the checker can handle"#, problem);
+        let splits = problem.split('-').collect::<Vec<&str>>();
+        if splits.len() != 2 {
+            return Err(err());
+        }
+        let semantics = splits[1].parse::<Semantics>().with_context(err)?;
+        match splits[0] {
+            "SE" => Ok(CheckedQuery::SE(semantics)),
+            "EE" => Ok(CheckedQuery::EE(semantics)),
+            _ => Err(err()),
+        }
+    }
+}
+
+/// An adjacency-indexed view of a framework used to check extensions.
+pub(crate) struct CheckedFramework<'a> {
+    framework: &'a AAFramework<String>,
+    attackers: Vec<Vec<usize>>,
+    attacked: Vec<Vec<usize>>,
+}
+
+impl<'a> CheckedFramework<'a> {
+    pub(crate) fn new(framework: &'a AAFramework<String>) -> Self {
+        let n_args = framework.argument_set().len();
+        let mut attackers = vec![vec![]; n_args];
+        let mut attacked = vec![vec![]; n_args];
+        for att in framework.iter_attacks() {
+            attackers[att.attacked().id()].push(att.attacker().id());
+            attacked[att.attacker().id()].push(att.attacked().id());
+        }
+        CheckedFramework {
+            framework,
+            attackers,
+            attacked,
+        }
+    }
+
+    pub(crate) fn membership<T>(&self, extension: &ArgumentSet<T>) -> Result<Vec<bool>>
+    where
+        T: LabelType + Borrow<str>,
+    {
+        let mut in_ext = vec![false; self.attackers.len()];
+        for arg in extension.iter() {
+            let id = self
+                .framework
+                .argument_set()
+                .get_argument_index::<str>(arg.label().borrow())?;
+            in_ext[id] = true;
+        }
+        Ok(in_ext)
+    }
+
+    pub(crate) fn attacked_by(&self, in_ext: &[bool]) -> Vec<bool> {
+        let mut result = vec![false; in_ext.len()];
+        in_ext
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| **b)
+            .for_each(|(i, _)| self.attacked[i].iter().for_each(|j| result[*j] = true));
+        result
+    }
+
+    fn is_conflict_free(&self, in_ext: &[bool]) -> bool {
+        (0..in_ext.len())
+            .filter(|i| in_ext[*i])
+            .all(|i| self.attackers[i].iter().all(|j| !in_ext[*j]))
+    }
+
+    fn defended(&self, ext_attacks: &[bool]) -> Vec<bool> {
+        (0..ext_attacks.len())
+            .map(|i| self.attackers[i].iter().all(|j| ext_attacks[*j]))
+            .collect()
+    }
+
+    pub(crate) fn grounded(&self) -> Vec<bool> {
+        self.framework.least_fixpoint()
+    }
+
+    /// Returns `true` iff an admissible set strictly includes the given one.
+    ///
+    /// Adding arguments one at a time is not enough, since a larger admissible set may need several new arguments to defend each other.
+    /// The existence of such a set is thus decided by the SAT solver, on a formula whose variables are the memberships of the arguments.
+    fn has_admissible_superset(&self, in_ext: &[bool]) -> bool {
+        let mut cnf = Cnf::default();
+        let vars = (0..in_ext.len())
+            .map(|_| cnf.new_var())
+            .collect::<Vec<i32>>();
+        (0..in_ext.len())
+            .filter(|i| in_ext[*i])
+            .for_each(|i| cnf.add_clause(&[vars[i]]));
+        cnf.add_clause(
+            &(0..in_ext.len())
+                .filter(|i| !in_ext[*i])
+                .map(|i| vars[i])
+                .collect::<Vec<i32>>(),
+        );
+        for (i, attackers) in self.attackers.iter().enumerate() {
+            for j in attackers.iter() {
+                cnf.add_clause(&[-vars[i], -vars[*j]]);
+                let mut defense = vec![-vars[i]];
+                defense.extend(self.attackers[*j].iter().map(|k| vars[*k]));
+                cnf.add_clause(&defense);
+            }
+        }
+        CdclSolver::new(&cnf).solve().is_some()
+    }
+
+    pub(crate) fn check(&self, semantics: Semantics, in_ext: &[bool]) -> Result<(), &'static str> {
+        if !self.is_conflict_free(in_ext) {
+            return Err("not conflict-free");
+        }
+        if semantics == Semantics::Stage {
+            return Ok(());
+        }
+        let ext_attacks = self.attacked_by(in_ext);
+        if semantics == Semantics::Stable {
+            return if (0..in_ext.len()).all(|i| in_ext[i] || ext_attacks[i]) {
+                Ok(())
+            } else {
+                Err("does not attack all the arguments outside of it")
+            };
+        }
+        let defended = self.defended(&ext_attacks);
+        if (0..in_ext.len()).any(|i| in_ext[i] && !defended[i]) {
+            return Err("not admissible");
+        }
+        if semantics == Semantics::Ideal {
+            return Ok(());
+        }
+        if (0..in_ext.len()).any(|i| !in_ext[i] && defended[i]) {
+            return Err("not complete");
+        }
+        if semantics == Semantics::Grounded && in_ext != self.grounded().as_slice() {
+            return Err("not the grounded extension");
+        }
+        if semantics == Semantics::Preferred && self.has_admissible_superset(in_ext) {
+            return Err("not preferred (a larger set is admissible)");
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Command<'a> for CheckCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about("checks the extensions given by a solver answer against a framework")
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_PROBLEM)
+                    .long("problem")
+                    .short("p")
+                    .takes_value(true)
+                    .help("sets the problem of the answer (SE-XX or EE-XX); EE answers are checked to contain all the extensions only for frameworks small enough for the naive solver, while only the given extensions are checked for larger ones")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_INPUT_FILE)
+                    .long("input-file")
+                    .short("f")
+                    .takes_value(true)
+                    .help("sets the input file containing the framework (Aspartix format)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_ANSWER_FILE)
+                    .long("answer")
+                    .short("r")
+                    .takes_value(true)
+                    .help("sets the file containing the solver answer")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_JOBS)
+                    .long("jobs")
+                    .short("j")
+                    .takes_value(true)
+                    .help("sets the number of threads used to check extensions (default: one per core)"),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let query = CheckedQuery::try_from_str(arg_matches.value_of(ARG_PROBLEM).unwrap())?;
+        let jobs = arg_matches
+            .value_of(ARG_JOBS)
+            .map(|j| j.parse::<usize>())
+            .transpose()
+            .context("while parsing the number of jobs")?;
+        let mut input_file = compression::open(arg_matches.value_of(ARG_INPUT_FILE).unwrap())
+            .context("while opening input file")
+            .with_exit_status(MALFORMED_INPUT)?;
+        let framework = AspartixReader::default()
+            .read(&mut input_file)
+            .context("while reading input file")
+            .with_exit_status(MALFORMED_INPUT)?;
+        info!("read a framework of {}", framework.summary());
+        let mut answer_br = BufReader::new(
+            File::open(arg_matches.value_of(ARG_ANSWER_FILE).unwrap())
+                .context("while opening answer file")?,
+        );
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs.unwrap_or(0))
+            .build()
+            .context("while building the thread pool")?;
+        let n_checked = pool
+            .install(|| check_answer(&framework, query, &mut answer_br))
+            .with_exit_status(WRONG_ANSWER)?;
+        info!("all the {} extension(s) are valid", n_checked);
+        Ok(())
+    }
+}
+
+fn check_answer(
+    framework: &AAFramework<String>,
+    query: CheckedQuery,
+    answer: &mut dyn BufRead,
+) -> Result<usize> {
+    let mut interner = LabelInterner::with_labels_of(framework.argument_set());
+    let reader = SolutionReader::default();
+    let (semantics, extensions, all_extensions) = match query {
+        CheckedQuery::SE(s) => (
+            s,
+            vec![reader.read_interned_extension(answer, &mut interner)?],
+            false,
+        ),
+        CheckedQuery::EE(s) => (
+            s,
+            reader.read_interned_extension_set(answer, &mut interner)?,
+            true,
+        ),
+    };
+    check_extensions(framework, semantics, &extensions, all_extensions)?;
+    Ok(extensions.len())
+}
+
+/// Checks the extensions of an answer, and whether they are all the extensions of the framework if `all_extensions` is set.
+///
+/// Since the answer is checked against the extensions computed by the naive solver, missing extensions are only detected for small frameworks.
+fn check_extensions(
+    framework: &AAFramework<String>,
+    semantics: Semantics,
+    extensions: &[ArgumentSet<InternedLabel>],
+    all_extensions: bool,
+) -> Result<()> {
+    let checked_framework = CheckedFramework::new(framework);
+    let memberships = extensions
+        .iter()
+        .enumerate()
+        .map(|(i, ext)| {
+            checked_framework
+                .membership(ext)
+                .with_context(|| format!("while checking extension #{}", i))
+        })
+        .collect::<Result<Vec<Vec<bool>>>>()?;
+    let mut first_occurrences = HashMap::with_capacity(memberships.len());
+    for (i, in_ext) in memberships.iter().enumerate() {
+        if let Some(j) = first_occurrences.insert(in_ext, i) {
+            return Err(anyhow!(
+                "extension #{} is a duplicate of extension #{}",
+                i,
+                j
+            ));
+        }
+    }
+    let maximality_failure = maximality_failure(semantics);
+    let unchecked = match (maximality_failure.is_some(), all_extensions) {
+        (true, true) => {
+            Some("the maximality of the extensions and the completeness of the answer are")
+        }
+        (true, false) => Some("the maximality of the extensions is"),
+        (false, true) => Some("the completeness of the answer is"),
+        (false, false) => None,
+    };
+    let exact_extensions = unchecked.and_then(|u| exact_extensions(framework, semantics, u));
+    let first_failure = memberships
+        .par_iter()
+        .enumerate()
+        .map(|(i, in_ext)| {
+            checked_framework
+                .check(semantics, in_ext)
+                .map_err(|e| anyhow!("extension #{} is {}", i, e))?;
+            match (&exact_extensions, maximality_failure) {
+                (Some(exact), Some(failure)) if !exact.contains(in_ext) => {
+                    Err(anyhow!("extension #{} is {}", i, failure))
+                }
+                _ => Ok(()),
+            }
+        })
+        .find_first(|r| r.is_err());
+    if let Some(e) = first_failure {
+        return e;
+    }
+    match &exact_extensions {
+        Some(exact) if all_extensions && memberships.len() < exact.len() => {
+            let missing = exact
+                .iter()
+                .find(|e| !first_occurrences.contains_key(e))
+                .unwrap();
+            let labels = missing
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| **b)
+                .map(|(i, _)| {
+                    framework
+                        .argument_set()
+                        .get_argument_by_id(i)
+                        .label()
+                        .as_str()
+                })
+                .collect::<Vec<&str>>();
+            Err(anyhow!(
+                "{} extension(s) are missing, including [{}]",
+                exact.len() - memberships.len(),
+                labels.join(",")
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns the failure message of the sets which are not extensions of the semantics whose maximality conditions cannot be checked locally (SST, STG and ID).
+fn maximality_failure(semantics: Semantics) -> Option<&'static str> {
+    match semantics {
+        Semantics::SemiStable => Some("not semi-stable (its range is not maximal)"),
+        Semantics::Ideal => Some("not the ideal extension"),
+        Semantics::Stage => Some("not a stage extension (its range is not maximal)"),
+        _ => None,
+    }
+}
+
+/// Computes the extensions of a semantics, to check the maximality conditions which cannot be checked locally and whether all the extensions are given.
+///
+/// The extensions are computed by the naive solver, and are thus only available for small frameworks; otherwise, a warning tells what is not checked.
+fn exact_extensions(
+    framework: &AAFramework<String>,
+    semantics: Semantics,
+    unchecked: &str,
+) -> Option<Vec<Vec<bool>>> {
+    match NaiveSolver::new(framework) {
+        Ok(solver) => Some(solver.extensions(semantics)),
+        Err(_) => {
+            warn!(
+                "the framework has more than {} arguments; {} not checked",
+                MAX_ARGUMENTS, unchecked
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_af(s: &str) -> AAFramework<String> {
+        AspartixReader::default().read(&mut s.as_bytes()).unwrap()
+    }
+
+    const INSTANCE: &str = "arg(a).\narg(b).\narg(c).\natt(a,b).\natt(b,a).\natt(b,c).\n";
+
+    #[test]
+    fn test_check_answer_ee_pr_ok() {
+        let af = read_af(INSTANCE);
+        let mut answer = "[\n[a,c]\n[b]\n]\n".as_bytes();
+        assert_eq!(
+            2,
+            check_answer(&af, CheckedQuery::EE(Semantics::Preferred), &mut answer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_check_answer_ee_co_not_complete() {
+        let af = read_af(INSTANCE);
+        let mut answer = "[\n[]\n[a]\n]\n".as_bytes();
+        assert_eq!(
+            "extension #1 is not complete",
+            check_answer(&af, CheckedQuery::EE(Semantics::Complete), &mut answer)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_check_answer_pr_not_maximal() {
+        let af = read_af("arg(a).\narg(b).\natt(a,b).\natt(b,a).\n");
+        let mut answer = "[]\n".as_bytes();
+        assert_eq!(
+            "extension #0 is not preferred (a larger set is admissible)",
+            check_answer(&af, CheckedQuery::SE(Semantics::Preferred), &mut answer)
+                .unwrap_err()
+                .to_string()
+        );
+        let mut answer = "[\n[]\n]\n".as_bytes();
+        assert!(check_answer(&af, CheckedQuery::EE(Semantics::Preferred), &mut answer).is_err());
+    }
+
+    #[test]
+    fn test_check_answer_pr_superset_of_several_arguments() {
+        // [] is complete; [a, b] is admissible, but neither [a] nor [b] are
+        let af = read_af("arg(a).\narg(b).\narg(c).\narg(d).\natt(c,a).\natt(d,b).\natt(b,c).\natt(a,d).\natt(c,d).\natt(d,c).\n");
+        let mut answer = "[]\n".as_bytes();
+        assert!(check_answer(&af, CheckedQuery::SE(Semantics::Preferred), &mut answer).is_err());
+        let mut answer = "[a,b]\n".as_bytes();
+        assert_eq!(
+            1,
+            check_answer(&af, CheckedQuery::SE(Semantics::Preferred), &mut answer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_check_answer_ee_missing_extensions() {
+        let af = read_af(INSTANCE);
+        for (semantics, answer) in &[
+            (Semantics::Preferred, "[\n[b]\n]\n"),
+            (Semantics::Complete, "[\n[]\n[b]\n]\n"),
+            (Semantics::Stable, "[\n[b]\n]\n"),
+        ] {
+            assert_eq!(
+                "1 extension(s) are missing, including [a,c]",
+                check_answer(&af, CheckedQuery::EE(*semantics), &mut answer.as_bytes())
+                    .map_err(|e| e.to_string())
+                    .unwrap_err(),
+                "{} {}",
+                semantics,
+                answer
+            );
+        }
+        let mut answer = "[\n]\n".as_bytes();
+        assert_eq!(
+            "2 extension(s) are missing, including [a,c]",
+            check_answer(&af, CheckedQuery::EE(Semantics::Stable), &mut answer)
+                .unwrap_err()
+                .to_string()
+        );
+        let mut answer = "[\n[]\n[b]\n[a,c]\n]\n".as_bytes();
+        assert_eq!(
+            3,
+            check_answer(&af, CheckedQuery::EE(Semantics::Complete), &mut answer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_check_answer_ee_duplicate() {
+        let af = read_af(INSTANCE);
+        let mut answer = "[\n[a,c]\n[b]\n[c,a]\n]\n".as_bytes();
+        assert_eq!(
+            "extension #2 is a duplicate of extension #0",
+            check_answer(&af, CheckedQuery::EE(Semantics::Preferred), &mut answer)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_check_answer_se_st_ok() {
+        let af = read_af(INSTANCE);
+        let mut answer = "[b]\n".as_bytes();
+        assert_eq!(
+            1,
+            check_answer(&af, CheckedQuery::SE(Semantics::Stable), &mut answer).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_check_answer_se_gr_wrong() {
+        let af = read_af(INSTANCE);
+        let mut answer = "[b]\n".as_bytes();
+        assert!(check_answer(&af, CheckedQuery::SE(Semantics::Grounded), &mut answer).is_err());
+    }
+
+    #[test]
+    fn test_check_answer_conflict() {
+        let af = read_af(INSTANCE);
+        let mut answer = "[a, b]\n".as_bytes();
+        assert_eq!(
+            "extension #0 is not conflict-free",
+            check_answer(&af, CheckedQuery::SE(Semantics::Stage), &mut answer)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_check_answer_unknown_argument() {
+        let af = read_af(INSTANCE);
+        let mut answer = "[d]\n".as_bytes();
+        assert!(check_answer(&af, CheckedQuery::SE(Semantics::Complete), &mut answer).is_err());
+    }
+
+    #[test]
+    fn test_check_answer_sst_range_not_maximal() {
+        // [b] is complete, but the range of [a, c] is larger
+        let af = read_af("arg(a).\narg(b).\narg(c).\narg(d).\narg(e).\natt(a,b).\natt(b,a).\natt(b,c).\natt(c,d).\natt(e,e).\natt(e,d).\n");
+        let mut answer = "[\n[a,c]\n[b]\n]\n".as_bytes();
+        assert_eq!(
+            "extension #1 is not semi-stable (its range is not maximal)",
+            check_answer(&af, CheckedQuery::EE(Semantics::SemiStable), &mut answer)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_check_answer_stg() {
+        // [a, d] is conflict-free, but the range of [b, d] is larger
+        let af = read_af("arg(a).\narg(b).\narg(c).\narg(d).\narg(e).\natt(a,b).\natt(b,a).\natt(b,c).\natt(c,d).\natt(e,e).\natt(e,d).\n");
+        let mut answer = "[\n[a,c]\n[b,d]\n]\n".as_bytes();
+        assert_eq!(
+            2,
+            check_answer(&af, CheckedQuery::EE(Semantics::Stage), &mut answer).unwrap()
+        );
+        let mut answer = "[a,d]\n".as_bytes();
+        assert_eq!(
+            "extension #0 is not a stage extension (its range is not maximal)",
+            check_answer(&af, CheckedQuery::SE(Semantics::Stage), &mut answer)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_check_answer_id() {
+        let af = read_af("arg(a).\narg(b).\narg(c).\narg(d).\natt(a,b).\natt(b,a).\natt(a,c).\natt(b,c).\natt(c,d).\natt(d,c).\n");
+        let mut answer = "[d]\n".as_bytes();
+        assert_eq!(
+            1,
+            check_answer(&af, CheckedQuery::SE(Semantics::Ideal), &mut answer).unwrap()
+        );
+        let mut answer = "[]\n".as_bytes();
+        assert_eq!(
+            "extension #0 is not the ideal extension",
+            check_answer(&af, CheckedQuery::SE(Semantics::Ideal), &mut answer)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_checked_query_from_str() {
+        assert_eq!(
+            CheckedQuery::EE(Semantics::Preferred),
+            CheckedQuery::try_from_str("EE-PR").unwrap()
+        );
+        assert!(CheckedQuery::try_from_str("DC-PR").is_err());
+        assert!(CheckedQuery::try_from_str("EE-PR-D").is_err());
+        assert!(CheckedQuery::try_from_str("EE-XX").is_err());
+    }
+}
@@ -0,0 +1,147 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    fs::File,
+    io::{BufWriter, Read, Write},
+};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_arg::{AAFramework, AspartixReader, AspartixWriter};
+
+use super::framework_state::FrameworkState;
+use super::temp_files::{TempFile, TempFiles};
+
+/// A checkpoint of a `wrap` run, made of the index of the next step to solve and the framework at this step.
+///
+/// Checkpoints are stored as a `step n` line followed by the framework in the Aspartix format.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Checkpoint {
+    next_step: usize,
+    state: FrameworkState,
+}
+
+impl Checkpoint {
+    /// Builds the checkpoint of a run that has not begun yet.
+    pub(crate) fn new(framework: &AAFramework<String>) -> Self {
+        Checkpoint {
+            next_step: 0,
+            state: FrameworkState::new(framework),
+        }
+    }
+
+    pub(crate) fn next_step(&self) -> usize {
+        self.next_step
+    }
+
+    /// Marks the current step as solved, applying the modification leading to the next step if there is one.
+    pub(crate) fn advance(&mut self, modification: Option<&str>) -> Result<()> {
+        if let Some(m) = modification {
+            self.state
+                .apply(m)
+                .with_context(|| format!("while applying modification {}", self.next_step + 1))?;
+        }
+        self.next_step += 1;
+        Ok(())
+    }
+
+    pub(crate) fn read(reader: &mut dyn Read) -> Result<Self> {
+        let mut content = String::new();
+        reader
+            .read_to_string(&mut content)
+            .context("while reading the checkpoint")?;
+        let (header, framework) = content.split_at(content.find('\n').unwrap_or(content.len()));
+        let next_step = header
+            .trim()
+            .strip_prefix("step ")
+            .and_then(|n| n.trim().parse::<usize>().ok())
+            .ok_or_else(|| anyhow!(r#"expected a "step n" header, found "{}""#, header.trim()))
+            .context("while reading the checkpoint")?;
+        let framework = AspartixReader::default()
+            .read(&mut framework.as_bytes())
+            .context("while reading the checkpointed framework")?;
+        Ok(Checkpoint {
+            next_step,
+            state: FrameworkState::new(&framework),
+        })
+    }
+
+    pub(crate) fn write(&self, writer: &mut dyn Write) -> Result<()> {
+        writeln!(writer, "step {}", self.next_step)?;
+        AspartixWriter::default().write(&self.state.to_framework()?, writer)
+    }
+
+    /// Saves the checkpoint to a file.
+    ///
+    /// The checkpoint is first written to a temporary file which is then renamed, so that an interruption never leaves a partial checkpoint.
+    pub(crate) fn save(&self, path: &str) -> Result<()> {
+        let context = || format!(r#"while saving the checkpoint to "{}""#, path);
+        let tmp_path = format!("{}.tmp", path);
+        let mut writer = BufWriter::new(File::create(&tmp_path).with_context(context)?);
+        self.write(&mut writer).with_context(context)?;
+        drop(writer);
+        std::fs::rename(&tmp_path, path).with_context(context)
+    }
+
+    /// Writes the framework of the checkpoint into a temporary Aspartix file, used as the initial framework of a resumed run.
+    pub(crate) fn write_instance(&self, temp_files: &TempFiles) -> Result<TempFile> {
+        let (instance, file) = temp_files
+            .create(&format!("resumed-step-{}.apx", self.next_step))
+            .context("while creating the resumed instance")?;
+        let mut writer = BufWriter::new(file);
+        AspartixWriter::default()
+            .write(&self.state.to_framework()?, &mut writer)
+            .context("while writing the resumed instance")?;
+        Ok(instance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_af(s: &str) -> AAFramework<String> {
+        AspartixReader::default().read(&mut s.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_write_read() {
+        let mut checkpoint = Checkpoint::new(&read_af("arg(a).\narg(b).\natt(a,b).\n"));
+        checkpoint.advance(Some("+arg(c).")).unwrap();
+        checkpoint.advance(Some("+att(c,c).")).unwrap();
+        assert_eq!(2, checkpoint.next_step());
+        let mut out = Vec::new();
+        checkpoint.write(&mut out).unwrap();
+        let written = String::from_utf8(out).unwrap();
+        assert_eq!(
+            "step 2\narg(a).\narg(b).\narg(c).\natt(a,b).\natt(c,c).\n",
+            written
+        );
+        assert_eq!(
+            checkpoint,
+            Checkpoint::read(&mut written.as_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_read_wrong_header() {
+        assert!(Checkpoint::read(&mut "arg(a).\n".as_bytes()).is_err());
+        assert!(Checkpoint::read(&mut "step x\narg(a).\n".as_bytes()).is_err());
+    }
+}
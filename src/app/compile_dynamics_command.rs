@@ -0,0 +1,406 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    collections::HashSet,
+    io::{BufRead, BufReader, BufWriter, Write},
+};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::{info, AppSettings, Arg, Command, SubCommand};
+use crusti_arg::{
+    compression,
+    dynamics::{self, Modification},
+    AAFramework, AspartixReader,
+};
+
+pub(crate) struct CompileDynamicsCommand;
+
+const CMD_NAME: &str = "compile-dynamics";
+
+const ARG_INPUT_FILE: &str = "INPUT_FILE";
+const ARG_CHANGE_FILE: &str = "CHANGE_FILE";
+const ARG_OUTPUT_FILE: &str = "OUTPUT_FILE";
+
+impl CompileDynamicsCommand {
+    pub fn new() -> Self {
+        CompileDynamicsCommand
+    }
+}
+
+impl<'a> Command<'a> for CompileDynamicsCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about("compiles high-level changes of a framework into a modification file")
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_INPUT_FILE)
+                    .long("input-file")
+                    .short("f")
+                    .takes_value(true)
+                    .help("sets the input file containing the initial framework (Aspartix format)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_CHANGE_FILE)
+                    .long("changes")
+                    .short("c")
+                    .takes_value(true)
+                    .help("sets the file containing the high-level changes, one per line")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_OUTPUT_FILE)
+                    .long("output-file")
+                    .short("o")
+                    .takes_value(true)
+                    .help("sets the file in which the modifications are written (default: standard output)"),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let framework = AspartixReader::default()
+            .read(
+                &mut compression::open(arg_matches.value_of(ARG_INPUT_FILE).unwrap())
+                    .context("while opening input file")?,
+            )
+            .context("while reading input file")?;
+        let mut changes = BufReader::new(
+            compression::open(arg_matches.value_of(ARG_CHANGE_FILE).unwrap())
+                .context("while opening change file")?,
+        );
+        let mut writer: Box<dyn Write> = match arg_matches.value_of(ARG_OUTPUT_FILE) {
+            Some(path) => Box::new(BufWriter::new(
+                compression::create(path)
+                    .with_context(|| format!(r#"while creating file "{}""#, path))?,
+            )),
+            None => Box::new(std::io::stdout()),
+        };
+        let n_modifications = compile_dynamics(
+            &mut DynamicFramework::new(&framework),
+            &mut changes,
+            &mut writer,
+        )?;
+        info!("wrote {} modification(s)", n_modifications);
+        Ok(())
+    }
+}
+
+/// A high-level change of a dynamic framework.
+#[derive(Debug, PartialEq)]
+enum Change {
+    /// A modification given as in a dynamics file.
+    Modification(Modification<String>),
+    /// The removal of an argument and all the attacks it is involved in.
+    RemoveArgument(String),
+    /// Sets the first argument as strictly preferred to the second one.
+    Prefer(String, String),
+    /// Removes the preference of the first argument over the second one.
+    Unprefer(String, String),
+    /// Replaces the preference of the first argument over the second one by the opposite preference.
+    SwapPreference(String, String),
+}
+
+fn read_change(line: &str) -> Result<Change> {
+    if let Ok(m) = dynamics::read_modification(line) {
+        return Ok(Change::Modification(m));
+    }
+    let wrong_change = || anyhow!(r#"expected a change, found "{}""#, line.trim());
+    let trimmed = line.trim().strip_suffix(").").ok_or_else(wrong_change)?;
+    let (name, params) = trimmed.split_at(trimmed.find('(').ok_or_else(wrong_change)?);
+    let params = params[1..]
+        .split(',')
+        .map(|p| p.trim().to_string())
+        .collect::<Vec<String>>();
+    if params.iter().any(|p| p.is_empty()) {
+        return Err(wrong_change());
+    }
+    match (name.trim(), params.as_slice()) {
+        ("remove_argument", [a]) => Ok(Change::RemoveArgument(a.clone())),
+        ("prefer", [a, b]) => Ok(Change::Prefer(a.clone(), b.clone())),
+        ("unprefer", [a, b]) => Ok(Change::Unprefer(a.clone(), b.clone())),
+        ("swap_preference", [a, b]) => Ok(Change::SwapPreference(a.clone(), b.clone())),
+        _ => Err(wrong_change()),
+    }
+}
+
+/// The state of a framework subject to changes, used to compute the modifications each change implies.
+///
+/// An attack from `a` to `b` is given to the solver unless `b` is strictly preferred to `a`.
+struct DynamicFramework {
+    arguments: HashSet<String>,
+    attacks: Vec<(String, String)>,
+    preferences: HashSet<(String, String)>,
+}
+
+impl DynamicFramework {
+    fn new(framework: &AAFramework<String>) -> Self {
+        DynamicFramework {
+            arguments: framework
+                .argument_set()
+                .iter()
+                .map(|a| a.label().clone())
+                .collect(),
+            attacks: framework
+                .iter_attacks()
+                .map(|att| {
+                    (
+                        att.attacker().label().clone(),
+                        att.attacked().label().clone(),
+                    )
+                })
+                .collect(),
+            preferences: HashSet::new(),
+        }
+    }
+
+    fn check_argument(&self, label: &str) -> Result<()> {
+        if self.arguments.contains(label) {
+            Ok(())
+        } else {
+            Err(anyhow!("no such argument: {}", label))
+        }
+    }
+
+    fn has_attack(&self, from: &str, to: &str) -> bool {
+        self.attacks.iter().any(|(a, b)| a == from && b == to)
+    }
+
+    fn is_defeat(&self, from: &str, to: &str) -> bool {
+        !self
+            .preferences
+            .contains(&(to.to_string(), from.to_string()))
+    }
+
+    fn apply(&mut self, change: Change) -> Result<Vec<Modification<String>>> {
+        match change {
+            Change::Modification(m) => self.apply_modification(m),
+            Change::RemoveArgument(a) => {
+                self.check_argument(&a)?;
+                let mut modifications = self
+                    .attacks
+                    .iter()
+                    .filter(|(from, to)| (*from == a || *to == a) && self.is_defeat(from, to))
+                    .map(|(from, to)| Modification::RemoveAttack(from.clone(), to.clone()))
+                    .collect::<Vec<Modification<String>>>();
+                self.remove_argument(&a);
+                modifications.push(Modification::RemoveArgument(a));
+                Ok(modifications)
+            }
+            Change::Prefer(a, b) => {
+                self.check_argument(&a)?;
+                self.check_argument(&b)?;
+                let context = || format!("cannot prefer {} to {}", a, b);
+                if a == b {
+                    return Err(anyhow!("an argument cannot be preferred to itself"))
+                        .with_context(context);
+                }
+                if self.preferences.contains(&(b.clone(), a.clone())) {
+                    return Err(anyhow!("the reverse preference is set")).with_context(context);
+                }
+                let was_defeat = self.is_defeat(&b, &a);
+                self.preferences.insert((a.clone(), b.clone()));
+                Ok(if was_defeat && self.has_attack(&b, &a) {
+                    vec![Modification::RemoveAttack(b, a)]
+                } else {
+                    vec![]
+                })
+            }
+            Change::Unprefer(a, b) => {
+                if !self.preferences.remove(&(a.clone(), b.clone())) {
+                    return Err(anyhow!("{} is not preferred to {}", a, b));
+                }
+                Ok(if self.has_attack(&b, &a) {
+                    vec![Modification::AddAttack(b, a)]
+                } else {
+                    vec![]
+                })
+            }
+            Change::SwapPreference(a, b) => {
+                let mut modifications = self.apply(Change::Unprefer(a.clone(), b.clone()))?;
+                modifications.append(&mut self.apply(Change::Prefer(b, a))?);
+                Ok(modifications)
+            }
+        }
+    }
+
+    fn apply_modification(
+        &mut self,
+        modification: Modification<String>,
+    ) -> Result<Vec<Modification<String>>> {
+        match &modification {
+            Modification::AddArgument(a) => {
+                if !self.arguments.insert(a.clone()) {
+                    return Err(anyhow!("argument {} already exists", a));
+                }
+            }
+            Modification::RemoveArgument(a) => {
+                self.check_argument(a)?;
+                self.remove_argument(a);
+            }
+            Modification::AddAttack(a, b) => {
+                self.check_argument(a)?;
+                self.check_argument(b)?;
+                if self.has_attack(a, b) {
+                    return Err(anyhow!("attack {} → {} already exists", a, b));
+                }
+                self.attacks.push((a.clone(), b.clone()));
+                if !self.is_defeat(a, b) {
+                    return Ok(vec![]);
+                }
+            }
+            Modification::RemoveAttack(a, b) => {
+                if !self.has_attack(a, b) {
+                    return Err(anyhow!("no such attack: {} → {}", a, b));
+                }
+                self.attacks.retain(|(from, to)| from != a || to != b);
+                if !self.is_defeat(a, b) {
+                    return Ok(vec![]);
+                }
+            }
+        }
+        Ok(vec![modification])
+    }
+
+    fn remove_argument(&mut self, label: &str) {
+        self.arguments.remove(label);
+        self.attacks.retain(|(a, b)| a != label && b != label);
+        self.preferences.retain(|(a, b)| a != label && b != label);
+    }
+}
+
+fn compile_dynamics(
+    framework: &mut DynamicFramework,
+    changes: &mut dyn BufRead,
+    writer: &mut dyn Write,
+) -> Result<usize> {
+    let mut n_modifications = 0;
+    for (i, l) in changes.lines().enumerate() {
+        let context = || format!("while compiling line {} of the change file", i + 1);
+        let line = l.with_context(context)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let change = read_change(&line).with_context(context)?;
+        for m in framework.apply(change).with_context(context)? {
+            writeln!(writer, "{}", m).with_context(context)?;
+            n_modifications += 1;
+        }
+    }
+    writer
+        .flush()
+        .context("while writing the compiled modifications")?;
+    Ok(n_modifications)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile(af: &str, changes: &str) -> Result<String> {
+        let framework = AspartixReader::default().read(&mut af.as_bytes())?;
+        let mut out = Vec::new();
+        compile_dynamics(
+            &mut DynamicFramework::new(&framework),
+            &mut changes.as_bytes(),
+            &mut out,
+        )?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    const AF: &str = "arg(a).\narg(b).\narg(c).\natt(a,b).\natt(b,a).\natt(c,b).\n";
+
+    #[test]
+    fn test_read_change() {
+        assert_eq!(
+            Change::Modification(Modification::AddArgument("d".to_string())),
+            read_change("+arg(d).").unwrap()
+        );
+        assert_eq!(
+            Change::RemoveArgument("a".to_string()),
+            read_change(" remove_argument( a ). ").unwrap()
+        );
+        assert_eq!(
+            Change::SwapPreference("a".to_string(), "b".to_string()),
+            read_change("swap_preference(a,b).").unwrap()
+        );
+        [
+            "prefer(a).",
+            "remove_argument(a,b).",
+            "foo(a).",
+            "prefer(a,).",
+            "prefer(a,b)",
+        ]
+        .iter()
+        .for_each(|l| assert!(read_change(l).is_err(), "{}", l));
+    }
+
+    #[test]
+    fn test_remove_argument_cascade() {
+        assert_eq!(
+            "-att(a,b).\n-att(b,a).\n-att(c,b).\n-arg(b).\n",
+            compile(AF, "remove_argument(b).\n").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_preferences() {
+        assert_eq!(
+            "-att(b,a).\n-att(c,b).\n+att(b,a).\n-att(a,b).\n-arg(c).\n",
+            compile(
+                AF,
+                "prefer(a,b).\nprefer(b,c).\nswap_preference(a,b).\n-arg(c).\n"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_modifications_under_preferences() {
+        assert_eq!(
+            "-att(b,a).\n+arg(d).\n+att(d,a).\n",
+            compile(
+                AF,
+                "prefer(a,b).\n-att(b,a).\n+arg(d).\n+att(b,a).\n+att(d,a).\n"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        [
+            "+arg(a).",
+            "-arg(d).",
+            "+att(a,b).",
+            "-att(a,c).",
+            "+att(a,d).",
+            "prefer(a,a).",
+            "prefer(a,b).\nprefer(b,a).",
+            "unprefer(a,b).",
+            "remove_argument(d).",
+        ]
+        .iter()
+        .for_each(|c| assert!(compile(AF, c).is_err(), "{}", c));
+    }
+}
@@ -0,0 +1,139 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+/// The runtime used to run solvers inside containers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ContainerRuntime {
+    Docker,
+    Apptainer,
+}
+
+/// A container image in which the solver runs, with the host files made available to it.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Container {
+    runtime: ContainerRuntime,
+    image: String,
+    mounts: Vec<PathBuf>,
+}
+
+impl Container {
+    /// Builds a new container description from a runtime and an image.
+    pub(crate) fn new(runtime: ContainerRuntime, image: &str) -> Self {
+        Container {
+            runtime,
+            image: image.to_string(),
+            mounts: vec![],
+        }
+    }
+
+    /// Makes a host file available inside the container, at the same path.
+    ///
+    /// The absolute path of the file is returned, as relative paths cannot be resolved inside the container.
+    pub(crate) fn bind_file(&mut self, path: &str) -> Result<String> {
+        let absolute = Path::new(path)
+            .canonicalize()
+            .with_context(|| format!(r#"while resolving the path of "{}""#, path))?;
+        let absolute_str = absolute.to_string_lossy().to_string();
+        if !self.mounts.contains(&absolute) {
+            self.mounts.push(absolute);
+        }
+        Ok(absolute_str)
+    }
+
+    /// Returns the program and the arguments running the solver inside the container.
+    ///
+    /// The container is run interactively, so that the dynamics are still exchanged through the standard streams of the solver.
+    pub(crate) fn wrap(&self, solver: &str, arguments: &[String]) -> (String, Vec<String>) {
+        let mut container_arguments = vec![];
+        match self.runtime {
+            ContainerRuntime::Docker => {
+                container_arguments.push("run".to_string());
+                container_arguments.push("-i".to_string());
+                container_arguments.push("--rm".to_string());
+                self.mounts.iter().for_each(|m| {
+                    container_arguments.push("-v".to_string());
+                    container_arguments.push(format!("{0}:{0}:ro", m.to_string_lossy()));
+                });
+            }
+            ContainerRuntime::Apptainer => {
+                container_arguments.push("exec".to_string());
+                self.mounts.iter().for_each(|m| {
+                    container_arguments.push("--bind".to_string());
+                    container_arguments.push(format!("{0}:{0}:ro", m.to_string_lossy()));
+                });
+            }
+        }
+        container_arguments.push(self.image.clone());
+        container_arguments.push(solver.to_string());
+        container_arguments.extend(arguments.iter().cloned());
+        let program = match self.runtime {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Apptainer => "apptainer",
+        };
+        (program.to_string(), container_arguments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_docker() {
+        let mut container = Container::new(ContainerRuntime::Docker, "solver:latest");
+        let instance = container.bind_file("Cargo.toml").unwrap();
+        assert!(Path::new(&instance).is_absolute());
+        container.bind_file("./Cargo.toml").unwrap();
+        let (program, arguments) =
+            container.wrap("/opt/solver", &["-f".to_string(), instance.clone()]);
+        assert_eq!("docker", program);
+        assert_eq!(
+            vec![
+                "run".to_string(),
+                "-i".to_string(),
+                "--rm".to_string(),
+                "-v".to_string(),
+                format!("{0}:{0}:ro", instance),
+                "solver:latest".to_string(),
+                "/opt/solver".to_string(),
+                "-f".to_string(),
+                instance,
+            ],
+            arguments
+        );
+    }
+
+    #[test]
+    fn test_wrap_apptainer() {
+        let container = Container::new(ContainerRuntime::Apptainer, "solver.sif");
+        let (program, arguments) = container.wrap("solver", &["-p".to_string()]);
+        assert_eq!("apptainer", program);
+        assert_eq!(vec!["exec", "solver.sif", "solver", "-p"], arguments);
+    }
+
+    #[test]
+    fn test_bind_missing_file() {
+        let mut container = Container::new(ContainerRuntime::Docker, "solver");
+        assert!(container.bind_file("/does/not/exist.apx").is_err());
+    }
+}
@@ -0,0 +1,219 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+use anyhow::{Context, Result};
+use crusti_app_helper::{info, AppSettings, Arg, Command, SubCommand};
+use crusti_arg::{
+    compression::{self, CompressedWriter},
+    AspartixReader, NumericWriter,
+};
+
+use super::translation::{LabelMap, NUMERIC_FORMAT};
+
+pub(crate) struct ConvertDynamicsCommand;
+
+const CMD_NAME: &str = "convert-dynamics";
+
+const ARG_INPUT_FILE: &str = "INPUT_FILE";
+const ARG_MODIFICATION_FILE: &str = "MODIFICATION_FILE";
+const ARG_OUTPUT_MODIFICATION_FILE: &str = "OUTPUT_MODIFICATION_FILE";
+const ARG_TO: &str = "TO";
+const ARG_OUTPUT_INSTANCE: &str = "OUTPUT_INSTANCE";
+
+const APX_DIALECT: &str = "apx";
+
+impl ConvertDynamicsCommand {
+    pub fn new() -> Self {
+        ConvertDynamicsCommand
+    }
+}
+
+impl<'a> Command<'a> for ConvertDynamicsCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about("translates a modification file between the Aspartix and the numeric dialects")
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_INPUT_FILE)
+                    .long("input-file")
+                    .short("f")
+                    .takes_value(true)
+                    .help("sets the input file containing the framework (Aspartix format)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_MODIFICATION_FILE)
+                    .long("modification")
+                    .short("m")
+                    .takes_value(true)
+                    .help("sets the modification file to translate")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_OUTPUT_MODIFICATION_FILE)
+                    .long("output-modification")
+                    .short("n")
+                    .takes_value(true)
+                    .help("sets the file in which the translated dynamics is written")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_TO)
+                    .long("to")
+                    .takes_value(true)
+                    .possible_values(&[NUMERIC_FORMAT, APX_DIALECT])
+                    .default_value(NUMERIC_FORMAT)
+                    .help("sets the dialect of the translated dynamics"),
+            )
+            .arg(
+                Arg::with_name(ARG_OUTPUT_INSTANCE)
+                    .long("output-instance")
+                    .short("o")
+                    .takes_value(true)
+                    .help("sets a file in which the framework is written in the numeric format"),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let framework = AspartixReader::default()
+            .read(
+                &mut compression::open(arg_matches.value_of(ARG_INPUT_FILE).unwrap())
+                    .context("while opening input file")?,
+            )
+            .context("while reading input file")?;
+        if let Some(path) = arg_matches.value_of(ARG_OUTPUT_INSTANCE) {
+            let mut writer = create_file(path)?;
+            NumericWriter::default()
+                .write(&framework, &mut writer)
+                .context("while writing the translated instance")?;
+        }
+        let mut label_map = LabelMap::new(&framework);
+        let mut mod_br = BufReader::new(
+            compression::open(arg_matches.value_of(ARG_MODIFICATION_FILE).unwrap())
+                .context("while opening modification file")?,
+        );
+        let mut mod_writer =
+            create_file(arg_matches.value_of(ARG_OUTPUT_MODIFICATION_FILE).unwrap())?;
+        let to_numeric = arg_matches.value_of(ARG_TO).unwrap() == NUMERIC_FORMAT;
+        let n_steps =
+            convert_modifications(&mut label_map, to_numeric, &mut mod_br, &mut mod_writer)?;
+        info!("translated {} modification(s)", n_steps);
+        Ok(())
+    }
+}
+
+fn create_file(path: &str) -> Result<BufWriter<CompressedWriter>> {
+    Ok(BufWriter::new(compression::create(path).with_context(
+        || format!(r#"while creating file "{}""#, path),
+    )?))
+}
+
+fn convert_modifications(
+    label_map: &mut LabelMap,
+    to_numeric: bool,
+    modifications: &mut dyn BufRead,
+    writer: &mut dyn Write,
+) -> Result<usize> {
+    let mut n_steps = 0;
+    for (i, l) in modifications.lines().enumerate() {
+        let context = || format!("while translating line {} of the modification file", i + 1);
+        let mod_line = l.with_context(context)?;
+        let mod_line = mod_line.trim_end_matches('\r');
+        if mod_line.is_empty() {
+            break;
+        }
+        let translated = if to_numeric {
+            label_map.translate_modification(mod_line)
+        } else {
+            label_map.restore_modification(mod_line)
+        }
+        .with_context(context)?;
+        writeln!(writer, "{}", translated).with_context(context)?;
+        n_steps += 1;
+    }
+    writer
+        .flush()
+        .context("while writing the translated modifications")?;
+    Ok(n_steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label_map(s: &str) -> LabelMap {
+        LabelMap::new(&AspartixReader::default().read(&mut s.as_bytes()).unwrap())
+    }
+
+    fn convert(af: &str, to_numeric: bool, modifications: &str) -> Result<String> {
+        let mut out = Vec::new();
+        convert_modifications(
+            &mut label_map(af),
+            to_numeric,
+            &mut modifications.as_bytes(),
+            &mut out,
+        )?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_convert_to_numeric() {
+        assert_eq!(
+            "+arg(3).\n+att(3,1).\n-att(1,2).\n",
+            convert(
+                "arg(x).\narg(y).\natt(x,y).\n",
+                true,
+                "+arg(z).\n+att(z,x).\n-att(x,y).\n\n+arg(w).\n"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_convert_round_trip() {
+        let af = "arg(x).\narg(y).\natt(x,y).\n";
+        let dynamics = "-arg(y).\n+arg(y).\n+att(y,x).\n";
+        let numeric = convert(af, true, dynamics).unwrap();
+        assert_eq!("-arg(2).\n+arg(2).\n+att(2,1).\n", numeric);
+        assert_eq!(dynamics, convert(af, false, &numeric).unwrap());
+    }
+
+    #[test]
+    fn test_convert_to_apx_new_argument() {
+        assert_eq!(
+            "+arg(a2).\n+att(a2,x).\n",
+            convert("arg(x).\n", false, "+arg(2).\n+att(2,1).\n").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_convert_unknown_identifier() {
+        let err = convert("arg(x).\n", false, "+att(1,1).\n+att(1,3).\n").unwrap_err();
+        assert_eq!(
+            "while translating line 2 of the modification file",
+            err.to_string()
+        );
+    }
+}
@@ -0,0 +1,202 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    collections::HashSet,
+    io::{BufWriter, Write},
+};
+
+use anyhow::{Context, Result};
+use crusti_app_helper::{info, AppSettings, Arg, Command, SubCommand};
+use crusti_arg::{compression, dynamics::Modification, AAFramework, AspartixReader};
+
+pub(crate) struct DiffCommand;
+
+const CMD_NAME: &str = "diff";
+
+const ARG_INPUT_FILE: &str = "INPUT_FILE";
+const ARG_TARGET_FILE: &str = "TARGET_FILE";
+const ARG_OUTPUT_FILE: &str = "OUTPUT_FILE";
+
+impl DiffCommand {
+    pub fn new() -> Self {
+        DiffCommand
+    }
+}
+
+impl<'a> Command<'a> for DiffCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about("computes the modification file transforming a framework into another one")
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_INPUT_FILE)
+                    .long("input-file")
+                    .short("f")
+                    .takes_value(true)
+                    .help("sets the input file containing the initial framework (Aspartix format)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_TARGET_FILE)
+                    .long("target-file")
+                    .short("t")
+                    .takes_value(true)
+                    .help("sets the file containing the framework to obtain (Aspartix format)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_OUTPUT_FILE)
+                    .long("output-file")
+                    .short("o")
+                    .takes_value(true)
+                    .help("sets the file in which the modifications are written (default: standard output)"),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let read_framework = |path: &str, file_kind: &str| {
+            AspartixReader::default()
+                .read(
+                    &mut compression::open(path)
+                        .with_context(|| format!("while opening {} file", file_kind))?,
+                )
+                .with_context(|| format!("while reading {} file", file_kind))
+        };
+        let from = read_framework(arg_matches.value_of(ARG_INPUT_FILE).unwrap(), "input")?;
+        let to = read_framework(arg_matches.value_of(ARG_TARGET_FILE).unwrap(), "target")?;
+        let mut writer: Box<dyn Write> = match arg_matches.value_of(ARG_OUTPUT_FILE) {
+            Some(path) => Box::new(BufWriter::new(
+                compression::create(path)
+                    .with_context(|| format!(r#"while creating file "{}""#, path))?,
+            )),
+            None => Box::new(std::io::stdout()),
+        };
+        let modifications = diff_frameworks(&from, &to);
+        for m in modifications.iter() {
+            writeln!(writer, "{}", m).context("while writing the modifications")?;
+        }
+        writer.flush().context("while writing the modifications")?;
+        info!("wrote {} modification(s)", modifications.len());
+        Ok(())
+    }
+}
+
+fn attacks(framework: &AAFramework<String>) -> Vec<(&str, &str)> {
+    framework
+        .iter_attacks()
+        .map(|att| {
+            (
+                att.attacker().label().as_str(),
+                att.attacked().label().as_str(),
+            )
+        })
+        .collect()
+}
+
+/// Computes the modifications transforming a framework into another one.
+///
+/// Attacks are removed first, then arguments are removed and added, and attacks are finally added.
+/// The attacks involving a removed argument are explicitly removed before it.
+/// Each kind of modification follows the order of the declarations in the frameworks.
+fn diff_frameworks(
+    from: &AAFramework<String>,
+    to: &AAFramework<String>,
+) -> Vec<Modification<String>> {
+    let (from_args, to_args) = (from.argument_set(), to.argument_set());
+    let (from_attacks, to_attacks) = (attacks(from), attacks(to));
+    let from_attack_set = from_attacks
+        .iter()
+        .copied()
+        .collect::<HashSet<(&str, &str)>>();
+    let to_attack_set = to_attacks
+        .iter()
+        .copied()
+        .collect::<HashSet<(&str, &str)>>();
+    let mut modifications = vec![];
+    from_attacks
+        .iter()
+        .filter(|att| !to_attack_set.contains(att))
+        .for_each(|(a, b)| {
+            modifications.push(Modification::RemoveAttack(a.to_string(), b.to_string()))
+        });
+    from_args
+        .difference(to_args)
+        .iter()
+        .for_each(|a| modifications.push(Modification::RemoveArgument(a.label().clone())));
+    to_args
+        .difference(from_args)
+        .iter()
+        .for_each(|a| modifications.push(Modification::AddArgument(a.label().clone())));
+    to_attacks
+        .iter()
+        .filter(|att| !from_attack_set.contains(att))
+        .for_each(|(a, b)| {
+            modifications.push(Modification::AddAttack(a.to_string(), b.to_string()))
+        });
+    modifications
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::framework_state::FrameworkState;
+
+    fn read_af(s: &str) -> AAFramework<String> {
+        AspartixReader::default().read(&mut s.as_bytes()).unwrap()
+    }
+
+    fn diff(from: &str, to: &str) -> Vec<String> {
+        diff_frameworks(&read_af(from), &read_af(to))
+            .iter()
+            .map(|m| m.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        let from = "arg(a).\narg(b).\narg(c).\natt(a,b).\natt(b,c).\natt(c,a).\n";
+        let to = "arg(a).\narg(c).\narg(d).\natt(c,a).\natt(a,d).\n";
+        assert_eq!(
+            vec![
+                "-att(a,b).",
+                "-att(b,c).",
+                "-arg(b).",
+                "+arg(d).",
+                "+att(a,d).",
+            ],
+            diff(from, to)
+        );
+        let mut state = FrameworkState::new(&read_af(from));
+        diff(from, to).iter().for_each(|m| {
+            state.apply(m).unwrap();
+        });
+        assert_eq!(FrameworkState::new(&read_af(to)), state);
+    }
+
+    #[test]
+    fn test_diff_same_framework() {
+        let af = "arg(a).\narg(b).\natt(a,b).\n";
+        assert!(diff(af, af).is_empty());
+    }
+}
@@ -0,0 +1,226 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    io::{BufWriter, Write},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::{info, warn, AppSettings, Arg, Command, SubCommand, WithExitStatus};
+use crusti_arg::{
+    compression,
+    enforcement::{Acceptance, Enforcer},
+    maxsat::MaxSatStatus,
+    AspartixReader, Semantics,
+};
+
+use super::exit_status::MALFORMED_INPUT;
+use super::wrap_command::read_count;
+
+pub(crate) struct EnforceCommand;
+
+const CMD_NAME: &str = "enforce";
+
+const ARG_PROBLEM: &str = "PROBLEM";
+const ARG_INPUT_FILE: &str = "INPUT_FILE";
+const ARG_ARGUMENT: &str = "ARGUMENT";
+const ARG_MAX_CHANGES: &str = "MAX_CHANGES";
+const ARG_ADDITION_COST: &str = "ADDITION_COST";
+const ARG_REMOVAL_COST: &str = "REMOVAL_COST";
+const ARG_TIME_LIMIT: &str = "TIME_LIMIT";
+const ARG_OUTPUT_FILE: &str = "OUTPUT_FILE";
+
+impl EnforceCommand {
+    pub fn new() -> Self {
+        EnforceCommand
+    }
+}
+
+impl<'a> Command<'a> for EnforceCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about("computes a minimal modification file after which a set of arguments is accepted")
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_PROBLEM)
+                    .long("problem")
+                    .short("p")
+                    .takes_value(true)
+                    .help("sets the acceptance to enforce and its semantics (e.g. DC-CO or DS-GR)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_INPUT_FILE)
+                    .long("input-file")
+                    .short("f")
+                    .takes_value(true)
+                    .help("sets the input file containing the framework (Aspartix format)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_ARGUMENT)
+                    .long("argument")
+                    .short("a")
+                    .takes_value(true)
+                    .multiple(true)
+                    .number_of_values(1)
+                    .help("sets an argument to accept (may be given more than once)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_MAX_CHANGES)
+                    .long("max-changes")
+                    .takes_value(true)
+                    .help("sets the maximal number of attack additions and removals"),
+            )
+            .arg(
+                Arg::with_name(ARG_ADDITION_COST)
+                    .long("addition-cost")
+                    .takes_value(true)
+                    .default_value("1")
+                    .help("sets the cost of an attack addition"),
+            )
+            .arg(
+                Arg::with_name(ARG_REMOVAL_COST)
+                    .long("removal-cost")
+                    .takes_value(true)
+                    .default_value("1")
+                    .help("sets the cost of an attack removal"),
+            )
+            .arg(
+                Arg::with_name(ARG_TIME_LIMIT)
+                    .long("time-limit")
+                    .takes_value(true)
+                    .help("sets a time limit in seconds, after which the best modifications found so far are written"),
+            )
+            .arg(
+                Arg::with_name(ARG_OUTPUT_FILE)
+                    .long("output-file")
+                    .short("o")
+                    .takes_value(true)
+                    .help("sets the file in which the modifications are written (default: standard output)"),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let (acceptance, semantics) = read_problem(arg_matches.value_of(ARG_PROBLEM).unwrap())?;
+        let framework = AspartixReader::default()
+            .read(
+                &mut compression::open(arg_matches.value_of(ARG_INPUT_FILE).unwrap())
+                    .context("while opening input file")
+                    .with_exit_status(MALFORMED_INPUT)?,
+            )
+            .context("while reading input file")
+            .with_exit_status(MALFORMED_INPUT)?;
+        let targets = arg_matches
+            .values_of(ARG_ARGUMENT)
+            .unwrap()
+            .map(|a| a.to_string())
+            .collect::<Vec<String>>();
+        let mut enforcer = Enforcer::default();
+        enforcer.set_addition_cost(read_count(arg_matches, ARG_ADDITION_COST)? as u64);
+        enforcer.set_removal_cost(read_count(arg_matches, ARG_REMOVAL_COST)? as u64);
+        if arg_matches.value_of(ARG_MAX_CHANGES).is_some() {
+            enforcer.set_max_changes(read_count(arg_matches, ARG_MAX_CHANGES)?);
+        }
+        if let Some(str_limit) = arg_matches.value_of(ARG_TIME_LIMIT) {
+            let limit = str_limit
+                .parse::<f64>()
+                .ok()
+                .filter(|t| *t > 0.)
+                .ok_or_else(|| anyhow!(r#"invalid time limit "{}""#, str_limit))?;
+            enforcer.set_deadline(Instant::now() + Duration::from_secs_f64(limit));
+        }
+        let enforcement = enforcer.enforce(&framework, semantics, acceptance, &targets)?;
+        let modifications = match enforcement.status() {
+            MaxSatStatus::Unsatisfiable => {
+                return Err(anyhow!(
+                    "no modification file within {} change(s) enforces the {}-{} acceptance",
+                    arg_matches.value_of(ARG_MAX_CHANGES).unwrap(),
+                    acceptance,
+                    semantics
+                ))
+            }
+            MaxSatStatus::Unknown => {
+                return Err(anyhow!(
+                    "the time limit was reached before any modification file was found"
+                ))
+            }
+            MaxSatStatus::Feasible => {
+                warn!("the time limit was reached; the modifications may not be of minimal cost");
+                enforcement.modifications().unwrap()
+            }
+            MaxSatStatus::Optimal => enforcement.modifications().unwrap(),
+        };
+        let mut writer: Box<dyn Write> = match arg_matches.value_of(ARG_OUTPUT_FILE) {
+            Some(path) => Box::new(BufWriter::new(
+                compression::create(path)
+                    .with_context(|| format!(r#"while creating file "{}""#, path))?,
+            )),
+            None => Box::new(std::io::stdout()),
+        };
+        for m in modifications.iter() {
+            writeln!(writer, "{}", m).context("while writing the modifications")?;
+        }
+        writer.flush().context("while writing the modifications")?;
+        info!(
+            "enforced the {}-{} acceptance with {} modification(s) of {} cost {}",
+            acceptance,
+            semantics,
+            modifications.len(),
+            enforcement.status(),
+            enforcement.cost().unwrap()
+        );
+        Ok(())
+    }
+}
+
+/// Reads an enforcement problem given as an acceptance followed by a semantics (e.g. `DC-CO`).
+pub(crate) fn read_problem(problem: &str) -> Result<(Acceptance, Semantics)> {
+    let context = || format!(r#"while reading the enforcement problem "{}""#, problem);
+    let mut words = problem.split('-');
+    match (words.next(), words.next(), words.next()) {
+        (Some(acceptance), Some(semantics), None) => Ok((
+            acceptance.parse().with_context(context)?,
+            semantics.parse().with_context(context)?,
+        )),
+        _ => Err(anyhow!("expected an acceptance and a semantics")).with_context(context),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_problem() {
+        assert_eq!(
+            (Acceptance::Skeptical, Semantics::Grounded),
+            read_problem("DS-GR").unwrap()
+        );
+        assert!(read_problem("DC").is_err());
+        assert!(read_problem("DC-CO-D").is_err());
+        assert!(read_problem("SE-CO").is_err());
+    }
+}
@@ -0,0 +1,105 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! The status codes IDW exits with, allowing scripts to classify the results without parsing the logs.
+//!
+//! Errors which are not classified make IDW exit with status 1.
+//! The classified errors keep their chain, so that the [`WrapError`] they were built upon can still be retrieved through [`ExitStatusError::error`].
+use crusti_app_helper::ExitStatusError;
+use iccma_dynamics::runner::WrapError;
+
+/// Any other failure, classified to prevent another classification by the callers.
+pub(crate) const FAILURE: i32 = 1;
+
+/// A solver gave a wrong answer, a malformed one, or an answer out of sync with the dynamics.
+pub(crate) const WRONG_ANSWER: i32 = 10;
+
+/// A solver crashed (after the allowed restarts, if any).
+pub(crate) const SOLVER_CRASH: i32 = 20;
+
+/// A solver did not answer the whole dynamics within the time limit, and was killed.
+pub(crate) const TIMEOUT: i32 = 30;
+
+/// An input file (framework, dynamics, arguments, checkpoint...) is malformed or cannot be read.
+pub(crate) const MALFORMED_INPUT: i32 = 40;
+
+/// The wrapper was interrupted by SIGINT or SIGTERM, and killed the solver(s).
+pub(crate) const INTERRUPTED: i32 = 50;
+
+/// Classifies the error of a solver run which did not crash, according to the kind of the failure.
+///
+/// Invalid modifications are classified as malformed inputs, and spawn failures as other failures; the remaining errors are considered as wrong answers.
+pub(crate) fn classify_solver_error(error: anyhow::Error) -> anyhow::Error {
+    let status = match error.downcast_ref::<WrapError>() {
+        Some(WrapError::ModificationParse { .. }) => MALFORMED_INPUT,
+        Some(WrapError::SolverSpawn { .. }) => FAILURE,
+        Some(WrapError::SolverExit { .. }) => SOLVER_CRASH,
+        _ => WRONG_ANSWER,
+    };
+    ExitStatusError::classify(error, status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{anyhow, Context};
+
+    fn status(error: anyhow::Error) -> i32 {
+        classify_solver_error(error)
+            .downcast_ref::<ExitStatusError>()
+            .unwrap()
+            .status()
+    }
+
+    #[test]
+    fn test_classify_solver_error() {
+        assert_eq!(
+            MALFORMED_INPUT,
+            status(
+                Err::<(), _>(anyhow!("e"))
+                    .context(WrapError::ModificationParse { line: 1 })
+                    .unwrap_err()
+            )
+        );
+        assert_eq!(
+            WRONG_ANSWER,
+            status(anyhow::Error::new(WrapError::EarlyAnswer { step: 0 }))
+        );
+        assert_eq!(WRONG_ANSWER, status(anyhow!("e")));
+        let error = classify_solver_error(anyhow::Error::new(WrapError::EarlyAnswer { step: 3 }));
+        assert!(matches!(
+            error
+                .downcast_ref::<ExitStatusError>()
+                .unwrap()
+                .error()
+                .downcast_ref::<WrapError>(),
+            Some(WrapError::EarlyAnswer { step: 3 })
+        ));
+        assert_eq!(
+            FAILURE,
+            status(
+                Err::<(), _>(anyhow!("e"))
+                    .context(WrapError::SolverSpawn {
+                        solver: "s".to_string()
+                    })
+                    .unwrap_err()
+            )
+        );
+    }
+}
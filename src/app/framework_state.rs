@@ -0,0 +1,209 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use anyhow::{anyhow, Result};
+use crusti_arg::{
+    dynamics::{self, Modification},
+    AAFramework, ArgumentSet,
+};
+
+/// The state of a framework under the dynamics, given by the labels of its arguments and its attacks.
+///
+/// The arguments are kept in the order they were added to the framework.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct FrameworkState {
+    arguments: Vec<String>,
+    attacks: Vec<(String, String)>,
+}
+
+impl FrameworkState {
+    pub(crate) fn new(framework: &AAFramework<String>) -> Self {
+        FrameworkState {
+            arguments: framework
+                .argument_set()
+                .iter()
+                .map(|a| a.label().to_string())
+                .collect(),
+            attacks: framework
+                .iter_attacks()
+                .map(|att| {
+                    (
+                        att.attacker().label().to_string(),
+                        att.attacked().label().to_string(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the labels of the arguments, in the order they were added to the framework.
+    pub(crate) fn arguments(&self) -> &[String] {
+        &self.arguments
+    }
+
+    /// Returns the attacks, given by the labels of their attacker and of the attacked argument.
+    pub(crate) fn attacks(&self) -> &[(String, String)] {
+        &self.attacks
+    }
+
+    fn check_argument(&self, label: &str) -> Result<()> {
+        if self.arguments.iter().any(|a| a == label) {
+            Ok(())
+        } else {
+            Err(anyhow!("no such argument: {}", label))
+        }
+    }
+
+    fn has_attack(&self, from: &str, to: &str) -> bool {
+        self.attacks.iter().any(|(a, b)| a == from && b == to)
+    }
+
+    /// Applies a modification line to this state.
+    ///
+    /// Removing an argument also removes the attacks it is involved in.
+    ///
+    /// Returns the labels of the remaining arguments whose set of attackers has changed, including a new argument.
+    pub(crate) fn apply(&mut self, line: &str) -> Result<Vec<String>> {
+        match dynamics::read_modification(line)? {
+            Modification::AddArgument(a) => {
+                if self.check_argument(&a).is_ok() {
+                    return Err(anyhow!("argument {} already exists", a));
+                }
+                self.arguments.push(a.clone());
+                Ok(vec![a])
+            }
+            Modification::RemoveArgument(a) => {
+                self.check_argument(&a)?;
+                let mut changed = self
+                    .attacks
+                    .iter()
+                    .filter(|(from, to)| *from == a && *to != a)
+                    .map(|(_, to)| to.clone())
+                    .collect::<Vec<String>>();
+                changed.dedup();
+                self.arguments.retain(|l| *l != a);
+                self.attacks.retain(|(from, to)| *from != a && *to != a);
+                Ok(changed)
+            }
+            Modification::AddAttack(a, b) => {
+                self.check_argument(&a)?;
+                self.check_argument(&b)?;
+                if self.has_attack(&a, &b) {
+                    return Err(anyhow!("attack {} → {} already exists", a, b));
+                }
+                self.attacks.push((a, b.clone()));
+                Ok(vec![b])
+            }
+            Modification::RemoveAttack(a, b) => {
+                if !self.has_attack(&a, &b) {
+                    return Err(anyhow!("no such attack: {} → {}", a, b));
+                }
+                self.attacks.retain(|(from, to)| *from != a || *to != b);
+                Ok(vec![b])
+            }
+        }
+    }
+
+    /// Returns the modifications building this state from an empty framework: the arguments, then the attacks.
+    pub(crate) fn to_modifications(&self) -> Vec<String> {
+        self.arguments
+            .iter()
+            .map(|a| Modification::AddArgument(a.clone()).to_string())
+            .chain(
+                self.attacks
+                    .iter()
+                    .map(|(a, b)| Modification::AddAttack(a.clone(), b.clone()).to_string()),
+            )
+            .collect()
+    }
+
+    pub(crate) fn to_framework(&self) -> Result<AAFramework<String>> {
+        let mut framework = AAFramework::new(ArgumentSet::new(self.arguments.clone()));
+        for (a, b) in self.attacks.iter() {
+            framework.new_attack(a, b)?;
+        }
+        Ok(framework)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crusti_arg::{AspartixReader, AspartixWriter};
+
+    fn read_af(s: &str) -> AAFramework<String> {
+        AspartixReader::default().read(&mut s.as_bytes()).unwrap()
+    }
+
+    fn write_af(framework: &AAFramework<String>) -> String {
+        let mut out = Vec::new();
+        AspartixWriter::default()
+            .write(framework, &mut out)
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut state = FrameworkState::new(&read_af("arg(a).\narg(b).\natt(a,b).\n"));
+        state.apply("+arg(c).").unwrap();
+        state.apply("+att(c,a).").unwrap();
+        state.apply("-att(a,b).").unwrap();
+        state.apply("+att(b,c).").unwrap();
+        state.apply("-arg(a).").unwrap();
+        assert_eq!(
+            "arg(b).\narg(c).\natt(b,c).\n",
+            write_af(&state.to_framework().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_apply_changed_arguments() {
+        let mut state = FrameworkState::new(&read_af(
+            "arg(a).\narg(b).\narg(c).\natt(a,a).\natt(a,b).\natt(a,c).\n",
+        ));
+        assert_eq!(vec!["d"], state.apply("+arg(d).").unwrap());
+        assert_eq!(vec!["a"], state.apply("+att(d,a).").unwrap());
+        assert_eq!(vec!["a"], state.apply("-att(d,a).").unwrap());
+        assert_eq!(vec!["b", "c"], state.apply("-arg(a).").unwrap());
+    }
+
+    #[test]
+    fn test_apply_errors() {
+        let mut state = FrameworkState::new(&read_af("arg(a).\narg(b).\natt(a,b).\n"));
+        assert!(state.apply("+arg(a).").is_err());
+        assert!(state.apply("-arg(c).").is_err());
+        assert!(state.apply("+att(a,b).").is_err());
+        assert!(state.apply("+att(a,c).").is_err());
+        assert!(state.apply("-att(b,a).").is_err());
+        assert!(state.apply("foo").is_err());
+    }
+
+    #[test]
+    fn test_to_modifications() {
+        let state = FrameworkState::new(&read_af("arg(a).\narg(b).\natt(a,b).\n"));
+        let modifications = state.to_modifications();
+        assert_eq!(vec!["+arg(a).", "+arg(b).", "+att(a,b)."], modifications);
+        let mut rebuilt = FrameworkState::new(&read_af(""));
+        modifications.iter().for_each(|m| {
+            rebuilt.apply(m).unwrap();
+        });
+        assert_eq!(state, rebuilt);
+    }
+}
@@ -0,0 +1,722 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    convert::TryFrom,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::{info, warn, AppSettings, Arg, Command, SubCommand};
+use crusti_arg::{
+    dynamics::{self, Modification},
+    solvers::SMALL_FRAMEWORK_SIZE,
+    testing::{FrameworkGenerator, Structure, TestRng},
+    Semantics,
+};
+use iccma_dynamics::runner::{self, QueryType, RunLimits, WrapError};
+
+use super::{
+    exit_status::classify_solver_error,
+    framework_state::FrameworkState,
+    generate_command::read_probability,
+    solve_command,
+    temp_files::TempFiles,
+    validate_command::{self, ValidatedQuery},
+    wrap_command::read_count,
+};
+
+pub(crate) struct FuzzSolverCommand;
+
+const CMD_NAME: &str = "fuzz-solver";
+
+const ARG_SOLVER: &str = "SOLVER";
+const ARG_PROBLEM: &str = "PROBLEM";
+const ARG_RUNS: &str = "RUNS";
+const ARG_SEED: &str = "SEED";
+const ARG_MAX_ARGUMENTS: &str = "MAX_ARGUMENTS";
+const ARG_MAX_STEPS: &str = "MAX_STEPS";
+const ARG_NEAR_VALID: &str = "NEAR_VALID";
+const ARG_STRUCTURE: &str = "STRUCTURE";
+const ARG_FOCUS_BIAS: &str = "FOCUS_BIAS";
+const ARG_OUTPUT_DIR: &str = "OUTPUT_DIR";
+
+/// The maximal number of solver runs spent shrinking a failing case.
+const SHRINK_BUDGET: usize = 200;
+
+impl FuzzSolverCommand {
+    pub fn new() -> Self {
+        FuzzSolverCommand
+    }
+}
+
+impl<'a> Command<'a> for FuzzSolverCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about("runs a solver on random frameworks and dynamics, checking its answers and shrinking the failing cases")
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_SOLVER)
+                    .long("solver")
+                    .short("s")
+                    .takes_value(true)
+                    .help("sets the solver to test")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_PROBLEM)
+                    .long("problem")
+                    .short("p")
+                    .takes_value(true)
+                    .help("sets the problem to test (e.g. DC-CO or DC-CO-D)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_RUNS)
+                    .long("runs")
+                    .takes_value(true)
+                    .default_value("100")
+                    .help("sets the number of generated cases"),
+            )
+            .arg(
+                Arg::with_name(ARG_SEED)
+                    .long("seed")
+                    .takes_value(true)
+                    .help("sets the seed of the generator (taken from the clock by default)"),
+            )
+            .arg(
+                Arg::with_name(ARG_MAX_ARGUMENTS)
+                    .long("max-arguments")
+                    .takes_value(true)
+                    .default_value("8")
+                    .help("sets the maximal number of arguments of the frameworks"),
+            )
+            .arg(
+                Arg::with_name(ARG_MAX_STEPS)
+                    .long("max-steps")
+                    .takes_value(true)
+                    .default_value("10")
+                    .help("sets the maximal number of modifications of the dynamics"),
+            )
+            .arg(
+                Arg::with_name(ARG_NEAR_VALID)
+                    .long("near-valid")
+                    .takes_value(false)
+                    .help("writes the inputs with unusual but legal labels and layouts (spaces, blank lines, CRLF)"),
+            )
+            .arg(
+                Arg::with_name(ARG_STRUCTURE)
+                    .long("structure")
+                    .takes_value(true)
+                    .possible_values(&Structure::NAMES)
+                    .default_value("random")
+                    .help("sets the structure of the attack graphs of the frameworks"),
+            )
+            .arg(
+                Arg::with_name(ARG_FOCUS_BIAS)
+                    .long("focus-bias")
+                    .takes_value(true)
+                    .default_value("0")
+                    .help("sets the probability of a modification to be drawn in the neighborhood of the queried argument (DC and DS problems)"),
+            )
+            .arg(
+                Arg::with_name(ARG_OUTPUT_DIR)
+                    .long("output-dir")
+                    .takes_value(true)
+                    .default_value(".")
+                    .help("sets the directory in which the failing case is saved"),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let solver = arg_matches.value_of(ARG_SOLVER).unwrap();
+        let problem = arg_matches.value_of(ARG_PROBLEM).unwrap();
+        let problem = if problem.split('-').count() == 2 {
+            format!("{}-D", problem)
+        } else {
+            problem.to_string()
+        };
+        let needs_argument = matches!(problem.split('-').next(), Some("DC") | Some("DS"));
+        QueryType::try_from((problem.as_str(), Some("a").filter(|_| needs_argument)))?;
+        let semantics = problem.split('-').nth(1).unwrap().parse::<Semantics>()?;
+        let runs = read_count(arg_matches, ARG_RUNS)?;
+        let max_arguments = read_count(arg_matches, ARG_MAX_ARGUMENTS)?;
+        if max_arguments > SMALL_FRAMEWORK_SIZE || (needs_argument && max_arguments == 0) {
+            return Err(anyhow!(
+                "the maximal number of arguments must be between {} and {}",
+                if needs_argument { 1 } else { 0 },
+                SMALL_FRAMEWORK_SIZE
+            ));
+        }
+        let seed = match arg_matches.value_of(ARG_SEED) {
+            Some(s) => s
+                .parse::<u64>()
+                .with_context(|| format!(r#"while parsing the seed "{}""#, s))?,
+            None => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or_default(),
+        };
+        info!("generating {} case(s) with seed {}", runs, seed);
+        let settings = CaseSettings {
+            max_arguments,
+            max_steps: read_count(arg_matches, ARG_MAX_STEPS)?,
+            needs_argument,
+            near_valid: arg_matches.is_present(ARG_NEAR_VALID),
+            structure: arg_matches
+                .value_of(ARG_STRUCTURE)
+                .unwrap()
+                .parse::<Structure>()?,
+            focus_bias: read_probability(arg_matches, ARG_FOCUS_BIAS)?,
+        };
+        let target = FuzzTarget {
+            solver,
+            problem: &problem,
+            semantics,
+            near_valid: settings.near_valid,
+            temp_files: TempFiles::default(),
+        };
+        let mut rng = TestRng::new(seed);
+        for run in 0..runs {
+            let case = FuzzCase::generate(&mut rng, &settings);
+            let error = match target.run(&case) {
+                Ok(()) => continue,
+                Err(e) if matches!(e.downcast_ref(), Some(WrapError::SolverSpawn { .. })) => {
+                    return Err(e)
+                }
+                Err(e) => e,
+            };
+            warn!("case {} failed: {:#}", run, error);
+            let (case, error) = shrink(&target, case, error);
+            let (instance, dynamics) = save_case(
+                &case,
+                settings.near_valid,
+                Path::new(arg_matches.value_of(ARG_OUTPUT_DIR).unwrap()),
+                &format!("fuzz-{}-{}", seed, run),
+            )?;
+            info!(
+                "the shrunk case ({}) is saved in \"{}\" and \"{}\"",
+                case.size_description(),
+                instance.display(),
+                dynamics.display()
+            );
+            info!(
+                "replay it with: wrap -s {} -p {} -f {} -z apx -m {}{}",
+                solver,
+                problem,
+                instance.display(),
+                dynamics.display(),
+                case.argument
+                    .as_ref()
+                    .map(|a| format!(" -a {}", a))
+                    .unwrap_or_default()
+            );
+            return Err(classify_solver_error(error)
+                .context(format!("while running case {} of seed {}", run, seed)));
+        }
+        info!("the solver passed the {} generated case(s)", runs);
+        Ok(())
+    }
+}
+
+/// The bounds and the layout of the generated cases.
+struct CaseSettings {
+    max_arguments: usize,
+    max_steps: usize,
+    needs_argument: bool,
+    near_valid: bool,
+    structure: Structure,
+    focus_bias: f64,
+}
+
+/// A test case: an initial framework, the modifications of its dynamics, and the argument of the query if the problem needs one.
+///
+/// The modifications can be applied in sequence, and never remove the queried argument.
+#[derive(Clone, Debug)]
+struct FuzzCase {
+    initial: FrameworkState,
+    modifications: Vec<String>,
+    argument: Option<String>,
+}
+
+impl FuzzCase {
+    /// Generates a case with the generators of `crusti_arg`, which are also available to the solver authors.
+    fn generate(rng: &mut TestRng, settings: &CaseSettings) -> Self {
+        let min_arguments = if settings.needs_argument { 1 } else { 0 };
+        let n_arguments = min_arguments + rng.below(settings.max_arguments + 1 - min_arguments);
+        let mut generator = FrameworkGenerator::default();
+        if settings.near_valid {
+            generator.set_labels(near_valid_label);
+        }
+        generator.set_max_arguments(settings.max_arguments);
+        generator.set_structure(settings.structure);
+        let density = rng.below(50) as f64 / 100.0;
+        let framework = generator.framework(rng, n_arguments, density);
+        let argument = if settings.needs_argument {
+            let id = rng.below(n_arguments);
+            Some(
+                framework
+                    .argument_set()
+                    .get_argument_by_id(id)
+                    .label()
+                    .clone(),
+            )
+        } else {
+            None
+        };
+        if let Some(a) = &argument {
+            if settings.focus_bias > 0.0 {
+                generator.set_focus(a, settings.focus_bias);
+            } else {
+                generator.keep_argument(a);
+            }
+        }
+        let n_modifications = rng.below(settings.max_steps + 1);
+        FuzzCase {
+            initial: FrameworkState::new(&framework),
+            modifications: generator
+                .modifications(rng, &framework, n_modifications)
+                .iter()
+                .map(|m| m.to_string())
+                .collect(),
+            argument,
+        }
+    }
+
+    /// Drops the modifications which cannot be applied in sequence to the initial framework, and the ones removing the queried argument.
+    fn normalized(mut self) -> Self {
+        let mut state = self.initial.clone();
+        let argument = self.argument.clone();
+        self.modifications.retain(|m| {
+            let removes_argument = matches!(
+                dynamics::read_modification(m),
+                Ok(Modification::RemoveArgument(a)) if Some(&a) == argument.as_ref()
+            );
+            !removes_argument && state.apply(m).is_ok()
+        });
+        self
+    }
+
+    /// Returns the cases obtained by removing some modifications, an argument or an attack of the initial framework, the largest reductions first.
+    fn reductions(&self) -> Vec<FuzzCase> {
+        let with_modifications = |modifications: Vec<String>| {
+            FuzzCase {
+                modifications,
+                ..self.clone()
+            }
+            .normalized()
+        };
+        let mut reductions = vec![];
+        let n_modifications = self.modifications.len();
+        if n_modifications > 0 {
+            reductions.push(with_modifications(vec![]));
+        }
+        if n_modifications > 1 {
+            reductions.push(with_modifications(
+                self.modifications[..n_modifications / 2].to_vec(),
+            ));
+        }
+        for i in 0..n_modifications {
+            let mut modifications = self.modifications.clone();
+            modifications.remove(i);
+            reductions.push(with_modifications(modifications));
+        }
+        let initial_changes = self
+            .initial
+            .arguments()
+            .iter()
+            .filter(|a| Some(*a) != self.argument.as_ref())
+            .map(|a| Modification::RemoveArgument(a.clone()))
+            .chain(
+                self.initial
+                    .attacks()
+                    .iter()
+                    .map(|(a, b)| Modification::RemoveAttack(a.clone(), b.clone())),
+            );
+        for change in initial_changes {
+            let mut initial = self.initial.clone();
+            if initial.apply(&change.to_string()).is_ok() {
+                reductions.push(
+                    FuzzCase {
+                        initial,
+                        ..self.clone()
+                    }
+                    .normalized(),
+                );
+            }
+        }
+        reductions
+    }
+
+    fn size_description(&self) -> String {
+        format!(
+            "{} argument(s), {} attack(s), {} modification(s)",
+            self.initial.arguments().len(),
+            self.initial.attacks().len(),
+            self.modifications.len()
+        )
+    }
+}
+
+/// Returns the label of the argument of index `id` in near-valid cases, which may begin with an underscore or contain uppercase letters.
+fn near_valid_label(id: usize) -> String {
+    match id % 4 {
+        1 => format!("_{}", id),
+        2 => format!("Arg_{}", id),
+        3 => format!("x{}y", id),
+        _ => format!("a{}", id),
+    }
+}
+
+/// Puts spaces around the labels of an Aspartix line or a modification line.
+fn spaced(line: &str) -> String {
+    line.replace('(', "( ")
+        .replace(',', " , ")
+        .replace(')', " )")
+}
+
+/// Writes the initial framework of a case in the Aspartix format.
+///
+/// Near-valid instances alternate legal but unusual layouts: spaces around the labels and at the ends of the lines, blank lines and CRLF line endings.
+fn render_instance(state: &FrameworkState, near_valid: bool) -> String {
+    state
+        .arguments()
+        .iter()
+        .map(|a| format!("arg({}).", a))
+        .chain(
+            state
+                .attacks()
+                .iter()
+                .map(|(a, b)| format!("att({},{}).", a, b)),
+        )
+        .enumerate()
+        .map(|(i, l)| match i % 4 {
+            1 if near_valid => format!("{}\n", spaced(&l)),
+            2 if near_valid => format!("  {} \n", l),
+            3 if near_valid => format!("{}\r\n\n", l),
+            _ => format!("{}\n", l),
+        })
+        .collect()
+}
+
+/// Writes the modifications of a case; near-valid modifications alternate spaces around the labels and at the ends of the lines.
+fn render_modifications(modifications: &[String], near_valid: bool) -> Vec<String> {
+    modifications
+        .iter()
+        .enumerate()
+        .map(|(i, m)| match i % 3 {
+            1 if near_valid => spaced(m),
+            2 if near_valid => format!(" {} ", m),
+            _ => m.clone(),
+        })
+        .collect()
+}
+
+/// The solver and the problem under test.
+struct FuzzTarget<'a> {
+    solver: &'a str,
+    problem: &'a str,
+    semantics: Semantics,
+    near_valid: bool,
+    temp_files: TempFiles,
+}
+
+impl FuzzTarget<'_> {
+    /// Runs the solver on a case, and compares its answers to the ones of the naive engine.
+    ///
+    /// The answers of SE problems are compared to all the extensions of the frameworks.
+    /// The errors of the solver run are not classified, so that spawn failures can be told apart from the failures of the solver.
+    fn run(&self, case: &FuzzCase) -> Result<()> {
+        let query = QueryType::try_from((self.problem, case.argument.as_deref()))?;
+        let (instance, mut file) = self.temp_files.create("fuzz.apx")?;
+        file.write_all(render_instance(&case.initial, self.near_valid).as_bytes())
+            .context("while writing the instance")?;
+        drop(file);
+        let arguments =
+            query.command_arguments(self.problem, &instance.path().to_string_lossy(), "apx");
+        let mut answers = String::new();
+        runner::run_solver(
+            self.solver,
+            &arguments,
+            &render_modifications(&case.modifications, self.near_valid),
+            &query,
+            &RunLimits::default(),
+            &mut |a| {
+                answers.push_str(&a);
+                Ok(())
+            },
+        )?;
+        let reference_query = match query {
+            QueryType::SE => QueryType::EE,
+            q => q,
+        };
+        let mut reference = Vec::new();
+        solve_command::solve_dynamics(
+            &case.initial.to_framework()?,
+            &case.modifications,
+            self.semantics,
+            solve_command::Engine::Naive,
+            &reference_query,
+            &mut reference,
+        )
+        .context("while computing the reference answers")?;
+        validate_command::validate(
+            ValidatedQuery::try_from_str(self.problem)?,
+            &mut answers.as_bytes(),
+            &mut reference.as_slice(),
+        )?;
+        Ok(())
+    }
+}
+
+/// Shrinks a failing case, keeping the first reduction the solver still fails on, until it passes all of them or the budget of runs is spent.
+///
+/// The smallest failing case found is returned with its failure.
+fn shrink(
+    target: &FuzzTarget,
+    mut case: FuzzCase,
+    mut error: anyhow::Error,
+) -> (FuzzCase, anyhow::Error) {
+    let mut budget = SHRINK_BUDGET;
+    'shrinking: loop {
+        for candidate in case.reductions() {
+            if budget == 0 {
+                break 'shrinking;
+            }
+            budget -= 1;
+            if let Err(e) = target.run(&candidate) {
+                case = candidate;
+                error = e;
+                continue 'shrinking;
+            }
+        }
+        break;
+    }
+    (case, error)
+}
+
+/// Saves a case in an instance file and a dynamics file, returning their paths.
+fn save_case(
+    case: &FuzzCase,
+    near_valid: bool,
+    dir: &Path,
+    name: &str,
+) -> Result<(PathBuf, PathBuf)> {
+    let instance = dir.join(format!("{}.apx", name));
+    fs::write(&instance, render_instance(&case.initial, near_valid))
+        .with_context(|| format!(r#"while writing "{}""#, instance.display()))?;
+    let dynamics = dir.join(format!("{}.apxm", name));
+    let content = render_modifications(&case.modifications, near_valid)
+        .iter()
+        .map(|m| format!("{}\n", m))
+        .collect::<String>();
+    fs::write(&dynamics, content)
+        .with_context(|| format!(r#"while writing "{}""#, dynamics.display()))?;
+    Ok((instance, dynamics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crusti_arg::{AAFramework, ArgumentSet};
+
+    fn settings(needs_argument: bool, near_valid: bool) -> CaseSettings {
+        CaseSettings {
+            max_arguments: 6,
+            max_steps: 8,
+            needs_argument,
+            near_valid,
+            structure: Structure::default(),
+            focus_bias: 0.0,
+        }
+    }
+
+    fn state(arguments: &[&str], attacks: &[(&str, &str)]) -> FrameworkState {
+        let mut framework = AAFramework::new(ArgumentSet::new(
+            arguments.iter().map(|a| a.to_string()).collect(),
+        ));
+        for (a, b) in attacks {
+            framework
+                .new_attack(&a.to_string(), &b.to_string())
+                .unwrap();
+        }
+        FrameworkState::new(&framework)
+    }
+
+    fn to_strings(modifications: &[&str]) -> Vec<String> {
+        modifications.iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn test_generate_valid_cases() {
+        let mut rng = TestRng::new(42);
+        for (near_valid, focus_bias) in [(false, 0.0), (true, 0.0), (false, 1.0)].iter() {
+            let settings = CaseSettings {
+                focus_bias: *focus_bias,
+                ..settings(true, *near_valid)
+            };
+            for _ in 0..100 {
+                let case = FuzzCase::generate(&mut rng, &settings);
+                let argument = case.argument.clone().unwrap();
+                assert!(case.initial.arguments().contains(&argument));
+                assert!(case.modifications.len() <= 8);
+                let mut state = case.initial.clone();
+                for m in case.modifications.iter() {
+                    state.apply(m).unwrap();
+                    assert!(state.arguments().len() <= 6);
+                    assert!(state.arguments().contains(&argument));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_reproducible() {
+        let render = |seed| {
+            let mut rng = TestRng::new(seed);
+            (0..10)
+                .map(|_| {
+                    let case = FuzzCase::generate(&mut rng, &settings(false, true));
+                    format!(
+                        "{}{}",
+                        render_instance(&case.initial, true),
+                        render_modifications(&case.modifications, true).join("\n")
+                    )
+                })
+                .collect::<String>()
+        };
+        assert_eq!(render(7), render(7));
+        assert_ne!(render(7), render(8));
+    }
+
+    #[test]
+    fn test_render_near_valid() {
+        let case_state = state(&["a", "_1", "b"], &[("a", "_1"), ("b", "b")]);
+        assert_eq!(
+            "arg(a).\narg( _1 ).\n  arg(b). \natt(a,_1).\r\n\natt(b,b).\n",
+            render_instance(&case_state, true)
+        );
+        let framework = crusti_arg::AspartixReader::default()
+            .read(&mut render_instance(&case_state, true).as_bytes())
+            .unwrap();
+        assert_eq!(case_state, FrameworkState::new(&framework));
+        let modifications = render_modifications(
+            &to_strings(&["+arg(c).", "-att(a,_1).", "+att(c,a)."]),
+            true,
+        );
+        assert_eq!(
+            vec!["+arg(c).", "-att( a , _1 ).", " +att(c,a). "],
+            modifications
+        );
+        let mut state = case_state.clone();
+        modifications
+            .iter()
+            .for_each(|m| assert!(state.apply(m).is_ok()));
+    }
+
+    #[test]
+    fn test_normalized() {
+        let case = FuzzCase {
+            initial: state(&["a", "b"], &[]),
+            modifications: to_strings(&["-arg(a).", "+att(b,a).", "+arg(c).", "+att(c,b)."]),
+            argument: Some("a".to_string()),
+        }
+        .normalized();
+        assert_eq!(
+            to_strings(&["+att(b,a).", "+arg(c).", "+att(c,b)."]),
+            case.modifications
+        );
+        let case = FuzzCase {
+            modifications: to_strings(&["+att(c,b)."]),
+            ..case
+        }
+        .normalized();
+        assert!(case.modifications.is_empty());
+    }
+
+    #[test]
+    fn test_reductions() {
+        let case = FuzzCase {
+            initial: state(&["a", "b"], &[("b", "a")]),
+            modifications: to_strings(&["-att(b,a).", "+att(a,b)."]),
+            argument: Some("a".to_string()),
+        };
+        let reductions = case
+            .reductions()
+            .iter()
+            .map(|r| r.size_description())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            vec![
+                "2 argument(s), 1 attack(s), 0 modification(s)",
+                "2 argument(s), 1 attack(s), 1 modification(s)",
+                "2 argument(s), 1 attack(s), 1 modification(s)",
+                "2 argument(s), 1 attack(s), 1 modification(s)",
+                "1 argument(s), 0 attack(s), 0 modification(s)",
+                "2 argument(s), 0 attack(s), 1 modification(s)",
+            ],
+            reductions
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shrink() {
+        use std::os::unix::fs::PermissionsExt;
+        let temp_files = TempFiles::default();
+        let (solver, mut file) = temp_files.create("solver.sh").unwrap();
+        // a solver answering NO for each step, which is wrong when the queried argument is not attacked
+        file.write_all(
+            b"#!/bin/sh\necho NO\nwhile read l; do [ -z \"$l\" ] && exit 0; echo NO; done\n",
+        )
+        .unwrap();
+        drop(file);
+        fs::set_permissions(solver.path(), fs::Permissions::from_mode(0o755)).unwrap();
+        let solver_path = solver.path().to_string_lossy().to_string();
+        let target = FuzzTarget {
+            solver: &solver_path,
+            problem: "DC-GR-D",
+            semantics: Semantics::Grounded,
+            near_valid: false,
+            temp_files,
+        };
+        let case = FuzzCase {
+            initial: state(&["a", "b", "c"], &[("b", "a"), ("c", "b")]),
+            modifications: to_strings(&["-att(c,b).", "-att(b,a).", "+arg(d)."]),
+            argument: Some("a".to_string()),
+        };
+        let error = target.run(&case).unwrap_err();
+        let (shrunk, error) = shrink(&target, case, error);
+        assert_eq!(
+            "1 argument(s), 0 attack(s), 0 modification(s)",
+            shrunk.size_description()
+        );
+        assert_eq!(
+            "step 0: the answer is NO while the reference is YES",
+            error.to_string()
+        );
+    }
+}
@@ -0,0 +1,258 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    io::{BufWriter, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::{info, AppSettings, Arg, Command, SubCommand};
+use crusti_arg::{
+    compression::{self, CompressedWriter},
+    dynamics::Modification,
+    examples::Template,
+    testing::{FrameworkGenerator, Structure, TestRng},
+    AspartixWriter,
+};
+
+use super::wrap_command::read_count;
+
+pub(crate) struct GenerateCommand;
+
+const CMD_NAME: &str = "generate";
+
+const ARG_ARGUMENTS: &str = "ARGUMENTS";
+const ARG_DENSITY: &str = "DENSITY";
+const ARG_STRUCTURE: &str = "STRUCTURE";
+const ARG_TEMPLATE: &str = "TEMPLATE";
+const ARG_STEPS: &str = "STEPS";
+const ARG_SEED: &str = "SEED";
+const ARG_OUTPUT_FILE: &str = "OUTPUT_FILE";
+const ARG_MODIFICATION_FILE: &str = "MODIFICATION_FILE";
+const ARG_FOCUS_ARGUMENT: &str = "FOCUS_ARGUMENT";
+const ARG_FOCUS_BIAS: &str = "FOCUS_BIAS";
+
+impl GenerateCommand {
+    pub fn new() -> Self {
+        GenerateCommand
+    }
+}
+
+impl<'a> Command<'a> for GenerateCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about(
+                "generates a random framework with a given structure, and optionally its dynamics",
+            )
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_ARGUMENTS)
+                    .long("arguments")
+                    .short("n")
+                    .takes_value(true)
+                    .help("sets the number of arguments of the framework")
+                    .required_unless(ARG_TEMPLATE),
+            )
+            .arg(
+                Arg::with_name(ARG_DENSITY)
+                    .long("density")
+                    .takes_value(true)
+                    .default_value("0.1")
+                    .help("sets the probability of each attack, besides the ones of the structure"),
+            )
+            .arg(
+                Arg::with_name(ARG_STRUCTURE)
+                    .long("structure")
+                    .takes_value(true)
+                    .possible_values(&Structure::NAMES)
+                    .default_value("random")
+                    .help("sets the structure of the attack graph"),
+            )
+            .arg(
+                Arg::with_name(ARG_TEMPLATE)
+                    .long("template")
+                    .takes_value(true)
+                    .possible_values(&Template::NAMES)
+                    .conflicts_with(ARG_ARGUMENTS)
+                    .help("writes a classic framework of the literature instead of a random one"),
+            )
+            .arg(
+                Arg::with_name(ARG_SEED)
+                    .long("seed")
+                    .takes_value(true)
+                    .help("sets the seed of the generator (taken from the clock by default)"),
+            )
+            .arg(
+                Arg::with_name(ARG_OUTPUT_FILE)
+                    .long("output-file")
+                    .short("o")
+                    .takes_value(true)
+                    .help("sets the file in which the framework is written (Aspartix format)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_MODIFICATION_FILE)
+                    .long("modification-file")
+                    .short("m")
+                    .takes_value(true)
+                    .help("sets the file in which the dynamics of the framework is written"),
+            )
+            .arg(
+                Arg::with_name(ARG_STEPS)
+                    .long("steps")
+                    .takes_value(true)
+                    .default_value("10")
+                    .requires(ARG_MODIFICATION_FILE)
+                    .help("sets the number of modifications of the dynamics"),
+            )
+            .arg(
+                Arg::with_name(ARG_FOCUS_ARGUMENT)
+                    .long("focus-argument")
+                    .takes_value(true)
+                    .requires(ARG_MODIFICATION_FILE)
+                    .help("biases the modifications toward the neighborhood of an argument (e.g. the queried one), which is never removed"),
+            )
+            .arg(
+                Arg::with_name(ARG_FOCUS_BIAS)
+                    .long("focus-bias")
+                    .takes_value(true)
+                    .default_value("0.8")
+                    .help("sets the probability of a modification to be drawn in the neighborhood of the focus argument"),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let seed = match arg_matches.value_of(ARG_SEED) {
+            Some(s) => s
+                .parse::<u64>()
+                .with_context(|| format!(r#"while parsing the seed "{}""#, s))?,
+            None => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or_default(),
+        };
+        let mut generator = FrameworkGenerator::default();
+        let mut rng = TestRng::new(seed);
+        let framework = match arg_matches.value_of(ARG_TEMPLATE) {
+            Some(t) => {
+                let template = t.parse::<Template>()?;
+                let framework = template.framework();
+                info!(
+                    "using the {} template, a framework of {}",
+                    template,
+                    framework.summary()
+                );
+                framework
+            }
+            None => {
+                let n_arguments = read_count(arg_matches, ARG_ARGUMENTS)?;
+                let density = read_probability(arg_matches, ARG_DENSITY)?;
+                let structure = arg_matches
+                    .value_of(ARG_STRUCTURE)
+                    .unwrap()
+                    .parse::<Structure>()?;
+                generator.set_structure(structure);
+                let framework = generator.framework(&mut rng, n_arguments, density);
+                info!(
+                    "generated a {} framework of {} with seed {}",
+                    structure,
+                    framework.summary(),
+                    seed
+                );
+                framework
+            }
+        };
+        if let Some(a) = arg_matches.value_of(ARG_FOCUS_ARGUMENT) {
+            if framework.argument_set().get_argument_index(a).is_err() {
+                return Err(anyhow!(r#"no such focus argument: "{}""#, a));
+            }
+            generator.set_focus(a, read_probability(arg_matches, ARG_FOCUS_BIAS)?);
+        }
+        let mut af_writer = create_file(arg_matches.value_of(ARG_OUTPUT_FILE).unwrap())?;
+        AspartixWriter::default()
+            .write(&framework, &mut af_writer)
+            .context("while writing the framework")?;
+        if let Some(path) = arg_matches.value_of(ARG_MODIFICATION_FILE) {
+            let modifications =
+                generator.modifications(&mut rng, &framework, read_count(arg_matches, ARG_STEPS)?);
+            write_modifications(&modifications, &mut create_file(path)?)?;
+            info!("generated {} modification(s)", modifications.len());
+        }
+        Ok(())
+    }
+}
+
+/// Reads the value of an argument which must be a probability.
+pub(crate) fn read_probability(
+    arg_matches: &crusti_app_helper::ArgMatches<'_>,
+    arg: &str,
+) -> Result<f64> {
+    let value = arg_matches.value_of(arg).unwrap();
+    let probability = value
+        .parse::<f64>()
+        .with_context(|| format!(r#"while parsing "{}""#, value))?;
+    if !(0.0..=1.0).contains(&probability) {
+        return Err(anyhow!("{} must be between 0 and 1", value));
+    }
+    Ok(probability)
+}
+
+fn create_file(path: &str) -> Result<BufWriter<CompressedWriter>> {
+    Ok(BufWriter::new(compression::create(path).with_context(
+        || format!(r#"while creating file "{}""#, path),
+    )?))
+}
+
+fn write_modifications(
+    modifications: &[Modification<String>],
+    writer: &mut dyn Write,
+) -> Result<()> {
+    for m in modifications.iter() {
+        writeln!(writer, "{}", m).context("while writing the modifications")?;
+    }
+    writer.flush().context("while writing the modifications")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::framework_state::FrameworkState;
+
+    #[test]
+    fn test_written_modifications_apply() {
+        let mut generator = FrameworkGenerator::default();
+        generator.set_structure(Structure::OddCycle);
+        let mut rng = TestRng::new(5);
+        let framework = generator.framework(&mut rng, 7, 0.1);
+        let modifications = generator.modifications(&mut rng, &framework, 15);
+        let mut output = Vec::new();
+        write_modifications(&modifications, &mut output).unwrap();
+        let mut state = FrameworkState::new(&framework);
+        let lines = String::from_utf8(output).unwrap();
+        assert_eq!(15, lines.lines().count());
+        lines.lines().for_each(|l| {
+            state.apply(l).unwrap();
+        });
+    }
+}
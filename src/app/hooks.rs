@@ -0,0 +1,121 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::warn;
+
+/// The environment variable giving the index of the step to the hook.
+pub(crate) const ENV_STEP: &str = "IDW_STEP";
+
+/// The environment variable giving the modification that led to the step (empty for the initial framework).
+pub(crate) const ENV_MODIFICATION: &str = "IDW_MODIFICATION";
+
+/// The environment variable giving the answer relayed for the step.
+pub(crate) const ENV_ANSWER: &str = "IDW_ANSWER";
+
+/// A user command run after each step of the dynamics, through the shell.
+///
+/// The step is described to the command by environment variables.
+/// The standard output of the command is redirected to the standard error of the wrapper, so that it cannot be mixed with the answers.
+pub(crate) struct StepHook {
+    command: String,
+}
+
+impl StepHook {
+    pub(crate) fn new(command: &str) -> Self {
+        StepHook {
+            command: command.to_string(),
+        }
+    }
+
+    /// Runs the hook for a step, waiting for it to end.
+    ///
+    /// A failure of the hook does not stop the run, but is logged as a warning.
+    pub(crate) fn run(&self, step: usize, modification: Option<&str>, answer: &str) {
+        if let Err(e) = self.try_run(step, modification, answer) {
+            warn!("{:#}", e);
+        }
+    }
+
+    fn try_run(&self, step: usize, modification: Option<&str>, answer: &str) -> Result<()> {
+        let context = || {
+            format!(
+                r#"while running the step hook "{}" for step {}"#,
+                self.command, step
+            )
+        };
+        let status = shell_command(&self.command)
+            .env(ENV_STEP, step.to_string())
+            .env(ENV_MODIFICATION, modification.unwrap_or_default())
+            .env(ENV_ANSWER, answer.trim())
+            .stdin(Stdio::null())
+            .stdout(Stdio::from(std::io::stderr()))
+            .status()
+            .with_context(context)?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(anyhow!("the hook failed ({})", status)).with_context(context)
+        }
+    }
+}
+
+#[cfg(unix)]
+fn shell_command(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_hook() {
+        let out = std::env::temp_dir().join(format!("idw-test-hook-{}", std::process::id()));
+        let hook = StepHook::new(&format!(
+            r#"echo "$IDW_STEP|$IDW_MODIFICATION|$IDW_ANSWER" >> '{}'"#,
+            out.display()
+        ));
+        hook.run(0, None, "YES\n");
+        hook.run(1, Some("+arg(b)."), "NO\n");
+        assert_eq!(
+            "0||YES\n1|+arg(b).|NO\n",
+            std::fs::read_to_string(&out).unwrap()
+        );
+        std::fs::remove_file(&out).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failing_hook() {
+        assert!(StepHook::new("exit 3").try_run(0, None, "YES").is_err());
+    }
+}
@@ -0,0 +1,465 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use crusti_app_helper::warn;
+use crusti_arg::{
+    compression,
+    dynamics::{self, Modification},
+    AspartixReader,
+};
+
+use super::json::json_string;
+use super::report::{RunResult, SolverSummary};
+use super::wrap_command::read_modifications;
+
+/// The maximal number of arguments of the frameworks drawn in the reports; the graphs of larger ones are omitted.
+const MAX_GRAPH_ARGUMENTS: usize = 500;
+
+const STYLE: &str = r##"
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; }
+section { border-top: 1px solid #888; margin-top: 2em; }
+svg { border: 1px solid #ccc; cursor: move; }
+.attack { stroke: #555; fill: none; }
+.argument circle { fill: #dde8f8; stroke: #335; }
+.argument text { font-size: 11px; pointer-events: none; }
+.failed { color: #b00; }
+"##;
+
+/// The script drawing the graphs of the instances, whose data are given by the `REPORT` constant.
+///
+/// The arguments are laid out on a circle and can be dragged; a slider applies the modifications of the dynamics and gives the answers of the runs at each step.
+const SCRIPT: &str = r##"
+"use strict";
+const SVG_NS = "http://www.w3.org/2000/svg";
+const SIZE = 480;
+
+function svgElement(name, attributes) {
+  const element = document.createElementNS(SVG_NS, name);
+  for (const [key, value] of Object.entries(attributes)) {
+    element.setAttribute(key, value);
+  }
+  return element;
+}
+
+function stateAt(graph, step) {
+  let args = graph.arguments.slice();
+  let attacks = graph.attacks.map(a => a.slice());
+  for (const [op, kind, labels] of graph.modifications.slice(0, step)) {
+    if (kind === "arg" && op === "+") {
+      args.push(labels[0]);
+    } else if (kind === "arg") {
+      args = args.filter(a => a !== labels[0]);
+      attacks = attacks.filter(([a, b]) => a !== labels[0] && b !== labels[0]);
+    } else if (op === "+") {
+      attacks.push(labels);
+    } else {
+      attacks = attacks.filter(([a, b]) => a !== labels[0] || b !== labels[1]);
+    }
+  }
+  return { args, attacks };
+}
+
+function layout(graph) {
+  const labels = [];
+  const seen = new Set();
+  const add = label => {
+    if (!seen.has(label)) {
+      seen.add(label);
+      labels.push(label);
+    }
+  };
+  graph.arguments.forEach(add);
+  graph.modifications.filter(m => m[1] === "arg").forEach(m => add(m[2][0]));
+  const positions = new Map();
+  const radius = SIZE / 2 - 30;
+  labels.forEach((label, i) => {
+    const angle = 2 * Math.PI * i / labels.length - Math.PI / 2;
+    positions.set(label, { x: SIZE / 2 + radius * Math.cos(angle), y: SIZE / 2 + radius * Math.sin(angle) });
+  });
+  return positions;
+}
+
+function drawGraph(svg, graph, positions, step) {
+  while (svg.firstChild) {
+    svg.removeChild(svg.firstChild);
+  }
+  const markerId = svg.id + "-arrow";
+  const defs = svgElement("defs", {});
+  const marker = svgElement("marker", { id: markerId, viewBox: "0 0 10 10", refX: 24, refY: 5, markerWidth: 6, markerHeight: 6, orient: "auto" });
+  marker.appendChild(svgElement("path", { d: "M 0 0 L 10 5 L 0 10 z" }));
+  defs.appendChild(marker);
+  svg.appendChild(defs);
+  const state = stateAt(graph, step);
+  for (const [a, b] of state.attacks) {
+    const p = positions.get(a);
+    const q = positions.get(b);
+    if (a === b) {
+      svg.appendChild(svgElement("circle", { cx: p.x, cy: p.y - 18, r: 10, class: "attack" }));
+    } else {
+      svg.appendChild(svgElement("line", { x1: p.x, y1: p.y, x2: q.x, y2: q.y, class: "attack", "marker-end": "url(#" + markerId + ")" }));
+    }
+  }
+  for (const label of state.args) {
+    const p = positions.get(label);
+    const node = svgElement("g", { class: "argument", transform: "translate(" + p.x + "," + p.y + ")" });
+    node.appendChild(svgElement("circle", { r: 14 }));
+    const text = svgElement("text", { "text-anchor": "middle", dy: "0.35em" });
+    text.textContent = label;
+    node.appendChild(text);
+    node.addEventListener("mousedown", event => {
+      event.preventDefault();
+      svg.dragged = label;
+    });
+    svg.appendChild(node);
+  }
+}
+
+function cell(row, content) {
+  const td = document.createElement("td");
+  td.textContent = content;
+  row.appendChild(td);
+  return td;
+}
+
+function renderInstance(container, instance, index) {
+  const section = document.createElement("section");
+  const title = document.createElement("h3");
+  title.textContent = instance.name;
+  section.appendChild(title);
+  const graph = instance.graph;
+  const nSteps = Math.max(0, graph ? graph.modifications.length : 0, ...instance.runs.map(r => r.answers.length - 1));
+  const controls = document.createElement("p");
+  const slider = document.createElement("input");
+  Object.assign(slider, { type: "range", min: 0, max: nSteps, value: 0 });
+  const stepLabel = document.createElement("span");
+  controls.append(slider, " ", stepLabel);
+  section.appendChild(controls);
+  let svg = null;
+  let positions = null;
+  const redraw = () => drawGraph(svg, graph, positions, Math.min(Number(slider.value), graph.modifications.length));
+  if (graph) {
+    svg = svgElement("svg", { id: "graph-" + index, width: SIZE, height: SIZE, viewBox: "0 0 " + SIZE + " " + SIZE });
+    positions = layout(graph);
+    svg.addEventListener("mousemove", event => {
+      if (svg.dragged === undefined || svg.dragged === null) {
+        return;
+      }
+      const point = svg.createSVGPoint();
+      point.x = event.clientX;
+      point.y = event.clientY;
+      const p = point.matrixTransform(svg.getScreenCTM().inverse());
+      positions.set(svg.dragged, { x: p.x, y: p.y });
+      redraw();
+    });
+    const release = () => { svg.dragged = null; };
+    svg.addEventListener("mouseup", release);
+    svg.addEventListener("mouseleave", release);
+    section.appendChild(svg);
+  } else {
+    const note = document.createElement("p");
+    note.textContent = "The framework of this instance is not available.";
+    section.appendChild(note);
+  }
+  const table = document.createElement("table");
+  const header = table.createTHead().insertRow();
+  ["solver", "problem", "arguments", "status", "time (s)", "answer"].forEach(h => cell(header, h));
+  const answerCells = instance.runs.map(run => {
+    const row = table.insertRow();
+    if (run.status !== 0) {
+      row.className = "failed";
+    }
+    cell(row, run.solver);
+    cell(row, run.problem);
+    cell(row, run.arguments.join(" "));
+    cell(row, run.status);
+    cell(row, run.time.toFixed(3));
+    return cell(row, "");
+  });
+  section.appendChild(table);
+  const update = () => {
+    const step = Number(slider.value);
+    stepLabel.textContent = "step " + step + " / " + nSteps;
+    if (graph) {
+      redraw();
+    }
+    instance.runs.forEach((run, i) => {
+      answerCells[i].textContent = step < run.answers.length ? run.answers[step] : "";
+    });
+  };
+  slider.addEventListener("input", update);
+  update();
+  container.appendChild(section);
+}
+
+REPORT.instances.forEach((instance, i) => renderInstance(document.getElementById("instances"), instance, i));
+"##;
+
+/// Writes a self-contained HTML report of the runs.
+///
+/// The report gives the summary of each solver, and for each instance an interactive drawing of its framework along its dynamics, with the answers of the runs at each step.
+/// The frameworks and the dynamics are read from the input files of the runs; when they cannot be read or are too large, the drawing is omitted.
+pub(crate) fn write_html_report(
+    writer: &mut dyn Write,
+    results: &[RunResult],
+    summaries: &[SolverSummary],
+    timeout: f64,
+) -> Result<()> {
+    writeln!(writer, "<!DOCTYPE html>")?;
+    writeln!(writer, r#"<html lang="en">"#)?;
+    writeln!(writer, "<head>")?;
+    writeln!(writer, r#"<meta charset="utf-8">"#)?;
+    writeln!(writer, "<title>Results</title>")?;
+    writeln!(writer, "<style>{}</style>", STYLE)?;
+    writeln!(writer, "</head>")?;
+    writeln!(writer, "<body>")?;
+    writeln!(writer, "<h1>Results</h1>")?;
+    writeln!(writer, "<p>Time limit: {} s</p>", timeout)?;
+    writeln!(writer, "<table>")?;
+    writeln!(
+        writer,
+        "<tr><th>solver</th><th>runs</th><th>solved</th><th>PAR-2</th></tr>"
+    )?;
+    for s in summaries.iter() {
+        writeln!(
+            writer,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.3}</td></tr>",
+            html_text(s.solver),
+            s.n_runs,
+            s.n_solved,
+            s.par2
+        )?;
+    }
+    writeln!(writer, "</table>")?;
+    writeln!(writer, "<h2>Instances</h2>")?;
+    writeln!(writer, r#"<div id="instances"></div>"#)?;
+    writeln!(
+        writer,
+        "<script>const REPORT = {};</script>",
+        script_safe(&report_json(results))
+    )?;
+    writeln!(writer, "<script>{}</script>", SCRIPT)?;
+    writeln!(writer, "</body>")?;
+    writeln!(writer, "</html>")?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Builds the data of the report, grouping the runs by instance in the order of their first appearance.
+fn report_json(results: &[RunResult]) -> String {
+    let mut instances: Vec<(&RunResult, Vec<&RunResult>)> = vec![];
+    for r in results.iter() {
+        match instances.iter_mut().find(|(i, _)| i.instance == r.instance) {
+            Some((_, runs)) => runs.push(r),
+            None => instances.push((r, vec![r])),
+        }
+    }
+    let instances = instances
+        .iter()
+        .map(|(first, runs)| {
+            let graph = match graph_json(&first.input_files) {
+                Ok(g) => g,
+                Err(e) => {
+                    warn!(
+                        r#"cannot draw the framework of instance "{}": {:#}"#,
+                        first.instance, e
+                    );
+                    None
+                }
+            };
+            format!(
+                r#"{{"name": {}, "graph": {}, "runs": [{}]}}"#,
+                json_string(&first.instance),
+                graph.unwrap_or_else(|| "null".to_string()),
+                runs.iter()
+                    .map(|r| run_json(r))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        })
+        .collect::<Vec<String>>();
+    format!(r#"{{"instances": [{}]}}"#, instances.join(", "))
+}
+
+fn run_json(run: &RunResult) -> String {
+    format!(
+        r#"{{"solver": {}, "problem": {}, "arguments": {}, "status": {}, "time": {}, "answers": {}}}"#,
+        json_string(&run.solver),
+        json_string(&run.problem),
+        string_array(&run.arguments),
+        run.status,
+        run.time,
+        string_array(&run.answers)
+    )
+}
+
+/// Reads the framework and the dynamics of an instance, given by its first two input files, and returns their data.
+///
+/// `None` is returned if the instance has no input file or if its framework is too large to be drawn.
+fn graph_json(input_files: &[String]) -> Result<Option<String>> {
+    let path = match input_files.first() {
+        Some(p) => p,
+        None => return Ok(None),
+    };
+    let framework = AspartixReader::default()
+        .read(
+            &mut compression::open(path)
+                .with_context(|| format!(r#"while opening file "{}""#, path))?,
+        )
+        .with_context(|| format!(r#"while reading file "{}""#, path))?;
+    if framework.argument_set().len() > MAX_GRAPH_ARGUMENTS {
+        return Ok(None);
+    }
+    let modifications = match input_files.get(1) {
+        Some(p) => read_modifications(p)?
+            .iter()
+            .map(|m| {
+                dynamics::read_modification(m)
+                    .with_context(|| format!(r#"while reading modification "{}""#, m))
+                    .map(|m| modification_json(&m))
+            })
+            .collect::<Result<Vec<String>>>()?,
+        None => vec![],
+    };
+    Ok(Some(format!(
+        r#"{{"arguments": {}, "attacks": [{}], "modifications": [{}]}}"#,
+        string_array(
+            &framework
+                .argument_set()
+                .iter()
+                .map(|a| a.label().clone())
+                .collect::<Vec<String>>()
+        ),
+        framework
+            .iter_attacks()
+            .map(|att| format!(
+                "[{}, {}]",
+                json_string(att.attacker().label()),
+                json_string(att.attacked().label())
+            ))
+            .collect::<Vec<String>>()
+            .join(", "),
+        modifications.join(", ")
+    )))
+}
+
+/// Formats a modification as an array made of its sign, its kind (`arg` or `att`) and its labels.
+fn modification_json(modification: &Modification<String>) -> String {
+    let (sign, kind, labels) = match modification {
+        Modification::AddArgument(a) => ("+", "arg", vec![a.clone()]),
+        Modification::RemoveArgument(a) => ("-", "arg", vec![a.clone()]),
+        Modification::AddAttack(a, b) => ("+", "att", vec![a.clone(), b.clone()]),
+        Modification::RemoveAttack(a, b) => ("-", "att", vec![a.clone(), b.clone()]),
+    };
+    format!(r#"["{}", "{}", {}]"#, sign, kind, string_array(&labels))
+}
+
+fn string_array(values: &[String]) -> String {
+    format!(
+        "[{}]",
+        values
+            .iter()
+            .map(|v| json_string(v))
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+
+/// Prevents JSON data from closing the script element in which it is embedded.
+fn script_safe(json: &str) -> String {
+    json.replace("</", "<\\/")
+}
+
+/// Escapes the HTML special characters of a text.
+fn html_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::json::JsonValue;
+    use crate::app::report::{self, tests::result};
+
+    #[test]
+    fn test_html_text() {
+        assert_eq!(
+            "a &lt;b&gt; &amp; &quot;c&quot;",
+            html_text(r#"a <b> & "c""#)
+        );
+        assert_eq!(r#"["<\/script>"]"#, script_safe(r#"["</script>"]"#));
+    }
+
+    #[test]
+    fn test_report_json() {
+        let prefix = std::env::temp_dir().join(format!("idw-test-html-{}", std::process::id()));
+        let af_path = prefix.with_extension("apx").to_string_lossy().to_string();
+        let dyn_path = prefix.with_extension("apxm").to_string_lossy().to_string();
+        std::fs::write(&af_path, "arg(a).\narg(b).\natt(a,b).\n").unwrap();
+        std::fs::write(&dyn_path, "+arg(c).\n-att(a,b).\n").unwrap();
+        let mut run = result("s1", &af_path, 0, 1.5);
+        run.input_files.push(dyn_path.clone());
+        run.answers = vec!["YES".to_string(), "NO".to_string()];
+        let json = report_json(&[run, result("s2", "missing.apx", 20, 3.)]);
+        std::fs::remove_file(&af_path).unwrap();
+        std::fs::remove_file(&dyn_path).unwrap();
+        let value = JsonValue::parse(&json).unwrap();
+        let instances = value.get("instances").unwrap().as_array().unwrap();
+        assert_eq!(2, instances.len());
+        let graph = instances[0].get("graph").unwrap();
+        assert_eq!(2, graph.get("arguments").unwrap().as_array().unwrap().len());
+        assert_eq!(
+            Some("-"),
+            graph.get("modifications").unwrap().as_array().unwrap()[1]
+                .as_array()
+                .unwrap()[0]
+                .as_str()
+        );
+        let runs = instances[0].get("runs").unwrap().as_array().unwrap();
+        assert_eq!(Some(1.5), runs[0].get("time").and_then(|t| t.as_f64()));
+        assert_eq!(Some(&JsonValue::Null), instances[1].get("graph"));
+    }
+
+    #[test]
+    fn test_write_html_report() {
+        let results = vec![result("s<1>", "missing.apx", 0, 1.)];
+        let summaries = report::summarize(&results, 10.);
+        let mut out = Vec::new();
+        write_html_report(&mut out, &results, &summaries, 10.).unwrap();
+        let html = String::from_utf8(out).unwrap();
+        assert!(html.contains("<tr><td>s&lt;1&gt;</td><td>1</td><td>1</td><td>1.000</td></tr>"));
+        assert!(
+            html.contains(r#"const REPORT = {"instances": [{"name": "missing.apx", "graph": null"#)
+        );
+    }
+}
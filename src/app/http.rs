@@ -0,0 +1,187 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::io::{BufRead, Write};
+
+use anyhow::{anyhow, Context, Result};
+
+use super::json::json_string;
+
+/// The maximal size of a request body, which must be large enough for the uploaded frameworks.
+const MAX_BODY_SIZE: usize = 64 << 20;
+
+/// The maximal number of header lines of a request.
+const MAX_HEADERS: usize = 100;
+
+/// An HTTP request, as read by the service mode.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Request {
+    pub(crate) method: String,
+    /// The path of the request, without its query string.
+    pub(crate) path: String,
+    pub(crate) body: String,
+}
+
+impl Request {
+    /// Returns the non-empty segments of the path.
+    pub(crate) fn segments(&self) -> Vec<&str> {
+        self.path.split('/').filter(|s| !s.is_empty()).collect()
+    }
+}
+
+/// An HTTP response with a JSON body.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Response {
+    pub(crate) status: u16,
+    pub(crate) body: String,
+}
+
+impl Response {
+    pub(crate) fn json(status: u16, body: String) -> Self {
+        Response { status, body }
+    }
+
+    /// Builds an error response, whose body is a JSON object giving the error message.
+    pub(crate) fn error(status: u16, e: &anyhow::Error) -> Self {
+        Response {
+            status,
+            body: format!(r#"{{"error":{}}}"#, json_string(&format!("{:#}", e))),
+        }
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+/// Reads an HTTP/1.x request, whose body is given by its `Content-Length` header.
+pub(crate) fn read_request(reader: &mut dyn BufRead) -> Result<Request> {
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .context("while reading the request line")?;
+    let mut parts = line.split_whitespace();
+    let (method, target) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(m), Some(t), Some(v)) if v.starts_with("HTTP/1.") => (m, t),
+        _ => return Err(anyhow!("malformed request line: {:?}", line.trim_end())),
+    };
+    let path = target.split('?').next().unwrap().to_string();
+    let method = method.to_string();
+    let mut content_length = 0;
+    for i in 0.. {
+        if i == MAX_HEADERS {
+            return Err(anyhow!("too many headers"));
+        }
+        line.clear();
+        reader
+            .read_line(&mut line)
+            .context("while reading the request headers")?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| anyhow!("malformed header: {:?}", header))?;
+        if name.trim().eq_ignore_ascii_case("content-length") {
+            content_length = value
+                .trim()
+                .parse::<usize>()
+                .with_context(|| format!("invalid content length: {:?}", value.trim()))?;
+        }
+    }
+    if content_length > MAX_BODY_SIZE {
+        return Err(anyhow!("the request body exceeds {} bytes", MAX_BODY_SIZE));
+    }
+    let mut body = vec![0; content_length];
+    reader
+        .read_exact(&mut body)
+        .context("while reading the request body")?;
+    let body = String::from_utf8(body).context("the request body is not valid UTF-8")?;
+    Ok(Request { method, path, body })
+}
+
+/// Writes an HTTP response, closing the connection after it.
+pub(crate) fn write_response(writer: &mut dyn Write, response: &Response) -> Result<()> {
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason_phrase(response.status),
+        response.body.len(),
+        response.body
+    )
+    .and_then(|_| writer.flush())
+    .context("while writing the response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_request() {
+        let request = read_request(
+            &mut "POST /sessions/1/modifications?x=y HTTP/1.1\r\nHost: localhost\r\ncontent-length: 4\r\n\r\n{}\r\nignored"
+                .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!("POST", request.method);
+        assert_eq!("/sessions/1/modifications", request.path);
+        assert_eq!("{}\r\n", request.body);
+        assert_eq!(vec!["sessions", "1", "modifications"], request.segments());
+    }
+
+    #[test]
+    fn test_read_request_errors() {
+        for request in &[
+            "",
+            "GET /\r\n\r\n",
+            "GET / HTTP/1.1\r\nHost\r\n\r\n",
+            "POST / HTTP/1.1\r\nContent-Length: x\r\n\r\n",
+            "POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\n{}",
+        ] {
+            assert!(
+                read_request(&mut request.as_bytes()).is_err(),
+                "{}",
+                request
+            );
+        }
+    }
+
+    #[test]
+    fn test_write_response() {
+        let mut out = Vec::new();
+        write_response(&mut out, &Response::error(404, &anyhow!("no \"x\""))).unwrap();
+        assert_eq!(
+            "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: 20\r\nConnection: close\r\n\r\n{\"error\":\"no \\\"x\\\"\"}",
+            String::from_utf8(out).unwrap()
+        );
+    }
+}
@@ -0,0 +1,312 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use anyhow::{anyhow, Result};
+
+/// A JSON value, as read from the run records and the results files.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Returns the value associated with a key if this value is an object containing it.
+    pub(crate) fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_f64(&self) -> Option<f64> {
+        match self {
+            JsonValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_array(&self) -> Option<&[JsonValue]> {
+        match self {
+            JsonValue::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Parses a JSON document, which must contain a single value.
+    pub(crate) fn parse(content: &str) -> Result<JsonValue> {
+        let mut parser = Parser {
+            content: content.as_bytes(),
+            position: 0,
+        };
+        let value = parser.parse_value()?;
+        parser.skip_whitespaces();
+        if parser.position < parser.content.len() {
+            return Err(parser.error("unexpected content after the JSON value"));
+        }
+        Ok(value)
+    }
+}
+
+struct Parser<'a> {
+    content: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> anyhow::Error {
+        anyhow!("{} at byte {}", message, self.position)
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while self.position < self.content.len()
+            && self.content[self.position].is_ascii_whitespace()
+        {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespaces();
+        self.content.get(self.position).copied()
+    }
+
+    fn expect(&mut self, c: u8) -> Result<()> {
+        if self.peek() == Some(c) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c as char)))
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue> {
+        match self.peek() {
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'"') => Ok(JsonValue::String(self.parse_string()?)),
+            Some(b'-') | Some(b'0'..=b'9') => self.parse_number(),
+            Some(_) => {
+                for (keyword, value) in &[
+                    ("null", JsonValue::Null),
+                    ("true", JsonValue::Bool(true)),
+                    ("false", JsonValue::Bool(false)),
+                ] {
+                    if self.content[self.position..].starts_with(keyword.as_bytes()) {
+                        self.position += keyword.len();
+                        return Ok(value.clone());
+                    }
+                }
+                Err(self.error("unexpected character"))
+            }
+            None => Err(self.error("unexpected end of content")),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<JsonValue> {
+        self.expect(b'{')?;
+        let mut entries = vec![];
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(JsonValue::Object(entries));
+        }
+        loop {
+            if self.peek() != Some(b'"') {
+                return Err(self.error("expected an object key"));
+            }
+            let key = self.parse_string()?;
+            self.expect(b':')?;
+            entries.push((key, self.parse_value()?));
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(JsonValue::Object(entries));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<JsonValue> {
+        self.expect(b'[')?;
+        let mut values = vec![];
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(JsonValue::Array(values));
+        }
+        loop {
+            values.push(self.parse_value()?);
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(JsonValue::Array(values));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue> {
+        let start = self.position;
+        while self.position < self.content.len()
+            && matches!(
+                self.content[self.position],
+                b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9'
+            )
+        {
+            self.position += 1;
+        }
+        std::str::from_utf8(&self.content[start..self.position])
+            .ok()
+            .and_then(|n| n.parse::<f64>().ok())
+            .map(JsonValue::Number)
+            .ok_or_else(|| self.error("invalid number"))
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        self.expect(b'"')?;
+        let mut bytes = vec![];
+        loop {
+            match self.content.get(self.position) {
+                None => return Err(self.error("unterminated string")),
+                Some(b'"') => {
+                    self.position += 1;
+                    break;
+                }
+                Some(b'\\') => {
+                    self.position += 1;
+                    let escaped = match self.content.get(self.position) {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => self.parse_unicode_escape()?,
+                        _ => return Err(self.error("invalid escape sequence")),
+                    };
+                    self.position += 1;
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(escaped.encode_utf8(&mut buffer).as_bytes());
+                }
+                Some(b) => {
+                    bytes.push(*b);
+                    self.position += 1;
+                }
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| self.error("invalid UTF-8 string"))
+    }
+
+    /// Reads the four hexadecimal digits following `\u`, leaving the position on the last one.
+    fn parse_unicode_escape(&mut self) -> Result<char> {
+        let digits = self
+            .content
+            .get(self.position + 1..self.position + 5)
+            .and_then(|d| std::str::from_utf8(d).ok())
+            .and_then(|d| u32::from_str_radix(d, 16).ok())
+            .ok_or_else(|| self.error("invalid unicode escape sequence"))?;
+        self.position += 4;
+        std::char::from_u32(digits).ok_or_else(|| self.error("unsupported unicode escape sequence"))
+    }
+}
+
+/// Formats a string as a JSON string literal.
+pub(crate) fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let value = JsonValue::parse(
+            r#" {"a": [1, -2.5e1, null, true], "b\n": {"c": "d\"\u00e9"}, "e": {}} "#,
+        )
+        .unwrap();
+        assert_eq!(
+            Some(
+                &[
+                    JsonValue::Number(1.),
+                    JsonValue::Number(-25.),
+                    JsonValue::Null,
+                    JsonValue::Bool(true)
+                ][..]
+            ),
+            value.get("a").and_then(|a| a.as_array())
+        );
+        assert_eq!(
+            Some("d\"é"),
+            value
+                .get("b\n")
+                .and_then(|b| b.get("c"))
+                .and_then(|c| c.as_str())
+        );
+        assert_eq!(Some(&JsonValue::Object(vec![])), value.get("e"));
+        assert_eq!(None, value.get("f"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for content in &["", "[1,]", "{\"a\" 1}", "\"abc", "[1] 2", "nul", "\"\\x\""] {
+            assert!(JsonValue::parse(content).is_err(), "{}", content);
+        }
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(r#""a\"b\\c\nd""#, json_string("a\"b\\c\nd"));
+        assert_eq!(r#""\u0001""#, json_string("\u{1}"));
+        assert_eq!(
+            Ok(JsonValue::String("a\"\u{1}".to_string())),
+            JsonValue::parse(&json_string("a\"\u{1}")).map_err(|e| e.to_string())
+        );
+    }
+}
@@ -0,0 +1,100 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use crusti_arg::{AAFramework, Semantics};
+
+/// Returns `true` iff the semantics satisfies the directionality principle.
+///
+/// Under such a semantics, the acceptance of an argument only depends on the arguments that reach it in the attack graph.
+/// The stable, semi-stable and stage semantics are not directional: a modification may change the acceptance of any argument.
+pub(crate) fn is_directional(semantics: Semantics) -> bool {
+    matches!(
+        semantics,
+        Semantics::Grounded | Semantics::Complete | Semantics::Preferred | Semantics::Ideal
+    )
+}
+
+/// Computes the arguments whose acceptance may have changed after a modification, as a membership vector.
+///
+/// The changed arguments are the ones whose attackers have been modified (see [`FrameworkState::apply`]); labels absent from the framework are ignored.
+/// The affected arguments are the ones of the strongly connected components of the changed arguments, and of the components these ones reach.
+///
+/// [`FrameworkState::apply`]: ../framework_state/struct.FrameworkState.html#method.apply
+pub(crate) fn affected_arguments(framework: &AAFramework<String>, changed: &[String]) -> Vec<bool> {
+    let n_args = framework.argument_set().len();
+    let components = framework.strongly_connected_components();
+    let mut component_of = vec![0; n_args];
+    for (i, component) in components.iter().enumerate() {
+        component.iter().for_each(|arg| component_of[*arg] = i);
+    }
+    let mut attacked_lists = vec![vec![]; n_args];
+    for att in framework.iter_attacks() {
+        attacked_lists[att.attacker().id()].push(att.attacked().id());
+    }
+    let mut affected_components = vec![false; components.len()];
+    changed
+        .iter()
+        .filter_map(|l| framework.argument_set().get_argument_index(l).ok())
+        .for_each(|arg| affected_components[component_of[arg]] = true);
+    for (i, component) in components.iter().enumerate() {
+        if !affected_components[i] {
+            continue;
+        }
+        for arg in component.iter() {
+            for attacked in attacked_lists[*arg].iter() {
+                affected_components[component_of[*attacked]] = true;
+            }
+        }
+    }
+    (0..n_args)
+        .map(|arg| affected_components[component_of[arg]])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crusti_arg::AspartixReader;
+
+    #[test]
+    fn test_affected_arguments() {
+        let af = AspartixReader::default()
+            .read(
+                &mut "arg(a).\narg(b).\narg(c).\narg(d).\narg(e).\natt(a,b).\natt(b,c).\natt(c,b).\natt(c,d).\n"
+                    .as_bytes(),
+            )
+            .unwrap();
+        assert_eq!(
+            vec![false, true, true, true, false],
+            affected_arguments(&af, &["c".to_string()])
+        );
+        assert_eq!(
+            vec![false, false, false, true, false],
+            affected_arguments(&af, &["d".to_string(), "f".to_string()])
+        );
+        assert_eq!(vec![false; 5], affected_arguments(&af, &[]));
+    }
+
+    #[test]
+    fn test_is_directional() {
+        assert!(is_directional(Semantics::Preferred));
+        assert!(!is_directional(Semantics::Stable));
+        assert!(!is_directional(Semantics::SemiStable));
+    }
+}
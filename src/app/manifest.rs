@@ -0,0 +1,291 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    fs::File,
+    io::Write,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use crusti_app_helper::ExitStatusError;
+
+use super::exit_status::{
+    FAILURE, INTERRUPTED, MALFORMED_INPUT, SOLVER_CRASH, TIMEOUT, WRONG_ANSWER,
+};
+use super::json::json_string;
+
+/// The record of a `wrap` run, written as a JSON object.
+///
+/// It gives the command line, the solver and its query, the hashes of the input files, the answers and timings of each step, and the classification of the run outcome.
+pub(crate) struct RunRecord {
+    command: Vec<String>,
+    solver: String,
+    problem: String,
+    arguments: Vec<String>,
+    input_files: Vec<(String, Option<String>)>,
+    steps: Vec<(String, Option<Duration>)>,
+    total_time: Duration,
+    status: i32,
+    error: Option<String>,
+    start: Instant,
+    last_step: Instant,
+}
+
+impl RunRecord {
+    /// Builds a new record, starting the timer of the run.
+    pub(crate) fn new(solver: &str, problem: &str) -> Self {
+        let now = Instant::now();
+        RunRecord {
+            command: std::env::args().collect(),
+            solver: solver.to_string(),
+            problem: problem.to_string(),
+            arguments: vec![],
+            input_files: vec![],
+            steps: vec![],
+            total_time: Duration::default(),
+            status: 0,
+            error: None,
+            start: now,
+            last_step: now,
+        }
+    }
+
+    pub(crate) fn set_arguments(&mut self, arguments: &[String]) {
+        self.arguments = arguments.to_vec();
+    }
+
+    /// Adds an input file to the record, computing the hash of its content if it can be read.
+    pub(crate) fn add_input_file(&mut self, path: &str) {
+        let hash = std::fs::read(path)
+            .ok()
+            .map(|content| hash_content(&content));
+        self.input_files.push((path.to_string(), hash));
+    }
+
+    /// Adds the answer of a step.
+    ///
+    /// If `timed` is set, the step is given the time elapsed since the previous one (or since the beginning of the run).
+    /// It should not be set when the answers are not relayed as soon as they are computed.
+    pub(crate) fn add_step(&mut self, answer: &str, timed: bool) {
+        let now = Instant::now();
+        let time = if timed {
+            Some(now - self.last_step)
+        } else {
+            None
+        };
+        self.last_step = now;
+        self.push_step(answer, time);
+    }
+
+    fn push_step(&mut self, answer: &str, time: Option<Duration>) {
+        self.steps.push((answer.trim().to_string(), time));
+    }
+
+    /// Sets the outcome of the run, stopping its timer.
+    pub(crate) fn finish(&mut self, result: &Result<()>) {
+        let total_time = self.start.elapsed();
+        self.set_outcome(result, total_time);
+    }
+
+    fn set_outcome(&mut self, result: &Result<()>, total_time: Duration) {
+        self.total_time = total_time;
+        if let Err(e) = result {
+            match e.downcast_ref::<ExitStatusError>() {
+                Some(s) => {
+                    self.status = s.status();
+                    self.error = Some(s.causes().join(": "));
+                }
+                None => {
+                    self.status = FAILURE;
+                    self.error = Some(format!("{:#}", e));
+                }
+            }
+        }
+    }
+
+    pub(crate) fn write(&self, writer: &mut dyn Write) -> Result<()> {
+        let string_array = |values: &[String]| {
+            format!(
+                "[{}]",
+                values
+                    .iter()
+                    .map(|v| json_string(v))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        };
+        writeln!(writer, "{{")?;
+        writeln!(writer, r#"  "command": {},"#, string_array(&self.command))?;
+        writeln!(writer, r#"  "solver": {},"#, json_string(&self.solver))?;
+        writeln!(writer, r#"  "problem": {},"#, json_string(&self.problem))?;
+        writeln!(
+            writer,
+            r#"  "arguments": {},"#,
+            string_array(&self.arguments)
+        )?;
+        writeln!(writer, r#"  "input_files": ["#)?;
+        for (i, (path, hash)) in self.input_files.iter().enumerate() {
+            writeln!(
+                writer,
+                r#"    {{"path": {}, "hash": {}}}{}"#,
+                json_string(path),
+                hash.as_deref()
+                    .map(json_string)
+                    .unwrap_or_else(|| "null".to_string()),
+                if i + 1 < self.input_files.len() {
+                    ","
+                } else {
+                    ""
+                }
+            )?;
+        }
+        writeln!(writer, "  ],")?;
+        writeln!(writer, r#"  "steps": ["#)?;
+        for (i, (answer, time)) in self.steps.iter().enumerate() {
+            writeln!(
+                writer,
+                r#"    {{"step": {}, "answer": {}, "time_ms": {}}}{}"#,
+                i,
+                json_string(answer),
+                time.map(json_millis).unwrap_or_else(|| "null".to_string()),
+                if i + 1 < self.steps.len() { "," } else { "" }
+            )?;
+        }
+        writeln!(writer, "  ],")?;
+        writeln!(
+            writer,
+            r#"  "total_time_ms": {},"#,
+            json_millis(self.total_time)
+        )?;
+        writeln!(writer, r#"  "status": {},"#, self.status)?;
+        writeln!(
+            writer,
+            r#"  "classification": {},"#,
+            json_string(classification(self.status))
+        )?;
+        writeln!(
+            writer,
+            r#"  "error": {}"#,
+            self.error
+                .as_deref()
+                .map(json_string)
+                .unwrap_or_else(|| "null".to_string())
+        )?;
+        writeln!(writer, "}}")?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub(crate) fn save(&self, path: &str) -> Result<()> {
+        let context = || format!(r#"while writing the result record to "{}""#, path);
+        let mut file = File::create(path).with_context(context)?;
+        self.write(&mut file).with_context(context)
+    }
+}
+
+/// Returns the name of the classification associated to an exit status.
+fn classification(status: i32) -> &'static str {
+    match status {
+        0 => "success",
+        WRONG_ANSWER => "wrong-answer",
+        SOLVER_CRASH => "crash",
+        TIMEOUT => "timeout",
+        MALFORMED_INPUT => "malformed-input",
+        INTERRUPTED => "interrupted",
+        _ => "failure",
+    }
+}
+
+/// Computes the 64-bits FNV-1a hash of a content, given as an hexadecimal string prefixed by the name of the algorithm.
+fn hash_content(content: &[u8]) -> String {
+    let hash = content.iter().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+        (h ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("fnv1a64:{:016x}", hash)
+}
+
+fn json_millis(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64() * 1000.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use crusti_app_helper::WithExitStatus;
+
+    fn record() -> RunRecord {
+        let mut record = RunRecord::new("./solver", "DC-CO-D");
+        record.set_arguments(&["a".to_string()]);
+        record.command = vec!["idw".to_string(), "wrap".to_string()];
+        record
+    }
+
+    #[test]
+    fn test_write_success() {
+        let mut record = record();
+        record
+            .input_files
+            .push(("af.apx".to_string(), Some(hash_content(b""))));
+        record.push_step("YES\n", Some(Duration::from_micros(1500)));
+        record.push_step("a: NO", None);
+        record.set_outcome(&Ok(()), Duration::from_millis(2));
+        let mut out = Vec::new();
+        record.write(&mut out).unwrap();
+        assert_eq!(
+            r#"{
+  "command": ["idw", "wrap"],
+  "solver": "./solver",
+  "problem": "DC-CO-D",
+  "arguments": ["a"],
+  "input_files": [
+    {"path": "af.apx", "hash": "fnv1a64:cbf29ce484222325"}
+  ],
+  "steps": [
+    {"step": 0, "answer": "YES", "time_ms": 1.500},
+    {"step": 1, "answer": "a: NO", "time_ms": null}
+  ],
+  "total_time_ms": 2.000,
+  "status": 0,
+  "classification": "success",
+  "error": null
+}
+"#,
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_finish_classified_error() {
+        let mut record = record();
+        record.finish(&Err(anyhow!("crash")).with_exit_status(SOLVER_CRASH));
+        assert_eq!(SOLVER_CRASH, record.status);
+        assert_eq!("crash", classification(record.status));
+        assert_eq!(Some("crash"), record.error.as_deref());
+        let mut unclassified = self::record();
+        unclassified.finish(&Err(anyhow!("error")));
+        assert_eq!(FAILURE, unclassified.status);
+    }
+
+    #[test]
+    fn test_hash_content() {
+        assert_eq!("fnv1a64:af63df4c8601f1a5", hash_content(b"b"));
+    }
+}
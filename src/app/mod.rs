@@ -17,4 +17,43 @@
 // Contributors:
 //   *   CRIL - initial API and implementation
 
+pub(crate) mod analyze_command;
+pub(crate) mod anonymize_command;
+pub(crate) mod batch_command;
+pub(crate) mod check_command;
+pub(crate) mod checkpoint;
+pub(crate) mod compile_dynamics_command;
+pub(crate) mod container;
+pub(crate) mod convert_dynamics_command;
+pub(crate) mod diff_command;
+pub(crate) mod enforce_command;
+pub(crate) mod exit_status;
+pub(crate) mod framework_state;
+#[cfg(feature = "testing")]
+pub(crate) mod fuzz_command;
+#[cfg(feature = "testing")]
+pub(crate) mod generate_command;
+pub(crate) mod hooks;
+pub(crate) mod html_report;
+pub(crate) mod http;
+pub(crate) mod json;
+pub(crate) mod locality;
+pub(crate) mod manifest;
+pub(crate) mod portfolio;
+pub(crate) mod progress;
+pub(crate) mod remote;
+pub(crate) mod report;
+pub(crate) mod report_command;
+#[cfg(feature = "testing")]
+pub(crate) mod sample_accept_command;
+pub(crate) mod serve_command;
+pub(crate) mod signals;
+pub(crate) mod solve_command;
+pub(crate) mod stats_command;
+pub(crate) mod temp_files;
+pub(crate) mod translation;
+pub(crate) mod validate_command;
+pub(crate) mod verification;
+pub(crate) mod viz_command;
+pub(crate) mod viz_dynamics_command;
 pub(crate) mod wrap_command;
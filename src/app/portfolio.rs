@@ -0,0 +1,429 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    collections::BTreeSet,
+    io::{BufReader, BufWriter},
+    sync::mpsc,
+};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::{info, warn, ExitStatusError};
+use crusti_arg::solutions;
+use iccma_dynamics::runner::{
+    check_end_of_output, execute_dynamics, AnswerConsumer, Cancellation, QueryType, SolverProcess,
+    Stop,
+};
+
+use super::exit_status::{classify_solver_error, SOLVER_CRASH, WRONG_ANSWER};
+use super::wrap_command::{send_preamble, timeout_error, SolverCommand};
+
+/// The way the answers of the solvers of a portfolio are combined.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum PortfolioMode {
+    /// The first valid answer of each step is relayed.
+    First,
+    /// The answers of all the solvers are awaited, and the majority answer of each step is relayed.
+    Majority,
+}
+
+/// An event sent by the thread handling a solver of the portfolio.
+enum PortfolioEvent {
+    /// The solver at the given index answered the given step.
+    Answer(usize, usize, String),
+    /// The solver at the given index failed, and left the portfolio.
+    Failure(usize, anyhow::Error),
+}
+
+/// Runs several solvers on the whole dynamics, relaying for each step an answer chosen according to the portfolio mode.
+///
+/// Each solver receives the modifications at its own pace, in a dedicated thread.
+/// A solver giving an invalid answer or crashing leaves the portfolio; the run fails if all the solvers left it before the last step is answered.
+/// Once the answers of all the steps are relayed, the runs of the solvers still in the portfolio are cancelled, which kills them.
+/// A solver reaching the time limit of its command is killed too, and leaves the portfolio.
+///
+/// The number of steps won by each solver is returned: the steps for which its answer was relayed in [`PortfolioMode::First`] mode, or the steps for which it agreed with the majority in [`PortfolioMode::Majority`] mode.
+pub(crate) fn run_portfolio(
+    commands: &[SolverCommand],
+    mode: PortfolioMode,
+    modifications: &[String],
+    query: &QueryType,
+    answer_consumer: &mut AnswerConsumer,
+) -> Result<Vec<usize>> {
+    let cancellation = Cancellation::new();
+    let mut processes = Vec::with_capacity(commands.len());
+    for command in commands.iter() {
+        let process = command
+            .spawn(Some(&cancellation))
+            .with_context(|| format!(r#"while spawning solver "{}""#, command.solver))?;
+        processes.push(process);
+    }
+    let joined_modifications = modifications.join("\n");
+    let (sender, receiver) = mpsc::channel();
+    std::thread::scope(|scope| {
+        for (i, (command, mut process)) in commands.iter().zip(processes).enumerate() {
+            let sender = sender.clone();
+            let joined_modifications = &joined_modifications;
+            scope.spawn(move || {
+                let result = run_portfolio_solver(
+                    i,
+                    command,
+                    &mut process,
+                    joined_modifications,
+                    query,
+                    &sender,
+                );
+                if let Err(e) = result {
+                    let _ = sender.send(PortfolioEvent::Failure(i, e));
+                }
+                drop(sender);
+                let _ = process.wait();
+            });
+        }
+        drop(sender);
+        let n_steps = modifications.len() + 1;
+        let result = match mode {
+            PortfolioMode::First => {
+                relay_first_answers(commands, n_steps, &receiver, answer_consumer)
+            }
+            PortfolioMode::Majority => {
+                relay_majority_answers(commands, n_steps, query, &receiver, answer_consumer)
+            }
+        };
+        cancellation.cancel();
+        result
+    })
+}
+
+/// Runs the dynamics protocol with a solver of the portfolio, sending its answers as events.
+///
+/// A solver failing for another reason than a crash is killed.
+fn run_portfolio_solver(
+    index: usize,
+    command: &SolverCommand,
+    process: &mut SolverProcess,
+    joined_modifications: &str,
+    query: &QueryType,
+    sender: &mpsc::Sender<PortfolioEvent>,
+) -> Result<()> {
+    let (child_stdin, child_stdout) = process.pipes();
+    let mut child_stdin = BufWriter::new(child_stdin);
+    let mut child_stdout = BufReader::new(child_stdout);
+    let mut step = 0;
+    let result = send_preamble(command, &mut child_stdin).and_then(|()| {
+        execute_dynamics(
+            &mut joined_modifications.as_bytes(),
+            query.answer_reading_function_with(command.solution_reader),
+            &Ok,
+            &mut |a| {
+                step += 1;
+                sender
+                    .send(PortfolioEvent::Answer(index, step - 1, a))
+                    .map_err(|_| anyhow!("the portfolio is over"))
+            },
+            &mut child_stdin,
+            &mut child_stdout,
+        )
+    });
+    drop(child_stdin);
+    let result = result.and_then(|last_step| check_end_of_output(&mut child_stdout, last_step));
+    result.map_err(|error| match process.stopped() {
+        Some(Stop::Timeout) => timeout_error(error, command.timeout),
+        Some(Stop::Cancellation) => error,
+        None if process.has_exited() => ExitStatusError::classify(error, SOLVER_CRASH),
+        None => {
+            process.kill();
+            classify_solver_error(error)
+        }
+    })
+}
+
+/// Relays the first answer received for each step, until all the steps are answered or all the solvers failed.
+fn relay_first_answers(
+    commands: &[SolverCommand],
+    n_steps: usize,
+    receiver: &mpsc::Receiver<PortfolioEvent>,
+    answer_consumer: &mut AnswerConsumer,
+) -> Result<Vec<usize>> {
+    let mut wins = vec![0; commands.len()];
+    let mut n_failed = 0;
+    let mut next_step = 0;
+    while next_step < n_steps {
+        match receiver.recv() {
+            Ok(PortfolioEvent::Answer(i, step, answer)) => {
+                if step == next_step {
+                    wins[i] += 1;
+                    next_step += 1;
+                    answer_consumer(answer)?;
+                }
+            }
+            Ok(PortfolioEvent::Failure(i, error)) => {
+                n_failed += 1;
+                handle_failure(commands, i, error, n_failed, next_step)?;
+            }
+            Err(_) => return Err(early_end(next_step)),
+        }
+    }
+    for (command, n_wins) in commands.iter().zip(wins.iter()) {
+        info!(r#"solver "{}" won {} step(s)"#, command.solver, n_wins);
+    }
+    Ok(wins)
+}
+
+/// Relays the majority answer of each step, once all the solvers still in the portfolio answered it.
+///
+/// Answers are compared by their meaning (e.g. the order of the extensions of EE answers does not matter).
+/// In case of a tie, the answer of the first solver of the portfolio is preferred.
+/// Each step for which the solvers disagree is logged with their answers.
+fn relay_majority_answers(
+    commands: &[SolverCommand],
+    n_steps: usize,
+    query: &QueryType,
+    receiver: &mpsc::Receiver<PortfolioEvent>,
+    answer_consumer: &mut AnswerConsumer,
+) -> Result<Vec<usize>> {
+    let mut agreements = vec![0; commands.len()];
+    let mut in_portfolio = vec![true; commands.len()];
+    let mut step_answers: Vec<Vec<Option<String>>> = vec![vec![None; commands.len()]; n_steps];
+    let mut n_failed = 0;
+    let mut n_disagreements = 0;
+    let mut next_step = 0;
+    while next_step < n_steps {
+        match receiver.recv() {
+            Ok(PortfolioEvent::Answer(i, step, answer)) => step_answers[step][i] = Some(answer),
+            Ok(PortfolioEvent::Failure(i, error)) => {
+                n_failed += 1;
+                in_portfolio[i] = false;
+                handle_failure(commands, i, error, n_failed, next_step)?;
+            }
+            Err(_) => return Err(early_end(next_step)),
+        }
+        while next_step < n_steps
+            && step_answers[next_step].iter().any(|a| a.is_some())
+            && (0..commands.len()).all(|i| !in_portfolio[i] || step_answers[next_step][i].is_some())
+        {
+            let answers = &step_answers[next_step];
+            let keys = answers
+                .iter()
+                .map(|a| a.as_ref().map(|s| answer_key(query, s)))
+                .collect::<Vec<Option<String>>>();
+            let votes = |i: usize| {
+                keys.iter()
+                    .filter(|k| k.is_some() && **k == keys[i])
+                    .count()
+            };
+            let majority = (0..commands.len())
+                .filter(|i| keys[*i].is_some())
+                .max_by(|i, j| votes(*i).cmp(&votes(*j)).then(j.cmp(i)))
+                .unwrap();
+            if keys.iter().any(|k| k.is_some() && *k != keys[majority]) {
+                n_disagreements += 1;
+                warn!(
+                    "the solvers disagree at step {}: {}",
+                    next_step,
+                    commands
+                        .iter()
+                        .zip(keys.iter())
+                        .filter_map(|(c, k)| k
+                            .as_ref()
+                            .map(|k| format!(r#""{}": {}"#, c.solver, k)))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                );
+            }
+            (0..commands.len())
+                .filter(|i| keys[*i] == keys[majority])
+                .for_each(|i| agreements[i] += 1);
+            answer_consumer(answers[majority].clone().unwrap())?;
+            next_step += 1;
+        }
+    }
+    info!("the solvers disagreed at {} step(s)", n_disagreements);
+    for (command, n_agreements) in commands.iter().zip(agreements.iter()) {
+        info!(
+            r#"solver "{}" agreed with the majority at {} step(s)"#,
+            command.solver, n_agreements
+        );
+    }
+    Ok(agreements)
+}
+
+/// Returns a one-line representation of an answer, such that answers with the same meaning have the same representation.
+fn answer_key(query: &QueryType, answer: &str) -> String {
+    if let QueryType::EE = query {
+        if let Ok(extensions) = solutions::read_extension_set(&mut answer.as_bytes()) {
+            let canonical = extensions
+                .iter()
+                .map(|e| {
+                    e.iter()
+                        .map(|a| a.label().to_string())
+                        .collect::<BTreeSet<String>>()
+                })
+                .collect::<BTreeSet<BTreeSet<String>>>();
+            return format!(
+                "[{}]",
+                canonical
+                    .iter()
+                    .map(|e| format!(
+                        "[{}]",
+                        e.iter().cloned().collect::<Vec<String>>().join(", ")
+                    ))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            );
+        }
+    }
+    answer.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Handles the failure of a solver, returning an error if all the solvers left the portfolio.
+fn handle_failure(
+    commands: &[SolverCommand],
+    index: usize,
+    error: anyhow::Error,
+    n_failed: usize,
+    step: usize,
+) -> Result<()> {
+    if n_failed == commands.len() {
+        return Err(error).with_context(|| {
+            format!(
+                "while waiting for the answer of step {}: all the solvers of the portfolio failed",
+                step
+            )
+        });
+    }
+    warn!(
+        r#"solver "{}" left the portfolio at step {}: {:#}"#,
+        commands[index].solver, step, error
+    );
+    Ok(())
+}
+
+/// Builds the error raised when all the solver threads ended before the last step is answered.
+fn early_end(step: usize) -> anyhow::Error {
+    ExitStatusError::classify(
+        anyhow!(
+            "the solvers of the portfolio ended before answering step {}",
+            step
+        ),
+        WRONG_ANSWER,
+    )
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crusti_arg::solutions::SolutionReader;
+
+    fn sh_commands(scripts: &[Vec<String>]) -> Vec<SolverCommand<'_>> {
+        scripts
+            .iter()
+            .map(|arguments| SolverCommand {
+                solver: "sh",
+                arguments,
+                instance: None,
+                warmstart: None,
+                remote: None,
+                container: None,
+                tee_file: None,
+                solution_reader: SolutionReader::default(),
+                keep_going: false,
+                fast_forward: 0,
+                batched: false,
+                timeout: None,
+            })
+            .collect()
+    }
+
+    fn script(s: &str) -> Vec<String> {
+        vec!["-c".to_string(), s.to_string()]
+    }
+
+    fn portfolio_answers(
+        scripts: &[Vec<String>],
+        mode: PortfolioMode,
+    ) -> Result<(Vec<String>, Vec<usize>)> {
+        let mut answers = vec![];
+        let wins = run_portfolio(
+            &sh_commands(scripts),
+            mode,
+            &["+arg(b).".to_string(), "+arg(c).".to_string()],
+            &QueryType::DC("a".to_string()),
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
+        )?;
+        Ok((answers, wins))
+    }
+
+    #[test]
+    fn test_portfolio_fastest_wins() {
+        let scripts = [
+            script("sleep 1; echo NO; while read l; do [ -z \"$l\" ] && exit 0; echo NO; done"),
+            script("echo YES; while read l; do [ -z \"$l\" ] && exit 0; echo YES; done"),
+        ];
+        let (answers, wins) = portfolio_answers(&scripts, PortfolioMode::First).unwrap();
+        assert_eq!(vec!["YES\n", "YES\n", "YES\n"], answers);
+        assert_eq!(vec![0, 3], wins);
+    }
+
+    #[test]
+    fn test_portfolio_solver_crash() {
+        let scripts = [
+            script("echo YES; read l; exit 1"),
+            script("sleep 0.5; echo NO; while read l; do [ -z \"$l\" ] && exit 0; echo NO; done"),
+        ];
+        let (answers, wins) = portfolio_answers(&scripts, PortfolioMode::First).unwrap();
+        assert_eq!(vec!["YES\n", "NO\n", "NO\n"], answers);
+        assert_eq!(vec![1, 2], wins);
+    }
+
+    #[test]
+    fn test_portfolio_all_fail() {
+        let scripts = [script("echo YES; exit 1"), script("echo foo; read l")];
+        let error = portfolio_answers(&scripts, PortfolioMode::First).unwrap_err();
+        assert!(format!("{:#}", error).contains("all the solvers of the portfolio failed"));
+    }
+
+    #[test]
+    fn test_portfolio_majority() {
+        let scripts = [
+            script("echo NO; while read l; do [ -z \"$l\" ] && exit 0; echo NO; done"),
+            script("sleep 0.2; echo YES; while read l; do [ -z \"$l\" ] && exit 0; echo YES; done"),
+            script("echo YES; read l; echo NO; while read l; do [ -z \"$l\" ] && exit 0; echo YES; done"),
+        ];
+        let (answers, agreements) = portfolio_answers(&scripts, PortfolioMode::Majority).unwrap();
+        assert_eq!(vec!["YES\n", "NO\n", "YES\n"], answers);
+        assert_eq!(vec![1, 2, 3], agreements);
+    }
+
+    #[test]
+    fn test_answer_key() {
+        assert_eq!(
+            answer_key(&QueryType::EE, "[\n[b, a]\n[c]\n]\n"),
+            answer_key(&QueryType::EE, "[\n[c]\n[a, b]\n]\n")
+        );
+        assert_eq!(
+            "[[a, b], [c]]",
+            answer_key(&QueryType::EE, "[\n[c]\n[a,b]\n]\n")
+        );
+        assert_eq!("YES", answer_key(&QueryType::DC("a".to_string()), "YES\n"));
+    }
+}
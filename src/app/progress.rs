@@ -0,0 +1,84 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::time::{Duration, Instant};
+
+use crusti_app_helper::info;
+
+/// The minimal time between two progress messages.
+pub(crate) const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A logger of the progress of a run through the steps of a dynamics.
+///
+/// To keep the logs readable, a message is logged for the first step, then at most once per interval, and for the last step.
+pub(crate) struct ProgressLogger {
+    last_step: usize,
+    interval: Duration,
+    last_log: Option<Instant>,
+}
+
+impl ProgressLogger {
+    /// Builds a logger for a dynamics ending at the given step.
+    pub(crate) fn new(last_step: usize, interval: Duration) -> Self {
+        ProgressLogger {
+            last_step,
+            interval,
+            last_log: None,
+        }
+    }
+
+    /// Logs that the given step was answered if it is time to, returning `true` if a message was logged.
+    pub(crate) fn step_answered(&mut self, step: usize) -> bool {
+        let now = Instant::now();
+        let due = match self.last_log {
+            Some(t) => step >= self.last_step || now.duration_since(t) >= self.interval,
+            None => true,
+        };
+        if due {
+            info!(
+                "progress: step {}/{} ({:.1}%)",
+                step,
+                self.last_step,
+                if self.last_step == 0 {
+                    100.
+                } else {
+                    100. * step as f64 / self.last_step as f64
+                }
+            );
+            self.last_log = Some(now);
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_answered() {
+        let mut logger = ProgressLogger::new(3, Duration::from_secs(3600));
+        assert!(logger.step_answered(0));
+        assert!(!logger.step_answered(1));
+        assert!(!logger.step_answered(2));
+        assert!(logger.step_answered(3));
+        let mut logger = ProgressLogger::new(3, Duration::default());
+        assert!((0..=3).all(|s| logger.step_answered(s)));
+    }
+}
@@ -0,0 +1,165 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    io::ErrorKind,
+    net::{Shutdown, TcpStream},
+    str::FromStr,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context, Result};
+
+const TCP_SCHEME: &str = "tcp://";
+const SSH_SCHEME: &str = "ssh://";
+
+/// A remote location at which the solver runs.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Remote {
+    /// A TCP endpoint (`host:port`) at which a solver session speaks the dynamics protocol.
+    ///
+    /// The solver is launched on the remote side with its problem and instance; the wrapper only exchanges the modifications and the answers.
+    Tcp(String),
+    /// An SSH destination (`[user@]host`) on which the solver is spawned with the usual arguments.
+    Ssh(String),
+}
+
+impl FromStr for Remote {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let build = |scheme: &str, f: fn(String) -> Remote| {
+            s.strip_prefix(scheme)
+                .filter(|rest| !rest.is_empty())
+                .map(|rest| f(rest.to_string()))
+        };
+        build(TCP_SCHEME, Remote::Tcp)
+            .or_else(|| build(SSH_SCHEME, Remote::Ssh))
+            .ok_or_else(|| {
+                anyhow!(
+                    r#"invalid remote "{}"; expected "{}HOST:PORT" or "{}[USER@]HOST""#,
+                    s,
+                    TCP_SCHEME,
+                    SSH_SCHEME
+                )
+            })
+    }
+}
+
+/// Builds the command spawning a solver, either locally or on an SSH destination.
+///
+/// The solver path and the arguments (including the instance file) are given as-is to the remote machine, and must thus be valid there.
+pub(crate) fn solver_process(
+    solver: &str,
+    arguments: &[String],
+    remote: Option<&Remote>,
+) -> std::process::Command {
+    match remote {
+        Some(Remote::Ssh(destination)) => {
+            let mut command = std::process::Command::new("ssh");
+            command.arg(destination).arg("--").arg(
+                std::iter::once(solver)
+                    .chain(arguments.iter().map(|a| a.as_str()))
+                    .map(shell_quote)
+                    .collect::<Vec<String>>()
+                    .join(" "),
+            );
+            command
+        }
+        _ => {
+            let mut command = std::process::Command::new(solver);
+            command.args(arguments);
+            command
+        }
+    }
+}
+
+/// Quotes a word for a POSIX shell, as the remote command of SSH is interpreted by a shell.
+fn shell_quote(word: &str) -> String {
+    format!("'{}'", word.replace('\'', r#"'\''"#))
+}
+
+/// Connects to a solver session listening at a TCP endpoint.
+pub(crate) fn connect(address: &str) -> Result<TcpStream> {
+    let stream = TcpStream::connect(address)
+        .with_context(|| format!(r#"while connecting to the remote solver at "{}""#, address))?;
+    stream
+        .set_nodelay(true)
+        .context("while configuring the connection to the remote solver")?;
+    Ok(stream)
+}
+
+/// Checks whether the remote side of a connection closed it, waiting a short time for it to do so.
+pub(crate) fn is_closed(stream: &TcpStream) -> bool {
+    if stream
+        .set_read_timeout(Some(Duration::from_millis(100)))
+        .is_err()
+    {
+        return false;
+    }
+    let mut buf = [0; 1];
+    let closed = match stream.peek(&mut buf) {
+        Ok(0) => true,
+        Ok(_) => false,
+        Err(e) => !matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut),
+    };
+    let _ = stream.set_read_timeout(None);
+    closed
+}
+
+/// Closes the sending side of a connection, signaling the end of the dynamics to the remote solver.
+pub(crate) fn close_input(stream: &TcpStream) -> Result<()> {
+    stream
+        .shutdown(Shutdown::Write)
+        .context("while closing the connection to the remote solver")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_remote() {
+        assert_eq!(
+            Remote::Tcp("localhost:4000".to_string()),
+            "tcp://localhost:4000".parse::<Remote>().unwrap()
+        );
+        assert_eq!(
+            Remote::Ssh("user@server".to_string()),
+            "ssh://user@server".parse::<Remote>().unwrap()
+        );
+        assert!("tcp://".parse::<Remote>().is_err());
+        assert!("http://server".parse::<Remote>().is_err());
+    }
+
+    #[test]
+    fn test_solver_process_ssh() {
+        let arguments = vec!["-f".to_string(), "my file's.apx".to_string()];
+        let command = solver_process(
+            "/opt/solver",
+            &arguments,
+            Some(&Remote::Ssh("server".to_string())),
+        );
+        assert_eq!("ssh", command.get_program());
+        assert_eq!(
+            vec!["server", "--", r#"'/opt/solver' '-f' 'my file'\''s.apx'"#],
+            command.get_args().collect::<Vec<&std::ffi::OsStr>>()
+        );
+    }
+}
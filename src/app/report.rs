@@ -0,0 +1,625 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::io::Write;
+
+use anyhow::{anyhow, Result};
+use crusti_arg::solutions;
+
+use super::json::JsonValue;
+
+/// The result of a run, as read from a run record.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct RunResult {
+    pub(crate) solver: String,
+    pub(crate) problem: String,
+    pub(crate) instance: String,
+    pub(crate) input_files: Vec<String>,
+    pub(crate) arguments: Vec<String>,
+    pub(crate) status: i32,
+    pub(crate) time: f64,
+    pub(crate) answers: Vec<String>,
+}
+
+impl RunResult {
+    fn from_record(record: &JsonValue) -> Result<Self> {
+        let string_field = |key: &str| {
+            record
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+                .ok_or_else(|| anyhow!(r#"missing or invalid field "{}""#, key))
+        };
+        let number_field = |key: &str| {
+            record
+                .get(key)
+                .and_then(|v| v.as_f64())
+                .ok_or_else(|| anyhow!(r#"missing or invalid field "{}""#, key))
+        };
+        let input_files = record
+            .get("input_files")
+            .and_then(|files| files.as_array())
+            .and_then(|files| {
+                files
+                    .iter()
+                    .map(|f| {
+                        f.get("path")
+                            .and_then(|p| p.as_str())
+                            .map(|p| p.to_string())
+                    })
+                    .collect::<Option<Vec<String>>>()
+            })
+            .ok_or_else(|| anyhow!(r#"missing or invalid field "input_files""#))?;
+        let arguments = record
+            .get("arguments")
+            .and_then(|args| args.as_array())
+            .and_then(|args| {
+                args.iter()
+                    .map(|a| a.as_str().map(|a| a.to_string()))
+                    .collect::<Option<Vec<String>>>()
+            })
+            .ok_or_else(|| anyhow!(r#"missing or invalid field "arguments""#))?;
+        let answers = record
+            .get("steps")
+            .and_then(|steps| steps.as_array())
+            .and_then(|steps| {
+                steps
+                    .iter()
+                    .map(|s| {
+                        s.get("answer")
+                            .and_then(|a| a.as_str())
+                            .map(|a| a.to_string())
+                    })
+                    .collect::<Option<Vec<String>>>()
+            })
+            .ok_or_else(|| anyhow!(r#"missing or invalid field "steps""#))?;
+        Ok(RunResult {
+            solver: string_field("solver")?,
+            problem: string_field("problem")?,
+            instance: input_files.join(" "),
+            input_files,
+            arguments,
+            status: number_field("status")? as i32,
+            time: number_field("total_time_ms")? / 1000.,
+            answers,
+        })
+    }
+
+    /// Returns `true` iff the run succeeded within the time limit (in seconds).
+    pub(crate) fn is_solved(&self, timeout: f64) -> bool {
+        self.status == 0 && self.time <= timeout
+    }
+
+    /// Returns the PAR-2 score of the run, that is its time if it is solved and twice the time limit otherwise.
+    pub(crate) fn par2(&self, timeout: f64) -> f64 {
+        if self.is_solved(timeout) {
+            self.time
+        } else {
+            2. * timeout
+        }
+    }
+}
+
+/// Reads the run results from a results file (or from a single run record).
+pub(crate) fn read_results(content: &str) -> Result<Vec<RunResult>> {
+    let value = JsonValue::parse(content)?;
+    let records = match &value {
+        JsonValue::Array(records) => records.iter().collect(),
+        JsonValue::Object(_) => vec![&value],
+        _ => return Err(anyhow!("expected an array of run records")),
+    };
+    records
+        .iter()
+        .enumerate()
+        .map(|(i, r)| RunResult::from_record(r).map_err(|e| anyhow!("in run record {}: {}", i, e)))
+        .collect()
+}
+
+/// Returns the solvers involved in the results, in the order of their first appearance.
+pub(crate) fn solvers(results: &[RunResult]) -> Vec<&str> {
+    let mut solvers: Vec<&str> = vec![];
+    for r in results.iter() {
+        if !solvers.contains(&r.solver.as_str()) {
+            solvers.push(&r.solver);
+        }
+    }
+    solvers
+}
+
+/// The summary of the results of a solver.
+#[derive(Debug, PartialEq)]
+pub(crate) struct SolverSummary<'a> {
+    pub(crate) solver: &'a str,
+    pub(crate) n_runs: usize,
+    pub(crate) n_solved: usize,
+    pub(crate) par2: f64,
+}
+
+/// Summarizes the results of each solver, given the time limit in seconds.
+///
+/// The PAR-2 score of a solver is the sum of the PAR-2 scores of its runs.
+pub(crate) fn summarize(results: &[RunResult], timeout: f64) -> Vec<SolverSummary<'_>> {
+    solvers(results)
+        .into_iter()
+        .map(|solver| {
+            let runs = results.iter().filter(|r| r.solver == solver);
+            SolverSummary {
+                solver,
+                n_runs: runs.clone().count(),
+                n_solved: runs.clone().filter(|r| r.is_solved(timeout)).count(),
+                par2: runs.map(|r| r.par2(timeout)).sum(),
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn write_summary_csv(writer: &mut dyn Write, summaries: &[SolverSummary]) -> Result<()> {
+    writeln!(writer, "solver,runs,solved,par2")?;
+    for s in summaries.iter() {
+        writeln!(
+            writer,
+            "{},{},{},{:.3}",
+            csv_field(s.solver),
+            s.n_runs,
+            s.n_solved,
+            s.par2
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes the data of a cactus plot.
+///
+/// For each solver, the times of the solved runs are sorted, and the n-th line gives the time needed to solve n instances.
+pub(crate) fn write_cactus_csv(
+    writer: &mut dyn Write,
+    results: &[RunResult],
+    timeout: f64,
+) -> Result<()> {
+    writeln!(writer, "solver,solved,time")?;
+    for solver in solvers(results) {
+        let mut times = results
+            .iter()
+            .filter(|r| r.solver == solver && r.is_solved(timeout))
+            .map(|r| r.time)
+            .collect::<Vec<f64>>();
+        times.sort_by(|t1, t2| t1.partial_cmp(t2).unwrap());
+        for (i, t) in times.iter().enumerate() {
+            writeln!(writer, "{},{},{:.3}", csv_field(solver), i + 1, t)?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes a LaTeX tabular giving the number of solved instances and the PAR-2 score of each solver.
+pub(crate) fn write_latex_table(writer: &mut dyn Write, summaries: &[SolverSummary]) -> Result<()> {
+    writeln!(writer, "\\begin{{tabular}}{{lrr}}")?;
+    writeln!(writer, "\\hline")?;
+    writeln!(writer, "Solver & Solved & PAR-2 \\\\")?;
+    writeln!(writer, "\\hline")?;
+    for s in summaries.iter() {
+        writeln!(
+            writer,
+            "{} & {}/{} & {:.2} \\\\",
+            latex_text(s.solver),
+            s.n_solved,
+            s.n_runs,
+            s.par2
+        )?;
+    }
+    writeln!(writer, "\\hline")?;
+    writeln!(writer, "\\end{{tabular}}")?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Pairs the runs of two solvers on the same instances of the same problems, querying the same arguments.
+///
+/// The pairs follow the order of the runs of the first solver.
+pub(crate) fn pair_results<'a>(
+    results: &'a [RunResult],
+    first_solver: &str,
+    second_solver: &str,
+) -> Vec<(&'a RunResult, &'a RunResult)> {
+    results
+        .iter()
+        .filter(|r| r.solver == first_solver)
+        .filter_map(|r1| {
+            results
+                .iter()
+                .find(|r2| {
+                    r2.solver == second_solver
+                        && r2.problem == r1.problem
+                        && r2.instance == r1.instance
+                        && r2.arguments == r1.arguments
+                })
+                .map(|r2| (r1, r2))
+        })
+        .collect()
+}
+
+/// Returns the first step at which the answers of two paired runs differ, along with these answers.
+///
+/// Sets of extensions are compared regardless of the order of their extensions and arguments, other answers are compared textually.
+/// Only the steps answered by both runs are considered.
+pub(crate) fn first_disagreement<'a>(
+    first: &'a RunResult,
+    second: &'a RunResult,
+) -> Option<(usize, &'a str, &'a str)> {
+    first
+        .answers
+        .iter()
+        .zip(second.answers.iter())
+        .enumerate()
+        .find(|(_, (a1, a2))| !answers_agree(a1, a2))
+        .map(|(step, (a1, a2))| (step, a1.as_str(), a2.as_str()))
+}
+
+/// Checks whether two answers agree, comparing them as sets of extensions if both can be read as such.
+fn answers_agree(first: &str, second: &str) -> bool {
+    if first == second {
+        return true;
+    }
+    match (
+        solutions::read_extension_set(&mut first.as_bytes()),
+        solutions::read_extension_set(&mut second.as_bytes()),
+    ) {
+        (Ok(e1), Ok(e2)) => solutions::extension_sets_equal(&e1, &e2),
+        _ => false,
+    }
+}
+
+/// Writes the instances on which two solvers disagree, giving the first step at which their answers differ.
+pub(crate) fn write_disagreements_csv(
+    writer: &mut dyn Write,
+    pairs: &[(&RunResult, &RunResult)],
+) -> Result<()> {
+    writeln!(writer, "problem,instance,step,first_answer,second_answer")?;
+    for (r1, r2) in pairs.iter() {
+        if let Some((step, a1, a2)) = first_disagreement(r1, r2) {
+            writeln!(
+                writer,
+                "{},{},{},{},{}",
+                csv_field(&r1.problem),
+                csv_field(&r1.instance),
+                step,
+                csv_field(a1),
+                csv_field(a2)
+            )?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Computes the speedups of the first solver over the second one (the ratio of their times) on the instances both solved.
+pub(crate) fn speedups(pairs: &[(&RunResult, &RunResult)], timeout: f64) -> Vec<Option<f64>> {
+    pairs
+        .iter()
+        .map(|(r1, r2)| {
+            if r1.is_solved(timeout) && r2.is_solved(timeout) && r1.time > 0. {
+                Some(r2.time / r1.time)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Writes the speedups of the first solver over the second one on the instances both solved.
+pub(crate) fn write_speedups_csv(
+    writer: &mut dyn Write,
+    pairs: &[(&RunResult, &RunResult)],
+    timeout: f64,
+) -> Result<()> {
+    writeln!(writer, "problem,instance,speedup")?;
+    for ((r1, _), speedup) in pairs.iter().zip(speedups(pairs, timeout)) {
+        if let Some(s) = speedup {
+            writeln!(
+                writer,
+                "{},{},{:.3}",
+                csv_field(&r1.problem),
+                csv_field(&r1.instance),
+                s
+            )?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes the data of a scatter plot comparing the times of two solvers; the time of unsolved runs is set to the time limit.
+pub(crate) fn write_scatter_csv(
+    writer: &mut dyn Write,
+    pairs: &[(&RunResult, &RunResult)],
+    timeout: f64,
+) -> Result<()> {
+    let plotted_time = |r: &RunResult| {
+        if r.is_solved(timeout) {
+            r.time
+        } else {
+            timeout
+        }
+    };
+    writeln!(writer, "problem,instance,first_time,second_time")?;
+    for (r1, r2) in pairs.iter() {
+        writeln!(
+            writer,
+            "{},{},{:.3},{:.3}",
+            csv_field(&r1.problem),
+            csv_field(&r1.instance),
+            plotted_time(r1),
+            plotted_time(r2)
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Describes the distribution of speedups by its minimum, median, geometric mean and maximum.
+pub(crate) fn describe_speedups(speedups: &[f64]) -> Option<String> {
+    if speedups.is_empty() {
+        return None;
+    }
+    let mut sorted = speedups.to_vec();
+    sorted.sort_by(|s1, s2| s1.partial_cmp(s2).unwrap());
+    let n = sorted.len();
+    let median = if n % 2 == 1 {
+        sorted[n / 2]
+    } else {
+        (sorted[n / 2 - 1] + sorted[n / 2]) / 2.
+    };
+    let geometric_mean = (sorted.iter().map(|s| s.ln()).sum::<f64>() / n as f64).exp();
+    Some(format!(
+        "min {:.3}, median {:.3}, geometric mean {:.3}, max {:.3}",
+        sorted[0],
+        median,
+        geometric_mean,
+        sorted[n - 1]
+    ))
+}
+
+/// Quotes a CSV field if needed.
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains(&[',', '"', '\n'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Escapes the LaTeX special characters of a text.
+pub(crate) fn latex_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn result(solver: &str, instance: &str, status: i32, time: f64) -> RunResult {
+        RunResult {
+            solver: solver.to_string(),
+            problem: "DC-CO-D".to_string(),
+            instance: instance.to_string(),
+            input_files: vec![instance.to_string()],
+            arguments: vec![],
+            status,
+            time,
+            answers: vec![],
+        }
+    }
+
+    fn results() -> Vec<RunResult> {
+        vec![
+            result("s1", "i1", 0, 2.),
+            result("s2", "i1", 0, 1.),
+            result("s1", "i2", 20, 0.5),
+            result("s2", "i2", 0, 12.),
+            result("s1", "i3", 0, 1.),
+        ]
+    }
+
+    #[test]
+    fn test_read_results() {
+        let content = r#"[{"solver": "s", "problem": "DC-CO-D", "arguments": ["a"], "input_files": [{"path": "af.apx", "hash": null}, {"path": "af.apxm", "hash": null}], "steps": [{"step": 0, "answer": "YES", "time_ms": null}], "total_time_ms": 1500.0, "status": 10}]"#;
+        assert_eq!(
+            vec![RunResult {
+                solver: "s".to_string(),
+                problem: "DC-CO-D".to_string(),
+                instance: "af.apx af.apxm".to_string(),
+                input_files: vec!["af.apx".to_string(), "af.apxm".to_string()],
+                arguments: vec!["a".to_string()],
+                status: 10,
+                time: 1.5,
+                answers: vec!["YES".to_string()],
+            }],
+            read_results(content).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_read_results_missing_field() {
+        let content = r#"[{"solver": "s", "arguments": [], "input_files": [], "steps": [], "total_time_ms": 1.0, "status": 0}]"#;
+        assert_eq!(
+            r#"in run record 0: missing or invalid field "problem""#,
+            read_results(content).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_summarize() {
+        let results = results();
+        let summaries = summarize(&results, 10.);
+        assert_eq!(
+            vec![
+                SolverSummary {
+                    solver: "s1",
+                    n_runs: 3,
+                    n_solved: 2,
+                    par2: 23.
+                },
+                SolverSummary {
+                    solver: "s2",
+                    n_runs: 2,
+                    n_solved: 1,
+                    par2: 21.
+                }
+            ],
+            summaries
+        );
+        let mut out = Vec::new();
+        write_summary_csv(&mut out, &summaries).unwrap();
+        assert_eq!(
+            "solver,runs,solved,par2\ns1,3,2,23.000\ns2,2,1,21.000\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_write_cactus_csv() {
+        let mut out = Vec::new();
+        write_cactus_csv(&mut out, &results(), 10.).unwrap();
+        assert_eq!(
+            "solver,solved,time\ns1,1,1.000\ns1,2,2.000\ns2,1,1.000\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_write_latex_table() {
+        let results = vec![result("my_solver", "i1", 0, 1.)];
+        let mut out = Vec::new();
+        write_latex_table(&mut out, &summarize(&results, 10.)).unwrap();
+        assert_eq!(
+            "\\begin{tabular}{lrr}\n\\hline\nSolver & Solved & PAR-2 \\\\\n\\hline\nmy\\_solver & 1/1 & 1.00 \\\\\n\\hline\n\\end{tabular}\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!("abc", csv_field("abc"));
+        assert_eq!("\"a,\"\"b\"\"\"", csv_field("a,\"b\""));
+    }
+
+    fn with_answers(mut result: RunResult, answers: &[&str]) -> RunResult {
+        result.answers = answers.iter().map(|a| a.to_string()).collect();
+        result
+    }
+
+    #[test]
+    fn test_pair_results() {
+        let results = results();
+        let pairs = pair_results(&results, "s2", "s1");
+        assert_eq!(
+            vec![(&results[1], &results[0]), (&results[3], &results[2])],
+            pairs
+        );
+        assert!(pair_results(&results, "s2", "s3").is_empty());
+        let mut other_args = results.clone();
+        other_args[0].arguments = vec!["a".to_string()];
+        assert_eq!(
+            vec![(&other_args[3], &other_args[2])],
+            pair_results(&other_args, "s2", "s1")
+        );
+    }
+
+    #[test]
+    fn test_write_disagreements_csv() {
+        let results = vec![
+            with_answers(result("s1", "i1", 0, 1.), &["YES", "NO", "NO"]),
+            with_answers(result("s2", "i1", 0, 1.), &["YES", "YES", "YES"]),
+            with_answers(result("s1", "i2", 0, 1.), &["YES", "NO"]),
+            with_answers(result("s2", "i2", 20, 1.), &["YES"]),
+        ];
+        let mut out = Vec::new();
+        write_disagreements_csv(&mut out, &pair_results(&results, "s1", "s2")).unwrap();
+        assert_eq!(
+            "problem,instance,step,first_answer,second_answer\nDC-CO-D,i1,1,NO,YES\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_first_disagreement_extension_sets() {
+        let r1 = with_answers(
+            result("s1", "i1", 0, 1.),
+            &["[\n[a, b]\n[c]\n]", "[\n[a]\n]", "YES"],
+        );
+        let r2 = with_answers(
+            result("s2", "i1", 0, 1.),
+            &["[\n[c]\n[b,a]\n]", "[\n[b]\n]", "YES"],
+        );
+        assert_eq!(
+            Some((1, "[\n[a]\n]", "[\n[b]\n]")),
+            first_disagreement(&r1, &r2)
+        );
+        assert!(answers_agree("[\n]", "[\n]"));
+        assert!(!answers_agree("YES", "NO"));
+    }
+
+    #[test]
+    fn test_speedups() {
+        let results = results();
+        let pairs = pair_results(&results, "s2", "s1");
+        assert_eq!(vec![Some(2.), None], speedups(&pairs, 20.));
+        let mut out = Vec::new();
+        write_speedups_csv(&mut out, &pairs, 20.).unwrap();
+        assert_eq!(
+            "problem,instance,speedup\nDC-CO-D,i1,2.000\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_write_scatter_csv() {
+        let results = results();
+        let mut out = Vec::new();
+        write_scatter_csv(&mut out, &pair_results(&results, "s1", "s2"), 10.).unwrap();
+        assert_eq!(
+            "problem,instance,first_time,second_time\nDC-CO-D,i1,2.000,1.000\nDC-CO-D,i2,10.000,10.000\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_describe_speedups() {
+        assert_eq!(None, describe_speedups(&[]));
+        assert_eq!(
+            Some("min 0.500, median 1.000, geometric mean 1.000, max 2.000".to_string()),
+            describe_speedups(&[2., 0.5, 1.])
+        );
+    }
+}
@@ -0,0 +1,253 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{fs::File, io::BufWriter};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::{info, AppSettings, Arg, Command, SubCommand, WithExitStatus};
+
+use super::exit_status::MALFORMED_INPUT;
+use super::html_report;
+use super::report::{self, RunResult};
+
+pub(crate) struct ReportCommand;
+
+const CMD_NAME: &str = "report";
+
+const ARG_RESULTS_FILES: &str = "RESULTS_FILES";
+const ARG_TIMEOUT: &str = "TIMEOUT";
+const ARG_CSV: &str = "CSV";
+const ARG_CACTUS: &str = "CACTUS";
+const ARG_LATEX: &str = "LATEX";
+const ARG_HTML: &str = "HTML";
+const ARG_COMPARE: &str = "COMPARE";
+const ARG_BASELINE: &str = "BASELINE";
+const ARG_DISAGREEMENTS: &str = "DISAGREEMENTS";
+const ARG_SPEEDUPS: &str = "SPEEDUPS";
+const ARG_SCATTER: &str = "SCATTER";
+
+impl ReportCommand {
+    pub fn new() -> Self {
+        ReportCommand
+    }
+}
+
+impl<'a> Command<'a> for ReportCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about("summarizes the results files written by the batch command (CSV, cactus plot data, LaTeX table, HTML page)")
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_RESULTS_FILES)
+                    .index(1)
+                    .multiple(true)
+                    .help("the results files (or run records) to summarize")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_TIMEOUT)
+                    .long("timeout")
+                    .short("t")
+                    .takes_value(true)
+                    .help("sets the time limit in seconds; longer runs are considered unsolved")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_CSV)
+                    .long("csv")
+                    .takes_value(true)
+                    .help("writes the number of solved instances and the PAR-2 score of each solver in this CSV file"),
+            )
+            .arg(
+                Arg::with_name(ARG_CACTUS)
+                    .long("cactus")
+                    .takes_value(true)
+                    .help("writes the cactus plot data in this CSV file"),
+            )
+            .arg(
+                Arg::with_name(ARG_LATEX)
+                    .long("latex")
+                    .takes_value(true)
+                    .help("writes a LaTeX tabular of the solved instances and PAR-2 scores in this file"),
+            )
+            .arg(
+                Arg::with_name(ARG_HTML)
+                    .long("html")
+                    .takes_value(true)
+                    .help("writes a HTML page browsing the runs, with an interactive graph of each instance and the answers at each step"),
+            )
+            .arg(
+                Arg::with_name(ARG_COMPARE)
+                    .long("compare")
+                    .takes_value(true)
+                    .requires(ARG_BASELINE)
+                    .help("compares the runs of this solver to the ones of the baseline solver"),
+            )
+            .arg(
+                Arg::with_name(ARG_BASELINE)
+                    .long("baseline")
+                    .takes_value(true)
+                    .requires(ARG_COMPARE)
+                    .help("sets the baseline solver of the comparison"),
+            )
+            .arg(
+                Arg::with_name(ARG_DISAGREEMENTS)
+                    .long("disagreements")
+                    .takes_value(true)
+                    .requires(ARG_COMPARE)
+                    .help("writes the instances on which the compared solvers disagree in this CSV file"),
+            )
+            .arg(
+                Arg::with_name(ARG_SPEEDUPS)
+                    .long("speedups")
+                    .takes_value(true)
+                    .requires(ARG_COMPARE)
+                    .help("writes the speedups over the baseline solver in this CSV file"),
+            )
+            .arg(
+                Arg::with_name(ARG_SCATTER)
+                    .long("scatter")
+                    .takes_value(true)
+                    .requires(ARG_COMPARE)
+                    .help("writes the scatter plot data of the compared solvers in this CSV file"),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let str_timeout = arg_matches.value_of(ARG_TIMEOUT).unwrap();
+        let timeout = str_timeout
+            .parse::<f64>()
+            .ok()
+            .filter(|t| *t > 0.)
+            .ok_or_else(|| anyhow!(r#"invalid timeout "{}""#, str_timeout))?;
+        let results = read_results_files(arg_matches).with_exit_status(MALFORMED_INPUT)?;
+        info!("read {} run result(s)", results.len());
+        let summaries = report::summarize(&results, timeout);
+        let mut has_output = false;
+        if let Some(path) = arg_matches.value_of(ARG_CSV) {
+            report::write_summary_csv(&mut create_file(path)?, &summaries)
+                .with_context(|| format!(r#"while writing to "{}""#, path))?;
+            has_output = true;
+        }
+        if let Some(path) = arg_matches.value_of(ARG_CACTUS) {
+            report::write_cactus_csv(&mut create_file(path)?, &results, timeout)
+                .with_context(|| format!(r#"while writing to "{}""#, path))?;
+            has_output = true;
+        }
+        if let Some(path) = arg_matches.value_of(ARG_LATEX) {
+            report::write_latex_table(&mut create_file(path)?, &summaries)
+                .with_context(|| format!(r#"while writing to "{}""#, path))?;
+            has_output = true;
+        }
+        if let Some(path) = arg_matches.value_of(ARG_HTML) {
+            html_report::write_html_report(&mut create_file(path)?, &results, &summaries, timeout)
+                .with_context(|| format!(r#"while writing to "{}""#, path))?;
+            has_output = true;
+        }
+        if let Some(solver) = arg_matches.value_of(ARG_COMPARE) {
+            compare(
+                arg_matches,
+                &results,
+                solver,
+                arg_matches.value_of(ARG_BASELINE).unwrap(),
+                timeout,
+            )?;
+            has_output = true;
+        }
+        if !has_output {
+            report::write_summary_csv(&mut std::io::stdout(), &summaries)
+                .context("while writing to stdout")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the runs of a solver to the ones of a baseline solver, logging a summary and writing the requested files.
+fn compare(
+    arg_matches: &crusti_app_helper::ArgMatches<'_>,
+    results: &[RunResult],
+    solver: &str,
+    baseline: &str,
+    timeout: f64,
+) -> Result<()> {
+    let known_solvers = report::solvers(results);
+    for s in &[solver, baseline] {
+        if !known_solvers.contains(s) {
+            return Err(anyhow!(r#"no run of solver "{}" in the results"#, s));
+        }
+    }
+    let pairs = report::pair_results(results, solver, baseline);
+    let n_disagreements = pairs
+        .iter()
+        .filter(|(r1, r2)| report::first_disagreement(r1, r2).is_some())
+        .count();
+    info!(
+        "{} common instance(s), {} disagreement(s)",
+        pairs.len(),
+        n_disagreements
+    );
+    let speedups = report::speedups(&pairs, timeout)
+        .into_iter()
+        .flatten()
+        .collect::<Vec<f64>>();
+    match report::describe_speedups(&speedups) {
+        Some(description) => info!(
+            "speedups over the baseline on {} instance(s) solved by both: {}",
+            speedups.len(),
+            description
+        ),
+        None => info!("no instance solved by both solvers"),
+    }
+    if let Some(path) = arg_matches.value_of(ARG_DISAGREEMENTS) {
+        report::write_disagreements_csv(&mut create_file(path)?, &pairs)
+            .with_context(|| format!(r#"while writing to "{}""#, path))?;
+    }
+    if let Some(path) = arg_matches.value_of(ARG_SPEEDUPS) {
+        report::write_speedups_csv(&mut create_file(path)?, &pairs, timeout)
+            .with_context(|| format!(r#"while writing to "{}""#, path))?;
+    }
+    if let Some(path) = arg_matches.value_of(ARG_SCATTER) {
+        report::write_scatter_csv(&mut create_file(path)?, &pairs, timeout)
+            .with_context(|| format!(r#"while writing to "{}""#, path))?;
+    }
+    Ok(())
+}
+
+fn read_results_files(arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<Vec<RunResult>> {
+    let mut results = vec![];
+    for path in arg_matches.values_of(ARG_RESULTS_FILES).unwrap() {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!(r#"while reading results file "{}""#, path))?;
+        results.append(
+            &mut report::read_results(&content)
+                .with_context(|| format!(r#"while reading results file "{}""#, path))?,
+        );
+    }
+    Ok(results)
+}
+
+fn create_file(path: &str) -> Result<BufWriter<File>> {
+    Ok(BufWriter::new(File::create(path).with_context(|| {
+        format!(r#"while creating file "{}""#, path)
+    })?))
+}
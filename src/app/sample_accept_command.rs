@@ -0,0 +1,385 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    io::{BufWriter, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::{info, AppSettings, Arg, Command, SubCommand, WithExitStatus};
+use crusti_arg::{
+    compression, enforcement::Acceptance, labels, solvers, testing::TestRng, AAFramework,
+    ArgumentSet, Semantics, WeightedAspartixReader,
+};
+use rayon::prelude::*;
+
+use super::enforce_command::read_problem;
+use super::exit_status::MALFORMED_INPUT;
+use super::wrap_command::read_count;
+
+pub(crate) struct SampleAcceptCommand;
+
+const CMD_NAME: &str = "sample-accept";
+
+const ARG_PROBLEM: &str = "PROBLEM";
+const ARG_INPUT_FILE: &str = "INPUT_FILE";
+const ARG_ARGUMENT: &str = "ARGUMENT";
+const ARG_SAMPLES: &str = "SAMPLES";
+const ARG_CONFIDENCE: &str = "CONFIDENCE";
+const ARG_SEED: &str = "SEED";
+const ARG_JOBS: &str = "JOBS";
+const ARG_OUTPUT_FILE: &str = "OUTPUT_FILE";
+
+/// The confidence levels of the intervals, and the related quantiles of the normal distribution.
+const CONFIDENCE_LEVELS: [(&str, f64); 3] = [("0.90", 1.645), ("0.95", 1.960), ("0.99", 2.576)];
+
+impl SampleAcceptCommand {
+    pub fn new() -> Self {
+        SampleAcceptCommand
+    }
+}
+
+impl<'a> Command<'a> for SampleAcceptCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about("estimates the acceptance probabilities of arguments in a probabilistic framework by sampling")
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_PROBLEM)
+                    .long("problem")
+                    .short("p")
+                    .takes_value(true)
+                    .help("sets the acceptance and its semantics (e.g. DC-CO or DS-GR)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_INPUT_FILE)
+                    .long("input-file")
+                    .short("f")
+                    .takes_value(true)
+                    .help("sets the input file containing the framework (weighted Aspartix format, the attack weights being their probabilities)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_ARGUMENT)
+                    .long("argument")
+                    .short("a")
+                    .takes_value(true)
+                    .multiple(true)
+                    .number_of_values(1)
+                    .help("sets an argument to query (may be given more than once; default: all the arguments)"),
+            )
+            .arg(
+                Arg::with_name(ARG_SAMPLES)
+                    .long("samples")
+                    .short("n")
+                    .takes_value(true)
+                    .default_value("1000")
+                    .help("sets the number of sampled frameworks"),
+            )
+            .arg(
+                Arg::with_name(ARG_CONFIDENCE)
+                    .long("confidence")
+                    .takes_value(true)
+                    .possible_values(&CONFIDENCE_LEVELS.iter().map(|(l, _)| *l).collect::<Vec<&str>>())
+                    .default_value("0.95")
+                    .help("sets the confidence level of the intervals"),
+            )
+            .arg(
+                Arg::with_name(ARG_SEED)
+                    .long("seed")
+                    .takes_value(true)
+                    .help("sets the seed of the sampler (taken from the clock by default)"),
+            )
+            .arg(
+                Arg::with_name(ARG_JOBS)
+                    .long("jobs")
+                    .short("j")
+                    .takes_value(true)
+                    .help("sets the number of threads used to solve the samples (default: one per core)"),
+            )
+            .arg(
+                Arg::with_name(ARG_OUTPUT_FILE)
+                    .long("output-file")
+                    .short("o")
+                    .takes_value(true)
+                    .help("sets the file in which the estimations are written (default: standard output)"),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let (acceptance, semantics) = read_problem(arg_matches.value_of(ARG_PROBLEM).unwrap())?;
+        let framework = WeightedAspartixReader::default()
+            .read(
+                &mut compression::open(arg_matches.value_of(ARG_INPUT_FILE).unwrap())
+                    .context("while opening input file")
+                    .with_exit_status(MALFORMED_INPUT)?,
+            )
+            .context("while reading input file")
+            .with_exit_status(MALFORMED_INPUT)?;
+        let sampler = Sampler::new(&framework).with_exit_status(MALFORMED_INPUT)?;
+        let targets = match arg_matches.values_of(ARG_ARGUMENT) {
+            Some(values) => values
+                .map(|a| framework.argument_set().get_argument_index(a))
+                .collect::<Result<Vec<usize>>>()?,
+            None => (0..framework.argument_set().len()).collect(),
+        };
+        let n_samples = read_count(arg_matches, ARG_SAMPLES)?;
+        if n_samples == 0 {
+            return Err(anyhow!("the number of samples must be positive"));
+        }
+        let confidence = arg_matches.value_of(ARG_CONFIDENCE).unwrap();
+        let z = CONFIDENCE_LEVELS
+            .iter()
+            .find(|(l, _)| *l == confidence)
+            .map(|(_, z)| *z)
+            .unwrap();
+        let seed = match arg_matches.value_of(ARG_SEED) {
+            Some(s) => s
+                .parse::<u64>()
+                .with_context(|| format!(r#"while parsing the seed "{}""#, s))?,
+            None => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or_default(),
+        };
+        let jobs = arg_matches
+            .value_of(ARG_JOBS)
+            .map(|j| j.parse::<usize>())
+            .transpose()
+            .context("while parsing the number of jobs")?;
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs.unwrap_or(0))
+            .build()
+            .context("while building the thread pool")?;
+        let counts = pool.install(|| {
+            sampler.count_acceptances(seed, n_samples, &targets, semantics, acceptance)
+        })?;
+        let mut writer: Box<dyn Write> = match arg_matches.value_of(ARG_OUTPUT_FILE) {
+            Some(path) => Box::new(BufWriter::new(
+                compression::create(path)
+                    .with_context(|| format!(r#"while creating file "{}""#, path))?,
+            )),
+            None => Box::new(std::io::stdout()),
+        };
+        for (target, count) in targets.iter().zip(counts.iter()) {
+            let (low, high) = wilson_interval(*count, n_samples, z);
+            writeln!(
+                writer,
+                "{} {:.4} [{:.4}, {:.4}]",
+                labels::format_label(framework.argument_set().get_argument_by_id(*target)),
+                *count as f64 / n_samples as f64,
+                low,
+                high
+            )
+            .context("while writing the estimations")?;
+        }
+        writer.flush().context("while writing the estimations")?;
+        info!(
+            "estimated the {}-{} acceptance of {} argument(s) on {} sample(s) with seed {}",
+            acceptance,
+            semantics,
+            targets.len(),
+            n_samples,
+            seed
+        );
+        Ok(())
+    }
+}
+
+/// Draws the subframeworks of a probabilistic framework, in which each attack exists with its probability.
+struct Sampler {
+    labels: Vec<String>,
+    attacks: Vec<(usize, usize, f64)>,
+}
+
+impl Sampler {
+    /// Builds a sampler; the attacks without weights are certain, and the other weights must be probabilities.
+    fn new(framework: &AAFramework<String>) -> Result<Self> {
+        let labels = framework
+            .argument_set()
+            .iter()
+            .map(|a| a.label().clone())
+            .collect();
+        let attacks = framework
+            .iter_attacks()
+            .map(|att| {
+                let probability = att.weight().unwrap_or(1.);
+                if (0.0..=1.0).contains(&probability) {
+                    Ok((att.attacker().id(), att.attacked().id(), probability))
+                } else {
+                    Err(anyhow!(
+                        "the probability of the attack {} must be between 0 and 1",
+                        att
+                    ))
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Sampler { labels, attacks })
+    }
+
+    /// Draws the subframework of the given sample; the generator of each sample is derived from the seed, so that the draws do not depend on the scheduling of the threads.
+    fn sample(&self, seed: u64, index: usize) -> AAFramework<String> {
+        let mut rng = TestRng::new(mix(seed.wrapping_add(index as u64)));
+        let mut framework = AAFramework::new(ArgumentSet::new(self.labels.clone()));
+        for (from, to, probability) in self.attacks.iter() {
+            if rng.next_f64() < *probability {
+                framework.new_attack_by_ids(*from, *to).unwrap();
+            }
+        }
+        framework
+    }
+
+    /// Counts, for each target, the samples in which it is accepted.
+    fn count_acceptances(
+        &self,
+        seed: u64,
+        n_samples: usize,
+        targets: &[usize],
+        semantics: Semantics,
+        acceptance: Acceptance,
+    ) -> Result<Vec<usize>> {
+        (0..n_samples)
+            .into_par_iter()
+            .map(|i| {
+                let sample = self.sample(seed, i);
+                let extensions = solvers::default_solver(&sample, semantics)
+                    .and_then(|s| s.all_extensions(semantics))
+                    .with_context(|| format!("while solving sample #{}", i))?;
+                Ok(targets
+                    .iter()
+                    .map(|t| match acceptance {
+                        Acceptance::Credulous => extensions.iter().any(|e| e[*t]),
+                        Acceptance::Skeptical => extensions.iter().all(|e| e[*t]),
+                    })
+                    .collect::<Vec<bool>>())
+            })
+            .try_fold(
+                || vec![0; targets.len()],
+                |mut counts, accepted: Result<Vec<bool>>| {
+                    accepted.map(|a| {
+                        counts.iter_mut().zip(a).for_each(|(c, b)| *c += b as usize);
+                        counts
+                    })
+                },
+            )
+            .try_reduce(
+                || vec![0; targets.len()],
+                |mut c1, c2| {
+                    c1.iter_mut().zip(c2).for_each(|(c, d)| *c += d);
+                    Ok(c1)
+                },
+            )
+    }
+}
+
+/// Scrambles a seed (SplitMix64 finalizer), so that close seeds give unrelated generators.
+fn mix(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Computes the Wilson score interval of a proportion, given the quantile `z` of the confidence level.
+fn wilson_interval(successes: usize, n: usize, z: f64) -> (f64, f64) {
+    let n = n as f64;
+    let p = successes as f64 / n;
+    let z2 = z * z;
+    let denominator = 1. + z2 / n;
+    let center = (p + z2 / (2. * n)) / denominator;
+    let half_width = z * (p * (1. - p) / n + z2 / (4. * n * n)).sqrt() / denominator;
+    ((center - half_width).max(0.), (center + half_width).min(1.))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(instance: &str) -> AAFramework<String> {
+        WeightedAspartixReader::default()
+            .read(&mut instance.as_bytes())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_wilson_interval() {
+        let (low, high) = wilson_interval(50, 100, 1.96);
+        assert!((low - 0.4038).abs() < 1e-3, "{}", low);
+        assert!((high - 0.5962).abs() < 1e-3, "{}", high);
+        let (low, high) = wilson_interval(0, 10, 1.96);
+        assert_eq!(0., low);
+        assert!(high > 0. && high < 0.5);
+    }
+
+    #[test]
+    fn test_count_acceptances() {
+        let framework = read("arg(a).\narg(b).\narg(c).\natt(a,b,0.5).\natt(c,a).\n");
+        let sampler = Sampler::new(&framework).unwrap();
+        let counts = sampler
+            .count_acceptances(
+                7,
+                200,
+                &[0, 1, 2],
+                Semantics::Grounded,
+                Acceptance::Credulous,
+            )
+            .unwrap();
+        assert_eq!(vec![0, 200, 200], counts);
+        let framework = read("arg(a).\narg(b).\natt(a,b,0.5).\n");
+        let counts = Sampler::new(&framework)
+            .unwrap()
+            .count_acceptances(7, 400, &[1], Semantics::Stable, Acceptance::Skeptical)
+            .unwrap();
+        assert!(counts[0] > 150 && counts[0] < 250, "{}", counts[0]);
+    }
+
+    #[test]
+    fn test_samples_do_not_depend_on_threads() {
+        let framework = read("arg(a).\narg(b).\natt(a,b,0.3).\natt(b,a,0.6).\n");
+        let sampler = Sampler::new(&framework).unwrap();
+        let count = |threads| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| {
+                    sampler.count_acceptances(
+                        3,
+                        100,
+                        &[0, 1],
+                        Semantics::Preferred,
+                        Acceptance::Skeptical,
+                    )
+                })
+                .unwrap()
+        };
+        assert_eq!(count(1), count(4));
+    }
+
+    #[test]
+    fn test_invalid_probability() {
+        assert!(Sampler::new(&read("arg(a).\natt(a,a,1.5).\n")).is_err());
+    }
+}
@@ -0,0 +1,800 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    io::{BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::{info, warn, AppSettings, Arg, Command, SubCommand};
+use crusti_arg::{solutions, AspartixReader};
+use iccma_dynamics::runner::{QueryType, RunLimits, SolverSession, Stop, WrapError};
+
+use super::http::{self, Request, Response};
+use super::json::{json_string, JsonValue};
+use super::temp_files::{TempFile, TempFiles};
+use super::wrap_command::{read_count, read_time_limit};
+
+pub(crate) struct ServeCommand;
+
+const CMD_NAME: &str = "serve";
+
+const ARG_SOLVER: &str = "SOLVER";
+const ARG_LISTEN: &str = "LISTEN";
+const ARG_MAX_SESSIONS: &str = "MAX_SESSIONS";
+const ARG_TMP_DIR: &str = "TMP_DIR";
+const ARG_TIMEOUT: &str = "TIMEOUT";
+
+/// The time after which a connection whose request is not fully received is closed.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+impl ServeCommand {
+    pub fn new() -> Self {
+        ServeCommand
+    }
+}
+
+impl<'a> Command<'a> for ServeCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about("runs an HTTP/JSON service driving a wrapped solver for each session")
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_SOLVER)
+                    .long("solver")
+                    .short("s")
+                    .takes_value(true)
+                    .help("sets the solver run for each session")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_LISTEN)
+                    .long("listen")
+                    .short("l")
+                    .takes_value(true)
+                    .default_value("127.0.0.1:8080")
+                    .help("sets the address on which the service listens"),
+            )
+            .arg(
+                Arg::with_name(ARG_MAX_SESSIONS)
+                    .long("max-sessions")
+                    .takes_value(true)
+                    .default_value("16")
+                    .help("sets the maximal number of sessions (i.e. running solvers) at once"),
+            )
+            .arg(
+                Arg::with_name(ARG_TMP_DIR)
+                    .long("tmp-dir")
+                    .takes_value(true)
+                    .help("sets the directory of the instance files given to the solvers (default: the system one)"),
+            )
+            .arg(
+                Arg::with_name(ARG_TIMEOUT)
+                    .long("timeout")
+                    .takes_value(true)
+                    .help("sets the time in seconds the solver of a session is given to answer each step; the session is dropped afterwards and the request fails with status 504"),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let mut limits = RunLimits::default();
+        if let Some(t) = read_time_limit(arg_matches, ARG_TIMEOUT)? {
+            limits.set_step_timeout(t);
+        }
+        let server = Arc::new(Server {
+            solver: arg_matches.value_of(ARG_SOLVER).unwrap().to_string(),
+            temp_files: TempFiles::new(arg_matches.value_of(ARG_TMP_DIR), false)?,
+            max_sessions: read_count(arg_matches, ARG_MAX_SESSIONS)?,
+            limits,
+            sessions: Mutex::new(Sessions::default()),
+        });
+        let address = arg_matches.value_of(ARG_LISTEN).unwrap();
+        let listener = TcpListener::bind(address)
+            .with_context(|| format!(r#"while listening on "{}""#, address))?;
+        info!("serving {} on http://{}", server.solver, address);
+        for stream in listener.incoming() {
+            match stream {
+                Ok(s) => {
+                    let server = Arc::clone(&server);
+                    std::thread::spawn(move || {
+                        if let Err(e) = server.serve_connection(s) {
+                            warn!("{:#}", e);
+                        }
+                    });
+                }
+                Err(e) => warn!("while accepting a connection: {}", e),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The answer of a solver for a step of a session.
+#[derive(Clone, Debug, PartialEq)]
+struct StepAnswer {
+    step: usize,
+    answer: String,
+}
+
+/// A request sent to the thread driving the solver of a session.
+enum SessionRequest {
+    Modification(String, Sender<Result<StepAnswer>>),
+    State(Sender<StepAnswer>),
+    Finish(Sender<Result<usize>>),
+}
+
+/// A running session, whose solver is driven by a dedicated thread.
+struct SessionHandle {
+    query: QueryType,
+    problem: String,
+    requests: Sender<SessionRequest>,
+    _instance: TempFile,
+}
+
+/// The sessions of the server.
+///
+/// A session is registered without a handle while its solver is starting, so that its slot is reserved.
+#[derive(Default)]
+struct Sessions {
+    next_id: usize,
+    running: BTreeMap<usize, Option<SessionHandle>>,
+}
+
+struct Server {
+    solver: String,
+    temp_files: TempFiles,
+    max_sessions: usize,
+    limits: RunLimits,
+    sessions: Mutex<Sessions>,
+}
+
+type HandlerResult = std::result::Result<Response, Response>;
+
+fn bad_request(e: anyhow::Error) -> Response {
+    Response::error(400, &e)
+}
+
+/// Builds the response to a solver failure, which is a timeout (504) if the solver was killed because it reached its time limit.
+fn solver_error(e: anyhow::Error) -> Response {
+    let status = match e.downcast_ref::<WrapError>() {
+        Some(WrapError::SolverStopped {
+            stop: Stop::Timeout,
+        }) => 504,
+        _ => 502,
+    };
+    Response::error(status, &e)
+}
+
+impl Server {
+    fn serve_connection(&self, stream: TcpStream) -> Result<()> {
+        stream
+            .set_read_timeout(Some(READ_TIMEOUT))
+            .context("while setting the timeout of a connection")?;
+        let mut writer = stream.try_clone().context("while handling a connection")?;
+        let response = match http::read_request(&mut BufReader::new(stream)) {
+            Ok(request) => self.handle(&request),
+            Err(e) => bad_request(e),
+        };
+        http::write_response(&mut writer, &response)
+    }
+
+    /// Computes the response to a request.
+    ///
+    /// The API handles sessions, each of them running the solver on a framework and a problem:
+    ///
+    /// * `POST /sessions` creates a session from a JSON object giving the `framework` (Aspartix format), the `problem` and the queried `argument` if any;
+    /// * `GET /sessions` lists the running sessions;
+    /// * `GET /sessions/ID` gives the current step of a session and its answer;
+    /// * `POST /sessions/ID/modifications` sends the `modifications` of a JSON object to the solver, and gives the answer for each of them;
+    /// * `DELETE /sessions/ID` ends the dynamics of a session and stops its solver.
+    fn handle(&self, request: &Request) -> Response {
+        let result = match (request.method.as_str(), request.segments().as_slice()) {
+            ("POST", ["sessions"]) => self.create_session(&request.body),
+            ("GET", ["sessions"]) => Ok(self.list_sessions()),
+            ("GET", ["sessions", id]) => self.session_state(id),
+            ("POST", ["sessions", id, "modifications"]) => {
+                self.push_modifications(id, &request.body)
+            }
+            ("DELETE", ["sessions", id]) => self.finish_session(id),
+            (_, ["sessions"]) | (_, ["sessions", _]) | (_, ["sessions", _, "modifications"]) => {
+                Err(Response::error(
+                    405,
+                    &anyhow!("method {} not allowed on {}", request.method, request.path),
+                ))
+            }
+            _ => Err(Response::error(
+                404,
+                &anyhow!("no such resource: {}", request.path),
+            )),
+        };
+        result.unwrap_or_else(|e| e)
+    }
+
+    fn create_session(&self, body: &str) -> HandlerResult {
+        let body = JsonValue::parse(body)
+            .context("while reading the request body")
+            .map_err(bad_request)?;
+        let field = |key: &str| body.get(key).and_then(|v| v.as_str());
+        let framework = field("framework")
+            .ok_or_else(|| bad_request(anyhow!(r#"missing "framework" string"#)))?;
+        let problem =
+            field("problem").ok_or_else(|| bad_request(anyhow!(r#"missing "problem" string"#)))?;
+        let query = QueryType::try_from((problem, field("argument"))).map_err(bad_request)?;
+        AspartixReader::default()
+            .read(&mut framework.as_bytes())
+            .context("while reading the framework")
+            .map_err(bad_request)?;
+        let id = self.reserve_session()?;
+        let started = self.start_session(framework, problem, &query);
+        let mut sessions = self.sessions.lock().unwrap();
+        let first_answer = match started {
+            Ok((handle, first_answer)) => {
+                sessions.running.insert(id, Some(handle));
+                first_answer
+            }
+            Err(e) => {
+                sessions.running.remove(&id);
+                return Err(e);
+            }
+        };
+        drop(sessions);
+        info!("started session {} ({})", id, problem);
+        Ok(Response::json(
+            201,
+            format!(
+                r#"{{"id":{},"problem":{},{}}}"#,
+                id,
+                json_string(problem),
+                step_json(&query, &first_answer).map_err(|e| Response::error(502, &e))?
+            ),
+        ))
+    }
+
+    /// Reserves the slot of a new session and returns its id, unless the maximal number of sessions is reached.
+    fn reserve_session(&self) -> std::result::Result<usize, Response> {
+        let mut sessions = self.sessions.lock().unwrap();
+        if sessions.running.len() >= self.max_sessions {
+            return Err(Response::error(
+                503,
+                &anyhow!(
+                    "the maximal number of sessions ({}) is reached",
+                    self.max_sessions
+                ),
+            ));
+        }
+        let id = sessions.next_id;
+        sessions.next_id += 1;
+        sessions.running.insert(id, None);
+        Ok(id)
+    }
+
+    /// Writes the instance file of a session and starts its solver, returning its handle and the answer for the initial framework.
+    fn start_session(
+        &self,
+        framework: &str,
+        problem: &str,
+        query: &QueryType,
+    ) -> std::result::Result<(SessionHandle, StepAnswer), Response> {
+        let (instance, mut file) = self
+            .temp_files
+            .create("instance.apx")
+            .map_err(|e| Response::error(500, &e))?;
+        file.write_all(framework.as_bytes())
+            .context("while writing the instance file")
+            .map_err(|e| Response::error(500, &e))?;
+        drop(file);
+        let arguments = query.command_arguments(problem, &instance.path().to_string_lossy(), "apx");
+        let (requests, first_answer) =
+            start_session(&self.solver, arguments, query, &self.limits).map_err(solver_error)?;
+        let handle = SessionHandle {
+            query: query.clone(),
+            problem: problem.to_string(),
+            requests,
+            _instance: instance,
+        };
+        Ok((handle, first_answer))
+    }
+
+    fn list_sessions(&self) -> Response {
+        let sessions = self.sessions.lock().unwrap();
+        let entries = sessions
+            .running
+            .iter()
+            .filter_map(|(id, s)| s.as_ref().map(|s| (id, s)))
+            .map(|(id, s)| format!(r#"{{"id":{},"problem":{}}}"#, id, json_string(&s.problem)))
+            .collect::<Vec<String>>();
+        Response::json(200, format!(r#"{{"sessions":[{}]}}"#, entries.join(",")))
+    }
+
+    /// Returns the channel of a running session and its query.
+    fn session(
+        &self,
+        id: &str,
+    ) -> std::result::Result<(usize, Sender<SessionRequest>, QueryType), Response> {
+        let not_found = || Response::error(404, &anyhow!("no such session: {}", id));
+        let id = id.parse::<usize>().map_err(|_| not_found())?;
+        let sessions = self.sessions.lock().unwrap();
+        let handle = sessions
+            .running
+            .get(&id)
+            .and_then(|s| s.as_ref())
+            .ok_or_else(not_found)?;
+        Ok((id, handle.requests.clone(), handle.query.clone()))
+    }
+
+    fn session_state(&self, id: &str) -> HandlerResult {
+        let (id, requests, query) = self.session(id)?;
+        let (sender, receiver) = mpsc::channel();
+        let state = requests
+            .send(SessionRequest::State(sender))
+            .ok()
+            .and_then(|_| receiver.recv().ok())
+            .ok_or_else(|| self.lost_session(id))?;
+        Ok(Response::json(
+            200,
+            format!(
+                r#"{{"id":{},{}}}"#,
+                id,
+                step_json(&query, &state).map_err(|e| Response::error(502, &e))?
+            ),
+        ))
+    }
+
+    fn push_modifications(&self, id: &str, body: &str) -> HandlerResult {
+        let (id, requests, query) = self.session(id)?;
+        let body = JsonValue::parse(body)
+            .context("while reading the request body")
+            .map_err(bad_request)?;
+        let modifications = body
+            .get("modifications")
+            .and_then(|m| m.as_array())
+            .and_then(|m| m.iter().map(|v| v.as_str()).collect::<Option<Vec<&str>>>())
+            .ok_or_else(|| bad_request(anyhow!(r#"missing "modifications" array of strings"#)))?;
+        let mut answers = Vec::with_capacity(modifications.len());
+        for m in modifications {
+            let (sender, receiver) = mpsc::channel();
+            let result = requests
+                .send(SessionRequest::Modification(m.to_string(), sender))
+                .ok()
+                .and_then(|_| receiver.recv().ok())
+                .ok_or_else(|| self.lost_session(id))?;
+            match result {
+                Ok(answer) => answers.push(
+                    step_json(&query, &answer)
+                        .map(|a| format!("{{{}}}", a))
+                        .map_err(|e| Response::error(502, &e))?,
+                ),
+                Err(e) => {
+                    if is_malformed_modification(&e) {
+                        return Err(bad_request(e));
+                    }
+                    self.sessions.lock().unwrap().running.remove(&id);
+                    warn!("session {} stopped: {:#}", id, e);
+                    return Err(solver_error(e));
+                }
+            }
+        }
+        Ok(Response::json(
+            200,
+            format!(r#"{{"answers":[{}]}}"#, answers.join(",")),
+        ))
+    }
+
+    fn finish_session(&self, id: &str) -> HandlerResult {
+        let (id, requests, _) = self.session(id)?;
+        self.sessions.lock().unwrap().running.remove(&id);
+        let (sender, receiver) = mpsc::channel();
+        let result = requests
+            .send(SessionRequest::Finish(sender))
+            .ok()
+            .and_then(|_| receiver.recv().ok())
+            .ok_or_else(|| self.lost_session(id))?;
+        info!("ended session {}", id);
+        match result {
+            Ok(steps) => Ok(Response::json(
+                200,
+                format!(r#"{{"id":{},"steps":{}}}"#, id, steps),
+            )),
+            Err(e) => Err(solver_error(e)),
+        }
+    }
+
+    /// Forgets a session whose thread has ended, and builds the corresponding response.
+    fn lost_session(&self, id: usize) -> Response {
+        self.sessions.lock().unwrap().running.remove(&id);
+        Response::error(502, &anyhow!("the solver of session {} is not running", id))
+    }
+}
+
+/// Starts the thread driving the solver of a session, returning its channel and the answer for the initial framework.
+fn start_session(
+    solver: &str,
+    arguments: Vec<String>,
+    query: &QueryType,
+    limits: &RunLimits,
+) -> Result<(Sender<SessionRequest>, StepAnswer)> {
+    let (requests, receiver) = mpsc::channel();
+    let (started_sender, started) = mpsc::channel();
+    let solver = solver.to_string();
+    let query = query.clone();
+    let limits = limits.clone();
+    std::thread::spawn(move || {
+        drive_session(
+            &solver,
+            &arguments,
+            &query,
+            &limits,
+            started_sender,
+            receiver,
+        )
+    });
+    let first_answer = started
+        .recv()
+        .map_err(|_| anyhow!("the session ended before it started"))??;
+    Ok((requests, first_answer))
+}
+
+/// Drives the solver of a session, answering the requests until the session is finished or its channel is closed.
+///
+/// The session is dropped, and its solver killed, when a modification cannot be processed for another reason than being malformed, including a solver exceeding its time limit.
+fn drive_session(
+    solver: &str,
+    arguments: &[String],
+    query: &QueryType,
+    limits: &RunLimits,
+    started: Sender<Result<StepAnswer>>,
+    requests: Receiver<SessionRequest>,
+) {
+    let mut session = match SolverSession::start(solver, arguments, query, limits) {
+        Ok(s) => s,
+        Err(e) => {
+            let _ = started.send(Err(e));
+            return;
+        }
+    };
+    let state = |s: &SolverSession| StepAnswer {
+        step: s.step(),
+        answer: s.answer().to_string(),
+    };
+    if started.send(Ok(state(&session))).is_err() {
+        return;
+    }
+    for request in requests.iter() {
+        match request {
+            SessionRequest::Modification(m, reply) => {
+                let result = match session.push_modification(&m) {
+                    Ok(_) => Ok(state(&session)),
+                    Err(e) => Err(e),
+                };
+                let fatal = matches!(&result, Err(e) if !is_malformed_modification(e));
+                let _ = reply.send(result);
+                if fatal {
+                    return;
+                }
+            }
+            SessionRequest::State(reply) => {
+                let _ = reply.send(state(&session));
+            }
+            SessionRequest::Finish(reply) => {
+                let _ = reply.send(session.finish());
+                return;
+            }
+        }
+    }
+}
+
+/// Returns `true` iff an error is due to a malformed modification, which is not sent to the solver.
+fn is_malformed_modification(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<WrapError>(),
+        Some(WrapError::ModificationParse { .. })
+    )
+}
+
+/// Formats the step of an answer, the raw answer and the answer parsed according to the query, as the members of a JSON object.
+///
+/// Extensions are arrays of labels, extension counts are strings (since they may exceed the JSON numbers) and acceptance statuses are booleans.
+fn step_json(query: &QueryType, answer: &StepAnswer) -> Result<String> {
+    let mut reader = answer.answer.as_bytes();
+    let extension_json = |labels: Vec<String>| {
+        format!(
+            "[{}]",
+            labels
+                .iter()
+                .map(|l| json_string(l))
+                .collect::<Vec<String>>()
+                .join(",")
+        )
+    };
+    let labels = |e: &crusti_arg::ArgumentSet<String>| {
+        e.iter().map(|a| a.label().clone()).collect::<Vec<String>>()
+    };
+    let parsed = match query {
+        QueryType::SE => extension_json(labels(&solutions::read_extension(&mut reader)?)),
+        QueryType::EE => format!(
+            "[{}]",
+            solutions::read_extension_set(&mut reader)?
+                .iter()
+                .map(|e| extension_json(labels(e)))
+                .collect::<Vec<String>>()
+                .join(",")
+        ),
+        QueryType::CE => json_string(&solutions::read_big_extension_count(&mut reader)?),
+        QueryType::DC(_) | QueryType::DS(_) => {
+            solutions::read_acceptance_status(&mut reader)?.to_string()
+        }
+    };
+    Ok(format!(
+        r#""step":{},"raw":{},"answer":{}"#,
+        answer.step,
+        json_string(&answer.answer),
+        parsed
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(step: usize, answer: &str) -> StepAnswer {
+        StepAnswer {
+            step,
+            answer: answer.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_step_json() {
+        assert_eq!(
+            r#""step":1,"raw":"[a, b]\n","answer":["a","b"]"#,
+            step_json(&QueryType::SE, &answer(1, "[a, b]\n")).unwrap()
+        );
+        assert_eq!(
+            r#""step":0,"raw":"[\n[]\n[a]\n]\n","answer":[[],["a"]]"#,
+            step_json(&QueryType::EE, &answer(0, "[\n[]\n[a]\n]\n")).unwrap()
+        );
+        assert_eq!(
+            r#""step":2,"raw":"NO\n","answer":false"#,
+            step_json(&QueryType::DS("a".to_string()), &answer(2, "NO\n")).unwrap()
+        );
+        assert_eq!(
+            r#""step":0,"raw":"3\n","answer":"3""#,
+            step_json(&QueryType::CE, &answer(0, "3\n")).unwrap()
+        );
+    }
+
+    fn request(method: &str, path: &str, body: &str) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    /// Builds a server running the given shell script, returned with the file of the script.
+    #[cfg(unix)]
+    fn script_server(script: &str, limits: RunLimits) -> (Server, TempFile) {
+        use std::os::unix::fs::PermissionsExt;
+        let temp_files = TempFiles::default();
+        let (solver, mut file) = temp_files.create("solver.sh").unwrap();
+        file.write_all(format!("#!/bin/sh\n{}\n", script).as_bytes())
+            .unwrap();
+        drop(file);
+        std::fs::set_permissions(solver.path(), std::fs::Permissions::from_mode(0o755)).unwrap();
+        let server = Server {
+            solver: solver.path().to_string_lossy().to_string(),
+            temp_files,
+            max_sessions: 1,
+            limits,
+            sessions: Mutex::new(Sessions::default()),
+        };
+        (server, solver)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sessions() {
+        // a solver answering YES for the initial framework, and NO after each modification
+        let (server, _solver) = script_server(
+            "echo YES\nwhile read l; do [ -z \"$l\" ] && exit 0; echo NO; done",
+            RunLimits::default(),
+        );
+        let create = request(
+            "POST",
+            "/sessions",
+            r#"{"framework": "arg(a).\n", "problem": "DC-CO-D", "argument": "a"}"#,
+        );
+        assert_eq!(
+            Response::json(
+                201,
+                r#"{"id":0,"problem":"DC-CO-D","step":0,"raw":"YES\n","answer":true}"#.to_string()
+            ),
+            server.handle(&create)
+        );
+        assert_eq!(503, server.handle(&create).status);
+        assert_eq!(
+            Response::json(
+                200,
+                r#"{"answers":[{"step":1,"raw":"NO\n","answer":false},{"step":2,"raw":"NO\n","answer":false}]}"#
+                    .to_string()
+            ),
+            server.handle(&request(
+                "POST",
+                "/sessions/0/modifications",
+                r#"{"modifications": ["+arg(b).", "+att(b,a)."]}"#
+            ))
+        );
+        assert_eq!(
+            400,
+            server
+                .handle(&request(
+                    "POST",
+                    "/sessions/0/modifications",
+                    r#"{"modifications": ["+arg(c)"]}"#
+                ))
+                .status
+        );
+        assert_eq!(
+            Response::json(
+                200,
+                r#"{"id":0,"step":2,"raw":"NO\n","answer":false}"#.to_string()
+            ),
+            server.handle(&request("GET", "/sessions/0", ""))
+        );
+        assert_eq!(
+            Response::json(
+                200,
+                r#"{"sessions":[{"id":0,"problem":"DC-CO-D"}]}"#.to_string()
+            ),
+            server.handle(&request("GET", "/sessions", ""))
+        );
+        assert_eq!(
+            Response::json(200, r#"{"id":0,"steps":2}"#.to_string()),
+            server.handle(&request("DELETE", "/sessions/0", ""))
+        );
+        assert_eq!(
+            404,
+            server.handle(&request("GET", "/sessions/0", "")).status
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_session_timeout() {
+        // a solver answering YES for the initial framework, and hanging at the first modification
+        let mut limits = RunLimits::default();
+        limits.set_step_timeout(Duration::from_millis(200));
+        let (server, _solver) = script_server("echo YES\nread l\nexec sleep 60", limits);
+        let create = request(
+            "POST",
+            "/sessions",
+            r#"{"framework": "arg(a).\n", "problem": "DC-CO-D", "argument": "a"}"#,
+        );
+        assert_eq!(201, server.handle(&create).status);
+        let modifications = request(
+            "POST",
+            "/sessions/0/modifications",
+            r#"{"modifications": ["+arg(b)."]}"#,
+        );
+        assert_eq!(504, server.handle(&modifications).status);
+        assert_eq!(
+            404,
+            server.handle(&request("GET", "/sessions/0", "")).status
+        );
+        assert_eq!(201, server.handle(&create).status);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_session_start_timeout() {
+        let mut limits = RunLimits::default();
+        limits.set_step_timeout(Duration::from_millis(200));
+        let (server, _solver) = script_server("exec sleep 60", limits);
+        let create = request(
+            "POST",
+            "/sessions",
+            r#"{"framework": "arg(a).\n", "problem": "DC-CO-D", "argument": "a"}"#,
+        );
+        assert_eq!(504, server.handle(&create).status);
+        assert!(server.sessions.lock().unwrap().running.is_empty());
+    }
+
+    #[test]
+    fn test_reserved_session() {
+        let server = Server {
+            solver: "/nonexistent/solver".to_string(),
+            temp_files: TempFiles::default(),
+            max_sessions: 1,
+            limits: RunLimits::default(),
+            sessions: Mutex::new(Sessions::default()),
+        };
+        assert_eq!(0, server.reserve_session().unwrap());
+        let create = request(
+            "POST",
+            "/sessions",
+            r#"{"framework": "arg(a).\n", "problem": "DC-CO-D", "argument": "a"}"#,
+        );
+        assert_eq!(503, server.handle(&create).status);
+        assert_eq!(
+            Response::json(200, r#"{"sessions":[]}"#.to_string()),
+            server.handle(&request("GET", "/sessions", ""))
+        );
+        assert_eq!(
+            404,
+            server.handle(&request("GET", "/sessions/0", "")).status
+        );
+    }
+
+    #[test]
+    fn test_request_errors() {
+        let server = Server {
+            solver: "/nonexistent/solver".to_string(),
+            temp_files: TempFiles::default(),
+            max_sessions: 1,
+            limits: RunLimits::default(),
+            sessions: Mutex::new(Sessions::default()),
+        };
+        for (method, path, body, status) in &[
+            ("GET", "/", "", 404),
+            ("PUT", "/sessions", "", 405),
+            ("POST", "/sessions", "{", 400),
+            ("POST", "/sessions", r#"{"problem": "SE-CO-D"}"#, 400),
+            (
+                "POST",
+                "/sessions",
+                r#"{"framework": "arg(a", "problem": "SE-CO-D"}"#,
+                400,
+            ),
+            (
+                "POST",
+                "/sessions",
+                r#"{"framework": "arg(a).", "problem": "SE-CO"}"#,
+                400,
+            ),
+            (
+                "POST",
+                "/sessions",
+                r#"{"framework": "arg(a).", "problem": "SE-CO-D"}"#,
+                502,
+            ),
+            ("POST", "/sessions/x/modifications", "{}", 404),
+        ] {
+            assert_eq!(
+                *status,
+                server.handle(&request(method, path, body)).status,
+                "{} {} {}",
+                method,
+                path,
+                body
+            );
+        }
+    }
+}
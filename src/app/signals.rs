@@ -0,0 +1,387 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! The teardown of the solvers when the wrapper is interrupted.
+//!
+//! On Unix systems, each solver leads its own process group, which is killed on SIGINT and SIGTERM.
+//! On Windows, each solver is assigned to a job object, which is terminated on Ctrl-C, Ctrl-Break and console close events, and closed (killing the processes it still contains) when the solver is released.
+use std::sync::atomic::{AtomicI32, AtomicIsize, AtomicUsize, Ordering};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::warn;
+use iccma_dynamics::runner::ProcessHooks;
+
+/// The maximal number of solver processes that can be tracked at the same time.
+const MAX_CHILDREN: usize = 64;
+
+/// The slot of a guard whose process is not tracked.
+const UNTRACKED: usize = usize::MAX;
+
+/// The solver processes to kill when the wrapper is interrupted.
+static CHILDREN: Children = Children::new();
+
+/// The number of the signal that interrupted the wrapper, or 0 if it was not interrupted.
+static INTERRUPTION: AtomicI32 = AtomicI32::new(0);
+
+/// A fixed-size set of process handles, usable from a signal handler since it never allocates nor locks.
+///
+/// The handles are process ids on Unix systems and job object handles on Windows.
+struct Children([AtomicIsize; MAX_CHILDREN]);
+
+impl Children {
+    const fn new() -> Self {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const EMPTY: AtomicIsize = AtomicIsize::new(0);
+        Children([EMPTY; MAX_CHILDREN])
+    }
+
+    /// Adds a handle to the set, returning the index of its slot or `None` if the set is full.
+    fn register(&self, handle: isize) -> Option<usize> {
+        self.0.iter().position(|slot| {
+            slot.compare_exchange(0, handle, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+        })
+    }
+
+    fn unregister(&self, index: usize) {
+        self.0[index].store(0, Ordering::SeqCst);
+    }
+
+    fn handles(&self) -> impl Iterator<Item = isize> + '_ {
+        self.0
+            .iter()
+            .map(|slot| slot.load(Ordering::SeqCst))
+            .filter(|handle| *handle != 0)
+    }
+
+    fn terminate_all(&self) {
+        self.handles().for_each(sys::terminate);
+    }
+}
+
+/// Keeps a solver process registered as a process to kill on interruption, until the guard is dropped.
+///
+/// On Unix systems, the process is expected to lead its own process group (see [`SolverCommand::process`](super::wrap_command::SolverCommand::process)), so that the processes it spawned are killed with it.
+/// On Windows, the process is assigned to a new job object, so that the processes it spawns are assigned to it too.
+///
+/// The process must be untracked before it is waited for, so that a process id reused after the end of the solver is never killed.
+pub(crate) struct ChildGuard {
+    handle: isize,
+    slot: AtomicUsize,
+}
+
+impl ChildGuard {
+    /// Starts tracking a solver process.
+    ///
+    /// An error is returned if the process cannot be tracked, or if too many processes are already tracked.
+    pub(crate) fn new(child: &tokio::process::Child) -> Result<Self> {
+        let handle = sys::track(child).context("while tracking the solver process")?;
+        let guard = ChildGuard {
+            handle,
+            slot: AtomicUsize::new(UNTRACKED),
+        };
+        guard.track()?;
+        Ok(guard)
+    }
+
+    /// Registers the process as a process to kill on interruption, if it is not already.
+    pub(crate) fn track(&self) -> Result<()> {
+        if self.slot.load(Ordering::SeqCst) == UNTRACKED {
+            let slot = CHILDREN.register(self.handle).ok_or_else(|| {
+                anyhow!(
+                    "cannot track more than {} solver processes at the same time",
+                    MAX_CHILDREN
+                )
+            })?;
+            self.slot.store(slot, Ordering::SeqCst);
+        }
+        Ok(())
+    }
+
+    /// Unregisters the process, which is not killed on interruption anymore.
+    pub(crate) fn untrack(&self) {
+        let slot = self.slot.swap(UNTRACKED, Ordering::SeqCst);
+        if slot != UNTRACKED {
+            CHILDREN.unregister(slot);
+        }
+    }
+
+    /// Kills the process (and the processes it spawned).
+    ///
+    /// The process must not have been waited for.
+    pub(crate) fn terminate(&self) {
+        sys::terminate(self.handle);
+    }
+}
+
+impl Drop for ChildGuard {
+    fn drop(&mut self) {
+        self.untrack();
+        sys::release(self.handle);
+    }
+}
+
+/// The hooks of the solver processes run by the wrapper, which track them with a [`ChildGuard`] and kill them (with the processes they spawned) when the wrapper is interrupted.
+#[derive(Default)]
+pub(crate) struct InterruptionHooks {
+    guard: Option<ChildGuard>,
+}
+
+impl ProcessHooks for InterruptionHooks {
+    fn spawned(&mut self, child: &tokio::process::Child) -> Result<()> {
+        self.guard = Some(ChildGuard::new(child)?);
+        Ok(())
+    }
+
+    fn waiting(&self) {
+        if let Some(g) = &self.guard {
+            g.untrack();
+        }
+    }
+
+    fn still_running(&self) {
+        if let Some(Err(e)) = self.guard.as_ref().map(|g| g.track()) {
+            warn!("{:#}; the solver will not be killed on interruption", e);
+        }
+    }
+
+    fn killing(&self) {
+        if let Some(g) = &self.guard {
+            g.terminate();
+        }
+    }
+
+    fn interrupted(&self) -> bool {
+        interruption().is_some()
+    }
+}
+
+/// Returns the number of the signal that interrupted the wrapper, if any.
+///
+/// On Windows, Ctrl-C is reported as SIGINT (2) and the other console events as SIGTERM (15).
+pub(crate) fn interruption() -> Option<i32> {
+    match INTERRUPTION.load(Ordering::SeqCst) {
+        0 => None,
+        signal => Some(signal),
+    }
+}
+
+/// Records an interruption and kills the registered solvers.
+///
+/// On the first interruption, the solvers are killed and the interruption is recorded, so that the run fails as soon as the wrapper notices the end of the solvers.
+/// A second interruption makes the wrapper exit immediately, which is useful when it waits for something else than a local solver (e.g. a remote one reached through TCP).
+fn interrupt(signal: i32) {
+    if INTERRUPTION.swap(signal, Ordering::SeqCst) != 0 {
+        sys::exit(super::exit_status::INTERRUPTED)
+    }
+    CHILDREN.terminate_all();
+}
+
+/// Installs the handlers of the interruptions (SIGINT and SIGTERM on Unix systems, console control events on Windows).
+pub(crate) fn install_handlers() {
+    sys::install_handlers();
+}
+
+#[cfg(unix)]
+mod sys {
+    use signal_hook::consts::{SIGINT, SIGTERM};
+
+    pub(super) fn install_handlers() {
+        for signal in [SIGINT, SIGTERM] {
+            // SAFETY: the handler only uses atomics, kill and _exit, which are async-signal-safe
+            let registration = unsafe {
+                signal_hook::low_level::register(signal, move || super::interrupt(signal))
+            };
+            if let Err(e) = registration {
+                crusti_app_helper::warn!("cannot handle signal {}: {}", signal, e);
+            }
+        }
+    }
+
+    pub(super) fn track(child: &tokio::process::Child) -> std::io::Result<isize> {
+        child
+            .id()
+            .map(|pid| pid as isize)
+            .ok_or_else(|| std::io::Error::other("the process has already been waited for"))
+    }
+
+    pub(super) fn release(_pid: isize) {}
+
+    /// Kills the process group led by a process.
+    pub(super) fn terminate(pid: isize) {
+        unsafe {
+            libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+        }
+    }
+
+    pub(super) fn exit(status: i32) -> ! {
+        signal_hook::low_level::exit(status)
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use windows_sys::core::BOOL;
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::Console::{SetConsoleCtrlHandler, CTRL_C_EVENT};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, TerminateJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+
+    const SIGINT: i32 = 2;
+    const SIGTERM: i32 = 15;
+
+    pub(super) fn install_handlers() {
+        if unsafe { SetConsoleCtrlHandler(Some(handle_control_event), 1) } == 0 {
+            crusti_app_helper::warn!(
+                "cannot handle console events: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+
+    unsafe extern "system" fn handle_control_event(event: u32) -> BOOL {
+        super::interrupt(if event == CTRL_C_EVENT {
+            SIGINT
+        } else {
+            SIGTERM
+        });
+        1
+    }
+
+    /// Assigns a process to a new job object killing its processes when closed, returning the handle of the job.
+    pub(super) fn track(child: &tokio::process::Child) -> std::io::Result<isize> {
+        let process = child
+            .raw_handle()
+            .ok_or_else(|| std::io::Error::other("the process has already been waited for"))?
+            as HANDLE;
+        unsafe {
+            let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if job.is_null() {
+                return Err(std::io::Error::last_os_error());
+            }
+            let mut information = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+            information.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            if SetInformationJobObject(
+                job,
+                JobObjectExtendedLimitInformation,
+                std::ptr::addr_of!(information).cast(),
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            ) == 0
+                || AssignProcessToJobObject(job, process) == 0
+            {
+                let error = std::io::Error::last_os_error();
+                CloseHandle(job);
+                return Err(error);
+            }
+            Ok(job as isize)
+        }
+    }
+
+    /// Closes a job object, which kills the processes it still contains.
+    pub(super) fn release(job: isize) {
+        unsafe {
+            CloseHandle(job as HANDLE);
+        }
+    }
+
+    pub(super) fn terminate(job: isize) {
+        unsafe {
+            TerminateJobObject(job as HANDLE, super::super::exit_status::INTERRUPTED as u32);
+        }
+    }
+
+    pub(super) fn exit(status: i32) -> ! {
+        std::process::exit(status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_children_registration() {
+        let children = Children::new();
+        let first = children.register(12).unwrap();
+        let second = children.register(34).unwrap();
+        assert_eq!(vec![12, 34], children.handles().collect::<Vec<isize>>());
+        children.unregister(first);
+        assert_eq!(vec![34], children.handles().collect::<Vec<isize>>());
+        assert_eq!(Some(first), children.register(56));
+        children.unregister(second);
+        assert_eq!(vec![56], children.handles().collect::<Vec<isize>>());
+    }
+
+    #[test]
+    fn test_children_full() {
+        let children = Children::new();
+        (1..=MAX_CHILDREN as isize).for_each(|handle| {
+            children.register(handle).unwrap();
+        });
+        assert!(children.register(1000).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_terminate_all() {
+        use std::os::unix::process::{CommandExt, ExitStatusExt};
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let mut command = std::process::Command::new("sh");
+        command.arg("-c").arg("sleep 60 & wait").process_group(0);
+        let mut child = {
+            let _context = runtime.enter();
+            tokio::process::Command::from(command).spawn().unwrap()
+        };
+        let children = Children::new();
+        children.register(sys::track(&child).unwrap()).unwrap();
+        children.terminate_all();
+        let status = runtime.block_on(child.wait()).unwrap();
+        assert_eq!(Some(libc::SIGKILL), status.signal());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_child_guard_tracking() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let mut child = {
+            let _context = runtime.enter();
+            tokio::process::Command::new("true").spawn().unwrap()
+        };
+        let pid = child.id().unwrap() as isize;
+        let guard = ChildGuard::new(&child).unwrap();
+        assert!(CHILDREN.handles().any(|h| h == pid));
+        guard.untrack();
+        assert!(!CHILDREN.handles().any(|h| h == pid));
+        guard.track().unwrap();
+        assert!(CHILDREN.handles().any(|h| h == pid));
+        guard.untrack();
+        runtime.block_on(child.wait()).unwrap();
+        drop(guard);
+        assert!(!CHILDREN.handles().any(|h| h == pid));
+    }
+}
@@ -0,0 +1,497 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    convert::TryFrom,
+    io::{BufWriter, Write},
+};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::{info, warn, AppSettings, Arg, Command, SubCommand, WithExitStatus};
+use crusti_arg::{
+    compression,
+    incremental::IncrementalSolver,
+    solutions::{self, ExtensionSetWriter},
+    solvers::{self, SMALL_FRAMEWORK_SIZE},
+    AAFramework, ArgumentSet, AspartixReader, Semantics,
+};
+use iccma_dynamics::runner::QueryType;
+
+use super::exit_status::MALFORMED_INPUT;
+use super::framework_state::FrameworkState;
+use super::locality;
+use super::wrap_command::read_modifications;
+
+pub(crate) struct SolveCommand;
+
+const CMD_NAME: &str = "solve";
+
+const ARG_PROBLEM: &str = "PROBLEM";
+const ARG_INPUT_FILE: &str = "INPUT_FILE";
+const ARG_MODIFICATION_FILE: &str = "MODIFICATION_FILE";
+const ARG_ARGUMENT: &str = "ARGUMENT";
+const ARG_ENGINE: &str = "ENGINE";
+
+const ENGINE_NAIVE: &str = "naive";
+const ENGINE_SAT: &str = "sat";
+
+/// The built-in engines solving the frameworks of a dynamics.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Engine {
+    /// The naive engine, enumerating the conflict-free sets of each framework.
+    Naive,
+    /// The SAT-based engine, reusing a single incremental SAT solver from a step to the next one.
+    Sat,
+}
+
+impl SolveCommand {
+    pub fn new() -> Self {
+        SolveCommand
+    }
+}
+
+impl<'a> Command<'a> for SolveCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about("solves a problem with a built-in engine, on a framework and optionally its dynamics")
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_PROBLEM)
+                    .long("problem")
+                    .short("p")
+                    .takes_value(true)
+                    .help("sets the problem to solve (e.g. DC-CO or DC-CO-D)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_INPUT_FILE)
+                    .long("input-file")
+                    .short("f")
+                    .takes_value(true)
+                    .help("sets the input file containing the framework (Aspartix format)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_MODIFICATION_FILE)
+                    .long("modification-file")
+                    .short("m")
+                    .takes_value(true)
+                    .help("sets the modification file; an answer is given for each step of the dynamics"),
+            )
+            .arg(
+                Arg::with_name(ARG_ARGUMENT)
+                    .long("argument")
+                    .short("a")
+                    .takes_value(true)
+                    .help("sets the argument (for DC and DS problems)"),
+            )
+            .arg(
+                Arg::with_name(ARG_ENGINE)
+                    .long("engine")
+                    .takes_value(true)
+                    .possible_values(&[ENGINE_NAIVE, ENGINE_SAT])
+                    .default_value(ENGINE_NAIVE)
+                    .help("sets the engine used to solve the problem (the sat engine handles the CO and ST semantics)"),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let problem = arg_matches.value_of(ARG_PROBLEM).unwrap();
+        let dynamic_problem = if problem.split('-').count() == 2 {
+            format!("{}-D", problem)
+        } else {
+            problem.to_string()
+        };
+        let query =
+            QueryType::try_from((dynamic_problem.as_str(), arg_matches.value_of(ARG_ARGUMENT)))?;
+        let semantics = dynamic_problem
+            .split('-')
+            .nth(1)
+            .unwrap()
+            .parse::<Semantics>()?;
+        let framework = AspartixReader::default()
+            .read(
+                &mut compression::open(arg_matches.value_of(ARG_INPUT_FILE).unwrap())
+                    .context("while opening input file")
+                    .with_exit_status(MALFORMED_INPUT)?,
+            )
+            .context("while reading input file")
+            .with_exit_status(MALFORMED_INPUT)?;
+        let modifications = match arg_matches.value_of(ARG_MODIFICATION_FILE) {
+            Some(path) => read_modifications(path).with_exit_status(MALFORMED_INPUT)?,
+            None => vec![],
+        };
+        let engine = match arg_matches.value_of(ARG_ENGINE) {
+            Some(ENGINE_SAT) => Engine::Sat,
+            _ => Engine::Naive,
+        };
+        let n_reused = solve_dynamics(
+            &framework,
+            &modifications,
+            semantics,
+            engine,
+            &query,
+            &mut BufWriter::new(std::io::stdout().lock()),
+        )?;
+        info!(
+            "solved {} step(s), reusing {} answer(s) unaffected by the modifications",
+            modifications.len() + 1,
+            n_reused
+        );
+        Ok(())
+    }
+}
+
+/// Solves a query on each step of a dynamics, returning the number of answers reused from the previous step.
+///
+/// Under a directional semantics, the answer of a DC or DS query is reused when no modification reaches the strongly connected component of the queried argument.
+/// The SAT-based engine keeps the same solver for all the steps, so that the clauses it learnt on a framework help it on the next ones.
+pub(crate) fn solve_dynamics(
+    framework: &AAFramework<String>,
+    modifications: &[String],
+    semantics: Semantics,
+    engine: Engine,
+    query: &QueryType,
+    writer: &mut dyn Write,
+) -> Result<usize> {
+    let local_query_arg = match query {
+        QueryType::DC(arg) | QueryType::DS(arg) if locality::is_directional(semantics) => Some(arg),
+        _ => None,
+    };
+    let mut incremental_solver = match engine {
+        Engine::Sat => Some(IncrementalSolver::new(semantics)?),
+        Engine::Naive => None,
+    };
+    let mut state = FrameworkState::new(framework);
+    let mut warned_size = false;
+    let mut previous_answer: Option<Vec<u8>> = None;
+    let mut changed = vec![];
+    let mut n_reused = 0;
+    for step in 0..=modifications.len() {
+        let step_framework = state.to_framework()?;
+        let reusable = match (local_query_arg, &previous_answer) {
+            (Some(arg), Some(_)) => match step_framework.argument_set().get_argument_index(arg) {
+                Ok(id) => !locality::affected_arguments(&step_framework, &changed)[id],
+                Err(_) => false,
+            },
+            _ => false,
+        };
+        if reusable {
+            n_reused += 1;
+        } else if let Some(solver) = incremental_solver.as_mut() {
+            let mut answer = Vec::new();
+            solve_incremental(solver, &step_framework, query, &mut answer)
+                .with_context(|| format!("while solving step {}", step))?;
+            previous_answer = Some(answer);
+        } else {
+            if !warned_size && step_framework.argument_set().len() > SMALL_FRAMEWORK_SIZE {
+                warn!(
+                    "the naive engine may be slow on frameworks with more than {} arguments",
+                    SMALL_FRAMEWORK_SIZE
+                );
+                warned_size = true;
+            }
+            let mut answer = Vec::new();
+            solve(&step_framework, semantics, query, &mut answer)
+                .with_context(|| format!("while solving step {}", step))?;
+            previous_answer = Some(answer);
+        }
+        writer
+            .write_all(previous_answer.as_ref().unwrap())
+            .and_then(|_| writer.flush())
+            .context("while writing the answer")?;
+        if let Some(m) = modifications.get(step) {
+            changed = state
+                .apply(m)
+                .with_context(|| format!(r#"while applying modification "{}""#, m))
+                .with_exit_status(MALFORMED_INPUT)?;
+        }
+    }
+    Ok(n_reused)
+}
+
+/// Solves a query on a framework with the native engines, writing the answer in the format expected from solvers.
+///
+/// SE queries without any extension are answered by `NO`.
+pub(crate) fn solve(
+    framework: &AAFramework<String>,
+    semantics: Semantics,
+    query: &QueryType,
+    writer: &mut dyn Write,
+) -> Result<()> {
+    let no_such_argument = |arg: &str| anyhow!(r#"no such argument: "{}""#, arg);
+    if let QueryType::DC(arg) | QueryType::DS(arg) = query {
+        if framework.argument_set().get_argument_index(arg).is_err() {
+            return Err(no_such_argument(arg));
+        }
+    }
+    let to_argument_set = |extension: &[bool]| to_argument_set(framework, extension);
+    let solver = solvers::default_solver(framework, semantics)?;
+    match query {
+        QueryType::SE => match solver.one_extension(semantics)? {
+            Some(e) => solutions::write_extension(writer, &to_argument_set(&e)),
+            None => solutions::write_acceptance_status(writer, false),
+        },
+        QueryType::EE => {
+            let mut set_writer = ExtensionSetWriter::new(writer);
+            set_writer.begin()?;
+            for e in solver.all_extensions(semantics)? {
+                set_writer.write_extension(&to_argument_set(&e))?;
+            }
+            set_writer.end()
+        }
+        QueryType::CE => {
+            solutions::write_extension_count(writer, solver.count_extensions(semantics)?)
+        }
+        QueryType::DC(arg) => solutions::write_acceptance_status(
+            writer,
+            framework.credulously_accepted(arg, semantics)?,
+        ),
+        QueryType::DS(arg) => solutions::write_acceptance_status(
+            writer,
+            framework.skeptically_accepted(arg, semantics)?,
+        ),
+    }?;
+    writer.flush().context("while writing the answer")
+}
+
+/// Solves a query on a framework with an incremental SAT-based solver, writing the answer in the format expected from solvers.
+///
+/// The framework becomes the current one of the solver.
+/// SE queries without any extension are answered by `NO`.
+fn solve_incremental(
+    solver: &mut IncrementalSolver<String>,
+    framework: &AAFramework<String>,
+    query: &QueryType,
+    writer: &mut dyn Write,
+) -> Result<()> {
+    solver.set_framework(framework);
+    match query {
+        QueryType::SE => match solver.one_extension() {
+            Some(e) => solutions::write_extension(writer, &to_argument_set(framework, &e)),
+            None => solutions::write_acceptance_status(writer, false),
+        },
+        QueryType::EE => {
+            let mut set_writer = ExtensionSetWriter::new(writer);
+            set_writer.begin()?;
+            for e in solver.all_extensions() {
+                set_writer.write_extension(&to_argument_set(framework, &e))?;
+            }
+            set_writer.end()
+        }
+        QueryType::CE => solutions::write_extension_count(writer, solver.all_extensions().len()),
+        QueryType::DC(arg) => {
+            solutions::write_acceptance_status(writer, solver.is_credulously_accepted(arg)?)
+        }
+        QueryType::DS(arg) => {
+            solutions::write_acceptance_status(writer, solver.is_skeptically_accepted(arg)?)
+        }
+    }?;
+    writer.flush().context("while writing the answer")
+}
+
+/// Returns the set of the arguments of a framework belonging to an extension.
+fn to_argument_set(framework: &AAFramework<String>, extension: &[bool]) -> ArgumentSet<String> {
+    ArgumentSet::new(
+        framework
+            .argument_set()
+            .iter()
+            .filter(|a| extension[a.id()])
+            .map(|a| a.label().clone())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve_to_string(instance: &str, semantics: Semantics, query: QueryType) -> String {
+        let af = AspartixReader::default()
+            .read(&mut instance.as_bytes())
+            .unwrap();
+        let mut out = Vec::new();
+        solve(&af, semantics, &query, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    const INSTANCE: &str = "arg(a).\narg(b).\narg(c).\natt(a,b).\natt(b,a).\natt(b,c).\n";
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(
+            "[\n[a, c]\n[b]\n]\n",
+            solve_to_string(INSTANCE, Semantics::Preferred, QueryType::EE)
+        );
+        assert_eq!(
+            "3\n",
+            solve_to_string(INSTANCE, Semantics::Complete, QueryType::CE)
+        );
+        assert_eq!(
+            "YES\n",
+            solve_to_string(INSTANCE, Semantics::Stable, QueryType::DC("c".to_string()))
+        );
+        assert_eq!(
+            "NO\n",
+            solve_to_string(INSTANCE, Semantics::Stable, QueryType::DS("c".to_string()))
+        );
+        assert_eq!(
+            "[]\n",
+            solve_to_string(INSTANCE, Semantics::Grounded, QueryType::SE)
+        );
+    }
+
+    #[test]
+    fn test_solve_se_no_extension() {
+        assert_eq!(
+            "NO\n",
+            solve_to_string("arg(a).\natt(a,a).\n", Semantics::Stable, QueryType::SE)
+        );
+    }
+
+    fn solve_dynamics_to_string(
+        instance: &str,
+        modifications: &[&str],
+        semantics: Semantics,
+        query: QueryType,
+    ) -> (String, usize) {
+        solve_dynamics_with_engine(instance, modifications, semantics, Engine::Naive, query)
+    }
+
+    fn solve_dynamics_with_engine(
+        instance: &str,
+        modifications: &[&str],
+        semantics: Semantics,
+        engine: Engine,
+        query: QueryType,
+    ) -> (String, usize) {
+        let af = AspartixReader::default()
+            .read(&mut instance.as_bytes())
+            .unwrap();
+        let modifications = modifications
+            .iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<String>>();
+        let mut out = Vec::new();
+        let n_reused =
+            solve_dynamics(&af, &modifications, semantics, engine, &query, &mut out).unwrap();
+        (String::from_utf8(out).unwrap(), n_reused)
+    }
+
+    #[test]
+    fn test_solve_dynamics_reuse() {
+        let instance = "arg(a).\narg(b).\narg(c).\natt(a,b).\n";
+        let modifications = ["+att(b,c).", "+att(c,a).", "-att(b,c)."];
+        assert_eq!(
+            ("YES\nYES\nNO\nNO\n".to_string(), 1),
+            solve_dynamics_to_string(
+                instance,
+                &modifications,
+                Semantics::Complete,
+                QueryType::DC("a".to_string())
+            )
+        );
+        assert_eq!(
+            ("YES\nYES\nNO\nNO\n".to_string(), 0),
+            solve_dynamics_to_string(
+                instance,
+                &modifications,
+                Semantics::Stable,
+                QueryType::DC("a".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn test_solve_dynamics_sat_engine() {
+        let instance = "arg(a).\narg(b).\narg(c).\natt(a,b).\natt(b,a).\n";
+        let modifications = ["+att(b,c).", "-arg(a).", "+arg(a).", "+att(c,c)."];
+        for semantics in [Semantics::Complete, Semantics::Stable] {
+            for query in [
+                QueryType::SE,
+                QueryType::EE,
+                QueryType::CE,
+                QueryType::DC("c".to_string()),
+                QueryType::DS("c".to_string()),
+            ] {
+                let (naive, _) = solve_dynamics_with_engine(
+                    instance,
+                    &modifications,
+                    semantics,
+                    Engine::Naive,
+                    query.clone(),
+                );
+                let (sat, _) = solve_dynamics_with_engine(
+                    instance,
+                    &modifications,
+                    semantics,
+                    Engine::Sat,
+                    query.clone(),
+                );
+                let sorted_lines = |answers: &str| {
+                    let mut lines = answers.lines().map(String::from).collect::<Vec<_>>();
+                    lines.sort();
+                    lines
+                };
+                match query {
+                    QueryType::SE => assert_eq!(
+                        naive.lines().map(|l| l == "NO").collect::<Vec<_>>(),
+                        sat.lines().map(|l| l == "NO").collect::<Vec<_>>()
+                    ),
+                    QueryType::EE => assert_eq!(sorted_lines(&naive), sorted_lines(&sat)),
+                    _ => assert_eq!(naive, sat, "{:?}", semantics),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_sat_engine_unhandled_semantics() {
+        let af = AspartixReader::default()
+            .read(&mut INSTANCE.as_bytes())
+            .unwrap();
+        assert!(solve_dynamics(
+            &af,
+            &[],
+            Semantics::Preferred,
+            Engine::Sat,
+            &QueryType::SE,
+            &mut Vec::new()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_solve_unknown_argument() {
+        let af = AspartixReader::default()
+            .read(&mut INSTANCE.as_bytes())
+            .unwrap();
+        assert!(solve(
+            &af,
+            Semantics::Complete,
+            &QueryType::DC("d".to_string()),
+            &mut Vec::new()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,220 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::io::{BufWriter, Write};
+
+use anyhow::{Context, Result};
+use crusti_app_helper::{info, AppSettings, Arg, Command, SubCommand};
+use crusti_arg::{compression, AAFramework, AspartixReader, LabelType};
+
+use super::framework_state::FrameworkState;
+use super::wrap_command::read_modifications;
+
+pub(crate) struct StatsCommand;
+
+const CMD_NAME: &str = "stats";
+
+const ARG_INPUT_FILE: &str = "INPUT_FILE";
+const ARG_MODIFICATION_FILE: &str = "MODIFICATION_FILE";
+const ARG_OUTPUT_FILE: &str = "OUTPUT_FILE";
+const ARG_HISTOGRAMS: &str = "HISTOGRAMS";
+
+const STATS_HEADER: &str =
+    "step,arguments,attacks,density,max_in_degree,max_out_degree,mean_degree,components";
+
+impl StatsCommand {
+    pub fn new() -> Self {
+        StatsCommand
+    }
+}
+
+impl<'a> Command<'a> for StatsCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about("computes structural statistics of a framework and optionally of each step of its dynamics")
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_INPUT_FILE)
+                    .long("input-file")
+                    .short("f")
+                    .takes_value(true)
+                    .help("sets the input file containing the framework (Aspartix format)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_MODIFICATION_FILE)
+                    .long("modification-file")
+                    .short("m")
+                    .takes_value(true)
+                    .help("sets the modification file; statistics are given for each step of the dynamics"),
+            )
+            .arg(
+                Arg::with_name(ARG_OUTPUT_FILE)
+                    .long("output-file")
+                    .short("o")
+                    .takes_value(true)
+                    .help("sets the file in which the statistics are written (default: standard output)"),
+            )
+            .arg(
+                Arg::with_name(ARG_HISTOGRAMS)
+                    .long("histograms")
+                    .takes_value(false)
+                    .help("writes the in- and out-degree histograms of the last step instead of the statistics"),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let framework = AspartixReader::default()
+            .read(
+                &mut compression::open(arg_matches.value_of(ARG_INPUT_FILE).unwrap())
+                    .context("while opening input file")?,
+            )
+            .context("while reading input file")?;
+        let modifications = match arg_matches.value_of(ARG_MODIFICATION_FILE) {
+            Some(path) => read_modifications(path)?,
+            None => vec![],
+        };
+        let mut writer: Box<dyn Write> = match arg_matches.value_of(ARG_OUTPUT_FILE) {
+            Some(path) => Box::new(BufWriter::new(
+                compression::create(path)
+                    .with_context(|| format!(r#"while creating file "{}""#, path))?,
+            )),
+            None => Box::new(std::io::stdout()),
+        };
+        if arg_matches.is_present(ARG_HISTOGRAMS) {
+            let mut state = FrameworkState::new(&framework);
+            for m in modifications.iter() {
+                state
+                    .apply(m)
+                    .with_context(|| format!(r#"while applying modification "{}""#, m))?;
+            }
+            write_histograms(&state.to_framework()?, &mut writer)?;
+        } else {
+            write_step_stats(&framework, &modifications, &mut writer)?;
+        }
+        writer.flush().context("while writing the statistics")?;
+        info!(
+            "computed the statistics of {} step(s)",
+            modifications.len() + 1
+        );
+        Ok(())
+    }
+}
+
+/// Writes a CSV line of statistics for each step of a dynamics, the first step being the initial framework.
+fn write_step_stats(
+    framework: &AAFramework<String>,
+    modifications: &[String],
+    writer: &mut dyn Write,
+) -> Result<()> {
+    writeln!(writer, "{}", STATS_HEADER).context("while writing the statistics")?;
+    let mut state = FrameworkState::new(framework);
+    for step in 0..=modifications.len() {
+        let step_framework = state.to_framework()?;
+        writeln!(writer, "{}", stats_line(step, &step_framework))
+            .context("while writing the statistics")?;
+        if let Some(m) = modifications.get(step) {
+            state
+                .apply(m)
+                .with_context(|| format!(r#"while applying modification "{}""#, m))?;
+        }
+    }
+    Ok(())
+}
+
+fn stats_line<T: LabelType>(step: usize, framework: &AAFramework<T>) -> String {
+    let degree_stats = framework.degree_stats();
+    format!(
+        "{},{},{},{:.3},{},{},{:.3},{}",
+        step,
+        framework.argument_set().len(),
+        framework.n_attacks(),
+        framework.density(),
+        degree_stats.max_in_degree(),
+        degree_stats.max_out_degree(),
+        degree_stats.mean_degree(),
+        framework.strongly_connected_components().len()
+    )
+}
+
+/// Writes the in- and out-degree histograms of a framework as a CSV table, with a line per degree.
+fn write_histograms<T: LabelType>(
+    framework: &AAFramework<T>,
+    writer: &mut dyn Write,
+) -> Result<()> {
+    let degree_stats = framework.degree_stats();
+    let in_histogram = degree_stats.in_degree_histogram();
+    let out_histogram = degree_stats.out_degree_histogram();
+    writeln!(writer, "degree,in,out").context("while writing the histograms")?;
+    for degree in 0..in_histogram.len().max(out_histogram.len()) {
+        writeln!(
+            writer,
+            "{},{},{}",
+            degree,
+            in_histogram.get(degree).unwrap_or(&0),
+            out_histogram.get(degree).unwrap_or(&0)
+        )
+        .context("while writing the histograms")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_af(s: &str) -> AAFramework<String> {
+        AspartixReader::default().read(&mut s.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_step_stats() {
+        let framework = read_af("arg(a).\narg(b).\narg(c).\natt(a,b).\natt(b,a).\n");
+        let modifications = vec!["+att(a,c).".to_string(), "-arg(b).".to_string()];
+        let mut output = Vec::new();
+        write_step_stats(&framework, &modifications, &mut output).unwrap();
+        assert_eq!(
+            vec![
+                STATS_HEADER,
+                "0,3,2,0.222,1,1,0.667,2",
+                "1,3,3,0.333,1,2,1.000,2",
+                "2,2,1,0.250,1,1,0.500,2",
+            ],
+            String::from_utf8(output)
+                .unwrap()
+                .lines()
+                .collect::<Vec<&str>>()
+        );
+    }
+
+    #[test]
+    fn test_histograms() {
+        let framework = read_af("arg(a).\narg(b).\narg(c).\natt(a,b).\natt(a,c).\n");
+        let mut output = Vec::new();
+        write_histograms(&framework, &mut output).unwrap();
+        assert_eq!(
+            "degree,in,out\n0,1,2\n1,2,0\n2,0,1\n",
+            String::from_utf8(output).unwrap()
+        );
+    }
+}
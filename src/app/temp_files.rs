@@ -0,0 +1,175 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    fs::{File, OpenOptions},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::info;
+
+/// A counter making the names of the temporary files unique within the process.
+static NEXT_TEMP_FILE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// The manager of the temporary files given to the solvers (translated, decompressed or resumed instances).
+///
+/// The files are created in a configurable directory, and are removed when their handle is dropped, including when the run fails or panics, unless they must be kept for debugging.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TempFiles {
+    dir: PathBuf,
+    keep: bool,
+}
+
+impl Default for TempFiles {
+    fn default() -> Self {
+        TempFiles {
+            dir: std::env::temp_dir(),
+            keep: false,
+        }
+    }
+}
+
+impl TempFiles {
+    /// Builds a manager creating its files in the given directory (or the system one), and keeping them if requested.
+    pub(crate) fn new(dir: Option<&str>, keep: bool) -> Result<Self> {
+        let dir = match dir {
+            Some(d) => {
+                let path = PathBuf::from(d);
+                if !path.is_dir() {
+                    return Err(anyhow!(r#"the temporary directory "{}" does not exist"#, d));
+                }
+                path
+            }
+            None => std::env::temp_dir(),
+        };
+        Ok(TempFiles { dir, keep })
+    }
+
+    /// Creates a new empty temporary file, whose name ends with the provided suffix.
+    ///
+    /// The name of the file is unique; the file is opened for writing.
+    pub(crate) fn create(&self, suffix: &str) -> Result<(TempFile, File)> {
+        loop {
+            let path = self.dir.join(format!(
+                "idw-{}-{}-{}",
+                std::process::id(),
+                NEXT_TEMP_FILE_ID.fetch_add(1, Ordering::Relaxed),
+                suffix
+            ));
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => {
+                    return Ok((
+                        TempFile {
+                            path,
+                            keep: self.keep,
+                        },
+                        file,
+                    ))
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => {
+                    return Err(e).with_context(|| {
+                        format!(
+                            r#"while creating a temporary file in "{}""#,
+                            self.dir.display()
+                        )
+                    })
+                }
+            }
+        }
+    }
+}
+
+/// A temporary file, removed when this object is dropped unless the files must be kept.
+#[derive(Debug)]
+pub(crate) struct TempFile {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl TempFile {
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if self.keep {
+            info!("kept temporary file {}", self.path.display());
+        } else {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_unique_names_and_cleanup() {
+        let temp_files = TempFiles::default();
+        let (first, mut file) = temp_files.create("instance.apx").unwrap();
+        writeln!(file, "arg(a).").unwrap();
+        let (second, _) = temp_files.create("instance.apx").unwrap();
+        assert_ne!(first.path(), second.path());
+        assert!(first.path().to_string_lossy().ends_with("instance.apx"));
+        let first_path = first.path().to_path_buf();
+        drop(first);
+        assert!(!first_path.exists());
+        assert!(second.path().exists());
+    }
+
+    #[test]
+    fn test_keep() {
+        let temp_files =
+            TempFiles::new(Some(&std::env::temp_dir().to_string_lossy()), true).unwrap();
+        let (kept, _) = temp_files.create("kept.apx").unwrap();
+        let path = kept.path().to_path_buf();
+        drop(kept);
+        assert!(path.exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_cleanup_on_panic() {
+        let temp_files = TempFiles::default();
+        let path = std::panic::catch_unwind(|| {
+            let (file, _) = temp_files.create("panic.apx").unwrap();
+            let path = file.path().to_path_buf();
+            if path.exists() {
+                panic!("{}", path.display());
+            }
+            path
+        })
+        .unwrap_err();
+        let path = PathBuf::from(path.downcast_ref::<String>().unwrap());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_missing_dir() {
+        assert!(TempFiles::new(Some("/does/not/exist"), false).is_err());
+    }
+}
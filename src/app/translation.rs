@@ -0,0 +1,418 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io::{BufRead, BufWriter},
+    path::Path,
+    rc::Rc,
+};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_arg::{
+    compression,
+    dynamics::{self, Modification},
+    solutions::{self, ExtensionSetWriter, SolutionReader},
+    AAFramework, ArgumentSet, AspartixReader, NumericWriter,
+};
+use iccma_dynamics::runner::{AnswerReadingFunction, QueryType};
+
+use super::temp_files::{TempFile, TempFiles};
+
+/// The name of the numeric format, as given on the command line.
+pub(crate) const NUMERIC_FORMAT: &str = "numeric";
+
+/// The dialects in which the modifications of a dynamics may be written.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ModificationDialect {
+    /// Arguments are given by their Aspartix labels.
+    Aspartix,
+    /// Arguments are given by their numeric identifiers.
+    Numeric,
+}
+
+impl ModificationDialect {
+    /// Returns the dialect of the modifications of an instance given in this format, if it is known.
+    pub(crate) fn of_instance_format(format: &str) -> Option<Self> {
+        match format {
+            "apx" => Some(ModificationDialect::Aspartix),
+            NUMERIC_FORMAT => Some(ModificationDialect::Numeric),
+            _ => None,
+        }
+    }
+
+    /// Returns the dialect of a modification line, if it is a modification in one of them.
+    pub(crate) fn of_modification(line: &str) -> Option<Self> {
+        if read_numeric_modification(line).is_ok() {
+            Some(ModificationDialect::Numeric)
+        } else if dynamics::read_modification(line).is_ok() {
+            Some(ModificationDialect::Aspartix)
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for ModificationDialect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModificationDialect::Aspartix => write!(f, "Aspartix"),
+            ModificationDialect::Numeric => write!(f, "numeric"),
+        }
+    }
+}
+
+/// A bidirectional mapping between the Aspartix labels of the arguments and their numeric identifiers.
+///
+/// Identifiers begin at 1 and follow the order of the arguments in the initial framework.
+/// Arguments added by the dynamics receive the next free identifiers.
+pub(crate) struct LabelMap {
+    labels: Vec<String>,
+    ids: HashMap<String, usize>,
+}
+
+impl LabelMap {
+    pub(crate) fn new(framework: &AAFramework<String>) -> Self {
+        let mut map = LabelMap {
+            labels: Vec::with_capacity(framework.argument_set().len()),
+            ids: HashMap::with_capacity(framework.argument_set().len()),
+        };
+        framework
+            .argument_set()
+            .iter()
+            .for_each(|a| map.add_label(a.label()));
+        map
+    }
+
+    fn add_label(&mut self, label: &str) {
+        if !self.ids.contains_key(label) {
+            self.labels.push(label.to_string());
+            self.ids.insert(label.to_string(), self.labels.len());
+        }
+    }
+
+    pub(crate) fn id_of(&self, label: &str) -> Result<usize> {
+        self.ids
+            .get(label)
+            .copied()
+            .ok_or_else(|| anyhow!("no such argument: {}", label))
+    }
+
+    pub(crate) fn label_of(&self, id: usize) -> Result<&str> {
+        if id == 0 || id > self.labels.len() {
+            return Err(anyhow!("no such argument identifier: {}", id));
+        }
+        Ok(&self.labels[id - 1])
+    }
+
+    /// Translates a modification line from the Aspartix dialect to the numeric one.
+    ///
+    /// New arguments are given fresh identifiers.
+    pub(crate) fn translate_modification(&mut self, line: &str) -> Result<String> {
+        self.map_modification(line, |i| i.to_string())
+    }
+
+    /// Rewrites a modification line, replacing each label by a new label computed from its identifier.
+    ///
+    /// New arguments are given fresh identifiers.
+    pub(crate) fn map_modification<F>(&mut self, line: &str, f: F) -> Result<String>
+    where
+        F: Fn(usize) -> String,
+    {
+        let modification = dynamics::read_modification(line)?;
+        if let Modification::AddArgument(a) = &modification {
+            self.add_label(a);
+        }
+        Ok(modification.try_map(|l| self.id_of(l).map(&f))?.to_string())
+    }
+
+    /// Translates a modification line from the numeric dialect to the Aspartix one.
+    ///
+    /// An added argument must either be already known or have the next free identifier, in which case it is given a fresh label.
+    pub(crate) fn restore_modification(&mut self, line: &str) -> Result<String> {
+        let modification = read_numeric_modification(line)?;
+        if let Modification::AddArgument(id) = &modification {
+            if *id == self.labels.len() + 1 {
+                let mut label = format!("a{}", id);
+                while self.ids.contains_key(&label) {
+                    label.push('_');
+                }
+                self.add_label(&label);
+            }
+        }
+        Ok(modification
+            .try_map(|i| self.label_of(*i).map(|l| l.to_string()))?
+            .to_string())
+    }
+
+    /// Returns the labels, the label at index `i` having the identifier `i+1`.
+    pub(crate) fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Maps back an extension given by numeric identifiers to the original labels.
+    pub(crate) fn project_extension(
+        &self,
+        extension: &ArgumentSet<usize>,
+    ) -> Result<ArgumentSet<String>> {
+        Ok(ArgumentSet::new(
+            extension
+                .iter()
+                .map(|a| self.label_of(*a.label()).map(|l| l.to_string()))
+                .collect::<Result<Vec<String>>>()?,
+        ))
+    }
+}
+
+/// Handles the translation of an Aspartix dynamic instance into the numeric format.
+///
+/// The translated instance is written to a temporary file which is removed when this object is dropped.
+pub(crate) struct NumericTranslation {
+    label_map: Rc<RefCell<LabelMap>>,
+    instance: TempFile,
+}
+
+impl NumericTranslation {
+    pub(crate) fn new(
+        input_file: &str,
+        input_format: &str,
+        temp_files: &TempFiles,
+    ) -> Result<Self> {
+        if input_format != "apx" {
+            return Err(anyhow!(
+                r#"cannot translate an instance from format "{}" to the numeric format; only "apx" is supported"#,
+                input_format
+            ));
+        }
+        let framework = AspartixReader::default()
+            .read(&mut compression::open(input_file).context("while opening input file")?)
+            .context("while reading input file")?;
+        let file_name = Path::new(input_file)
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let (instance, file) = temp_files
+            .create(&format!("{}.{}", file_name, NUMERIC_FORMAT))
+            .context("while creating the translated instance")?;
+        let mut writer = BufWriter::new(file);
+        NumericWriter::default()
+            .write(&framework, &mut writer)
+            .context("while writing the translated instance")?;
+        Ok(NumericTranslation {
+            label_map: Rc::new(RefCell::new(LabelMap::new(&framework))),
+            instance,
+        })
+    }
+
+    pub(crate) fn instance_path(&self) -> &Path {
+        self.instance.path()
+    }
+
+    pub(crate) fn translate_query(&self, query: &QueryType) -> Result<QueryType> {
+        let translate = |a: &str| self.label_map.borrow().id_of(a).map(|i| i.to_string());
+        Ok(match query {
+            QueryType::DC(a) => QueryType::DC(translate(a)?),
+            QueryType::DS(a) => QueryType::DS(translate(a)?),
+            q => q.clone(),
+        })
+    }
+
+    pub(crate) fn modification_translator(&self) -> impl Fn(String) -> Result<String> {
+        let label_map = Rc::clone(&self.label_map);
+        move |l| label_map.borrow_mut().translate_modification(&l)
+    }
+
+    pub(crate) fn answer_reading_function(
+        &self,
+        query: &QueryType,
+        solution_reader: SolutionReader,
+    ) -> AnswerReadingFunction {
+        let label_map = Rc::clone(&self.label_map);
+        match query {
+            QueryType::SE => Box::new(move |reader: &mut dyn BufRead| {
+                let extension = solution_reader
+                    .read_numeric_extension(reader)
+                    .context("while reading child process stdout")?;
+                let projected = label_map.borrow().project_extension(&extension)?;
+                write_to_string(|w| solutions::write_extension(w, &projected))
+            }),
+            QueryType::EE => Box::new(move |reader: &mut dyn BufRead| {
+                let extensions = solution_reader
+                    .read_numeric_extension_set(reader)
+                    .context("while reading child process stdout")?;
+                let label_map = label_map.borrow();
+                write_to_string(|w| {
+                    let mut set_writer = ExtensionSetWriter::new(w);
+                    set_writer.begin()?;
+                    for e in extensions.iter() {
+                        set_writer.write_extension(&label_map.project_extension(e)?)?;
+                    }
+                    set_writer.end()
+                })
+            }),
+            _ => query.answer_reading_function_with(solution_reader),
+        }
+    }
+}
+
+fn read_numeric_modification(line: &str) -> Result<Modification<usize>> {
+    let wrong_modification = || {
+        anyhow!(
+            r#"expected a numeric modification, found "{}""#,
+            line.trim()
+        )
+    };
+    let trimmed = line.trim();
+    let (sign, rest) = match trimmed.chars().next() {
+        Some(c @ '+') | Some(c @ '-') => (c, trimmed[1..].trim_start()),
+        _ => return Err(wrong_modification()),
+    };
+    let (kind, rest) = rest.split_at(rest.find('(').ok_or_else(wrong_modification)?);
+    let ids = rest
+        .strip_prefix('(')
+        .and_then(|r| r.strip_suffix(")."))
+        .ok_or_else(wrong_modification)?
+        .split(',')
+        .map(|id| id.trim().parse::<usize>().map_err(|_| wrong_modification()))
+        .collect::<Result<Vec<usize>>>()?;
+    match (sign, kind, ids.as_slice()) {
+        ('+', "arg", [a]) => Ok(Modification::AddArgument(*a)),
+        ('-', "arg", [a]) => Ok(Modification::RemoveArgument(*a)),
+        ('+', "att", [a, b]) => Ok(Modification::AddAttack(*a, *b)),
+        ('-', "att", [a, b]) => Ok(Modification::RemoveAttack(*a, *b)),
+        _ => Err(wrong_modification()),
+    }
+}
+
+fn write_to_string<F>(writing_fn: F) -> Result<String>
+where
+    F: FnOnce(&mut dyn std::io::Write) -> Result<()>,
+{
+    let mut out = Vec::new();
+    writing_fn(&mut out)?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label_map() -> LabelMap {
+        let framework = AspartixReader::default()
+            .read(&mut "arg(a).\narg(b).\natt(a,b).\n".as_bytes())
+            .unwrap();
+        LabelMap::new(&framework)
+    }
+
+    #[test]
+    fn test_ids_and_labels() {
+        let map = label_map();
+        assert_eq!(1, map.id_of("a").unwrap());
+        assert_eq!(2, map.id_of("b").unwrap());
+        assert!(map.id_of("c").is_err());
+        assert_eq!("a", map.label_of(1).unwrap());
+        assert!(map.label_of(0).is_err());
+        assert!(map.label_of(3).is_err());
+    }
+
+    #[test]
+    fn test_translate_modification() {
+        let mut map = label_map();
+        assert_eq!(
+            "-att(1,2).",
+            map.translate_modification("-att(a,b).").unwrap()
+        );
+        assert_eq!("+arg(3).", map.translate_modification("+arg(c).").unwrap());
+        assert_eq!(
+            "+att(3,1).",
+            map.translate_modification("+att(c,a).").unwrap()
+        );
+        assert_eq!("c", map.label_of(3).unwrap());
+        assert!(map.translate_modification("+att(a,d).").is_err());
+    }
+
+    #[test]
+    fn test_restore_modification() {
+        let mut map = label_map();
+        assert_eq!(
+            "-att(a,b).",
+            map.restore_modification("-att(1, 2).").unwrap()
+        );
+        assert_eq!("+arg(a3).", map.restore_modification("+ arg(3).").unwrap());
+        assert_eq!(
+            "+att(a3,a).",
+            map.restore_modification("+att(3,1).").unwrap()
+        );
+        assert_eq!("+arg(b).", map.restore_modification("+arg(2).").unwrap());
+        assert_eq!(3, map.labels().len());
+        assert!(map.restore_modification("+arg(5).").is_err());
+        assert!(map.restore_modification("+att(a,b).").is_err());
+        assert!(map.restore_modification("+att(1).").is_err());
+        assert!(map.restore_modification("*arg(1).").is_err());
+    }
+
+    #[test]
+    fn test_restore_modification_fresh_label_collision() {
+        let framework = AspartixReader::default()
+            .read(&mut "arg(a2).\n".as_bytes())
+            .unwrap();
+        let mut map = LabelMap::new(&framework);
+        assert_eq!("+arg(a2_).", map.restore_modification("+arg(2).").unwrap());
+        assert_eq!("-arg(a2).", map.restore_modification("-arg(1).").unwrap());
+    }
+
+    #[test]
+    fn test_modification_dialect() {
+        assert_eq!(
+            Some(ModificationDialect::Aspartix),
+            ModificationDialect::of_instance_format("apx")
+        );
+        assert_eq!(
+            Some(ModificationDialect::Numeric),
+            ModificationDialect::of_instance_format(NUMERIC_FORMAT)
+        );
+        assert_eq!(None, ModificationDialect::of_instance_format("tgf"));
+        assert_eq!(
+            Some(ModificationDialect::Numeric),
+            ModificationDialect::of_modification("+att(1,2).")
+        );
+        assert_eq!(
+            Some(ModificationDialect::Aspartix),
+            ModificationDialect::of_modification("+att(a,\"2\").")
+        );
+        assert_eq!(None, ModificationDialect::of_modification("+att(1,2)"));
+    }
+
+    #[test]
+    fn test_project_extension() {
+        let map = label_map();
+        let projected = map
+            .project_extension(&ArgumentSet::new(vec![2, 1]))
+            .unwrap();
+        assert_eq!(
+            vec!["b".to_string(), "a".to_string()],
+            projected
+                .iter()
+                .map(|a| a.label().to_string())
+                .collect::<Vec<String>>()
+        );
+        assert!(map.project_extension(&ArgumentSet::new(vec![3])).is_err());
+    }
+}
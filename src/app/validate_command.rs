@@ -0,0 +1,379 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    collections::BTreeSet,
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::{
+    info, AppSettings, Arg, Command, ExitStatusError, SubCommand, WithExitStatus,
+};
+use crusti_arg::{solutions, ArgumentSet};
+
+use super::exit_status::{MALFORMED_INPUT, WRONG_ANSWER};
+
+pub(crate) struct ValidateCommand;
+
+const CMD_NAME: &str = "validate";
+
+const ARG_PROBLEM: &str = "PROBLEM";
+const ARG_ANSWER_FILE: &str = "ANSWER_FILE";
+const ARG_REFERENCE_FILE: &str = "REFERENCE_FILE";
+
+impl ValidateCommand {
+    pub fn new() -> Self {
+        ValidateCommand
+    }
+}
+
+/// The kinds of answers the validator is able to compare.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ValidatedQuery {
+    SE,
+    EE,
+    CE,
+    Acceptance,
+}
+
+impl ValidatedQuery {
+    pub(crate) fn try_from_str(problem: &str) -> Result<Self> {
+        match problem.split('-').next().unwrap_or_default() {
+            "SE" => Ok(ValidatedQuery::SE),
+            "EE" => Ok(ValidatedQuery::EE),
+            "CE" => Ok(ValidatedQuery::CE),
+            "DC" | "DS" => Ok(ValidatedQuery::Acceptance),
+            _ => Err(anyhow!(
+                r#""{}" is not a problem the validator can handle"#,
+                problem
+            )),
+        }
+    }
+}
+
+type Extension = BTreeSet<String>;
+
+/// The answer given at a step of the dynamics, in a form allowing semantic comparisons.
+enum StepAnswer {
+    Extension(ArgumentSet<String>),
+    ExtensionSet(Vec<ArgumentSet<String>>),
+    Count(String),
+    Status(bool),
+}
+
+fn to_extension(extension: &ArgumentSet<String>) -> Extension {
+    extension.iter().map(|a| a.label().to_string()).collect()
+}
+
+fn format_extension(extension: &Extension) -> String {
+    format!(
+        "[{}]",
+        extension
+            .iter()
+            .cloned()
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+
+/// Reads the answer of a step.
+///
+/// The reference answers of SE problems are expected to be sets of extensions.
+/// An SE answer `NO`, given when there is no extension, is read as an empty set of extensions.
+fn read_step_answer(
+    query: ValidatedQuery,
+    reader: &mut dyn BufRead,
+    is_reference: bool,
+) -> Result<StepAnswer> {
+    Ok(match query {
+        ValidatedQuery::SE if !is_reference && reader.fill_buf()?.starts_with(b"NO") => {
+            solutions::read_acceptance_status(reader)?;
+            StepAnswer::ExtensionSet(vec![])
+        }
+        ValidatedQuery::SE if !is_reference => {
+            StepAnswer::Extension(solutions::read_extension(reader)?)
+        }
+        ValidatedQuery::SE | ValidatedQuery::EE => {
+            StepAnswer::ExtensionSet(solutions::read_extension_set(reader)?)
+        }
+        ValidatedQuery::CE => StepAnswer::Count(solutions::read_big_extension_count(reader)?),
+        ValidatedQuery::Acceptance => {
+            StepAnswer::Status(solutions::read_acceptance_status(reader)?)
+        }
+    })
+}
+
+/// Compares an answer to the reference one, returning a description of the first difference if they diverge.
+fn compare_answers(answer: &StepAnswer, reference: &StepAnswer) -> Option<String> {
+    match (answer, reference) {
+        (StepAnswer::Extension(e), StepAnswer::ExtensionSet(r)) => {
+            if solutions::extension_set_difference(std::slice::from_ref(e), r).is_empty() {
+                None
+            } else {
+                Some(format!(
+                    "extension {} is not in the reference set",
+                    format_extension(&to_extension(e))
+                ))
+            }
+        }
+        (StepAnswer::ExtensionSet(a), StepAnswer::ExtensionSet(r)) => {
+            let sorted_difference = |first, second| {
+                let mut difference = solutions::extension_set_difference(first, second)
+                    .into_iter()
+                    .map(to_extension)
+                    .collect::<Vec<Extension>>();
+                difference.sort();
+                difference
+            };
+            let extra = sorted_difference(a, r);
+            let missing = sorted_difference(r, a);
+            if let Some(e) = extra.first() {
+                Some(format!(
+                    "extension {} is not in the reference set",
+                    format_extension(e)
+                ))
+            } else {
+                missing.first().map(|e| {
+                    format!(
+                        "reference extension {} is missing from the answer",
+                        format_extension(e)
+                    )
+                })
+            }
+        }
+        (StepAnswer::Count(a), StepAnswer::Count(r)) if a != r => Some(format!(
+            "the answer counts {} extension(s) while the reference counts {}",
+            a, r
+        )),
+        (StepAnswer::Status(a), StepAnswer::Status(r)) if a != r => Some(format!(
+            "the answer is {} while the reference is {}",
+            if *a { "YES" } else { "NO" },
+            if *r { "YES" } else { "NO" }
+        )),
+        _ => None,
+    }
+}
+
+/// Skips the blank lines of a reader, returning `true` if its end is reached.
+fn skip_blank_lines(reader: &mut dyn BufRead) -> Result<bool> {
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            return Ok(true);
+        }
+        match buffer.iter().position(|b| !b.is_ascii_whitespace()) {
+            Some(i) => {
+                reader.consume(i);
+                return Ok(false);
+            }
+            None => {
+                let n = buffer.len();
+                reader.consume(n);
+            }
+        }
+    }
+}
+
+fn divergence(step: usize, description: &str) -> anyhow::Error {
+    anyhow::Error::new(ExitStatusError::new(
+        WRONG_ANSWER,
+        format!("step {}: {}", step, description),
+    ))
+}
+
+/// Compares the answers to the reference ones step by step, returning the number of steps.
+///
+/// An error describing the first divergence is returned if the answers do not match.
+pub(crate) fn validate(
+    query: ValidatedQuery,
+    answers: &mut dyn BufRead,
+    reference: &mut dyn BufRead,
+) -> Result<usize> {
+    let mut step = 0;
+    loop {
+        let answers_end = skip_blank_lines(answers).context("while reading the answers")?;
+        let reference_end = skip_blank_lines(reference)
+            .context("while reading the reference answers")
+            .with_exit_status(MALFORMED_INPUT)?;
+        match (answers_end, reference_end) {
+            (true, true) => return Ok(step),
+            (true, false) => {
+                return Err(divergence(
+                    step,
+                    "the answers end while the reference goes on",
+                ))
+            }
+            (false, true) => {
+                return Err(divergence(
+                    step,
+                    "the answers go on while the reference ends",
+                ))
+            }
+            (false, false) => {}
+        }
+        let answer = read_step_answer(query, answers, false)
+            .with_context(|| format!("while reading the answer for step {}", step))
+            .with_exit_status(WRONG_ANSWER)?;
+        let reference_answer = read_step_answer(query, reference, true)
+            .with_context(|| format!("while reading the reference answer for step {}", step))
+            .with_exit_status(MALFORMED_INPUT)?;
+        if let Some(d) = compare_answers(&answer, &reference_answer) {
+            return Err(divergence(step, &d));
+        }
+        step += 1;
+    }
+}
+
+impl<'a> Command<'a> for ValidateCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about("compares the answers of a solver along the dynamics to reference answers")
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_PROBLEM)
+                    .long("problem")
+                    .short("p")
+                    .takes_value(true)
+                    .help("sets the problem of the answers")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_ANSWER_FILE)
+                    .long("answer")
+                    .short("r")
+                    .takes_value(true)
+                    .help("sets the file containing the solver answers")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_REFERENCE_FILE)
+                    .long("reference")
+                    .short("e")
+                    .takes_value(true)
+                    .help("sets the file containing the reference answers (extension sets for SE problems)")
+                    .required(true),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let query = ValidatedQuery::try_from_str(arg_matches.value_of(ARG_PROBLEM).unwrap())?;
+        let mut answers = BufReader::new(
+            File::open(arg_matches.value_of(ARG_ANSWER_FILE).unwrap())
+                .context("while opening answer file")?,
+        );
+        let mut reference = BufReader::new(
+            File::open(arg_matches.value_of(ARG_REFERENCE_FILE).unwrap())
+                .context("while opening reference file")
+                .with_exit_status(MALFORMED_INPUT)?,
+        );
+        let n_steps = validate(query, &mut answers, &mut reference)?;
+        info!("the answers of the {} step(s) match the reference", n_steps);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(problem: &str, answers: &str, reference: &str) -> Result<usize> {
+        validate(
+            ValidatedQuery::try_from_str(problem).unwrap(),
+            &mut answers.as_bytes(),
+            &mut reference.as_bytes(),
+        )
+    }
+
+    #[test]
+    fn test_validate_ee_order_insensitive() {
+        let answers = "[\n[b, a]\n[c]\n]\n[]\n";
+        let reference = "[\n[c]\n[a,b]\n]\n[\n]\n";
+        assert_eq!(2, run("EE-PR-D", answers, reference).unwrap());
+    }
+
+    #[test]
+    fn test_validate_ee_divergence() {
+        let answers = "[\n[a]\n]\n[\n[a]\n]\n";
+        let reference = "[\n[a]\n]\n[\n[a]\n[b]\n]\n";
+        let err = run("EE-PR-D", answers, reference).unwrap_err().to_string();
+        assert_eq!(
+            "step 1: reference extension [b] is missing from the answer",
+            err
+        );
+    }
+
+    #[test]
+    fn test_validate_se() {
+        let reference = "[\n[a]\n[b]\n]\n[\n[c]\n]\n";
+        assert_eq!(2, run("SE-PR-D", "[b]\n[c]\n", reference).unwrap());
+        assert_eq!(
+            "step 1: extension [a] is not in the reference set",
+            run("SE-PR-D", "[b]\n[a]\n", reference)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_validate_se_no_extension() {
+        assert_eq!(2, run("SE-ST-D", "NO\n[a]\n", "[\n]\n[\n[a]\n]\n").unwrap());
+        assert_eq!(
+            "step 0: reference extension [a] is missing from the answer",
+            run("SE-ST-D", "NO\n", "[\n[a]\n]\n")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_validate_acceptance() {
+        assert_eq!(
+            3,
+            run("DC-CO-D", "YES\nNO\nYES\n", "YES\nNO\nYES\n").unwrap()
+        );
+        assert_eq!(
+            "step 2: the answer is NO while the reference is YES",
+            run("DS-CO-D", "YES\nNO\nNO\n", "YES\nNO\nYES\n")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_validate_different_lengths() {
+        assert!(run("DC-CO-D", "YES\n", "YES\nNO\n").is_err());
+        assert!(run("CE-CO-D", "1\n2\n", "1\n").is_err());
+        assert!(run("CE-CO-D", "1\n2\n", "1\n3\n").is_err());
+    }
+
+    #[test]
+    fn test_validated_query_from_str() {
+        assert_eq!(
+            ValidatedQuery::Acceptance,
+            ValidatedQuery::try_from_str("DS-ST").unwrap()
+        );
+        assert!(ValidatedQuery::try_from_str("XX-CO-D").is_err());
+    }
+}
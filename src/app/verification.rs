@@ -0,0 +1,299 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::{warn, WithExitStatus};
+use crusti_arg::{
+    solutions,
+    solvers::{NaiveSolver, SMALL_FRAMEWORK_SIZE},
+    AAFramework, Semantics,
+};
+
+use iccma_dynamics::runner::{QueryType, FAILED_ANSWER};
+
+use super::{
+    check_command::CheckedFramework, exit_status::MALFORMED_INPUT, framework_state::FrameworkState,
+};
+
+/// Checks the answers relayed at each step against the tracked state of the framework.
+///
+/// SE answers are checked by the extension checker.
+/// DC and DS answers are checked against the grounded extension, which decides them for the grounded semantics and for skeptical acceptance under the complete semantics.
+/// For the other semantics containing the grounded extension, only the arguments in the grounded extension or attacked by it are checked.
+/// Stage answers are checked against the stage extensions computed by the naive solver, for frameworks of at most `SMALL_FRAMEWORK_SIZE` arguments.
+/// Unknown answers and the placeholders of the answers which could not be read are not checked.
+pub(crate) struct AnswerVerifier {
+    state: FrameworkState,
+    semantics: Semantics,
+    query: QueryType,
+    step: usize,
+    n_wrong_answers: usize,
+}
+
+impl AnswerVerifier {
+    pub(crate) fn new(state: FrameworkState, problem: &str, query: &QueryType) -> Result<Self> {
+        if let QueryType::EE | QueryType::CE = query {
+            return Err(anyhow!(
+                "answer verification is only available for SE, DC and DS problems"
+            ));
+        }
+        let semantics = problem
+            .rsplit('-')
+            .nth(1)
+            .unwrap_or_default()
+            .parse::<Semantics>()
+            .context("while setting up answer verification")?;
+        Ok(AnswerVerifier {
+            state,
+            semantics,
+            query: query.clone(),
+            step: 0,
+            n_wrong_answers: 0,
+        })
+    }
+
+    /// Verifies the answer of the current step, warning if it is wrong, and applies the modification leading to the next step if there is one.
+    pub(crate) fn verify(&mut self, answer: &str, modification: Option<&str>) -> Result<()> {
+        let framework = self.state.to_framework()?;
+        if let Some(reason) = self.wrong_answer_reason(&framework, answer)? {
+            warn!("wrong answer at step {}: {}", self.step, reason);
+            self.n_wrong_answers += 1;
+        }
+        self.skip(modification)
+    }
+
+    /// Goes to the next step without checking the answer of the current one, applying the modification leading to the next step if there is one.
+    ///
+    /// The modification may be made of several lines, as the steps of the batched dynamics.
+    pub(crate) fn skip(&mut self, modification: Option<&str>) -> Result<()> {
+        for m in modification.iter().flat_map(|m| m.lines()) {
+            self.state
+                .apply(m)
+                .with_context(|| {
+                    format!(
+                        r#"while applying modification "{}" to the tracked framework"#,
+                        m
+                    )
+                })
+                .with_exit_status(MALFORMED_INPUT)?;
+        }
+        self.step += 1;
+        Ok(())
+    }
+
+    /// Sets the number of the first verified step, for the runs which do not begin at the initial framework.
+    pub(crate) fn set_first_step(&mut self, step: usize) {
+        self.step = step;
+    }
+
+    pub(crate) fn n_wrong_answers(&self) -> usize {
+        self.n_wrong_answers
+    }
+
+    fn wrong_answer_reason(
+        &self,
+        framework: &AAFramework<String>,
+        answer: &str,
+    ) -> Result<Option<String>> {
+        if answer == FAILED_ANSWER {
+            return Ok(None);
+        }
+        let checked_framework = CheckedFramework::new(framework);
+        let argument = match &self.query {
+            QueryType::SE => {
+                let extension = solutions::read_extension(&mut answer.as_bytes())?;
+                return Ok(match checked_framework.membership(&extension) {
+                    Ok(in_ext) => checked_framework
+                        .check(self.semantics, &in_ext)
+                        .err()
+                        .map(|e| format!("the extension is {}", e))
+                        .or_else(|| match self.stage_extensions(framework) {
+                            Some(stage) if !stage.contains(&in_ext) => Some(
+                                "the extension is not a stage extension (its range is not maximal)"
+                                    .to_string(),
+                            ),
+                            _ => None,
+                        }),
+                    Err(e) => Some(format!("{}", e)),
+                });
+            }
+            QueryType::DC(a) | QueryType::DS(a) => a,
+            QueryType::EE | QueryType::CE => return Ok(None),
+        };
+        let status = match solutions::read_acceptance_status_or_unknown(&mut answer.as_bytes())? {
+            Some(s) => s,
+            None => return Ok(None),
+        };
+        let id = match framework.argument_set().get_argument_index(argument) {
+            Ok(id) => id,
+            Err(_) => return Ok(Some(format!("no such argument: {}", argument))),
+        };
+        let grounded = checked_framework.grounded();
+        let rejected = checked_framework.attacked_by(&grounded);
+        let decided_by_grounded = match (&self.query, self.semantics) {
+            (_, Semantics::Grounded) | (QueryType::DS(_), Semantics::Complete) => true,
+            (_, Semantics::Complete)
+            | (_, Semantics::Preferred)
+            | (_, Semantics::SemiStable)
+            | (_, Semantics::Ideal) => grounded[id] || rejected[id],
+            _ => false,
+        };
+        if decided_by_grounded && status != grounded[id] {
+            return Ok(Some(format!(
+                "{} is {} the grounded extension, but the answer is {}",
+                argument,
+                if grounded[id] {
+                    "in"
+                } else if rejected[id] {
+                    "attacked by"
+                } else {
+                    "not in"
+                },
+                if status { "YES" } else { "NO" }
+            )));
+        }
+        if let Some(stage) = self.stage_extensions(framework) {
+            let (expected, description) = match self.query {
+                QueryType::DS(_) => {
+                    let accepted = stage.iter().all(|e| e[id]);
+                    (accepted, if accepted { "in every" } else { "not in every" })
+                }
+                _ => {
+                    let accepted = stage.iter().any(|e| e[id]);
+                    (accepted, if accepted { "in a" } else { "in no" })
+                }
+            };
+            if status != expected {
+                return Ok(Some(format!(
+                    "{} is {} stage extension, but the answer is {}",
+                    argument,
+                    description,
+                    if status { "YES" } else { "NO" }
+                )));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Computes the stage extensions of small frameworks when verifying the answers of a stage problem.
+    fn stage_extensions(&self, framework: &AAFramework<String>) -> Option<Vec<Vec<bool>>> {
+        if self.semantics != Semantics::Stage
+            || framework.argument_set().len() > SMALL_FRAMEWORK_SIZE
+        {
+            return None;
+        }
+        NaiveSolver::new(framework)
+            .ok()
+            .map(|solver| solver.extensions(Semantics::Stage))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crusti_arg::AspartixReader;
+
+    fn verifier(problem: &str, query: QueryType) -> AnswerVerifier {
+        let framework = AspartixReader::default()
+            .read(&mut "arg(a).\narg(b).\narg(c).\natt(a,b).\natt(b,c).\natt(c,b).\n".as_bytes())
+            .unwrap();
+        AnswerVerifier::new(FrameworkState::new(&framework), problem, &query).unwrap()
+    }
+
+    #[test]
+    fn test_verify_se() {
+        let mut v = verifier("SE-CO-D", QueryType::SE);
+        v.verify("[a, c]\n", None).unwrap();
+        assert_eq!(0, v.n_wrong_answers());
+        v.verify("[a]\n", None).unwrap();
+        assert_eq!(1, v.n_wrong_answers());
+        v.verify("[d]\n", None).unwrap();
+        assert_eq!(2, v.n_wrong_answers());
+    }
+
+    #[test]
+    fn test_verify_dc_with_modifications() {
+        let mut v = verifier("DC-PR-D", QueryType::DC("b".to_string()));
+        v.verify("NO\n", Some("-att(a,b).")).unwrap();
+        assert_eq!(0, v.n_wrong_answers());
+        v.verify("YES\n", Some("+att(a,b).")).unwrap();
+        assert_eq!(0, v.n_wrong_answers());
+        v.verify("YES\n", None).unwrap();
+        assert_eq!(1, v.n_wrong_answers());
+        v.verify("UNKNOWN\n", None).unwrap();
+        assert_eq!(1, v.n_wrong_answers());
+        v.verify(FAILED_ANSWER, None).unwrap();
+        assert_eq!(1, v.n_wrong_answers());
+    }
+
+    #[test]
+    fn test_verify_after_skip() {
+        let mut v = verifier("DC-PR-D", QueryType::DC("b".to_string()));
+        v.set_first_step(3);
+        v.skip(Some("+att(a,c).\n+arg(d).")).unwrap();
+        v.verify("YES\n", None).unwrap();
+        assert_eq!(1, v.n_wrong_answers());
+        assert_eq!(5, v.step);
+    }
+
+    #[test]
+    fn test_verify_ds_co() {
+        let mut v = verifier("DS-CO-D", QueryType::DS("c".to_string()));
+        v.verify("NO\n", Some("-att(b,c).")).unwrap();
+        assert_eq!(1, v.n_wrong_answers());
+        v.verify("YES\n", None).unwrap();
+        assert_eq!(1, v.n_wrong_answers());
+    }
+
+    #[test]
+    fn test_verify_stg() {
+        // the stage extensions are [a, c] and [b, d]
+        let framework = AspartixReader::default()
+            .read(&mut "arg(a).\narg(b).\narg(c).\narg(d).\narg(e).\natt(a,b).\natt(b,a).\natt(b,c).\natt(c,d).\natt(e,e).\natt(e,d).\n".as_bytes())
+            .unwrap();
+        let mut v =
+            AnswerVerifier::new(FrameworkState::new(&framework), "SE-STG-D", &QueryType::SE)
+                .unwrap();
+        v.verify("[b, d]\n", None).unwrap();
+        assert_eq!(0, v.n_wrong_answers());
+        v.verify("[a, d]\n", None).unwrap();
+        assert_eq!(1, v.n_wrong_answers());
+        let mut v = AnswerVerifier::new(
+            FrameworkState::new(&framework),
+            "DS-STG-D",
+            &QueryType::DS("d".to_string()),
+        )
+        .unwrap();
+        v.verify("YES\n", Some("-att(b,a).")).unwrap();
+        assert_eq!(1, v.n_wrong_answers());
+        v.verify("NO\n", None).unwrap();
+        assert_eq!(1, v.n_wrong_answers());
+    }
+
+    #[test]
+    fn test_verify_ee_unsupported() {
+        let framework = AspartixReader::default()
+            .read(&mut "arg(a).\n".as_bytes())
+            .unwrap();
+        assert!(
+            AnswerVerifier::new(FrameworkState::new(&framework), "EE-CO-D", &QueryType::EE)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,298 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::io::{BufReader, BufWriter, Write};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::{info, AppSettings, Arg, Command, SubCommand, WithExitStatus};
+use crusti_arg::{
+    compression, solutions, solvers, AAFramework, ArgumentSet, AspartixReader, DotWriter,
+    LabellingStatus, Semantics,
+};
+
+use super::exit_status::MALFORMED_INPUT;
+use super::framework_state::FrameworkState;
+use super::wrap_command::read_count;
+use super::wrap_command::read_modifications;
+
+pub(crate) struct VizCommand;
+
+const CMD_NAME: &str = "viz";
+
+const ARG_INPUT_FILE: &str = "INPUT_FILE";
+const ARG_MODIFICATION_FILE: &str = "MODIFICATION_FILE";
+const ARG_STEP: &str = "STEP";
+const ARG_SEMANTICS: &str = "SEMANTICS";
+const ARG_ANSWER_FILE: &str = "ANSWER_FILE";
+const ARG_OUTPUT_FILE: &str = "OUTPUT_FILE";
+
+impl VizCommand {
+    pub fn new() -> Self {
+        VizCommand
+    }
+}
+
+impl<'a> Command<'a> for VizCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about("writes a framework or a step of its dynamics in the DOT format, colored by a labelling")
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_INPUT_FILE)
+                    .long("input-file")
+                    .short("f")
+                    .takes_value(true)
+                    .help("sets the input file containing the framework (Aspartix format)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_MODIFICATION_FILE)
+                    .long("modification-file")
+                    .short("m")
+                    .takes_value(true)
+                    .help("sets the modification file containing the dynamics of the framework"),
+            )
+            .arg(
+                Arg::with_name(ARG_STEP)
+                    .long("step")
+                    .takes_value(true)
+                    .requires(ARG_MODIFICATION_FILE)
+                    .help("sets the step of the dynamics to draw, 0 being the initial framework (default: the last step)"),
+            )
+            .arg(
+                Arg::with_name(ARG_SEMANTICS)
+                    .long("semantics")
+                    .short("s")
+                    .takes_value(true)
+                    .help("colors the arguments by the labelling of an extension computed natively for this semantics (e.g. GR)"),
+            )
+            .arg(
+                Arg::with_name(ARG_ANSWER_FILE)
+                    .long("answer-file")
+                    .takes_value(true)
+                    .conflicts_with(ARG_SEMANTICS)
+                    .help("colors the arguments by the labelling of the extension given in this solver answer"),
+            )
+            .arg(
+                Arg::with_name(ARG_OUTPUT_FILE)
+                    .long("output-file")
+                    .short("o")
+                    .takes_value(true)
+                    .help("sets the file in which the DOT graph is written (default: standard output)"),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let framework = AspartixReader::default()
+            .read(
+                &mut compression::open(arg_matches.value_of(ARG_INPUT_FILE).unwrap())
+                    .context("while opening input file")
+                    .with_exit_status(MALFORMED_INPUT)?,
+            )
+            .context("while reading input file")
+            .with_exit_status(MALFORMED_INPUT)?;
+        let modifications = match arg_matches.value_of(ARG_MODIFICATION_FILE) {
+            Some(path) => read_modifications(path).with_exit_status(MALFORMED_INPUT)?,
+            None => vec![],
+        };
+        let step = match arg_matches.value_of(ARG_STEP) {
+            Some(_) => read_count(arg_matches, ARG_STEP)?,
+            None => modifications.len(),
+        };
+        let (previous, current) = frameworks_at_step(&framework, &modifications, step)?;
+        let labelling = if let Some(s) = arg_matches.value_of(ARG_SEMANTICS) {
+            Some(native_labelling(&current, s.parse::<Semantics>()?)?)
+        } else if let Some(path) = arg_matches.value_of(ARG_ANSWER_FILE) {
+            let extension = solutions::read_extension(&mut BufReader::new(
+                compression::open(path).context("while opening answer file")?,
+            ))
+            .context("while reading answer file")
+            .with_exit_status(MALFORMED_INPUT)?;
+            Some(answer_labelling(&current, &extension).with_exit_status(MALFORMED_INPUT)?)
+        } else {
+            None
+        };
+        let mut writer: Box<dyn Write> = match arg_matches.value_of(ARG_OUTPUT_FILE) {
+            Some(path) => Box::new(BufWriter::new(
+                compression::create(path)
+                    .with_context(|| format!(r#"while creating file "{}""#, path))?,
+            )),
+            None => Box::new(std::io::stdout()),
+        };
+        write_dot(
+            previous.as_ref(),
+            &current,
+            labelling.as_deref(),
+            &mut writer,
+        )?;
+        info!(
+            "wrote the framework at step {} ({})",
+            step,
+            current.summary()
+        );
+        Ok(())
+    }
+}
+
+/// Returns the framework at a step of a dynamics, and the one at the previous step if any.
+///
+/// Step 0 is the initial framework; step `n` is the framework obtained after applying the first `n` modifications.
+pub(crate) fn frameworks_at_step(
+    framework: &AAFramework<String>,
+    modifications: &[String],
+    step: usize,
+) -> Result<(Option<AAFramework<String>>, AAFramework<String>)> {
+    if step > modifications.len() {
+        return Err(anyhow!(
+            "cannot draw step {}, since the dynamics has {} step(s)",
+            step,
+            modifications.len()
+        ));
+    }
+    let mut state = FrameworkState::new(framework);
+    let mut previous = None;
+    for (i, m) in modifications.iter().take(step).enumerate() {
+        if i + 1 == step {
+            previous = Some(state.to_framework()?);
+        }
+        state
+            .apply(m)
+            .with_context(|| format!(r#"while applying modification "{}""#, m))
+            .with_exit_status(MALFORMED_INPUT)?;
+    }
+    Ok((previous, state.to_framework()?))
+}
+
+/// Computes the labelling induced by an extension of a framework, given by the native engines.
+pub(crate) fn native_labelling(
+    framework: &AAFramework<String>,
+    semantics: Semantics,
+) -> Result<Vec<LabellingStatus>> {
+    match solvers::default_solver(framework, semantics)?.one_extension(semantics)? {
+        Some(extension) => Ok(framework.labelling(&extension)),
+        None => Err(anyhow!("the framework has no {} extension", semantics)),
+    }
+}
+
+/// Computes the labelling induced by an extension read from a solver answer.
+fn answer_labelling(
+    framework: &AAFramework<String>,
+    extension: &ArgumentSet<String>,
+) -> Result<Vec<LabellingStatus>> {
+    let mut membership = vec![false; framework.argument_set().len()];
+    for arg in extension.iter() {
+        let id = framework
+            .argument_set()
+            .get_argument_index(arg.label())
+            .context("while reading the extension of the answer")?;
+        membership[id] = true;
+    }
+    Ok(framework.labelling(&membership))
+}
+
+/// Writes a framework in the DOT format, highlighting the changes since the previous step if any.
+pub(crate) fn write_dot(
+    previous: Option<&AAFramework<String>>,
+    framework: &AAFramework<String>,
+    labelling: Option<&[LabellingStatus]>,
+    writer: &mut dyn Write,
+) -> Result<()> {
+    let dot_writer = DotWriter::default();
+    match (previous, labelling) {
+        (Some(p), _) => dot_writer.write_step(p, framework, labelling, writer),
+        (None, Some(l)) => dot_writer.write_labelled(framework, l, writer),
+        (None, None) => dot_writer.write(framework, writer),
+    }
+    .context("while writing the DOT graph")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_af(s: &str) -> AAFramework<String> {
+        AspartixReader::default().read(&mut s.as_bytes()).unwrap()
+    }
+
+    fn modifications() -> Vec<String> {
+        vec!["+arg(c).".to_string(), "+att(c,a).".to_string()]
+    }
+
+    #[test]
+    fn test_frameworks_at_step() {
+        let framework = read_af("arg(a).\narg(b).\natt(a,b).\n");
+        let (previous, current) = frameworks_at_step(&framework, &modifications(), 0).unwrap();
+        assert!(previous.is_none());
+        assert_eq!(1, current.n_attacks());
+        let (previous, current) = frameworks_at_step(&framework, &modifications(), 2).unwrap();
+        assert_eq!(1, previous.unwrap().n_attacks());
+        assert_eq!(2, current.n_attacks());
+        assert!(frameworks_at_step(&framework, &modifications(), 3).is_err());
+    }
+
+    #[test]
+    fn test_write_step_with_grounded_labelling() {
+        let framework = read_af("arg(a).\narg(b).\natt(a,b).\n");
+        let (previous, current) = frameworks_at_step(&framework, &modifications(), 2).unwrap();
+        let labelling = native_labelling(&current, Semantics::Grounded).unwrap();
+        let mut out = Vec::new();
+        write_dot(previous.as_ref(), &current, Some(&labelling), &mut out).unwrap();
+        assert_eq!(
+            vec![
+                "digraph {",
+                r#"    "a" [style="filled", fillcolor=lightcoral];"#,
+                r#"    "b" [style="filled", fillcolor=palegreen];"#,
+                r#"    "c" [style="filled", fillcolor=palegreen];"#,
+                r#"    "a" -> "b";"#,
+                r#"    "c" -> "a" [style="bold", color=blue];"#,
+                "}",
+            ],
+            String::from_utf8(out)
+                .unwrap()
+                .lines()
+                .collect::<Vec<&str>>()
+        );
+    }
+
+    #[test]
+    fn test_answer_labelling() {
+        let framework = read_af("arg(a).\narg(b).\narg(c).\natt(a,b).\natt(b,c).\n");
+        let extension = solutions::read_extension(&mut "[c]".as_bytes()).unwrap();
+        assert_eq!(
+            vec![
+                LabellingStatus::Undec,
+                LabellingStatus::Undec,
+                LabellingStatus::In
+            ],
+            answer_labelling(&framework, &extension).unwrap()
+        );
+        let extension = solutions::read_extension(&mut "[d]".as_bytes()).unwrap();
+        assert!(answer_labelling(&framework, &extension).is_err());
+    }
+
+    #[test]
+    fn test_native_labelling_without_extension() {
+        let framework = read_af("arg(a).\natt(a,a).\n");
+        assert!(native_labelling(&framework, Semantics::Stable).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+use std::{
+    fs::File,
+    io::{BufWriter, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_app_helper::{info, warn, AppSettings, Arg, Command, SubCommand, WithExitStatus};
+use crusti_arg::{compression, AAFramework, AspartixReader, Semantics};
+
+use super::exit_status::MALFORMED_INPUT;
+use super::framework_state::FrameworkState;
+use super::viz_command::{native_labelling, write_dot};
+use super::wrap_command::read_modifications;
+
+pub(crate) struct VizDynamicsCommand;
+
+const CMD_NAME: &str = "viz-dynamics";
+
+const ARG_INPUT_FILE: &str = "INPUT_FILE";
+const ARG_MODIFICATION_FILE: &str = "MODIFICATION_FILE";
+const ARG_OUTPUT_DIR: &str = "OUTPUT_DIR";
+const ARG_SEMANTICS: &str = "SEMANTICS";
+const ARG_SVG: &str = "SVG";
+
+/// The program of Graphviz used to render the frames.
+const DOT_PROGRAM: &str = "dot";
+
+impl VizDynamicsCommand {
+    pub fn new() -> Self {
+        VizDynamicsCommand
+    }
+}
+
+impl<'a> Command<'a> for VizDynamicsCommand {
+    fn name(&self) -> &str {
+        CMD_NAME
+    }
+
+    fn clap_subcommand(&self) -> crusti_app_helper::App<'a, 'a> {
+        SubCommand::with_name(CMD_NAME)
+            .about("writes a DOT frame for each step of a dynamics, colored by a labelling")
+            .setting(AppSettings::DisableVersion)
+            .arg(
+                Arg::with_name(ARG_INPUT_FILE)
+                    .long("input-file")
+                    .short("f")
+                    .takes_value(true)
+                    .help("sets the input file containing the framework (Aspartix format)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_MODIFICATION_FILE)
+                    .long("modification-file")
+                    .short("m")
+                    .takes_value(true)
+                    .help("sets the modification file containing the dynamics of the framework")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_OUTPUT_DIR)
+                    .long("output-dir")
+                    .short("d")
+                    .takes_value(true)
+                    .help("sets the directory in which the frames are written (created if needed)")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name(ARG_SEMANTICS)
+                    .long("semantics")
+                    .short("s")
+                    .takes_value(true)
+                    .default_value("GR")
+                    .help("sets the semantics of the extension giving the labelling, computed natively"),
+            )
+            .arg(
+                Arg::with_name(ARG_SVG)
+                    .long("svg")
+                    .takes_value(false)
+                    .help("also renders each frame as a SVG file, if the dot program of Graphviz is available"),
+            )
+    }
+
+    fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
+        let framework = AspartixReader::default()
+            .read(
+                &mut compression::open(arg_matches.value_of(ARG_INPUT_FILE).unwrap())
+                    .context("while opening input file")
+                    .with_exit_status(MALFORMED_INPUT)?,
+            )
+            .context("while reading input file")
+            .with_exit_status(MALFORMED_INPUT)?;
+        let modifications =
+            read_modifications(arg_matches.value_of(ARG_MODIFICATION_FILE).unwrap())
+                .with_exit_status(MALFORMED_INPUT)?;
+        let semantics = arg_matches
+            .value_of(ARG_SEMANTICS)
+            .unwrap()
+            .parse::<Semantics>()?;
+        let dir = Path::new(arg_matches.value_of(ARG_OUTPUT_DIR).unwrap());
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!(r#"while creating directory "{}""#, dir.display()))?;
+        let frames = write_frames(&framework, &modifications, semantics, dir)?;
+        info!("wrote {} DOT frame(s) in {}", frames.len(), dir.display());
+        if arg_matches.is_present(ARG_SVG) {
+            let mut n_rendered = 0;
+            for frame in frames.iter() {
+                if !render_svg(frame)? {
+                    warn!(
+                        "the {} program was not found; no SVG frame was rendered",
+                        DOT_PROGRAM
+                    );
+                    break;
+                }
+                n_rendered += 1;
+            }
+            info!("rendered {} SVG frame(s)", n_rendered);
+        }
+        Ok(())
+    }
+}
+
+/// Returns the path of the frame of a step, padded so that the frames are sorted by their names.
+fn frame_path(dir: &Path, step: usize, n_steps: usize) -> PathBuf {
+    let width = n_steps.to_string().len();
+    dir.join(format!("frame-{:0width$}.dot", step, width = width))
+}
+
+/// Writes a DOT frame for each step of a dynamics, the first one being the initial framework, and returns their paths.
+///
+/// Each frame highlights the changes since the previous one, and colors the arguments by the labelling of an extension of the semantics.
+/// When a step has no extension, its frame is written without labelling.
+fn write_frames(
+    framework: &AAFramework<String>,
+    modifications: &[String],
+    semantics: Semantics,
+    dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let mut state = FrameworkState::new(framework);
+    let mut previous: Option<AAFramework<String>> = None;
+    let mut frames = Vec::with_capacity(modifications.len() + 1);
+    for step in 0..=modifications.len() {
+        let current = state.to_framework()?;
+        let labelling = match native_labelling(&current, semantics) {
+            Ok(l) => Some(l),
+            Err(e) => {
+                warn!("no labelling for step {}: {}", step, e);
+                None
+            }
+        };
+        let path = frame_path(dir, step, modifications.len());
+        let mut writer = BufWriter::new(
+            File::create(&path)
+                .with_context(|| format!(r#"while creating file "{}""#, path.display()))?,
+        );
+        write_dot(
+            previous.as_ref(),
+            &current,
+            labelling.as_deref(),
+            &mut writer,
+        )
+        .with_context(|| format!("while writing the frame of step {}", step))?;
+        frames.push(path);
+        if let Some(m) = modifications.get(step) {
+            state
+                .apply(m)
+                .with_context(|| format!(r#"while applying modification "{}""#, m))
+                .with_exit_status(MALFORMED_INPUT)?;
+        }
+        previous = Some(current);
+    }
+    Ok(frames)
+}
+
+/// Renders a DOT frame as a SVG file with the same name, returning `false` if the dot program is not available.
+fn render_svg(frame: &Path) -> Result<bool> {
+    let svg = frame.with_extension("svg");
+    let status = match std::process::Command::new(DOT_PROGRAM)
+        .arg("-Tsvg")
+        .arg("-o")
+        .arg(&svg)
+        .arg(frame)
+        .status()
+    {
+        Ok(s) => s,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+        Err(e) => {
+            return Err(e)
+                .with_context(|| format!("while running {} on {}", DOT_PROGRAM, frame.display()))
+        }
+    };
+    if !status.success() {
+        return Err(anyhow!(
+            "{} failed on {} ({})",
+            DOT_PROGRAM,
+            frame.display(),
+            status
+        ));
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_path() {
+        let dir = Path::new("frames");
+        assert_eq!(dir.join("frame-0.dot"), frame_path(dir, 0, 9));
+        assert_eq!(dir.join("frame-007.dot"), frame_path(dir, 7, 120));
+    }
+
+    #[test]
+    fn test_write_frames() {
+        let dir =
+            std::env::temp_dir().join(format!("idw-test-viz-dynamics-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let framework = AspartixReader::default()
+            .read(&mut "arg(a).\narg(b).\natt(a,b).\n".as_bytes())
+            .unwrap();
+        let modifications = vec!["+arg(c).".to_string(), "+att(c,a).".to_string()];
+        let frames = write_frames(&framework, &modifications, Semantics::Grounded, &dir).unwrap();
+        let contents = frames
+            .iter()
+            .map(|f| std::fs::read_to_string(f).unwrap())
+            .collect::<Vec<String>>();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(3, contents.len());
+        assert!(contents[0].contains(r#""b" [style="filled", fillcolor=lightcoral];"#));
+        assert!(
+            contents[1].contains(r#""c" [style="filled,bold", fillcolor=palegreen, color=blue];"#)
+        );
+        assert!(contents[2].contains(r#""c" -> "a" [style="bold", color=blue];"#));
+        assert!(contents[2].contains(r#""b" [style="filled", fillcolor=palegreen];"#));
+    }
+}
@@ -18,15 +18,45 @@
 //   *   CRIL - initial API and implementation
 
 use std::{
-    convert::TryFrom,
+    cell::RefCell,
     fs::File,
     io::BufRead,
-    io::{BufReader, Cursor, Read, Seek, SeekFrom, Write},
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+    time::Duration,
 };
 
 use anyhow::{anyhow, Context, Result};
-use crusti_app_helper::{AppSettings, Arg, Command, SubCommand};
-use crusti_arg::{solutions, ArgumentSet};
+use crusti_app_helper::{
+    info, warn, AppSettings, Arg, Command, ExitStatusError, SubCommand, WithExitStatus,
+};
+use crusti_arg::{
+    compression, dynamics,
+    solutions::{Conformance, LabelGrammar, SolutionReader},
+    warmstart, AspartixReader, AspartixWriter,
+};
+use iccma_dynamics::runner::{
+    self, check_end_of_output, execute_batched_dynamics, execute_dynamics, write_answer,
+    AnswerConsumer, Cancellation, QueryType, RunLimits, SolverProcess, Stop, TaskRegistry,
+    WrapError, FAILED_ANSWER,
+};
+
+use super::checkpoint::Checkpoint;
+use super::container::{Container, ContainerRuntime};
+use super::exit_status::{
+    classify_solver_error, FAILURE, INTERRUPTED, MALFORMED_INPUT, SOLVER_CRASH, TIMEOUT,
+    WRONG_ANSWER,
+};
+use super::framework_state::FrameworkState;
+use super::hooks::StepHook;
+use super::manifest::RunRecord;
+use super::portfolio::{self, PortfolioMode};
+use super::progress::{ProgressLogger, PROGRESS_INTERVAL};
+use super::remote::{self, Remote};
+use super::signals::{self, InterruptionHooks};
+use super::temp_files::{TempFile, TempFiles};
+use super::translation::{LabelMap, ModificationDialect, NumericTranslation, NUMERIC_FORMAT};
+use super::verification::AnswerVerifier;
 
 pub(crate) struct WrapCommand;
 
@@ -37,115 +67,66 @@ const ARG_PROBLEM: &str = "PROBLEM";
 const ARG_INPUT_FILE: &str = "INPUT_FILE";
 const ARG_INPUT_FORMAT: &str = "INPUT_FORMAT";
 const ARG_ARGUMENT: &str = "ARGUMENT";
+const ARG_ARGUMENT_FILE: &str = "ARGUMENT_FILE";
 const ARG_MODIFICATION_FILE: &str = "MODIFICATION_FILE";
+const ARG_SOLVER_FORMAT: &str = "SOLVER_FORMAT";
+const ARG_STATIC: &str = "STATIC";
+const ARG_TASK_FILE: &str = "TASK_FILE";
+const ARG_RESTART_ON_FAILURE: &str = "RESTART_ON_FAILURE";
+const ARG_TIMEOUT: &str = "TIMEOUT";
+const ARG_CHECKPOINT: &str = "CHECKPOINT";
+const ARG_CHECKPOINT_INTERVAL: &str = "CHECKPOINT_INTERVAL";
+const ARG_RESUME: &str = "RESUME";
+const ARG_WARMSTART: &str = "WARMSTART";
+const ARG_INSTANCE_ON_STDIN: &str = "INSTANCE_ON_STDIN";
+const ARG_NO_INSTANCE_ARGUMENT: &str = "NO_INSTANCE_ARGUMENT";
+const ARG_INLINE_INSTANCE: &str = "INLINE_INSTANCE";
+const ARG_TRACK_STATE: &str = "TRACK_STATE";
+const ARG_VERIFY_ANSWERS: &str = "VERIFY_ANSWERS";
+const ARG_MANIFEST: &str = "MANIFEST";
+const ARG_PORTFOLIO: &str = "PORTFOLIO";
+const ARG_PORTFOLIO_MODE: &str = "PORTFOLIO_MODE";
+const ARG_REMOTE: &str = "REMOTE";
+const ARG_DOCKER_IMAGE: &str = "DOCKER_IMAGE";
+const ARG_APPTAINER_IMAGE: &str = "APPTAINER_IMAGE";
+const ARG_TMP_DIR: &str = "TMP_DIR";
+const ARG_KEEP_TEMP: &str = "KEEP_TEMP";
+const ARG_TEE_MODIFICATIONS: &str = "TEE_MODIFICATIONS";
+const ARG_ON_STEP: &str = "ON_STEP";
+const ARG_CONFORMANCE: &str = "CONFORMANCE";
+const ARG_LABEL_GRAMMAR: &str = "LABEL_GRAMMAR";
+const ARG_TRACK: &str = "TRACK";
+const ARG_ALLOW_UNKNOWN: &str = "ALLOW_UNKNOWN";
+const ARG_KEEP_GOING: &str = "KEEP_GOING";
+const ARG_FROM_STEP: &str = "FROM_STEP";
+const ARG_TO_STEP: &str = "TO_STEP";
+const ARG_FAST_FORWARD: &str = "FAST_FORWARD";
+const ARG_QUERY_EVERY: &str = "QUERY_EVERY";
+const ARG_PROGRESS: &str = "PROGRESS";
+const ARG_CHECK_MODIFICATIONS: &str = "CHECK_MODIFICATIONS";
+const ARG_CHECK_INSTANCE: &str = "CHECK_INSTANCE";
+const ARG_BATCHED: &str = "BATCHED";
 
-impl WrapCommand {
-    pub fn new() -> Self {
-        WrapCommand
-    }
-}
+const DYNAMIC_SUFFIX: &str = "-D";
 
-pub enum QueryType {
-    SE,
-    EE,
-    CE,
-    DC(String),
-    DS(String),
-}
+const STDIN_INSTANCE_PATH: &str = "-";
 
-impl QueryType {
-    fn command_arguments(&self, problem: &str, input_file: &str, file_format: &str) -> Vec<String> {
-        let mut default_arguments = vec![
-            "-p".to_string(),
-            problem.to_string(),
-            "-f".to_string(),
-            input_file.to_string(),
-            "-fo".to_string(),
-            file_format.to_string(),
-        ];
-        match self {
-            QueryType::SE | QueryType::EE | QueryType::CE => default_arguments,
-            QueryType::DC(arg) | QueryType::DS(arg) => {
-                default_arguments.push("-a".to_string());
-                default_arguments.push(arg.clone());
-                default_arguments
-            }
-        }
-    }
+const PORTFOLIO_MODE_FIRST: &str = "first";
+const PORTFOLIO_MODE_MAJORITY: &str = "majority";
 
-    fn answer_reading_function(&self) -> Box<dyn Fn(&mut dyn BufRead) -> Result<String>> {
-        fn compose_rw<T, R, W>(
-            reading_fn: &'static R,
-            writing_fn: &'static W,
-        ) -> Box<dyn Fn(&mut dyn BufRead) -> Result<String>>
-        where
-            R: Fn(&mut dyn BufRead) -> Result<T>,
-            W: Fn(&mut dyn Write, &T) -> Result<()>,
-        {
-            Box::new(move |reader| -> Result<String> {
-                let read = reading_fn(reader).context("while reading child process stdout")?;
-                let mut cursor = Cursor::new(vec![]);
-                writing_fn(&mut cursor, &read)?;
-                cursor.seek(SeekFrom::Start(0)).unwrap();
-                let mut out = Vec::new();
-                cursor.read_to_end(&mut out).unwrap();
-                Ok(String::from_utf8(out).unwrap())
-            })
-        }
-        match self {
-            QueryType::SE => compose_rw(&solutions::read_extension, &solutions::write_extension),
-            QueryType::EE => compose_rw(&solutions::read_extension_set, &|w, s| {
-                solutions::write_extension_set(w, &s.iter().collect::<Vec<&ArgumentSet<String>>>())
-            }),
-            QueryType::CE => compose_rw(&solutions::read_extension_count, &|w, c| {
-                solutions::write_extension_count(w, *c)
-            }),
-            QueryType::DC(_) | QueryType::DS(_) => {
-                compose_rw(&solutions::read_acceptance_status, &|w, b| {
-                    solutions::write_acceptance_status(w, *b)
-                })
-            }
-        }
-    }
-}
+const CONFORMANCE_STRICT: &str = "strict";
+const CONFORMANCE_LENIENT: &str = "lenient";
 
-impl TryFrom<(&str, Option<&str>)> for QueryType {
-    type Error = anyhow::Error;
+const LABEL_GRAMMAR_IDENTIFIER: &str = "identifier";
+const LABEL_GRAMMAR_NUMERIC: &str = "numeric";
+const LABEL_GRAMMAR_QUOTED: &str = "quoted";
 
-    fn try_from(value: (&str, Option<&str>)) -> Result<Self, Self::Error> {
-        let (problem, arg) = value;
-        let splits = problem.split('-').collect::<Vec<&str>>();
-        let err_builder = |s| anyhow!(r#""{}" is not a valid dynamic track"#, s);
-        if splits.len() != 3
-            || !vec!["CO", "GR", "PR", "ST", "SST", "STG", "ID"].contains(&splits[1])
-            || splits[2] != "D"
-        {
-            return Err(err_builder(problem));
-        }
-        let ok_if_no_arg = |q: QueryType| {
-            if arg.is_none() {
-                Ok(q)
-            } else {
-                Err(anyhow!(
-                    r#"problem "{}" does not require an argument but one is provided"#,
-                    problem
-                ))
-            }
-        };
-        let on_missing_arg = || {
-            anyhow!(
-                r#"problem "{}" requires an argument none is provided"#,
-                problem
-            )
-        };
-        match splits[0] {
-            "SE" => ok_if_no_arg(QueryType::SE),
-            "EE" => ok_if_no_arg(QueryType::EE),
-            "CE" => ok_if_no_arg(QueryType::CE),
-            "DC" => Ok(QueryType::DC(arg.ok_or(on_missing_arg())?.to_string())),
-            "DS" => Ok(QueryType::DS(arg.ok_or(on_missing_arg())?.to_string())),
-            _ => Err(err_builder(problem)),
-        }
+const TRACK_EXACT: &str = "exact";
+const TRACK_APPROXIMATE: &str = "approximate";
+
+impl WrapCommand {
+    pub fn new() -> Self {
+        WrapCommand
     }
 }
 
@@ -165,15 +146,15 @@ impl<'a> Command<'a> for WrapCommand {
                     .long("solver")
                     .short("s")
                     .takes_value(true)
-                    .help("sets the solver to call")
-                    .required(true),
+                    .conflicts_with(ARG_PORTFOLIO)
+                    .help("sets the solver to call (required unless --portfolio is given)"),
             )
             .arg(
                 Arg::with_name(ARG_PROBLEM)
                     .long("problem")
                     .short("p")
                     .takes_value(true)
-                    .help("sets the problem to solve")
+                    .help("sets the problem to solve (the dynamic suffix \"-D\" is appended if missing)")
                     .required(true),
             )
             .arg(
@@ -197,7 +178,15 @@ impl<'a> Command<'a> for WrapCommand {
                     .long("argument")
                     .short("a")
                     .takes_value(true)
-                    .help("sets the argument for acceptance decision problems"),
+                    .multiple(true)
+                    .number_of_values(1)
+                    .help("sets the argument for acceptance decision problems (may be given more than once)"),
+            )
+            .arg(
+                Arg::with_name(ARG_ARGUMENT_FILE)
+                    .long("argument-file")
+                    .takes_value(true)
+                    .help("sets a file containing arguments for acceptance decision problems, one per line"),
             )
             .arg(
                 Arg::with_name(ARG_MODIFICATION_FILE)
@@ -207,63 +196,1728 @@ impl<'a> Command<'a> for WrapCommand {
                     .help("sets the modification file containing the dynamics of the framework")
                     .required(true),
             )
+            .arg(
+                Arg::with_name(ARG_SOLVER_FORMAT)
+                    .long("solver-format")
+                    .short("t")
+                    .takes_value(true)
+                    .possible_values(&[NUMERIC_FORMAT])
+                    .help("translates the Aspartix instance and dynamics to this format for the solver (answers are given with the original labels)"),
+            )
+            .arg(
+                Arg::with_name(ARG_STATIC)
+                    .long("static")
+                    .takes_value(false)
+                    .help("gives the problem to the solver without the dynamic suffix \"-D\""),
+            )
+            .arg(
+                Arg::with_name(ARG_TASK_FILE)
+                    .long("task-file")
+                    .takes_value(true)
+                    .help("sets a file registering new task names, each line giving a name and the task it behaves like (SE, EE, CE, DC or DS)"),
+            )
+            .arg(
+                Arg::with_name(ARG_RESTART_ON_FAILURE)
+                    .long("restart-on-failure")
+                    .takes_value(true)
+                    .help("sets the number of times the solver may be restarted if it crashes (default: 0)"),
+            )
+            .arg(
+                Arg::with_name(ARG_TIMEOUT)
+                    .long("timeout")
+                    .takes_value(true)
+                    .help("sets the time in seconds a solver process is given to answer the whole dynamics; it is killed afterwards and IDW exits with status 30"),
+            )
+            .arg(
+                Arg::with_name(ARG_KEEP_GOING)
+                    .long("keep-going")
+                    .takes_value(false)
+                    .help("relays FAILED for the steps whose answer cannot be read and goes on with the next modifications, reporting the failed steps at the end"),
+            )
+            .arg(
+                Arg::with_name(ARG_CHECKPOINT)
+                    .long("checkpoint")
+                    .takes_value(true)
+                    .help("sets a file in which the current step and framework are saved during the run"),
+            )
+            .arg(
+                Arg::with_name(ARG_CHECKPOINT_INTERVAL)
+                    .long("checkpoint-interval")
+                    .takes_value(true)
+                    .requires(ARG_CHECKPOINT)
+                    .help("sets the number of steps between two checkpoints (default: 1)"),
+            )
+            .arg(
+                Arg::with_name(ARG_RESUME)
+                    .long("resume")
+                    .takes_value(false)
+                    .requires(ARG_CHECKPOINT)
+                    .help("resumes an interrupted run from its checkpoint file, if it exists"),
+            )
+            .arg(
+                Arg::with_name(ARG_FROM_STEP)
+                    .long("from-step")
+                    .takes_value(true)
+                    .conflicts_with(ARG_CHECKPOINT)
+                    .help("sets the first step relayed, the previous modifications being applied to the framework before the solver is run (default: 0)"),
+            )
+            .arg(
+                Arg::with_name(ARG_TO_STEP)
+                    .long("to-step")
+                    .takes_value(true)
+                    .conflicts_with(ARG_CHECKPOINT)
+                    .help("sets the last step relayed, the next modifications being ignored (default: the last step)"),
+            )
+            .arg(
+                Arg::with_name(ARG_FAST_FORWARD)
+                    .long("fast-forward")
+                    .takes_value(true)
+                    .conflicts_with(ARG_CHECKPOINT)
+                    .help("sets a number of modifications sent to the solver without waiting for their answers, which are discarded (default: 0)"),
+            )
+            .arg(
+                Arg::with_name(ARG_QUERY_EVERY)
+                    .long("query-every")
+                    .takes_value(true)
+                    .conflicts_with(ARG_CHECKPOINT)
+                    .help("relays the answer of one step out of this number, discarding the other ones (default: 1)"),
+            )
+            .arg(
+                Arg::with_name(ARG_PROGRESS)
+                    .long("progress")
+                    .takes_value(false)
+                    .help("logs the current step and the number of steps of the dynamics regularly"),
+            )
+            .arg(
+                Arg::with_name(ARG_CHECK_MODIFICATIONS)
+                    .long("check-modifications")
+                    .takes_value(false)
+                    .help("checks the syntax of the whole modification file before running the solver"),
+            )
+            .arg(
+                Arg::with_name(ARG_CHECK_INSTANCE)
+                    .long("check-instance")
+                    .takes_value(false)
+                    .help("reads the instance before running the solver, warning about its recoverable issues (e.g. duplicated arguments or attacks)"),
+            )
+            .arg(
+                Arg::with_name(ARG_BATCHED)
+                    .long("batched")
+                    .takes_value(false)
+                    .conflicts_with_all(&[ARG_PORTFOLIO, ARG_CHECKPOINT, ARG_FAST_FORWARD])
+                    .help("reads the modification file in the batched dialect, in which the modifications of a step are terminated by an empty line or a query. line"),
+            )
+            .arg(
+                Arg::with_name(ARG_WARMSTART)
+                    .long("warmstart")
+                    .takes_value(true)
+                    .help("sets a file whose content (e.g. known extensions) is sent to the solver before its first answer"),
+            )
+            .arg(
+                Arg::with_name(ARG_INSTANCE_ON_STDIN)
+                    .long("instance-on-stdin")
+                    .takes_value(false)
+                    .help("sends the instance to the solver through its standard input, before the dynamics, and gives it \"-f -\""),
+            )
+            .arg(
+                Arg::with_name(ARG_NO_INSTANCE_ARGUMENT)
+                    .long("no-instance-argument")
+                    .takes_value(false)
+                    .requires(ARG_INSTANCE_ON_STDIN)
+                    .help("gives no \"-f\" argument at all to a solver receiving the instance through its standard input"),
+            )
+            .arg(
+                Arg::with_name(ARG_INLINE_INSTANCE)
+                    .long("inline-instance")
+                    .takes_value(false)
+                    .conflicts_with(ARG_INSTANCE_ON_STDIN)
+                    .help("gives no instance file to the solver, but sends the initial framework as modifications of the empty one before the dynamics"),
+            )
+            .arg(
+                Arg::with_name(ARG_TRACK_STATE)
+                    .long("track-state")
+                    .takes_value(false)
+                    .help("applies the modifications to an in-memory copy of the framework, checking them before they are sent"),
+            )
+            .arg(
+                Arg::with_name(ARG_VERIFY_ANSWERS)
+                    .long("verify-answers")
+                    .takes_value(false)
+                    .requires(ARG_TRACK_STATE)
+                    .help("checks the SE, DC and DS answers against the tracked framework, warning about wrong ones"),
+            )
+            .arg(
+                Arg::with_name(ARG_PORTFOLIO)
+                    .long("portfolio")
+                    .takes_value(true)
+                    .conflicts_with_all(&[ARG_SOLVER_FORMAT, ARG_RESTART_ON_FAILURE, ARG_CHECKPOINT, ARG_KEEP_GOING, ARG_FAST_FORWARD])
+                    .help("sets a comma-separated list of solvers run in parallel, relaying the first answer given for each step"),
+            )
+            .arg(
+                Arg::with_name(ARG_PORTFOLIO_MODE)
+                    .long("portfolio-mode")
+                    .takes_value(true)
+                    .possible_values(&[PORTFOLIO_MODE_FIRST, PORTFOLIO_MODE_MAJORITY])
+                    .requires(ARG_PORTFOLIO)
+                    .help("sets whether the first answer or the majority answer of the portfolio is relayed at each step (default: first)"),
+            )
+            .arg(
+                Arg::with_name(ARG_REMOTE)
+                    .long("remote")
+                    .takes_value(true)
+                    .conflicts_with(ARG_PORTFOLIO)
+                    .help("runs the solver remotely: on an SSH destination (ssh://[USER@]HOST), or through a solver session listening at a TCP endpoint (tcp://HOST:PORT)"),
+            )
+            .arg(
+                Arg::with_name(ARG_DOCKER_IMAGE)
+                    .long("docker-image")
+                    .takes_value(true)
+                    .conflicts_with(ARG_APPTAINER_IMAGE)
+                    .help("runs the solver inside a container of this Docker image, in which the instance and modification files are mounted"),
+            )
+            .arg(
+                Arg::with_name(ARG_APPTAINER_IMAGE)
+                    .long("apptainer-image")
+                    .takes_value(true)
+                    .help("runs the solver inside a container of this Apptainer image, in which the instance and modification files are mounted"),
+            )
+            .arg(
+                Arg::with_name(ARG_TMP_DIR)
+                    .long("tmp-dir")
+                    .takes_value(true)
+                    .help("sets the directory of the temporary files given to the solver (default: the system one)"),
+            )
+            .arg(
+                Arg::with_name(ARG_KEEP_TEMP)
+                    .long("keep-temp")
+                    .takes_value(false)
+                    .help("keeps the temporary files given to the solver after the run, for debugging purposes"),
+            )
+            .arg(
+                Arg::with_name(ARG_TEE_MODIFICATIONS)
+                    .long("tee-modifications")
+                    .takes_value(true)
+                    .conflicts_with(ARG_PORTFOLIO)
+                    .help("saves the exact stream sent to the solver on its standard input to this file"),
+            )
+            .arg(
+                Arg::with_name(ARG_ON_STEP)
+                    .long("on-step")
+                    .takes_value(true)
+                    .conflicts_with(ARG_CHECKPOINT)
+                    .help("runs this shell command after each step, giving it the step index, the modification and the answer in the IDW_STEP, IDW_MODIFICATION and IDW_ANSWER environment variables"),
+            )
+            .arg(
+                Arg::with_name(ARG_CONFORMANCE)
+                    .long("conformance")
+                    .takes_value(true)
+                    .possible_values(&[CONFORMANCE_STRICT, CONFORMANCE_LENIENT])
+                    .help("sets whether the answers of the solver must strictly follow the output grammar, or may contain extra whitespace and comment lines (default: lenient)"),
+            )
+            .arg(
+                Arg::with_name(ARG_LABEL_GRAMMAR)
+                    .long("label-grammar")
+                    .takes_value(true)
+                    .conflicts_with(ARG_SOLVER_FORMAT)
+                    .possible_values(&[LABEL_GRAMMAR_IDENTIFIER, LABEL_GRAMMAR_NUMERIC, LABEL_GRAMMAR_QUOTED])
+                    .help("sets the grammar of the argument labels in the extensions output by the solver (default: identifier)"),
+            )
+            .arg(
+                Arg::with_name(ARG_TRACK)
+                    .long("track")
+                    .takes_value(true)
+                    .possible_values(&[TRACK_EXACT, TRACK_APPROXIMATE])
+                    .conflicts_with(ARG_VERIFY_ANSWERS)
+                    .help("sets whether the DC and DS answers are exact, or given by an approximate solver with an optional confidence or UNKNOWN (default: exact)"),
+            )
+            .arg(
+                Arg::with_name(ARG_ALLOW_UNKNOWN)
+                    .long("allow-unknown")
+                    .takes_value(false)
+                    .help("accepts UNKNOWN and TIMEOUT as DC and DS answers, recording them as UNKNOWN instead of stopping the run"),
+            )
+            .arg(
+                Arg::with_name(ARG_MANIFEST)
+                    .long("manifest")
+                    .takes_value(true)
+                    .help("writes a JSON record of the run (command, input hashes, answers, timings and outcome) to this file"),
+            )
     }
 
     fn execute(&self, arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<()> {
-        let problem = arg_matches.value_of(ARG_PROBLEM).unwrap();
-        let arg = arg_matches.value_of(ARG_ARGUMENT);
-        let query = QueryType::try_from((problem, arg))?;
-        let mut process = std::process::Command::new(arg_matches.value_of(ARG_SOLVER).unwrap())
-            .args(query.command_arguments(
-                problem,
-                arg_matches.value_of(ARG_INPUT_FILE).unwrap(),
+        signals::install_handlers();
+        let solver = match (
+            arg_matches.value_of(ARG_SOLVER),
+            arg_matches.value_of(ARG_PORTFOLIO),
+            arg_matches.value_of(ARG_REMOTE),
+        ) {
+            (Some(s), _, _) | (None, Some(s), _) => s,
+            (None, None, Some(r)) if r.starts_with("tcp://") => r,
+            (None, None, _) => {
+                return Err(anyhow!(
+                    "no solver given; use --solver or --portfolio to set the solver(s) to call"
+                ))
+            }
+        };
+        match arg_matches.value_of(ARG_MANIFEST) {
+            None => wrap(arg_matches, None),
+            Some(m) => {
+                let mut record = RunRecord::new(solver, arg_matches.value_of(ARG_PROBLEM).unwrap());
+                record.add_input_file(arg_matches.value_of(ARG_INPUT_FILE).unwrap());
+                record.add_input_file(arg_matches.value_of(ARG_MODIFICATION_FILE).unwrap());
+                let result = wrap(arg_matches, Some(&mut record));
+                record.finish(&result);
+                if let Err(e) = record.save(m) {
+                    warn!("{:#}", e);
+                }
+                result
+            }
+        }
+    }
+}
+
+/// Runs the wrapper, recording the answers relayed at each step if a run record is given.
+fn wrap(
+    arg_matches: &crusti_app_helper::ArgMatches<'_>,
+    mut record: Option<&mut RunRecord>,
+) -> Result<()> {
+    let problem = dynamic_problem(arg_matches.value_of(ARG_PROBLEM).unwrap());
+    let problem = problem.as_str();
+    let solver_problem = if arg_matches.is_present(ARG_STATIC) {
+        problem.strip_suffix(DYNAMIC_SUFFIX).unwrap_or(problem)
+    } else {
+        problem
+    };
+    let tasks = read_task_registry(arg_matches).with_exit_status(MALFORMED_INPUT)?;
+    let arguments = read_query_arguments(arg_matches).with_exit_status(MALFORMED_INPUT)?;
+    if let Some(r) = record.as_mut() {
+        r.set_arguments(&arguments);
+    }
+    let input_file = arg_matches.value_of(ARG_INPUT_FILE).unwrap();
+    let modification_file = arg_matches.value_of(ARG_MODIFICATION_FILE).unwrap();
+    if arg_matches.is_present(ARG_CHECK_INSTANCE) {
+        let n_warnings =
+            check_instance(input_file, arg_matches.value_of(ARG_INPUT_FORMAT).unwrap())
+                .context("while checking input file")
+                .with_exit_status(MALFORMED_INPUT)?;
+        info!("the instance was read with {} warning(s)", n_warnings);
+    }
+    let batched = arg_matches.is_present(ARG_BATCHED);
+    let modifications = if batched {
+        read_batched_modifications(modification_file)
+    } else {
+        read_modifications(modification_file)
+    }
+    .with_exit_status(MALFORMED_INPUT)?;
+    let translated_modifications = check_modification_dialect(
+        input_file,
+        arg_matches.value_of(ARG_INPUT_FORMAT).unwrap(),
+        &modifications,
+        arg_matches.is_present(ARG_TRACK_STATE),
+    )
+    .with_exit_status(MALFORMED_INPUT)?;
+    let check_modifications =
+        arg_matches.is_present(ARG_CHECK_MODIFICATIONS) && translated_modifications.is_none();
+    let modifications = translated_modifications.unwrap_or(modifications);
+    if check_modifications && !batched {
+        let n_steps = dynamics::validate(&mut BufReader::new(
+            compression::open(modification_file)
+                .context("while opening modification file")
+                .with_exit_status(MALFORMED_INPUT)?,
+        ))
+        .context("while checking modification file")
+        .with_exit_status(MALFORMED_INPUT)?;
+        info!("the modification file is valid ({} step(s))", n_steps);
+    }
+    if check_modifications && batched {
+        check_batched_modifications(&modifications)
+            .context("while checking modification file")
+            .with_exit_status(MALFORMED_INPUT)?;
+        info!(
+            "the modification file is valid ({} step(s))",
+            modifications.len()
+        );
+    }
+    let (first_step, last_step) = read_step_range(arg_matches, modifications.len())?;
+    let fast_forwarded_instance = if first_step > 0 {
+        info!("fast-forwarding the first {} modification(s)", first_step);
+        Some(
+            fast_forward(
+                input_file,
                 arg_matches.value_of(ARG_INPUT_FORMAT).unwrap(),
+                &modifications[..first_step],
+                &read_temp_files(arg_matches)?,
+            )
+            .with_exit_status(MALFORMED_INPUT)?,
+        )
+    } else {
+        None
+    };
+    let fast_forwarded_input_file = fast_forwarded_instance
+        .as_ref()
+        .map(|i| i.path().to_string_lossy().to_string());
+    let input_file = fast_forwarded_input_file.as_deref().unwrap_or(input_file);
+    let window = &modifications[first_step..last_step];
+    let first_relayed_step = first_step + read_fast_forward(arg_matches, window.len())?;
+    let query_every = read_query_every(arg_matches)?;
+    let hook = arg_matches.value_of(ARG_ON_STEP).map(StepHook::new);
+    let run_hook = |step: usize, answer: &str| {
+        if let Some(h) = &hook {
+            let modification = step
+                .checked_sub(1)
+                .and_then(|i| modifications.get(i))
+                .map(|m| m.as_str());
+            h.run(step, modification, answer);
+        }
+    };
+    if arguments.len() <= 1 {
+        let query = tasks.query(problem, arguments.first().map(|a| a.as_str()))?;
+        let stdout = std::io::stdout();
+        let mut stdout = BufWriter::new(stdout.lock());
+        let mut step = first_relayed_step;
+        let mut relay = |a: String| {
+            if let Some(r) = record.as_mut() {
+                r.add_step(&a, true);
+            }
+            write_answer(&mut stdout, &a)?;
+            run_hook(step, &a);
+            step += query_every;
+            Ok(())
+        };
+        return match arg_matches.value_of(ARG_CHECKPOINT) {
+            Some(c) => run_checkpointed_query(
+                arg_matches,
+                solver_problem,
+                &query,
+                input_file,
+                window,
+                c,
+                &mut relay,
+            ),
+            None => run_query(
+                arg_matches,
+                solver_problem,
+                &query,
+                input_file,
+                window,
+                first_step,
+                &mut relay,
+            ),
+        };
+    }
+    if arg_matches.is_present(ARG_CHECKPOINT) {
+        return Err(anyhow!(
+            "checkpoints are not available when several arguments are queried"
+        ));
+    }
+    let mut answers = Vec::with_capacity(arguments.len());
+    for arg in arguments.iter() {
+        let query = tasks.query(problem, Some(arg.as_str()))?;
+        let mut arg_answers = vec![];
+        run_query(
+            arg_matches,
+            solver_problem,
+            &query,
+            input_file,
+            window,
+            first_step,
+            &mut |a| {
+                arg_answers.push(a);
+                Ok(())
+            },
+        )
+        .with_context(|| format!(r#"while solving the query for argument "{}""#, arg))?;
+        answers.push(arg_answers);
+    }
+    let aggregated = aggregate_answers(&arguments, &answers);
+    if let Some(r) = record {
+        aggregated.lines().for_each(|l| r.add_step(l, false));
+    }
+    write_answer(&mut BufWriter::new(std::io::stdout().lock()), &aggregated)?;
+    aggregated
+        .lines()
+        .enumerate()
+        .for_each(|(i, l)| run_hook(first_relayed_step + i * query_every, l));
+    Ok(())
+}
+
+/// Reads the range of the steps to relay, given by the first step and the number of modifications sent before the last step.
+fn read_step_range(
+    arg_matches: &crusti_app_helper::ArgMatches<'_>,
+    n_modifications: usize,
+) -> Result<(usize, usize)> {
+    let read_step = |arg, default| match arg_matches.value_of(arg) {
+        Some(s) => s
+            .parse::<usize>()
+            .with_context(|| format!(r#"while parsing the step "{}""#, s)),
+        None => Ok(default),
+    };
+    let first_step = read_step(ARG_FROM_STEP, 0)?;
+    let last_step = read_step(ARG_TO_STEP, n_modifications)?;
+    if last_step > n_modifications {
+        return Err(anyhow!(
+            "cannot relay step {}: the dynamics has {} step(s) after the initial one",
+            last_step,
+            n_modifications
+        ));
+    }
+    if first_step > last_step {
+        return Err(anyhow!(
+            "the first step to relay ({}) is after the last one ({})",
+            first_step,
+            last_step
+        ));
+    }
+    Ok((first_step, last_step))
+}
+
+/// Reads the number of modifications sent to the solver before its answers are relayed.
+fn read_fast_forward(
+    arg_matches: &crusti_app_helper::ArgMatches<'_>,
+    n_modifications: usize,
+) -> Result<usize> {
+    let n = match arg_matches.value_of(ARG_FAST_FORWARD) {
+        Some(n) => n.parse::<usize>().with_context(|| {
+            format!(
+                r#"while parsing the number of modifications to fast-forward "{}""#,
+                n
+            )
+        })?,
+        None => return Ok(0),
+    };
+    if n > n_modifications {
+        return Err(anyhow!(
+            "cannot fast-forward {} modification(s): only {} are sent to the solver",
+            n,
+            n_modifications
+        ));
+    }
+    Ok(n)
+}
+
+/// Reads the number of steps between two relayed answers.
+fn read_query_every(arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<usize> {
+    match arg_matches.value_of(ARG_QUERY_EVERY) {
+        Some(k) => match k.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(anyhow!(
+                r#"expected a positive number of steps between relayed answers, found "{}""#,
+                k
+            )),
+        },
+        None => Ok(1),
+    }
+}
+
+/// Applies the first modifications of the dynamics to an Aspartix framework, writing the result into a temporary file used as the initial framework of the run.
+fn fast_forward(
+    input_file: &str,
+    input_format: &str,
+    modifications: &[String],
+    temp_files: &TempFiles,
+) -> Result<TempFile> {
+    if input_format != "apx" {
+        return Err(anyhow!(
+            r#"cannot fast-forward a framework in format "{}"; only "apx" is supported"#,
+            input_format
+        ));
+    }
+    let mut checkpoint = Checkpoint::new(
+        &AspartixReader::default()
+            .read(&mut compression::open(input_file).context("while opening input file")?)
+            .context("while reading input file")?,
+    );
+    modifications
+        .iter()
+        .flat_map(|m| m.lines())
+        .try_for_each(|m| checkpoint.advance(Some(m)))?;
+    checkpoint.write_instance(temp_files)
+}
+
+/// Appends the dynamic suffix to a problem name if it is missing (e.g. `DC-CO` becomes `DC-CO-D`).
+fn dynamic_problem(problem: &str) -> String {
+    if problem.contains('-') && !problem.ends_with(DYNAMIC_SUFFIX) {
+        let dynamic = format!("{}{}", problem, DYNAMIC_SUFFIX);
+        warn!(
+            r#"problem "{}" is not a dynamic one; considering "{}" instead"#,
+            problem, dynamic
+        );
+        dynamic
+    } else {
+        problem.to_string()
+    }
+}
+
+/// Reads the task registry, extended by the task file if one is given.
+fn read_task_registry(arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<TaskRegistry> {
+    let mut registry = TaskRegistry::default();
+    if let Some(path) = arg_matches.value_of(ARG_TASK_FILE) {
+        let file = File::open(path).with_context(|| format!(r#"while opening "{}""#, path))?;
+        registry
+            .read(&mut BufReader::new(file))
+            .with_context(|| format!(r#"while reading "{}""#, path))?;
+    }
+    Ok(registry)
+}
+
+fn read_query_arguments(arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<Vec<String>> {
+    let mut arguments: Vec<String> = arg_matches
+        .values_of(ARG_ARGUMENT)
+        .map(|v| v.map(|a| a.to_string()).collect())
+        .unwrap_or_default();
+    if let Some(f) = arg_matches.value_of(ARG_ARGUMENT_FILE) {
+        let reader = BufReader::new(File::open(f).context("while opening argument file")?);
+        for l in reader.lines() {
+            let line = l.context("while reading argument file")?;
+            let arg = line.trim();
+            if !arg.is_empty() {
+                arguments.push(arg.to_string());
+            }
+        }
+    }
+    Ok(arguments)
+}
+
+/// Runs a query while saving checkpoints, resuming the run from an existing checkpoint if requested.
+///
+/// The run starts from the given input file, which is the one the caller prepared (e.g. fast-forwarded) rather than the one of the command line.
+/// When resuming, the solver is given the framework of the checkpoint and the remaining modifications.
+fn run_checkpointed_query(
+    arg_matches: &crusti_app_helper::ArgMatches<'_>,
+    problem: &str,
+    query: &QueryType,
+    input_file: &str,
+    modifications: &[String],
+    checkpoint_path: &str,
+    answer_consumer: &mut AnswerConsumer,
+) -> Result<()> {
+    let input_format = arg_matches.value_of(ARG_INPUT_FORMAT).unwrap();
+    if input_format != "apx" {
+        return Err(anyhow!(
+            r#"checkpoints are not available for format "{}"; only "apx" is supported"#,
+            input_format
+        ));
+    }
+    let interval = match arg_matches.value_of(ARG_CHECKPOINT_INTERVAL) {
+        Some(n) => match n.parse::<usize>() {
+            Ok(i) if i > 0 => i,
+            _ => {
+                return Err(anyhow!(
+                    r#"expected a positive number of steps between checkpoints, found "{}""#,
+                    n
+                ))
+            }
+        },
+        None => 1,
+    };
+    let mut checkpoint =
+        if arg_matches.is_present(ARG_RESUME) && Path::new(checkpoint_path).exists() {
+            Checkpoint::read(
+                &mut File::open(checkpoint_path)
+                    .context("while opening checkpoint file")
+                    .with_exit_status(MALFORMED_INPUT)?,
+            )
+            .with_exit_status(MALFORMED_INPUT)?
+        } else {
+            Checkpoint::new(
+                &AspartixReader::default()
+                    .read(
+                        &mut compression::open(input_file)
+                            .context("while opening input file")
+                            .with_exit_status(MALFORMED_INPUT)?,
+                    )
+                    .context("while reading input file")
+                    .with_exit_status(MALFORMED_INPUT)?,
+            )
+        };
+    let first_step = checkpoint.next_step();
+    if first_step > modifications.len() {
+        info!("the checkpointed run is already complete");
+        return Ok(());
+    }
+    let resumed_instance = if first_step > 0 {
+        info!("resuming the run at step {}", first_step);
+        Some(checkpoint.write_instance(&read_temp_files(arg_matches)?)?)
+    } else {
+        None
+    };
+    let resumed_input_file = resumed_instance
+        .as_ref()
+        .map(|i| i.path().to_string_lossy().to_string());
+    run_query(
+        arg_matches,
+        problem,
+        query,
+        resumed_input_file.as_deref().unwrap_or(input_file),
+        &modifications[first_step..],
+        first_step,
+        &mut |a| {
+            answer_consumer(a)?;
+            checkpoint.advance(
+                modifications
+                    .get(checkpoint.next_step())
+                    .map(|m| m.as_str()),
+            )?;
+            let next_step = checkpoint.next_step();
+            if next_step % interval == 0 || next_step > modifications.len() {
+                checkpoint.save(checkpoint_path)?;
+            }
+            Ok(())
+        },
+    )
+}
+
+fn run_query(
+    arg_matches: &crusti_app_helper::ArgMatches<'_>,
+    problem: &str,
+    query: &QueryType,
+    input_file: &str,
+    modifications: &[String],
+    first_step: usize,
+    answer_consumer: &mut AnswerConsumer,
+) -> Result<()> {
+    let input_format = arg_matches.value_of(ARG_INPUT_FORMAT).unwrap();
+    let temp_files = read_temp_files(arg_matches)?;
+    let mut container = read_container(arg_matches);
+    let mut solver_instance = prepare_instance(
+        arg_matches,
+        problem,
+        query,
+        input_file,
+        &temp_files,
+        container.as_mut(),
+    )?;
+    let warmstart = match arg_matches.value_of(ARG_WARMSTART) {
+        Some(w) => {
+            solver_instance
+                .arguments
+                .push(warmstart::WARMSTART_FLAG.to_string());
+            Some(read_block(w, "warm-start file").with_exit_status(MALFORMED_INPUT)?)
+        }
+        None => None,
+    };
+    let max_restarts = match arg_matches.value_of(ARG_RESTART_ON_FAILURE) {
+        Some(n) => n
+            .parse::<usize>()
+            .with_context(|| format!(r#"while parsing the number of restarts "{}""#, n))?,
+        None => 0,
+    };
+    let timeout = read_time_limit(arg_matches, ARG_TIMEOUT)?;
+    let tracked_state = if arg_matches.is_present(ARG_TRACK_STATE) {
+        let state = read_framework_state(input_file, input_format)
+            .context("while reading the framework to track")
+            .with_exit_status(MALFORMED_INPUT)?;
+        info!(
+            "tracking the state of a framework of {} argument(s) and {} attack(s)",
+            state.arguments().len(),
+            state.attacks().len()
+        );
+        Some(state)
+    } else {
+        None
+    };
+    let restart_state = match &tracked_state {
+        _ if max_restarts == 0 => None,
+        Some(s) => Some(s.clone()),
+        None => Some(
+            read_framework_state(input_file, input_format)
+                .context("while reading the framework restarted solvers start from")
+                .with_exit_status(MALFORMED_INPUT)?,
+        ),
+    };
+    let remote = match arg_matches.value_of(ARG_REMOTE) {
+        Some(r) => Some(r.parse::<Remote>()?),
+        None => None,
+    };
+    if let (Some(Remote::Tcp(_)), Some(_)) = (&remote, &container) {
+        return Err(anyhow!(
+            "containers cannot be used with remote solvers reached through TCP"
+        ));
+    }
+    let mut solution_reader = SolutionReader::new(match arg_matches.value_of(ARG_CONFORMANCE) {
+        Some(CONFORMANCE_STRICT) => Conformance::Strict,
+        _ => Conformance::Lenient,
+    });
+    solution_reader.set_label_grammar(match arg_matches.value_of(ARG_LABEL_GRAMMAR) {
+        Some(LABEL_GRAMMAR_NUMERIC) => LabelGrammar::Numeric,
+        Some(LABEL_GRAMMAR_QUOTED) => LabelGrammar::Quoted,
+        _ => LabelGrammar::Identifier,
+    });
+    solution_reader.set_approximate(arg_matches.value_of(ARG_TRACK) == Some(TRACK_APPROXIMATE));
+    solution_reader.set_unknown_allowed(arg_matches.is_present(ARG_ALLOW_UNKNOWN));
+    let fast_forward = read_fast_forward(arg_matches, modifications.len())?;
+    let query_every = read_query_every(arg_matches)?;
+    let portfolio_solvers = arg_matches.value_of(ARG_PORTFOLIO).map(|p| {
+        p.split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<&str>>()
+    });
+    let commands = match &portfolio_solvers {
+        Some(solvers) if solvers.is_empty() => {
+            return Err(anyhow!("the portfolio must contain at least one solver"))
+        }
+        Some(solvers) => solvers.clone(),
+        None => vec![arg_matches
+            .value_of(ARG_SOLVER)
+            .or_else(|| arg_matches.value_of(ARG_REMOTE))
+            .unwrap()],
+    }
+    .into_iter()
+    .map(|solver| SolverCommand {
+        solver,
+        arguments: &solver_instance.arguments,
+        instance: solver_instance.stdin_instance.as_deref(),
+        warmstart: warmstart.as_deref(),
+        remote: remote.as_ref(),
+        container: container.as_ref(),
+        tee_file: arg_matches.value_of(ARG_TEE_MODIFICATIONS),
+        solution_reader,
+        keep_going: arg_matches.is_present(ARG_KEEP_GOING),
+        fast_forward,
+        batched: arg_matches.is_present(ARG_BATCHED),
+        timeout,
+    })
+    .collect::<Vec<SolverCommand>>();
+    let mut verifier = match &tracked_state {
+        Some(s) if arg_matches.is_present(ARG_VERIFY_ANSWERS) => {
+            let mut verifier = AnswerVerifier::new(s.clone(), problem, query)?;
+            verifier.set_first_step(first_step);
+            modifications[..fast_forward]
+                .iter()
+                .try_for_each(|m| verifier.skip(Some(m)))?;
+            Some(verifier)
+        }
+        _ => None,
+    };
+    let mut progress = if arg_matches.is_present(ARG_PROGRESS) {
+        let modification_file = arg_matches.value_of(ARG_MODIFICATION_FILE).unwrap();
+        let last_step = if arg_matches.is_present(ARG_BATCHED) {
+            read_batched_modifications(modification_file)?.len()
+        } else {
+            dynamics::count_steps(&mut BufReader::new(
+                compression::open(modification_file).context("while opening modification file")?,
             ))
-            .stdin(std::process::Stdio::piped())
-            .stdout(std::process::Stdio::piped())
-            .spawn()
-            .context("while spawning child process")?;
-        let mut child_stdin = process.stdin.take().unwrap();
-        let mut child_stdout = BufReader::new(process.stdout.take().unwrap());
-        let mut mod_br = BufReader::new(
-            File::open(arg_matches.value_of(ARG_MODIFICATION_FILE).unwrap())
-                .context("while opening modification file")?,
+            .context("while counting the steps of the modification file")?
+        };
+        Some(ProgressLogger::new(last_step, PROGRESS_INTERVAL))
+    } else {
+        None
+    };
+    let mut n_steps = 0;
+    let mut failed_steps = vec![];
+    let mut verifying_consumer = |a: String| {
+        let step = fast_forward + n_steps;
+        let sampled = n_steps % query_every == 0;
+        n_steps += 1;
+        if let Some(p) = progress.as_mut() {
+            p.step_answered(first_step + step);
+        }
+        if a == FAILED_ANSWER {
+            failed_steps.push(first_step + step);
+        }
+        let modification = modifications.get(step).map(|m| m.as_str());
+        match verifier.as_mut() {
+            Some(v) if sampled => v.verify(&a, modification)?,
+            Some(v) => v.skip(modification)?,
+            None => {}
+        }
+        if sampled {
+            answer_consumer(a)
+        } else {
+            Ok(())
+        }
+    };
+    let result = if portfolio_solvers.is_some() {
+        let mode = match arg_matches.value_of(ARG_PORTFOLIO_MODE) {
+            Some(PORTFOLIO_MODE_MAJORITY) => PortfolioMode::Majority,
+            _ => PortfolioMode::First,
+        };
+        portfolio::run_portfolio(
+            &commands,
+            mode,
+            modifications,
+            query,
+            &mut verifying_consumer,
+        )
+        .map(|_| {})
+    } else {
+        run_with_restarts(
+            max_restarts,
+            &mut verifying_consumer,
+            &mut |n_relayed, consumer| match (n_relayed, &restart_state) {
+                (Some(n), Some(state)) => restart_solver(
+                    &SolverRestart {
+                        arg_matches,
+                        problem,
+                        query,
+                        temp_files: &temp_files,
+                        initial_state: state,
+                        track_state: tracked_state.is_some(),
+                    },
+                    &commands[0],
+                    modifications,
+                    fast_forward + n,
+                    first_step,
+                    consumer,
+                ),
+                _ => run_solver(
+                    &commands[0],
+                    modifications,
+                    tracked_state.as_ref(),
+                    solver_instance.translation.as_ref(),
+                    query,
+                    consumer,
+                ),
+            },
+        )
+    };
+    if let (Err(_), Some(signal)) = (&result, signals::interruption()) {
+        return Err(anyhow::Error::new(ExitStatusError::new(
+            INTERRUPTED,
+            format!(
+                "interrupted by signal {} after {} relayed step(s); the solver(s) were killed",
+                signal, n_steps
+            ),
+        )));
+    }
+    result?;
+    if !failed_steps.is_empty() {
+        return Err(anyhow::Error::new(ExitStatusError::new(
+            WRONG_ANSWER,
+            format!(
+                "the answers of {} step(s) could not be read (steps {})",
+                failed_steps.len(),
+                failed_steps
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        )));
+    }
+    match verifier.map(|v| v.n_wrong_answers()) {
+        Some(n) if n > 0 => Err(anyhow::Error::new(ExitStatusError::new(
+            WRONG_ANSWER,
+            format!("the solver gave {} wrong answer(s)", n),
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// The instance given to a solver, along with the command line arguments and the standard input it implies.
+struct SolverInstance {
+    translation: Option<NumericTranslation>,
+    _decompressed: Option<TempFile>,
+    arguments: Vec<String>,
+    stdin_instance: Option<Vec<String>>,
+}
+
+/// Prepares the instance given to a solver, translating or decompressing it if needed and making it available inside the container.
+fn prepare_instance(
+    arg_matches: &crusti_app_helper::ArgMatches<'_>,
+    problem: &str,
+    query: &QueryType,
+    input_file: &str,
+    temp_files: &TempFiles,
+    container: Option<&mut Container>,
+) -> Result<SolverInstance> {
+    let input_format = arg_matches.value_of(ARG_INPUT_FORMAT).unwrap();
+    let translation = match arg_matches.value_of(ARG_SOLVER_FORMAT) {
+        Some(_) => Some(
+            NumericTranslation::new(input_file, input_format, temp_files)
+                .with_exit_status(MALFORMED_INPUT)?,
+        ),
+        None => None,
+    };
+    let instance_on_stdin = arg_matches.is_present(ARG_INSTANCE_ON_STDIN);
+    let inline_instance = arg_matches.is_present(ARG_INLINE_INSTANCE);
+    let decompressed_instance = if translation.is_none() && !instance_on_stdin && !inline_instance {
+        decompress_instance(input_file, temp_files).with_exit_status(MALFORMED_INPUT)?
+    } else {
+        None
+    };
+    let instance_path = match (&translation, &decompressed_instance) {
+        (Some(t), _) => t.instance_path().to_string_lossy().to_string(),
+        (None, Some(d)) => d.path().to_string_lossy().to_string(),
+        (None, None) => input_file.to_string(),
+    };
+    let instance_argument = if instance_on_stdin {
+        STDIN_INSTANCE_PATH
+    } else {
+        &instance_path
+    };
+    let mut command_arguments = match &translation {
+        Some(t) => {
+            t.translate_query(query)?
+                .command_arguments(problem, instance_argument, NUMERIC_FORMAT)
+        }
+        None => query.command_arguments(problem, instance_argument, input_format),
+    };
+    if arg_matches.is_present(ARG_NO_INSTANCE_ARGUMENT) || inline_instance {
+        remove_instance_argument(&mut command_arguments);
+    }
+    let stdin_instance = if instance_on_stdin {
+        Some(read_block(&instance_path, "instance file").with_exit_status(MALFORMED_INPUT)?)
+    } else if inline_instance {
+        Some(
+            read_inline_instance(input_file, input_format, translation.as_ref())
+                .with_exit_status(MALFORMED_INPUT)?,
+        )
+    } else {
+        None
+    };
+    if let Some(c) = container.filter(|_| !instance_on_stdin && !inline_instance) {
+        let mounted_instance = c.bind_file(&instance_path)?;
+        command_arguments
+            .iter_mut()
+            .filter(|a| **a == instance_path)
+            .for_each(|a| *a = mounted_instance.clone());
+        c.bind_file(arg_matches.value_of(ARG_MODIFICATION_FILE).unwrap())?;
+    }
+    Ok(SolverInstance {
+        translation,
+        _decompressed: decompressed_instance,
+        arguments: command_arguments,
+        stdin_instance,
+    })
+}
+
+/// Reads the initial framework into a state which can follow the dynamics.
+fn read_framework_state(input_file: &str, input_format: &str) -> Result<FrameworkState> {
+    if input_format != "apx" {
+        return Err(anyhow!(
+            r#"cannot read the state of a framework in format "{}"; only "apx" is supported"#,
+            input_format
+        ));
+    }
+    let framework = AspartixReader::default()
+        .read(&mut compression::open(input_file).context("while opening input file")?)
+        .context("while reading input file")?;
+    Ok(FrameworkState::new(&framework))
+}
+
+/// A solver run, given the number of answers already relayed when the solver is restarted.
+type SolverRun<'a> =
+    dyn FnMut(Option<usize>, &mut AnswerConsumer) -> std::result::Result<(), SolverFailure> + 'a;
+
+/// Calls a solver run function, restarting it at most `max_restarts` times in case the solver crashes.
+///
+/// The run function is given `None` for the first run, and the number of answers already relayed to the consumer when the solver is restarted; the restarted run must then resume the dynamics at the step following the last relayed one.
+fn run_with_restarts(
+    max_restarts: usize,
+    answer_consumer: &mut AnswerConsumer,
+    run: &mut SolverRun,
+) -> Result<()> {
+    let mut n_relayed = 0;
+    let mut n_restarts = 0;
+    loop {
+        let restart = Some(n_relayed).filter(|_| n_restarts > 0);
+        let mut counting_consumer = |a: String| {
+            n_relayed += 1;
+            answer_consumer(a).with_exit_status(FAILURE)
+        };
+        let failure = match run(restart, &mut counting_consumer) {
+            Ok(()) => return Ok(()),
+            Err(f) => f,
+        };
+        if !failure.crashed || signals::interruption().is_some() {
+            return Err(failure.error);
+        }
+        if n_restarts == max_restarts {
+            return Err(failure.error).with_exit_status(SOLVER_CRASH);
+        }
+        n_restarts += 1;
+        warn!(
+            "the solver crashed ({:#}); restarting it after {} relayed step(s) [restart {}/{}]",
+            failure.error, n_relayed, n_restarts, max_restarts
         );
-        execute_dynamics(
-            &mut mod_br,
-            query.answer_reading_function(),
-            &mut child_stdin,
-            &mut child_stdout,
-        )?;
-        process
-            .wait()
-            .with_context(|| "while waiting for the end of child process")
-            .map(|_| {})
-    }
-}
-
-fn execute_dynamics<F: ?Sized>(
-    modifications: &mut dyn BufRead,
-    answer_reading_function: Box<F>,
-    child_stdin: &mut dyn Write,
-    child_stdout: &mut dyn BufRead,
-) -> Result<()>
-where
-    F: Fn(&mut dyn BufRead) -> Result<String>,
-{
-    const CONTEXT_WRITING: &str = "while writing to child process stdin";
-    for l in modifications.lines() {
-        let mod_line = l.context("while reading modification file")?;
-        if mod_line.is_empty() {
+    }
+}
+
+/// The settings shared by the restarts of a crashed solver.
+struct SolverRestart<'a> {
+    arg_matches: &'a crusti_app_helper::ArgMatches<'a>,
+    problem: &'a str,
+    query: &'a QueryType,
+    temp_files: &'a TempFiles,
+    /// The framework before the first modification sent to the solver.
+    initial_state: &'a FrameworkState,
+    track_state: bool,
+}
+
+/// Restarts a crashed solver at a step of the dynamics.
+///
+/// The solver is given the framework of this step, computed from the initial state, as its initial framework; the following modifications are then sent as usual.
+/// The restarted solver receives no warm start, since the warm start was computed for the initial framework.
+fn restart_solver(
+    restart: &SolverRestart,
+    command: &SolverCommand,
+    modifications: &[String],
+    step: usize,
+    first_step: usize,
+    answer_consumer: &mut AnswerConsumer,
+) -> std::result::Result<(), SolverFailure> {
+    if step > modifications.len() {
+        return Ok(());
+    }
+    let not_crashed = |error| SolverFailure {
+        error,
+        crashed: false,
+    };
+    let mut state = restart.initial_state.clone();
+    modifications[..step]
+        .iter()
+        .flat_map(|m| m.lines())
+        .try_for_each(|m| state.apply(m).map(|_| ()))
+        .context("while computing the framework of the restarted solver")
+        .map_err(not_crashed)?;
+    let (instance_file, file) = restart
+        .temp_files
+        .create(&format!("restart-step-{}.apx", first_step + step))
+        .context("while creating the instance of the restarted solver")
+        .map_err(not_crashed)?;
+    let mut writer = BufWriter::new(file);
+    state
+        .to_framework()
+        .and_then(|f| AspartixWriter::default().write(&f, &mut writer))
+        .and_then(|_| writer.flush().map_err(anyhow::Error::from))
+        .context("while writing the instance of the restarted solver")
+        .map_err(not_crashed)?;
+    drop(writer);
+    let mut container = command.container.cloned();
+    let solver_instance = prepare_instance(
+        restart.arg_matches,
+        restart.problem,
+        restart.query,
+        &instance_file.path().to_string_lossy(),
+        restart.temp_files,
+        container.as_mut(),
+    )
+    .map_err(not_crashed)?;
+    let restarted_command = SolverCommand {
+        arguments: &solver_instance.arguments,
+        instance: solver_instance.stdin_instance.as_deref(),
+        warmstart: None,
+        container: container.as_ref(),
+        fast_forward: 0,
+        ..*command
+    };
+    run_solver(
+        &restarted_command,
+        &modifications[step..],
+        Some(&state).filter(|_| restart.track_state),
+        solver_instance.translation.as_ref(),
+        restart.query,
+        answer_consumer,
+    )
+}
+
+/// Reads the steps of a modification file written in the batched dialect, the modifications of each step being joined by newlines.
+fn read_batched_modifications(path: &str) -> Result<Vec<String>> {
+    let mut reader =
+        BufReader::new(compression::open(path).context("while opening modification file")?);
+    Ok(dynamics::read_batches(&mut reader)
+        .context("while reading modification file")?
+        .into_iter()
+        .map(|batch| batch.join("\n"))
+        .collect())
+}
+
+/// Reads an Aspartix instance, logging the warnings raised by the reader, and returns their number.
+fn check_instance(input_file: &str, input_format: &str) -> Result<usize> {
+    if input_format != "apx" {
+        return Err(anyhow!(
+            r#"cannot check an instance in format "{}"; only "apx" is supported"#,
+            input_format
+        ));
+    }
+    let mut n_warnings = 0;
+    let mut handler = |line: usize, warning: String| {
+        warn!("line {} of the input file: {}", line + 1, warning);
+        n_warnings += 1;
+    };
+    let mut reader = AspartixReader::default();
+    reader.add_warning_handler(&mut handler);
+    reader
+        .read(&mut compression::open(input_file).context("while opening input file")?)
+        .context("while reading input file")?;
+    drop(reader);
+    Ok(n_warnings)
+}
+
+/// Checks that the modifications are written in the dialect of the instance format, based on the first one.
+///
+/// Numeric modifications of an Aspartix instance are translated to the Aspartix dialect if `translate` is set, in which case the translated modifications are returned.
+/// Other mismatches lead to an error.
+fn check_modification_dialect(
+    input_file: &str,
+    input_format: &str,
+    modifications: &[String],
+    translate: bool,
+) -> Result<Option<Vec<String>>> {
+    let instance_dialect = match ModificationDialect::of_instance_format(input_format) {
+        Some(d) => d,
+        None => return Ok(None),
+    };
+    let modification_dialect = match modifications
+        .iter()
+        .flat_map(|m| m.lines())
+        .next()
+        .and_then(ModificationDialect::of_modification)
+    {
+        Some(d) if d != instance_dialect => d,
+        _ => return Ok(None),
+    };
+    if !translate || modification_dialect != ModificationDialect::Numeric {
+        return Err(anyhow!(
+            r#"the modification file is written in the {} dialect while the instance is in the "{}" format; use the convert-dynamics subcommand to translate it{}"#,
+            modification_dialect,
+            input_format,
+            if modification_dialect == ModificationDialect::Numeric {
+                ", or --track-state to translate it on the fly"
+            } else {
+                ""
+            }
+        ));
+    }
+    info!("translating the numeric modifications to the Aspartix dialect");
+    let framework = AspartixReader::default()
+        .read(&mut compression::open(input_file).context("while opening input file")?)
+        .context("while reading input file")?;
+    let mut label_map = LabelMap::new(&framework);
+    modifications
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            m.lines()
+                .map(|l| label_map.restore_modification(l))
+                .collect::<Result<Vec<String>>>()
+                .map(|lines| lines.join("\n"))
+                .with_context(|| format!("while translating the modifications of step {}", i + 1))
+        })
+        .collect::<Result<Vec<String>>>()
+        .map(Some)
+}
+
+/// Checks the syntax of the modifications of each step read by `read_batched_modifications`.
+fn check_batched_modifications(modifications: &[String]) -> Result<()> {
+    modifications.iter().enumerate().try_for_each(|(i, m)| {
+        m.lines()
+            .try_for_each(|l| dynamics::read_modification(l).map(|_| ()))
+            .with_context(|| format!("while reading the modifications of step {}", i + 1))
+    })
+}
+
+/// Reads the value of a count argument.
+pub(crate) fn read_count(
+    arg_matches: &crusti_app_helper::ArgMatches<'_>,
+    arg: &str,
+) -> Result<usize> {
+    let value = arg_matches.value_of(arg).unwrap();
+    value
+        .parse::<usize>()
+        .with_context(|| format!(r#"while parsing "{}""#, value))
+}
+
+/// Reads the value of an optional time limit argument, given in seconds.
+pub(crate) fn read_time_limit(
+    arg_matches: &crusti_app_helper::ArgMatches<'_>,
+    arg: &str,
+) -> Result<Option<Duration>> {
+    match arg_matches.value_of(arg) {
+        Some(t) => Ok(Some(
+            t.parse::<f64>()
+                .ok()
+                .and_then(|t| Duration::try_from_secs_f64(t).ok())
+                .ok_or_else(|| anyhow!(r#"invalid time limit "{}""#, t))?,
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Reads the lines of a modification file, until its end or its first empty line.
+///
+/// Lines may end with CRLF, in which case the carriage returns are not sent to the solvers.
+pub(crate) fn read_modifications(path: &str) -> Result<Vec<String>> {
+    let reader =
+        BufReader::new(compression::open(path).context("while opening modification file")?);
+    let mut modifications = vec![];
+    for l in reader.lines() {
+        let line = l.context("while reading modification file")?;
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() {
             break;
         }
-        let read = answer_reading_function(child_stdout)?;
-        print!("{}", read);
-        writeln!(child_stdin, "{}", mod_line).context(CONTEXT_WRITING)?;
+        modifications.push(line.to_string());
+    }
+    Ok(modifications)
+}
+
+/// Reads the initial framework as the modifications building it from the empty one, translated for the solver if needed.
+fn read_inline_instance(
+    input_file: &str,
+    input_format: &str,
+    translation: Option<&NumericTranslation>,
+) -> Result<Vec<String>> {
+    let modifications = read_framework_state(input_file, input_format)
+        .context("while inlining the instance")?
+        .to_modifications();
+    match translation {
+        Some(t) => modifications
+            .into_iter()
+            .map(t.modification_translator())
+            .collect(),
+        None => Ok(modifications),
+    }
+}
+
+/// Reads the settings of the temporary files given to the solver.
+fn read_temp_files(arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Result<TempFiles> {
+    TempFiles::new(
+        arg_matches.value_of(ARG_TMP_DIR),
+        arg_matches.is_present(ARG_KEEP_TEMP),
+    )
+}
+
+/// Decompresses an instance for the solver into a temporary file, if it is compressed.
+fn decompress_instance(input_file: &str, temp_files: &TempFiles) -> Result<Option<TempFile>> {
+    let mut reader = compression::open(input_file).context("while opening input file")?;
+    if reader.compression() == compression::Compression::None {
+        return Ok(None);
+    }
+    let file_name = Path::new(input_file)
+        .file_stem()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let (instance, mut file) = temp_files
+        .create(&file_name)
+        .context("while creating the decompressed instance")?;
+    std::io::copy(&mut reader, &mut file).context("while decompressing the input file")?;
+    Ok(Some(instance))
+}
+
+/// Removes the "-f" argument and its value from the command line of a solver.
+fn remove_instance_argument(command_arguments: &mut Vec<String>) {
+    if let Some(i) = command_arguments.iter().position(|a| a == "-f") {
+        command_arguments.drain(i..(i + 2).min(command_arguments.len()));
+    }
+}
+
+/// Reads the non-empty lines of a file, which are sent to the solver as a block (e.g. a warm-start block).
+fn read_block(path: &str, file_kind: &str) -> Result<Vec<String>> {
+    let reader = BufReader::new(
+        compression::open(path).with_context(|| format!("while opening {}", file_kind))?,
+    );
+    let mut lines = vec![];
+    for l in reader.lines() {
+        let line = l.with_context(|| format!("while reading {}", file_kind))?;
+        if !line.trim().is_empty() {
+            lines.push(line.trim_end_matches('\r').to_string());
+        }
+    }
+    Ok(lines)
+}
+
+/// Writes a warm-start block to the solver, ending it by an empty line.
+pub(crate) fn send_warmstart(warmstart: &[String], child_stdin: &mut dyn Write) -> Result<()> {
+    send_block(warmstart, "warm-start block", child_stdin)
+}
+
+/// Writes the lines of a block to the solver, ending it by an empty line.
+fn send_block(block: &[String], block_kind: &str, child_stdin: &mut dyn Write) -> Result<()> {
+    let context_sending = || format!("while sending the {} to the child process", block_kind);
+    for line in block {
+        writeln!(child_stdin, "{}", line).with_context(context_sending)?;
+    }
+    writeln!(child_stdin).with_context(context_sending)?;
+    child_stdin.flush().with_context(context_sending)
+}
+
+/// Sends the blocks a solver must receive before the dynamics: the instance, then the warm-start block.
+pub(crate) fn send_preamble(command: &SolverCommand, child_stdin: &mut dyn Write) -> Result<()> {
+    if let Some(i) = command.instance {
+        send_block(i, "instance", child_stdin)?;
+    }
+    if let Some(w) = command.warmstart {
+        send_warmstart(w, child_stdin)?;
+    }
+    Ok(())
+}
+
+/// The command used to launch a solver, the blocks (instance, warm start) it must receive first, the file in which its input is saved, and the reader of its answers.
+pub(crate) struct SolverCommand<'a> {
+    pub(crate) solver: &'a str,
+    pub(crate) arguments: &'a [String],
+    pub(crate) instance: Option<&'a [String]>,
+    pub(crate) warmstart: Option<&'a [String]>,
+    pub(crate) remote: Option<&'a Remote>,
+    pub(crate) container: Option<&'a Container>,
+    pub(crate) tee_file: Option<&'a str>,
+    pub(crate) solution_reader: SolutionReader,
+    pub(crate) keep_going: bool,
+    pub(crate) fast_forward: usize,
+    pub(crate) batched: bool,
+    pub(crate) timeout: Option<Duration>,
+}
+
+impl SolverCommand<'_> {
+    /// Builds the command spawning the solver, taking into account its container and its remote location.
+    ///
+    /// The solver leads its own process group (see [`signals`]), so that the interruptions of the wrapper are not delivered to it directly.
+    pub(crate) fn process(&self) -> std::process::Command {
+        #[allow(unused_mut)]
+        let mut process = match self.container {
+            Some(c) => {
+                let (program, arguments) = c.wrap(self.solver, self.arguments);
+                remote::solver_process(&program, &arguments, self.remote)
+            }
+            None => remote::solver_process(self.solver, self.arguments, self.remote),
+        };
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            process.process_group(0);
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            use windows_sys::Win32::System::Threading::CREATE_NEW_PROCESS_GROUP;
+            process.creation_flags(CREATE_NEW_PROCESS_GROUP);
+        }
+        process
+    }
+
+    /// Spawns the solver, which is killed if it reaches its time limit, if its run is cancelled or if the wrapper is interrupted.
+    pub(crate) fn spawn(&self, cancellation: Option<&Cancellation>) -> Result<SolverProcess> {
+        let mut limits = RunLimits::default();
+        if let Some(t) = self.timeout {
+            limits.set_timeout(t);
+        }
+        if let Some(c) = cancellation {
+            limits.set_cancellation(c);
+        }
+        SolverProcess::spawn_with_hooks(
+            self.process(),
+            &limits,
+            Box::new(InterruptionHooks::default()),
+        )
+    }
+}
+
+/// Reads the container in which the solvers must run, if any.
+fn read_container(arg_matches: &crusti_app_helper::ArgMatches<'_>) -> Option<Container> {
+    match (
+        arg_matches.value_of(ARG_DOCKER_IMAGE),
+        arg_matches.value_of(ARG_APPTAINER_IMAGE),
+    ) {
+        (Some(image), _) => Some(Container::new(ContainerRuntime::Docker, image)),
+        (None, Some(image)) => Some(Container::new(ContainerRuntime::Apptainer, image)),
+        (None, None) => None,
+    }
+}
+
+/// The failure of a solver run.
+struct SolverFailure {
+    error: anyhow::Error,
+    crashed: bool,
+}
+
+/// Runs a solver on the whole dynamics.
+///
+/// If an initial state is given, each modification is applied to a copy of it before being sent, so that invalid modifications are reported with the labels involved.
+///
+/// In case of failure, the returned object indicates whether the solver crashed, in which case the run may be restarted.
+fn run_solver(
+    command: &SolverCommand,
+    modifications: &[String],
+    initial_state: Option<&FrameworkState>,
+    translation: Option<&NumericTranslation>,
+    query: &QueryType,
+    answer_consumer: &mut AnswerConsumer,
+) -> std::result::Result<(), SolverFailure> {
+    if let Some(Remote::Tcp(address)) = command.remote {
+        return run_tcp_solver(
+            address,
+            command,
+            modifications,
+            initial_state,
+            translation,
+            query,
+            answer_consumer,
+        );
+    }
+    let mut process = command
+        .spawn(None)
+        .with_context(|| WrapError::SolverSpawn {
+            solver: command.solver.to_string(),
+        })
+        .map_err(|error| SolverFailure {
+            error,
+            crashed: false,
+        })?;
+    let (child_stdin, child_stdout) = process.pipes();
+    let mut child_stdin = BufWriter::new(child_stdin);
+    let mut child_stdout = BufReader::new(child_stdout);
+    let result = send_dynamics(
+        command,
+        modifications,
+        initial_state,
+        translation,
+        query,
+        answer_consumer,
+        &mut child_stdin,
+        &mut child_stdout,
+    );
+    // the process state is checked before closing its stdin, which would make it exit after errors raised by the wrapper
+    let crashed_during_dynamics = result
+        .as_ref()
+        .err()
+        .filter(|_| process.stopped().is_none())
+        .map(|_| process.has_exited());
+    drop(child_stdin);
+    let result = result
+        .and_then(|last_step| check_end_of_output(&mut child_stdout, last_step))
+        .and_then(|()| {
+            process
+                .wait()
+                .context("while waiting for the end of child process")
+                .map(|_| {})
+        });
+    match result {
+        Ok(()) => Ok(()),
+        Err(error) if process.stopped() == Some(Stop::Timeout) => {
+            let _ = process.wait();
+            Err(SolverFailure {
+                error: timeout_error(error, command.timeout),
+                crashed: false,
+            })
+        }
+        Err(error) => {
+            let crashed = crashed_during_dynamics.unwrap_or_else(|| process.has_exited());
+            if crashed {
+                return Err(SolverFailure { error, crashed });
+            }
+            process.kill();
+            let _ = process.wait();
+            Err(SolverFailure {
+                error: classify_solver_error(error),
+                crashed,
+            })
+        }
     }
-    let read = answer_reading_function(child_stdout)?;
-    print!("{}", read);
-    writeln!(child_stdin).context(CONTEXT_WRITING)
+}
+
+/// Classifies the error of a solver killed because it reached the time limit.
+pub(crate) fn timeout_error(error: anyhow::Error, timeout: Option<Duration>) -> anyhow::Error {
+    let limit = timeout.map(|t| t.as_secs_f64()).unwrap_or_default();
+    ExitStatusError::classify(
+        error.context(format!(
+            "the solver did not answer the dynamics within {}s and was killed",
+            limit
+        )),
+        TIMEOUT,
+    )
+}
+
+/// Runs a remote solver session listening at a TCP endpoint on the whole dynamics.
+///
+/// The session is considered as crashed if it closed the connection when an error occurs.
+#[allow(clippy::too_many_arguments)]
+fn run_tcp_solver(
+    address: &str,
+    command: &SolverCommand,
+    modifications: &[String],
+    initial_state: Option<&FrameworkState>,
+    translation: Option<&NumericTranslation>,
+    query: &QueryType,
+    answer_consumer: &mut AnswerConsumer,
+) -> std::result::Result<(), SolverFailure> {
+    let not_crashed = |error| SolverFailure {
+        error,
+        crashed: false,
+    };
+    let stream = remote::connect(address).map_err(not_crashed)?;
+    let mut session_input = BufWriter::new(
+        stream
+            .try_clone()
+            .context("while connecting to the remote solver")
+            .map_err(not_crashed)?,
+    );
+    let mut session_output = BufReader::new(
+        stream
+            .try_clone()
+            .context("while connecting to the remote solver")
+            .map_err(not_crashed)?,
+    );
+    let result = send_dynamics(
+        command,
+        modifications,
+        initial_state,
+        translation,
+        query,
+        answer_consumer,
+        &mut session_input,
+        &mut session_output,
+    );
+    let closed_during_dynamics = result.as_ref().err().map(|_| remote::is_closed(&stream));
+    drop(session_input);
+    let result = result
+        .and_then(|last_step| remote::close_input(&stream).map(|_| last_step))
+        .and_then(|last_step| check_end_of_output(&mut session_output, last_step));
+    result.map_err(|error| {
+        let crashed = closed_during_dynamics.unwrap_or(false);
+        SolverFailure {
+            error: if crashed {
+                error
+            } else {
+                classify_solver_error(error)
+            },
+            crashed,
+        }
+    })
+}
+
+/// Sends the instance and warm-start blocks and the dynamics to a solver, relaying its answers.
+///
+/// The number of modifications sent to the solver is returned.
+#[allow(clippy::too_many_arguments)]
+fn send_dynamics<R: Read>(
+    command: &SolverCommand,
+    modifications: &[String],
+    initial_state: Option<&FrameworkState>,
+    translation: Option<&NumericTranslation>,
+    query: &QueryType,
+    answer_consumer: &mut AnswerConsumer,
+    solver_input: &mut dyn Write,
+    solver_output: &mut BufReader<R>,
+) -> Result<usize> {
+    let mut tee_writer;
+    let solver_input: &mut dyn Write = match command.tee_file {
+        Some(path) => {
+            tee_writer = TeeWriter {
+                inner: solver_input,
+                tee: BufWriter::new(
+                    File::create(path)
+                        .with_context(|| format!(r#"while creating the tee file "{}""#, path))?,
+                ),
+            };
+            &mut tee_writer
+        }
+        None => solver_input,
+    };
+    let joined_modifications = if command.batched {
+        modifications.join(&format!("\n{}\n", dynamics::QUERY_MARKER))
+    } else {
+        modifications.join("\n")
+    };
+    let mut mod_reader = joined_modifications.as_bytes();
+    let tracked_state = initial_state.cloned().map(RefCell::new);
+    let tracker = |l: String| -> Result<String> {
+        if let Some(s) = &tracked_state {
+            s.borrow_mut()
+                .apply(&l)
+                .with_context(|| {
+                    format!(
+                        r#"while applying modification "{}" to the tracked framework"#,
+                        l
+                    )
+                })
+                .with_exit_status(MALFORMED_INPUT)?;
+        }
+        Ok(l)
+    };
+    send_preamble(command, solver_input)?;
+    let answer_reading_function = match translation {
+        Some(t) => t.answer_reading_function(query, command.solution_reader),
+        None => query.answer_reading_function_with(command.solution_reader),
+    };
+    let answer_reading_function = if command.keep_going {
+        runner::keep_going(
+            answer_reading_function,
+            Box::new(|step, e| {
+                warn!(
+                    "could not read the answer for step {} ({:#}); going on with the next step",
+                    step, e
+                )
+            }),
+        )
+    } else {
+        answer_reading_function
+    };
+    let modification_translator = |l: String| match translation {
+        Some(t) => tracker(l)
+            .and_then(t.modification_translator())
+            .with_exit_status(MALFORMED_INPUT),
+        None => tracker(l),
+    };
+    if command.batched {
+        return execute_batched_dynamics(
+            &mut mod_reader,
+            answer_reading_function,
+            &modification_translator,
+            answer_consumer,
+            solver_input,
+            solver_output,
+        );
+    }
+    let n_fast_forwarded = runner::fast_forward_dynamics(
+        command.fast_forward,
+        &mut mod_reader,
+        &*answer_reading_function,
+        &modification_translator,
+        solver_input,
+        solver_output,
+    )?;
+    execute_dynamics(
+        &mut mod_reader,
+        answer_reading_function,
+        &modification_translator,
+        answer_consumer,
+        solver_input,
+        solver_output,
+    )
+    .map(|n| n_fast_forwarded + n)
+}
+
+/// A writer copying the data it is given to a second writer.
+///
+/// The copy is flushed each time the writer is, so that it stays complete if the run is interrupted.
+struct TeeWriter<'a, W: Write> {
+    inner: &'a mut dyn Write,
+    tee: W,
+}
+
+impl<W: Write> Write for TeeWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.tee.write_all(&buf[..n])?;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.tee.flush()?;
+        self.inner.flush()
+    }
+}
+
+/// Merges the answers obtained for several arguments, writing one line per step.
+///
+/// Each line gives the answer for each argument, in the order of the arguments.
+fn aggregate_answers(arguments: &[String], answers: &[Vec<String>]) -> String {
+    let n_steps = answers.iter().map(|a| a.len()).min().unwrap_or(0);
+    let mut out = String::new();
+    for step in 0..n_steps {
+        let line = arguments
+            .iter()
+            .zip(answers.iter())
+            .map(|(arg, arg_answers)| format!("{}: {}", arg, arg_answers[step].trim()))
+            .collect::<Vec<String>>()
+            .join(", ");
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
 }
 
 #[cfg(test)]
@@ -271,78 +1925,850 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_execute_dynamics_no_dyn_acceptance_status() {
-        let mut modifications = BufReader::new("".as_bytes());
-        let answer_reader = QueryType::DC("a".to_string()).answer_reading_function();
-        let mut cursor = Cursor::new(vec![]);
-        let mut child_stdout = BufReader::new("YES\n".as_bytes());
-        execute_dynamics(
-            &mut modifications,
-            answer_reader,
-            &mut cursor,
-            &mut child_stdout,
+    fn test_aggregate_answers() {
+        let arguments = vec!["a".to_string(), "b".to_string()];
+        let answers = vec![
+            vec!["YES\n".to_string(), "NO\n".to_string()],
+            vec!["NO\n".to_string(), "NO\n".to_string()],
+        ];
+        assert_eq!(
+            "a: YES, b: NO\na: NO, b: NO\n",
+            aggregate_answers(&arguments, &answers)
+        );
+    }
+
+    #[test]
+    fn test_dynamic_problem() {
+        assert_eq!("DC-CO-D", dynamic_problem("DC-CO"));
+        assert_eq!("DC-CO-D", dynamic_problem("DC-CO-D"));
+        assert_eq!("DC", dynamic_problem("DC"));
+        assert_eq!("DC-APX-CO-D", dynamic_problem("DC-APX-CO"));
+    }
+
+    fn flaky_run(
+        n_runs: &mut usize,
+        restart: Option<usize>,
+        consumer: &mut AnswerConsumer,
+    ) -> std::result::Result<(), SolverFailure> {
+        *n_runs += 1;
+        let first_answer = restart.unwrap_or(0);
+        let last_answer = if *n_runs == 1 { 2 } else { 4 };
+        for i in first_answer..last_answer {
+            consumer(format!("answer {}", i)).unwrap();
+        }
+        if *n_runs == 1 {
+            Err(SolverFailure {
+                error: anyhow!("crash"),
+                crashed: true,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_run_with_restarts() {
+        let mut n_runs = 0;
+        let mut restarts = vec![];
+        let mut answers = vec![];
+        run_with_restarts(
+            1,
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
+            &mut |r, c| {
+                restarts.push(r);
+                flaky_run(&mut n_runs, r, c)
+            },
+        )
+        .unwrap();
+        assert_eq!(vec![None, Some(2)], restarts);
+        assert_eq!(
+            vec!["answer 0", "answer 1", "answer 2", "answer 3"],
+            answers
+        );
+    }
+
+    #[test]
+    fn test_run_with_restarts_no_restart() {
+        let mut n_runs = 0;
+        let error = run_with_restarts(0, &mut |_| Ok(()), &mut |r, c| flaky_run(&mut n_runs, r, c))
+            .unwrap_err();
+        assert_eq!(1, n_runs);
+        assert_eq!(
+            SOLVER_CRASH,
+            error.downcast_ref::<ExitStatusError>().unwrap().status()
+        );
+    }
+
+    /// Writes a file among temporary ones.
+    fn write_temp_file(temp_files: &TempFiles, suffix: &str, content: &str) -> TempFile {
+        let (temp_file, mut file) = temp_files.create(suffix).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        temp_file
+    }
+
+    /// Writes a fake SE solver answering with all the arguments of its instance for the initial framework, and with the empty extension after each modification.
+    ///
+    /// If a crash marker path is given, the solver crashes after its first answer unless the marker exists, and creates the marker before crashing.
+    #[cfg(unix)]
+    fn write_listing_solver(temp_files: &TempFiles, crash_marker: Option<&Path>) -> TempFile {
+        use std::os::unix::fs::PermissionsExt;
+        let crash = match crash_marker {
+            Some(m) => format!(
+                "[ -e '{0}' ] || {{ touch '{0}'; exit 1; }}\n",
+                m.to_string_lossy()
+            ),
+            None => String::new(),
+        };
+        let solver = write_temp_file(
+            temp_files,
+            "solver.sh",
+            &format!(
+                "#!/bin/sh\nwhile [ \"$1\" != -f ]; do shift; done\nprintf '[%s]\\n' \"$(sed -n 's/^arg(\\(.*\\))\\.$/\\1/p' \"$2\" | paste -sd, -)\"\n{}while read l; do [ -z \"$l\" ] && exit 0; echo '[]'; done\n",
+                crash
+            ),
+        );
+        std::fs::set_permissions(solver.path(), std::fs::Permissions::from_mode(0o755)).unwrap();
+        solver
+    }
+
+    fn wrap_matches<'a>(arguments: &[&str]) -> crusti_app_helper::ArgMatches<'a> {
+        WrapCommand::new()
+            .clap_subcommand()
+            .get_matches_from(std::iter::once("wrap").chain(arguments.iter().copied()))
+    }
+
+    #[test]
+    fn test_from_step_conflicts_with_checkpoint() {
+        let matches = WrapCommand::new()
+            .clap_subcommand()
+            .get_matches_from_safe(vec![
+                "wrap",
+                "-s",
+                "solver",
+                "-p",
+                "SE-CO-D",
+                "-f",
+                "af.apx",
+                "-m",
+                "af.apxm",
+                "--from-step",
+                "1",
+                "--checkpoint",
+                "run.ckpt",
+            ]);
+        assert!(matches.is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_checkpointed_query_uses_given_input() {
+        let temp_files = TempFiles::default();
+        let input_file = write_temp_file(&temp_files, "af.apx", "arg(a).\n");
+        let fast_forwarded_file = write_temp_file(&temp_files, "af.apx", "arg(a).\narg(b).\n");
+        let modification_file = write_temp_file(&temp_files, "af.apxm", "+arg(c).\n");
+        let checkpoint_file = temp_files.create("ckpt").unwrap().0;
+        std::fs::remove_file(checkpoint_file.path()).unwrap();
+        let solver = write_listing_solver(&temp_files, None);
+        let solver_path = solver.path().to_string_lossy().to_string();
+        let input_path = input_file.path().to_string_lossy().to_string();
+        let modification_path = modification_file.path().to_string_lossy().to_string();
+        let checkpoint_path = checkpoint_file.path().to_string_lossy().to_string();
+        let matches = wrap_matches(&[
+            "-s",
+            &solver_path,
+            "-p",
+            "SE-CO-D",
+            "-f",
+            &input_path,
+            "-z",
+            "apx",
+            "-m",
+            &modification_path,
+            "--checkpoint",
+            &checkpoint_path,
+        ]);
+        let mut answers = vec![];
+        run_checkpointed_query(
+            &matches,
+            "SE-CO-D",
+            &QueryType::SE,
+            &fast_forwarded_file.path().to_string_lossy(),
+            &read_modifications(&modification_path).unwrap(),
+            &checkpoint_path,
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
         )
         .unwrap();
-        let mut out = Vec::new();
-        cursor.seek(SeekFrom::Start(0)).unwrap();
-        cursor.read_to_end(&mut out).unwrap();
-        let child_stdin = String::from_utf8(out).unwrap();
-        assert_eq!("\n", child_stdin);
+        assert_eq!(vec!["[a, b]\n", "[]\n"], answers);
+        let checkpoint = Checkpoint::read(&mut File::open(&checkpoint_path).unwrap()).unwrap();
+        assert_eq!(2, checkpoint.next_step());
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_execute_dynamics_one_dyn_acceptance_status() {
-        let mut modifications = BufReader::new("+arg(a).\n".as_bytes());
-        let answer_reader = QueryType::DC("a".to_string()).answer_reading_function();
-        let mut cursor = Cursor::new(vec![]);
-        let mut child_stdout = BufReader::new("YES\nNO\n".as_bytes());
-        execute_dynamics(
-            &mut modifications,
-            answer_reader,
-            &mut cursor,
-            &mut child_stdout,
+    fn test_run_query_restart_from_state() {
+        let temp_files = TempFiles::default();
+        let input_file = write_temp_file(&temp_files, "af.apx", "arg(a).\n");
+        let modification_file = write_temp_file(&temp_files, "af.apxm", "+arg(b).\n+arg(c).\n");
+        let marker = temp_files.create("crashed").unwrap().0;
+        std::fs::remove_file(marker.path()).unwrap();
+        let solver = write_listing_solver(&temp_files, Some(marker.path()));
+        let solver_path = solver.path().to_string_lossy().to_string();
+        let input_path = input_file.path().to_string_lossy().to_string();
+        let modification_path = modification_file.path().to_string_lossy().to_string();
+        let matches = wrap_matches(&[
+            "-s",
+            &solver_path,
+            "-p",
+            "SE-CO-D",
+            "-f",
+            &input_path,
+            "-z",
+            "apx",
+            "-m",
+            &modification_path,
+            "--restart-on-failure",
+            "1",
+        ]);
+        let mut answers = vec![];
+        run_query(
+            &matches,
+            "SE-CO-D",
+            &QueryType::SE,
+            &input_path,
+            &read_modifications(&modification_path).unwrap(),
+            0,
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
         )
         .unwrap();
-        let mut out = Vec::new();
-        cursor.seek(SeekFrom::Start(0)).unwrap();
-        cursor.read_to_end(&mut out).unwrap();
-        let child_stdin = String::from_utf8(out).unwrap();
-        assert_eq!("+arg(a).\n\n", child_stdin);
+        // the restarted solver is given the framework of step 1, not the initial one
+        assert_eq!(vec!["[a]\n", "[a, b]\n", "[]\n"], answers);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_solver_crash() {
+        let arguments = vec!["-c".to_string(), "echo YES; exit 1".to_string()];
+        let failure = run_solver(
+            &SolverCommand {
+                solver: "sh",
+                arguments: &arguments,
+                instance: None,
+                warmstart: None,
+                remote: None,
+                container: None,
+                tee_file: None,
+                solution_reader: SolutionReader::default(),
+                keep_going: false,
+                fast_forward: 0,
+                batched: false,
+                timeout: None,
+            },
+            &["+arg(b).".to_string()],
+            None,
+            None,
+            &QueryType::DC("a".to_string()),
+            &mut |_| Ok(()),
+        )
+        .unwrap_err();
+        assert!(failure.crashed);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_solver_timeout() {
+        let arguments = vec!["-c".to_string(), "echo YES; sleep 60".to_string()];
+        let mut n_answers = 0;
+        let failure = run_solver(
+            &SolverCommand {
+                solver: "sh",
+                arguments: &arguments,
+                instance: None,
+                warmstart: None,
+                remote: None,
+                container: None,
+                tee_file: None,
+                solution_reader: SolutionReader::default(),
+                keep_going: false,
+                fast_forward: 0,
+                batched: false,
+                timeout: Some(Duration::from_millis(200)),
+            },
+            &["+arg(b).".to_string()],
+            None,
+            None,
+            &QueryType::DC("a".to_string()),
+            &mut |_| {
+                n_answers += 1;
+                Ok(())
+            },
+        )
+        .unwrap_err();
+        assert!(!failure.crashed);
+        assert_eq!(1, n_answers);
+        assert_eq!(
+            TIMEOUT,
+            failure
+                .error
+                .downcast_ref::<ExitStatusError>()
+                .unwrap()
+                .status()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_solver_keep_going() {
+        let script = r#"echo YES; read l; echo MAYBE; read l; echo NO; read l"#;
+        let arguments = vec!["-c".to_string(), script.to_string()];
+        let mut answers = vec![];
+        run_solver(
+            &SolverCommand {
+                solver: "sh",
+                arguments: &arguments,
+                instance: None,
+                warmstart: None,
+                remote: None,
+                container: None,
+                tee_file: None,
+                solution_reader: SolutionReader::default(),
+                keep_going: true,
+                fast_forward: 0,
+                batched: false,
+                timeout: None,
+            },
+            &["+arg(b).".to_string(), "+arg(c).".to_string()],
+            None,
+            None,
+            &QueryType::DC("a".to_string()),
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
+        )
+        .map_err(|f| f.error)
+        .unwrap();
+        assert_eq!(vec!["YES\n", FAILED_ANSWER, "NO\n"], answers);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_solver_fast_forward() {
+        let script = r#"echo NO; while read l; do [ -z "$l" ] && exit 0; echo "$l" | grep -q "att" && echo NO || echo YES; done"#;
+        let arguments = vec!["-c".to_string(), script.to_string()];
+        let mut answers = vec![];
+        let n_steps = run_solver(
+            &SolverCommand {
+                solver: "sh",
+                arguments: &arguments,
+                instance: None,
+                warmstart: None,
+                remote: None,
+                container: None,
+                tee_file: None,
+                solution_reader: SolutionReader::default(),
+                keep_going: false,
+                fast_forward: 2,
+                batched: false,
+                timeout: None,
+            },
+            &[
+                "+arg(b).".to_string(),
+                "+att(b,a).".to_string(),
+                "+arg(c).".to_string(),
+            ],
+            None,
+            None,
+            &QueryType::DC("a".to_string()),
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
+        )
+        .map_err(|f| f.error);
+        n_steps.unwrap();
+        assert_eq!(vec!["NO\n", "YES\n"], answers);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_solver_batched() {
+        let script = r#"n=0; echo YES; while read l; do [ -z "$l" ] && exit 0; if [ "$l" = "query." ]; then [ $((n%2)) -eq 0 ] && echo YES || echo NO; else n=$((n+1)); fi; done"#;
+        let arguments = vec!["-c".to_string(), script.to_string()];
+        let mut answers = vec![];
+        run_solver(
+            &SolverCommand {
+                solver: "sh",
+                arguments: &arguments,
+                instance: None,
+                warmstart: None,
+                remote: None,
+                container: None,
+                tee_file: None,
+                solution_reader: SolutionReader::default(),
+                keep_going: false,
+                fast_forward: 0,
+                batched: true,
+                timeout: None,
+            },
+            &[
+                "+arg(b).\n+arg(c).".to_string(),
+                "".to_string(),
+                "-arg(b).".to_string(),
+            ],
+            None,
+            None,
+            &QueryType::DC("a".to_string()),
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
+        )
+        .map_err(|f| f.error)
+        .unwrap();
+        assert_eq!(vec!["YES\n", "YES\n", "YES\n", "NO\n"], answers);
+    }
+
+    #[test]
+    fn test_check_batched_modifications() {
+        check_batched_modifications(&["+arg(a).\n+att(a,a).".to_string(), "".to_string()]).unwrap();
+        let error = check_batched_modifications(&[
+            "+arg(a).".to_string(),
+            "+arg(b).\natt(b,a).".to_string(),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            "while reading the modifications of step 2",
+            error.to_string()
+        );
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_execute_dynamics_two_dyn_acceptance_statuses() {
-        let mut modifications = BufReader::new("+arg(a).\n+arg(a).\n".as_bytes());
-        let answer_reader = QueryType::DC("a".to_string()).answer_reading_function();
-        let mut cursor = Cursor::new(vec![]);
-        let mut child_stdout = BufReader::new("YES\nYES\nNO\n".as_bytes());
-        execute_dynamics(
-            &mut modifications,
-            answer_reader,
-            &mut cursor,
-            &mut child_stdout,
+    fn test_run_solver_approximate() {
+        let script =
+            r#"echo "YES 0.8"; while read l; do [ -z "$l" ] && exit 0; echo UNKNOWN; done"#;
+        let arguments = vec!["-c".to_string(), script.to_string()];
+        let mut solution_reader = SolutionReader::default();
+        solution_reader.set_approximate(true);
+        let mut answers = vec![];
+        run_solver(
+            &SolverCommand {
+                solver: "sh",
+                arguments: &arguments,
+                instance: None,
+                warmstart: None,
+                remote: None,
+                container: None,
+                tee_file: None,
+                solution_reader,
+                keep_going: false,
+                fast_forward: 0,
+                batched: false,
+                timeout: None,
+            },
+            &["+arg(b).".to_string()],
+            None,
+            None,
+            &QueryType::DC("a".to_string()),
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
         )
+        .map_err(|f| f.error)
         .unwrap();
-        println!("{:?}", child_stdout);
-        let mut out = Vec::new();
-        cursor.seek(SeekFrom::Start(0)).unwrap();
-        cursor.read_to_end(&mut out).unwrap();
-        let child_stdin = String::from_utf8(out).unwrap();
-        assert_eq!("+arg(a).\n+arg(a).\n\n", child_stdin);
+        assert_eq!(vec!["YES 0.8\n", "UNKNOWN\n"], answers);
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_execute_dynamics_wrong_answer() {
-        let mut modifications = BufReader::new("+arg(a).\n".as_bytes());
-        let answer_reader = QueryType::DC("a".to_string()).answer_reading_function();
-        let mut cursor = Cursor::new(vec![]);
-        let mut child_stdout = BufReader::new("foo\n".as_bytes());
-        assert!(execute_dynamics(
-            &mut modifications,
-            answer_reader,
-            &mut cursor,
-            &mut child_stdout,
+    fn test_run_solver_unknown_allowed() {
+        let script = r#"echo TIMEOUT; while read l; do [ -z "$l" ] && exit 0; echo NO; done"#;
+        let arguments = vec!["-c".to_string(), script.to_string()];
+        let mut solution_reader = SolutionReader::default();
+        solution_reader.set_unknown_allowed(true);
+        let mut answers = vec![];
+        run_solver(
+            &SolverCommand {
+                solver: "sh",
+                arguments: &arguments,
+                instance: None,
+                warmstart: None,
+                remote: None,
+                container: None,
+                tee_file: None,
+                solution_reader,
+                keep_going: false,
+                fast_forward: 0,
+                batched: false,
+                timeout: None,
+            },
+            &["+arg(b).".to_string()],
+            None,
+            None,
+            &QueryType::DS("a".to_string()),
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
+        )
+        .map_err(|f| f.error)
+        .unwrap();
+        assert_eq!(vec!["UNKNOWN\n", "NO\n"], answers);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_solver_warmstart() {
+        let script = r#"read w; read e; [ "$w" = "[a]" ] && [ -z "$e" ] && echo YES || echo NO; while read l; do [ -z "$l" ] && exit 0; echo NO; done"#;
+        let mut answers = vec![];
+        let arguments = vec!["-c".to_string(), script.to_string()];
+        run_solver(
+            &SolverCommand {
+                solver: "sh",
+                arguments: &arguments,
+                instance: None,
+                warmstart: Some(&["[a]".to_string()]),
+                remote: None,
+                container: None,
+                tee_file: None,
+                solution_reader: SolutionReader::default(),
+                keep_going: false,
+                fast_forward: 0,
+                batched: false,
+                timeout: None,
+            },
+            &["+arg(b).".to_string()],
+            None,
+            None,
+            &QueryType::DC("a".to_string()),
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
+        )
+        .map_err(|f| f.error)
+        .unwrap();
+        assert_eq!(vec!["YES\n", "NO\n"], answers);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_solver_stdin_instance() {
+        let script = r#"[ "$1" = "-" ] || exit 1; n=0; while read l; do [ -z "$l" ] && break; n=$((n+1)); done; echo $n; while read l; do [ -z "$l" ] && exit 0; echo 0; done"#;
+        let mut answers = vec![];
+        let arguments = vec![
+            "-c".to_string(),
+            script.to_string(),
+            "sh".to_string(),
+            "-".to_string(),
+        ];
+        run_solver(
+            &SolverCommand {
+                solver: "sh",
+                arguments: &arguments,
+                instance: Some(&["arg(a).".to_string(), "arg(b).".to_string()]),
+                warmstart: None,
+                remote: None,
+                container: None,
+                tee_file: None,
+                solution_reader: SolutionReader::default(),
+                keep_going: false,
+                fast_forward: 0,
+                batched: false,
+                timeout: None,
+            },
+            &["+arg(c).".to_string()],
+            None,
+            None,
+            &QueryType::CE,
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
+        )
+        .map_err(|f| f.error)
+        .unwrap();
+        assert_eq!(vec!["2\n", "0\n"], answers);
+    }
+
+    #[test]
+    fn test_decompress_instance() {
+        let path = std::env::temp_dir().join(format!("idw-test-{}.apx.gz", std::process::id()));
+        let mut writer = compression::create(&path).unwrap();
+        writeln!(writer, "arg(a).").unwrap();
+        writer.finish().unwrap();
+        let instance = decompress_instance(&path.to_string_lossy(), &TempFiles::default())
+            .unwrap()
+            .unwrap();
+        let decompressed_path = instance.path().to_path_buf();
+        assert_eq!(
+            "arg(a).\n",
+            std::fs::read_to_string(&decompressed_path).unwrap()
+        );
+        drop(instance);
+        assert!(!decompressed_path.exists());
+        std::fs::remove_file(&path).unwrap();
+        assert!(decompress_instance("Cargo.toml", &TempFiles::default())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_fast_forward() {
+        let path = std::env::temp_dir().join(format!("idw-test-ff-{}.apx", std::process::id()));
+        std::fs::write(&path, "arg(a).\narg(b).\natt(a,b).\n").unwrap();
+        let path = path.to_string_lossy().to_string();
+        let instance = fast_forward(
+            &path,
+            "apx",
+            &["-att(a,b).".to_string(), "+arg(c).".to_string()],
+            &TempFiles::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            "arg(a).\narg(b).\narg(c).\n",
+            std::fs::read_to_string(instance.path()).unwrap()
+        );
+        assert!(fast_forward(
+            &path,
+            "apx",
+            &["-arg(d).".to_string()],
+            &TempFiles::default()
         )
         .is_err());
+        assert!(fast_forward(&path, "tgf", &[], &TempFiles::default()).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_check_instance() {
+        let path = std::env::temp_dir().join(format!("idw-test-check-{}.apx", std::process::id()));
+        std::fs::write(&path, "arg(a).\narg(a).\natt(a,a).\natt(a,a).\n").unwrap();
+        let path = path.to_string_lossy().to_string();
+        assert_eq!(2, check_instance(&path, "apx").unwrap());
+        assert!(check_instance(&path, "tgf").is_err());
+        std::fs::write(&path, "arg(a).\natt(a,b).\n").unwrap();
+        assert!(check_instance(&path, "apx").is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_check_modification_dialect() {
+        let path =
+            std::env::temp_dir().join(format!("idw-test-dialect-{}.apx", std::process::id()));
+        std::fs::write(&path, "arg(a).\narg(b).\natt(a,b).\n").unwrap();
+        let path = path.to_string_lossy().to_string();
+        let labels = vec!["-att(a,b).".to_string(), "+arg(c).".to_string()];
+        let numeric = vec!["-att(1,2).".to_string(), "+arg(3).\n+att(3,1).".to_string()];
+        assert!(check_modification_dialect(&path, "apx", &labels, false)
+            .unwrap()
+            .is_none());
+        assert!(check_modification_dialect(&path, "tgf", &numeric, false)
+            .unwrap()
+            .is_none());
+        assert!(check_modification_dialect(&path, "apx", &numeric, false).is_err());
+        assert!(check_modification_dialect(&path, NUMERIC_FORMAT, &labels, true).is_err());
+        assert_eq!(
+            Some(vec![
+                "-att(a,b).".to_string(),
+                "+arg(a3).\n+att(a3,a).".to_string()
+            ]),
+            check_modification_dialect(&path, "apx", &numeric, true).unwrap()
+        );
+        assert!(
+            check_modification_dialect(&path, "apx", &["+att(1,3).".to_string()], true).is_err()
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_remove_instance_argument() {
+        let mut arguments = QueryType::DC("a".to_string()).command_arguments("DC-CO-D", "-", "apx");
+        remove_instance_argument(&mut arguments);
+        assert_eq!(vec!["-p", "DC-CO-D", "-fo", "apx", "-a", "a"], arguments);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_solver_tee() {
+        let tee_path =
+            std::env::temp_dir().join(format!("idw-test-tee-{}.apxm", std::process::id()));
+        let tee_file = tee_path.to_string_lossy().to_string();
+        let arguments = vec![
+            "-c".to_string(),
+            "read w; read e; echo YES; while read l; do [ -z \"$l\" ] && exit 0; echo NO; done"
+                .to_string(),
+        ];
+        run_solver(
+            &SolverCommand {
+                solver: "sh",
+                arguments: &arguments,
+                instance: None,
+                warmstart: Some(&["[a]".to_string()]),
+                remote: None,
+                container: None,
+                tee_file: Some(&tee_file),
+                solution_reader: SolutionReader::default(),
+                keep_going: false,
+                fast_forward: 0,
+                batched: false,
+                timeout: None,
+            },
+            &["+arg(b).".to_string(), "+att(b,a).".to_string()],
+            None,
+            None,
+            &QueryType::DC("a".to_string()),
+            &mut |_| Ok(()),
+        )
+        .map_err(|f| f.error)
+        .unwrap();
+        assert_eq!(
+            "[a]\n\n+arg(b).\n+att(b,a).\n\n",
+            std::fs::read_to_string(&tee_path).unwrap()
+        );
+        std::fs::remove_file(&tee_path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_solver_track_state() {
+        let arguments = vec![
+            "-c".to_string(),
+            "echo YES; while read l; do echo YES; done".to_string(),
+        ];
+        let initial_state = FrameworkState::new(
+            &AspartixReader::default()
+                .read(&mut "arg(a).\n".as_bytes())
+                .unwrap(),
+        );
+        let mut n_answers = 0;
+        let failure = run_solver(
+            &SolverCommand {
+                solver: "sh",
+                arguments: &arguments,
+                instance: None,
+                warmstart: None,
+                remote: None,
+                container: None,
+                tee_file: None,
+                solution_reader: SolutionReader::default(),
+                keep_going: false,
+                fast_forward: 0,
+                batched: false,
+                timeout: None,
+            },
+            &["+arg(b).".to_string(), "+att(b,z).".to_string()],
+            Some(&initial_state),
+            None,
+            &QueryType::DC("a".to_string()),
+            &mut |_| {
+                n_answers += 1;
+                Ok(())
+            },
+        )
+        .unwrap_err();
+        assert!(!failure.crashed);
+        assert_eq!(2, n_answers);
+        let status_error = failure.error.downcast_ref::<ExitStatusError>().unwrap();
+        assert_eq!(MALFORMED_INPUT, status_error.status());
+        assert!(status_error
+            .causes()
+            .contains(&"no such argument: z".to_string()));
+    }
+
+    #[test]
+    fn test_run_solver_tcp() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let session = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut output = stream.try_clone().unwrap();
+            writeln!(output, "YES").unwrap();
+            let mut received = vec![];
+            for l in BufReader::new(stream).lines() {
+                let line = l.unwrap();
+                if line.is_empty() {
+                    break;
+                }
+                writeln!(output, "NO").unwrap();
+                received.push(line);
+            }
+            received
+        });
+        let remote = Remote::Tcp(address.clone());
+        let mut answers = vec![];
+        run_solver(
+            &SolverCommand {
+                solver: &address,
+                arguments: &[],
+                instance: None,
+                warmstart: None,
+                remote: Some(&remote),
+                container: None,
+                tee_file: None,
+                solution_reader: SolutionReader::default(),
+                keep_going: false,
+                fast_forward: 0,
+                batched: false,
+                timeout: None,
+            },
+            &["+arg(b).".to_string(), "+att(b,a).".to_string()],
+            None,
+            None,
+            &QueryType::DC("a".to_string()),
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
+        )
+        .map_err(|f| f.error)
+        .unwrap();
+        assert_eq!(vec!["YES\n", "NO\n", "NO\n"], answers);
+        assert_eq!(vec!["+arg(b).", "+att(b,a)."], session.join().unwrap());
+    }
+
+    #[test]
+    fn test_run_solver_tcp_closed() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let session = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            writeln!(stream, "YES").unwrap();
+        });
+        let remote = Remote::Tcp(address.clone());
+        let failure = run_solver(
+            &SolverCommand {
+                solver: &address,
+                arguments: &[],
+                instance: None,
+                warmstart: None,
+                remote: Some(&remote),
+                container: None,
+                tee_file: None,
+                solution_reader: SolutionReader::default(),
+                keep_going: false,
+                fast_forward: 0,
+                batched: false,
+                timeout: None,
+            },
+            &["+arg(b).".to_string()],
+            None,
+            None,
+            &QueryType::DC("a".to_string()),
+            &mut |_| Ok(()),
+        )
+        .unwrap_err();
+        session.join().unwrap();
+        assert!(failure.crashed);
     }
 }
@@ -0,0 +1,29 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! The library part of the ICCMA'21 dynamics wrapper, allowing other tools to run solvers on dynamic frameworks without calling the wrapper binary.
+//!
+//! The [`runner`] module implements the protocol between the wrapper and the solvers.
+//! The `iccma_dynamics_ffi` crate (in `local_crates`) exposes it to C and C++ programs through a shared and a static library.
+//!
+//! [`runner`]: runner/index.html
+
+#![warn(missing_docs)]
+
+pub mod runner;
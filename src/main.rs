@@ -19,6 +19,27 @@
 
 mod app;
 
+use app::analyze_command::AnalyzeDynamicsCommand;
+use app::anonymize_command::AnonymizeCommand;
+use app::batch_command::BatchCommand;
+use app::check_command::CheckCommand;
+use app::compile_dynamics_command::CompileDynamicsCommand;
+use app::convert_dynamics_command::ConvertDynamicsCommand;
+use app::diff_command::DiffCommand;
+use app::enforce_command::EnforceCommand;
+#[cfg(feature = "testing")]
+use app::fuzz_command::FuzzSolverCommand;
+#[cfg(feature = "testing")]
+use app::generate_command::GenerateCommand;
+use app::report_command::ReportCommand;
+#[cfg(feature = "testing")]
+use app::sample_accept_command::SampleAcceptCommand;
+use app::serve_command::ServeCommand;
+use app::solve_command::SolveCommand;
+use app::stats_command::StatsCommand;
+use app::validate_command::ValidateCommand;
+use app::viz_command::VizCommand;
+use app::viz_dynamics_command::VizDynamicsCommand;
 use app::wrap_command::WrapCommand;
 use crusti_app_helper::{AppHelper, Command, LicenseCommand};
 
@@ -29,10 +50,32 @@ fn main() {
         "Emmanuel Lonca <lonca@cril.fr>",
         "An app for Feature Models.",
     );
-    let commands: Vec<Box<dyn Command>> = vec![
+    #[allow(unused_mut)]
+    let mut commands: Vec<Box<dyn Command>> = vec![
         Box::new(WrapCommand::new()),
+        Box::new(CheckCommand::new()),
+        Box::new(AnonymizeCommand::new()),
+        Box::new(CompileDynamicsCommand::new()),
+        Box::new(ConvertDynamicsCommand::new()),
+        Box::new(DiffCommand::new()),
+        Box::new(ValidateCommand::new()),
+        Box::new(BatchCommand::new()),
+        Box::new(ReportCommand::new()),
+        Box::new(SolveCommand::new()),
+        Box::new(AnalyzeDynamicsCommand::new()),
+        Box::new(StatsCommand::new()),
+        Box::new(EnforceCommand::new()),
+        Box::new(VizCommand::new()),
+        Box::new(VizDynamicsCommand::new()),
+        Box::new(ServeCommand::new()),
         Box::new(LicenseCommand::new(include_str!("../LICENSE").to_string())),
     ];
+    #[cfg(feature = "testing")]
+    commands.extend(vec![
+        Box::new(FuzzSolverCommand::new()) as Box<dyn Command>,
+        Box::new(GenerateCommand::new()),
+        Box::new(SampleAcceptCommand::new()),
+    ]);
     for c in commands {
         app.add_command(c);
     }
@@ -0,0 +1,2477 @@
+// iccma21-dynamics-wrapper
+// Copyright (C) 2020  Artois University and CNRS
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// Contributors:
+//   *   CRIL - initial API and implementation
+
+//! The execution of a solver on a dynamics, following the protocol of the dynamic tracks of ICCMA'21.
+//!
+//! The solver is given the initial framework and the query on its command line.
+//! It first answers the query for the initial framework on its standard output, and then waits for a modification on its standard input.
+//! Each time it receives a modification, it answers the query for the modified framework; an empty line (or the end of its input) ends the dynamics.
+//!
+//! [`run_solver`] runs a solver on a whole dynamics; [`execute_dynamics`] implements the protocol on any pair of streams (e.g. those of a remote solver).
+//! A [`SolverSession`] runs a solver on a dynamics given one modification at a time.
+//! Both drive the solver through a [`SolverProcess`], which relays its standard error and kills it when it reaches the time limits or the cancellation given by [`RunLimits`].
+//!
+//! The errors returned by these functions are built upon a [`WrapError`] giving the kind of the failure, which can be retrieved with `downcast_ref`.
+//!
+//! The names of the tasks accepted in problem names (`SE`, `EE`, `CE`, `DC` and `DS` by default) are given by a [`TaskRegistry`], which can be extended with new task names without changing the wrapper.
+//!
+//! [`run_solver`]: fn.run_solver.html
+//! [`execute_dynamics`]: fn.execute_dynamics.html
+//! [`SolverSession`]: struct.SolverSession.html
+//! [`SolverProcess`]: struct.SolverProcess.html
+//! [`RunLimits`]: struct.RunLimits.html
+//! [`WrapError`]: enum.WrapError.html
+//! [`TaskRegistry`]: struct.TaskRegistry.html
+use std::{
+    cell::Cell,
+    collections::BTreeMap,
+    convert::TryFrom,
+    fmt::Display,
+    future::Future,
+    io::{BufRead, BufReader, BufWriter, Read, Write},
+    process::{Command, ExitStatus, Stdio},
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context, Result};
+use crusti_arg::{
+    dynamics,
+    solutions::{self, ApproximateStatus, ExtensionSetWriter, SolutionReader},
+    ArgumentSet,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    runtime::Runtime,
+    sync::watch,
+    task::JoinHandle,
+    time::Instant,
+};
+
+/// The type of the functions receiving the answers relayed at each step.
+pub type AnswerConsumer<'a> = dyn FnMut(String) -> Result<()> + 'a;
+
+/// The type of the functions used to read the answers of the solver and to format them for the user.
+pub type AnswerReadingFunction = Box<dyn Fn(&mut dyn BufRead) -> Result<String>>;
+
+/// The answer relayed for a step whose answer could not be read, when the run keeps going (see [`keep_going`]).
+///
+/// [`keep_going`]: fn.keep_going.html
+pub const FAILED_ANSWER: &str = "FAILED\n";
+
+/// Wraps an answer reading function so that the answers which cannot be read are replaced by [`FAILED_ANSWER`] instead of stopping the run.
+///
+/// The reading errors are given to the failure handler, with the step of the answer; the steps are counted from 0, each call to the returned function reading the answer of the next step.
+/// Since the output of the solver consumed while reading a malformed answer is lost, the next answers are read after it.
+///
+/// # Example
+///
+/// ```
+/// # use iccma_dynamics::runner::{self, QueryType};
+/// let read_answer = runner::keep_going(
+///     QueryType::DC("a".to_string()).answer_reading_function(),
+///     Box::new(|step, _| assert_eq!(1, step)),
+/// );
+/// assert_eq!("YES\n", read_answer(&mut "YES\n".as_bytes()).unwrap());
+/// assert_eq!(runner::FAILED_ANSWER, read_answer(&mut "MAYBE\n".as_bytes()).unwrap());
+/// assert_eq!("NO\n", read_answer(&mut "NO\n".as_bytes()).unwrap());
+/// ```
+///
+/// [`FAILED_ANSWER`]: constant.FAILED_ANSWER.html
+pub fn keep_going(
+    answer_reading_function: AnswerReadingFunction,
+    on_failure: Box<dyn Fn(usize, anyhow::Error)>,
+) -> AnswerReadingFunction {
+    let step = Cell::new(0);
+    Box::new(move |reader| {
+        let current_step = step.replace(step.get() + 1);
+        Ok(answer_reading_function(reader).unwrap_or_else(|e| {
+            on_failure(current_step, e);
+            FAILED_ANSWER.to_string()
+        }))
+    })
+}
+
+/// The kind of failure of a solver run, allowing the callers to react to it without parsing the error messages.
+///
+/// The errors returned by the functions of this module are built upon a `WrapError` when the kind of the failure is known.
+/// The `WrapError` may be the context of another error, which keeps the cause of the failure in the error chain.
+///
+/// # Example
+///
+/// ```
+/// # use iccma_dynamics::runner::{self, QueryType, RunLimits, WrapError};
+/// let error = runner::run_solver(
+///     "/nonexistent/solver",
+///     &[],
+///     &[],
+///     &QueryType::SE,
+///     &RunLimits::default(),
+///     &mut |_| Ok(()),
+/// )
+/// .unwrap_err();
+/// assert!(matches!(
+///     error.downcast_ref::<WrapError>(),
+///     Some(WrapError::SolverSpawn { .. })
+/// ));
+/// ```
+#[derive(Debug)]
+pub enum WrapError {
+    /// The modification at the given line (starting at 1) could not be read or prepared for the solver.
+    ModificationParse {
+        /// the line of the modification
+        line: usize,
+    },
+    /// The answer of the solver for a step could not be read.
+    SolverAnswerParse {
+        /// the step the answer was expected for
+        step: usize,
+        /// the output of the solver consumed while reading the answer
+        raw: String,
+    },
+    /// The solver started answering a step before receiving its modification.
+    EarlyAnswer {
+        /// the last step answered in sync
+        step: usize,
+    },
+    /// The solver gave more answers than expected.
+    ExtraAnswers {
+        /// the last step of the dynamics
+        step: usize,
+    },
+    /// The solver could not be spawned.
+    SolverSpawn {
+        /// the solver command
+        solver: String,
+    },
+    /// The solver exited with a failure status before the end of the dynamics.
+    SolverExit {
+        /// the exit status of the solver
+        status: ExitStatus,
+    },
+    /// An I/O error occurred while sending a step to the solver or reading its output.
+    Io {
+        /// the step during which the error occurred
+        step: usize,
+    },
+    /// The solver was killed because it reached a limit of its run (see [`RunLimits`]).
+    ///
+    /// [`RunLimits`]: struct.RunLimits.html
+    SolverStopped {
+        /// the reason of the stop
+        stop: Stop,
+    },
+}
+
+impl Display for WrapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WrapError::ModificationParse { line } => {
+                write!(f, "while reading the modification at line {}", line)
+            }
+            WrapError::SolverAnswerParse { step, .. } => {
+                write!(f, "while reading the answer for step {}", step)
+            }
+            WrapError::EarlyAnswer { step } => write!(
+                f,
+                "solver output desynchronized at step {}: the solver gave an answer before receiving the next modification",
+                step
+            ),
+            WrapError::ExtraAnswers { step } => write!(
+                f,
+                "solver output desynchronized after step {}: the solver gave more answers than expected",
+                step
+            ),
+            WrapError::SolverSpawn { solver } => {
+                write!(f, r#"while spawning solver "{}""#, solver)
+            }
+            WrapError::SolverExit { status } => write!(f, "the solver failed ({})", status),
+            WrapError::Io { step } => {
+                write!(f, "while communicating with the solver at step {}", step)
+            }
+            WrapError::SolverStopped { stop } => write!(f, "{}", stop),
+        }
+    }
+}
+
+impl std::error::Error for WrapError {}
+
+/// The kind of query a solver answers at each step, given by the problem it solves.
+#[derive(Clone, Debug, PartialEq)]
+pub enum QueryType {
+    /// Giving an extension (SE problems).
+    SE,
+    /// Giving all the extensions (EE problems).
+    EE,
+    /// Counting the extensions (CE problems).
+    CE,
+    /// Deciding the credulous acceptance of an argument (DC problems).
+    DC(String),
+    /// Deciding the skeptical acceptance of an argument (DS problems).
+    DS(String),
+}
+
+impl QueryType {
+    /// Builds the command line arguments of a solver answering this query, following the ICCMA'21 conventions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use iccma_dynamics::runner::QueryType;
+    /// assert_eq!(
+    ///     vec!["-p", "DC-CO-D", "-f", "af.apx", "-fo", "apx", "-a", "a"],
+    ///     QueryType::DC("a".to_string()).command_arguments("DC-CO-D", "af.apx", "apx")
+    /// );
+    /// ```
+    pub fn command_arguments(
+        &self,
+        problem: &str,
+        input_file: &str,
+        file_format: &str,
+    ) -> Vec<String> {
+        let mut default_arguments = vec![
+            "-p".to_string(),
+            problem.to_string(),
+            "-f".to_string(),
+            input_file.to_string(),
+            "-fo".to_string(),
+            file_format.to_string(),
+        ];
+        match self {
+            QueryType::SE | QueryType::EE | QueryType::CE => default_arguments,
+            QueryType::DC(arg) | QueryType::DS(arg) => {
+                default_arguments.push("-a".to_string());
+                default_arguments.push(arg.clone());
+                default_arguments
+            }
+        }
+    }
+
+    /// Returns the function reading an answer of the solver for this query, and formatting it for the user.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use iccma_dynamics::runner::QueryType;
+    /// let read_answer = QueryType::SE.answer_reading_function();
+    /// assert_eq!("[a, b]\n", read_answer(&mut "[a,b]\n".as_bytes()).unwrap());
+    /// ```
+    pub fn answer_reading_function(&self) -> AnswerReadingFunction {
+        self.answer_reading_function_with(SolutionReader::default())
+    }
+
+    /// Returns the function reading an answer of the solver for this query using the given solution reader, which sets the level of conformance to the output grammar and the grammar of the labels.
+    ///
+    /// If the reader expects answers from approximate solvers, the DC and DS answers may come with a confidence, or be `UNKNOWN`.
+    /// If the reader allows unknown answers, the DC and DS answers may be `UNKNOWN`, and `TIMEOUT` answers are written as `UNKNOWN`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use iccma_dynamics::runner::QueryType;
+    /// # use crusti_arg::solutions::{Conformance, LabelGrammar, SolutionReader};
+    /// let read_answer = QueryType::SE.answer_reading_function_with(SolutionReader::new(Conformance::Strict));
+    /// assert_eq!("[a, b]\n", read_answer(&mut "[a,b]\n".as_bytes()).unwrap());
+    /// assert!(read_answer(&mut "[a, b ]\n".as_bytes()).is_err());
+    /// let mut solution_reader = SolutionReader::default();
+    /// solution_reader.set_label_grammar(LabelGrammar::Numeric);
+    /// let read_answer = QueryType::SE.answer_reading_function_with(solution_reader);
+    /// assert_eq!("[1, 17]\n", read_answer(&mut "[1,17]\n".as_bytes()).unwrap());
+    /// let mut solution_reader = SolutionReader::default();
+    /// solution_reader.set_approximate(true);
+    /// let read_answer = QueryType::DC("a".to_string()).answer_reading_function_with(solution_reader);
+    /// assert_eq!("YES 0.9\n", read_answer(&mut "YES  0.9\n".as_bytes()).unwrap());
+    /// assert_eq!("UNKNOWN\n", read_answer(&mut "UNKNOWN\n".as_bytes()).unwrap());
+    /// let mut solution_reader = SolutionReader::default();
+    /// solution_reader.set_unknown_allowed(true);
+    /// let read_answer = QueryType::DS("a".to_string()).answer_reading_function_with(solution_reader);
+    /// assert_eq!("NO\n", read_answer(&mut "NO\n".as_bytes()).unwrap());
+    /// assert_eq!("UNKNOWN\n", read_answer(&mut "TIMEOUT\n".as_bytes()).unwrap());
+    /// ```
+    pub fn answer_reading_function_with(&self, reader: SolutionReader) -> AnswerReadingFunction {
+        fn compose_rw<T, R, W>(reading_fn: R, writing_fn: &'static W) -> AnswerReadingFunction
+        where
+            R: Fn(&mut dyn BufRead) -> Result<T> + 'static,
+            W: Fn(&mut dyn Write, &T) -> Result<()>,
+        {
+            Box::new(move |reader| -> Result<String> {
+                let read = reading_fn(reader).context("while reading child process stdout")?;
+                let mut out = Vec::new();
+                writing_fn(&mut out, &read)?;
+                Ok(String::from_utf8(out).unwrap())
+            })
+        }
+        match self {
+            QueryType::SE => compose_rw(
+                move |r: &mut dyn BufRead| reader.read_extension(r),
+                &solutions::write_extension,
+            ),
+            QueryType::EE => compose_rw(
+                move |r: &mut dyn BufRead| reader.read_extension_set(r),
+                &|w, s: &Vec<ArgumentSet<String>>| {
+                    let mut set_writer = ExtensionSetWriter::new(w);
+                    set_writer.begin()?;
+                    for e in s {
+                        set_writer.write_extension(e)?;
+                    }
+                    set_writer.end()
+                },
+            ),
+            QueryType::CE => compose_rw(
+                move |r: &mut dyn BufRead| reader.read_big_extension_count(r),
+                &|w, c: &String| solutions::write_big_extension_count(w, c),
+            ),
+            QueryType::DC(_) | QueryType::DS(_) if reader.is_approximate() => compose_rw(
+                move |r: &mut dyn BufRead| reader.read_approximate_status(r),
+                &|w, s: &ApproximateStatus| solutions::write_approximate_status(w, *s),
+            ),
+            QueryType::DC(_) | QueryType::DS(_) if reader.is_unknown_allowed() => compose_rw(
+                move |r: &mut dyn BufRead| reader.read_acceptance_status_or_unknown(r),
+                &|w, s: &Option<bool>| solutions::write_acceptance_status_or_unknown(w, *s),
+            ),
+            QueryType::DC(_) | QueryType::DS(_) => compose_rw(
+                move |r: &mut dyn BufRead| reader.read_acceptance_status(r),
+                &|w, b: &bool| solutions::write_acceptance_status(w, *b),
+            ),
+        }
+    }
+}
+
+/// Builds the query of a dynamic problem (e.g. `DC-CO-D`), given the queried argument if any.
+///
+/// The task names are the ones of the default [`TaskRegistry`].
+///
+/// # Example
+///
+/// ```
+/// # use iccma_dynamics::runner::QueryType;
+/// # use std::convert::TryFrom;
+/// assert_eq!(
+///     QueryType::DS("a".to_string()),
+///     QueryType::try_from(("DS-PR-D", Some("a"))).unwrap()
+/// );
+/// assert!(QueryType::try_from(("DS-PR", Some("a"))).is_err());
+/// ```
+///
+/// [`TaskRegistry`]: struct.TaskRegistry.html
+impl TryFrom<(&str, Option<&str>)> for QueryType {
+    type Error = anyhow::Error;
+
+    fn try_from(value: (&str, Option<&str>)) -> Result<Self, Self::Error> {
+        TaskRegistry::default().query(value.0, value.1)
+    }
+}
+
+/// The kind of answer expected for a task, given by the query of one of the standard tasks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskKind {
+    /// An extension, as for SE problems.
+    SE,
+    /// A set of extensions, as for EE problems.
+    EE,
+    /// A number of extensions, as for CE problems.
+    CE,
+    /// The credulous acceptance status of an argument, as for DC problems.
+    DC,
+    /// The skeptical acceptance status of an argument, as for DS problems.
+    DS,
+}
+
+impl FromStr for TaskKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "SE" => Ok(TaskKind::SE),
+            "EE" => Ok(TaskKind::EE),
+            "CE" => Ok(TaskKind::CE),
+            "DC" => Ok(TaskKind::DC),
+            "DS" => Ok(TaskKind::DS),
+            _ => Err(anyhow!(r#"unknown task kind "{}""#, s)),
+        }
+    }
+}
+
+/// The semantics accepted in the names of the dynamic problems.
+const SEMANTICS: [&str; 7] = ["CO", "GR", "PR", "ST", "SST", "STG", "ID"];
+
+/// The task names accepted in the names of the dynamic problems, with the kind of answer they expect.
+///
+/// The default registry contains the standard tasks `SE`, `EE`, `CE`, `DC` and `DS`.
+/// New task names (e.g. the ones of future or approximate tracks) can be registered as aliases of the standard kinds, programmatically or by reading a task file.
+///
+/// # Example
+///
+/// ```
+/// # use iccma_dynamics::runner::{QueryType, TaskKind, TaskRegistry};
+/// let mut registry = TaskRegistry::default();
+/// assert!(registry.query("ES-CO-D", None).is_err());
+/// registry.register("ES", TaskKind::SE);
+/// assert_eq!(QueryType::SE, registry.query("ES-CO-D", None).unwrap());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaskRegistry {
+    tasks: BTreeMap<String, TaskKind>,
+}
+
+impl Default for TaskRegistry {
+    fn default() -> Self {
+        let mut registry = TaskRegistry {
+            tasks: BTreeMap::new(),
+        };
+        registry.register("SE", TaskKind::SE);
+        registry.register("EE", TaskKind::EE);
+        registry.register("CE", TaskKind::CE);
+        registry.register("DC", TaskKind::DC);
+        registry.register("DS", TaskKind::DS);
+        registry
+    }
+}
+
+impl TaskRegistry {
+    /// Registers a task name, expecting the answers of the given kind.
+    ///
+    /// A task already registered under this name is replaced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use iccma_dynamics::runner::{TaskKind, TaskRegistry};
+    /// let mut registry = TaskRegistry::default();
+    /// registry.register("DC-APX", TaskKind::DC);
+    /// assert_eq!(Some(TaskKind::DC), registry.kind("DC-APX"));
+    /// ```
+    pub fn register(&mut self, task: &str, kind: TaskKind) {
+        self.tasks.insert(task.to_string(), kind);
+    }
+
+    /// Returns the kind of answer expected for a task, if it is registered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use iccma_dynamics::runner::{TaskKind, TaskRegistry};
+    /// let registry = TaskRegistry::default();
+    /// assert_eq!(Some(TaskKind::CE), registry.kind("CE"));
+    /// assert_eq!(None, registry.kind("ES"));
+    /// ```
+    pub fn kind(&self, task: &str) -> Option<TaskKind> {
+        self.tasks.get(task).copied()
+    }
+
+    /// Iterates over the registered task names, in lexicographic order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use iccma_dynamics::runner::TaskRegistry;
+    /// let registry = TaskRegistry::default();
+    /// assert_eq!(vec!["CE", "DC", "DS", "EE", "SE"], registry.tasks().collect::<Vec<&str>>());
+    /// ```
+    pub fn tasks(&self) -> impl Iterator<Item = &str> {
+        self.tasks.keys().map(|t| t.as_str())
+    }
+
+    /// Registers the tasks listed by a task file.
+    ///
+    /// Each line of the file gives a task name and the kind of its answers (`SE`, `EE`, `CE`, `DC` or `DS`), separated by spaces.
+    /// Empty lines and lines beginning with `#` are ignored.
+    /// Since the semantics and the `-D` suffix follow the last dashes of a problem name, task names may contain dashes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use iccma_dynamics::runner::{TaskKind, TaskRegistry};
+    /// let mut registry = TaskRegistry::default();
+    /// registry.read(&mut "# future tasks\nES SE\nDC-APX DC\n".as_bytes()).unwrap();
+    /// assert_eq!(Some(TaskKind::SE), registry.kind("ES"));
+    /// assert_eq!(Some(TaskKind::DC), registry.kind("DC-APX"));
+    /// assert!(registry.read(&mut "ES\n".as_bytes()).is_err());
+    /// ```
+    pub fn read(&mut self, reader: &mut dyn BufRead) -> Result<()> {
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.context("while reading the task file")?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let words = line.split_whitespace().collect::<Vec<&str>>();
+            match words.as_slice() {
+                [task, kind] => {
+                    let kind = kind.parse::<TaskKind>().with_context(|| {
+                        format!("while reading line {} of the task file", i + 1)
+                    })?;
+                    self.register(task, kind);
+                }
+                _ => {
+                    return Err(anyhow!(
+                        r#"line {} of the task file must give a task name and a task kind, got "{}""#,
+                        i + 1,
+                        line
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds the query of a dynamic problem (e.g. `DC-CO-D`), given the queried argument if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use iccma_dynamics::runner::{QueryType, TaskKind, TaskRegistry};
+    /// let mut registry = TaskRegistry::default();
+    /// registry.register("DC-APX", TaskKind::DC);
+    /// assert_eq!(
+    ///     QueryType::DC("a".to_string()),
+    ///     registry.query("DC-APX-GR-D", Some("a")).unwrap()
+    /// );
+    /// assert!(registry.query("DC-APX-GR-D", None).is_err());
+    /// ```
+    pub fn query(&self, problem: &str, arg: Option<&str>) -> Result<QueryType> {
+        let err_builder = |s| anyhow!(r#""{}" is not a valid dynamic track"#, s);
+        let mut splits = problem.rsplitn(3, '-');
+        let (suffix, semantics, task) = match (splits.next(), splits.next(), splits.next()) {
+            (Some(suffix), Some(semantics), Some(task)) => (suffix, semantics, task),
+            _ => return Err(err_builder(problem)),
+        };
+        if !SEMANTICS.contains(&semantics) || suffix != "D" {
+            return Err(err_builder(problem));
+        }
+        let ok_if_no_arg = |q: QueryType| {
+            if arg.is_none() {
+                Ok(q)
+            } else {
+                Err(anyhow!(
+                    r#"problem "{}" does not require an argument but one is provided"#,
+                    problem
+                ))
+            }
+        };
+        let on_missing_arg = || {
+            anyhow!(
+                r#"problem "{}" requires an argument none is provided"#,
+                problem
+            )
+        };
+        match self.kind(task) {
+            Some(TaskKind::SE) => ok_if_no_arg(QueryType::SE),
+            Some(TaskKind::EE) => ok_if_no_arg(QueryType::EE),
+            Some(TaskKind::CE) => ok_if_no_arg(QueryType::CE),
+            Some(TaskKind::DC) => Ok(QueryType::DC(arg.ok_or_else(on_missing_arg)?.to_string())),
+            Some(TaskKind::DS) => Ok(QueryType::DS(arg.ok_or_else(on_missing_arg)?.to_string())),
+            None => Err(err_builder(problem)),
+        }
+    }
+}
+
+/// Runs a solver on a whole dynamics, giving its answer for each step to the consumer.
+///
+/// The solver is spawned with the provided arguments (see [`QueryType::command_arguments`]), and receives the modifications on its standard input.
+/// Once all the modifications are sent, the standard input of the solver is closed, and an error is returned if the solver gives more answers than expected.
+/// The number of modifications sent to the solver is returned.
+///
+/// The solver is driven by a [`SolverProcess`]: its standard error is relayed to the one of the current process, and it is killed if it reaches a limit of its run, in which case the error is built upon [`WrapError::SolverStopped`].
+///
+/// # Example
+///
+/// ```
+/// # use iccma_dynamics::runner::{self, QueryType, RunLimits};
+/// # #[cfg(unix)]
+/// # {
+/// // a fake solver answering YES for the initial framework, and NO after each modification
+/// let arguments = vec![
+///     "-c".to_string(),
+///     "echo YES; while read l; do [ -z \"$l\" ] && exit 0; echo NO; done".to_string(),
+/// ];
+/// let mut answers = vec![];
+/// let n_modifications = runner::run_solver(
+///     "sh",
+///     &arguments,
+///     &["+arg(b).".to_string(), "+att(b,a).".to_string()],
+///     &QueryType::DC("a".to_string()),
+///     &RunLimits::default(),
+///     &mut |a| {
+///         answers.push(a);
+///         Ok(())
+///     },
+/// )
+/// .unwrap();
+/// assert_eq!(2, n_modifications);
+/// assert_eq!(vec!["YES\n", "NO\n", "NO\n"], answers);
+/// # }
+/// ```
+///
+/// [`QueryType::command_arguments`]: enum.QueryType.html#method.command_arguments
+/// [`SolverProcess`]: struct.SolverProcess.html
+/// [`WrapError::SolverStopped`]: enum.WrapError.html#variant.SolverStopped
+pub fn run_solver(
+    solver: &str,
+    arguments: &[String],
+    modifications: &[String],
+    query: &QueryType,
+    limits: &RunLimits,
+    answer_consumer: &mut AnswerConsumer,
+) -> Result<usize> {
+    let mut process = spawn_solver(solver, arguments, limits)?;
+    let (child_stdin, child_stdout) = process.pipes();
+    let mut child_stdin = BufWriter::new(child_stdin);
+    let mut child_stdout = BufReader::new(child_stdout);
+    let result = execute_dynamics(
+        &mut modifications.join("\n").as_bytes(),
+        query.answer_reading_function(),
+        &Ok,
+        answer_consumer,
+        &mut child_stdin,
+        &mut child_stdout,
+    );
+    drop(child_stdin);
+    let result = result
+        .and_then(|last_step| check_end_of_output(&mut child_stdout, last_step).map(|_| last_step));
+    terminate_solver(&process, result)
+}
+
+fn spawn_solver(solver: &str, arguments: &[String], limits: &RunLimits) -> Result<SolverProcess> {
+    let mut command = Command::new(solver);
+    command.args(arguments);
+    SolverProcess::spawn(command, limits).with_context(|| WrapError::SolverSpawn {
+        solver: solver.to_string(),
+    })
+}
+
+/// Waits for the end of a solver once its run is over, killing it if the run failed while it is still running.
+fn terminate_solver(process: &SolverProcess, result: Result<usize>) -> Result<usize> {
+    let killed = result.is_err() && process.stopped().is_none() && !process.has_exited();
+    if killed {
+        process.kill();
+    }
+    let status = process.wait();
+    if let Some(stop) = process.stopped() {
+        let error = match result {
+            Err(e) => e,
+            Ok(_) => anyhow::Error::new(stop.error()),
+        };
+        return Err(error.context(WrapError::SolverStopped { stop }));
+    }
+    let status = status.context("while waiting for the end of the solver")?;
+    match result {
+        Err(e) if !killed && !status.success() => Err(e.context(WrapError::SolverExit { status })),
+        _ => result,
+    }
+}
+
+/// A solver run on a dynamics given one modification at a time, for callers computing the dynamics while it is run.
+///
+/// [`SolverSession::start`] spawns the solver and reads its answer for the initial framework; each call to [`SolverSession::push_modification`] sends a modification to the solver and reads its answer for the modified framework.
+/// [`SolverSession::finish`] ends the dynamics and waits for the end of the solver; a session dropped before being finished kills its solver.
+///
+/// The solver is driven by a [`SolverProcess`], and is killed if it reaches a limit of its run; a step time limit (see [`RunLimits::set_step_timeout`]) is the time given to the solver to answer each modification.
+/// Once an error occurred while communicating with the solver, the session should be finished or dropped.
+///
+/// # Example
+///
+/// ```
+/// # use iccma_dynamics::runner::{QueryType, RunLimits, SolverSession};
+/// # #[cfg(unix)]
+/// # {
+/// // a fake solver answering YES for the initial framework, and NO after each modification
+/// let arguments = vec![
+///     "-c".to_string(),
+///     "echo YES; while read l; do [ -z \"$l\" ] && exit 0; echo NO; done".to_string(),
+/// ];
+/// let mut session = SolverSession::start(
+///     "sh",
+///     &arguments,
+///     &QueryType::DC("a".to_string()),
+///     &RunLimits::default(),
+/// )
+/// .unwrap();
+/// assert_eq!("YES\n", session.answer());
+/// assert_eq!("NO\n", session.push_modification("+arg(b).").unwrap());
+/// assert!(session.push_modification("+arg(b)").is_err());
+/// assert_eq!(1, session.finish().unwrap());
+/// # }
+/// ```
+///
+/// [`SolverSession::start`]: struct.SolverSession.html#method.start
+/// [`SolverSession::push_modification`]: struct.SolverSession.html#method.push_modification
+/// [`SolverSession::finish`]: struct.SolverSession.html#method.finish
+/// [`SolverProcess`]: struct.SolverProcess.html
+/// [`RunLimits::set_step_timeout`]: struct.RunLimits.html#method.set_step_timeout
+pub struct SolverSession {
+    process: SolverProcess,
+    child_stdin: Option<BufWriter<SolverInput>>,
+    child_stdout: BufReader<SolverOutput>,
+    answer_reading_function: AnswerReadingFunction,
+    recorded: Vec<u8>,
+    step: usize,
+    answer: String,
+}
+
+impl SolverSession {
+    /// Spawns a solver with the provided arguments (see [`QueryType::command_arguments`]) and reads its answer for the initial framework.
+    ///
+    /// # Arguments
+    ///
+    /// * `solver` - the solver command
+    /// * `arguments` - the command line arguments of the solver
+    /// * `query` - the query answered by the solver
+    /// * `limits` - the limits of the run of the solver
+    ///
+    /// [`QueryType::command_arguments`]: enum.QueryType.html#method.command_arguments
+    pub fn start(
+        solver: &str,
+        arguments: &[String],
+        query: &QueryType,
+        limits: &RunLimits,
+    ) -> Result<Self> {
+        let mut process = spawn_solver(solver, arguments, limits)?;
+        let (child_stdin, child_stdout) = process.pipes();
+        let mut session = SolverSession {
+            process,
+            child_stdin: Some(BufWriter::new(child_stdin)),
+            child_stdout: BufReader::new(child_stdout),
+            answer_reading_function: query.answer_reading_function(),
+            recorded: vec![],
+            step: 0,
+            answer: String::new(),
+        };
+        match session.read_answer() {
+            Ok(answer) => {
+                session.answer = answer;
+                Ok(session)
+            }
+            Err(e) => Err(session.terminate(Err(e)).unwrap_err()),
+        }
+    }
+
+    /// Returns the current step, i.e. the number of modifications sent to the solver.
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Returns the answer of the solver for the current step.
+    pub fn answer(&self) -> &str {
+        &self.answer
+    }
+
+    /// Sends a modification to the solver and returns its answer for the modified framework.
+    ///
+    /// The modification is checked before it is sent; a malformed modification is not sent, and the session can go on.
+    ///
+    /// # Arguments
+    ///
+    /// * `modification` - the modification, e.g. `+att(a,b).`
+    pub fn push_modification(&mut self, modification: &str) -> Result<&str> {
+        let line = modification.trim_end_matches(['\n', '\r']);
+        let step = self.step + 1;
+        dynamics::read_modification(line)
+            .with_context(|| WrapError::ModificationParse { line: step })?;
+        let child_stdin = self
+            .child_stdin
+            .as_mut()
+            .ok_or_else(|| anyhow!("the input of the solver is closed"))?;
+        writeln!(child_stdin, "{}", line)
+            .and_then(|_| child_stdin.flush())
+            .with_context(|| WrapError::Io { step })
+            .map_err(|e| self.stop_context(e))?;
+        self.step = step;
+        self.answer = self.read_answer().map_err(|e| self.stop_context(e))?;
+        Ok(&self.answer)
+    }
+
+    /// Ends the dynamics and waits for the end of the solver, returning the number of modifications sent to it.
+    ///
+    /// An error is returned if the solver gives more answers than expected or fails.
+    pub fn finish(mut self) -> Result<usize> {
+        let step = self.step;
+        let result = match self.child_stdin.take() {
+            Some(mut child_stdin) => writeln!(child_stdin)
+                .and_then(|_| child_stdin.flush())
+                .with_context(|| WrapError::Io { step }),
+            None => Ok(()),
+        }
+        .and_then(|_| check_end_of_output(&mut self.child_stdout, step))
+        .map(|_| step);
+        self.terminate(result)
+    }
+
+    fn read_answer(&mut self) -> Result<String> {
+        read_step_answer(
+            &*self.answer_reading_function,
+            &mut self.child_stdout,
+            self.step,
+            &mut self.recorded,
+        )
+    }
+
+    /// Adds the reason of the stop of the solver to an error, if it was killed because it reached a limit of its run.
+    fn stop_context(&self, error: anyhow::Error) -> anyhow::Error {
+        match self.process.stopped() {
+            Some(stop) => error.context(WrapError::SolverStopped { stop }),
+            None => error,
+        }
+    }
+
+    /// Waits for the end of the solver, killing it if the run failed while it is still running.
+    fn terminate(&mut self, result: Result<usize>) -> Result<usize> {
+        self.child_stdin = None;
+        terminate_solver(&self.process, result)
+    }
+}
+
+/// Runs the dynamics protocol with a child process.
+///
+/// Each modification line is flushed to the child as soon as the answer for the previous step is read, and each answer is given to the consumer before the next modification is sent.
+/// The number of modifications sent to the child is returned.
+///
+/// Steps are numbered by the number of modifications sent before them (step 0 is the initial framework).
+/// Modification lines may end with CRLF; the carriage returns are not sent to the child.
+/// If the child starts answering a step before the corresponding modification is sent, an error is returned.
+pub fn execute_dynamics<F, R: Read>(
+    modifications: &mut dyn BufRead,
+    answer_reading_function: Box<F>,
+    modification_translator: &dyn Fn(String) -> Result<String>,
+    answer_consumer: &mut AnswerConsumer,
+    child_stdin: &mut dyn Write,
+    child_stdout: &mut BufReader<R>,
+) -> Result<usize>
+where
+    F: Fn(&mut dyn BufRead) -> Result<String> + ?Sized,
+{
+    let mut step = 0;
+    let mut mod_line = String::new();
+    let mut recorded = vec![];
+    loop {
+        let modification_error = || WrapError::ModificationParse { line: step + 1 };
+        if !read_modification_line(modifications, &mut mod_line).with_context(modification_error)?
+            || mod_line.is_empty()
+        {
+            break;
+        }
+        answer_consumer(read_step_answer(
+            &answer_reading_function,
+            child_stdout,
+            step,
+            &mut recorded,
+        )?)?;
+        mod_line = modification_translator(std::mem::take(&mut mod_line))
+            .with_context(modification_error)?;
+        writeln!(child_stdin, "{}", mod_line)
+            .and_then(|_| child_stdin.flush())
+            .with_context(|| WrapError::Io { step: step + 1 })?;
+        step += 1;
+    }
+    answer_consumer(read_step_answer(
+        &answer_reading_function,
+        child_stdout,
+        step,
+        &mut recorded,
+    )?)?;
+    writeln!(child_stdin)
+        .and_then(|_| child_stdin.flush())
+        .with_context(|| WrapError::Io { step })?;
+    Ok(step)
+}
+
+/// The number of modifications sent at once by [`fast_forward_dynamics`](fn.fast_forward_dynamics.html) before the answers of the corresponding steps are discarded.
+pub const FAST_FORWARD_BATCH_SIZE: usize = 64;
+
+/// Sends the first modifications of the dynamics to a child process without relaying the answers of the corresponding steps.
+///
+/// The first `n_steps` modification lines are consumed and sent to the child by batches of [`FAST_FORWARD_BATCH_SIZE`] lines, without waiting for the answers between them.
+/// The answers of the steps of a batch are read and discarded before the next batch is sent, so that the pipes between the processes never fill up.
+/// The answer of the step following the last modification sent is left to the caller, which usually goes on with [`execute_dynamics`] on the remaining modifications.
+/// The number of modifications sent to the child is returned; it is lower than `n_steps` if the dynamics ends before.
+///
+/// # Example
+///
+/// ```
+/// # use iccma_dynamics::runner::{self, QueryType};
+/// # use std::io::{BufReader, Read};
+/// let mut modifications = "+arg(b).\n+arg(c).\n+arg(d).\n".as_bytes();
+/// let mut child_stdin = Vec::new();
+/// let mut child_stdout = BufReader::new("NO\nNO\nYES\nYES\n".as_bytes());
+/// let n_sent = runner::fast_forward_dynamics(
+///     2,
+///     &mut modifications,
+///     &*QueryType::DC("a".to_string()).answer_reading_function(),
+///     &Ok,
+///     &mut child_stdin,
+///     &mut child_stdout,
+/// )
+/// .unwrap();
+/// assert_eq!(2, n_sent);
+/// assert_eq!("+arg(b).\n+arg(c).\n", String::from_utf8(child_stdin).unwrap());
+/// assert_eq!("+arg(d).\n", String::from_utf8(modifications.to_vec()).unwrap());
+/// let mut remaining = String::new();
+/// child_stdout.read_to_string(&mut remaining).unwrap();
+/// assert_eq!("YES\nYES\n", remaining);
+/// ```
+///
+/// [`FAST_FORWARD_BATCH_SIZE`]: constant.FAST_FORWARD_BATCH_SIZE.html
+/// [`execute_dynamics`]: fn.execute_dynamics.html
+pub fn fast_forward_dynamics<F, R: Read>(
+    n_steps: usize,
+    modifications: &mut dyn BufRead,
+    answer_reading_function: &F,
+    modification_translator: &dyn Fn(String) -> Result<String>,
+    child_stdin: &mut dyn Write,
+    child_stdout: &mut BufReader<R>,
+) -> Result<usize>
+where
+    F: Fn(&mut dyn BufRead) -> Result<String> + ?Sized,
+{
+    let mut mod_line = String::new();
+    let mut recorded = vec![];
+    let mut n_sent = 0;
+    while n_sent < n_steps {
+        let batch_start = n_sent;
+        let batch_end = (n_sent + FAST_FORWARD_BATCH_SIZE).min(n_steps);
+        while n_sent < batch_end {
+            let modification_error = || WrapError::ModificationParse { line: n_sent + 1 };
+            if !read_modification_line(modifications, &mut mod_line)
+                .with_context(modification_error)?
+                || mod_line.is_empty()
+            {
+                break;
+            }
+            mod_line = modification_translator(std::mem::take(&mut mod_line))
+                .with_context(modification_error)?;
+            writeln!(child_stdin, "{}", mod_line)
+                .with_context(|| WrapError::Io { step: n_sent + 1 })?;
+            n_sent += 1;
+        }
+        child_stdin
+            .flush()
+            .with_context(|| WrapError::Io { step: n_sent })?;
+        for step in batch_start..n_sent {
+            read_recorded_answer(answer_reading_function, child_stdout, step, &mut recorded)?;
+        }
+        if n_sent < batch_end {
+            break;
+        }
+    }
+    Ok(n_sent)
+}
+
+/// Runs the dynamics protocol with a child process, for a dynamics written in the batched dialect.
+///
+/// In this dialect, a step may contain several modification lines, terminated by an empty line or a `query.` line (see `crusti_arg::dynamics::read_batches`).
+/// All the modifications of a step are sent to the child, followed by a `query.` line, before the answer for this step is read; the child must thus support this dialect.
+/// Apart from that, this function behaves like [`execute_dynamics`]; the number of steps sent to the child is returned.
+///
+/// # Example
+///
+/// ```
+/// # use iccma_dynamics::runner::{self, QueryType};
+/// # use std::io::BufReader;
+/// let mut child_stdin = Vec::new();
+/// let mut answers = vec![];
+/// let n_steps = runner::execute_batched_dynamics(
+///     &mut "+arg(b).\n+att(b,a).\n".as_bytes(),
+///     QueryType::DC("a".to_string()).answer_reading_function(),
+///     &Ok,
+///     &mut |a| {
+///         answers.push(a);
+///         Ok(())
+///     },
+///     &mut child_stdin,
+///     &mut BufReader::new("YES\n".as_bytes()),
+/// );
+/// // the answer of the last step is missing
+/// assert!(n_steps.is_err());
+/// assert_eq!("+arg(b).\n+att(b,a).\nquery.\n", String::from_utf8(child_stdin).unwrap());
+/// assert_eq!(vec!["YES\n"], answers);
+/// ```
+///
+/// [`execute_dynamics`]: fn.execute_dynamics.html
+pub fn execute_batched_dynamics<F, R: Read>(
+    modifications: &mut dyn BufRead,
+    answer_reading_function: Box<F>,
+    modification_translator: &dyn Fn(String) -> Result<String>,
+    answer_consumer: &mut AnswerConsumer,
+    child_stdin: &mut dyn Write,
+    child_stdout: &mut BufReader<R>,
+) -> Result<usize>
+where
+    F: Fn(&mut dyn BufRead) -> Result<String> + ?Sized,
+{
+    let mut step = 0;
+    let mut batch = vec![];
+    let mut recorded = vec![];
+    let mut lines = modifications.lines().enumerate();
+    loop {
+        let (ends_step, at_end) = match lines.next() {
+            Some((i, l)) => {
+                let line = l.with_context(|| WrapError::ModificationParse { line: i + 1 })?;
+                let line = line.trim_end_matches('\r');
+                if line.trim() == dynamics::QUERY_MARKER {
+                    (true, false)
+                } else if line.trim().is_empty() {
+                    (!batch.is_empty(), false)
+                } else {
+                    batch.push((i + 1, line.to_string()));
+                    (false, false)
+                }
+            }
+            None => (!batch.is_empty(), true),
+        };
+        if ends_step {
+            answer_consumer(read_step_answer(
+                &answer_reading_function,
+                child_stdout,
+                step,
+                &mut recorded,
+            )?)?;
+            for (line, modification) in batch.drain(..) {
+                let modification = modification_translator(modification)
+                    .with_context(|| WrapError::ModificationParse { line })?;
+                writeln!(child_stdin, "{}", modification)
+                    .with_context(|| WrapError::Io { step: step + 1 })?;
+            }
+            writeln!(child_stdin, "{}", dynamics::QUERY_MARKER)
+                .and_then(|_| child_stdin.flush())
+                .with_context(|| WrapError::Io { step: step + 1 })?;
+            step += 1;
+        }
+        if at_end {
+            break;
+        }
+    }
+    answer_consumer(read_step_answer(
+        &answer_reading_function,
+        child_stdout,
+        step,
+        &mut recorded,
+    )?)?;
+    writeln!(child_stdin)
+        .and_then(|_| child_stdin.flush())
+        .with_context(|| WrapError::Io { step })?;
+    Ok(step)
+}
+
+/// Reads the next modification line into a buffer, without its line ending, returning `false` at the end of the input.
+///
+/// The buffer is cleared before reading, so that a single buffer can be reused for all the steps.
+/// Any carriage return preceding the line feed is removed.
+fn read_modification_line(
+    modifications: &mut dyn BufRead,
+    line: &mut String,
+) -> std::io::Result<bool> {
+    line.clear();
+    if modifications.read_line(line)? == 0 {
+        return Ok(false);
+    }
+    if line.ends_with('\n') {
+        line.pop();
+    }
+    line.truncate(line.trim_end_matches('\r').len());
+    Ok(true)
+}
+
+fn read_step_answer<F, R>(
+    answer_reading_function: &F,
+    child_stdout: &mut BufReader<R>,
+    step: usize,
+    recorded: &mut Vec<u8>,
+) -> Result<String>
+where
+    F: Fn(&mut dyn BufRead) -> Result<String> + ?Sized,
+    R: Read,
+{
+    let answer = read_recorded_answer(answer_reading_function, child_stdout, step, recorded)?;
+    if has_answer_content(child_stdout.buffer()) {
+        return Err(anyhow::Error::new(WrapError::EarlyAnswer { step }));
+    }
+    Ok(answer)
+}
+
+/// Reads the answer of a step, keeping the output consumed while reading it in the error if the answer is malformed.
+///
+/// The output is recorded into a buffer which is cleared first, so that a single buffer can be reused for all the steps.
+fn read_recorded_answer<F, R>(
+    answer_reading_function: &F,
+    child_stdout: &mut BufReader<R>,
+    step: usize,
+    recorded: &mut Vec<u8>,
+) -> Result<String>
+where
+    F: Fn(&mut dyn BufRead) -> Result<String> + ?Sized,
+    R: Read,
+{
+    recorded.clear();
+    let mut recorder = RecordingReader {
+        inner: child_stdout,
+        consumed: recorded,
+    };
+    let answer = answer_reading_function(&mut recorder);
+    answer.with_context(|| WrapError::SolverAnswerParse {
+        step,
+        raw: String::from_utf8_lossy(recorded).to_string(),
+    })
+}
+
+/// Checks whether some solver output contains something else than whitespace and comment lines (beginning with `#`).
+fn has_answer_content(output: &[u8]) -> bool {
+    output.split(|b| *b == b'\n').any(|line| {
+        let mut content = line.iter().skip_while(|b| b.is_ascii_whitespace());
+        !matches!(content.next(), None | Some(b'#'))
+    })
+}
+
+/// A reader keeping a copy of the data consumed from an underlying buffered reader.
+struct RecordingReader<'a, R: Read> {
+    inner: &'a mut BufReader<R>,
+    consumed: &'a mut Vec<u8>,
+}
+
+impl<R: Read> Read for RecordingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<R: Read> BufRead for RecordingReader<'_, R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.consumed.extend_from_slice(&self.inner.buffer()[..amt]);
+        self.inner.consume(amt);
+    }
+}
+
+/// Writes an answer to the user output, flushing it before the next step begins.
+///
+/// Answer consumers relaying the answers to the user should call this function on a buffered writer, so that each answer is written at once and the answers are never interleaved with other outputs.
+///
+/// # Example
+///
+/// ```
+/// # use iccma_dynamics::runner;
+/// let mut out = std::io::BufWriter::new(vec![]);
+/// runner::write_answer(&mut out, "YES\n").unwrap();
+/// assert!(out.buffer().is_empty());
+/// assert_eq!(b"YES\n", out.get_ref().as_slice());
+/// ```
+pub fn write_answer(writer: &mut dyn Write, answer: &str) -> Result<()> {
+    const CONTEXT_WRITING: &str = "while writing an answer";
+    writer
+        .write_all(answer.as_bytes())
+        .context(CONTEXT_WRITING)?;
+    writer.flush().context(CONTEXT_WRITING)
+}
+
+/// Checks the child gave no output after the answer of the last step, apart from whitespace and comment lines.
+///
+/// This function reads the output of the child until its end, and must thus be called after the input of the child is closed.
+pub fn check_end_of_output(child_stdout: &mut dyn BufRead, last_step: usize) -> Result<()> {
+    let mut remaining = String::new();
+    child_stdout
+        .read_to_string(&mut remaining)
+        .with_context(|| WrapError::Io { step: last_step })?;
+    if !has_answer_content(remaining.as_bytes()) {
+        Ok(())
+    } else {
+        Err(anyhow::Error::new(WrapError::ExtraAnswers {
+            step: last_step,
+        }))
+    }
+}
+
+/// The time given to a solver to exit when checking whether it crashed.
+const EXIT_DELAY: Duration = Duration::from_millis(100);
+
+/// The period at which the hooks of a solver are asked whether it must be killed while it is waited for.
+const INTERRUPTION_CHECK_PERIOD: Duration = Duration::from_millis(50);
+
+/// The time given to the processes spawned by a solver to close its standard error once it exited.
+const STDERR_DRAIN_DELAY: Duration = Duration::from_millis(100);
+
+/// The reason why a solver was killed before the end of its run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stop {
+    /// The solver reached its time limit.
+    Timeout,
+    /// The run of the solver was cancelled.
+    Cancellation,
+}
+
+impl Stop {
+    fn error(self) -> std::io::Error {
+        match self {
+            Stop::Timeout => std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "the solver reached its time limit",
+            ),
+            Stop::Cancellation => std::io::Error::other("the run of the solver was cancelled"),
+        }
+    }
+}
+
+impl Display for Stop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stop::Timeout => write!(f, "the solver reached its time limit and was killed"),
+            Stop::Cancellation => write!(f, "the run of the solver was cancelled"),
+        }
+    }
+}
+
+/// A switch cancelling the runs of the solvers it was given to, which are then killed.
+///
+/// Cancellations are cheap to clone, and all the clones share the same switch.
+///
+/// # Example
+///
+/// ```
+/// # use iccma_dynamics::runner::{Cancellation, RunLimits};
+/// let cancellation = Cancellation::new();
+/// let mut limits = RunLimits::default();
+/// limits.set_cancellation(&cancellation);
+/// assert!(!cancellation.is_cancelled());
+/// cancellation.clone().cancel();
+/// assert!(cancellation.is_cancelled());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Cancellation(Arc<watch::Sender<bool>>);
+
+impl Default for Cancellation {
+    fn default() -> Self {
+        Cancellation(Arc::new(watch::channel(false).0))
+    }
+}
+
+impl Cancellation {
+    /// Builds a new switch, which is not cancelled.
+    pub fn new() -> Self {
+        Cancellation::default()
+    }
+
+    /// Cancels the runs of the solvers given this switch.
+    pub fn cancel(&self) {
+        self.0.send_replace(true);
+    }
+
+    /// Returns `true` iff the switch was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        *self.0.borrow()
+    }
+}
+
+/// The limits of the runs of solvers.
+///
+/// By default, solvers have no time limit and cannot be cancelled.
+/// A solver reaching a limit is killed, and the pending operation on its pipes fails.
+#[derive(Clone, Debug, Default)]
+pub struct RunLimits {
+    timeout: Option<Duration>,
+    step_timeout: Option<Duration>,
+    cancellation: Option<Cancellation>,
+}
+
+impl RunLimits {
+    /// Sets the time given to the solver for its whole run, from the moment it is spawned.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - the time limit
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = Some(timeout);
+    }
+
+    /// Sets the time given to the solver for each step, i.e. from the moment it is spawned or last written to, until it is written to again or it exits.
+    ///
+    /// The time spent by the caller between two steps is thus not counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `step_timeout` - the time limit of each step
+    pub fn set_step_timeout(&mut self, step_timeout: Duration) {
+        self.step_timeout = Some(step_timeout);
+    }
+
+    /// Sets the switch cancelling the run of the solver.
+    ///
+    /// # Arguments
+    ///
+    /// * `cancellation` - the switch
+    pub fn set_cancellation(&mut self, cancellation: &Cancellation) {
+        self.cancellation = Some(cancellation.clone());
+    }
+}
+
+/// Hooks called by a [`SolverProcess`] along the life of its process, e.g. to kill the processes spawned by the solver with it.
+///
+/// All the methods do nothing by default.
+///
+/// [`SolverProcess`]: struct.SolverProcess.html
+pub trait ProcessHooks: Send + Sync {
+    /// Called once the process is spawned; if an error is returned, the process is killed and waited for, and the spawn fails.
+    fn spawned(&mut self, _child: &tokio::process::Child) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called before the process is waited for, after which its id may be reused by another process.
+    fn waiting(&self) {}
+
+    /// Called when the process was waited for a short time but is still running (see [`SolverProcess::has_exited`]).
+    ///
+    /// [`SolverProcess::has_exited`]: struct.SolverProcess.html#method.has_exited
+    fn still_running(&self) {}
+
+    /// Called before the process is killed; the process was not waited for yet.
+    fn killing(&self) {}
+
+    /// Returns `true` iff the process must be killed while it is waited for.
+    ///
+    /// This function is polled periodically while the process is waited for.
+    fn interrupted(&self) -> bool {
+        false
+    }
+}
+
+struct NoHooks;
+
+impl ProcessHooks for NoHooks {}
+
+/// The state of a solver process shared with its pipes.
+struct ProcessState {
+    runtime: Runtime,
+    child: Mutex<tokio::process::Child>,
+    hooks: Box<dyn ProcessHooks>,
+    stderr_relay: Mutex<Option<JoinHandle<()>>>,
+    deadline: Option<Instant>,
+    step_timeout: Option<Duration>,
+    step_deadline: Mutex<Option<Instant>>,
+    cancellation: Option<watch::Receiver<bool>>,
+    stop: Mutex<Option<Stop>>,
+}
+
+/// A running solver process, with piped standard streams, driven by a single-threaded asynchronous runtime.
+///
+/// The pipes of the solver ([`SolverProcess::pipes`]) are synchronous readers and writers: each read or write blocks on the runtime, which meanwhile relays the standard error of the solver to the one of the current process and watches the limits of the run.
+/// When a limit is reached (see [`RunLimits`]), the solver is killed and the pending read or write fails; [`SolverProcess::stopped`] then gives the reason of the stop.
+/// No thread is dedicated to a solver: the runtime only makes progress while the solver is read from, written to or waited for.
+///
+/// A solver process is killed when it is dropped, unless it was waited for.
+///
+/// # Example
+///
+/// ```
+/// # use iccma_dynamics::runner::{RunLimits, SolverProcess, Stop};
+/// # use std::io::Read;
+/// # #[cfg(unix)]
+/// # {
+/// let mut limits = RunLimits::default();
+/// limits.set_timeout(std::time::Duration::from_millis(50));
+/// let mut command = std::process::Command::new("sleep");
+/// command.arg("60");
+/// let mut process = SolverProcess::spawn(command, &limits).unwrap();
+/// let (_input, mut output) = process.pipes();
+/// assert!(output.read(&mut [0; 16]).is_err());
+/// assert_eq!(Some(Stop::Timeout), process.stopped());
+/// assert!(!process.wait().unwrap().success());
+/// # }
+/// ```
+///
+/// [`SolverProcess::pipes`]: struct.SolverProcess.html#method.pipes
+/// [`SolverProcess::stopped`]: struct.SolverProcess.html#method.stopped
+/// [`RunLimits`]: struct.RunLimits.html
+pub struct SolverProcess {
+    state: Arc<ProcessState>,
+    pipes: Option<(tokio::process::ChildStdin, tokio::process::ChildStdout)>,
+}
+
+impl SolverProcess {
+    /// Spawns a solver process given its command, whose standard streams are replaced by pipes.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - the command of the solver
+    /// * `limits` - the limits of its run
+    pub fn spawn(command: Command, limits: &RunLimits) -> Result<Self> {
+        SolverProcess::spawn_with_hooks(command, limits, Box::new(NoHooks))
+    }
+
+    /// Spawns a solver process given its command, whose standard streams are replaced by pipes, and the hooks called along the life of the process.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - the command of the solver
+    /// * `limits` - the limits of its run
+    /// * `hooks` - the hooks
+    pub fn spawn_with_hooks(
+        mut command: Command,
+        limits: &RunLimits,
+        mut hooks: Box<dyn ProcessHooks>,
+    ) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("while creating the runtime of the solver")?;
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let mut child = {
+            let _context = runtime.enter();
+            tokio::process::Command::from(command).spawn()?
+        };
+        if let Err(e) = hooks.spawned(&child) {
+            let _ = child.start_kill();
+            let _ = runtime.block_on(child.wait());
+            return Err(e);
+        }
+        let stderr_relay = runtime.spawn(relay_stderr(child.stderr.take().unwrap()));
+        let now = Instant::now();
+        let pipes = Some((child.stdin.take().unwrap(), child.stdout.take().unwrap()));
+        Ok(SolverProcess {
+            state: Arc::new(ProcessState {
+                runtime,
+                child: Mutex::new(child),
+                hooks,
+                stderr_relay: Mutex::new(Some(stderr_relay)),
+                deadline: limits.timeout.map(|t| now + t),
+                step_timeout: limits.step_timeout,
+                step_deadline: Mutex::new(limits.step_timeout.map(|t| now + t)),
+                cancellation: limits.cancellation.as_ref().map(|c| c.0.subscribe()),
+                stop: Mutex::new(None),
+            }),
+            pipes,
+        })
+    }
+
+    /// Takes the standard input and output of the solver.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pipes were already taken.
+    pub fn pipes(&mut self) -> (SolverInput, SolverOutput) {
+        let (stdin, stdout) = self.pipes.take().expect("pipes already taken");
+        (
+            SolverInput {
+                state: Arc::clone(&self.state),
+                stdin,
+            },
+            SolverOutput {
+                state: Arc::clone(&self.state),
+                stdout,
+            },
+        )
+    }
+
+    /// Returns the reason why the solver was killed, if a limit of its run was reached.
+    pub fn stopped(&self) -> Option<Stop> {
+        *self.state.stop.lock().unwrap()
+    }
+
+    /// Checks whether the solver has exited, waiting a short time for it to terminate.
+    ///
+    /// The solver is not killed if the hooks report an interruption while it is waited for.
+    pub fn has_exited(&self) -> bool {
+        let state = &self.state;
+        let mut child = state.child.lock().unwrap();
+        state.hooks.waiting();
+        let exited = state
+            .runtime
+            .block_on(async { tokio::time::timeout(EXIT_DELAY, child.wait()).await })
+            .is_ok_and(|status| status.is_ok());
+        if !exited {
+            state.hooks.still_running();
+        }
+        exited
+    }
+
+    /// Kills the solver, unless it was already waited for.
+    pub fn kill(&self) {
+        self.state.kill(&mut self.state.child.lock().unwrap());
+    }
+
+    /// Waits for the end of the solver.
+    ///
+    /// If a limit of the run is reached in the meantime, the solver is killed and an error is returned; a solver already stopped is just waited for.
+    /// The solver is killed if the hooks report an interruption while it is waited for.
+    pub fn wait(&self) -> std::io::Result<ExitStatus> {
+        let state = &self.state;
+        let mut child = state.child.lock().unwrap();
+        let stderr_relay = state.stderr_relay.lock().unwrap().take();
+        state.hooks.waiting();
+        let already_stopped = self.stopped().is_some();
+        state.runtime.block_on(async {
+            let result = tokio::select! {
+                biased;
+                status = child.wait() => status,
+                stop = state.stopping(), if !already_stopped => {
+                    state.set_stop(stop);
+                    state.kill(&mut child);
+                    child.wait().await.and(Err(stop.error()))
+                }
+                _ = state.interruption() => {
+                    state.kill(&mut child);
+                    child.wait().await
+                }
+            };
+            if let Some(relay) = stderr_relay {
+                let _ = tokio::time::timeout(STDERR_DRAIN_DELAY, relay).await;
+            }
+            result
+        })
+    }
+}
+
+impl Drop for SolverProcess {
+    fn drop(&mut self) {
+        if let Ok(mut child) = self.state.child.lock() {
+            if child.id().is_some() {
+                self.state.kill(&mut child);
+                self.state.hooks.waiting();
+                let _ = self.state.runtime.block_on(child.wait());
+            }
+        }
+    }
+}
+
+impl ProcessState {
+    /// Kills the solver, unless it was already waited for (so that a reused process id is never targeted).
+    fn kill(&self, child: &mut tokio::process::Child) {
+        if child.id().is_some() {
+            self.hooks.killing();
+            let _ = child.start_kill();
+        }
+    }
+
+    fn set_stop(&self, stop: Stop) {
+        *self.stop.lock().unwrap() = Some(stop);
+    }
+
+    /// Completes when a limit of the run is reached, giving the reason of the stop.
+    async fn stopping(&self) -> Stop {
+        let step_deadline = *self.step_deadline.lock().unwrap();
+        let deadline = match (self.deadline, step_deadline) {
+            (Some(d1), Some(d2)) => Some(d1.min(d2)),
+            (d1, d2) => d1.or(d2),
+        };
+        let timeout = async {
+            match deadline {
+                Some(d) => tokio::time::sleep_until(d).await,
+                None => std::future::pending().await,
+            }
+        };
+        let cancellation = async {
+            match self.cancellation.clone() {
+                Some(mut c) => {
+                    let _ = c.wait_for(|cancelled| *cancelled).await;
+                }
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            _ = timeout => Stop::Timeout,
+            _ = cancellation => Stop::Cancellation,
+        }
+    }
+
+    /// Completes when the hooks report an interruption.
+    async fn interruption(&self) {
+        let mut interval = tokio::time::interval(INTERRUPTION_CHECK_PERIOD);
+        while !self.hooks.interrupted() {
+            interval.tick().await;
+        }
+    }
+
+    /// Runs an operation on the pipes of the solver, killing it if a limit of the run is reached first.
+    fn block_on<T>(
+        &self,
+        operation: impl Future<Output = std::io::Result<T>>,
+    ) -> std::io::Result<T> {
+        if let Some(stop) = *self.stop.lock().unwrap() {
+            return Err(stop.error());
+        }
+        self.runtime
+            .block_on(async {
+                tokio::select! {
+                    biased;
+                    stop = self.stopping() => Err(stop),
+                    result = operation => Ok(result),
+                }
+            })
+            .unwrap_or_else(|stop| {
+                self.set_stop(stop);
+                self.kill(&mut self.child.lock().unwrap());
+                Err(stop.error())
+            })
+    }
+
+    /// Starts a new step, whose time limit counts from now.
+    fn restart_step(&self) {
+        if let Some(t) = self.step_timeout {
+            *self.step_deadline.lock().unwrap() = Some(Instant::now() + t);
+        }
+    }
+}
+
+/// Copies the standard error of a solver to the one of the current process.
+async fn relay_stderr(mut stderr: tokio::process::ChildStderr) {
+    let mut buffer = [0; 4096];
+    while let Ok(n) = stderr.read(&mut buffer).await {
+        if n == 0 || std::io::stderr().write_all(&buffer[..n]).is_err() {
+            break;
+        }
+    }
+}
+
+/// The standard input of a solver process.
+///
+/// Each write to the input starts a new step of the run (see [`RunLimits::set_step_timeout`]).
+///
+/// [`RunLimits::set_step_timeout`]: struct.RunLimits.html#method.set_step_timeout
+pub struct SolverInput {
+    state: Arc<ProcessState>,
+    stdin: tokio::process::ChildStdin,
+}
+
+impl Write for SolverInput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.state.restart_step();
+        self.state.block_on(self.stdin.write(buf))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.state.restart_step();
+        self.state.block_on(self.stdin.flush())
+    }
+}
+
+/// The standard output of a solver process.
+pub struct SolverOutput {
+    state: Arc<ProcessState>,
+    stdout: tokio::process::ChildStdout,
+}
+
+impl Read for SolverOutput {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.state.block_on(self.stdout.read(buf))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crusti_arg::{solutions::Conformance, test_utils::WritableString};
+    use std::{cell::RefCell, rc::Rc};
+
+    /// A reader giving its content line by line, as a child process waiting for modifications would do.
+    #[derive(Debug)]
+    struct LineByLine(Vec<String>);
+
+    impl Read for LineByLine {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.0.is_empty() {
+                return Ok(0);
+            }
+            let line = self.0.remove(0);
+            buf[..line.len()].copy_from_slice(line.as_bytes());
+            Ok(line.len())
+        }
+    }
+
+    fn child_output(s: &str) -> BufReader<LineByLine> {
+        BufReader::new(LineByLine(
+            s.split_inclusive('\n').map(|l| l.to_string()).collect(),
+        ))
+    }
+
+    #[test]
+    fn test_execute_dynamics_no_dyn_acceptance_status() {
+        let mut modifications = BufReader::new("".as_bytes());
+        let answer_reader = QueryType::DC("a".to_string()).answer_reading_function();
+        let mut child_stdin = WritableString::default();
+        let mut child_stdout = child_output("YES\n");
+        execute_dynamics(
+            &mut modifications,
+            answer_reader,
+            &Ok,
+            &mut |_| Ok(()),
+            &mut child_stdin,
+            &mut child_stdout,
+        )
+        .unwrap();
+        assert_eq!("\n", child_stdin.as_str());
+    }
+
+    #[test]
+    fn test_execute_dynamics_one_dyn_acceptance_status() {
+        let mut modifications = BufReader::new("+arg(a).\n".as_bytes());
+        let answer_reader = QueryType::DC("a".to_string()).answer_reading_function();
+        let mut child_stdin = WritableString::default();
+        let mut child_stdout = child_output("YES\nNO\n");
+        execute_dynamics(
+            &mut modifications,
+            answer_reader,
+            &Ok,
+            &mut |_| Ok(()),
+            &mut child_stdin,
+            &mut child_stdout,
+        )
+        .unwrap();
+        assert_eq!("+arg(a).\n\n", child_stdin.as_str());
+    }
+
+    #[test]
+    fn test_execute_dynamics_two_dyn_acceptance_statuses() {
+        let mut modifications = BufReader::new("+arg(a).\n+arg(a).\n".as_bytes());
+        let answer_reader = QueryType::DC("a".to_string()).answer_reading_function();
+        let mut child_stdin = WritableString::default();
+        let mut child_stdout = child_output("YES\nYES\nNO\n");
+        execute_dynamics(
+            &mut modifications,
+            answer_reader,
+            &Ok,
+            &mut |_| Ok(()),
+            &mut child_stdin,
+            &mut child_stdout,
+        )
+        .unwrap();
+        println!("{:?}", child_stdout);
+        assert_eq!("+arg(a).\n+arg(a).\n\n", child_stdin.as_str());
+    }
+
+    #[test]
+    fn test_read_modification_line() {
+        let mut modifications = "+arg(a).\r\r\n\n-arg(a).".as_bytes();
+        let mut line = String::from("previous");
+        assert!(read_modification_line(&mut modifications, &mut line).unwrap());
+        assert_eq!("+arg(a).", line);
+        assert!(read_modification_line(&mut modifications, &mut line).unwrap());
+        assert_eq!("", line);
+        assert!(read_modification_line(&mut modifications, &mut line).unwrap());
+        assert_eq!("-arg(a).", line);
+        assert!(!read_modification_line(&mut modifications, &mut line).unwrap());
+    }
+
+    #[test]
+    fn test_execute_dynamics_crlf() {
+        let mut modifications = BufReader::new("+arg(a).\r\n\r\n+arg(b).\r\n".as_bytes());
+        let answer_reader = QueryType::EE.answer_reading_function();
+        let mut answers = vec![];
+        let mut child_stdin = WritableString::default();
+        let mut child_stdout = child_output("[\r\n[]\r\n]\r\n[\r\n[a]\r\n]\r\n");
+        execute_dynamics(
+            &mut modifications,
+            answer_reader,
+            &Ok,
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
+            &mut child_stdin,
+            &mut child_stdout,
+        )
+        .unwrap();
+        assert_eq!(vec!["[\n[]\n]\n", "[\n[a]\n]\n"], answers);
+        assert_eq!("+arg(a).\n\n", child_stdin.as_str());
+    }
+
+    /// A writer recording its content each time it is flushed.
+    #[derive(Default)]
+    struct FlushRecorder {
+        content: Vec<u8>,
+        flushes: Vec<String>,
+    }
+
+    impl Write for FlushRecorder {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.content.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.flushes
+                .push(String::from_utf8(self.content.clone()).unwrap());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_execute_dynamics_user_output() {
+        let mut modifications = BufReader::new("+arg(b).\n-arg(b).\n".as_bytes());
+        let answer_reader = QueryType::SE.answer_reading_function();
+        let mut child_stdout = child_output("[a]\n[ a,b ]\n[a]\n");
+        let mut user_output = BufWriter::new(FlushRecorder::default());
+        execute_dynamics(
+            &mut modifications,
+            answer_reader,
+            &Ok,
+            &mut |a| write_answer(&mut user_output, &a),
+            &mut std::io::sink(),
+            &mut child_stdout,
+        )
+        .unwrap();
+        assert_eq!(
+            vec!["[a]\n", "[a]\n[a, b]\n", "[a]\n[a, b]\n[a]\n"],
+            user_output.get_ref().flushes
+        );
+    }
+
+    #[test]
+    fn test_execute_dynamics_wrong_answer() {
+        let mut modifications = BufReader::new("+arg(a).\n".as_bytes());
+        let answer_reader = QueryType::DC("a".to_string()).answer_reading_function();
+        let mut child_stdin = WritableString::default();
+        let mut child_stdout = child_output("foo\n");
+        let err = execute_dynamics(
+            &mut modifications,
+            answer_reader,
+            &Ok,
+            &mut |_| Ok(()),
+            &mut child_stdin,
+            &mut child_stdout,
+        )
+        .unwrap_err();
+        match err.downcast_ref::<WrapError>() {
+            Some(WrapError::SolverAnswerParse { step, raw }) => {
+                assert_eq!(0, *step);
+                assert_eq!("foo\n", raw);
+            }
+            e => panic!("unexpected error kind: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_execute_dynamics_modification_error() {
+        let mut modifications = BufReader::new("+arg(a).\n+arg(b).\n".as_bytes());
+        let mut child_stdout = child_output("YES\nYES\n");
+        let err = execute_dynamics(
+            &mut modifications,
+            QueryType::DC("a".to_string()).answer_reading_function(),
+            &|l| {
+                if l == "+arg(b)." {
+                    Err(anyhow!("wrong modification"))
+                } else {
+                    Ok(l)
+                }
+            },
+            &mut |_| Ok(()),
+            &mut Vec::new(),
+            &mut child_stdout,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<WrapError>(),
+            Some(WrapError::ModificationParse { line: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_execute_dynamics_answers() {
+        let mut modifications = BufReader::new("+arg(a).\n".as_bytes());
+        let answer_reader = QueryType::DC("a".to_string()).answer_reading_function();
+        let mut answers = vec![];
+        let mut child_stdout = child_output("NO\nYES\n");
+        execute_dynamics(
+            &mut modifications,
+            answer_reader,
+            &Ok,
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
+            &mut Vec::new(),
+            &mut child_stdout,
+        )
+        .unwrap();
+        assert_eq!(vec!["NO\n".to_string(), "YES\n".to_string()], answers);
+    }
+
+    /// A fake child process, only answering once the modification of the current step has been flushed.
+    struct FakeChild {
+        pending: Vec<u8>,
+        received_lines: usize,
+        n_answers: usize,
+    }
+
+    struct FakeChildStdin(Rc<RefCell<FakeChild>>);
+
+    impl Write for FakeChildStdin {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().pending.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            let mut child = self.0.borrow_mut();
+            child.received_lines += child.pending.iter().filter(|b| **b == b'\n').count();
+            child.pending.clear();
+            Ok(())
+        }
+    }
+
+    struct FakeChildStdout(Rc<RefCell<FakeChild>>);
+
+    impl Read for FakeChildStdout {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let mut child = self.0.borrow_mut();
+            if child.n_answers > child.received_lines {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::WouldBlock,
+                    "deadlock: the modification was not flushed",
+                ));
+            }
+            child.n_answers += 1;
+            buf[..4].copy_from_slice(b"YES\n");
+            Ok(4)
+        }
+    }
+
+    #[test]
+    fn test_execute_dynamics_flushes_each_step() {
+        let child = Rc::new(RefCell::new(FakeChild {
+            pending: vec![],
+            received_lines: 0,
+            n_answers: 0,
+        }));
+        let mut child_stdin = BufWriter::new(FakeChildStdin(Rc::clone(&child)));
+        let mut child_stdout = BufReader::new(FakeChildStdout(Rc::clone(&child)));
+        let mut modifications = BufReader::new("+arg(b).\n+att(a,b).\n".as_bytes());
+        let mut answers = vec![];
+        execute_dynamics(
+            &mut modifications,
+            QueryType::DC("a".to_string()).answer_reading_function(),
+            &Ok,
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
+            &mut child_stdin,
+            &mut child_stdout,
+        )
+        .unwrap();
+        assert_eq!(3, answers.len());
+        assert_eq!(3, child.borrow().received_lines);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_dynamics_child_process() {
+        let mut process = std::process::Command::new("sh")
+            .args([
+                "-c",
+                "echo YES; while read l; do [ -z \"$l\" ] && exit 0; echo NO; done",
+            ])
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut child_stdin = BufWriter::new(process.stdin.take().unwrap());
+        let mut child_stdout = BufReader::new(process.stdout.take().unwrap());
+        let mut modifications = BufReader::new("+arg(b).\n+att(a,b).\n".as_bytes());
+        let mut out = Vec::new();
+        execute_dynamics(
+            &mut modifications,
+            QueryType::DC("a".to_string()).answer_reading_function(),
+            &Ok,
+            &mut |a| {
+                out.extend_from_slice(a.as_bytes());
+                Ok(())
+            },
+            &mut child_stdin,
+            &mut child_stdout,
+        )
+        .unwrap();
+        drop(child_stdin);
+        assert!(process.wait().unwrap().success());
+        assert_eq!("YES\nNO\nNO\n", String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn test_execute_dynamics_early_answer() {
+        let mut modifications = BufReader::new("+arg(a).\n+arg(b).\n".as_bytes());
+        let mut child_stdout = BufReader::new("YES\nYES\nNO\n".as_bytes());
+        let err = execute_dynamics(
+            &mut modifications,
+            QueryType::DC("a".to_string()).answer_reading_function(),
+            &Ok,
+            &mut |_| Ok(()),
+            &mut Vec::new(),
+            &mut child_stdout,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("step 0"));
+        assert!(matches!(
+            err.downcast_ref::<WrapError>(),
+            Some(WrapError::EarlyAnswer { step: 0 })
+        ));
+    }
+
+    #[test]
+    fn test_execute_dynamics_comments() {
+        let mut modifications = BufReader::new("+arg(b).\n".as_bytes());
+        let mut answers = vec![];
+        let mut child_stdout = child_output("# c\nYES\n# c\nNO\n# c\n");
+        execute_dynamics(
+            &mut modifications,
+            QueryType::DC("a".to_string()).answer_reading_function(),
+            &Ok,
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
+            &mut Vec::new(),
+            &mut child_stdout,
+        )
+        .unwrap();
+        assert_eq!(vec!["YES\n", "NO\n"], answers);
+        check_end_of_output(&mut child_stdout, 1).unwrap();
+        let mut child_stdout = child_output("# c\nYES\n");
+        assert!(execute_dynamics(
+            &mut "".as_bytes(),
+            QueryType::DC("a".to_string())
+                .answer_reading_function_with(SolutionReader::new(Conformance::Strict)),
+            &Ok,
+            &mut |_| Ok(()),
+            &mut Vec::new(),
+            &mut child_stdout,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_execute_dynamics_keep_going() {
+        let mut modifications = BufReader::new("+arg(b).\n+arg(c).\n".as_bytes());
+        let mut answers = vec![];
+        let failures = Rc::new(RefCell::new(vec![]));
+        let failures_clone = Rc::clone(&failures);
+        let mut child_stdout = child_output("YES\nMAYBE\nNO\n");
+        let last_step = execute_dynamics(
+            &mut modifications,
+            keep_going(
+                QueryType::DC("a".to_string()).answer_reading_function(),
+                Box::new(move |step, _| failures_clone.borrow_mut().push(step)),
+            ),
+            &Ok,
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
+            &mut Vec::new(),
+            &mut child_stdout,
+        )
+        .unwrap();
+        assert_eq!(2, last_step);
+        assert_eq!(vec!["YES\n", FAILED_ANSWER, "NO\n"], answers);
+        assert_eq!(vec![1], *failures.borrow());
+    }
+
+    #[test]
+    fn test_fast_forward_dynamics() {
+        let modifications = (0..70)
+            .map(|i| format!("+arg(a{}).\n", i))
+            .collect::<String>();
+        let mut child_stdin = Vec::new();
+        let mut child_stdout = child_output(&"NO\n".repeat(71));
+        let n_sent = fast_forward_dynamics(
+            70,
+            &mut modifications.as_bytes(),
+            &*QueryType::DC("a".to_string()).answer_reading_function(),
+            &Ok,
+            &mut child_stdin,
+            &mut child_stdout,
+        )
+        .unwrap();
+        assert_eq!(70, n_sent);
+        assert_eq!(modifications, String::from_utf8(child_stdin).unwrap());
+        let mut remaining = String::new();
+        child_stdout.read_to_string(&mut remaining).unwrap();
+        assert_eq!("NO\n", remaining);
+        let n_sent = fast_forward_dynamics(
+            5,
+            &mut "+arg(b).\n\n+arg(c).\n".as_bytes(),
+            &*QueryType::DC("a".to_string()).answer_reading_function(),
+            &Ok,
+            &mut Vec::new(),
+            &mut child_output("NO\nYES\n"),
+        )
+        .unwrap();
+        assert_eq!(1, n_sent);
+        let err = fast_forward_dynamics(
+            1,
+            &mut "+arg(b).\n".as_bytes(),
+            &*QueryType::DC("a".to_string()).answer_reading_function(),
+            &Ok,
+            &mut Vec::new(),
+            &mut child_output("MAYBE\n"),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<WrapError>(),
+            Some(WrapError::SolverAnswerParse { step: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_execute_batched_dynamics() {
+        let mut answers = vec![];
+        let mut child_stdin = Vec::new();
+        let mut child_stdout = child_output("NO\nYES\nYES\nNO\n");
+        let n_steps = execute_batched_dynamics(
+            &mut "+arg(b).\n+att(b,a).\n\n\n-att(b,a).\nquery.\nquery.\n".as_bytes(),
+            QueryType::DC("a".to_string()).answer_reading_function(),
+            &|l| Ok(l.to_uppercase()),
+            &mut |a| {
+                answers.push(a);
+                Ok(())
+            },
+            &mut child_stdin,
+            &mut child_stdout,
+        )
+        .unwrap();
+        assert_eq!(3, n_steps);
+        assert_eq!(vec!["NO\n", "YES\n", "YES\n", "NO\n"], answers);
+        assert_eq!(
+            "+ARG(B).\n+ATT(B,A).\nquery.\n-ATT(B,A).\nquery.\nquery.\n\n",
+            String::from_utf8(child_stdin).unwrap()
+        );
+        let err = execute_batched_dynamics(
+            &mut "+arg(b).\n+att(b,a).\n".as_bytes(),
+            QueryType::DC("a".to_string()).answer_reading_function(),
+            &|l| {
+                if l.contains("att") {
+                    Err(anyhow!("no"))
+                } else {
+                    Ok(l)
+                }
+            },
+            &mut |_| Ok(()),
+            &mut Vec::new(),
+            &mut child_output("YES\n"),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<WrapError>(),
+            Some(WrapError::ModificationParse { line: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_query_from_problem() {
+        assert_eq!(
+            QueryType::CE,
+            QueryType::try_from(("CE-ST-D", None)).unwrap()
+        );
+        for problem in &["CE-ST", "CE-XX-D", "CE-ST-S", "ES-ST-D", "-ST-D"] {
+            assert!(QueryType::try_from((*problem, None)).is_err());
+        }
+        assert!(QueryType::try_from(("CE-ST-D", Some("a"))).is_err());
+        assert!(QueryType::try_from(("DC-ST-D", None)).is_err());
+    }
+
+    #[test]
+    fn test_task_registry() {
+        let mut registry = TaskRegistry::default();
+        registry
+            .read(&mut "\n  # comment\nES SE\nDS-APX   DS\nSE EE\n".as_bytes())
+            .unwrap();
+        assert_eq!(QueryType::SE, registry.query("ES-PR-D", None).unwrap());
+        assert_eq!(QueryType::EE, registry.query("SE-PR-D", None).unwrap());
+        assert_eq!(
+            QueryType::DS("a".to_string()),
+            registry.query("DS-APX-PR-D", Some("a")).unwrap()
+        );
+        assert!(registry.read(&mut "ES XX\n".as_bytes()).is_err());
+        assert!(registry.read(&mut "ES SE extra\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_check_end_of_output() {
+        assert!(check_end_of_output(&mut "\n".as_bytes(), 2).is_ok());
+        let err = check_end_of_output(&mut "YES\n".as_bytes(), 2).unwrap_err();
+        assert!(err.to_string().contains("after step 2"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_solver_too_many_answers() {
+        let arguments = vec!["-c".to_string(), "echo YES; echo YES".to_string()];
+        let err = run_solver(
+            "sh",
+            &arguments,
+            &[],
+            &QueryType::DC("a".to_string()),
+            &RunLimits::default(),
+            &mut |_| Ok(()),
+        )
+        .unwrap_err();
+        // both answers may be read at once, in which case the second one is detected before the end of the dynamics
+        assert!(format!("{:#}", err).contains("solver output desynchronized"));
+        assert!(matches!(
+            err.downcast_ref::<WrapError>(),
+            Some(WrapError::EarlyAnswer { step: 0 }) | Some(WrapError::ExtraAnswers { step: 0 })
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_solver_exit() {
+        let arguments = vec!["-c".to_string(), "exit 3".to_string()];
+        let err = run_solver(
+            "sh",
+            &arguments,
+            &[],
+            &QueryType::DC("a".to_string()),
+            &RunLimits::default(),
+            &mut |_| Ok(()),
+        )
+        .unwrap_err();
+        match err.downcast_ref::<WrapError>() {
+            Some(WrapError::SolverExit { status }) => assert_eq!(Some(3), status.code()),
+            e => panic!("unexpected error kind: {:?}", e),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_solver_session_exit() {
+        let arguments = vec!["-c".to_string(), "exit 3".to_string()];
+        let err = SolverSession::start(
+            "sh",
+            &arguments,
+            &QueryType::DC("a".to_string()),
+            &RunLimits::default(),
+        )
+        .err()
+        .unwrap();
+        match err.downcast_ref::<WrapError>() {
+            Some(WrapError::SolverExit { status }) => assert_eq!(Some(3), status.code()),
+            e => panic!("unexpected error kind: {:?}", e),
+        }
+    }
+
+    #[cfg(unix)]
+    fn shell(script: &str) -> Command {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(script);
+        command
+    }
+
+    #[cfg(unix)]
+    fn limits(timeout: Option<u64>, step_timeout: Option<u64>) -> RunLimits {
+        let mut limits = RunLimits::default();
+        if let Some(t) = timeout {
+            limits.set_timeout(Duration::from_millis(t));
+        }
+        if let Some(t) = step_timeout {
+            limits.set_step_timeout(Duration::from_millis(t));
+        }
+        limits
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_solver_process_interaction() {
+        let mut process = SolverProcess::spawn(shell("cat"), &RunLimits::default()).unwrap();
+        let (mut input, output) = process.pipes();
+        let mut output = BufReader::new(output);
+        input.write_all(b"a\n").unwrap();
+        let mut line = String::new();
+        output.read_line(&mut line).unwrap();
+        assert_eq!("a\n", line);
+        drop(input);
+        assert_eq!(0, output.read_line(&mut line).unwrap());
+        assert!(process.wait().unwrap().success());
+        assert_eq!(None, process.stopped());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_solver_process_timeout() {
+        use std::os::unix::process::ExitStatusExt;
+        let mut process =
+            SolverProcess::spawn(shell("exec sleep 60"), &limits(Some(50), None)).unwrap();
+        let (_input, mut output) = process.pipes();
+        let error = output.read(&mut [0; 16]).unwrap_err();
+        assert_eq!(std::io::ErrorKind::TimedOut, error.kind());
+        assert_eq!(Some(Stop::Timeout), process.stopped());
+        assert_eq!(Some(9), process.wait().unwrap().signal());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_solver_process_timeout_while_waiting() {
+        let mut process =
+            SolverProcess::spawn(shell("exec 1>&- sleep 60"), &limits(Some(50), None)).unwrap();
+        let (input, mut output) = process.pipes();
+        drop(input);
+        assert_eq!(0, output.read(&mut [0; 16]).unwrap());
+        assert_eq!(
+            std::io::ErrorKind::TimedOut,
+            process.wait().unwrap_err().kind()
+        );
+        assert_eq!(Some(Stop::Timeout), process.stopped());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_solver_process_step_timeout() {
+        let mut process =
+            SolverProcess::spawn(shell("read l; exec sleep 60"), &limits(None, Some(300))).unwrap();
+        let (mut input, mut output) = process.pipes();
+        std::thread::sleep(Duration::from_millis(200));
+        writeln!(input).and_then(|_| input.flush()).unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+        assert_eq!(None, process.stopped());
+        assert!(output.read(&mut [0; 16]).is_err());
+        assert_eq!(Some(Stop::Timeout), process.stopped());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_solver_process_cancellation() {
+        use std::os::unix::process::ExitStatusExt;
+        let cancellation = Cancellation::new();
+        let mut limits = RunLimits::default();
+        limits.set_cancellation(&cancellation);
+        let mut process = SolverProcess::spawn(shell("exec sleep 60"), &limits).unwrap();
+        let (_input, mut output) = process.pipes();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            cancellation.cancel();
+        });
+        assert!(output.read(&mut [0; 16]).is_err());
+        assert_eq!(Some(Stop::Cancellation), process.stopped());
+        assert_eq!(Some(9), process.wait().unwrap().signal());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_solver_process_has_exited() {
+        use std::os::unix::process::ExitStatusExt;
+        let process = SolverProcess::spawn(shell("exit 3"), &RunLimits::default()).unwrap();
+        assert!(process.has_exited());
+        process.kill();
+        assert_eq!(Some(3), process.wait().unwrap().code());
+        let process = SolverProcess::spawn(shell("exec sleep 60"), &RunLimits::default()).unwrap();
+        assert!(!process.has_exited());
+        process.kill();
+        assert_eq!(Some(9), process.wait().unwrap().signal());
+    }
+
+    /// Hooks recording the calls they receive.
+    #[derive(Default)]
+    struct RecordingHooks(Arc<Mutex<Vec<&'static str>>>);
+
+    impl ProcessHooks for RecordingHooks {
+        fn spawned(&mut self, _child: &tokio::process::Child) -> Result<()> {
+            self.0.lock().unwrap().push("spawned");
+            Ok(())
+        }
+
+        fn waiting(&self) {
+            self.0.lock().unwrap().push("waiting");
+        }
+
+        fn still_running(&self) {
+            self.0.lock().unwrap().push("still_running");
+        }
+
+        fn killing(&self) {
+            self.0.lock().unwrap().push("killing");
+        }
+
+        fn interrupted(&self) -> bool {
+            true
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_solver_process_hooks() {
+        let hooks = RecordingHooks::default();
+        let calls = Arc::clone(&hooks.0);
+        let process = SolverProcess::spawn_with_hooks(
+            shell("exec sleep 60"),
+            &RunLimits::default(),
+            Box::new(hooks),
+        )
+        .unwrap();
+        assert!(!process.has_exited());
+        assert!(!process.wait().unwrap().success());
+        assert_eq!(
+            vec!["spawned", "waiting", "still_running", "waiting", "killing"],
+            *calls.lock().unwrap()
+        );
+        drop(process);
+        assert_eq!(5, calls.lock().unwrap().len());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_solver_timeout() {
+        let arguments = vec!["-c".to_string(), "echo YES; exec sleep 60".to_string()];
+        let err = run_solver(
+            "sh",
+            &arguments,
+            &["+arg(b).".to_string()],
+            &QueryType::DC("a".to_string()),
+            &limits(Some(100), None),
+            &mut |_| Ok(()),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<WrapError>(),
+            Some(WrapError::SolverStopped {
+                stop: Stop::Timeout
+            })
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_solver_session_step_timeout() {
+        let arguments = vec![
+            "-c".to_string(),
+            "echo YES; read l; sleep 0.2; echo NO; read l; exec sleep 60".to_string(),
+        ];
+        let mut session = SolverSession::start(
+            "sh",
+            &arguments,
+            &QueryType::DC("a".to_string()),
+            &limits(None, Some(400)),
+        )
+        .unwrap();
+        std::thread::sleep(Duration::from_millis(500));
+        assert_eq!("NO\n", session.push_modification("+arg(b).").unwrap());
+        let err = session.push_modification("+arg(c).").unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<WrapError>(),
+            Some(WrapError::SolverStopped {
+                stop: Stop::Timeout
+            })
+        ));
+        assert!(session.finish().is_err());
+    }
+}